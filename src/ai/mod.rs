@@ -1,3 +1,4 @@
+mod redact;
 mod summarizer;
 
-pub use summarizer::Summarizer;
+pub use summarizer::{cited_sources, content_hash, Completion, Summarizer};
@@ -0,0 +1,180 @@
+//! Redaction of sensitive text before it goes to a cloud model, and the
+//! audit log of what was sent where.
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::Serialize;
+
+use crate::config::RedactionConfig;
+use crate::error::{AppError, Result};
+
+static EMAIL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}").unwrap());
+
+/// Digit runs with phone punctuation; `is_phone_number` checks the length
+static PHONE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\+?\(?\d[\d\s().-]{6,}\d").unwrap());
+
+/// Phone numbers have 10-15 digits (with country code); fewer is more
+/// likely a date range, price or count
+const PHONE_DIGITS: std::ops::RangeInclusive<usize> = 10..=15;
+
+/// The configured redaction rules
+pub struct Redactor {
+    emails: bool,
+    phone_numbers: bool,
+    patterns: Vec<Regex>,
+}
+
+/// How many matches each kind of rule removed
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Redactions {
+    pub emails: usize,
+    pub phone_numbers: usize,
+    pub custom: usize,
+}
+
+impl Redactor {
+    pub fn from_config(config: &RedactionConfig) -> Result<Self> {
+        let patterns = config
+            .patterns
+            .iter()
+            .map(|p| Regex::new(p).map_err(|e| AppError::Config(format!("Invalid redaction pattern {:?}: {}", p, e))))
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            emails: config.emails,
+            phone_numbers: config.phone_numbers,
+            patterns,
+        })
+    }
+
+    /// Whether any rule is on
+    pub fn is_active(&self) -> bool {
+        self.emails || self.phone_numbers || !self.patterns.is_empty()
+    }
+
+    /// `text` with every match replaced by a marker, and the counts removed.
+    /// Custom patterns run first so they can match around emails or numbers.
+    pub fn redact(&self, text: &str) -> (String, Redactions) {
+        let mut redactions = Redactions::default();
+        let mut text = text.to_string();
+        for pattern in &self.patterns {
+            redactions.custom += pattern.find_iter(&text).count();
+            text = pattern.replace_all(&text, "[redacted]").into_owned();
+        }
+        if self.emails {
+            redactions.emails += EMAIL.find_iter(&text).count();
+            text = EMAIL.replace_all(&text, "[email]").into_owned();
+        }
+        if self.phone_numbers {
+            text = PHONE
+                .replace_all(&text, |caps: &regex::Captures| {
+                    if is_phone_number(&caps[0]) {
+                        redactions.phone_numbers += 1;
+                        "[phone]".to_string()
+                    } else {
+                        caps[0].to_string()
+                    }
+                })
+                .into_owned();
+        }
+        (text, redactions)
+    }
+}
+
+fn is_phone_number(candidate: &str) -> bool {
+    PHONE_DIGITS.contains(&candidate.chars().filter(char::is_ascii_digit).count())
+}
+
+/// One request to a cloud model, as written to the audit log
+#[derive(Debug, Serialize)]
+pub struct AuditEntry<'a> {
+    pub at: DateTime<Utc>,
+    /// The endpoint the text went to
+    pub destination: &'a str,
+    pub model: &'a str,
+    /// "summary", "tags", "question" or "briefing"
+    pub purpose: &'a str,
+    /// Article title(s) or question the request was about
+    pub subject: &'a str,
+    pub bytes: usize,
+    /// SHA-256 of the text sent, to match it against a stored copy
+    pub sha256: String,
+    pub redactions: Redactions,
+}
+
+/// Append-only JSON Lines file of cloud requests
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+impl AuditLog {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn record(&self, entry: AuditEntry) {
+        let line = match serde_json::to_string(&entry) {
+            Ok(line) => line,
+            Err(e) => {
+                tracing::warn!("Failed to serialize audit entry: {}", e);
+                return;
+            }
+        };
+        let written = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| writeln!(file, "{}", line));
+        if let Err(e) = written {
+            tracing::warn!("Failed to write LLM audit log {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn redactor(patterns: &[&str]) -> Redactor {
+        Redactor::from_config(&RedactionConfig {
+            emails: true,
+            phone_numbers: true,
+            patterns: patterns.iter().map(|p| p.to_string()).collect(),
+            audit_log: None,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_redact_emails_phones_and_patterns() {
+        let text = "Contact jane.doe@example.co.uk or +1 (415) 555-0132. \
+                    Source: Deep Throat. Revenue rose 12% in 2023-2024 to $1,200,000.";
+        let (redacted, counts) = redactor(&[r"Source: [^.]+"]).redact(text);
+        assert_eq!(
+            redacted,
+            "Contact [email] or [phone]. [redacted]. Revenue rose 12% in 2023-2024 to $1,200,000."
+        );
+        assert_eq!(
+            counts,
+            Redactions {
+                emails: 1,
+                phone_numbers: 1,
+                custom: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_a_config_error() {
+        let config = RedactionConfig {
+            patterns: vec!["(unclosed".to_string()],
+            ..Default::default()
+        };
+        assert!(Redactor::from_config(&config).is_err());
+        assert!(!Redactor::from_config(&RedactionConfig::default()).unwrap().is_active());
+    }
+}
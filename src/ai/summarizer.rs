@@ -1,13 +1,37 @@
+use std::sync::LazyLock;
 use std::time::Duration;
 
+use regex::Regex;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+use url::Url;
 
+use super::redact::{AuditEntry, AuditLog, Redactions, Redactor};
+use crate::config::{Config, LocalLlmConfig, ProviderKind};
 use crate::error::{AppError, Result};
+use crate::services::{is_local_url, restrict_to_local};
 
 const CLAUDE_API_URL: &str = "https://api.anthropic.com/v1/messages";
 const CLAUDE_MODEL: &str = "claude-3-5-haiku-20241022";
 
+/// Local models can be slow, especially on CPU
+const LOCAL_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Waits before retrying a request that failed transiently
+const RETRY_DELAYS: [Duration; 2] = [Duration::from_secs(2), Duration::from_secs(8)];
+
+static CITATION: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\[(\d+(?:\s*,\s*\d+)*)\]").unwrap());
+
+/// Article text sent with a question, shared between the articles asked about
+const MAX_CONTEXT_BYTES: usize = 40_000;
+
+/// Content shorter than this (in characters) isn't hashed: teasers and
+/// empty bodies would otherwise share one summary across unrelated articles
+const MIN_HASHED_CHARS: usize = 200;
+
 #[derive(Debug, Serialize)]
 struct MessageRequest {
     model: String,
@@ -35,84 +59,588 @@ struct ContentBlock {
     text: Option<String>,
 }
 
-pub struct Summarizer {
+/// Chat request for OpenAI, or Ollama or llama.cpp's server (which speak its API)
+#[derive(Debug, Serialize)]
+struct ChatRequest {
+    model: String,
+    max_tokens: u32,
+    messages: Vec<Message>,
+    stream: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoice {
+    message: ChatMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatMessage {
+    content: Option<String>,
+}
+
+/// Where completions come from
+enum Provider {
+    Claude { api_key: String },
+    /// OpenAI, or another service speaking its API
+    OpenAi { api_key: String, url: String, model: String },
+    /// A model served on this machine or network by Ollama or llama.cpp
+    Local { url: String, model: String },
+}
+
+impl Provider {
+    /// How summaries record where they came from
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Claude { .. } => "claude",
+            Self::OpenAi { .. } => "openai",
+            Self::Local { .. } => "local",
+        }
+    }
+
+    fn model(&self) -> &str {
+        match self {
+            Self::Claude { .. } => CLAUDE_MODEL,
+            Self::OpenAi { model, .. } | Self::Local { model, .. } => model,
+        }
+    }
+
+    /// Text for cloud providers is redacted and audited first
+    fn is_cloud(&self) -> bool {
+        !matches!(self, Self::Local { .. })
+    }
+}
+
+/// A provider and the HTTP client for it (local models get a longer
+/// timeout, and under `local_only` a client that stays on the network)
+struct Backend {
+    provider: Provider,
     client: Client,
-    api_key: String,
+}
+
+/// A reply, and which provider and model wrote it
+#[derive(Debug, Clone)]
+pub struct Completion {
+    pub text: String,
+    pub provider: &'static str,
+    pub model: String,
+}
+
+pub struct Summarizer {
+    /// Tried in order until one succeeds
+    backends: Vec<Backend>,
+    /// Applied to text sent to cloud providers
+    redactor: Option<Redactor>,
+    audit_log: Option<AuditLog>,
+    /// Earliest time the next request may start, when rate limited
+    next_request: Mutex<Instant>,
+    request_interval: Option<Duration>,
 }
 
 impl Summarizer {
-    pub fn new(api_key: String) -> Self {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(60))
-            .build()
-            .expect("Failed to create HTTP client");
-        Self { client, api_key }
+    /// The configured summarizer, if any: the providers in `[summaries]
+    /// providers`, or else every configured one with `[local_llm]` first.
+    /// With `local_only` only the local model is used, and its HTTP client
+    /// can't reach anything off the local network.
+    pub fn from_config(config: &Config) -> Result<Option<Self>> {
+        let explicit = !config.summaries.providers.is_empty();
+        let order = match explicit {
+            true => config.summaries.providers.clone(),
+            false => vec![ProviderKind::Local, ProviderKind::Claude, ProviderKind::OpenAi],
+        };
+
+        let mut backends = Vec::new();
+        for kind in order {
+            if config.local_only && kind != ProviderKind::Local {
+                if explicit {
+                    tracing::warn!("local_only is set: skipping {:?} in summaries.providers", kind);
+                }
+                continue;
+            }
+            let backend = match kind {
+                ProviderKind::Local => config.local_llm.as_ref().map(|local| local_backend(local, config.local_only)).transpose()?,
+                ProviderKind::Claude => config.claude_api_key.as_ref().map(|api_key| Backend {
+                    provider: Provider::Claude { api_key: api_key.clone() },
+                    client: cloud_client(),
+                }),
+                ProviderKind::OpenAi => config.openai.as_ref().map(|openai| Backend {
+                    provider: Provider::OpenAi {
+                        api_key: openai.api_key.clone(),
+                        url: openai.url.trim_end_matches('/').to_string(),
+                        model: openai.model.clone(),
+                    },
+                    client: cloud_client(),
+                }),
+            };
+            match backend {
+                Some(backend) => backends.push(backend),
+                None if explicit => {
+                    return Err(AppError::Config(format!(
+                        "summaries.providers lists {:?}, which isn't configured",
+                        kind
+                    )))
+                }
+                None => {}
+            }
+        }
+
+        if backends.is_empty() {
+            if config.local_only {
+                tracing::warn!("local_only is set without a [local_llm] model: summaries are off");
+            }
+            return Ok(None);
+        }
+        let mut summarizer = Self::with_backends(backends, config.summaries.requests_per_minute);
+        if summarizer.backends.iter().any(|b| b.provider.is_cloud()) {
+            let redactor = Redactor::from_config(&config.redaction)?;
+            summarizer.redactor = redactor.is_active().then_some(redactor);
+            summarizer.audit_log = config.redaction.audit_log.clone().map(AuditLog::new);
+        }
+        Ok(Some(summarizer))
+    }
+
+    /// `requests_per_minute` spaces requests out to stay under the
+    /// providers' rate limits; 0 sends them as fast as they come
+    fn with_backends(backends: Vec<Backend>, requests_per_minute: u32) -> Self {
+        Self {
+            backends,
+            redactor: None,
+            audit_log: None,
+            next_request: Mutex::new(Instant::now()),
+            request_interval: (requests_per_minute > 0)
+                .then(|| Duration::from_secs(60) / requests_per_minute),
+        }
+    }
+
+    /// Wait for this request's slot under the rate limit
+    async fn pace(&self) {
+        let Some(interval) = self.request_interval else {
+            return;
+        };
+        let start = {
+            let mut next = self.next_request.lock().await;
+            let start = (*next).max(Instant::now());
+            *next = start + interval;
+            start
+        };
+        tokio::time::sleep_until(start).await;
     }
 
     pub async fn generate_summary(
         &self,
         article_title: &str,
         article_content: &str,
-    ) -> Result<String> {
+    ) -> Result<Completion> {
         let system_prompt = r#"Summarize this article as 3-5 bullet points.
 Output ONLY the bullet points - no introductions, conclusions, or commentary.
 Start each line with "• " and state one key fact or finding.
 Never write phrases like "Here are the key points" or "In summary" - just the bullets."#;
 
-        // Truncate content if too long (find valid UTF-8 boundary)
-        let content = if article_content.len() > 10000 {
-            let mut end = 10000;
-            while end > 0 && !article_content.is_char_boundary(end) {
-                end -= 1;
-            }
-            &article_content[..end]
-        } else {
-            article_content
-        };
-
         let user_message = format!(
             "Please summarize the following article:\n\nTitle: {}\n\nContent:\n{}",
-            article_title, content
+            article_title,
+            truncate(article_content)
+        );
+        self.complete("summary", article_title, system_prompt, user_message, 1024).await
+    }
+
+    /// Ask for up to `limit` short topic tags, lowercased and without commas
+    pub async fn suggest_tags(&self, article_title: &str, article_content: &str, limit: usize) -> Result<Vec<String>> {
+        let system_prompt = format!(
+            r#"List up to {} topic tags for this article, one per line.
+Each tag is 1-3 lowercase words naming a subject, product, organisation or person.
+Output ONLY the tags - no numbering, bullets or commentary."#,
+            limit
         );
+        let user_message = format!("Title: {}\n\nContent:\n{}", article_title, truncate(article_content));
+        let reply = self.complete("tags", article_title, &system_prompt, user_message, 100).await?.text;
+
+        let mut tags: Vec<String> = Vec::new();
+        for line in reply.lines() {
+            let tag = line
+                .trim_start_matches(|c: char| c == '-' || c == '•' || c == '#' || c.is_ascii_digit() || c == '.')
+                .replace(',', " ")
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+                .to_lowercase();
+            if !tag.is_empty() && !tags.contains(&tag) && tags.len() < limit {
+                tags.push(tag);
+            }
+        }
+        Ok(tags)
+    }
+
+    /// Answer `question` from `sources` (title, text) alone, citing the ones
+    /// used by number: [1] is the first source
+    pub async fn answer_question(&self, question: &str, sources: &[(String, String)]) -> Result<String> {
+        let system_prompt = r#"Answer the question using only the numbered articles provided.
+Cite every article you draw on by its number in square brackets, like [1] or [2][3].
+If the articles don't contain the answer, say so plainly. Be concise: a short paragraph or a few bullets."#;
+
+        let mut user_message = numbered_sources(sources);
+        user_message.push_str(&format!("Question: {}", question));
+        Ok(self.complete("question", question, system_prompt, user_message, 1024).await?.text)
+    }
 
-        let request = MessageRequest {
-            model: CLAUDE_MODEL.to_string(),
-            max_tokens: 1024,
-            messages: vec![Message {
+    /// Compare how several outlets cover one story. `sources` are (outlet
+    /// and title, text); the briefing cites them by number like
+    /// `answer_question`.
+    pub async fn compare_coverage(&self, sources: &[(String, String)]) -> Result<String> {
+        let system_prompt = r#"These numbered articles from different outlets cover the same story.
+Write a briefing for a reporter about to cover it:
+First, one or two lines on the facts every outlet agrees on.
+Then one bullet per outlet, starting "• [n] Outlet:", stating what it adds, claims or quotes that the others don't (new facts, sources, numbers, angle).
+Finish with any contradictions between outlets, or "No contradictions."
+Cite articles only by their numbers in square brackets. No introductions or commentary."#;
+
+        let subject = sources.iter().map(|(title, _)| title.as_str()).collect::<Vec<_>>().join(" | ");
+        Ok(self.complete("briefing", &subject, system_prompt, numbered_sources(sources), 1500).await?.text)
+    }
+
+    /// Send one request to each provider in turn until one answers,
+    /// retrying a provider after a short wait if it's overloaded or
+    /// unreachable. `purpose` and `subject` (what it's about) go in the
+    /// audit log. Fails with the last provider's error.
+    async fn complete(
+        &self,
+        purpose: &str,
+        subject: &str,
+        system_prompt: &str,
+        user_message: String,
+        max_tokens: u32,
+    ) -> Result<Completion> {
+        let mut backends = self.backends.iter().peekable();
+        while let Some(backend) = backends.next() {
+            let mut delays = RETRY_DELAYS.iter();
+            let result = loop {
+                match self.attempt(backend, purpose, subject, system_prompt, user_message.clone(), max_tokens).await {
+                    Err(e) if e.is_transient() => match delays.next() {
+                        Some(delay) => {
+                            tracing::debug!("Retrying {} request in {:?}: {}", purpose, delay, e);
+                            tokio::time::sleep(*delay).await;
+                        }
+                        None => break Err(e),
+                    },
+                    result => break result,
+                }
+            };
+            match result {
+                Ok(text) => {
+                    return Ok(Completion {
+                        text,
+                        provider: backend.provider.name(),
+                        model: backend.provider.model().to_string(),
+                    })
+                }
+                Err(e) => match backends.peek() {
+                    Some(next) => tracing::warn!(
+                        "{} failed ({}), trying {}",
+                        backend.provider.name(),
+                        e,
+                        next.provider.name()
+                    ),
+                    None => return Err(e),
+                },
+            }
+        }
+        Err(AppError::Config("No summary provider configured".to_string()))
+    }
+
+    async fn attempt(
+        &self,
+        backend: &Backend,
+        purpose: &str,
+        subject: &str,
+        system_prompt: &str,
+        user_message: String,
+        max_tokens: u32,
+    ) -> Result<String> {
+        self.pace().await;
+        let provider = &backend.provider;
+        let (user_message, redactions) = match (&self.redactor, provider.is_cloud()) {
+            (Some(redactor), true) => redactor.redact(&user_message),
+            _ => (user_message, Redactions::default()),
+        };
+        if let (Some(audit_log), true) = (&self.audit_log, provider.is_cloud()) {
+            let destination = match provider {
+                Provider::OpenAi { url, .. } => url.as_str(),
+                _ => CLAUDE_API_URL,
+            };
+            audit_log.record(AuditEntry {
+                at: chrono::Utc::now(),
+                destination,
+                model: provider.model(),
+                purpose,
+                subject,
+                bytes: user_message.len(),
+                sha256: sha256_hex(&user_message),
+                redactions,
+            });
+        }
+        match provider {
+            Provider::Claude { api_key } => {
+                complete_claude(&backend.client, api_key, system_prompt, user_message, max_tokens).await
+            }
+            Provider::OpenAi { .. } | Provider::Local { .. } => {
+                complete_chat(&backend.client, provider, system_prompt, user_message, max_tokens).await
+            }
+        }
+    }
+}
+
+/// Client for a hosted API
+fn cloud_client() -> Client {
+    Client::builder()
+        .timeout(Duration::from_secs(60))
+        .build()
+        .expect("Failed to create HTTP client")
+}
+
+fn local_backend(local: &LocalLlmConfig, local_only: bool) -> Result<Backend> {
+    let url = Url::parse(&local.url)
+        .map_err(|e| AppError::Config(format!("Invalid local_llm url {}: {}", local.url, e)))?;
+    let mut builder = Client::builder().timeout(LOCAL_TIMEOUT);
+    if local_only {
+        if !is_local_url(&url) {
+            tracing::warn!("local_llm url {} must resolve to this machine or network", url);
+        }
+        builder = restrict_to_local(builder);
+    }
+    Ok(Backend {
+        provider: Provider::Local {
+            url: local.url.trim_end_matches('/').to_string(),
+            model: local.model.clone(),
+        },
+        client: builder.build().expect("Failed to create HTTP client"),
+    })
+}
+
+async fn complete_claude(
+    client: &Client,
+    api_key: &str,
+    system_prompt: &str,
+    user_message: String,
+    max_tokens: u32,
+) -> Result<String> {
+    let request = MessageRequest {
+        model: CLAUDE_MODEL.to_string(),
+        max_tokens,
+        messages: vec![Message {
+            role: "user".to_string(),
+            content: user_message,
+        }],
+        system: Some(system_prompt.to_string()),
+    };
+
+    let response = client
+        .post(CLAUDE_API_URL)
+        .header("x-api-key", api_key)
+        .header("anthropic-version", "2023-06-01")
+        .header("content-type", "application/json")
+        .json(&request)
+        .send()
+        .await?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let error_text = response.text().await?;
+        if is_unavailable(status) {
+            return Err(AppError::Unavailable(format!("Claude API {}: {}", status, error_text)));
+        }
+        return Err(AppError::ClaudeApi(format!("API error: {}", error_text)));
+    }
+
+    let message_response: MessageResponse = response.json().await?;
+
+    let text = message_response
+        .content
+        .into_iter()
+        .filter_map(|block| block.text)
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Ok(text)
+}
+
+/// A request to an OpenAI-style `/v1/chat/completions` endpoint: OpenAI
+/// itself, or a local server
+async fn complete_chat(
+    client: &Client,
+    provider: &Provider,
+    system_prompt: &str,
+    user_message: String,
+    max_tokens: u32,
+) -> Result<String> {
+    let (url, api_key) = match provider {
+        Provider::OpenAi { url, api_key, .. } => (url.as_str(), Some(api_key)),
+        Provider::Local { url, .. } => (url.as_str(), None),
+        Provider::Claude { .. } => unreachable!("Claude has its own API"),
+    };
+    let request = ChatRequest {
+        model: provider.model().to_string(),
+        max_tokens,
+        messages: vec![
+            Message {
+                role: "system".to_string(),
+                content: system_prompt.to_string(),
+            },
+            Message {
                 role: "user".to_string(),
                 content: user_message,
-            }],
-            system: Some(system_prompt.to_string()),
-        };
+            },
+        ],
+        stream: false,
+    };
 
-        let response = self
-            .client
-            .post(CLAUDE_API_URL)
-            .header("x-api-key", &self.api_key)
-            .header("anthropic-version", "2023-06-01")
-            .header("content-type", "application/json")
-            .json(&request)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            let error_text = response.text().await?;
-            return Err(AppError::ClaudeApi(format!("API error: {}", error_text)));
+    let mut request = client.post(format!("{}/v1/chat/completions", url)).json(&request);
+    if let Some(api_key) = api_key {
+        request = request.bearer_auth(api_key);
+    }
+    let response = request.send().await?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let error_text = response.text().await?;
+        if is_unavailable(status) {
+            return Err(AppError::Unavailable(format!("{} {}: {}", url, status, error_text)));
         }
+        let message = format!("{}: {}", url, error_text);
+        return Err(match provider {
+            Provider::OpenAi { .. } => AppError::OpenAiApi(message),
+            _ => AppError::LocalLlm(message),
+        });
+    }
 
-        let message_response: MessageResponse = response.json().await?;
+    let chat: ChatResponse = response.json().await?;
+    Ok(chat
+        .choices
+        .into_iter()
+        .filter_map(|choice| choice.message.content)
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
 
-        let summary = message_response
-            .content
-            .into_iter()
-            .filter_map(|block| block.text)
-            .collect::<Vec<_>>()
-            .join("\n");
+/// Cut content to 10,000 bytes (on a UTF-8 boundary) to bound request size
+fn truncate(content: &str) -> &str {
+    truncate_to(content, 10000)
+}
 
-        Ok(summary)
+fn truncate_to(content: &str, max_bytes: usize) -> &str {
+    if content.len() <= max_bytes {
+        return content;
     }
+    let mut end = max_bytes;
+    while end > 0 && !content.is_char_boundary(end) {
+        end -= 1;
+    }
+    &content[..end]
+}
+
+/// Sources as "[1] title" then the text, sharing `MAX_CONTEXT_BYTES`
+fn numbered_sources(sources: &[(String, String)]) -> String {
+    let budget = MAX_CONTEXT_BYTES / sources.len().max(1);
+    let mut message = String::new();
+    for (number, (title, text)) in sources.iter().enumerate() {
+        message.push_str(&format!("[{}] {}\n{}\n\n", number + 1, title, truncate_to(text, budget)));
+    }
+    message
+}
+
+/// Source numbers cited in an answer as "[2]" or "[1, 3]", in order of first
+/// citation, ignoring numbers outside 1..=`count`
+pub fn cited_sources(answer: &str, count: usize) -> Vec<usize> {
+    let mut cited = Vec::new();
+    for group in CITATION.captures_iter(answer) {
+        for number in group[1].split(',').filter_map(|n| n.trim().parse::<usize>().ok()) {
+            if (1..=count).contains(&number) && !cited.contains(&number) {
+                cited.push(number);
+            }
+        }
+    }
+    cited
+}
+
+/// SHA-256 (hex) of the content with whitespace collapsed, so identical wire
+/// copy from different feeds maps to one summary. None if it's too short.
+pub fn content_hash(content: &str) -> Option<String> {
+    let normalized = content.split_whitespace().collect::<Vec<_>>().join(" ");
+    if normalized.chars().count() < MIN_HASHED_CHARS {
+        return None;
+    }
+    Some(sha256_hex(&normalized))
+}
+
+fn sha256_hex(text: &str) -> String {
+    Sha256::digest(text.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Rate limited or overloaded (Anthropic answers 529), rather than refused
+fn is_unavailable(status: reqwest::StatusCode) -> bool {
+    status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_hash_ignores_whitespace_and_skips_short_text() {
+        let copy = "NEW YORK (AP) — Stocks rose on Tuesday. ".repeat(10);
+        let reflowed = copy.replace(' ', "\n  ");
+        assert_eq!(content_hash(&copy), content_hash(&reflowed));
+        assert_eq!(content_hash(&copy).map(|h| h.len()), Some(64));
+        assert_ne!(content_hash(&copy), content_hash(&copy.replace("rose", "fell")));
+        assert_eq!(content_hash("Read more..."), None);
+    }
+
+    #[test]
+    fn test_cited_sources() {
+        let answer = "Prices rose [2]. Analysts disagree [1, 3][2]; see also [7] and [0].";
+        assert_eq!(cited_sources(answer, 3), [2, 1, 3]);
+        assert!(cited_sources("No citations here", 3).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_falls_through_to_next_provider() {
+        use axum::{http::StatusCode, routing::post, Json, Router};
+
+        let app = Router::new()
+            .route("/refuses/v1/chat/completions", post(|| async { (StatusCode::UNAUTHORIZED, "bad key") }))
+            .route(
+                "/answers/v1/chat/completions",
+                post(|| async { Json(serde_json::json!({"choices": [{"message": {"content": "• Done"}}]})) }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let backend = |provider| Backend { provider, client: Client::new() };
+        let mut summarizer = Summarizer::with_backends(
+            vec![
+                backend(Provider::OpenAi {
+                    api_key: "sk-test".to_string(),
+                    url: format!("{}/refuses", base),
+                    model: "gpt-4o-mini".to_string(),
+                }),
+                backend(Provider::Local {
+                    url: format!("{}/answers", base),
+                    model: "llama3.1".to_string(),
+                }),
+            ],
+            0,
+        );
+        let completion = summarizer.generate_summary("Title", "Text").await.unwrap();
+        assert_eq!((completion.text.as_str(), completion.provider), ("• Done", "local"));
+        assert_eq!(completion.model, "llama3.1");
 
-    pub fn model_version(&self) -> &'static str {
-        CLAUDE_MODEL
+        // With nothing to fall back on, the error comes through
+        summarizer.backends.truncate(1);
+        let error = summarizer.generate_summary("Title", "Text").await.unwrap_err();
+        assert!(matches!(error, AppError::OpenAiApi(ref m) if m.contains("bad key")), "{}", error);
     }
 }
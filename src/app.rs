@@ -1,28 +1,254 @@
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Instant;
 
+use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
 
-use crate::ai::Summarizer;
-use crate::blocklist::Blocklist;
-use crate::config::Config;
-use crate::db::Repository;
-use crate::error::Result;
-use crate::feed::{export_opml_file, parse_opml_file, FeedFetcher};
-use crate::models::{Article, Feed, Summary, SummaryStatus};
-use crate::services::{ContentFetcher, RaindropClient};
-use crate::tui::AppAction;
+use crate::ai::{cited_sources, content_hash, Completion, Summarizer};
+use crate::blocklist::{Blocklist, SharedBlocklist, SubscriptionStatus, BLOCKLIST_POLL};
+use crate::config::{Config, EmbedConfig, ScheduleConfig, Subscription};
+use crate::db::{ArticleBatch, ArticleFilter, FeedBulkUpdate, FeedRemoval, ListedFeed, Pipeline, Query, Repository};
+use crate::error::{AppError, Result};
+use crate::export::{
+    sanitize_file_name, Clipboard, Embed, NoteExporter, PacketExporter, PacketFormat, PacketItem, PdfExporter, Templates,
+};
+use crate::focus::FocusTimer;
+use crate::metrics::metrics;
+use crate::feed::{
+    candidate_domains, discover_recommendations, export_opml_file, export_opml_string, OpmlHead, find_by_url, find_duplicate, link_target,
+    normalize_authors, parse_bookmarks_file, parse_opml_file, parse_starred_file, parse_urls, DuplicateKind, FeedFetcher, FeedRecommendation,
+    FeedRefresh, Shelf, MAX_SUGGESTED_TAGS,
+};
+use crate::models::{
+    estimate_reading_minutes, Article, ArticleRevision, AuthorCount, AuthorRule, Comment, Discussion, EntityCount, Feed, FeedPriority,
+    NewArticle, NewFeed, ReadingMode, Summary, SummaryFailure, SummaryStatus,
+};
+use crate::services::{
+    Archiver, ContentFetcher, DiscussionClient, FairQueue, HttpCache, Playback, Player, RaindropClient, RemovalChecker,
+};
+use crate::tui::{
+    article_links, external_editor, format_snooze, fuzzy_score, palette_matches, parse_snooze, reader_text,
+    sidebar_rows, AppAction, ArticleFind, ArticleLink, BlockTarget, KeyBinding, KeyContext, Macro, MacroStep, NoteEdit,
+    PaletteCommand, PaneLayout, Panes, SidebarRow, SidebarView, PANES_SETTING,
+};
+use crate::stories::{story_group, STORY_WINDOW_HOURS};
+use crate::trends::{load_headlines, load_trends, Headline, Trend};
+use crate::calendar::{load_calendar, Calendar, CALENDAR_WEEKS};
+use crate::watchlist::{load_timelines, Timeline, TIMELINE_DAYS};
+use crate::subscriptions::{reconcile, Reconciliation};
+use crate::backup::{self, BackedUpArticle, Backup};
+use crate::bundle::{self, Bundle, BundledArticle};
+
+/// The link after (or before) `current`, wrapping around; the first (or
+/// last) if none is highlighted
+fn step_link(links: &[ArticleLink], current: Option<usize>, forward: bool) -> usize {
+    let position = current.and_then(|n| links.iter().position(|l| l.number == n));
+    let index = match (position, forward) {
+        (None, true) => 0,
+        (None, false) => links.len() - 1,
+        (Some(i), true) => (i + 1) % links.len(),
+        (Some(i), false) => (i + links.len() - 1) % links.len(),
+    };
+    links[index].number
+}
+
+/// Number of articles fetched from the database per page
+const ARTICLE_PAGE_SIZE: usize = 200;
+
+/// How many unsubscribed starred-article sites to probe for feeds
+const RECOMMENDATION_CANDIDATES: usize = 15;
+
+/// The entity browser lists this many entities from this many days back
+const ENTITY_BROWSE_LIMIT: usize = 40;
+const ENTITY_BROWSE_DAYS: i64 = 7;
+
+/// The author browser lists this many bylines from this many days back
+const AUTHOR_BROWSE_LIMIT: usize = 40;
+const AUTHOR_BROWSE_DAYS: i64 = 30;
+
+/// Trending terms shown by the trends popup
+const TRENDS_SHOWN: usize = 15;
+
+/// Questions about the articles in view use at most this many of them
+pub const ASK_MAX_ARTICLES: usize = 20;
+
+/// Outlets compared in a story briefing, including the selected article's
+const BRIEFING_MAX_OUTLETS: usize = 8;
+
+/// Days of coverage recounted for watched entities after each refresh
+/// (older articles are gone by then)
+const COVERAGE_RECOUNT_DAYS: i64 = 7;
+
+/// Discussion lookups: articles this recent are checked, re-checked this often,
+/// and at most this many per refresh
+const DISCUSSION_MAX_AGE_HOURS: u32 = 48;
+const DISCUSSION_RECHECK_HOURS: u32 = 6;
+const DISCUSSION_BATCH: usize = 30;
+
+/// Saved articles checked for having been taken down, at most per refresh
+const REMOVAL_BATCH: usize = 40;
+
+/// How often to ask the podcast player for its position
+const PLAYBACK_POLL_SECS: u64 = 5;
+
+/// An episode counts as played when stopped within this many seconds of the end
+const PLAYED_MARGIN_SECS: u32 = 30;
+
+/// How often to bring back snoozed articles that are due
+const SNOOZE_CHECK_SECS: u64 = 60;
+
+/// How often to look at the urls file for changes
+const URLS_FILE_CHECK_SECS: u64 = 5;
+
+/// Load the next page once the selection is this close to the end of the loaded list
+const PAGE_PREFETCH_MARGIN: usize = 20;
+
+/// Which articles the list shows (`f` cycles through them)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FilterMode {
+    #[default]
+    All,
+    Starred,
+    Queue,
+    /// Articles with an HN/Lobsters thread, most discussed first
+    Discussed,
+    /// The articles opened last, whether or not they're still unread
+    History,
+    /// A named collection, in its own order (see `App::collection`)
+    Collection,
+}
+
+impl FilterMode {
+    pub fn next(self) -> Self {
+        match self {
+            FilterMode::All => FilterMode::Starred,
+            FilterMode::Starred => FilterMode::Queue,
+            FilterMode::Queue => FilterMode::Discussed,
+            FilterMode::Discussed => FilterMode::History,
+            FilterMode::History | FilterMode::Collection => FilterMode::All,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            FilterMode::All => "All",
+            FilterMode::Starred => "Starred",
+            FilterMode::Queue => "Queued",
+            FilterMode::Discussed => "Discussed",
+            FilterMode::History => "Recently read",
+            FilterMode::Collection => "Collected",
+        }
+    }
+}
+
+/// Key the last session's view is saved under in the settings table
+const SESSION_SETTING: &str = "session";
+
+/// How deep into the All view to page looking for the last-read article
+const SESSION_RESTORE_PAGES: usize = 5;
+
+/// Where the user left off: what the list was showing, and which article
+/// was open. Saved on quit and put back on the next launch.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct Session {
+    filter: FilterMode,
+    feed_id: Option<i64>,
+    group: Option<String>,
+    today: bool,
+    /// Folded feed list groups
+    collapsed: Vec<String>,
+    tag: Option<String>,
+    search: Option<String>,
+    article_id: Option<i64>,
+    /// Reading full screen, in the list-only layout
+    reading: bool,
+    find: Option<String>,
+    /// The collection shown, for the collection view
+    collection: Option<String>,
+}
+
+/// A feed list row, detached from the feeds it borrows
+enum SidebarPick {
+    View(SidebarView),
+    Group(String),
+    Feed(i64, Option<String>),
+}
+
+impl From<&SidebarRow<'_>> for SidebarPick {
+    fn from(row: &SidebarRow) -> Self {
+        match row {
+            SidebarRow::View(view) => SidebarPick::View(*view),
+            SidebarRow::Group { name, .. } => SidebarPick::Group(name.clone()),
+            SidebarRow::Feed { feed, group } => SidebarPick::Feed(feed.id, group.clone()),
+        }
+    }
+}
 
 // Message for completed summary
 pub struct SummaryResult {
     pub article_id: i64,
-    pub result: std::result::Result<(String, String), String>, // (content, model) or error
+    pub result: Result<Completion>,
+    /// Tags from the AI, when `tagging.llm` is on
+    pub tags: Option<Vec<String>>,
+    /// Hash of the summarized content, stored so duplicates can reuse it
+    pub content_hash: Option<String>,
+}
+
+/// What the answer popup is waiting on Claude for
+enum LlmTask {
+    Question(String),
+    Briefing,
+}
+
+// Message for an answered question or finished briefing
+pub struct AnswerResult {
+    /// The question, or what the briefing is about
+    pub question: String,
+    pub result: std::result::Result<String, String>,
+}
+
+/// The answer to a question asked with `A` (or a briefing from `B`), and
+/// the articles it cites
+#[derive(Debug, Clone)]
+pub struct Answer {
+    pub question: String,
+    pub text: String,
+    pub citations: Vec<Citation>,
+}
+
+/// An article an answer cites by number
+#[derive(Debug, Clone)]
+pub struct Citation {
+    pub number: usize,
+    pub article_id: i64,
+    pub title: String,
+}
+
+/// How one article in a batch summarize went
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchOutcome {
+    Generated,
+    /// Identical content had already been summarized
+    Reused,
+    Failed(String),
 }
 
-// Message for completed refresh
-pub struct RefreshResult {
-    pub results: Vec<(i64, Vec<crate::models::NewArticle>)>,
+// Message for a finished run of failed-summary retries
+pub struct RetryResult {
+    pub article_ids: Vec<i64>,
+    pub succeeded: usize,
+}
+
+// Messages from a running refresh
+pub enum RefreshResult {
+    /// One feed's fetch finished
+    Feed(i64, FeedRefresh),
+    /// Every due feed has been fetched
+    Done,
 }
 
 // Message for completed feed discovery
@@ -30,15 +256,133 @@ pub struct FeedDiscoveryResult {
     pub result: std::result::Result<crate::models::NewFeed, String>,
 }
 
+// Message for a fetched comment thread
+pub struct CommentsResult {
+    pub article_id: i64,
+    pub result: std::result::Result<Vec<Comment>, String>,
+}
+
+// Message for finished HN/Lobsters lookups: every checked article, with
+// whatever threads were found for it
+pub struct DiscussionResult {
+    pub checked: Vec<(i64, Vec<Discussion>)>,
+}
+
+// Message for finished take-down checks: the articles whose pages were
+// reached, and whether each was gone
+pub struct RemovalResult {
+    pub checked: Vec<(i64, bool)>,
+}
+
+// Message for a finished starred-article snapshot
+pub struct ArchiveResult {
+    pub article_id: i64,
+    pub result: std::result::Result<std::path::PathBuf, String>,
+}
+
+// Message for a finished PDF export
+pub struct ExportResult {
+    pub result: std::result::Result<std::path::PathBuf, String>,
+}
+
+// Message for completed feed recommendations
+pub struct RecommendationResult {
+    pub recommendations: Vec<FeedRecommendation>,
+}
+
 pub struct App {
     // Data
     pub feeds: Vec<Feed>,
     pub articles: Vec<Article>,
+    pub total_articles: usize,
+    pub has_more_articles: bool,
+    pub queue_minutes: u32,
     pub current_summary: Option<Summary>,
-    blocklist: Blocklist,
+    /// Comment thread fetched for an article (article_id, comments)
+    pub comments: Option<(i64, Vec<Comment>)>,
+    blocklist: SharedBlocklist,
+    /// The blocklist's generation when last announced
+    blocklist_generation: u64,
 
     // UI State
+    pub filter_mode: FilterMode,
     pub selected_index: usize,
+    /// Pane layout and widths, saved between sessions
+    pub panes: Panes,
+    pub feeds_focused: bool,
+    /// Reading the selected article full screen (list-only layout)
+    pub reading: bool,
+    /// The collection the collection view shows
+    pub collection: Option<String>,
+    /// Show only this feed's articles, picked in the feed list (in the All view)
+    pub feed_filter: Option<i64>,
+    /// A feed list group (folder or tag) whose articles are shown
+    pub group_filter: Option<String>,
+    /// Only today's articles ("Today" in the feed list)
+    pub today_only: bool,
+    /// The group the selected feed was picked under, as tag groups can
+    /// list a feed more than once
+    feed_group: Option<String>,
+    /// Folded feed list groups, as `SidebarGrouping::collapse_key`s
+    pub collapsed_groups: Vec<String>,
+    /// Find in the open article: the prompt, then the search once confirmed
+    pub find_input_active: bool,
+    pub find_input: String,
+    pub find: Option<ArticleFind>,
+    /// Link highlighted in the reader, by its number
+    pub link_number: Option<usize>,
+    /// Digits typed before a command (`3o` opens link 3)
+    pub count: Option<usize>,
+    /// Selection mode: articles picked one by one, plus the start of a
+    /// range that runs to the cursor
+    pub select_mode: bool,
+    pub selected_ids: Vec<i64>,
+    pub range_anchor: Option<usize>,
+    pub batch_tag_active: bool,
+    pub batch_tag_input: String,
+    /// The reader's own note on the selected article
+    pub note: Option<String>,
+    pub note_input_active: bool,
+    pub note_input: String,
+    /// A note waiting to be opened in `$EDITOR` by the main loop
+    pub editor_request: Option<NoteEdit>,
+    pub snooze_active: bool,
+    /// A snooze time being typed out
+    pub snooze_input: Option<String>,
+    /// When snoozes last ended, checked about once a minute
+    snooze_checked_at: Option<Instant>,
+    /// The `urls_file` setting, when it was last looked at, and its
+    /// modification time at the last sync
+    urls_file: Option<PathBuf>,
+    urls_checked_at: Option<Instant>,
+    urls_synced_modified: Option<std::time::SystemTime>,
+    urls_error: Option<String>,
+    /// `[[subscriptions]]` from the config; empty when feeds are managed
+    /// in the app
+    subscriptions: Vec<Subscription>,
+    /// An embargo time being typed out for the selected article
+    pub embargo_input: Option<String>,
+    /// Rules from `[[embargo]]`, with their hold in hours
+    embargo_rules: Vec<(Query, u32)>,
+    /// `[[macros]]`, in order
+    macros: Vec<Macro>,
+    /// Their keys, for `handle_key_event`
+    pub macro_keys: Vec<KeyBinding>,
+    /// Embargoes lifted up to here have been picked up by the list
+    embargo_checked_to: chrono::DateTime<chrono::Utc>,
+    /// Seconds spent reading today, and on the selected article (saved
+    /// time only; see `time_on_article`)
+    pub reading_today_secs: u64,
+    article_reading_secs: u64,
+    pub daily_goal_minutes: Option<u32>,
+    nudge_minutes: Option<u32>,
+    focus: FocusTimer,
+    /// The day `reading_today_secs` counts
+    focus_day: chrono::NaiveDate,
+    goal_reached: bool,
+    pub palette_active: bool,
+    pub palette_input: String,
+    pub palette_index: usize,
     pub show_help: bool,
     pub bookmark_prefix_active: bool,
     pub bookmark_status: Option<(String, Instant)>, // (message, timestamp)
@@ -47,12 +391,107 @@ pub struct App {
     pub feed_input_active: bool,
     pub feed_input: String,
     pub feed_input_status: Option<String>,
+    /// Feed URL the user was warned shares a site with a subscription;
+    /// confirming it again adds it anyway
+    same_site_ack: Option<String>,
     pub opml_input_active: bool,
     pub opml_input: String,
     pub opml_input_status: Option<String>,
     pub opml_export_active: bool,
     pub opml_export_input: String,
     pub opml_export_status: Option<String>,
+    pub rename_feed_active: bool,
+    pub rename_feed_input: String,
+    rename_feed_id: Option<i64>,
+    /// Feed the unsubscribe prompt is asking about
+    pub remove_feed_id: Option<i64>,
+    /// Feed whose URL stopped working, asked about switching to the one
+    /// found on its site
+    pub feed_move_id: Option<i64>,
+    /// Moved feeds put off until the next start
+    feed_moves_later: HashSet<i64>,
+    /// Show only articles with this suggested tag (in the All view)
+    pub tag_filter: Option<String>,
+    pub tag_filter_active: bool,
+    pub tag_filter_input: String,
+    /// Search typed at the `/` prompt, shown as entered, and its parse
+    pub search: Option<(String, Query)>,
+    pub search_active: bool,
+    pub search_input: String,
+    /// Why the last search didn't parse, shown under the prompt
+    pub search_error: Option<String>,
+    /// Most used tags, offered in the tag filter popup
+    pub tag_suggestions: Vec<(String, usize)>,
+    /// Show only articles mentioning this entity (in the All view)
+    pub entity_filter: Option<String>,
+    pub entities_active: bool,
+    pub entities: Vec<EntityCount>,
+    pub entity_index: usize,
+    /// Show only articles by this author (in the All view)
+    pub author_filter: Option<String>,
+    pub authors_active: bool,
+    pub authors: Vec<AuthorCount>,
+    pub author_index: usize,
+    /// The blocklist's rules, shown by the blocklist popup
+    pub blocklist_active: bool,
+    pub blocklist_entries: Vec<String>,
+    pub blocklist_subscriptions: Vec<SubscriptionStatus>,
+    pub blocklist_index: usize,
+    /// An entry being typed in the blocklist popup
+    pub blocklist_input: Option<String>,
+    /// The entry the one being typed replaces
+    blocklist_editing: Option<String>,
+    /// Followed and muted authors, reread before each refresh is stored
+    author_rules: Vec<(String, AuthorRule)>,
+    /// Watched entities' coverage, shown by the watchlist popup
+    pub watch_active: bool,
+    pub watch_timelines: Vec<Timeline>,
+    pub watch_index: usize,
+    /// Watched entities whose coverage spiked today
+    pub coverage_spikes: Vec<String>,
+    /// (author, title) of articles by followed authors from the last refresh
+    pub followed_news: Vec<(String, String)>,
+    /// (feed, title) of unread articles from high-priority feeds from the
+    /// last refresh
+    pub priority_news: Vec<(String, String)>,
+    /// (title, archived copy) of saved articles found taken down by the
+    /// last check
+    pub removed_news: Vec<(String, Option<String>)>,
+    /// Show only articles containing this trending term (in the All view)
+    pub term_filter: Option<String>,
+    pub trends_active: bool,
+    /// Earlier versions of the selected article, newest first, shown
+    /// against the one after each
+    pub revisions_active: bool,
+    pub revisions: Vec<ArticleRevision>,
+    pub revision_index: usize,
+    pub revision_scroll: u16,
+    /// Articles per day, shown by the calendar popup
+    pub calendar_active: bool,
+    pub calendar: Option<Calendar>,
+    /// Show only articles from this local day, picked in the calendar
+    pub day_filter: Option<chrono::NaiveDate>,
+    pub trends: Vec<Trend>,
+    pub trend_index: usize,
+    /// Question being typed; `ask_all` asks about the articles in view
+    /// rather than just the selected one
+    pub ask_input_active: bool,
+    pub ask_input: String,
+    pub ask_all: bool,
+    pub answer_active: bool,
+    pub answer: Option<Answer>,
+    pub answer_status: Option<String>,
+    pub answer_index: usize,
+    /// Articles sent with the pending question, numbered from 1 (id, title)
+    ask_sources: Vec<(i64, String)>,
+    /// Failed summaries, shown by the failure browser
+    pub failures_active: bool,
+    pub failures: Vec<SummaryFailure>,
+    pub failure_index: usize,
+    pub recommendations_active: bool,
+    pub recommendations: Vec<FeedRecommendation>,
+    pub recommendation_index: usize,
+    pub recommendations_status: Option<String>,
     pub is_saved_to_raindrop: bool,
     pub last_deleted: Option<(i64, String)>, // (feed_id, guid) for undo
     pub spinner_frame: usize,
@@ -60,61 +499,211 @@ pub struct App {
 
     // Async state
     pub is_refreshing: bool,
+    /// Feeds the running refresh is fetching
+    pub refreshing_feeds: usize,
+    /// When the running (or last) refresh started; its new articles were
+    /// stored after this
+    refresh_started_at: chrono::DateTime<chrono::Utc>,
     pub summary_status: SummaryStatus,
     pub pending_summary_article_id: Option<i64>,
+    pub pending_comments_article_id: Option<i64>,
+    /// Episode being played (article_id, player process)
+    pub playback: Option<(i64, Playback)>,
+    last_playback_poll: Instant,
     summary_rx: mpsc::Receiver<SummaryResult>,
     summary_tx: mpsc::Sender<SummaryResult>,
     refresh_rx: mpsc::Receiver<RefreshResult>,
     refresh_tx: mpsc::Sender<RefreshResult>,
     discovery_rx: mpsc::Receiver<FeedDiscoveryResult>,
     discovery_tx: mpsc::Sender<FeedDiscoveryResult>,
+    recommendation_rx: mpsc::Receiver<RecommendationResult>,
+    recommendation_tx: mpsc::Sender<RecommendationResult>,
+    answer_rx: mpsc::Receiver<AnswerResult>,
+    answer_tx: mpsc::Sender<AnswerResult>,
+    retry_rx: mpsc::Receiver<RetryResult>,
+    retry_tx: mpsc::Sender<RetryResult>,
+    retry_running: bool,
+    comments_rx: mpsc::Receiver<CommentsResult>,
+    comments_tx: mpsc::Sender<CommentsResult>,
+    discussion_rx: mpsc::Receiver<DiscussionResult>,
+    discussion_tx: mpsc::Sender<DiscussionResult>,
+    discussion_lookup_running: bool,
+    removal_rx: mpsc::Receiver<RemovalResult>,
+    removal_tx: mpsc::Sender<RemovalResult>,
+    removal_check_running: bool,
+    archive_rx: mpsc::Receiver<ArchiveResult>,
+    archive_tx: mpsc::Sender<ArchiveResult>,
+    export_rx: mpsc::Receiver<ExportResult>,
+    export_tx: mpsc::Sender<ExportResult>,
 
     // Services
     pub repository: Repository,
     fetcher: FeedFetcher,
     summarizer: Option<Arc<Summarizer>>,
+    llm_tagging: bool,
+    /// Which destinations get the stored summary and tags
+    embed: EmbedConfig,
+    /// Quiet hours and refresh windows
+    schedule: ScheduleConfig,
     raindrop: Option<RaindropClient>,
     content_fetcher: ContentFetcher,
+    http_cache: HttpCache,
+    discussions: Option<DiscussionClient>,
+    /// Set when `[deletions]` is on, with its re-check interval in hours
+    removals: Option<(RemovalChecker, u32)>,
+    player: Player,
+    archiver: Option<Archiver>,
+    pdf_exporter: PdfExporter,
+    packet_exporter: PacketExporter,
+    notes: Option<NoteExporter>,
+    templates: Arc<Templates>,
+    clipboard: Clipboard,
+    /// Owner details for exported OPML files
+    opml_head: OpmlHead,
 }
 
 impl App {
     pub async fn new(config: &Config) -> Result<Self> {
         let repository = Repository::new(&config.db_path).await?;
-        let fetcher = FeedFetcher::new();
+        let today = chrono::Local::now().date_naive();
+        let reading_today_secs = repository.reading_seconds_on(today).await?;
+        // The fetcher drops blocked articles; the file is watched for edits
+        let blocklist = SharedBlocklist::load();
+        blocklist.watch(BLOCKLIST_POLL, config.blocklist.refresh());
+        let fetcher = FeedFetcher::new().with_blocklist(blocklist.clone());
 
-        let summarizer = config
-            .claude_api_key
-            .as_ref()
-            .map(|key| Arc::new(Summarizer::new(key.clone())));
+        let summarizer = Summarizer::from_config(config)?.map(Arc::new);
 
+        // Local-only mode sends nothing to outside services
         let raindrop = config
             .raindrop_token
             .as_ref()
+            .filter(|_| !config.local_only)
             .map(|token| RaindropClient::new(token.clone()));
 
-        let content_fetcher = ContentFetcher::new();
-
-        // Clean up articles older than 7 days
-        let deleted = repository.delete_old_articles(7).await?;
-        if deleted > 0 {
-            tracing::info!("Deleted {} articles older than 7 days", deleted);
-        }
+        let http_cache = HttpCache::new(&config.http_cache);
+        let content_fetcher = ContentFetcher::new(&config.content, !config.local_only, http_cache.clone());
+        let discussions = (config.discussions.enabled && !config.local_only).then(DiscussionClient::new);
+        let removals = config
+            .deletions
+            .enabled
+            .then(|| (RemovalChecker::new(), config.deletions.recheck_hours));
+        let player = Player::new(&config.podcast.player);
+        let templates = Arc::new(Templates::load(&config.templates)?);
+        let embargo_rules = config
+            .embargo
+            .iter()
+            .map(|rule| {
+                Query::parse(&rule.query)
+                    .map(|query| (query, rule.hours))
+                    .map_err(|e| AppError::Config(format!("Bad [[embargo]] query \"{}\": {}", rule.query, e)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let macros = config.macros.iter().map(Macro::from_config).collect::<Result<Vec<_>>>()?;
+        let archiver = config
+            .archive
+            .enabled
+            .then(|| Archiver::new(config.archive.dir(), templates.clone(), http_cache.clone()));
+        let pdf_exporter = PdfExporter::new(&config.export.pdf_command, config.export.dir(), templates.clone());
+        let notes = NoteExporter::new(&config.notes, &config.default_tags, templates.clone());
+        let clipboard = Clipboard::new(config.export.clipboard_command.as_deref());
+        let packet_exporter = PacketExporter::new(&config.export.epub_command, config.export.dir());
 
         let feeds = repository.get_all_feeds().await?;
-        let articles = repository.get_all_articles_sorted().await?;
+        // Proposals left from an earlier session are asked about again
+        let feed_move_id = feeds.iter().find(|f| f.moved_to.is_some()).map(|f| f.id);
+        let articles = repository.get_articles_page(ARTICLE_PAGE_SIZE, None).await?;
+        let has_more_articles = articles.len() == ARTICLE_PAGE_SIZE;
+        let total_articles = repository.count_articles().await?;
+        let (_, queue_minutes) = repository.queue_stats().await?;
+        let author_rules = repository.get_author_rules().await?;
+        let panes = match repository.get_setting(PANES_SETTING).await? {
+            Some(saved) => serde_json::from_str(&saved).unwrap_or_default(),
+            None => Panes::default(),
+        };
+
+        // Clean up articles older than 7 days once the first page is loaded,
+        // so a big cleanup doesn't hold up startup
+        let cleanup = repository.clone();
+        tokio::spawn(async move {
+            match cleanup.delete_old_articles(7).await {
+                Ok(0) => {}
+                Ok(deleted) => tracing::info!("Deleted {} articles older than 7 days", deleted),
+                Err(e) => tracing::warn!("Failed to delete old articles: {}", e),
+            }
+        });
 
         let (summary_tx, summary_rx) = mpsc::channel(1);
-        let (refresh_tx, refresh_rx) = mpsc::channel(1);
+        let (refresh_tx, refresh_rx) = mpsc::channel(16);
         let (discovery_tx, discovery_rx) = mpsc::channel(1);
-
-        let blocklist = Blocklist::load();
+        let (recommendation_tx, recommendation_rx) = mpsc::channel(1);
+        let (answer_tx, answer_rx) = mpsc::channel(1);
+        let (retry_tx, retry_rx) = mpsc::channel(1);
+        let (comments_tx, comments_rx) = mpsc::channel(1);
+        let (discussion_tx, discussion_rx) = mpsc::channel(1);
+        let (removal_tx, removal_rx) = mpsc::channel(1);
+        // Several articles can be starred while snapshots are still running
+        let (archive_tx, archive_rx) = mpsc::channel(8);
+        let (export_tx, export_rx) = mpsc::channel(8);
 
         Ok(Self {
             feeds,
             articles,
+            total_articles,
+            has_more_articles,
+            queue_minutes,
             current_summary: None,
+            comments: None,
+            blocklist_generation: blocklist.generation(),
             blocklist,
+            filter_mode: FilterMode::default(),
             selected_index: 0,
+            panes,
+            feeds_focused: false,
+            reading: false,
+            collection: None,
+            feed_filter: None,
+            group_filter: None,
+            today_only: false,
+            feed_group: None,
+            collapsed_groups: Vec::new(),
+            find_input_active: false,
+            find_input: String::new(),
+            find: None,
+            link_number: None,
+            count: None,
+            select_mode: false,
+            selected_ids: Vec::new(),
+            range_anchor: None,
+            batch_tag_active: false,
+            batch_tag_input: String::new(),
+            note: None,
+            note_input_active: false,
+            note_input: String::new(),
+            editor_request: None,
+            snooze_active: false,
+            snooze_input: None,
+            snooze_checked_at: None,
+            urls_file: config.urls_file.clone(),
+            urls_checked_at: None,
+            urls_synced_modified: None,
+            urls_error: None,
+            subscriptions: config.subscriptions.clone(),
+            embargo_input: None,
+            embargo_rules,
+            macro_keys: macros.iter().map(|m| m.key).collect(),
+            macros,
+            embargo_checked_to: chrono::Utc::now(),
+            reading_today_secs,
+            article_reading_secs: 0,
+            daily_goal_minutes: config.focus.daily_goal_minutes,
+            nudge_minutes: config.focus.nudge_minutes,
+            focus: FocusTimer::new(Instant::now()),
+            focus_day: today,
+            goal_reached: config.focus.daily_goal_minutes.is_some_and(|goal| reading_today_secs >= u64::from(goal) * 60),
+            palette_active: false,
+            palette_input: String::new(),
+            palette_index: 0,
             show_help: false,
             bookmark_prefix_active: false,
             bookmark_status: None,
@@ -123,30 +712,135 @@ impl App {
             feed_input_active: false,
             feed_input: String::new(),
             feed_input_status: None,
+            same_site_ack: None,
             opml_input_active: false,
             opml_input: String::new(),
             opml_input_status: None,
             opml_export_active: false,
             opml_export_input: String::new(),
             opml_export_status: None,
+            rename_feed_active: false,
+            rename_feed_input: String::new(),
+            rename_feed_id: None,
+            remove_feed_id: None,
+            feed_move_id,
+            feed_moves_later: HashSet::new(),
+            tag_filter: None,
+            tag_filter_active: false,
+            tag_filter_input: String::new(),
+            search: None,
+            search_active: false,
+            search_input: String::new(),
+            search_error: None,
+            tag_suggestions: Vec::new(),
+            entity_filter: None,
+            entities_active: false,
+            entities: Vec::new(),
+            entity_index: 0,
+            author_filter: None,
+            authors_active: false,
+            authors: Vec::new(),
+            author_index: 0,
+            blocklist_active: false,
+            blocklist_entries: Vec::new(),
+            blocklist_subscriptions: Vec::new(),
+            blocklist_index: 0,
+            blocklist_input: None,
+            blocklist_editing: None,
+            author_rules,
+            watch_active: false,
+            watch_timelines: Vec::new(),
+            watch_index: 0,
+            coverage_spikes: Vec::new(),
+            followed_news: Vec::new(),
+            priority_news: Vec::new(),
+            removed_news: Vec::new(),
+            term_filter: None,
+            trends_active: false,
+            revisions_active: false,
+            revisions: Vec::new(),
+            revision_index: 0,
+            revision_scroll: 0,
+            calendar_active: false,
+            calendar: None,
+            day_filter: None,
+            trends: Vec::new(),
+            trend_index: 0,
+            ask_input_active: false,
+            ask_input: String::new(),
+            ask_all: false,
+            answer_active: false,
+            answer: None,
+            answer_status: None,
+            answer_index: 0,
+            ask_sources: Vec::new(),
+            failures_active: false,
+            failures: Vec::new(),
+            failure_index: 0,
+            recommendations_active: false,
+            recommendations: Vec::new(),
+            recommendation_index: 0,
+            recommendations_status: None,
             is_saved_to_raindrop: false,
             last_deleted: None,
             spinner_frame: 0,
             saved_count: 0,
             is_refreshing: false,
+            refreshing_feeds: 0,
+            refresh_started_at: chrono::Utc::now(),
             summary_status: SummaryStatus::NotGenerated,
             pending_summary_article_id: None,
+            pending_comments_article_id: None,
+            playback: None,
+            last_playback_poll: Instant::now(),
             summary_rx,
             summary_tx,
             refresh_rx,
             refresh_tx,
             discovery_rx,
             discovery_tx,
+            recommendation_rx,
+            recommendation_tx,
+            answer_rx,
+            answer_tx,
+            retry_rx,
+            retry_tx,
+            retry_running: false,
+            comments_rx,
+            comments_tx,
+            discussion_rx,
+            discussion_tx,
+            discussion_lookup_running: false,
+            removal_rx,
+            removal_tx,
+            removal_check_running: false,
+            archive_rx,
+            archive_tx,
+            export_rx,
+            export_tx,
             repository,
             fetcher,
             summarizer,
+            llm_tagging: config.tagging.llm,
+            embed: config.summaries.embed.clone(),
+            schedule: config.schedule.clone(),
             raindrop,
             content_fetcher,
+            http_cache,
+            discussions,
+            removals,
+            player,
+            archiver,
+            pdf_exporter,
+            packet_exporter,
+            notes,
+            templates,
+            clipboard,
+            opml_head: OpmlHead {
+                title: None,
+                owner_name: config.export.owner_name.clone(),
+                owner_email: config.export.owner_email.clone(),
+            },
         })
     }
 
@@ -159,14 +853,64 @@ impl App {
         articles.get(self.selected_index).copied()
     }
 
-    #[allow(dead_code)]
-    pub fn blocklist(&self) -> &Blocklist {
+    /// Snapshot of the modal UI state for key routing
+    pub fn key_context(&self) -> KeyContext {
+        KeyContext {
+            tag_input_active: self.tag_input_active,
+            feed_input_active: self.feed_input_active,
+            opml_input_active: self.opml_input_active,
+            opml_export_active: self.opml_export_active,
+            rename_feed_active: self.rename_feed_active,
+            remove_feed_active: self.remove_feed_id.is_some(),
+            feed_move_active: self.feed_move_id.is_some(),
+            tag_filter_active: self.tag_filter_active,
+            search_active: self.search_active,
+            entities_active: self.entities_active,
+            authors_active: self.authors_active,
+            blocklist_active: self.blocklist_active,
+            blocklist_input_active: self.blocklist_input.is_some(),
+            watch_active: self.watch_active,
+            trends_active: self.trends_active,
+            calendar_active: self.calendar_active,
+            revisions_active: self.revisions_active,
+            ask_input_active: self.ask_input_active,
+            answer_active: self.answer_active,
+            failures_active: self.failures_active,
+            show_help: self.show_help,
+            bookmark_prefix_active: self.bookmark_prefix_active,
+            recommendations_active: self.recommendations_active,
+            feeds_focused: self.feeds_focused,
+            reading: self.reading,
+            find_input_active: self.find_input_active,
+            reader_marked: self.find.is_some() || self.link_number.is_some(),
+            select_mode: self.select_mode,
+            batch_tag_active: self.batch_tag_active,
+            palette_active: self.palette_active,
+            note_input_active: self.note_input_active,
+            snooze_active: self.snooze_active,
+            snooze_input_active: self.snooze_input.is_some(),
+            embargo_input_active: self.embargo_input.is_some(),
+        }
+    }
+
+    pub fn blocklist(&self) -> &SharedBlocklist {
         &self.blocklist
     }
 
     pub async fn handle_action(&mut self, action: AppAction) -> Result<bool> {
+        // A count only applies to the command right after it
+        let count = match action {
+            AppAction::CountDigit(_) => None,
+            _ => self.count.take(),
+        };
+
         match action {
             AppAction::Quit => {
+                if let Some((id, time)) = self.focus.take_pending() {
+                    self.save_reading_time(id, time).await?;
+                }
+                self.stop_playback().await?;
+                self.save_session().await?;
                 // Compact database on exit (remove old articles and vacuum)
                 let _ = self.repository.compact_database(7).await;
                 return Ok(true);
@@ -186,6 +930,9 @@ impl App {
                     self.selected_index += 1;
                     self.on_selection_changed().await?;
                 }
+                if self.selected_index + PAGE_PREFETCH_MARGIN >= len {
+                    self.load_more_articles().await?;
+                }
             }
 
             AppAction::MoveToTop => {
@@ -201,28 +948,218 @@ impl App {
                     self.selected_index = len - 1;
                     self.on_selection_changed().await?;
                 }
+                // Bottom of what's loaded so far; pull in the next page lazily
+                self.load_more_articles().await?;
             }
 
             AppAction::SelectArticle => {
+                if self.panes.layout == PaneLayout::ListOnly && self.selected_article().is_some() {
+                    self.reading = true;
+                }
+                self.record_opened().await?;
                 self.generate_summary().await?;
             }
 
+            AppAction::CloseReader => {
+                self.reading = false;
+            }
+
+            AppAction::CycleLayout => {
+                self.panes.layout = self.panes.layout.next();
+                self.reading = false;
+                self.feeds_focused = false;
+                self.bookmark_status = Some((format!("Layout: {}", self.panes.layout.label()), Instant::now()));
+                self.save_panes().await?;
+            }
+
+            AppAction::ResizeList(delta) => {
+                self.panes.resize_list(delta);
+                self.save_panes().await?;
+            }
+
+            AppAction::ResizeFeeds(delta) => {
+                if self.panes.layout == PaneLayout::ThreePane {
+                    self.panes.resize_feeds(delta);
+                    self.save_panes().await?;
+                }
+            }
+
+            AppAction::ToggleUnreadFeeds => {
+                self.panes.unread_feeds_only = !self.panes.unread_feeds_only;
+                self.save_panes().await?;
+            }
+
+            AppAction::ToggleFeedFocus => {
+                self.feeds_focused = !self.feeds_focused && self.feed_list_visible();
+            }
+
+            AppAction::FeedUp | AppAction::FeedDown => {
+                let rows = self.sidebar_rows();
+                let current = self.sidebar_row();
+                let row = match action {
+                    AppAction::FeedUp => current.saturating_sub(1),
+                    _ => (current + 1).min(rows.len() - 1),
+                };
+                if row != current {
+                    let pick = SidebarPick::from(&rows[row]);
+                    self.pick_sidebar(pick).await?;
+                }
+            }
+
+            AppAction::FeedListSelect => {
+                let group = match self.sidebar_rows().get(self.sidebar_row()) {
+                    Some(SidebarRow::Group { name, .. }) => Some(self.panes.grouping.collapse_key(name)),
+                    _ => None,
+                };
+                match group {
+                    Some(key) => {
+                        match self.collapsed_groups.iter().position(|k| *k == key) {
+                            Some(i) => {
+                                self.collapsed_groups.remove(i);
+                            }
+                            None => self.collapsed_groups.push(key),
+                        }
+                        self.save_session().await?;
+                    }
+                    None => self.feeds_focused = false,
+                }
+            }
+
+            AppAction::CycleSidebarGrouping => {
+                self.panes.grouping = self.panes.grouping.next();
+                self.save_panes().await?;
+                // Folder and tag names don't carry over
+                if self.group_filter.take().is_some() {
+                    self.show_filtered().await?;
+                }
+            }
+
             AppAction::RefreshFeeds => {
-                self.refresh_feeds();
+                self.refresh_feeds().await?;
             }
 
             AppAction::OpenInBrowser => {
-                if let Some(article) = self.selected_article() {
-                    let url = article.url.clone();
+                // A link picked by count or highlighted with `v`, else the article
+                let url = match count.or(self.link_number) {
+                    Some(number) => match self.link_url(number) {
+                        Some(url) => Some(url),
+                        None => {
+                            self.bookmark_status = Some((format!("No link {}", number), Instant::now()));
+                            None
+                        }
+                    },
+                    // The page is gone, so its snapshot stands in for it
+                    None => self.selected_article().map(|a| match (&a.removed_at, &a.archive_path) {
+                        (Some(_), Some(path)) => path.clone(),
+                        _ => self.reader_view(a),
+                    }),
+                };
+                if let Some(url) = url {
                     std::thread::spawn(move || {
                         let _ = open::that(&url);
                     });
+                    self.record_opened().await?;
+                }
+            }
+
+            AppAction::CountDigit(digit) => {
+                self.count = Some(self.count.unwrap_or(0).saturating_mul(10).saturating_add(digit as usize));
+            }
+
+            AppAction::NextLink | AppAction::PrevLink => {
+                let links = article_links(&self.reader_text());
+                let number = match (count, action) {
+                    _ if links.is_empty() => None,
+                    (Some(number), _) => links.iter().find(|l| l.number == number).map(|l| l.number),
+                    (None, AppAction::NextLink) => Some(step_link(&links, self.link_number, true)),
+                    (None, _) => Some(step_link(&links, self.link_number, false)),
+                };
+                match number {
+                    Some(number) => {
+                        self.link_number = Some(number);
+                        self.find = None;
+                    }
+                    None if links.is_empty() => {
+                        self.bookmark_status = Some(("No links in this article".to_string(), Instant::now()));
+                    }
+                    None => {
+                        let wanted = count.unwrap_or_default();
+                        self.bookmark_status = Some((format!("No link {}", wanted), Instant::now()));
+                    }
+                }
+            }
+
+            AppAction::FindStart => {
+                if self.selected_article().is_some() {
+                    self.find_input = self.find.as_ref().map(|f| f.term.clone()).unwrap_or_default();
+                    self.find_input_active = true;
+                }
+            }
+
+            AppAction::FindChar(c) => {
+                self.find_input.push(c);
+            }
+
+            AppAction::FindBackspace => {
+                self.find_input.pop();
+            }
+
+            AppAction::FindConfirm => {
+                self.find_input_active = false;
+                self.find = ArticleFind::new(&std::mem::take(&mut self.find_input));
+                self.link_number = None;
+                if let Some(find) = &self.find {
+                    if find.matches(&self.reader_text()).is_empty() {
+                        self.bookmark_status = Some((format!("No matches for \"{}\"", find.term), Instant::now()));
+                    }
+                }
+            }
+
+            AppAction::FindCancel => {
+                self.find_input_active = false;
+                self.find_input.clear();
+            }
+
+            AppAction::FindNext | AppAction::FindPrev => {
+                let forward = matches!(action, AppAction::FindNext);
+                let text = self.reader_text();
+                if let Some(find) = &mut self.find {
+                    let total = find.matches(&text).len();
+                    if total > 0 {
+                        find.current = if forward { (find.current + 1) % total } else { (find.current + total - 1) % total };
+                    }
+                } else {
+                    // Just a link highlighted: step through the links instead
+                    let links = article_links(&text);
+                    if !links.is_empty() {
+                        self.link_number = Some(step_link(&links, self.link_number, forward));
+                    }
+                }
+            }
+
+            AppAction::ClearFind => {
+                self.find = None;
+                self.link_number = None;
+            }
+
+            AppAction::OpenDiscussion => {
+                match self.selected_article().and_then(|a| a.discussion.as_ref()) {
+                    Some(discussion) => {
+                        let url = discussion.url.clone();
+                        std::thread::spawn(move || {
+                            let _ = open::that(&url);
+                        });
+                    }
+                    None => {
+                        self.bookmark_status = Some(("No HN/Lobsters thread".to_string(), Instant::now()));
+                    }
                 }
             }
 
             AppAction::EmailArticle => {
                 if let Some(article) = self.selected_article() {
-                    self.email_article(article);
+                    let embed = load_embed(&self.repository, article, self.embed.email).await?;
+                    email_article(article, &embed);
                 }
             }
 
@@ -236,6 +1173,10 @@ impl App {
                 self.bookmark_prefix_active = false;
             }
 
+            AppAction::RunMacro(index) => {
+                return self.run_macro(index).await;
+            }
+
             AppAction::SaveToRaindrop => {
                 if self.raindrop.is_some() && self.selected_article().is_some() {
                     self.tag_input_active = true;
@@ -256,16 +1197,50 @@ impl App {
                 self.generate_summary().await?;
             }
 
+            AppAction::FetchComments => {
+                self.start_comments_fetch();
+            }
+
+            AppAction::ExportPdf => {
+                if let Some(article) = self.selected_article().cloned() {
+                    let embed = load_embed(&self.repository, &article, self.embed.pdf).await?;
+                    let exporter = self.pdf_exporter.clone();
+                    let tx = self.export_tx.clone();
+                    self.bookmark_status = Some(("Exporting PDF...".to_string(), Instant::now()));
+                    tokio::spawn(async move {
+                        let result = exporter.export(&article, &embed).await.map_err(|e| e.to_string());
+                        let _ = tx.send(ExportResult { result }).await;
+                    });
+                }
+            }
+
+            AppAction::CopyBrief => {
+                self.copy_brief().await?;
+            }
+
+            AppAction::SaveNote => {
+                self.save_note().await?;
+            }
+
+            AppAction::TogglePlayback => {
+                self.toggle_playback().await?;
+            }
+
             AppAction::DeleteArticle => {
                 if let Some(article) = self.selected_article() {
                     let id = article.id;
                     let feed_id = article.feed_id;
                     let guid = article.guid.clone();
+                    let was_unread = !article.is_read;
                     self.repository.delete_article(id).await?;
+                    if was_unread {
+                        self.adjust_unread(feed_id, true);
+                    }
                     // Store for undo
                     self.last_deleted = Some((feed_id, guid));
                     // Remove from local list
                     self.articles.retain(|a| a.id != id);
+                    self.total_articles = self.total_articles.saturating_sub(1);
                     // Adjust selection if needed
                     let len = self.filtered_articles().len();
                     if len > 0 && self.selected_index >= len {
@@ -279,429 +1254,3687 @@ impl App {
 
             AppAction::DeleteFeed => {
                 if let Some(article) = self.selected_article() {
-                    let feed_id = article.feed_id;
-                    // Delete the feed (cascades to articles via foreign key)
-                    self.repository.delete_feed(feed_id).await?;
-                    // Remove all articles from this feed from local list
-                    self.articles.retain(|a| a.feed_id != feed_id);
-                    // Reload feeds list
-                    self.feeds = self.repository.get_all_feeds().await?;
-                    // Adjust selection if needed
-                    let len = self.filtered_articles().len();
-                    if len > 0 && self.selected_index >= len {
-                        self.selected_index = len - 1;
-                    }
-                    // Reset summary state
-                    self.summary_status = SummaryStatus::NotGenerated;
-                    self.current_summary = None;
+                    self.remove_feed_id = Some(article.feed_id);
                 }
             }
 
-            AppAction::UndeleteArticle => {
-                if let Some((feed_id, guid)) = self.last_deleted.take() {
-                    self.repository.undelete_article(feed_id, &guid).await?;
-                    // Article will return on next refresh (press 'r')
+            AppAction::RemoveFeed(removal) => {
+                if let Some(feed_id) = self.remove_feed_id.take() {
+                    self.remove_feed(feed_id, removal).await?;
                 }
             }
 
-            AppAction::ShowHelp => {
-                self.show_help = true;
+            AppAction::RemoveFeedCancel => {
+                self.remove_feed_id = None;
             }
 
-            AppAction::HideHelp => {
-                self.show_help = false;
+            AppAction::AcceptFeedMove => {
+                if let Some(feed_id) = self.feed_move_id.take() {
+                    let status = match self.accept_feed_move(feed_id).await {
+                        Ok(Some(url)) => format!("Now fetching {}", url),
+                        Ok(None) => "No new URL for that feed".to_string(),
+                        Err(AppError::Config(message)) => message,
+                        Err(e) => return Err(e),
+                    };
+                    self.bookmark_status = Some((status, Instant::now()));
+                    self.ask_next_feed_move();
+                }
             }
 
-            AppAction::TagInputChar(c) => {
-                self.tag_input.push(c);
+            AppAction::DismissFeedMove => {
+                if let Some(feed_id) = self.feed_move_id.take() {
+                    self.repository.dismiss_feed_move(feed_id).await?;
+                    self.reload_feeds().await?;
+                    self.ask_next_feed_move();
+                }
             }
 
-            AppAction::TagInputBackspace => {
-                self.tag_input.pop();
+            AppAction::FeedMoveLater => {
+                if let Some(feed_id) = self.feed_move_id.take() {
+                    self.feed_moves_later.insert(feed_id);
+                    self.ask_next_feed_move();
+                }
             }
 
-            AppAction::TagInputConfirm => {
-                self.save_to_raindrop().await?;
-                self.tag_input_active = false;
-                self.tag_input.clear();
+            AppAction::ToggleStar => {
+                if let Some(article) = self.selected_article() {
+                    let (id, starred) = (article.id, !article.is_starred);
+                    self.repository.set_starred(id, starred).await?;
+                    self.update_star_state(id, starred, false).await?;
+                    if starred {
+                        self.start_archive(id);
+                    }
+                }
             }
 
-            AppAction::TagInputCancel => {
-                self.tag_input_active = false;
-                self.tag_input.clear();
+            AppAction::TogglePin => {
+                if let Some(article) = self.selected_article() {
+                    let (id, pinned) = (article.id, !article.is_pinned);
+                    self.repository.set_pinned(id, pinned).await?;
+                    // Pinning stars the article; unpinning leaves it starred
+                    self.update_star_state(id, true, pinned).await?;
+                    self.start_archive(id);
+                }
             }
 
-            AppAction::AddFeed => {
-                self.feed_input_active = true;
-                self.feed_input.clear();
-                self.feed_input_status = None;
+            AppAction::ToggleQueue => {
+                if let Some(article) = self.selected_article() {
+                    let (id, queued) = (article.id, !article.is_queued);
+                    self.repository.set_queued(id, queued).await?;
+                    if self.filter_mode == FilterMode::Queue {
+                        self.reload_articles().await?;
+                        self.on_selection_changed().await?;
+                    } else {
+                        if let Some(article) = self.articles.iter_mut().find(|a| a.id == id) {
+                            article.is_queued = queued;
+                        }
+                        self.queue_minutes = self.repository.queue_stats().await?.1;
+                    }
+                }
             }
 
-            AppAction::FeedInputChar(c) => {
-                self.feed_input.push(c);
+            AppAction::SelectModeStart => {
+                self.select_mode = true;
+                self.selected_ids.clear();
+                self.range_anchor = None;
             }
 
-            AppAction::FeedInputBackspace => {
-                self.feed_input.pop();
+            AppAction::SelectModeEnd => {
+                self.end_select_mode();
             }
 
-            AppAction::FeedInputConfirm => {
-                self.start_feed_discovery();
+            AppAction::ToggleSelected => {
+                if let Some(id) = self.selected_article().map(|a| a.id) {
+                    match self.selected_ids.iter().position(|&s| s == id) {
+                        Some(i) => {
+                            self.selected_ids.remove(i);
+                        }
+                        None => self.selected_ids.push(id),
+                    }
+                }
             }
 
-            AppAction::FeedInputCancel => {
-                self.feed_input_active = false;
-                self.feed_input.clear();
-                self.feed_input_status = None;
+            AppAction::ToggleRange => match self.range_anchor {
+                None => self.range_anchor = Some(self.selected_index),
+                Some(_) => {
+                    self.selected_ids = self.batch_ids();
+                    self.range_anchor = None;
+                }
+            },
+
+            AppAction::BatchRead | AppAction::BatchStar => {
+                let ids = self.batch_ids();
+                let picked = self.articles.iter().filter(|a| ids.contains(&a.id));
+                // Mark read (star) unless every one already is; then undo it
+                let (batch, verb) = if matches!(action, AppAction::BatchRead) {
+                    let read = !picked.clone().all(|a| a.is_read);
+                    (ArticleBatch::Read(read), if read { "Marked read" } else { "Marked unread" })
+                } else {
+                    let starred = !picked.clone().all(|a| a.is_starred);
+                    (ArticleBatch::Starred(starred), if starred { "Starred" } else { "Unstarred" })
+                };
+                self.apply_batch(ids, batch, verb).await?;
             }
 
-            AppAction::ImportOpmlStart => {
-                self.opml_input_active = true;
-                self.opml_input.clear();
-                self.opml_input_status = None;
+            AppAction::BatchDelete => {
+                let ids = self.batch_ids();
+                self.apply_batch(ids, ArticleBatch::Delete, "Deleted").await?;
             }
 
-            AppAction::OpmlInputChar(c) => {
-                self.opml_input.push(c);
+            AppAction::BatchTagStart => {
+                if !self.batch_ids().is_empty() {
+                    self.batch_tag_active = true;
+                    self.batch_tag_input.clear();
+                }
             }
 
-            AppAction::OpmlInputBackspace => {
-                self.opml_input.pop();
+            AppAction::BatchTagChar(c) => {
+                self.batch_tag_input.push(c);
             }
 
-            AppAction::OpmlInputConfirm => {
-                self.import_opml_from_input().await?;
+            AppAction::BatchTagBackspace => {
+                self.batch_tag_input.pop();
             }
 
-            AppAction::OpmlInputCancel => {
-                self.opml_input_active = false;
-                self.opml_input.clear();
-                self.opml_input_status = None;
+            AppAction::BatchTagConfirm => {
+                self.batch_tag_active = false;
+                let tag = std::mem::take(&mut self.batch_tag_input);
+                let tag = tag.trim().trim_start_matches('#').split_whitespace().collect::<Vec<_>>().join(" ");
+                if !tag.is_empty() {
+                    let ids = self.batch_ids();
+                    let verb = format!("Tagged #{}:", tag.to_lowercase());
+                    self.apply_batch(ids, ArticleBatch::Tag(tag.to_lowercase()), &verb).await?;
+                }
             }
 
-            AppAction::ExportOpmlStart => {
-                self.opml_export_active = true;
-                self.opml_export_input = "~/feeds.opml".to_string();
-                self.opml_export_status = None;
+            AppAction::BatchTagCancel => {
+                self.batch_tag_active = false;
+                self.batch_tag_input.clear();
             }
 
-            AppAction::OpmlExportChar(c) => {
-                self.opml_export_input.push(c);
+            AppAction::EditNote => {
+                if let Some(article_id) = self.selected_article().map(|a| a.id) {
+                    let text = self.note.clone().unwrap_or_default();
+                    match external_editor() {
+                        Some(editor) => self.editor_request = Some(NoteEdit { article_id, editor, text }),
+                        None => {
+                            self.note_input = text;
+                            self.note_input_active = true;
+                        }
+                    }
+                }
             }
 
-            AppAction::OpmlExportBackspace => {
-                self.opml_export_input.pop();
+            AppAction::NoteInputChar(c) => {
+                self.note_input.push(c);
             }
 
-            AppAction::OpmlExportConfirm => {
-                self.export_opml_to_file()?;
+            AppAction::NoteInputBackspace => {
+                self.note_input.pop();
             }
 
-            AppAction::OpmlExportCancel => {
-                self.opml_export_active = false;
-                self.opml_export_input.clear();
-                self.opml_export_status = None;
+            AppAction::NoteInputConfirm => {
+                self.note_input_active = false;
+                let text = std::mem::take(&mut self.note_input);
+                if let Some(article_id) = self.selected_article().map(|a| a.id) {
+                    self.save_annotation(article_id, Some(text)).await?;
+                }
             }
-        }
 
-        Ok(false)
-    }
+            AppAction::NoteInputCancel => {
+                self.note_input_active = false;
+                self.note_input.clear();
+            }
 
-    async fn on_selection_changed(&mut self) -> Result<()> {
-        // Reset state when selection changes
+            AppAction::SnoozeStart => {
+                if self.selected_article().is_some() {
+                    self.snooze_active = true;
+                    self.snooze_input = None;
+                }
+            }
+
+            AppAction::Snooze(choice) => {
+                self.snooze_active = false;
+                self.snooze_selected(choice.until(chrono::Local::now().naive_local())).await?;
+            }
+
+            AppAction::SnoozeCustomStart => {
+                self.snooze_input = Some(String::new());
+            }
+
+            AppAction::SnoozeInputChar(c) => {
+                if let Some(input) = &mut self.snooze_input {
+                    input.push(c);
+                }
+            }
+
+            AppAction::SnoozeInputBackspace => {
+                if let Some(input) = &mut self.snooze_input {
+                    input.pop();
+                }
+            }
+
+            AppAction::SnoozeInputConfirm => {
+                let input = self.snooze_input.clone().unwrap_or_default();
+                match parse_snooze(&input, chrono::Local::now().naive_local()) {
+                    Some(until) => {
+                        self.snooze_active = false;
+                        self.snooze_input = None;
+                        self.snooze_selected(until).await?;
+                    }
+                    None => {
+                        let status = format!("Can't snooze until \"{}\": try 3h, 2d, 18:30 or 2026-03-02 09:00", input);
+                        self.bookmark_status = Some((status, Instant::now()));
+                    }
+                }
+            }
+
+            AppAction::EmbargoStart => {
+                if let Some(article) = self.selected_article() {
+                    // Start from the current embargo, to adjust or clear it
+                    let now = chrono::Local::now();
+                    let current = article
+                        .embargo_until
+                        .filter(|until| *until > now)
+                        .map(|until| until.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string());
+                    self.embargo_input = Some(current.unwrap_or_default());
+                }
+            }
+
+            AppAction::EmbargoInputChar(c) => {
+                if let Some(input) = &mut self.embargo_input {
+                    input.push(c);
+                }
+            }
+
+            AppAction::EmbargoInputBackspace => {
+                if let Some(input) = &mut self.embargo_input {
+                    input.pop();
+                }
+            }
+
+            AppAction::EmbargoInputConfirm => {
+                let input = self.embargo_input.clone().unwrap_or_default();
+                if input.trim().is_empty() {
+                    self.embargo_input = None;
+                    self.embargo_selected(None).await?;
+                } else {
+                    match parse_snooze(&input, chrono::Local::now().naive_local()) {
+                        Some(until) => {
+                            self.embargo_input = None;
+                            self.embargo_selected(Some(until)).await?;
+                        }
+                        None => {
+                            let status = format!("Can't embargo until \"{}\": try 18:30, 2026-03-02 09:00 or 2d", input);
+                            self.bookmark_status = Some((status, Instant::now()));
+                        }
+                    }
+                }
+            }
+
+            AppAction::EmbargoCancel => {
+                self.embargo_input = None;
+            }
+
+            AppAction::SnoozeCancel => {
+                self.snooze_active = false;
+                self.snooze_input = None;
+            }
+
+            AppAction::CycleReadingMode => {
+                if let Some(feed_id) = self.selected_article().map(|a| a.feed_id) {
+                    let reading = self.reading_mode(feed_id).next();
+                    let update = FeedBulkUpdate { reading: Some(reading), ..Default::default() };
+                    self.bulk_update_feeds(vec![feed_id], update).await?;
+                    self.find = None;
+                    self.link_number = None;
+                    let title = self.feeds.iter().find(|f| f.id == feed_id).map(|f| f.display_title().to_string());
+                    let label = match reading {
+                        ReadingMode::Full => "full articles",
+                        ReadingMode::Teaser => "first paragraph only",
+                        ReadingMode::Headlines => "headlines only",
+                    };
+                    let status = format!("{}: {}", title.unwrap_or_default(), label);
+                    self.bookmark_status = Some((status, Instant::now()));
+                }
+            }
+
+            AppAction::CycleFeedPriority => {
+                if let Some(feed_id) = self.selected_article().map(|a| a.feed_id) {
+                    let priority = self.feed_priority(feed_id).next();
+                    let update = FeedBulkUpdate { priority: Some(priority), ..Default::default() };
+                    self.bulk_update_feeds(vec![feed_id], update).await?;
+                    // Its articles sort differently now; stay on the same one
+                    let id = self.selected_article().map(|a| a.id);
+                    self.reload_articles().await?;
+                    if let Some(index) = self.filtered_articles().iter().position(|a| Some(a.id) == id) {
+                        self.selected_index = index;
+                    }
+                    let title = self.feeds.iter().find(|f| f.id == feed_id).map(|f| f.display_title().to_string());
+                    let status = format!("{}: {} priority", title.unwrap_or_default(), priority.as_str());
+                    self.bookmark_status = Some((status, Instant::now()));
+                }
+            }
+
+            AppAction::PaletteStart => {
+                self.palette_active = true;
+                self.palette_input.clear();
+                self.palette_index = 0;
+            }
+
+            AppAction::PaletteChar(c) => {
+                self.palette_input.push(c);
+                self.palette_index = 0;
+            }
+
+            AppAction::PaletteBackspace => {
+                self.palette_input.pop();
+                self.palette_index = 0;
+            }
+
+            AppAction::PaletteUp => {
+                self.palette_index = self.palette_index.saturating_sub(1);
+            }
+
+            AppAction::PaletteDown => {
+                let len = palette_matches(&self.palette_input).len();
+                if self.palette_index + 1 < len {
+                    self.palette_index += 1;
+                }
+            }
+
+            AppAction::PaletteConfirm => {
+                self.palette_active = false;
+                let input = std::mem::take(&mut self.palette_input);
+                if let Some(command) = PaletteCommand::parse(&input) {
+                    return self.run_palette_command(command).await;
+                }
+                if let Some(entry) = palette_matches(&input).get(self.palette_index) {
+                    return Box::pin(self.handle_action(entry.action.clone())).await;
+                }
+            }
+
+            AppAction::PaletteCancel => {
+                self.palette_active = false;
+                self.palette_input.clear();
+            }
+
+            AppAction::ToggleRead => {
+                if let Some(article) = self.selected_article() {
+                    let (id, feed_id, read) = (article.id, article.feed_id, !article.is_read);
+                    self.repository.set_read(id, read).await?;
+                    if let Some(article) = self.articles.iter_mut().find(|a| a.id == id) {
+                        article.is_read = read;
+                    }
+                    self.adjust_unread(feed_id, read);
+                }
+            }
+
+            AppAction::MovePinnedUp | AppAction::MovePinnedDown => {
+                let up = matches!(action, AppAction::MovePinnedUp);
+                if self.filter_mode == FilterMode::Starred {
+                    if let Some(id) = self.selected_article().map(|a| a.id) {
+                        if self.repository.move_pinned(id, up).await? {
+                            self.reload_articles().await?;
+                            // Keep the moved article selected
+                            if let Some(pos) = self.articles.iter().position(|a| a.id == id) {
+                                self.selected_index = pos;
+                            }
+                        }
+                    }
+                }
+            }
+
+            AppAction::CycleFilter => {
+                self.show_view(self.filter_mode.next()).await?;
+            }
+
+            AppAction::UndeleteArticle => {
+                if let Some((feed_id, guid)) = self.last_deleted.take() {
+                    self.repository.undelete_article(feed_id, &guid).await?;
+                    // Article will return on next refresh (press 'r')
+                }
+            }
+
+            AppAction::ShowHelp => {
+                self.show_help = true;
+            }
+
+            AppAction::HideHelp => {
+                self.show_help = false;
+            }
+
+            AppAction::TagInputChar(c) => {
+                self.tag_input.push(c);
+            }
+
+            AppAction::TagInputBackspace => {
+                self.tag_input.pop();
+            }
+
+            AppAction::TagInputConfirm => {
+                if self.select_mode && !self.batch_ids().is_empty() {
+                    self.save_selection_to_raindrop().await?;
+                } else {
+                    self.save_to_raindrop().await?;
+                }
+                self.tag_input_active = false;
+                self.tag_input.clear();
+            }
+
+            AppAction::TagInputCancel => {
+                self.tag_input_active = false;
+                self.tag_input.clear();
+            }
+
+            AppAction::AddFeed => {
+                self.feed_input_active = true;
+                self.feed_input.clear();
+                self.feed_input_status = None;
+            }
+
+            AppAction::FeedInputChar(c) => {
+                self.feed_input.push(c);
+            }
+
+            AppAction::FeedInputBackspace => {
+                self.feed_input.pop();
+            }
+
+            AppAction::FeedInputConfirm => {
+                self.start_feed_discovery();
+            }
+
+            AppAction::FeedInputCancel => {
+                self.feed_input_active = false;
+                self.feed_input.clear();
+                self.feed_input_status = None;
+                self.same_site_ack = None;
+            }
+
+            AppAction::ShowRecommendations => {
+                self.start_recommendations().await?;
+            }
+
+            AppAction::RecommendationUp => {
+                self.recommendation_index = self.recommendation_index.saturating_sub(1);
+            }
+
+            AppAction::RecommendationDown => {
+                if self.recommendation_index + 1 < self.recommendations.len() {
+                    self.recommendation_index += 1;
+                }
+            }
+
+            AppAction::SubscribeRecommendation => {
+                self.subscribe_recommendation().await?;
+            }
+
+            AppAction::CloseRecommendations => {
+                self.recommendations_active = false;
+                self.recommendations_status = None;
+            }
+
+            AppAction::ImportOpmlStart => {
+                self.opml_input_active = true;
+                self.opml_input.clear();
+                self.opml_input_status = None;
+            }
+
+            AppAction::OpmlInputChar(c) => {
+                self.opml_input.push(c);
+            }
+
+            AppAction::OpmlInputBackspace => {
+                self.opml_input.pop();
+            }
+
+            AppAction::OpmlInputConfirm => {
+                self.import_opml_from_input().await?;
+            }
+
+            AppAction::OpmlInputCancel => {
+                self.opml_input_active = false;
+                self.opml_input.clear();
+                self.opml_input_status = None;
+            }
+
+            AppAction::ExportOpmlStart => {
+                self.opml_export_active = true;
+                self.opml_export_input = match &self.group_filter {
+                    Some(group) => format!("~/feeds-{}.opml", sanitize_file_name(group).to_lowercase().replace(' ', "-")),
+                    None => "~/feeds.opml".to_string(),
+                };
+                self.opml_export_status = None;
+            }
+
+            AppAction::OpmlExportChar(c) => {
+                self.opml_export_input.push(c);
+            }
+
+            AppAction::OpmlExportBackspace => {
+                self.opml_export_input.pop();
+            }
+
+            AppAction::OpmlExportConfirm => {
+                self.export_opml_to_file()?;
+            }
+
+            AppAction::OpmlExportCancel => {
+                self.opml_export_active = false;
+                self.opml_export_input.clear();
+                self.opml_export_status = None;
+            }
+
+            AppAction::RenameFeedStart => {
+                let feed = self
+                    .selected_article()
+                    .and_then(|article| self.feeds.iter().find(|f| f.id == article.feed_id));
+                if let Some(feed) = feed {
+                    self.rename_feed_input = feed.display_title().to_string();
+                    self.rename_feed_id = Some(feed.id);
+                    self.rename_feed_active = true;
+                }
+            }
+
+            AppAction::RenameFeedChar(c) => {
+                self.rename_feed_input.push(c);
+            }
+
+            AppAction::RenameFeedBackspace => {
+                self.rename_feed_input.pop();
+            }
+
+            AppAction::RenameFeedConfirm => {
+                self.rename_feed().await?;
+            }
+
+            AppAction::RenameFeedCancel => {
+                self.rename_feed_active = false;
+                self.rename_feed_input.clear();
+                self.rename_feed_id = None;
+            }
+
+            AppAction::TagFilterStart => {
+                self.tag_filter_input = self.tag_filter.clone().unwrap_or_default();
+                self.tag_suggestions = self.repository.tag_counts(None, 12).await?;
+                self.tag_filter_active = true;
+            }
+
+            AppAction::TagFilterChar(c) => {
+                self.tag_filter_input.push(c);
+            }
+
+            AppAction::TagFilterBackspace => {
+                self.tag_filter_input.pop();
+            }
+
+            AppAction::TagFilterConfirm => {
+                self.tag_filter_active = false;
+                let tag = std::mem::take(&mut self.tag_filter_input).trim().trim_start_matches('#').to_lowercase();
+                self.tag_filter = (!tag.is_empty()).then_some(tag);
+                self.show_filtered().await?;
+            }
+
+            AppAction::TagFilterCancel => {
+                self.tag_filter_active = false;
+                self.tag_filter_input.clear();
+            }
+
+            AppAction::SearchStart => {
+                self.search_input = self.search.as_ref().map(|(text, _)| text.clone()).unwrap_or_default();
+                self.search_error = None;
+                self.search_active = true;
+            }
+
+            AppAction::SearchChar(c) => {
+                self.search_input.push(c);
+                self.search_error = None;
+            }
+
+            AppAction::SearchBackspace => {
+                self.search_input.pop();
+                self.search_error = None;
+            }
+
+            AppAction::SearchConfirm => {
+                let text = self.search_input.trim().to_string();
+                if text.is_empty() {
+                    self.search = None;
+                } else {
+                    match Query::parse(&text) {
+                        Ok(query) => self.search = Some((text, query)),
+                        // Keep the prompt open so the query can be fixed
+                        Err(e) => {
+                            self.search_error = Some(e.to_string());
+                            return Ok(false);
+                        }
+                    }
+                }
+                self.search_error = None;
+                self.show_filtered().await?;
+                // Still open, with the reason, if the search timed out
+                if self.search_error.is_none() {
+                    self.search_active = false;
+                    self.search_input.clear();
+                }
+            }
+
+            AppAction::SearchCancel => {
+                self.search_active = false;
+                self.search_input.clear();
+                self.search_error = None;
+            }
+
+            AppAction::ShowEntities => {
+                let since = chrono::Utc::now() - chrono::Duration::days(ENTITY_BROWSE_DAYS);
+                self.entities = self
+                    .repository
+                    .recent_entities(None, since, None, ENTITY_BROWSE_LIMIT)
+                    .await?;
+                self.entity_index = 0;
+                self.entities_active = true;
+            }
+
+            AppAction::EntityUp => {
+                self.entity_index = self.entity_index.saturating_sub(1);
+            }
+
+            AppAction::EntityDown => {
+                if self.entity_index + 1 < self.entities.len() {
+                    self.entity_index += 1;
+                }
+            }
+
+            AppAction::SelectEntity => {
+                self.entities_active = false;
+                self.entity_filter = self.entities.get(self.entity_index).map(|e| e.name.clone());
+                self.show_filtered().await?;
+            }
+
+            AppAction::ClearEntityFilter => {
+                self.entities_active = false;
+                self.entity_filter = None;
+                self.show_filtered().await?;
+            }
+
+            AppAction::CloseEntities => {
+                self.entities_active = false;
+            }
+
+            AppAction::ShowAuthors => {
+                let since = chrono::Utc::now() - chrono::Duration::days(AUTHOR_BROWSE_DAYS);
+                self.authors = self
+                    .repository
+                    .recent_authors(None, since, AUTHOR_BROWSE_LIMIT)
+                    .await?;
+                // Followed and muted authors stay listed when they've gone quiet
+                self.author_rules = self.repository.get_author_rules().await?;
+                for (name, rule) in &self.author_rules {
+                    if !self.authors.iter().any(|a| a.name.eq_ignore_ascii_case(name)) {
+                        self.authors.push(AuthorCount {
+                            name: name.clone(),
+                            articles: 0,
+                            feeds: 0,
+                            rule: Some(*rule),
+                        });
+                    }
+                }
+                self.author_index = 0;
+                self.authors_active = true;
+            }
+
+            AppAction::AuthorUp => {
+                self.author_index = self.author_index.saturating_sub(1);
+            }
+
+            AppAction::AuthorDown => {
+                if self.author_index + 1 < self.authors.len() {
+                    self.author_index += 1;
+                }
+            }
+
+            AppAction::SelectAuthor => {
+                self.authors_active = false;
+                self.author_filter = self.authors.get(self.author_index).map(|a| a.name.clone());
+                self.show_filtered().await?;
+            }
+
+            AppAction::ClearAuthorFilter => {
+                self.authors_active = false;
+                self.author_filter = None;
+                self.show_filtered().await?;
+            }
+
+            AppAction::CloseAuthors => {
+                self.authors_active = false;
+            }
+
+            AppAction::ToggleAuthorRule(rule) => {
+                if let Some(author) = self.authors.get_mut(self.author_index) {
+                    let rule = (author.rule != Some(rule)).then_some(rule);
+                    self.repository.set_author_rule(&author.name, rule).await?;
+                    author.rule = rule;
+                    let status = match rule {
+                        Some(AuthorRule::Follow) => format!("Following {}", author.name),
+                        Some(AuthorRule::Mute) => format!("Muted {}", author.name),
+                        None => format!("Cleared rule for {}", author.name),
+                    };
+                    self.bookmark_status = Some((status, Instant::now()));
+                    self.author_rules = self.repository.get_author_rules().await?;
+                    self.reload_articles().await?;
+                }
+            }
+
+            AppAction::ShowArticleAuthor => {
+                if let Some(article) = self.selected_article() {
+                    let bylines: Vec<String> = article.author.iter().cloned().collect();
+                    match normalize_authors(&bylines).into_iter().next() {
+                        Some(author) => {
+                            self.author_filter = Some(author);
+                            self.show_filtered().await?;
+                        }
+                        None => {
+                            self.bookmark_status = Some(("No author on this article".to_string(), Instant::now()));
+                        }
+                    }
+                }
+            }
+
+            AppAction::ShowCitingArticles => {
+                if let Some(url) = self.selected_article().map(|a| a.url.clone()).filter(|u| !u.is_empty()) {
+                    self.search = Some((format!("links:\"{}\"", url), Query::links_to(&url)));
+                    self.show_filtered().await?;
+                    if self.filtered_articles().is_empty() {
+                        self.bookmark_status = Some(("Nothing in your feeds links to this article".to_string(), Instant::now()));
+                    }
+                }
+            }
+
+            AppAction::ShowBlocklist => {
+                self.blocklist_entries = self.blocklist.entries();
+                self.blocklist_subscriptions = self.blocklist.subscriptions();
+                self.blocklist_index = 0;
+                self.blocklist_active = true;
+            }
+
+            AppAction::BlocklistUp => {
+                self.blocklist_index = self.blocklist_index.saturating_sub(1);
+            }
+
+            AppAction::BlocklistDown => {
+                if self.blocklist_index + 1 < self.blocklist_entries.len() {
+                    self.blocklist_index += 1;
+                }
+            }
+
+            AppAction::BlocklistAdd => {
+                self.blocklist_editing = None;
+                self.blocklist_input = Some(String::new());
+            }
+
+            AppAction::BlocklistEdit => {
+                if let Some(entry) = self.blocklist_entries.get(self.blocklist_index) {
+                    self.blocklist_editing = Some(entry.clone());
+                    self.blocklist_input = Some(entry.clone());
+                }
+            }
+
+            AppAction::BlocklistRemove => {
+                if let Some(entry) = self.blocklist_entries.get(self.blocklist_index).cloned() {
+                    let status = self.unblock_entry(&entry)?;
+                    self.blocklist_edited(None, status);
+                }
+            }
+
+            AppAction::BlocklistToggle => {
+                let entry = self.blocklist_entries.get(self.blocklist_index);
+                let subscription = self
+                    .blocklist_subscriptions
+                    .iter()
+                    .find(|s| entry.is_some_and(|e| e.ends_with(&s.url)))
+                    .cloned();
+                match subscription {
+                    Some(subscription) => {
+                        let enabled = !subscription.enabled;
+                        self.blocklist.set_subscription_enabled(&subscription.url, enabled)?;
+                        let status = format!("{} {}", if enabled { "Enabled" } else { "Disabled" }, subscription.url);
+                        self.blocklist_edited(None, status);
+                    }
+                    None => {
+                        self.bookmark_status = Some(("Only shared lists turn on and off".to_string(), Instant::now()));
+                    }
+                }
+            }
+
+            AppAction::CloseBlocklist => {
+                self.blocklist_active = false;
+            }
+
+            AppAction::BlocklistInputChar(c) => {
+                if let Some(input) = self.blocklist_input.as_mut() {
+                    input.push(c);
+                }
+            }
+
+            AppAction::BlocklistInputBackspace => {
+                if let Some(input) = self.blocklist_input.as_mut() {
+                    input.pop();
+                }
+            }
+
+            AppAction::BlocklistInputConfirm => {
+                let input = self.blocklist_input.take().unwrap_or_default();
+                let replacing = self.blocklist_editing.take();
+                if !input.trim().is_empty() && replacing.as_deref() != Some(input.as_str()) {
+                    let result = match &replacing {
+                        Some(old) => self.blocklist.replace(old, &input),
+                        None => self.blocklist.add(&input),
+                    };
+                    match result {
+                        Ok(Some(entry)) => {
+                            let status = format!("Blocked {} in new articles", entry);
+                            self.blocklist_edited(Some(&entry), status);
+                        }
+                        Ok(None) => self.blocklist_edited(None, format!("Already blocked: {}", input.trim())),
+                        Err(AppError::Config(message)) => {
+                            // Leave it open to fix
+                            self.blocklist_input = Some(input);
+                            self.blocklist_editing = replacing;
+                            self.bookmark_status = Some((message, Instant::now()));
+                        }
+                        Err(e) => return Err(e),
+                    }
+                }
+            }
+
+            AppAction::BlocklistInputCancel => {
+                self.blocklist_input = None;
+                self.blocklist_editing = None;
+            }
+
+            AppAction::ShowWatchlist => {
+                self.watch_timelines = self.watch_timelines(TIMELINE_DAYS).await?;
+                self.watch_index = 0;
+                self.watch_active = true;
+            }
+
+            AppAction::WatchUp => {
+                self.watch_index = self.watch_index.saturating_sub(1);
+            }
+
+            AppAction::WatchDown => {
+                if self.watch_index + 1 < self.watch_timelines.len() {
+                    self.watch_index += 1;
+                }
+            }
+
+            AppAction::SelectWatched => {
+                self.watch_active = false;
+                self.entity_filter = self.watch_timelines.get(self.watch_index).map(|t| t.entity.clone());
+                self.show_filtered().await?;
+            }
+
+            AppAction::CloseWatchlist => {
+                self.watch_active = false;
+            }
+
+            AppAction::ShowTrends => {
+                self.trends = load_trends(&self.repository, None, TRENDS_SHOWN).await?;
+                self.trend_index = 0;
+                self.trends_active = true;
+            }
+
+            AppAction::TrendUp => {
+                self.trend_index = self.trend_index.saturating_sub(1);
+            }
+
+            AppAction::TrendDown => {
+                if self.trend_index + 1 < self.trends.len() {
+                    self.trend_index += 1;
+                }
+            }
+
+            AppAction::SelectTrend => {
+                self.trends_active = false;
+                self.term_filter = self.trends.get(self.trend_index).map(|t| t.term.clone());
+                self.show_filtered().await?;
+            }
+
+            AppAction::ClearTrendFilter => {
+                self.trends_active = false;
+                self.term_filter = None;
+                self.show_filtered().await?;
+            }
+
+            AppAction::CloseTrends => {
+                self.trends_active = false;
+            }
+
+            AppAction::ShowRevisions => {
+                if let Some(id) = self.selected_article().map(|a| a.id) {
+                    self.revisions = self.repository.get_revisions(id).await?;
+                    if self.revisions.is_empty() {
+                        self.bookmark_status = Some(("No earlier versions of this article".to_string(), Instant::now()));
+                    } else {
+                        self.revision_index = 0;
+                        self.revision_scroll = 0;
+                        self.revisions_active = true;
+                    }
+                }
+            }
+
+            AppAction::RevisionOlder => {
+                if self.revision_index + 1 < self.revisions.len() {
+                    self.revision_index += 1;
+                    self.revision_scroll = 0;
+                }
+            }
+
+            AppAction::RevisionNewer => {
+                if self.revision_index > 0 {
+                    self.revision_index -= 1;
+                    self.revision_scroll = 0;
+                }
+            }
+
+            AppAction::RevisionScrollDown => {
+                self.revision_scroll = self.revision_scroll.saturating_add(1);
+            }
+
+            AppAction::RevisionScrollUp => {
+                self.revision_scroll = self.revision_scroll.saturating_sub(1);
+            }
+
+            AppAction::CloseRevisions => {
+                self.revisions_active = false;
+            }
+
+            AppAction::ShowCalendar => {
+                // For the feed being shown, if there is one
+                let feed = self
+                    .feed_filter
+                    .and_then(|id| self.feeds.iter().find(|f| f.id == id))
+                    .map(|f| (f.id, f.display_title().to_string()));
+                let mut calendar = load_calendar(&self.repository, feed, CALENDAR_WEEKS).await?;
+                if let Some(day) = self.day_filter {
+                    calendar.move_by((day - calendar.selected).num_days());
+                }
+                self.calendar = Some(calendar);
+                self.calendar_active = true;
+            }
+
+            AppAction::CalendarMove(days) => {
+                if let Some(calendar) = &mut self.calendar {
+                    calendar.move_by(days);
+                }
+            }
+
+            AppAction::CalendarToggleFeed => {
+                if let Some(calendar) = self.calendar.take() {
+                    // Between every feed and the selected article's
+                    let feed = match calendar.feed {
+                        Some(_) => None,
+                        None => self
+                            .selected_article()
+                            .and_then(|a| self.feeds.iter().find(|f| f.id == a.feed_id))
+                            .map(|f| (f.id, f.display_title().to_string())),
+                    };
+                    let mut toggled = load_calendar(&self.repository, feed, CALENDAR_WEEKS).await?;
+                    toggled.move_by((calendar.selected - toggled.selected).num_days());
+                    self.calendar = Some(toggled);
+                }
+            }
+
+            AppAction::SelectCalendarDay => {
+                self.calendar_active = false;
+                if let Some(calendar) = &self.calendar {
+                    self.day_filter = Some(calendar.selected);
+                    self.today_only = false;
+                    self.group_filter = None;
+                    self.feed_filter = calendar.feed.as_ref().map(|(id, _)| *id);
+                    self.show_filtered().await?;
+                }
+            }
+
+            AppAction::ClearDayFilter => {
+                self.calendar_active = false;
+                self.day_filter = None;
+                self.show_filtered().await?;
+            }
+
+            AppAction::CloseCalendar => {
+                self.calendar_active = false;
+            }
+
+            AppAction::AskStart => {
+                if self.summarizer.is_none() {
+                    self.bookmark_status = Some(("Set claude_api_key, [openai] or [local_llm] to ask questions".to_string(), Instant::now()));
+                } else {
+                    self.ask_input.clear();
+                    self.ask_all = self.selected_article().is_none();
+                    self.ask_input_active = true;
+                }
+            }
+
+            AppAction::ShowBriefing => {
+                self.start_briefing().await?;
+            }
+
+            AppAction::AskChar(c) => {
+                self.ask_input.push(c);
+            }
+
+            AppAction::AskBackspace => {
+                self.ask_input.pop();
+            }
+
+            AppAction::AskToggleScope => {
+                self.ask_all = !self.ask_all || self.selected_article().is_none();
+            }
+
+            AppAction::AskConfirm => {
+                self.ask_input_active = false;
+                let question = std::mem::take(&mut self.ask_input).trim().to_string();
+                if !question.is_empty() {
+                    self.start_question(question).await?;
+                }
+            }
+
+            AppAction::AskCancel => {
+                self.ask_input_active = false;
+                self.ask_input.clear();
+            }
+
+            AppAction::AnswerUp => {
+                self.answer_index = self.answer_index.saturating_sub(1);
+            }
+
+            AppAction::AnswerDown => {
+                let cited = self.answer.as_ref().map_or(0, |a| a.citations.len());
+                if self.answer_index + 1 < cited {
+                    self.answer_index += 1;
+                }
+            }
+
+            AppAction::OpenAnswerSource => {
+                let article_id = self
+                    .answer
+                    .as_ref()
+                    .and_then(|a| a.citations.get(self.answer_index))
+                    .map(|c| c.article_id);
+                if let Some(article_id) = article_id {
+                    match self.filtered_articles().iter().position(|a| a.id == article_id) {
+                        Some(index) => {
+                            self.answer_active = false;
+                            self.selected_index = index;
+                            self.on_selection_changed().await?;
+                        }
+                        None => self.answer_status = Some("That article is no longer in the list".to_string()),
+                    }
+                }
+            }
+
+            AppAction::CloseAnswer => {
+                self.answer_active = false;
+            }
+
+            AppAction::ShowFailures => {
+                self.failures = self.repository.get_summary_failures().await?;
+                self.failure_index = 0;
+                self.failures_active = true;
+            }
+
+            AppAction::FailureUp => {
+                self.failure_index = self.failure_index.saturating_sub(1);
+            }
+
+            AppAction::FailureDown => {
+                if self.failure_index + 1 < self.failures.len() {
+                    self.failure_index += 1;
+                }
+            }
+
+            AppAction::RetryFailure => {
+                if let Some(failure) = self.failures.get(self.failure_index) {
+                    let article_id = failure.article_id;
+                    self.queue_summary_retries(vec![article_id]);
+                }
+            }
+
+            AppAction::RetryAllFailures => {
+                let article_ids = self.failures.iter().map(|f| f.article_id).collect();
+                self.queue_summary_retries(article_ids);
+            }
+
+            AppAction::CloseFailures => {
+                self.failures_active = false;
+            }
+        }
+
+        Ok(false)
+    }
+
+    async fn on_selection_changed(&mut self) -> Result<()> {
+        // Reset state when selection changes; a find carries over to the next article
+        self.link_number = None;
+        if let Some(find) = &mut self.find {
+            find.current = 0;
+        }
+        self.summary_status = SummaryStatus::NotGenerated;
+        self.current_summary = None;
+        self.is_saved_to_raindrop = false;
+        self.bookmark_status = None;
+        self.note = None;
+        self.article_reading_secs = 0;
+
+        // Check if current article is saved to raindrop
+        let article_id = self.selected_article().map(|a| a.id);
+        if let Some(id) = article_id {
+            self.is_saved_to_raindrop = self
+                .repository
+                .is_saved_to_raindrop(id)
+                .await?;
+
+            self.note = self.repository.get_annotation(id).await?;
+            self.article_reading_secs = self.repository.article_reading_seconds(id).await?;
+
+            // Paging through articles full screen opens each one
+            if self.reading {
+                self.repository.record_opened(id).await?;
+            }
+
+            // Check for cached summary
+            if let Some(summary) = self.repository.get_summary(id).await? {
+                self.current_summary = Some(summary);
+                self.summary_status = SummaryStatus::Generated;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn generate_summary(&mut self) -> Result<()> {
+        let Some(summarizer) = &self.summarizer else {
+            self.summary_status = SummaryStatus::NoApiKey;
+            return Ok(());
+        };
+        let summarizer = Arc::clone(summarizer);
+
+        let Some(article) = self.selected_article().cloned() else {
+            return Ok(());
+        };
+
+        // Check cache first
+        if let Some(summary) = self.repository.get_summary(article.id).await? {
+            self.current_summary = Some(summary);
+            self.summary_status = SummaryStatus::Generated;
+            return Ok(());
+        }
+
+        let article_id = article.id;
+        self.summary_status = SummaryStatus::Generating;
+        self.pending_summary_article_id = Some(article_id);
+
+        let (content, fetched) = self.summary_content(&article).await?;
+        if let Some(full_content) = fetched {
+            if let Some(a) = self.articles.iter_mut().find(|a| a.id == article_id) {
+                let minutes = estimate_reading_minutes(&full_content);
+                if a.reading_minutes.is_none_or(|m| minutes > m) {
+                    a.reading_minutes = Some(minutes);
+                }
+                a.full_text = Some(full_content);
+            }
+        }
+
+        let content_hash = content_hash(&content);
+        if let Some(summary) = reuse_summary(&self.repository, article_id, content_hash.as_deref()).await? {
+            self.current_summary = Some(summary);
+            self.summary_status = SummaryStatus::Generated;
+            self.pending_summary_article_id = None;
+            return Ok(());
+        }
+
+        // Spawn background task for summary generation
+        let tx = self.summary_tx.clone();
+        let llm_tagging = self.llm_tagging;
+        let title = article.title;
+
+        tokio::spawn(async move {
+            let _in_flight = metrics().summary_started();
+            let (result, tags) = summarize_and_tag(&summarizer, &title, &content, llm_tagging).await;
+            let _ = tx.send(SummaryResult { article_id, result, tags, content_hash }).await;
+        });
+
+        // Don't update local is_read state - keep article visible in filtered list
+        // Database is already updated, so it will show as read next session
+
+        Ok(())
+    }
+
+    /// Text to summarize: the stored page text, else the page fetched now
+    /// (using browser cookies), else the feed's own content. A freshly
+    /// fetched page is saved and also returned, so callers can update their copy.
+    async fn summary_content(&self, article: &Article) -> Result<(String, Option<String>)> {
+        if let Some(text) = &article.full_text {
+            return Ok((text.clone(), None));
+        }
+
+        // Get RSS content as fallback
+        let rss_content = article
+            .content_text
+            .clone()
+            .or_else(|| article.content.clone())
+            .unwrap_or_default();
+
+        match self.content_fetcher.fetch_full_content(&self.reader_view(article)).await {
+            Ok(Some(full_content)) => {
+                tracing::info!("Fetched full content for: {}", article.url);
+                self.store_full_text(article, full_content.clone()).await?;
+                Ok((full_content.clone(), Some(full_content)))
+            }
+            Ok(None) => {
+                tracing::debug!("No full content available, using RSS content");
+                Ok((rss_content, None))
+            }
+            Err(e) => {
+                tracing::debug!("Failed to fetch full content: {}, using RSS", e);
+                Ok((rss_content, None))
+            }
+        }
+    }
+
+    /// Save fetched page text, raising the reading time if it's longer
+    async fn store_full_text(&self, article: &Article, text: String) -> Result<()> {
+        let minutes = estimate_reading_minutes(&text);
+        self.repository.save_full_text(article.id, text).await?;
+        if article.reading_minutes.is_none_or(|m| minutes > m) {
+            self.repository.update_reading_minutes(article.id, minutes).await?;
+        }
+        Ok(())
+    }
+
+    /// Fetch the page text of every unread article in `feed_ids` that
+    /// doesn't have it yet, `jobs` at a time, with one request per site in
+    /// flight so a big site's backlog doesn't starve the rest. Journaled:
+    /// articles an interrupted run didn't get to are fetched first.
+    /// `on_done` sees each article as it finishes, whether its text was
+    /// found, the number done so far and the total queued.
+    pub async fn fetch_full_text_backlog(
+        &self,
+        feed_ids: &[i64],
+        jobs: usize,
+        mut on_done: impl FnMut(&Article, bool, usize, usize),
+    ) -> Result<()> {
+        use futures::stream::{FuturesUnordered, StreamExt};
+
+        let mut articles = Vec::new();
+        for id in self.repository.journal_pending(Pipeline::FullText).await? {
+            match self.repository.get_article(id).await? {
+                Some(article) if article.full_text.is_none() => articles.push(article),
+                // Fetched or deleted since
+                _ => self.repository.journal_done(Pipeline::FullText, id).await?,
+            }
+        }
+        if !feed_ids.is_empty() {
+            let queued: Vec<i64> = articles.iter().map(|a| a.id).collect();
+            let new = self.repository.articles_without_full_text(feed_ids.to_vec()).await?;
+            articles.extend(new.into_iter().filter(|a| !queued.contains(&a.id)));
+        }
+        self.repository
+            .journal_add(Pipeline::FullText, articles.iter().map(|a| a.id).collect())
+            .await?;
+
+        let total = articles.len();
+        let mut queue = FairQueue::new(1);
+        for article in articles {
+            // Sites are taken one at a time; a reader view is the site then
+            let url = self.reader_view(&article);
+            let site = link_target(&url).map(|(domain, _)| domain).unwrap_or_default();
+            queue.push(&site, (article, url));
+        }
+        let mut running = FuturesUnordered::new();
+        let mut done = 0;
+        loop {
+            while running.len() < jobs.max(1) {
+                let Some((site, (article, url))) = queue.claim() else {
+                    break;
+                };
+                running.push(async move {
+                    let result = self.content_fetcher.fetch_full_content(&url).await;
+                    (site, article, result)
+                });
+            }
+            let Some((site, article, result)) = running.next().await else {
+                break;
+            };
+            queue.done(&site);
+            let found = match result {
+                Ok(Some(text)) => {
+                    self.store_full_text(&article, text).await?;
+                    true
+                }
+                Ok(None) => false,
+                Err(e) => {
+                    tracing::debug!("Failed to fetch full content for {}: {}", article.url, e);
+                    false
+                }
+            };
+            self.repository.journal_done(Pipeline::FullText, article.id).await?;
+            done += 1;
+            on_done(&article, found, done, total);
+        }
+        Ok(())
+    }
+
+    /// Summarize every unread, not yet summarized article in `feed_ids`,
+    /// `jobs` at a time (for CLI use). `on_done` sees each article as it
+    /// finishes, with the number done so far and the total queued.
+    pub async fn summarize_unread(
+        &self,
+        feed_ids: &[i64],
+        jobs: usize,
+        on_done: impl FnMut(&Article, &BatchOutcome, usize, usize),
+    ) -> Result<()> {
+        let mut queue = Vec::new();
+        for &feed_id in feed_ids {
+            let filter = ArticleFilter {
+                feed_id: Some(feed_id),
+                unread_only: true,
+                unsummarized: true,
+                ..Default::default()
+            };
+            // Every match: SQLite takes the limit as an i64
+            queue.extend(self.repository.list_articles(&filter, i64::MAX as usize, None).await?);
+        }
+        self.summarize_batch(queue, jobs, on_done).await
+    }
+
+    /// Retry every failed summary, `jobs` at a time, as `summarize_unread`
+    pub async fn retry_failed_summaries(
+        &self,
+        jobs: usize,
+        on_done: impl FnMut(&Article, &BatchOutcome, usize, usize),
+    ) -> Result<()> {
+        let mut queue = Vec::new();
+        for failure in self.repository.get_summary_failures().await? {
+            queue.extend(self.repository.get_article(failure.article_id).await?);
+        }
+        self.summarize_batch(queue, jobs, on_done).await
+    }
+
+    async fn summarize_batch(
+        &self,
+        queue: Vec<Article>,
+        jobs: usize,
+        mut on_done: impl FnMut(&Article, &BatchOutcome, usize, usize),
+    ) -> Result<()> {
+        use futures::stream::{self, StreamExt};
+
+        let summarizer = self
+            .summarizer
+            .as_ref()
+            .ok_or_else(|| AppError::Config("Set claude_api_key, [openai] or [local_llm] to summarize articles".to_string()))?;
+
+        // Journaled, so a batch cut short is finished on the next start
+        self.repository
+            .journal_add(Pipeline::Summary, queue.iter().map(|a| a.id).collect())
+            .await?;
+        let total = queue.len();
+        let mut results = stream::iter(queue)
+            .map(|article| async move {
+                let outcome = match self.summarize_queued(summarizer, &article).await {
+                    Ok(outcome) => outcome,
+                    Err(e) => BatchOutcome::Failed(e.to_string()),
+                };
+                (article, outcome)
+            })
+            .buffer_unordered(jobs.max(1));
+
+        let mut done = 0;
+        while let Some((article, outcome)) = results.next().await {
+            self.repository.journal_done(Pipeline::Summary, article.id).await?;
+            done += 1;
+            on_done(&article, &outcome, done, total);
+        }
+        Ok(())
+    }
+
+    async fn summarize_queued(&self, summarizer: &Summarizer, article: &Article) -> Result<BatchOutcome> {
+        let (content, _) = self.summary_content(article).await?;
+        summarize_and_save(&self.repository, summarizer, article, &content, self.llm_tagging).await
+    }
+
+    /// Retry failed summaries in the background, one after another, from
+    /// the text already stored for each article
+    fn start_summary_retries(&mut self, article_ids: Vec<i64>) {
+        let Some(summarizer) = &self.summarizer else {
+            return;
+        };
+        if self.retry_running || article_ids.is_empty() {
+            return;
+        }
+        self.retry_running = true;
+
+        let summarizer = Arc::clone(summarizer);
+        let repository = self.repository.clone();
+        let llm_tagging = self.llm_tagging;
+        let tx = self.retry_tx.clone();
+        tokio::spawn(async move {
+            let mut succeeded = 0;
+            for &article_id in &article_ids {
+                let article = match repository.get_article(article_id).await {
+                    Ok(Some(article)) => article,
+                    // Deleted since it failed (or was queued)
+                    Ok(None) => {
+                        let _ = repository.journal_done(Pipeline::Summary, article_id).await;
+                        continue;
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to load article {} for retry: {}", article_id, e);
+                        continue;
+                    }
+                };
+                let content = stored_text(&article);
+                match summarize_and_save(&repository, &summarizer, &article, &content, llm_tagging).await {
+                    Ok(BatchOutcome::Failed(e)) => tracing::warn!("Summary retry failed for {}: {}", article_id, e),
+                    Ok(_) => succeeded += 1,
+                    Err(e) => {
+                        tracing::warn!("Failed to save retried summary: {}", e);
+                        continue;
+                    }
+                }
+                if let Err(e) = repository.journal_done(Pipeline::Summary, article_id).await {
+                    tracing::warn!("Failed to journal summary progress: {}", e);
+                }
+            }
+            let _ = tx.send(RetryResult { article_ids, succeeded }).await;
+        });
+    }
+
+    /// Pick up work a crash or kill cut short: summaries still queued from a
+    /// batch are finished in the background from the stored text (an
+    /// interrupted refresh resumes with the next `refresh_feeds`)
+    pub async fn resume_interrupted(&mut self) -> Result<()> {
+        let pending = self.repository.journal_pending(Pipeline::Summary).await?;
+        if pending.is_empty() || self.summarizer.is_none() {
+            return Ok(());
+        }
+        tracing::info!("Resuming {} interrupted summaries", pending.len());
+        self.bookmark_status = Some((format!("Resuming {} interrupted summaries...", pending.len()), Instant::now()));
+        self.start_summary_retries(pending);
+        Ok(())
+    }
+
+    /// Whether the last refresh stopped before storing every feed
+    pub async fn refresh_interrupted(&self) -> Result<bool> {
+        Ok(!self.is_refreshing && !self.repository.journal_pending(Pipeline::Refresh).await?.is_empty())
+    }
+
+    /// Retry summaries the user picked in the failure browser, saying why not
+    /// when that can't happen now
+    fn queue_summary_retries(&mut self, article_ids: Vec<i64>) {
+        let message = if self.summarizer.is_none() {
+            "Set claude_api_key, [openai] or [local_llm] to summarize articles".to_string()
+        } else if self.retry_running {
+            "Already retrying failed summaries".to_string()
+        } else if article_ids.is_empty() {
+            "No failed summaries".to_string()
+        } else {
+            let message = format!("Retrying {} summaries...", article_ids.len());
+            self.start_summary_retries(article_ids);
+            message
+        };
+        self.bookmark_status = Some((message, Instant::now()));
+    }
+
+    /// Poll for finished summary retries (non-blocking)
+    pub async fn poll_summary_retries(&mut self) -> Result<()> {
+        let Ok(result) = self.retry_rx.try_recv() else {
+            return Ok(());
+        };
+        self.retry_running = false;
+        self.bookmark_status = Some((
+            format!("Retried {} summaries: {} succeeded", result.article_ids.len(), result.succeeded),
+            Instant::now(),
+        ));
+        if self.failures_active {
+            self.failures = self.repository.get_summary_failures().await?;
+            self.failure_index = self.failure_index.min(self.failures.len().saturating_sub(1));
+        }
+
+        // The reader pane may be showing one that now has a summary
+        let Some(article_id) = self.selected_article().map(|a| a.id) else {
+            return Ok(());
+        };
+        if result.article_ids.contains(&article_id) && self.pending_summary_article_id.is_none() {
+            if let Some(summary) = self.repository.get_summary(article_id).await? {
+                self.current_summary = Some(summary);
+                self.summary_status = SummaryStatus::Generated;
+            }
+        }
+        Ok(())
+    }
+
+    /// Advance the spinner animation frame
+    pub fn tick_spinner(&mut self) {
+        self.spinner_frame = (self.spinner_frame + 1) % 10;
+    }
+
+    /// Get the current spinner character
+    pub fn spinner_char(&self) -> char {
+        const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+        SPINNER[self.spinner_frame]
+    }
+
+    /// Clear bookmark status after 2 seconds
+    pub fn check_bookmark_status_timeout(&mut self) {
+        if let Some((_, timestamp)) = &self.bookmark_status {
+            if timestamp.elapsed().as_secs() >= 2 {
+                self.bookmark_status = None;
+            }
+        }
+    }
+
+    /// Poll for completed summary results (non-blocking)
+    pub async fn poll_summary_result(&mut self) -> Result<()> {
+        if let Ok(result) = self.summary_rx.try_recv() {
+            // Only process if this is the summary we're waiting for
+            if self.pending_summary_article_id == Some(result.article_id) {
+                // Check if the article still exists (might have been deleted)
+                let article_exists = self.articles.iter().any(|a| a.id == result.article_id);
+
+                match result.result {
+                    Ok(completion) => {
+                        if article_exists {
+                            // Save to database only if article still exists
+                            if let Err(e) = self
+                                .repository
+                                .save_summary(
+                                    result.article_id,
+                                    completion.text.clone(),
+                                    completion.model.clone(),
+                                    Some(completion.provider.to_string()),
+                                    result.content_hash,
+                                )
+                                .await
+                            {
+                                tracing::warn!("Failed to save summary (article may have been deleted): {}", e);
+                            }
+                            if let Some(tags) = result.tags {
+                                self.repository.set_article_tags(result.article_id, tags.clone()).await?;
+                                if let Some(a) = self.articles.iter_mut().find(|a| a.id == result.article_id) {
+                                    a.tags = tags;
+                                }
+                            }
+
+                            self.current_summary = Some(Summary {
+                                id: 0,
+                                article_id: result.article_id,
+                                content: completion.text,
+                                model_version: completion.model,
+                                provider: Some(completion.provider.to_string()),
+                                generated_at: chrono::Utc::now(),
+                            });
+                            self.summary_status = SummaryStatus::Generated;
+                        } else {
+                            tracing::debug!("Discarding summary for deleted article {}", result.article_id);
+                            self.summary_status = SummaryStatus::NotGenerated;
+                        }
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to generate summary: {}", e);
+                        if article_exists {
+                            self.repository
+                                .record_summary_failure(result.article_id, e.to_string(), e.is_transient())
+                                .await?;
+                        }
+                        self.summary_status = SummaryStatus::Failed;
+                    }
+                }
+                self.pending_summary_article_id = None;
+            }
+        }
+        Ok(())
+    }
+
+    /// Start discovering a feed from a URL (non-blocking)
+    fn start_feed_discovery(&mut self) {
+        let url = self.feed_input.trim().to_string();
+        if url.is_empty() {
+            self.feed_input_active = false;
+            return;
+        }
+
+        // Normalize URL - add https:// if no protocol specified
+        let url = if !url.starts_with("http://") && !url.starts_with("https://") {
+            format!("https://{}", url)
+        } else {
+            url
+        };
+
+        // Catch an exact re-add before going to the network
+        if let Some(feed) = find_by_url(&self.feeds, &url) {
+            self.feed_input_status = Some(format!("Already subscribed: {}", feed.display_title()));
+            return;
+        }
+
+        self.feed_input_status = Some("Discovering feed...".to_string());
+
+        let fetcher = self.fetcher.clone();
+        let tx = self.discovery_tx.clone();
+
+        tokio::spawn(async move {
+            let result = match fetcher.discover_feed(&url).await {
+                Ok(feed) => Ok(feed),
+                Err(e) => Err(e.to_string()),
+            };
+            let _ = tx.send(FeedDiscoveryResult { result }).await;
+        });
+    }
+
+    /// Poll for completed feed discovery results (non-blocking)
+    pub async fn poll_discovery_result(&mut self) -> Result<()> {
+        if let Ok(result) = self.discovery_rx.try_recv() {
+            match result.result {
+                Ok(new_feed) => {
+                    // Check the resolved URL and site against existing subscriptions
+                    if let Some(duplicate) = find_duplicate(&self.feeds, &new_feed) {
+                        let acknowledged = self.same_site_ack.as_deref() == Some(new_feed.url.as_str());
+                        if duplicate.kind == DuplicateKind::SameUrl || !acknowledged {
+                            let mut status = duplicate.describe();
+                            if duplicate.kind == DuplicateKind::SameSite {
+                                status.push_str(" - Enter again to add anyway");
+                                self.same_site_ack = Some(new_feed.url.clone());
+                            }
+                            self.feed_input_status = Some(status);
+                            return Ok(());
+                        }
+                    }
+                    self.same_site_ack = None;
+
+                    let feed_title = new_feed.title.clone();
+                    match self.repository.insert_feed(new_feed).await {
+                        Ok(feed_id) => {
+                            self.feed_input_status = Some(format!("Added: {}", feed_title));
+                            tracing::info!("Added new feed: {} (id={})", feed_title, feed_id);
+
+                            // Reload feeds list
+                            self.feeds = self.repository.get_all_feeds().await?;
+
+                            // Clear input after short delay to show success message
+                            self.feed_input_active = false;
+                            self.feed_input.clear();
+
+                            // Refresh the new feed
+                            self.refresh_feeds().await?;
+                        }
+                        Err(e) => {
+                            self.feed_input_status = Some(format!("Error: {}", e));
+                            tracing::error!("Failed to insert feed: {}", e);
+                        }
+                    }
+                }
+                Err(_) => {
+                    self.feed_input_status = Some("No feed here.".to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Ask Claude `question` about the selected article, or the articles in
+    /// view when `ask_all` is set (non-blocking)
+    async fn start_question(&mut self, question: String) -> Result<()> {
+        let mut sources = Vec::new();
+        if self.ask_all {
+            for article in self.filtered_articles().into_iter().take(ASK_MAX_ARTICLES) {
+                sources.push((article.id, article.title.clone(), stored_text(article)));
+            }
+        } else if let Some(article) = self.selected_article().cloned() {
+            let (text, _) = self.summary_content(&article).await?;
+            sources.push((article.id, article.title, text));
+        }
+        self.start_answer(question.clone(), sources, LlmTask::Question(question));
+        Ok(())
+    }
+
+    /// Compare the outlets covering the selected article's story (non-blocking)
+    async fn start_briefing(&mut self) -> Result<()> {
+        if self.summarizer.is_none() {
+            self.bookmark_status = Some(("Set claude_api_key, [openai] or [local_llm] for briefings".to_string(), Instant::now()));
+            return Ok(());
+        }
+        let Some(article) = self.selected_article().cloned() else {
+            return Ok(());
+        };
+
+        let target = Headline {
+            article_id: article.id,
+            feed_id: article.feed_id,
+            title: article.title.clone(),
+            at: article.published_at.unwrap_or(article.fetched_at),
+        };
+        let since = target.at - chrono::Duration::hours(STORY_WINDOW_HOURS);
+        let headlines = load_headlines(&self.repository, since, None).await?;
+        let group: Vec<i64> = story_group(&target, &headlines, BRIEFING_MAX_OUTLETS)
+            .iter()
+            .map(|h| h.article_id)
+            .collect();
+        if group.len() < 2 {
+            self.bookmark_status = Some(("No other outlets found covering this story".to_string(), Instant::now()));
+            return Ok(());
+        }
+
+        let mut sources = Vec::new();
+        for id in group {
+            let Some(covering) = self.repository.get_article(id).await? else {
+                continue;
+            };
+            let outlet = self
+                .feeds
+                .iter()
+                .find(|f| f.id == covering.feed_id)
+                .map(|f| f.display_title().to_string())
+                .unwrap_or_else(|| covering.feed_title.clone().unwrap_or_default());
+            let text = if id == article.id {
+                self.summary_content(&article).await?.0
+            } else {
+                stored_text(&covering)
+            };
+            sources.push((id, format!("{}: {}", outlet, covering.title), text));
+        }
+        self.start_answer(format!("How outlets cover: {}", article.title), sources, LlmTask::Briefing);
+        Ok(())
+    }
+
+    /// Open the answer popup and send `task` with `sources` (id, title,
+    /// text) to Claude; `heading` tops the answer
+    fn start_answer(&mut self, heading: String, sources: Vec<(i64, String, String)>, task: LlmTask) {
+        let Some(summarizer) = &self.summarizer else {
+            return;
+        };
+        let summarizer = Arc::clone(summarizer);
+
+        self.answer_active = true;
+        self.answer = None;
+        self.answer_index = 0;
+        if sources.is_empty() {
+            self.answer_status = Some("No articles to ask about".to_string());
+            return;
+        }
+        self.answer_status = Some(format!("Reading {} article(s)...", sources.len()));
+        self.ask_sources = sources.iter().map(|(id, title, _)| (*id, title.clone())).collect();
+
+        let context: Vec<(String, String)> = sources.into_iter().map(|(_, title, text)| (title, text)).collect();
+        let tx = self.answer_tx.clone();
+        tokio::spawn(async move {
+            let result = match task {
+                LlmTask::Question(question) => summarizer.answer_question(&question, &context).await,
+                LlmTask::Briefing => summarizer.compare_coverage(&context).await,
+            };
+            let result = result.map_err(|e| e.to_string());
+            let _ = tx.send(AnswerResult { question: heading, result }).await;
+        });
+    }
+
+    /// Say so when the watched blocklist picks up an edit or a shared
+    /// list's download (non-blocking)
+    pub fn poll_blocklist(&mut self) {
+        let generation = self.blocklist.generation();
+        if generation != self.blocklist_generation {
+            self.blocklist_generation = generation;
+            if self.blocklist_active {
+                self.blocklist_entries = self.blocklist.entries();
+                self.blocklist_subscriptions = self.blocklist.subscriptions();
+                self.blocklist_index = self.blocklist_index.min(self.blocklist_entries.len().saturating_sub(1));
+            }
+            self.bookmark_status = Some((
+                format!("Blocklist reloaded: {} rules", self.blocklist.rule_count()),
+                Instant::now(),
+            ));
+        }
+    }
+
+    /// Poll for an answered question (non-blocking)
+    pub fn poll_answer_result(&mut self) {
+        let Ok(result) = self.answer_rx.try_recv() else {
+            return;
+        };
+        match result.result {
+            Ok(text) => {
+                let citations = cited_sources(&text, self.ask_sources.len())
+                    .into_iter()
+                    .map(|number| {
+                        let (article_id, title) = self.ask_sources[number - 1].clone();
+                        Citation { number, article_id, title }
+                    })
+                    .collect();
+                self.answer = Some(Answer {
+                    question: result.question,
+                    text,
+                    citations,
+                });
+                self.answer_status = None;
+            }
+            Err(e) => {
+                tracing::error!("Failed to answer question: {}", e);
+                self.answer_status = Some(format!("Error: {}", e));
+            }
+        }
+        self.answer_index = 0;
+        self.ask_sources.clear();
+    }
+
+    /// Whether a question is waiting for its answer
+    pub fn answer_pending(&self) -> bool {
+        !self.ask_sources.is_empty()
+    }
+
+    /// Look for feeds on the sites behind starred articles (non-blocking)
+    async fn start_recommendations(&mut self) -> Result<()> {
+        self.recommendations_active = true;
+        self.recommendations.clear();
+        self.recommendation_index = 0;
+
+        let starred = self.repository.get_starred_articles().await?;
+        let candidates = candidate_domains(&starred, &self.feeds, RECOMMENDATION_CANDIDATES);
+        if candidates.is_empty() {
+            self.recommendations_status =
+                Some("No unsubscribed sites among starred articles".to_string());
+            return Ok(());
+        }
+
+        self.recommendations_status = Some(format!("Checking {} sites for feeds...", candidates.len()));
+
+        let fetcher = self.fetcher.clone();
+        let feeds = self.feeds.clone();
+        let tx = self.recommendation_tx.clone();
+
+        tokio::spawn(async move {
+            let recommendations = discover_recommendations(&fetcher, candidates, &feeds).await;
+            let _ = tx.send(RecommendationResult { recommendations }).await;
+        });
+
+        Ok(())
+    }
+
+    /// Poll for completed feed recommendations (non-blocking)
+    pub async fn poll_recommendation_result(&mut self) -> Result<()> {
+        if let Ok(result) = self.recommendation_rx.try_recv() {
+            self.recommendations_status = if result.recommendations.is_empty() {
+                Some("No feeds found on starred-article sites".to_string())
+            } else {
+                None
+            };
+            self.recommendations = result.recommendations;
+            self.recommendation_index = 0;
+        }
+        Ok(())
+    }
+
+    /// Subscribe to the highlighted recommendation and drop it from the list
+    async fn subscribe_recommendation(&mut self) -> Result<()> {
+        if self.recommendation_index >= self.recommendations.len() {
+            return Ok(());
+        }
+
+        let recommendation = self.recommendations.remove(self.recommendation_index);
+        let title = recommendation.feed.title.clone();
+        match self.repository.insert_feed(recommendation.feed).await {
+            Ok(feed_id) => {
+                tracing::info!("Subscribed to recommended feed: {} (id={})", title, feed_id);
+                self.recommendations_status = Some(format!("Subscribed: {}", title));
+                self.feeds = self.repository.get_all_feeds().await?;
+                self.refresh_feeds().await?;
+            }
+            Err(e) => {
+                self.recommendations_status = Some(format!("Error: {}", e));
+            }
+        }
+
+        if self.recommendation_index >= self.recommendations.len() {
+            self.recommendation_index = self.recommendations.len().saturating_sub(1);
+        }
+        Ok(())
+    }
+
+    /// Play the selected episode's enclosure, resuming where it left off, or
+    /// stop it if it's the one playing
+    async fn toggle_playback(&mut self) -> Result<()> {
+        let playing_id = self.playback.as_ref().map(|(id, _)| *id);
+        self.stop_playback().await?;
+
+        let Some(article) = self.selected_article() else {
+            return Ok(());
+        };
+        if playing_id == Some(article.id) {
+            return Ok(());
+        }
+        let Some(enclosure) = &article.enclosure else {
+            self.bookmark_status = Some(("No audio enclosure".to_string(), Instant::now()));
+            return Ok(());
+        };
+
+        let article_id = article.id;
+        let start = article.playback_secs.unwrap_or(0);
+        match self.player.play(&enclosure.url, start) {
+            Ok(playback) => {
+                self.playback = Some((article_id, playback));
+                self.last_playback_poll = Instant::now();
+            }
+            Err(e) => {
+                tracing::error!("Failed to start podcast player: {}", e);
+                self.bookmark_status = Some(("Player failed to start".to_string(), Instant::now()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Stop the player, if any, and remember its position
+    async fn stop_playback(&mut self) -> Result<()> {
+        if let Some((article_id, mut playback)) = self.playback.take() {
+            playback.update_position().await;
+            playback.stop().await;
+            self.record_playback(article_id, &playback).await?;
+        }
+        Ok(())
+    }
+
+    /// Save the player's position every few seconds, and the final one when
+    /// it exits on its own
+    pub async fn poll_playback(&mut self) -> Result<()> {
+        let Some((article_id, playback)) = self.playback.as_mut() else {
+            return Ok(());
+        };
+        let article_id = *article_id;
+
+        if playback.exited().is_some() {
+            if let Some((_, playback)) = self.playback.take() {
+                self.record_playback(article_id, &playback).await?;
+            }
+            return Ok(());
+        }
+
+        if self.last_playback_poll.elapsed().as_secs() >= PLAYBACK_POLL_SECS {
+            self.last_playback_poll = Instant::now();
+            playback.update_position().await;
+            let position = playback.position_secs;
+            self.repository.save_playback(article_id, position, false).await?;
+            if let Some(a) = self.articles.iter_mut().find(|a| a.id == article_id) {
+                a.playback_secs = Some(position);
+            }
+        }
+        Ok(())
+    }
+
+    async fn record_playback(&mut self, article_id: i64, playback: &Playback) -> Result<()> {
+        let duration = playback.duration_secs.or_else(|| {
+            self.articles
+                .iter()
+                .find(|a| a.id == article_id)
+                .and_then(|a| a.enclosure.as_ref()?.duration_secs)
+        });
+        let finished = duration.is_some_and(|d| playback.position_secs + PLAYED_MARGIN_SECS >= d);
+
+        self.repository
+            .save_playback(article_id, playback.position_secs, finished)
+            .await?;
+        if let Some(a) = self.articles.iter_mut().find(|a| a.id == article_id) {
+            a.playback_secs = Some(if finished { 0 } else { playback.position_secs });
+            a.is_played |= finished;
+        }
+        Ok(())
+    }
+
+    /// What the reader pane shows of the selected article
+    pub fn reader_text(&self) -> String {
+        let article = self.selected_article();
+        reader_text(article, article.map_or(ReadingMode::Full, |a| self.reading_mode(a.feed_id)))
+    }
+
+    /// Where the find matches in the reader text
+    pub fn find_matches(&self) -> Vec<std::ops::Range<usize>> {
+        self.find.as_ref().map(|f| f.matches(&self.reader_text())).unwrap_or_default()
+    }
+
+    /// The spot the reader scrolls to: the current match, or the
+    /// highlighted link's first reference
+    pub fn reader_mark(&self) -> Option<std::ops::Range<usize>> {
+        let text = self.reader_text();
+        match (&self.find, self.link_number) {
+            (Some(find), _) => {
+                let matches = find.matches(&text);
+                matches.get(find.current.min(matches.len().saturating_sub(1))).cloned()
+            }
+            (None, Some(number)) => article_links(&text).into_iter().find(|l| l.number == number).map(|l| l.at),
+            (None, None) => None,
+        }
+    }
+
+    /// Link `number` of the selected article, made absolute against the article's URL
+    pub fn link_url(&self, number: usize) -> Option<String> {
+        let link = article_links(&self.reader_text()).into_iter().find(|l| l.number == number)?;
+        let base = self.selected_article().and_then(|a| url::Url::parse(&a.url).ok());
+        match base.and_then(|base| base.join(&link.url).ok()) {
+            Some(url) => Some(url.to_string()),
+            None => Some(link.url),
+        }
+    }
+
+    /// Comments loaded for the selected article, if any
+    pub fn selected_comments(&self) -> Option<&[Comment]> {
+        let article = self.selected_article()?;
+        match &self.comments {
+            Some((article_id, comments)) if *article_id == article.id => Some(comments),
+            _ => None,
+        }
+    }
+
+    /// Fetch the selected article's comment feed (non-blocking)
+    fn start_comments_fetch(&mut self) {
+        let Some(article) = self.selected_article() else {
+            return;
+        };
+        let Some(url) = article.comments_url.clone() else {
+            self.bookmark_status = Some(("No comment feed for this article".to_string(), Instant::now()));
+            return;
+        };
+
+        let article_id = article.id;
+        self.pending_comments_article_id = Some(article_id);
+
+        let fetcher = self.fetcher.clone();
+        let tx = self.comments_tx.clone();
+
+        tokio::spawn(async move {
+            let result = fetcher.fetch_comments(&url).await.map_err(|e| e.to_string());
+            let _ = tx.send(CommentsResult { article_id, result }).await;
+        });
+    }
+
+    /// Poll for a fetched comment thread (non-blocking)
+    pub fn poll_comments_result(&mut self) {
+        if let Ok(result) = self.comments_rx.try_recv() {
+            if self.pending_comments_article_id != Some(result.article_id) {
+                return;
+            }
+            self.pending_comments_article_id = None;
+            match result.result {
+                Ok(comments) => {
+                    self.bookmark_status = Some((format!("{} comments", comments.len()), Instant::now()));
+                    self.comments = Some((result.article_id, comments));
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to fetch comments: {}", e);
+                    self.bookmark_status = Some(("Failed to fetch comments".to_string(), Instant::now()));
+                }
+            }
+        }
+    }
+
+    /// Start fetching the feeds that are due in the background; each feed's
+    /// articles are stored as they arrive. The journal lists the feeds not
+    /// stored yet, so after a crash the next refresh finishes just those.
+    pub async fn refresh_feeds(&mut self) -> Result<()> {
+        if self.is_refreshing {
+            return Ok(()); // Already refreshing
+        }
+        // Rules may have been changed from the CLI since startup
+        self.author_rules = self.repository.get_author_rules().await?;
+
+        let now = chrono::Utc::now();
+        let interrupted = self.repository.journal_pending(Pipeline::Refresh).await?;
+        let feeds: Vec<Feed> = self
+            .feeds
+            .iter()
+            .filter(|f| interrupted.is_empty() || interrupted.contains(&f.id))
+            .cloned()
+            .collect();
+        let due: Vec<i64> = feeds.iter().filter(|f| f.is_due(now)).map(|f| f.id).collect();
+        if !interrupted.is_empty() {
+            tracing::info!("Resuming an interrupted refresh of {} feeds", due.len());
+        }
+        self.repository.journal_clear(Pipeline::Refresh).await?;
+        self.is_refreshing = true;
+        self.refreshing_feeds = due.len();
+        self.refresh_started_at = now;
+        self.repository.journal_add(Pipeline::Refresh, due).await?;
+
+        let fetcher = self.fetcher.clone();
+        let tx = self.refresh_tx.clone();
+
+        tokio::spawn(async move {
+            use futures::StreamExt;
+
+            let mut results = std::pin::pin!(fetcher.refresh_all(feeds));
+            while let Some((feed_id, outcome)) = results.next().await {
+                if tx.send(RefreshResult::Feed(feed_id, outcome)).await.is_err() {
+                    return;
+                }
+            }
+            let _ = tx.send(RefreshResult::Done).await;
+        });
+        Ok(())
+    }
+
+    /// Store whatever the running refresh has fetched so far (non-blocking)
+    pub async fn poll_refresh_result(&mut self) -> Result<()> {
+        while let Ok(result) = self.refresh_rx.try_recv() {
+            match result {
+                RefreshResult::Feed(feed_id, outcome) => self.store_refreshed_feed(feed_id, outcome).await,
+                RefreshResult::Done => self.finish_refresh().await?,
+            }
+        }
+        Ok(())
+    }
+
+    async fn store_refreshed_feed(&mut self, feed_id: i64, outcome: FeedRefresh) {
+        match outcome {
+            FeedRefresh::Fetched(fetched) => {
+                // Blocked keywords were already left out by the fetcher
+                for article in fetched.articles {
+                    if article.authors.iter().any(|a| self.is_muted(a)) {
+                        continue;
+                    }
+
+                    if let Err(e) = self.repository.upsert_article(article).await {
+                        tracing::warn!("Failed to upsert article: {}", e);
+                    }
+                }
+                if let Err(e) = self.repository.update_feed_last_fetched(feed_id, fetched.repaired).await {
+                    tracing::warn!("Failed to update feed last_fetched: {}", e);
+                }
+                // The feed list's count goes up while the rest are still fetching
+                match self.repository.feed_unread_count(feed_id).await {
+                    Ok(unread) => {
+                        if let Some(feed) = self.feeds.iter_mut().find(|f| f.id == feed_id) {
+                            feed.unread = unread;
+                        }
+                    }
+                    Err(e) => tracing::warn!("Failed to read unread count: {}", e),
+                }
+            }
+            FeedRefresh::Throttled(until) => {
+                if let Err(e) = self.repository.record_feed_throttled(feed_id, until).await {
+                    tracing::warn!("Failed to record rate limit: {}", e);
+                }
+            }
+            FeedRefresh::Missing(moved_to) => {
+                if let Err(e) = self.repository.record_feed_missing(feed_id, moved_to).await {
+                    tracing::warn!("Failed to record missing feed: {}", e);
+                }
+            }
+            FeedRefresh::Failed => {}
+        }
+        if let Err(e) = self.repository.journal_done(Pipeline::Refresh, feed_id).await {
+            tracing::warn!("Failed to journal refresh progress: {}", e);
+        }
+    }
+
+    /// Everything that runs once a refresh's articles are all stored
+    async fn finish_refresh(&mut self) -> Result<()> {
+        self.repository.journal_clear(Pipeline::Refresh).await?;
+        let stored_since = self.refresh_started_at;
+
+        // Back-off windows live on the feed rows, so pick them up (and clear
+        // the ones that just succeeded) before the next cycle
+        self.reload_feeds().await?;
+        self.ask_next_feed_move();
+
+        if let Err(e) = self.repository.apply_tag_rules(stored_since).await {
+            tracing::warn!("Failed to apply tag rules: {}", e);
+        }
+        // Before any announcements, so embargoed articles stay quiet
+        match self.repository.apply_embargo_rules(stored_since, self.embargo_rules.clone()).await {
+            Ok(0) => {}
+            Ok(n) => tracing::info!("Embargoed {} new articles", n),
+            Err(e) => tracing::warn!("Failed to apply embargo rules: {}", e),
+        }
+
+        // Count watched entities' coverage while the articles are still here
+        if let Err(e) = self.check_coverage_spikes().await {
+            tracing::warn!("Failed to update watchlist coverage: {}", e);
+        }
+        if let Err(e) = self.announce_new_articles(stored_since).await {
+            tracing::warn!("Failed to check followed authors and priority feeds: {}", e);
+        }
+
+        // Snoozes that ended while nobody was looking (a headless refresh)
+        if let Err(e) = self.repository.wake_snoozed(chrono::Utc::now()).await {
+            tracing::warn!("Failed to bring back snoozed articles: {}", e);
+        }
+
+        // Clean up articles older than 7 days after refresh
+        let deleted = self.repository.delete_old_articles(7).await?;
+        if deleted > 0 {
+            tracing::info!("Deleted {} articles older than 7 days", deleted);
+        }
+        if let Err(e) = self.http_cache.prune().await {
+            tracing::warn!("Failed to prune the HTTP cache: {}", e);
+        }
+
+        self.reload_articles().await?;
+        self.is_refreshing = false;
+
+        self.start_discussion_lookup().await?;
+        self.start_removal_check().await?;
+
+        // Transient summary failures whose back-off has run out
+        let due = self.repository.due_summary_retries(chrono::Utc::now()).await?;
+        self.start_summary_retries(due);
+        Ok(())
+    }
+
+    /// Look up HN/Lobsters threads for recent articles (non-blocking)
+    async fn start_discussion_lookup(&mut self) -> Result<()> {
+        let Some(client) = self.discussions.clone() else {
+            return Ok(());
+        };
+        if self.discussion_lookup_running {
+            return Ok(());
+        }
+
+        let candidates = self
+            .repository
+            .articles_needing_discussion_lookup(
+                DISCUSSION_MAX_AGE_HOURS,
+                DISCUSSION_RECHECK_HOURS,
+                DISCUSSION_BATCH,
+            )
+            .await?;
+        if candidates.is_empty() {
+            return Ok(());
+        }
+
+        self.discussion_lookup_running = true;
+        let tx = self.discussion_tx.clone();
+
+        tokio::spawn(async move {
+            // One article at a time: these are free public APIs
+            let mut checked = Vec::with_capacity(candidates.len());
+            for (article_id, url) in candidates {
+                checked.push((article_id, client.lookup(&url).await));
+            }
+            let _ = tx.send(DiscussionResult { checked }).await;
+        });
+
+        Ok(())
+    }
+
+    /// Poll for finished discussion lookups (non-blocking)
+    pub async fn poll_discussion_result(&mut self) -> Result<()> {
+        if let Ok(result) = self.discussion_rx.try_recv() {
+            self.discussion_lookup_running = false;
+            let found = result.checked.iter().filter(|(_, d)| !d.is_empty()).count();
+            for (article_id, discussions) in result.checked {
+                // The article may have been deleted while we were looking
+                if let Err(e) = self.repository.save_discussions(article_id, discussions).await {
+                    tracing::debug!("Failed to save discussions for {}: {}", article_id, e);
+                }
+            }
+            if found > 0 {
+                tracing::info!("Found discussions for {} articles", found);
+                self.reload_articles().await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Check saved articles' pages for take-downs (non-blocking)
+    async fn start_removal_check(&mut self) -> Result<()> {
+        let Some((checker, recheck_hours)) = self.removals.clone() else {
+            return Ok(());
+        };
+        if self.removal_check_running {
+            return Ok(());
+        }
+
+        let candidates = self
+            .repository
+            .articles_needing_removal_check(recheck_hours, REMOVAL_BATCH)
+            .await?;
+        if candidates.is_empty() {
+            return Ok(());
+        }
+
+        self.removal_check_running = true;
+        let tx = self.removal_tx.clone();
+
+        tokio::spawn(async move {
+            let mut checked = Vec::with_capacity(candidates.len());
+            for (article_id, url) in candidates {
+                match checker.is_removed(&url).await {
+                    Ok(removed) => checked.push((article_id, removed)),
+                    Err(e) => tracing::debug!("Couldn't check {} for a take-down: {}", url, e),
+                }
+            }
+            let _ = tx.send(RemovalResult { checked }).await;
+        });
+        Ok(())
+    }
+
+    /// Poll for finished take-down checks (non-blocking), pointing out
+    /// articles that have gone and whether there's a copy to read instead
+    pub async fn poll_removal_result(&mut self) -> Result<()> {
+        let Ok(result) = self.removal_rx.try_recv() else {
+            return Ok(());
+        };
+        self.removal_check_running = false;
+        let mut removed = Vec::new();
+        for (article_id, gone) in result.checked {
+            self.repository.save_removal_check(article_id, gone).await?;
+            if gone {
+                if let Some(article) = self.repository.get_article(article_id).await? {
+                    removed.push((article.title, article.archive_path));
+                }
+            }
+        }
+        self.removed_news = removed;
+        let status = match self.removed_news.as_slice() {
+            [] => return Ok(()),
+            [(title, Some(_))] => format!("Taken down from its site: {} (o opens the archived copy)", title),
+            [(title, None)] => format!("Taken down from its site: {}", title),
+            many => format!("{} saved articles taken down from their sites (is:removed)", many.len()),
+        };
+        tracing::info!("{}", status);
+        self.reload_articles().await?;
+        self.bookmark_status = Some((status, Instant::now()));
+        Ok(())
+    }
+
+    /// Snapshot a newly starred article in the background, unless archiving
+    /// is off or it already has a snapshot
+    fn start_archive(&mut self, article_id: i64) {
+        let Some(archiver) = self.archiver.clone() else {
+            return;
+        };
+        let Some(article) = self.articles.iter().find(|a| a.id == article_id) else {
+            return;
+        };
+        if article.archive_path.is_some() {
+            return;
+        }
+
+        let article = article.clone();
+        let repository = self.repository.clone();
+        let include = self.embed.archive;
+        let tx = self.archive_tx.clone();
+        tokio::spawn(async move {
+            let result = match load_embed(&repository, &article, include).await {
+                Ok(embed) => archiver.archive(&article, &embed).await,
+                Err(e) => Err(e),
+            };
+            let result = result.map_err(|e| e.to_string());
+            let _ = tx.send(ArchiveResult { article_id, result }).await;
+        });
+    }
+
+    /// Poll for finished article snapshots (non-blocking)
+    pub async fn poll_archive_result(&mut self) -> Result<()> {
+        while let Ok(result) = self.archive_rx.try_recv() {
+            match result.result {
+                Ok(path) => {
+                    let path = path.to_string_lossy().to_string();
+                    self.repository
+                        .set_archive_path(result.article_id, path.clone())
+                        .await?;
+                    if let Some(article) = self.articles.iter_mut().find(|a| a.id == result.article_id) {
+                        article.archive_path = Some(path);
+                    }
+                    self.bookmark_status = Some(("Archived".to_string(), Instant::now()));
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to archive article {}: {}", result.article_id, e);
+                    self.bookmark_status = Some(("Failed to archive article".to_string(), Instant::now()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the selected article to the notes vault, with its summary if
+    /// one has been generated
+    async fn save_note(&mut self) -> Result<()> {
+        let Some(notes) = &self.notes else {
+            self.bookmark_status = Some(("Set [notes] vault in the config".to_string(), Instant::now()));
+            return Ok(());
+        };
+        let Some(article) = self.selected_article() else {
+            return Ok(());
+        };
+
+        let embed = load_embed(&self.repository, article, self.embed.notes).await?;
+        let message = match notes.write(article, &embed).await {
+            Ok(path) => {
+                let name = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+                format!("Saved note {}", name)
+            }
+            Err(e) => {
+                tracing::warn!("Failed to save note: {}", e);
+                format!("Failed to save note: {}", e)
+            }
+        };
+        self.bookmark_status = Some((message, Instant::now()));
+        Ok(())
+    }
+
+    /// Poll for finished PDF exports (non-blocking)
+    pub fn poll_export_result(&mut self) {
+        while let Ok(result) = self.export_rx.try_recv() {
+            let message = match result.result {
+                Ok(path) => format!("Saved {}", path.display()),
+                Err(e) => {
+                    tracing::warn!("PDF export failed: {}", e);
+                    format!("PDF export failed: {}", e)
+                }
+            };
+            self.bookmark_status = Some((message, Instant::now()));
+        }
+    }
+
+    /// Copy the selected article's title, link, date and summary, laid out
+    /// by the `brief` template, to the clipboard
+    async fn copy_brief(&mut self) -> Result<()> {
+        let Some(article) = self.selected_article().cloned() else {
+            return Ok(());
+        };
+        let embed = load_embed(&self.repository, &article, true).await?;
+        let text = crate::export::brief(&self.templates, &article, &embed);
+        let message = match self.clipboard.copy(&text).await {
+            Ok(_) if embed.summary.is_none() => "Copied brief (no summary yet)".to_string(),
+            Ok(_) => "Copied brief".to_string(),
+            Err(e) => {
+                tracing::warn!("Copying brief failed: {}", e);
+                format!("Copy failed: {}", e)
+            }
+        };
+        self.bookmark_status = Some((message, Instant::now()));
+        Ok(())
+    }
+
+    /// Export one article to PDF (for CLI use)
+    pub async fn export_pdf(&self, article_id: i64) -> Result<std::path::PathBuf> {
+        let article = self
+            .repository
+            .get_article(article_id)
+            .await?
+            .ok_or_else(|| AppError::Config(format!("No article with id {}", article_id)))?;
+        let embed = load_embed(&self.repository, &article, self.embed.pdf).await?;
+        self.pdf_exporter.export(&article, &embed).await
+    }
+
+    /// Write the collection `name` out as one document with each article's
+    /// summary and note, returning the file's path
+    pub async fn export_collection(&self, name: &str, format: PacketFormat) -> Result<std::path::PathBuf> {
+        let articles = self.repository.get_collection_articles(name).await?;
+        if articles.is_empty() {
+            return Err(AppError::Config(format!("No collection {} (or it's empty)", name)));
+        }
+        let name = self
+            .repository
+            .get_collections()
+            .await?
+            .into_iter()
+            .find(|c| c.name.eq_ignore_ascii_case(name.trim()))
+            .map_or_else(|| name.to_string(), |c| c.name);
+        let mut items = Vec::with_capacity(articles.len());
+        for article in articles {
+            items.push(PacketItem {
+                summary: self.repository.get_summary(article.id).await?,
+                note: self.repository.get_annotation(article.id).await?,
+                article,
+            });
+        }
+        self.packet_exporter.export(&name, &items, format).await
+    }
+
+    /// Re-fetch title, description and site link for one feed or all of
+    /// them (for CLI use). Returns (feeds updated, feeds that failed).
+    pub async fn refresh_feed_metadata(&mut self, feed_id: Option<i64>, keep_titles: bool) -> Result<(usize, usize)> {
+        use futures::stream::{self, StreamExt};
+
+        let feeds: Vec<Feed> = self
+            .feeds
+            .iter()
+            .filter(|f| feed_id.is_none_or(|id| f.id == id) && !f.is_local())
+            .cloned()
+            .collect();
+        if let (Some(id), true) = (feed_id, feeds.is_empty()) {
+            return Err(AppError::Config(format!("No feed with id {}", id)));
+        }
+
+        let fetcher = &self.fetcher;
+        let results: Vec<_> = stream::iter(feeds)
+            .map(|feed| async move {
+                let result = fetcher.fetch_metadata(&feed.url).await;
+                (feed, result)
+            })
+            .buffer_unordered(5)
+            .collect()
+            .await;
+
+        let (mut updated, mut failed) = (0, 0);
+        for (feed, result) in results {
+            match result {
+                Ok(meta) => {
+                    let title = meta.title.clone();
+                    if self.repository.update_feed_metadata(feed.id, meta, keep_titles).await? {
+                        updated += 1;
+                        if !keep_titles && title != feed.title {
+                            tracing::info!("Feed {} renamed: {} -> {}", feed.id, feed.title, title);
+                        }
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to fetch metadata for {}: {}", feed.url, e);
+                    failed += 1;
+                }
+            }
+        }
+
+        self.feeds = self.repository.get_all_feeds().await?;
+        Ok((updated, failed))
+    }
+
+    /// Apply one change to several feeds at once (for CLI use). Returns how
+    /// many feeds were changed.
+    pub async fn bulk_update_feeds(&mut self, ids: Vec<i64>, update: FeedBulkUpdate) -> Result<usize> {
+        let count = self.repository.bulk_update_feeds(ids, update).await?;
+        self.feeds = self.repository.get_all_feeds().await?;
+        Ok(count)
+    }
+
+    /// Unsubscribe from a feed, dealing with its articles as `removal` says
+    async fn remove_feed(&mut self, feed_id: i64, removal: FeedRemoval) -> Result<()> {
+        self.repository.remove_feed(feed_id, removal).await?;
+        // The feed list was showing just this feed; go back to all of them
+        let was_filtered = self.feed_filter == Some(feed_id);
+        if was_filtered {
+            self.feed_filter = None;
+        }
+        // Starred articles now belong to the orphanage, so reload rather than drop them
+        if removal == FeedRemoval::KeepStarred || was_filtered {
+            self.reload_articles().await?;
+        } else {
+            self.articles.retain(|a| a.feed_id != feed_id);
+        }
+        self.total_articles = self.repository.count_articles().await?;
+        self.feeds = self.repository.get_all_feeds().await?;
+        // Adjust selection if needed
+        let len = self.filtered_articles().len();
+        if len > 0 && self.selected_index >= len {
+            self.selected_index = len - 1;
+        }
+        // Reset summary state
         self.summary_status = SummaryStatus::NotGenerated;
         self.current_summary = None;
-        self.is_saved_to_raindrop = false;
-        self.bookmark_status = None;
 
-        // Check if current article is saved to raindrop
-        let article_id = self.selected_article().map(|a| a.id);
-        if let Some(id) = article_id {
-            self.is_saved_to_raindrop = self
-                .repository
-                .is_saved_to_raindrop(id)
-                .await?;
+        let message = match removal {
+            FeedRemoval::DeleteAll => "Feed deleted",
+            FeedRemoval::KeepStarred => "Feed deleted; starred articles moved to Orphaned",
+            FeedRemoval::Archive => "Feed archived (beatcheck feeds restore to undo)",
+        };
+        self.bookmark_status = Some((message.to_string(), Instant::now()));
+        Ok(())
+    }
 
-            // Check for cached summary
-            if let Some(summary) = self.repository.get_summary(id).await? {
-                self.current_summary = Some(summary);
-                self.summary_status = SummaryStatus::Generated;
+    /// Fetch the feed from the URL found on its site from now on. Returns
+    /// the new URL, or `None` if none was found.
+    pub async fn accept_feed_move(&mut self, feed_id: i64) -> Result<Option<String>> {
+        let moved = self.repository.accept_feed_move(feed_id).await?;
+        self.feeds = self.repository.get_all_feeds().await?;
+        Ok(moved)
+    }
+
+    /// Open the moved-feed prompt for the next feed with a URL waiting to
+    /// be accepted, unless one is already open
+    fn ask_next_feed_move(&mut self) {
+        if self.feed_move_id.is_none() {
+            self.feed_move_id = self
+                .feeds
+                .iter()
+                .find(|f| f.moved_to.is_some() && !self.feed_moves_later.contains(&f.id))
+                .map(|f| f.id);
+        }
+    }
+
+    /// Bring back an archived feed. Returns false if it wasn't archived.
+    pub async fn restore_feed(&mut self, feed_id: i64) -> Result<bool> {
+        let restored = self.repository.restore_feed(feed_id).await?;
+        self.feeds = self.repository.get_all_feeds().await?;
+        Ok(restored)
+    }
+
+    /// Refresh feeds and wait for completion (blocking, for CLI/headless use)
+    pub async fn refresh_feeds_blocking(&mut self) -> Result<()> {
+        self.refresh_feeds().await?;
+
+        // Wait for the refresh to complete
+        while self.is_refreshing {
+            self.poll_refresh_result().await?;
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        // and for take-down checks, so they can be announced
+        while self.removal_check_running {
+            self.poll_removal_result().await?;
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+
+        Ok(())
+    }
+
+    /// Re-read the feed list from the database (it may change outside this process)
+    pub async fn reload_feeds(&mut self) -> Result<()> {
+        self.feeds = self.repository.get_all_feeds().await?;
+        Ok(())
+    }
+
+    /// Switch to the All view, reloaded under the current tag/entity/author/term
+    /// filters and search
+    async fn show_filtered(&mut self) -> Result<()> {
+        self.filter_mode = FilterMode::All;
+        self.articles.clear();
+        self.selected_index = 0;
+        self.reload_articles().await?;
+        self.on_selection_changed().await
+    }
+
+    /// Coverage of every watched entity over the last `days` days
+    pub async fn watch_timelines(&self, days: usize) -> Result<Vec<Timeline>> {
+        load_timelines(&self.repository, days).await
+    }
+
+    /// Recount recent coverage of watched entities and note which spiked,
+    /// flashing a status for ones that weren't spiking before
+    async fn check_coverage_spikes(&mut self) -> Result<()> {
+        let since = chrono::Utc::now().date_naive() - chrono::Duration::days(COVERAGE_RECOUNT_DAYS);
+        self.repository.update_entity_coverage(since).await?;
+        let spikes: Vec<Timeline> = self
+            .watch_timelines(TIMELINE_DAYS)
+            .await?
+            .into_iter()
+            .filter(Timeline::is_spike)
+            .collect();
+        let new = spikes.iter().find(|t| !self.coverage_spikes.contains(&t.entity));
+        if let Some(new) = new.filter(|_| !self.is_quiet()) {
+            tracing::info!("Coverage spike: {}", new.describe_spike());
+            self.bookmark_status = Some((format!("Coverage spike: {}", new.describe_spike()), Instant::now()));
+        }
+        self.coverage_spikes = spikes.into_iter().map(|t| t.entity).collect();
+        Ok(())
+    }
+
+    /// Inside `[schedule] quiet_hours`, when nothing is announced
+    pub fn is_quiet(&self) -> bool {
+        self.schedule.is_quiet(chrono::Local::now().naive_local())
+    }
+
+    /// Whether `[schedule]` lets a scheduled refresh run now
+    pub fn may_refresh_now(&self) -> bool {
+        self.schedule.may_refresh(chrono::Local::now().naive_local())
+    }
+
+    fn is_muted(&self, author: &str) -> bool {
+        self.author_rules
+            .iter()
+            .any(|(name, rule)| *rule == AuthorRule::Mute && name.eq_ignore_ascii_case(author))
+    }
+
+    /// Flash a status for new articles from high-priority feeds, or failing
+    /// that by followed authors, stored since `since`
+    async fn announce_new_articles(&mut self, since: chrono::DateTime<chrono::Utc>) -> Result<()> {
+        self.followed_news = self.repository.new_from_followed_authors(since).await?;
+        self.priority_news = self.repository.new_from_priority_feeds(since).await?;
+        let status = match (self.priority_news.as_slice(), self.followed_news.as_slice()) {
+            _ if self.is_quiet() => return Ok(()),
+            ([], []) => return Ok(()),
+            ([(feed, title)], _) => format!("New in {}: {}", feed, title),
+            ([(feed, _), ..], _) => format!("{} new articles from priority feeds, latest in {}", self.priority_news.len(), feed),
+            ([], [(author, title)]) => format!("New from {}: {}", author, title),
+            ([], [(author, _), ..]) => format!("{} new articles from followed authors, latest by {}", self.followed_news.len(), author),
+        };
+        tracing::info!("{}", status);
+        self.bookmark_status = Some((status, Instant::now()));
+        Ok(())
+    }
+
+    /// Whether the feed list is on screen: three-pane layout in a terminal
+    /// wide enough for it
+    fn feed_list_visible(&self) -> bool {
+        self.panes.layout == PaneLayout::ThreePane
+            && crossterm::terminal::size().is_ok_and(|(width, _)| self.panes.fit(width) == PaneLayout::ThreePane)
+    }
+
+    /// Feeds in the feed list: those with unread articles if the user
+    /// asked for that, keeping the picked ones so the selection stays put
+    pub fn listed_feeds(&self) -> Vec<&Feed> {
+        let picked = |f: &Feed| {
+            self.feed_filter == Some(f.id)
+                || self.group_filter.as_ref().is_some_and(|g| self.panes.grouping.groups(f).contains(g))
+        };
+        self.feeds
+            .iter()
+            .filter(|f| !self.panes.unread_feeds_only || f.unread > 0 || picked(f))
+            .collect()
+    }
+
+    pub fn sidebar_rows(&self) -> Vec<SidebarRow<'_>> {
+        sidebar_rows(&self.listed_feeds(), self.panes.grouping, &self.collapsed_groups)
+    }
+
+    /// The feed list row matching what the article list shows. A feed in a
+    /// folded group selects the group's header.
+    pub fn sidebar_row(&self) -> usize {
+        let rows = self.sidebar_rows();
+        let find = |wanted: &dyn Fn(&SidebarRow) -> bool| rows.iter().position(wanted);
+        if let Some(id) = self.feed_filter {
+            let groups = self.feeds.iter().find(|f| f.id == id).map(|f| self.panes.grouping.groups(f)).unwrap_or_default();
+            return find(&|r| matches!(r, SidebarRow::Feed { feed, group } if feed.id == id && *group == self.feed_group))
+                .or_else(|| find(&|r| matches!(r, SidebarRow::Feed { feed, .. } if feed.id == id)))
+                .or_else(|| find(&|r| matches!(r, SidebarRow::Group { name, .. } if groups.contains(name))))
+                .unwrap_or(0);
+        }
+        if let Some(group) = &self.group_filter {
+            if let Some(row) = find(&|r| matches!(r, SidebarRow::Group { name, .. } if name == group)) {
+                return row;
+            }
+        }
+        let view = match self.filter_mode {
+            FilterMode::All if self.today_only => SidebarView::Today,
+            FilterMode::All => SidebarView::All,
+            FilterMode::Starred => SidebarView::Starred,
+            FilterMode::Queue => SidebarView::Queue,
+            FilterMode::Discussed => SidebarView::Discussed,
+            FilterMode::History => SidebarView::History,
+            FilterMode::Collection => return 0,
+        };
+        find(&|r| matches!(r, SidebarRow::View(v) if *v == view)).unwrap_or(0)
+    }
+
+    /// Show what a feed list row stands for
+    async fn pick_sidebar(&mut self, pick: SidebarPick) -> Result<()> {
+        self.feed_filter = None;
+        self.group_filter = None;
+        self.today_only = false;
+        self.day_filter = None;
+        match pick {
+            SidebarPick::View(SidebarView::All) => {}
+            SidebarPick::View(SidebarView::Today) => self.today_only = true,
+            SidebarPick::View(SidebarView::Starred) => return self.show_view(FilterMode::Starred).await,
+            SidebarPick::View(SidebarView::Queue) => return self.show_view(FilterMode::Queue).await,
+            SidebarPick::View(SidebarView::Discussed) => return self.show_view(FilterMode::Discussed).await,
+            SidebarPick::View(SidebarView::History) => return self.show_view(FilterMode::History).await,
+            SidebarPick::Group(name) => self.group_filter = Some(name),
+            SidebarPick::Feed(id, group) => {
+                self.feed_filter = Some(id);
+                self.feed_group = group;
+            }
+        }
+        self.show_filtered().await
+    }
+
+    /// Switch to the All, Starred, Queue or Discussed view from the top
+    async fn show_view(&mut self, mode: FilterMode) -> Result<()> {
+        self.filter_mode = mode;
+        self.articles.clear();
+        self.selected_index = 0;
+        self.reload_articles().await?;
+        self.on_selection_changed().await
+    }
+
+    /// Unread articles in each folder, from the feeds' counters
+    pub fn folder_unread(&self) -> Vec<(String, u32)> {
+        let mut folders: Vec<(String, u32)> = Vec::new();
+        for feed in &self.feeds {
+            let Some(folder) = &feed.folder else { continue };
+            match folders.iter_mut().find(|(name, _)| name == folder) {
+                Some((_, unread)) => *unread += feed.unread,
+                None => folders.push((folder.clone(), feed.unread)),
+            }
+        }
+        folders.sort_by_key(|(name, _)| name.to_lowercase());
+        folders
+    }
+
+    /// Articles the next batch action applies to: those picked with Space,
+    /// plus the range from the anchor to the cursor, in list order
+    pub fn batch_ids(&self) -> Vec<i64> {
+        let range = match self.range_anchor {
+            Some(anchor) => anchor.min(self.selected_index)..=anchor.max(self.selected_index),
+            #[allow(clippy::reversed_empty_ranges)]
+            None => 1..=0,
+        };
+        self.articles
+            .iter()
+            .enumerate()
+            .filter(|(i, a)| range.contains(i) || self.selected_ids.contains(&a.id))
+            .map(|(_, a)| a.id)
+            .collect()
+    }
+
+    fn end_select_mode(&mut self) {
+        self.select_mode = false;
+        self.selected_ids.clear();
+        self.range_anchor = None;
+    }
+
+    /// Run `batch` on `ids` in one transaction, update the loaded list to
+    /// match, and leave selection mode
+    async fn apply_batch(&mut self, ids: Vec<i64>, batch: ArticleBatch, verb: &str) -> Result<()> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        let count = self.repository.update_articles(ids.clone(), batch.clone()).await?;
+        match &batch {
+            ArticleBatch::Read(read) => {
+                let mut changed = Vec::new();
+                for article in self.articles.iter_mut().filter(|a| ids.contains(&a.id) && a.is_read != *read) {
+                    article.is_read = *read;
+                    changed.push(article.feed_id);
+                }
+                for feed_id in changed {
+                    self.adjust_unread(feed_id, *read);
+                }
+            }
+            ArticleBatch::Starred(starred) => {
+                if self.filter_mode == FilterMode::Starred {
+                    self.reload_articles().await?;
+                } else {
+                    for article in self.articles.iter_mut().filter(|a| ids.contains(&a.id)) {
+                        article.is_starred = *starred;
+                        article.is_pinned &= *starred;
+                    }
+                }
+                if *starred {
+                    for &id in &ids {
+                        self.start_archive(id);
+                    }
+                }
+            }
+            ArticleBatch::Tag(tag) => {
+                for article in self.articles.iter_mut().filter(|a| ids.contains(&a.id)) {
+                    if !article.tags.contains(tag) {
+                        article.tags.push(tag.clone());
+                    }
+                }
+            }
+            ArticleBatch::Delete => {
+                let unread: Vec<i64> = self
+                    .articles
+                    .iter()
+                    .filter(|a| ids.contains(&a.id) && !a.is_read)
+                    .map(|a| a.feed_id)
+                    .collect();
+                for feed_id in unread {
+                    self.adjust_unread(feed_id, true);
+                }
+                self.articles.retain(|a| !ids.contains(&a.id));
+                self.total_articles = self.total_articles.saturating_sub(count);
+                let len = self.filtered_articles().len();
+                if len > 0 && self.selected_index >= len {
+                    self.selected_index = len - 1;
+                }
+                self.on_selection_changed().await?;
             }
         }
-
+        self.end_select_mode();
+        let noun = if count == 1 { "article" } else { "articles" };
+        self.bookmark_status = Some((format!("{} {} {}", verb, count, noun), Instant::now()));
         Ok(())
     }
 
-    async fn generate_summary(&mut self) -> Result<()> {
-        let Some(summarizer) = &self.summarizer else {
-            self.summary_status = SummaryStatus::NoApiKey;
-            return Ok(());
-        };
+    /// Mirror a read-state change in the loaded feed's counter
+    fn adjust_unread(&mut self, feed_id: i64, read: bool) {
+        if let Some(feed) = self.feeds.iter_mut().find(|f| f.id == feed_id) {
+            feed.unread = if read { feed.unread.saturating_sub(1) } else { feed.unread + 1 };
+        }
+    }
 
-        let Some(article) = self.selected_article() else {
-            return Ok(());
+    /// Store a note written in the inline editor or `$EDITOR`; `None` when
+    /// the editor failed and the note was left alone
+    pub async fn save_annotation(&mut self, article_id: i64, text: Option<String>) -> Result<()> {
+        let status = match text {
+            Some(text) => {
+                self.repository.set_annotation(article_id, text.clone()).await?;
+                let note = Some(text.trim_end().to_string()).filter(|t| !t.trim().is_empty());
+                let status = if note.is_some() { "Note saved" } else { "Note removed" };
+                if self.selected_article().is_some_and(|a| a.id == article_id) {
+                    self.note = note;
+                }
+                status
+            }
+            None => "Editor failed; note not saved",
         };
+        self.bookmark_status = Some((status.to_string(), Instant::now()));
+        Ok(())
+    }
 
-        // Check cache first
-        if let Some(summary) = self.repository.get_summary(article.id).await? {
-            self.current_summary = Some(summary);
-            self.summary_status = SummaryStatus::Generated;
-            return Ok(());
+    /// A key was pressed, so whoever is at the terminal is still reading
+    pub fn note_activity(&mut self) {
+        self.focus.activity(Instant::now());
+    }
+
+    /// Count reading time toward the article on screen, saving it every so
+    /// often, and nudge once a sitting runs past `[focus] nudge_minutes`
+    pub async fn poll_focus(&mut self) -> Result<()> {
+        let now = Instant::now();
+        let on_screen = self.reading || self.panes.layout != PaneLayout::ListOnly;
+        let article = self.selected_article().map(|a| a.id).filter(|_| on_screen);
+        if let Some((id, time)) = self.focus.tick(now, article) {
+            self.save_reading_time(id, time).await?;
+        }
+        let nudge = self.nudge_minutes.filter(|&minutes| minutes > 0);
+        if let Some(minutes) = nudge {
+            if self.focus.nudge(now, std::time::Duration::from_secs(u64::from(minutes) * 60)) {
+                let status = format!("You've been doomscrolling for {} minutes. Time for a break?", minutes);
+                self.bookmark_status = Some((status, Instant::now()));
+            }
         }
+        Ok(())
+    }
 
-        let article_id = article.id;
-        let title = article.title.clone();
-        let article_url = article.url.clone();
+    async fn save_reading_time(&mut self, id: i64, time: std::time::Duration) -> Result<()> {
+        let secs = time.as_secs_f64().round() as u64;
+        if secs == 0 {
+            return Ok(());
+        }
+        let today = chrono::Local::now().date_naive();
+        if today != self.focus_day {
+            self.focus_day = today;
+            self.reading_today_secs = 0;
+            self.goal_reached = false;
+        }
+        self.repository.add_reading_time(id, today, secs).await?;
+        self.reading_today_secs += secs;
+        if self.selected_article().is_some_and(|a| a.id == id) {
+            self.article_reading_secs += secs;
+        }
+        if let Some(goal) = self.daily_goal_minutes.filter(|_| !self.goal_reached) {
+            if self.reading_today_secs >= u64::from(goal) * 60 {
+                self.goal_reached = true;
+                let status = format!("Reading goal reached: {} minutes today", goal);
+                self.bookmark_status = Some((status, Instant::now()));
+            }
+        }
+        Ok(())
+    }
 
-        // Get RSS content as fallback
-        let rss_content = article
-            .content_text
-            .clone()
-            .or_else(|| article.content.clone())
-            .unwrap_or_default();
+    /// Seconds spent reading the selected article, counting the time not
+    /// saved yet
+    pub fn time_on_article(&self) -> u64 {
+        let unsaved = self.selected_article().map_or(0, |a| self.focus.unsaved(a.id).as_secs());
+        self.article_reading_secs + unsaved
+    }
 
-        self.summary_status = SummaryStatus::Generating;
-        self.pending_summary_article_id = Some(article_id);
+    /// Add the selected article to the history. The history view itself
+    /// isn't reordered, so the selection stays put.
+    async fn record_opened(&self) -> Result<()> {
+        if let Some(article) = self.selected_article() {
+            self.repository.record_opened(article.id).await?;
+        }
+        Ok(())
+    }
 
-        // Try to fetch full content using browser cookies
-        let content = match self.content_fetcher.fetch_full_content(&article_url).await {
-            Ok(Some(full_content)) => {
-                tracing::info!("Fetched full content for: {}", article_url);
-                full_content
-            }
-            Ok(None) => {
-                tracing::debug!("No full content available, using RSS content");
-                rss_content
-            }
-            Err(e) => {
-                tracing::debug!("Failed to fetch full content: {}, using RSS", e);
-                rss_content
-            }
+    /// Hide the selected article until `until` (local time)
+    async fn snooze_selected(&mut self, until: chrono::NaiveDateTime) -> Result<()> {
+        let Some(id) = self.selected_article().map(|a| a.id) else {
+            return Ok(());
         };
+        let Some(at) = chrono::TimeZone::from_local_datetime(&chrono::Local, &until).earliest() else {
+            return Ok(());
+        };
+        self.repository.snooze_article(id, at.with_timezone(&chrono::Utc)).await?;
+        self.articles.retain(|a| a.id != id);
+        self.total_articles = self.total_articles.saturating_sub(1);
+        let len = self.filtered_articles().len();
+        if len > 0 && self.selected_index >= len {
+            self.selected_index = len - 1;
+        }
+        self.summary_status = SummaryStatus::NotGenerated;
+        self.current_summary = None;
+        let status = format!("Snoozed until {}", format_snooze(until, chrono::Local::now().naive_local()));
+        self.bookmark_status = Some((status, Instant::now()));
+        Ok(())
+    }
 
-        // Spawn background task for summary generation
-        let summarizer = Arc::clone(summarizer);
-        let tx = self.summary_tx.clone();
+    /// Embargo the selected article until `until` (local time), or lift
+    /// its embargo
+    async fn embargo_selected(&mut self, until: Option<chrono::NaiveDateTime>) -> Result<()> {
+        let Some(id) = self.selected_article().map(|a| a.id) else {
+            return Ok(());
+        };
+        let at = match until {
+            Some(until) => match chrono::TimeZone::from_local_datetime(&chrono::Local, &until).earliest() {
+                Some(at) => Some(at.with_timezone(&chrono::Utc)),
+                None => return Ok(()),
+            },
+            None => None,
+        };
+        self.repository.set_embargo(id, at).await?;
 
-        tokio::spawn(async move {
-            let result = match summarizer.generate_summary(&title, &content).await {
-                Ok(summary_text) => {
-                    let model = summarizer.model_version().to_string();
-                    Ok((summary_text, model))
-                }
-                Err(e) => Err(e.to_string()),
-            };
+        // Gone from the list unless it's an `is:embargoed` search
+        self.reload_articles().await?;
+        let len = self.filtered_articles().len();
+        if len > 0 && self.selected_index >= len {
+            self.selected_index = len - 1;
+        }
+        self.summary_status = SummaryStatus::NotGenerated;
+        self.current_summary = None;
+        let status = match until {
+            Some(until) => format!("Embargoed until {}", format_snooze(until, chrono::Local::now().naive_local())),
+            None => "Embargo lifted".to_string(),
+        };
+        self.bookmark_status = Some((status, Instant::now()));
+        Ok(())
+    }
 
-            let _ = tx.send(SummaryResult { article_id, result }).await;
-        });
+    /// Bring back snoozed articles that are due, and show articles whose
+    /// embargo lifted, about once a minute
+    /// Bring the subscriptions in line with the urls file, if it changed
+    /// since the last sync: subscribe to new URLs, update tags and names,
+    /// archive feeds it no longer lists. Returns (added, updated, archived),
+    /// or `None` without a urls file or a change to it.
+    pub async fn sync_urls_file(&mut self) -> Result<Option<(usize, usize, usize)>> {
+        let Some(path) = self.urls_file.clone() else {
+            return Ok(None);
+        };
+        let modified = tokio::fs::metadata(&path)
+            .await
+            .and_then(|m| m.modified())
+            .map_err(|e| AppError::Config(format!("Can't read urls file {}: {}", path.display(), e)))?;
+        if self.urls_synced_modified == Some(modified) {
+            return Ok(None);
+        }
+        let entries = parse_urls(&tokio::fs::read_to_string(&path).await?);
 
-        // Don't update local is_read state - keep article visible in filtered list
-        // Database is already updated, so it will show as read next session
+        let new = entries
+            .iter()
+            .filter(|e| find_by_url(&self.feeds, &e.url).is_none())
+            .map(|e| e.url.clone())
+            .collect();
+        let ids = self.subscribe_listed(new).await?;
+        let added: Vec<i64> = ids.values().copied().collect();
+
+        let listed = entries
+            .iter()
+            .filter_map(|entry| {
+                let id = find_by_url(&self.feeds, &entry.url).map(|f| f.id).or_else(|| ids.get(&entry.url).copied())?;
+                Some(ListedFeed {
+                    id,
+                    tags: entry.tags.clone(),
+                    title: entry.title.clone(),
+                })
+            })
+            .collect();
+        let (changed, archived) = self.repository.sync_listed_feeds(listed).await?;
+        let updated = changed.iter().filter(|id| !added.contains(id)).count();
+        self.urls_synced_modified = Some(modified);
 
-        Ok(())
+        self.reload_feeds().await?;
+        if !added.is_empty() {
+            self.refresh_feeds().await?;
+        }
+        Ok(Some((added.len(), updated, archived)))
     }
 
-    /// Advance the spinner animation frame
-    pub fn tick_spinner(&mut self) {
-        self.spinner_frame = (self.spinner_frame + 1) % 10;
+    /// How the subscriptions differ from `[[subscriptions]]`, or `None`
+    /// when the config doesn't declare any
+    pub fn subscription_drift(&self) -> Option<Reconciliation> {
+        (!self.subscriptions.is_empty()).then(|| reconcile(&self.subscriptions, &self.feeds))
     }
 
-    /// Get the current spinner character
-    pub fn spinner_char(&self) -> char {
-        const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
-        SPINNER[self.spinner_frame]
-    }
+    /// Bring the subscriptions in line with `[[subscriptions]]`: subscribe
+    /// to missing feeds and rename, file and tag the rest as declared.
+    /// Feeds the config doesn't list are left alone and returned as extras
+    /// (with the added feeds as missing and the fixes as changes).
+    pub async fn reconcile_subscriptions(&mut self) -> Result<Option<Reconciliation>> {
+        let Some(drift) = self.subscription_drift() else {
+            return Ok(None);
+        };
+        if drift.is_empty() {
+            return Ok(Some(drift));
+        }
+        self.subscribe_listed(drift.missing.iter().map(|s| s.url.clone()).collect()).await?;
+        self.reload_feeds().await?;
 
-    /// Clear bookmark status after 2 seconds
-    pub fn check_bookmark_status_timeout(&mut self) {
-        if let Some((_, timestamp)) = &self.bookmark_status {
-            if timestamp.elapsed().as_secs() >= 2 {
-                self.bookmark_status = None;
+        let mut applied = self.subscription_drift().unwrap_or_default();
+        for change in &applied.changes {
+            if let Some(title) = &change.title {
+                self.repository.set_feed_custom_title(change.feed_id, title.clone()).await?;
             }
+            if change.update != FeedBulkUpdate::default() {
+                self.repository.bulk_update_feeds(vec![change.feed_id], change.update.clone()).await?;
+            }
+        }
+        self.reload_feeds().await?;
+        applied.missing = drift.missing;
+        if !applied.extras.is_empty() {
+            tracing::warn!("{} feeds aren't in [[subscriptions]]", applied.extras.len());
+            self.bookmark_status = Some((
+                format!(
+                    "{} feeds aren't in [[subscriptions]]; `beatcheck subscriptions prune` archives them",
+                    applied.extras.len()
+                ),
+                Instant::now(),
+            ));
         }
+        Ok(Some(applied))
     }
 
-    /// Poll for completed summary results (non-blocking)
-    pub async fn poll_summary_result(&mut self) -> Result<()> {
-        if let Ok(result) = self.summary_rx.try_recv() {
-            // Only process if this is the summary we're waiting for
-            if self.pending_summary_article_id == Some(result.article_id) {
-                // Check if the article still exists (might have been deleted)
-                let article_exists = self.articles.iter().any(|a| a.id == result.article_id);
-
-                match result.result {
-                    Ok((summary_text, model)) => {
-                        if article_exists {
-                            // Save to database only if article still exists
-                            if let Err(e) = self
-                                .repository
-                                .save_summary(result.article_id, summary_text.clone(), model.clone())
-                                .await
-                            {
-                                tracing::warn!("Failed to save summary (article may have been deleted): {}", e);
-                            }
-
-                            self.current_summary = Some(Summary {
-                                id: 0,
-                                article_id: result.article_id,
-                                content: summary_text,
-                                model_version: model,
-                                generated_at: chrono::Utc::now(),
-                            });
-                            self.summary_status = SummaryStatus::Generated;
-                        } else {
-                            tracing::debug!("Discarding summary for deleted article {}", result.article_id);
-                            self.summary_status = SummaryStatus::NotGenerated;
-                        }
-                    }
+    /// Subscribe to feeds listed in a file or the config, fetching their
+    /// titles up front as when one is added by hand. Returns each URL's
+    /// feed id.
+    async fn subscribe_listed(&mut self, urls: Vec<String>) -> Result<HashMap<String, i64>> {
+        use futures::stream::{self, StreamExt};
+        let fetcher = &self.fetcher;
+        let fetched: Vec<_> = stream::iter(urls)
+            .map(|url| async move {
+                let meta = match fetcher.fetch_metadata(&url).await {
+                    Ok(meta) => NewFeed { url: url.clone(), ..meta },
                     Err(e) => {
-                        tracing::error!("Failed to generate summary: {}", e);
-                        self.summary_status = SummaryStatus::Failed;
+                        tracing::warn!("Couldn't fetch {}: {}", url, e);
+                        NewFeed {
+                            title: url.clone(),
+                            url: url.clone(),
+                            site_url: None,
+                            description: None,
+                        }
                     }
-                }
-                self.pending_summary_article_id = None;
-            }
+                };
+                (url, meta)
+            })
+            .buffer_unordered(5)
+            .collect()
+            .await;
+        let mut ids = HashMap::new();
+        for (url, meta) in fetched {
+            ids.insert(url, self.repository.insert_feed(meta).await?);
         }
-        Ok(())
+        Ok(ids)
     }
 
-    /// Start discovering a feed from a URL (non-blocking)
-    fn start_feed_discovery(&mut self) {
-        let url = self.feed_input.trim().to_string();
-        if url.is_empty() {
-            self.feed_input_active = false;
-            return;
+    /// Sync the urls file every few seconds, saying what changed
+    pub async fn poll_urls_file(&mut self) -> Result<()> {
+        if self.urls_checked_at.is_some_and(|at| at.elapsed().as_secs() < URLS_FILE_CHECK_SECS) {
+            return Ok(());
         }
+        self.urls_checked_at = Some(Instant::now());
+        let status = match self.sync_urls_file().await {
+            Ok(Some((0, 0, 0))) | Ok(None) => {
+                self.urls_error = None;
+                return Ok(());
+            }
+            Ok(Some((added, updated, archived))) => {
+                self.urls_error = None;
+                self.reload_articles().await?;
+                format!("urls file: {} added, {} updated, {} archived", added, updated, archived)
+            }
+            // Said once, not every few seconds
+            Err(e) if self.urls_error.as_deref() == Some(&e.to_string()) => return Ok(()),
+            Err(e) => {
+                tracing::warn!("Failed to sync the urls file: {}", e);
+                self.urls_error = Some(e.to_string());
+                format!("Failed to sync the urls file: {}", e)
+            }
+        };
+        self.bookmark_status = Some((status, Instant::now()));
+        Ok(())
+    }
 
-        // Normalize URL - add https:// if no protocol specified
-        let url = if !url.starts_with("http://") && !url.starts_with("https://") {
-            format!("https://{}", url)
-        } else {
-            url
+    pub async fn poll_snoozed(&mut self) -> Result<()> {
+        if self.snooze_checked_at.is_some_and(|at| at.elapsed().as_secs() < SNOOZE_CHECK_SECS) {
+            return Ok(());
+        }
+        self.snooze_checked_at = Some(Instant::now());
+        let now = chrono::Utc::now();
+        let woken = self.repository.wake_snoozed(now).await?;
+        let lifted = self.repository.count_embargoes_lifted(self.embargo_checked_to, now).await?;
+        self.embargo_checked_to = now;
+        if woken == 0 && lifted == 0 {
+            return Ok(());
+        }
+        // They sort on top now; stay on the same article
+        let id = self.selected_article().map(|a| a.id);
+        self.reload_articles().await?;
+        if let Some(index) = self.filtered_articles().iter().position(|a| Some(a.id) == id) {
+            self.selected_index = index;
+        }
+        self.feeds = self.repository.get_all_feeds().await?;
+        let status = match (woken, lifted) {
+            (0, 1) => "An embargo lifted".to_string(),
+            (0, n) => format!("{} embargoes lifted", n),
+            (1, _) => "A snoozed article is back".to_string(),
+            (n, _) => format!("{} snoozed articles are back", n),
         };
+        self.bookmark_status = Some((status, Instant::now()));
+        Ok(())
+    }
 
-        self.feed_input_status = Some("Discovering feed...".to_string());
+    /// How the user reads the feed's articles
+    pub fn reading_mode(&self, feed_id: i64) -> ReadingMode {
+        self.feeds.iter().find(|f| f.id == feed_id).map(|f| f.reading).unwrap_or_default()
+    }
 
-        let fetcher = self.fetcher.clone();
-        let tx = self.discovery_tx.clone();
+    /// Where to fetch or open the article: through its feed's reader view,
+    /// if it has one
+    pub fn reader_view(&self, article: &Article) -> String {
+        match self.feeds.iter().find(|f| f.id == article.feed_id) {
+            Some(feed) => feed.reader_view(&article.url),
+            None => article.url.clone(),
+        }
+    }
 
-        tokio::spawn(async move {
-            let result = match fetcher.discover_feed(&url).await {
-                Ok(feed) => Ok(feed),
-                Err(e) => Err(e.to_string()),
+    pub fn feed_priority(&self, feed_id: i64) -> FeedPriority {
+        self.feeds.iter().find(|f| f.id == feed_id).map(|f| f.priority).unwrap_or_default()
+    }
+
+    /// Run the macro's steps on the selected article. Steps only change what
+    /// needs changing, and after `next` they apply to the next article.
+    async fn run_macro(&mut self, index: usize) -> Result<bool> {
+        let Some(Macro { name, steps, .. }) = self.macros.get(index).cloned() else {
+            return Ok(false);
+        };
+        if self.selected_article().is_none() {
+            self.bookmark_status = Some((format!("No article for {}", name), Instant::now()));
+            return Ok(false);
+        }
+        let status_before = self.bookmark_status.as_ref().map(|(_, at)| *at);
+        for step in steps {
+            let Some(article) = self.selected_article() else {
+                break;
             };
-            let _ = tx.send(FeedDiscoveryResult { result }).await;
-        });
+            let action = match step {
+                MacroStep::Star(starred) => (article.is_starred != starred).then_some(AppAction::ToggleStar),
+                MacroStep::Read(read) => (article.is_read != read).then_some(AppAction::ToggleRead),
+                MacroStep::Queue(queued) => (article.is_queued != queued).then_some(AppAction::ToggleQueue),
+                MacroStep::Tag(tag) => {
+                    self.run_palette_command(PaletteCommand::TagAdd(tag)).await?;
+                    None
+                }
+                MacroStep::Untag(tag) => {
+                    self.run_palette_command(PaletteCommand::TagRemove(tag)).await?;
+                    None
+                }
+                MacroStep::Collection(collection) => {
+                    self.run_palette_command(PaletteCommand::CollectionAdd(collection)).await?;
+                    None
+                }
+                MacroStep::Raindrop(tags) => {
+                    self.tag_input = tags;
+                    self.save_to_raindrop().await?;
+                    self.tag_input.clear();
+                    None
+                }
+                MacroStep::Summarize => {
+                    self.generate_summary().await?;
+                    None
+                }
+                MacroStep::SaveNote => Some(AppAction::SaveNote),
+                MacroStep::ExportPdf => Some(AppAction::ExportPdf),
+                MacroStep::CopyBrief => Some(AppAction::CopyBrief),
+                MacroStep::Open => Some(AppAction::OpenInBrowser),
+                MacroStep::Delete => Some(AppAction::DeleteArticle),
+                MacroStep::Next => Some(AppAction::MoveDown),
+            };
+            if let Some(action) = action {
+                if Box::pin(self.handle_action(action)).await? {
+                    return Ok(true);
+                }
+            }
+        }
+        // A step's own message (a failed bookmark, say) says more
+        if self.bookmark_status.as_ref().map(|(_, at)| *at) == status_before {
+            self.bookmark_status = Some((format!("Ran {}", name), Instant::now()));
+        }
+        Ok(false)
     }
 
-    /// Poll for completed feed discovery results (non-blocking)
-    pub async fn poll_discovery_result(&mut self) -> Result<()> {
-        if let Ok(result) = self.discovery_rx.try_recv() {
-            match result.result {
-                Ok(new_feed) => {
-                    // Check if feed already exists
-                    if self.feeds.iter().any(|f| f.url == new_feed.url) {
-                        self.feed_input_status = Some(format!("Feed already exists: {}", new_feed.title));
-                        return Ok(());
+    /// Run a command typed into the palette with its arguments
+    async fn run_palette_command(&mut self, command: PaletteCommand) -> Result<bool> {
+        let status = match command {
+            PaletteCommand::TagAdd(tag) | PaletteCommand::TagRemove(tag) if self.selected_article().is_none() => {
+                format!("No article to tag #{}", tag)
+            }
+            PaletteCommand::TagAdd(tag) => {
+                let id = self.selected_article().map(|a| a.id).unwrap_or_default();
+                self.repository.update_articles(vec![id], ArticleBatch::Tag(tag.clone())).await?;
+                if let Some(article) = self.articles.iter_mut().find(|a| a.id == id) {
+                    if !article.tags.contains(&tag) {
+                        article.tags.push(tag.clone());
                     }
-
-                    let feed_title = new_feed.title.clone();
-                    match self.repository.insert_feed(new_feed).await {
-                        Ok(feed_id) => {
-                            self.feed_input_status = Some(format!("Added: {}", feed_title));
-                            tracing::info!("Added new feed: {} (id={})", feed_title, feed_id);
-
-                            // Reload feeds list
-                            self.feeds = self.repository.get_all_feeds().await?;
-
-                            // Clear input after short delay to show success message
-                            self.feed_input_active = false;
-                            self.feed_input.clear();
-
-                            // Refresh the new feed
-                            self.refresh_feeds();
+                }
+                format!("Tagged #{}", tag)
+            }
+            PaletteCommand::TagRemove(tag) => {
+                let id = self.selected_article().map(|a| a.id).unwrap_or_default();
+                if let Some(article) = self.articles.iter_mut().find(|a| a.id == id) {
+                    article.tags.retain(|t| *t != tag);
+                    self.repository.set_article_tags(id, article.tags.clone()).await?;
+                }
+                format!("Removed #{}", tag)
+            }
+            PaletteCommand::GotoFeed(name) => {
+                let best = self
+                    .feeds
+                    .iter()
+                    .filter_map(|feed| fuzzy_score(&name, feed.display_title()).map(|score| (score, feed)))
+                    .max_by_key(|(score, _)| *score)
+                    .map(|(_, feed)| (feed.id, feed.display_title().to_string()));
+                match best {
+                    Some((id, title)) => {
+                        self.feed_filter = Some(id);
+                        self.show_filtered().await?;
+                        title
+                    }
+                    None => format!("No feed matches \"{}\"", name),
+                }
+            }
+            PaletteCommand::GotoView(view) => {
+                let mode = match view.as_str() {
+                    "starred" => FilterMode::Starred,
+                    "queue" => FilterMode::Queue,
+                    "discussed" => FilterMode::Discussed,
+                    "history" => FilterMode::History,
+                    _ => FilterMode::All,
+                };
+                self.show_view(mode).await?;
+                mode.label().to_string()
+            }
+            PaletteCommand::CollectionAdd(name) => {
+                // The articles picked in select mode, else the selected one
+                let mut ids = if self.select_mode { self.batch_ids() } else { Vec::new() };
+                if ids.is_empty() {
+                    ids.extend(self.selected_article().map(|a| a.id));
+                }
+                if ids.is_empty() {
+                    format!("No article to add to {}", name)
+                } else {
+                    let added = self.repository.add_to_collection(&name, ids).await?;
+                    self.end_select_mode();
+                    match added {
+                        0 => format!("Already in {}", name),
+                        1 => format!("Added to {}", name),
+                        n => format!("Added {} articles to {}", n, name),
+                    }
+                }
+            }
+            PaletteCommand::CollectionRemove(name) => {
+                let id = self.selected_article().map(|a| a.id);
+                match id {
+                    Some(id) if self.repository.remove_from_collection(&name, vec![id]).await? > 0 => {
+                        if self.filter_mode == FilterMode::Collection {
+                            self.reload_articles().await?;
+                            self.on_selection_changed().await?;
                         }
-                        Err(e) => {
-                            self.feed_input_status = Some(format!("Error: {}", e));
-                            tracing::error!("Failed to insert feed: {}", e);
+                        format!("Removed from {}", name)
+                    }
+                    _ => format!("Not in {}", name),
+                }
+            }
+            PaletteCommand::CollectionMove(position) => {
+                let name = self.collection.clone().filter(|_| self.filter_mode == FilterMode::Collection);
+                match (name, self.selected_article().map(|a| a.id)) {
+                    (Some(name), Some(id)) => {
+                        self.repository.move_in_collection(&name, id, position).await?;
+                        self.reload_articles().await?;
+                        if let Some(index) = self.articles.iter().position(|a| a.id == id) {
+                            self.selected_index = index;
                         }
+                        format!("Moved to {} in {}", self.selected_index + 1, name)
                     }
+                    _ => "Open a collection first: goto collection NAME".to_string(),
                 }
-                Err(_) => {
-                    self.feed_input_status = Some("No feed here.".to_string());
+            }
+            PaletteCommand::CollectionExport(name, format) => {
+                let format = PacketFormat::parse(format.as_deref().unwrap_or("md"))?;
+                match self.export_collection(&name, format).await {
+                    Ok(path) => format!("Exported to {}", path.display()),
+                    Err(e) => format!("Export failed: {}", e),
                 }
             }
-        }
-        Ok(())
+            PaletteCommand::GotoCollection(name) => {
+                let best = self
+                    .repository
+                    .get_collections()
+                    .await?
+                    .into_iter()
+                    .filter_map(|c| fuzzy_score(&name, &c.name).map(|score| (score, c.name)))
+                    .max_by_key(|(score, _)| *score)
+                    .map(|(_, name)| name);
+                match best {
+                    Some(name) => {
+                        self.collection = Some(name.clone());
+                        self.show_view(FilterMode::Collection).await?;
+                        name
+                    }
+                    None => format!("No collection matches \"{}\"", name),
+                }
+            }
+            PaletteCommand::Block(BlockTarget::Entry(entry)) => self.block_entry(&entry)?,
+            PaletteCommand::Block(BlockTarget::ArticleSite) => {
+                let site = self
+                    .selected_article()
+                    .and_then(|a| Blocklist::normalize_entry(&format!("site:{}", a.url)));
+                match site {
+                    Some(site) => self.block_entry(&site)?,
+                    None => "No site to block for this article".to_string(),
+                }
+            }
+            PaletteCommand::Block(BlockTarget::ArticleAuthor) => {
+                let bylines: Vec<String> = self.selected_article().and_then(|a| a.author.clone()).into_iter().collect();
+                let authors = normalize_authors(&bylines);
+                if authors.is_empty() {
+                    "No author on this article".to_string()
+                } else {
+                    for author in &authors {
+                        self.repository.set_author_rule(author, Some(AuthorRule::Mute)).await?;
+                    }
+                    self.author_rules = self.repository.get_author_rules().await?;
+                    self.reload_articles().await?;
+                    format!("Muted {}", authors.join(", "))
+                }
+            }
+            PaletteCommand::Unblock(entry) => self.unblock_entry(&entry)?,
+            PaletteCommand::Search(text) => {
+                // Through the search prompt, so a bad query stays open to fix
+                self.search_active = true;
+                self.search_input = text;
+                return Box::pin(self.handle_action(AppAction::SearchConfirm)).await;
+            }
+        };
+        self.bookmark_status = Some((status, Instant::now()));
+        Ok(false)
     }
 
-    pub fn refresh_feeds(&mut self) {
-        if self.is_refreshing {
-            return; // Already refreshing
-        }
-        self.is_refreshing = true;
-        self.blocklist.reload();
+    /// Add a keyword or `site:` entry to the blocklist, returning what to
+    /// say about it
+    fn block_entry(&mut self, entry: &str) -> Result<String> {
+        let status = match self.blocklist.add(entry) {
+            Ok(Some(entry)) => format!("Blocked {} in new articles", entry),
+            Ok(None) => format!("Already blocked: {}", entry.trim()),
+            Err(AppError::Config(message)) => message,
+            Err(e) => return Err(e),
+        };
+        // Said here, so not again by poll_blocklist
+        self.blocklist_generation = self.blocklist.generation();
+        Ok(status)
+    }
 
-        let feeds = self.feeds.clone();
-        let fetcher = self.fetcher.clone();
-        let tx = self.refresh_tx.clone();
+    fn unblock_entry(&mut self, entry: &str) -> Result<String> {
+        let status = match self.blocklist.remove(entry) {
+            Ok(true) => format!("Unblocked {}", entry.trim()),
+            Ok(false) => format!("Not in the blocklist: {}", entry.trim()),
+            Err(AppError::Config(message)) => message,
+            Err(e) => return Err(e),
+        };
+        self.blocklist_generation = self.blocklist.generation();
+        Ok(status)
+    }
 
-        tokio::spawn(async move {
-            let results = fetcher.refresh_all(feeds).await;
-            let _ = tx.send(RefreshResult { results }).await;
-        });
+    /// Show the blocklist popup's rules after an edit, with `selected`
+    /// highlighted if given
+    fn blocklist_edited(&mut self, selected: Option<&str>, status: String) {
+        self.blocklist_generation = self.blocklist.generation();
+        self.blocklist_entries = self.blocklist.entries();
+        self.blocklist_subscriptions = self.blocklist.subscriptions();
+        let index = selected.and_then(|entry| self.blocklist_entries.iter().position(|e| e == entry));
+        self.blocklist_index = index
+            .unwrap_or(self.blocklist_index)
+            .min(self.blocklist_entries.len().saturating_sub(1));
+        self.bookmark_status = Some((status, Instant::now()));
     }
 
-    /// Poll for completed refresh results (non-blocking)
-    pub async fn poll_refresh_result(&mut self) -> Result<()> {
-        if let Ok(result) = self.refresh_rx.try_recv() {
-            // Process the refresh results
-            for (feed_id, articles) in result.results {
-                for article in articles {
-                    // Filter: skip articles containing blocked keywords
-                    let content_ref = article.content_text.as_deref()
-                        .or(article.content.as_deref());
-                    if self.blocklist.contains_blocked_keyword(&article.title, content_ref) {
-                        continue; // Silent skip - FILTER-06
-                    }
+    async fn save_session(&self) -> Result<()> {
+        let session = Session {
+            filter: self.filter_mode,
+            feed_id: self.feed_filter,
+            group: self.group_filter.clone(),
+            today: self.today_only,
+            collapsed: self.collapsed_groups.clone(),
+            tag: self.tag_filter.clone(),
+            search: self.search.as_ref().map(|(text, _)| text.clone()),
+            article_id: self.selected_article().map(|a| a.id),
+            reading: self.reading,
+            find: self.find.as_ref().map(|find| find.term.clone()),
+            collection: self.collection.clone(),
+        };
+        self.repository.set_setting(SESSION_SETTING, serde_json::to_string(&session)?).await
+    }
 
-                    if let Err(e) = self.repository.upsert_article(article).await {
-                        tracing::warn!("Failed to upsert article: {}", e);
-                    }
-                }
-                if let Err(e) = self.repository.update_feed_last_fetched(feed_id).await {
-                    tracing::warn!("Failed to update feed last_fetched: {}", e);
-                }
+    /// Reopen the view saved at the last quit. Anything that no longer
+    /// applies (an unsubscribed feed, an expired article) is left out.
+    pub async fn restore_session(&mut self) -> Result<()> {
+        let Some(saved) = self.repository.get_setting(SESSION_SETTING).await? else {
+            return Ok(());
+        };
+        let session: Session = serde_json::from_str(&saved).unwrap_or_default();
+
+        self.feed_filter = session.feed_id.filter(|id| self.feeds.iter().any(|f| f.id == *id));
+        let grouping = self.panes.grouping;
+        self.group_filter = session.group.filter(|g| self.feeds.iter().any(|f| grouping.groups(f).contains(g)));
+        self.today_only = session.today;
+        self.collapsed_groups = session.collapsed;
+        self.tag_filter = session.tag;
+        self.search = session.search.and_then(|text| Query::parse(&text).ok().map(|query| (text, query)));
+        self.filter_mode = session.filter;
+        if self.filter_mode == FilterMode::Collection {
+            let collections = self.repository.get_collections().await?;
+            self.collection = session
+                .collection
+                .and_then(|name| collections.into_iter().find(|c| c.name.eq_ignore_ascii_case(&name)))
+                .map(|c| c.name);
+            if self.collection.is_none() {
+                self.filter_mode = FilterMode::All;
+            }
+        }
+        self.articles.clear();
+        self.selected_index = 0;
+        self.reload_articles().await?;
+
+        if let Some(id) = session.article_id {
+            let mut pages = 0;
+            while !self.articles.iter().any(|a| a.id == id) && self.has_more_articles && pages < SESSION_RESTORE_PAGES {
+                self.load_more_articles().await?;
+                pages += 1;
+            }
+            if let Some(index) = self.filtered_articles().iter().position(|a| a.id == id) {
+                self.selected_index = index;
+                self.reading = session.reading && self.panes.layout == PaneLayout::ListOnly;
+                self.find = session.find.as_deref().and_then(ArticleFind::new);
             }
+        }
+        self.on_selection_changed().await
+    }
+
+    async fn save_panes(&self) -> Result<()> {
+        self.repository.set_setting(PANES_SETTING, serde_json::to_string(&self.panes)?).await
+    }
 
-            // Clean up articles older than 7 days after refresh
-            let deleted = self.repository.delete_old_articles(7).await?;
-            if deleted > 0 {
-                tracing::info!("Deleted {} articles older than 7 days", deleted);
+    /// Restrictions for the paged All view
+    fn article_filter(&self) -> ArticleFilter {
+        let grouping = self.panes.grouping;
+        let feed_ids = self.group_filter.as_ref().map(|group| {
+            self.feeds.iter().filter(|f| grouping.groups(f).contains(group)).map(|f| f.id).collect()
+        });
+        let midnight = chrono::Local::now()
+            .date_naive()
+            .and_hms_opt(0, 0, 0)
+            .and_then(|t| t.and_local_timezone(chrono::Local).earliest())
+            .map(|t| t.with_timezone(&chrono::Utc));
+        // A day picked in the calendar, from its midnight to the next
+        let day_start = |day: chrono::NaiveDate| {
+            day.and_hms_opt(0, 0, 0)
+                .and_then(|t| t.and_local_timezone(chrono::Local).earliest())
+                .map(|t| t.with_timezone(&chrono::Utc))
+        };
+        let (since, until) = match self.day_filter {
+            Some(day) => (day_start(day), day.succ_opt().and_then(day_start)),
+            None => (midnight.filter(|_| self.today_only), None),
+        };
+        ArticleFilter {
+            feed_id: self.feed_filter,
+            feed_ids,
+            since,
+            until,
+            tag: self.tag_filter.clone(),
+            entity: self.entity_filter.clone(),
+            author: self.author_filter.clone(),
+            text: self.term_filter.clone(),
+            query: self.search.as_ref().map(|(_, query)| query.clone()),
+            ..Default::default()
+        }
+    }
+
+    async fn reload_articles(&mut self) -> Result<()> {
+        match self.filter_mode {
+            FilterMode::All => {
+                // Keep at least as many rows loaded as before so the selection stays put
+                let limit = self.articles.len().max(ARTICLE_PAGE_SIZE);
+                self.articles = self.list_filtered(limit, None).await?;
+                self.has_more_articles = self.articles.len() == limit;
+                self.total_articles = self.repository.count_articles().await?;
+            }
+            FilterMode::Starred => {
+                // The starred list is hand-curated and small, so it loads in one go
+                self.articles = self.repository.get_starred_articles().await?;
+                self.has_more_articles = false;
+                self.total_articles = self.articles.len();
             }
+            FilterMode::Queue => {
+                self.articles = self.repository.get_queued_articles().await?;
+                self.has_more_articles = false;
+                self.total_articles = self.articles.len();
+            }
+            FilterMode::Discussed => {
+                self.articles = self.repository.get_discussed_articles(ARTICLE_PAGE_SIZE).await?;
+                self.has_more_articles = false;
+                self.total_articles = self.articles.len();
+            }
+            FilterMode::History => {
+                self.articles = self.repository.get_history_articles().await?;
+                self.has_more_articles = false;
+                self.total_articles = self.articles.len();
+            }
+            FilterMode::Collection => {
+                let name = self.collection.clone().unwrap_or_default();
+                self.articles = self.repository.get_collection_articles(&name).await?;
+                self.has_more_articles = false;
+                self.total_articles = self.articles.len();
+            }
+        }
+        self.queue_minutes = self.repository.queue_stats().await?.1;
 
-            self.reload_articles().await?;
-            self.is_refreshing = false;
+        let len = self.filtered_articles().len();
+        if len > 0 && self.selected_index >= len {
+            self.selected_index = len - 1;
         }
         Ok(())
     }
 
-    /// Refresh feeds and wait for completion (blocking, for CLI/headless use)
-    pub async fn refresh_feeds_blocking(&mut self) -> Result<()> {
-        self.refresh_feeds();
+    /// Reflect a star/pin change in the loaded list. The starred view is
+    /// re-queried because pins reorder it; the main list is updated in place.
+    async fn update_star_state(&mut self, id: i64, starred: bool, pinned: bool) -> Result<()> {
+        if self.filter_mode == FilterMode::Starred {
+            self.reload_articles().await?;
+            if let Some(pos) = self.articles.iter().position(|a| a.id == id) {
+                self.selected_index = pos;
+            }
+            self.on_selection_changed().await?;
+        } else if let Some(article) = self.articles.iter_mut().find(|a| a.id == id) {
+            article.is_starred = starred;
+            article.is_pinned = pinned;
+        }
+        Ok(())
+    }
 
-        // Wait for the refresh to complete
-        while self.is_refreshing {
-            self.poll_refresh_result().await?;
-            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    /// Append the next page of articles to the loaded list (no-op once exhausted)
+    async fn load_more_articles(&mut self) -> Result<()> {
+        if !self.has_more_articles {
+            return Ok(());
         }
 
+        let after = self.articles.last().map(|a| a.id);
+        let page = self.list_filtered(ARTICLE_PAGE_SIZE, after).await?;
+        self.has_more_articles = page.len() == ARTICLE_PAGE_SIZE;
+        self.articles.extend(page);
         Ok(())
     }
 
-    async fn reload_articles(&mut self) -> Result<()> {
-        self.articles = self.repository.get_all_articles_sorted().await?;
-        Ok(())
+    /// A page of the All view. A search that runs out of time (a slow regex)
+    /// is dropped, with the reason left in `search_error`, rather than
+    /// failing every reload.
+    async fn list_filtered(&mut self, limit: usize, after: Option<i64>) -> Result<Vec<Article>> {
+        match self.repository.list_articles(&self.article_filter(), limit, after).await {
+            Err(AppError::Config(message)) if self.search.is_some() => {
+                tracing::warn!("Search dropped: {}", message);
+                self.search = None;
+                self.bookmark_status = Some((message.clone(), Instant::now()));
+                self.search_error = Some(message);
+                self.repository.list_articles(&self.article_filter(), limit, after).await
+            }
+            result => result,
+        }
     }
 
     async fn save_to_raindrop(&mut self) -> Result<()> {
@@ -709,7 +4942,7 @@ impl App {
             return Ok(());
         };
 
-        let Some(article) = self.selected_article() else {
+        let Some(article) = self.selected_article().cloned() else {
             return Ok(());
         };
 
@@ -724,22 +4957,7 @@ impl App {
         let url = article.url.clone();
         let title = article.title.clone();
 
-        // Get excerpt: first sentence of summary (cleaned), or first sentence of article content
-        let excerpt = self
-            .current_summary
-            .as_ref()
-            .map(|s| Self::clean_summary_for_excerpt(&s.content))
-            .filter(|s| !s.is_empty())
-            .or_else(|| {
-                article
-                    .content_text
-                    .as_ref()
-                    .or(article.content.as_ref())
-                    .map(|c| Self::get_first_sentence(c))
-            });
-
-        // Get AI summary for note field (if available)
-        let note = self.current_summary.as_ref().map(|s| s.content.clone());
+        let (excerpt, note) = self.raindrop_fields(&article).await?;
 
         match raindrop
             .save_bookmark(&url, Some(&title), excerpt.as_deref(), note.as_deref(), tags.clone())
@@ -770,13 +4988,57 @@ impl App {
         Ok(())
     }
 
+    /// Bookmark every selected article, with the tags typed at the prompt
+    async fn save_selection_to_raindrop(&mut self) -> Result<()> {
+        let Some(raindrop) = &self.raindrop else {
+            return Ok(());
+        };
+        let tags: Vec<String> = self
+            .tag_input
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        let ids = self.batch_ids();
+        let articles: Vec<Article> = self.articles.iter().filter(|a| ids.contains(&a.id)).cloned().collect();
+        let (mut saved, mut failed) = (0, 0);
+        for article in &articles {
+            let (excerpt, note) = self.raindrop_fields(article).await?;
+            match raindrop
+                .save_bookmark(&article.url, Some(&article.title), excerpt.as_deref(), note.as_deref(), tags.clone())
+                .await
+            {
+                Ok(raindrop_id) => {
+                    self.repository.mark_saved_to_raindrop(article.id, raindrop_id, tags.clone()).await?;
+                    tracing::info!("Saved to Raindrop: {}", article.url);
+                    saved += 1;
+                }
+                Err(e) => {
+                    tracing::error!("Failed to save to Raindrop: {}", e);
+                    failed += 1;
+                }
+            }
+        }
+
+        self.saved_count += saved;
+        self.is_saved_to_raindrop |= self.selected_article().is_some_and(|a| ids.contains(&a.id)) && saved > 0;
+        self.end_select_mode();
+        let status = match failed {
+            0 => format!("Bookmarked {} articles", saved),
+            _ => format!("Bookmarked {} articles, {} failed", saved, failed),
+        };
+        self.bookmark_status = Some((status, Instant::now()));
+        Ok(())
+    }
+
     /// Save to Raindrop with a preset tag (no user input needed)
     async fn save_to_raindrop_with_tag(&mut self, tag: &str) -> Result<()> {
         let Some(raindrop) = &self.raindrop else {
             return Ok(());
         };
 
-        let Some(article) = self.selected_article() else {
+        let Some(article) = self.selected_article().cloned() else {
             return Ok(());
         };
 
@@ -786,22 +5048,7 @@ impl App {
         let url = article.url.clone();
         let title = article.title.clone();
 
-        // Get excerpt: first sentence of summary (cleaned), or first sentence of article content
-        let excerpt = self
-            .current_summary
-            .as_ref()
-            .map(|s| Self::clean_summary_for_excerpt(&s.content))
-            .filter(|s| !s.is_empty())
-            .or_else(|| {
-                article
-                    .content_text
-                    .as_ref()
-                    .or(article.content.as_ref())
-                    .map(|c| Self::get_first_sentence(c))
-            });
-
-        // Get AI summary for note field (if available)
-        let note = self.current_summary.as_ref().map(|s| s.content.clone());
+        let (excerpt, note) = self.raindrop_fields(&article).await?;
 
         match raindrop
             .save_bookmark(&url, Some(&title), excerpt.as_deref(), note.as_deref(), tags.clone())
@@ -825,6 +5072,30 @@ impl App {
         Ok(())
     }
 
+    /// Raindrop's excerpt and note: the summary's first point and the whole
+    /// summary with the tags, unless `[summaries.embed] raindrop` is off.
+    /// The excerpt falls back to the article's first sentence.
+    async fn raindrop_fields(&self, article: &Article) -> Result<(Option<String>, Option<String>)> {
+        let embed = load_embed(&self.repository, article, self.embed.raindrop).await?;
+        let excerpt = embed
+            .summary
+            .as_ref()
+            .map(|s| Self::clean_summary_for_excerpt(&s.content))
+            .filter(|s| !s.is_empty())
+            .or_else(|| {
+                article
+                    .content_text
+                    .as_ref()
+                    .or(article.content.as_ref())
+                    .map(|c| Self::get_first_sentence(c))
+            });
+        let note = embed.summary.map(|summary| match embed.tags.is_empty() {
+            true => summary.content,
+            false => format!("{}\n\nTags: {}", summary.content, embed.tags.join(", ")),
+        });
+        Ok((excerpt, note))
+    }
+
     /// Extract the first sentence from text (up to ~200 chars for Raindrop excerpt)
     fn get_first_sentence(text: &str) -> String {
         let text = text.trim();
@@ -888,19 +5159,394 @@ impl App {
         Self::get_first_sentence(&text)
     }
 
-    pub async fn import_opml(&mut self, path: &Path) -> Result<()> {
+    /// Import an OPML file, skipping feeds already subscribed (or listed
+    /// twice) under a variant of the same URL. Returns (added, skipped).
+    pub async fn import_opml(&mut self, path: &Path) -> Result<(usize, usize)> {
         let feeds = parse_opml_file(path)?;
 
+        let (mut added, mut skipped) = (0, 0);
         for feed in feeds {
-            // Silently ignore duplicates (UNIQUE constraint on url)
-            let _ = self.repository.insert_feed(feed).await;
+            if let Some(existing) = find_by_url(&self.feeds, &feed.url) {
+                tracing::info!("Skipping {}: already subscribed as {}", feed.url, existing.url);
+                skipped += 1;
+                continue;
+            }
+            match self.repository.insert_feed(feed).await {
+                Ok(_) => added += 1,
+                Err(e) => tracing::warn!("Failed to import feed: {}", e),
+            }
+            // Later entries are checked against this one too
+            self.feeds = self.repository.get_all_feeds().await?;
+        }
+
+        self.audit_import("opml", path, format!("{} feeds added, {} already subscribed", added, skipped)).await;
+        // Refresh the newly imported feeds
+        self.refresh_feeds().await?;
+
+        Ok((added, skipped))
+    }
+
+    /// Bring in a read-later backlog from a Pocket export or browser
+    /// bookmarks file. Pocket's unread links are queued, archived ones
+    /// starred and read, bookmarks starred. Links already stored (from a
+    /// feed or an earlier import) are marked rather than duplicated; new ones
+    /// go in the Saved pseudo-feed, and their text is fetched when first
+    /// summarized or exported. Returns (new, already here).
+    pub async fn import_bookmarks(&mut self, path: &Path) -> Result<(usize, usize)> {
+        let bookmarks = parse_bookmarks_file(path)?;
+        let saved_feed = self.repository.saved_feed_id().await?;
+
+        let (mut added, mut existing) = (0, 0);
+        for bookmark in bookmarks {
+            let id = match self.repository.article_id_by_url(&bookmark.url).await? {
+                Some(id) => {
+                    existing += 1;
+                    id
+                }
+                None => {
+                    let article = NewArticle {
+                        feed_id: saved_feed,
+                        guid: bookmark.url.clone(),
+                        title: bookmark.title,
+                        url: bookmark.url,
+                        author: None,
+                        content: None,
+                        content_text: None,
+                        published_at: bookmark.saved_at,
+                        comments_url: None,
+                        enclosure: None,
+                        extras: None,
+                        tags: bookmark.tags,
+                        entities: Vec::new(),
+                        authors: Vec::new(),
+                        links: Vec::new(),
+                    };
+                    match self.repository.upsert_article(article).await {
+                        // Deleted here before; leave it gone
+                        Ok(0) => continue,
+                        Ok(id) => {
+                            added += 1;
+                            id
+                        }
+                        Err(e) => {
+                            tracing::warn!("Failed to import bookmark: {}", e);
+                            continue;
+                        }
+                    }
+                }
+            };
+            match bookmark.shelf {
+                Shelf::ToRead => self.repository.set_queued(id, true).await?,
+                Shelf::Read => {
+                    self.repository.set_starred(id, true).await?;
+                    self.repository.set_read(id, true).await?;
+                }
+                Shelf::Bookmarked => self.repository.set_starred(id, true).await?,
+            }
         }
 
         self.feeds = self.repository.get_all_feeds().await?;
+        self.audit_import("bookmarks", path, format!("{} links added, {} already here", added, existing)).await;
+        Ok((added, existing))
+    }
 
-        // Refresh the newly imported feeds
-        self.refresh_feeds();
+    /// Bring in the starred articles from a NetNewsWire or Reeder JSON
+    /// export, starring them. Links already stored are starred where they
+    /// are; new ones go under their feed when it's subscribed here (import
+    /// the export's OPML first), otherwise in the Saved pseudo-feed.
+    /// Returns (new, already here).
+    pub async fn import_starred(&mut self, path: &Path) -> Result<(usize, usize)> {
+        let items = parse_starred_file(path)?;
+        let saved_feed = self.repository.saved_feed_id().await?;
+
+        let (mut added, mut existing) = (0, 0);
+        for item in items {
+            let id = match self.repository.article_id_by_url(&item.url).await? {
+                Some(id) => {
+                    existing += 1;
+                    id
+                }
+                None => {
+                    let feed_id = item
+                        .feed_url
+                        .as_deref()
+                        .and_then(|url| find_by_url(&self.feeds, url))
+                        .map_or(saved_feed, |feed| feed.id);
+                    let article = NewArticle {
+                        feed_id,
+                        guid: item.url.clone(),
+                        title: item.title,
+                        url: item.url,
+                        author: item.author,
+                        content: None,
+                        content_text: None,
+                        published_at: item.published_at,
+                        comments_url: None,
+                        enclosure: None,
+                        extras: None,
+                        tags: Vec::new(),
+                        entities: Vec::new(),
+                        authors: Vec::new(),
+                        links: Vec::new(),
+                    };
+                    match self.repository.upsert_article(article).await {
+                        // Deleted here before; leave it gone
+                        Ok(0) => continue,
+                        Ok(id) => {
+                            added += 1;
+                            id
+                        }
+                        Err(e) => {
+                            tracing::warn!("Failed to import starred article: {}", e);
+                            continue;
+                        }
+                    }
+                }
+            };
+            self.repository.set_starred(id, true).await?;
+        }
+
+        self.feeds = self.repository.get_all_feeds().await?;
+        self.audit_import("starred", path, format!("{} articles added, {} already here", added, existing)).await;
+        Ok((added, existing))
+    }
+
+    /// Write a travel bundle: the feeds plus the unread articles (fetched
+    /// in the last `days`, if given) and every starred one, with text and
+    /// summaries. Returns (feeds, articles) written.
+    pub async fn export_bundle(&self, path: &Path, days: Option<u32>) -> Result<(usize, usize)> {
+        let since = days.map(|d| chrono::Utc::now() - chrono::Duration::days(i64::from(d)));
+        let mut articles = Vec::new();
+        for article in self.repository.get_bundle_articles(since).await? {
+            articles.push(BundledArticle {
+                summary: self.repository.get_summary(article.id).await?,
+                article,
+            });
+        }
+        let bundle = Bundle::new(self.feeds.clone(), articles);
+        bundle.write(path)?;
+        Ok((bundle.feeds.len(), bundle.articles.len()))
+    }
+
+    /// Load a travel bundle into this database. Feeds not subscribed here
+    /// are added with their name, folder, tags and settings; articles are
+    /// stored with their full text and summaries, read and starred as they
+    /// were. Articles already here (by URL) are only starred if starred in
+    /// the bundle, and pseudo-feed articles go in Saved.
+    /// Returns (feeds added, articles added, articles already here).
+    pub async fn import_bundle(&mut self, path: &Path) -> Result<(usize, usize, usize)> {
+        let bundle = Bundle::read(path)?;
+        let (feed_ids, feeds_added) = self.add_missing_feeds(&bundle.feeds).await?;
+
+        let (mut added, mut existing) = (0, 0);
+        for BundledArticle { article, summary } in bundle.articles {
+            let Some(&feed_id) = feed_ids.get(&article.feed_id) else {
+                continue;
+            };
+            if let Some(id) = self.repository.article_id_by_url(&article.url).await? {
+                existing += 1;
+                if article.is_starred {
+                    self.repository.set_starred(id, true).await?;
+                }
+                continue;
+            }
+            let id = match self.repository.upsert_article(bundle::new_article(&article, feed_id)).await? {
+                // Deleted here before; leave it gone
+                0 => continue,
+                id => id,
+            };
+            added += 1;
+            self.repository.set_starred(id, article.is_starred).await?;
+            self.repository.set_read(id, article.is_read).await?;
+            if let Some(text) = article.full_text {
+                self.repository.save_full_text(id, text).await?;
+            }
+            if let Some(summary) = summary {
+                self.repository
+                    .save_summary(id, summary.content, summary.model_version, summary.provider, None)
+                    .await?;
+            }
+        }
+
+        self.feeds = self.repository.get_all_feeds().await?;
+        let detail = format!("{} feeds added, {} articles added, {} already here", feeds_added, added, existing);
+        self.audit_import("bundle", path, detail).await;
+        Ok((feeds_added, added, existing))
+    }
+
+    /// Subscribe to those of `feeds` (from another machine) not subscribed
+    /// here, with their name, folder, tags, settings and source notes.
+    /// Returns the ids here by id there, and how many were added.
+    async fn add_missing_feeds(&mut self, feeds: &[Feed]) -> Result<(HashMap<i64, i64>, usize)> {
+        let mut feed_ids = HashMap::new();
+        let mut added = 0;
+        for feed in feeds {
+            let id = if feed.is_local() {
+                self.repository.saved_feed_id().await?
+            } else if let Some(existing) = find_by_url(&self.feeds, &feed.url) {
+                existing.id
+            } else {
+                let new_feed = NewFeed {
+                    title: feed.title.clone(),
+                    url: feed.url.clone(),
+                    site_url: feed.site_url.clone(),
+                    description: feed.description.clone(),
+                };
+                let id = self.repository.insert_feed(new_feed).await?;
+                if feed.custom_title.is_some() {
+                    self.repository.set_feed_custom_title(id, feed.custom_title.clone()).await?;
+                }
+                let update = FeedBulkUpdate {
+                    folder: Some(feed.folder.clone()),
+                    add_tags: feed.tags.clone(),
+                    refresh_minutes: Some(feed.refresh_minutes),
+                    paused: Some(feed.paused),
+                    reading: Some(feed.reading),
+                    priority: Some(feed.priority),
+                    beat: Some(feed.source.beat.clone()),
+                    contact: Some(feed.source.contact.clone()),
+                    reliability: Some(feed.source.reliability),
+                    notes: Some(feed.source.notes.clone()),
+                    ..Default::default()
+                };
+                self.repository.bulk_update_feeds(vec![id], update).await?;
+                added += 1;
+                // Later feeds are checked against this one too
+                self.feeds = self.repository.get_all_feeds().await?;
+                id
+            };
+            feed_ids.insert(feed.id, id);
+        }
+        Ok((feed_ids, added))
+    }
+
+    /// Write a backup of everything: `config` (config.toml's text, less its
+    /// secrets and database path), the feeds, and every article with its state,
+    /// tags, note and summary. Returns (feeds, articles) written.
+    pub async fn export_backup(&self, path: &Path, config: Option<&str>) -> Result<(usize, usize)> {
+        let config = config.map(backup::portable_config).transpose()?;
+        let feeds: Vec<&Feed> = self.feeds.iter().collect();
+        let opml = export_opml_string(&feeds, &self.opml_head)?;
+
+        let mut notes = self.repository.get_annotations().await?;
+        let mut articles = Vec::new();
+        for article in self.repository.get_backup_articles().await? {
+            articles.push(BackedUpArticle {
+                summary: self.repository.get_summary(article.id).await?,
+                note: notes.remove(&article.id),
+                article,
+            });
+        }
+        let backup = Backup::new(config, opml, self.feeds.clone(), articles);
+        backup.write(path)?;
+        Ok((backup.feeds.len(), backup.articles.len()))
+    }
+
+    /// Restore a backup into this database, alongside what's here. Missing
+    /// feeds are added as `import_bundle` adds them; articles are stored with
+    /// their state, tags, note and summary. Articles already here (by URL)
+    /// are marked read or starred if they were in the backup, gain its tags,
+    /// and take its note and summary where they have none.
+    /// Returns (feeds added, articles added, articles already here) and the
+    /// backup's settings, to restore with `backup::restore_config`.
+    pub async fn import_backup(&mut self, path: &Path) -> Result<((usize, usize, usize), Option<String>)> {
+        let backup = Backup::read(path)?;
+        let (feed_ids, feeds_added) = self.add_missing_feeds(&backup.feeds).await?;
+
+        let (mut added, mut existing) = (0, 0);
+        for BackedUpArticle { article, summary, note } in backup.articles {
+            let Some(&feed_id) = feed_ids.get(&article.feed_id) else {
+                continue;
+            };
+            let id = match self.repository.article_id_by_url(&article.url).await? {
+                Some(id) => {
+                    existing += 1;
+                    if article.is_starred {
+                        self.repository.set_starred(id, true).await?;
+                    }
+                    if article.is_read {
+                        self.repository.set_read(id, true).await?;
+                    }
+                    if let Some(here) = self.repository.get_article(id).await? {
+                        let mut tags = here.tags;
+                        for tag in article.tags {
+                            if !tags.contains(&tag) {
+                                tags.push(tag);
+                            }
+                        }
+                        self.repository.set_article_tags(id, tags).await?;
+                    }
+                    if let Some(note) = note {
+                        if self.repository.get_annotation(id).await?.is_none() {
+                            self.repository.set_annotation(id, note).await?;
+                        }
+                    }
+                    if self.repository.get_summary(id).await?.is_some() {
+                        continue;
+                    }
+                    id
+                }
+                None => match self.repository.upsert_article(bundle::new_article(&article, feed_id)).await? {
+                    // Deleted here before; leave it gone
+                    0 => continue,
+                    id => {
+                        added += 1;
+                        self.repository.set_starred(id, article.is_starred).await?;
+                        self.repository.set_read(id, article.is_read).await?;
+                        if let Some(text) = article.full_text {
+                            self.repository.save_full_text(id, text).await?;
+                        }
+                        if let Some(note) = note {
+                            self.repository.set_annotation(id, note).await?;
+                        }
+                        id
+                    }
+                },
+            };
+            if let Some(summary) = summary {
+                self.repository
+                    .save_summary(id, summary.content, summary.model_version, summary.provider, None)
+                    .await?;
+            }
+        }
+
+        self.feeds = self.repository.get_all_feeds().await?;
+        let detail = format!("{} feeds added, {} articles added, {} already here", feeds_added, added, existing);
+        self.audit_import("backup", path, detail).await;
+        Ok(((feeds_added, added, existing), backup.config))
+    }
 
+    /// Note an import in the audit log; failing to doesn't fail the import
+    async fn audit_import(&self, kind: &str, path: &Path, detail: String) {
+        let action = format!("import.{}", kind);
+        if let Err(e) = self.repository.record_audit(&action, Some(path.display().to_string()), Some(detail)).await {
+            tracing::warn!("Failed to record the import: {}", e);
+        }
+    }
+
+    /// Apply the rename popup. An empty name, or the feed's own title,
+    /// clears the custom name.
+    async fn rename_feed(&mut self) -> Result<()> {
+        self.rename_feed_active = false;
+        let name = std::mem::take(&mut self.rename_feed_input).trim().to_string();
+        let Some(feed) = self
+            .rename_feed_id
+            .take()
+            .and_then(|id| self.feeds.iter().find(|f| f.id == id))
+        else {
+            return Ok(());
+        };
+
+        let feed_id = feed.id;
+        let custom_title = (!name.is_empty() && name != feed.title).then_some(name);
+        let display = custom_title.clone().unwrap_or_else(|| feed.title.clone());
+        self.repository.set_feed_custom_title(feed_id, custom_title).await?;
+
+        self.feeds = self.repository.get_all_feeds().await?;
+        for article in self.articles.iter_mut().filter(|a| a.feed_id == feed_id) {
+            article.feed_title = Some(display.clone());
+        }
+        self.bookmark_status = Some((format!("Renamed feed to {}", display), Instant::now()));
         Ok(())
     }
 
@@ -930,9 +5576,12 @@ impl App {
         }
 
         match self.import_opml(&expanded).await {
-            Ok(()) => {
+            Ok((_, skipped)) => {
                 let count = self.feeds.len();
-                self.opml_input_status = Some(format!("Imported! {} feeds total", count));
+                self.opml_input_status = Some(format!(
+                    "Imported! {} feeds total ({} duplicates skipped)",
+                    count, skipped
+                ));
                 self.opml_input_active = false;
                 self.opml_input.clear();
             }
@@ -944,6 +5593,15 @@ impl App {
         Ok(())
     }
 
+    /// The feeds `w` exports: the folder or tag picked in the feed list,
+    /// or every feed
+    pub fn opml_export_feeds(&self) -> Vec<&Feed> {
+        self.feeds
+            .iter()
+            .filter(|f| self.group_filter.as_ref().is_none_or(|g| self.panes.grouping.groups(f).contains(g)))
+            .collect()
+    }
+
     fn export_opml_to_file(&mut self) -> Result<()> {
         let input = self.opml_export_input.trim().to_string();
         if input.is_empty() {
@@ -964,9 +5622,14 @@ impl App {
 
         self.opml_export_status = Some("Exporting...".to_string());
 
-        match export_opml_file(&expanded, &self.feeds) {
+        let feeds = self.opml_export_feeds();
+        let head = OpmlHead {
+            title: self.group_filter.as_ref().map(|group| format!("BeatCheck Feeds: {}", group)),
+            ..self.opml_head.clone()
+        };
+        match export_opml_file(&expanded, &feeds, &head) {
             Ok(()) => {
-                let count = self.feeds.len();
+                let count = feeds.len();
                 self.opml_export_status = Some(format!("Exported {} feeds!", count));
                 self.opml_export_active = false;
                 self.opml_export_input.clear();
@@ -978,42 +5641,165 @@ impl App {
 
         Ok(())
     }
+}
 
-    fn email_article(&self, article: &Article) {
-        let subject = urlencoding::encode(&article.title);
+/// Open a draft email of the article with `embed`'s summary and tags
+fn email_article(article: &Article, embed: &Embed) {
+    let subject = urlencoding::encode(&article.title);
 
-        // Build email body with title, URL, summary (if available), and content
-        let mut body_parts = Vec::new();
+    // Build email body with title, URL, summary (if available), and content
+    let mut body_parts = Vec::new();
 
-        // Add title
-        body_parts.push(format!("Title: {}", article.title));
-        body_parts.push(String::new()); // blank line
+    // Add title
+    body_parts.push(format!("Title: {}", article.title));
+    body_parts.push(String::new()); // blank line
 
-        // Add URL
-        body_parts.push(format!("URL: {}", article.url));
+    // Add URL
+    body_parts.push(format!("URL: {}", article.url));
+    body_parts.push(String::new()); // blank line
+
+    // Add AI summary if available
+    if let Some(summary) = &embed.summary {
+        body_parts.push("AI Summary:".to_string());
+        body_parts.push(summary.content.clone());
+        body_parts.push(String::new()); // blank line
+    }
+    if !embed.tags.is_empty() {
+        body_parts.push(format!("Tags: {}", embed.tags.join(", ")));
         body_parts.push(String::new()); // blank line
+    }
 
-        // Add AI summary if available
-        if let Some(summary) = &self.current_summary {
-            body_parts.push("AI Summary:".to_string());
-            body_parts.push(summary.content.clone());
-            body_parts.push(String::new()); // blank line
-        }
+    // Add article content if available
+    if let Some(content) = article.content_text.as_ref().or(article.content.as_ref()) {
+        body_parts.push("Article Content:".to_string());
+        body_parts.push(content.clone());
+    }
 
-        // Add article content if available
-        if let Some(content) = article.content_text.as_ref().or(article.content.as_ref()) {
-            body_parts.push("Article Content:".to_string());
-            body_parts.push(content.clone());
-        }
+    let body_text = body_parts.join("\n");
+    let body = urlencoding::encode(&body_text);
+
+    let mailto_url = format!("mailto:?subject={}&body={}", subject, body);
 
-        let body_text = body_parts.join("\n");
-        let body = urlencoding::encode(&body_text);
+    // Open the mailto link in the default email client (in background to avoid blocking)
+    std::thread::spawn(move || {
+        let _ = open::that(&mailto_url);
+    });
+}
 
-        let mailto_url = format!("mailto:?subject={}&body={}", subject, body);
+/// The stored summary and suggested tags to send with `article`, or nothing
+/// when the destination's `[summaries.embed]` setting (`include`) is off
+async fn load_embed(repository: &Repository, article: &Article, include: bool) -> Result<Embed> {
+    if !include {
+        return Ok(Embed::default());
+    }
+    Ok(Embed {
+        summary: repository.get_summary(article.id).await?,
+        tags: article.tags.clone(),
+    })
+}
 
-        // Open the mailto link in the default email client (in background to avoid blocking)
-        std::thread::spawn(move || {
-            let _ = open::that(&mailto_url);
-        });
+/// An article's text as stored: page text if fetched, else the feed's content
+fn stored_text(article: &Article) -> String {
+    article
+        .full_text
+        .clone()
+        .or_else(|| article.content_text.clone())
+        .or_else(|| article.content.clone())
+        .unwrap_or_default()
+}
+
+/// Identical copy already summarized (e.g. the same wire story in
+/// another feed): copy its summary to this article and return it
+async fn reuse_summary(
+    repository: &Repository,
+    article_id: i64,
+    content_hash: Option<&str>,
+) -> Result<Option<Summary>> {
+    let Some(hash) = content_hash else {
+        return Ok(None);
+    };
+    let Some(existing) = repository.find_summary_by_hash(hash.to_string()).await? else {
+        return Ok(None);
+    };
+    tracing::debug!("Reusing summary of article {} for {}", existing.article_id, article_id);
+    repository
+        .save_summary(
+            article_id,
+            existing.content.clone(),
+            existing.model_version.clone(),
+            existing.provider.clone(),
+            Some(hash.to_string()),
+        )
+        .await?;
+    Ok(Some(Summary {
+        id: 0,
+        article_id,
+        content: existing.content,
+        model_version: existing.model_version,
+        provider: existing.provider,
+        generated_at: chrono::Utc::now(),
+    }))
+}
+
+/// Summarize `article` from `content` and save the summary (copied from an
+/// identical article when there is one) and any AI tags. A failure is
+/// recorded for the failure browser, to be retried later if it was transient.
+async fn summarize_and_save(
+    repository: &Repository,
+    summarizer: &Summarizer,
+    article: &Article,
+    content: &str,
+    llm_tagging: bool,
+) -> Result<BatchOutcome> {
+    let content_hash = content_hash(content);
+    if reuse_summary(repository, article.id, content_hash.as_deref()).await?.is_some() {
+        return Ok(BatchOutcome::Reused);
     }
+
+    let _in_flight = metrics().summary_started();
+    let (result, tags) = summarize_and_tag(summarizer, &article.title, content, llm_tagging).await;
+    let completion = match result {
+        Ok(completion) => completion,
+        Err(e) => {
+            repository.record_summary_failure(article.id, e.to_string(), e.is_transient()).await?;
+            return Ok(BatchOutcome::Failed(e.to_string()));
+        }
+    };
+    repository
+        .save_summary(
+            article.id,
+            completion.text,
+            completion.model,
+            Some(completion.provider.to_string()),
+            content_hash,
+        )
+        .await?;
+    if let Some(tags) = tags {
+        repository.set_article_tags(article.id, tags).await?;
+    }
+    Ok(BatchOutcome::Generated)
+}
+
+/// Summarize an article and, when `llm_tagging` is on, ask for its tags too.
+/// Returns the summary or the error, and the tags if any came back.
+async fn summarize_and_tag(
+    summarizer: &Summarizer,
+    title: &str,
+    content: &str,
+    llm_tagging: bool,
+) -> (Result<Completion>, Option<Vec<String>>) {
+    let result = summarizer.generate_summary(title, content).await;
+    let tags = if llm_tagging && result.is_ok() {
+        match summarizer.suggest_tags(title, content, MAX_SUGGESTED_TAGS).await {
+            Ok(tags) if !tags.is_empty() => Some(tags),
+            Ok(_) => None,
+            Err(e) => {
+                tracing::warn!("AI tagging failed: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    (result, tags)
 }
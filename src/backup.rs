@@ -0,0 +1,220 @@
+//! Backups: the whole app state as one compressed file, the supported way
+//! to move to another machine. Settings go without their secrets; the feeds
+//! go as OPML (for other readers) and as records; every article goes with
+//! its read and starred state, tags, note and summary.
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{AppError, Result};
+use crate::models::{Article, Feed, Summary};
+
+/// Bumped when a backup written now couldn't be read by older versions
+pub const BACKUP_VERSION: u32 = 1;
+
+const COMPRESS_LEVEL: i32 = 9;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Backup {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    /// config.toml with its secrets taken out, if there was one
+    #[serde(default)]
+    pub config: Option<String>,
+    /// The feeds for another reader; importing here uses `feeds`, which
+    /// keeps tags and pause/priority settings too
+    pub opml: String,
+    pub feeds: Vec<Feed>,
+    pub articles: Vec<BackedUpArticle>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackedUpArticle {
+    #[serde(flatten)]
+    pub article: Article,
+    #[serde(default)]
+    pub summary: Option<Summary>,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+impl Backup {
+    pub fn new(config: Option<String>, opml: String, feeds: Vec<Feed>, articles: Vec<BackedUpArticle>) -> Self {
+        Self {
+            version: BACKUP_VERSION,
+            exported_at: Utc::now(),
+            config,
+            opml,
+            feeds,
+            articles,
+        }
+    }
+
+    /// A backup written by `write`, refusing ones from a newer version
+    pub fn read(path: &Path) -> Result<Self> {
+        let file = std::fs::File::open(path)
+            .map_err(|e| AppError::Config(format!("Can't read backup {}: {}", path.display(), e)))?;
+        let mut json = String::new();
+        zstd::stream::Decoder::new(file)
+            .and_then(|mut decoder| decoder.read_to_string(&mut json))
+            .map_err(|e| AppError::Config(format!("{} isn't a beatcheck backup: {}", path.display(), e)))?;
+        let backup: Backup = serde_json::from_str(&json)
+            .map_err(|e| AppError::Config(format!("{} isn't a beatcheck backup: {}", path.display(), e)))?;
+        if backup.version > BACKUP_VERSION {
+            return Err(AppError::Config(format!(
+                "{} was written by a newer beatcheck (backup version {})",
+                path.display(),
+                backup.version
+            )));
+        }
+        Ok(backup)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_vec(self)?;
+        std::fs::write(path, zstd::stream::encode_all(&json[..], COMPRESS_LEVEL)?)?;
+        Ok(())
+    }
+}
+
+/// Put a backup's settings at `config_path`, or beside it as
+/// config.restored.toml when there are settings there already (other than
+/// `defaults`, as a first run writes them), to merge by hand. Returns where
+/// they went.
+pub fn restore_config(config: &str, config_path: &Path, defaults: &str) -> Result<PathBuf> {
+    let customized = std::fs::read_to_string(config_path).is_ok_and(|here| here != defaults);
+    let path = if customized {
+        config_path.with_file_name("config.restored.toml")
+    } else {
+        config_path.to_path_buf()
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, config)?;
+    Ok(path)
+}
+
+/// `config` (config.toml's text) as it can go to another machine: without
+/// API keys, tokens, passwords and secrets at any depth, or the database
+/// path (the other machine keeps its own)
+pub fn portable_config(config: &str) -> Result<String> {
+    let mut table: toml::Table = toml::from_str(config)?;
+    table.remove("db_path");
+    strip_table(&mut table);
+    toml::to_string_pretty(&table).map_err(|e| AppError::Config(e.to_string()))
+}
+
+fn strip_table(table: &mut toml::Table) {
+    table.retain(|key, _| !is_secret(key));
+    for (_, value) in table.iter_mut() {
+        match value {
+            toml::Value::Table(inner) => strip_table(inner),
+            toml::Value::Array(items) => {
+                for item in items {
+                    if let toml::Value::Table(inner) = item {
+                        strip_table(inner);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn is_secret(key: &str) -> bool {
+    let key = key.to_lowercase();
+    ["api_key", "token", "password", "secret"].iter().any(|secret| key.ends_with(secret))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_portable_config() {
+        let config = r#"
+db_path = "/home/leo/.local/share/beatcheck/feeds.db"
+claude_api_key = "sk-test"
+raindrop_token = "rd-secret"
+refresh_interval_minutes = 15
+
+[openai]
+api_key = "sk-openai"
+model = "gpt-4o-mini"
+
+[[subscriptions]]
+url = "https://example.com/feed"
+token = "x"
+"#;
+        let stripped = portable_config(config).unwrap();
+        for secret in ["sk-test", "rd-secret", "sk-openai", "token", "db_path"] {
+            assert!(!stripped.contains(secret), "{}", secret);
+        }
+        let table: toml::Table = toml::from_str(&stripped).unwrap();
+        assert_eq!(table["refresh_interval_minutes"].as_integer(), Some(15));
+        assert_eq!(table["openai"]["model"].as_str(), Some("gpt-4o-mini"));
+        assert_eq!(table["subscriptions"][0]["url"].as_str(), Some("https://example.com/feed"));
+    }
+
+    #[test]
+    fn test_restore_config_keeps_customized_settings() {
+        let dir = std::env::temp_dir().join(format!("beatcheck-restore-{}", std::process::id()));
+        let path = dir.join("beatcheck").join("config.toml");
+        let defaults = "refresh_interval_minutes = 30\n";
+
+        assert_eq!(restore_config("a = 1\n", &path, defaults).unwrap(), path);
+        std::fs::write(&path, defaults).unwrap();
+        assert_eq!(restore_config("a = 1\n", &path, defaults).unwrap(), path);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "a = 1\n");
+
+        let beside = restore_config("a = 2\n", &path, defaults).unwrap();
+        assert_eq!(beside, path.with_file_name("config.restored.toml"));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "a = 1\n");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_backup_reads_back_and_refuses_newer_versions() {
+        let dir = std::env::temp_dir().join(format!("beatcheck-backup-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("state.beatcheck");
+
+        let article: Article = serde_json::from_value(serde_json::json!({
+            "id": 7, "feed_id": 1, "guid": "g", "title": "Chips", "url": "https://example.com/a",
+            "author": null, "content": "<p>Fabs</p>", "content_text": "Fabs", "published_at": null,
+            "fetched_at": "2026-03-02T10:00:00Z", "feed_title": "Wire", "is_starred": false,
+            "is_pinned": false, "reading_minutes": null, "is_queued": false, "is_read": true,
+            "full_text": null, "comments_url": null, "discussion": null, "enclosure": null,
+            "playback_secs": null, "is_played": false, "extras": null, "archive_path": null,
+            "tags": ["chips"]
+        }))
+        .unwrap();
+        let backed_up = BackedUpArticle {
+            article,
+            summary: None,
+            note: Some("Call the fab".to_string()),
+        };
+        Backup::new(Some("refresh_interval_minutes = 15\n".to_string()), "<opml/>".to_string(), Vec::new(), vec![backed_up])
+            .write(&path)
+            .unwrap();
+        let backup = Backup::read(&path).unwrap();
+        assert_eq!(backup.version, BACKUP_VERSION);
+        let restored = &backup.articles[0];
+        assert!(restored.article.is_read);
+        assert_eq!(restored.article.tags, ["chips"]);
+        assert_eq!(restored.note.as_deref(), Some("Call the fab"));
+
+        let mut newer = backup;
+        newer.version = BACKUP_VERSION + 1;
+        newer.write(&path).unwrap();
+        assert!(Backup::read(&path).is_err());
+
+        std::fs::write(&path, "not a backup").unwrap();
+        assert!(Backup::read(&path).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
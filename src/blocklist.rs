@@ -1,33 +1,148 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
-use std::time::SystemTime;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock, Weak};
+use std::time::{Duration, Instant, SystemTime};
 
+use chrono::{Local, NaiveDate};
 use regex::Regex;
 
+use crate::error::{AppError, Result};
+
+/// How often `SharedBlocklist::watch` checks blocklist.txt for edits
+pub const BLOCKLIST_POLL: Duration = Duration::from_secs(2);
+
+/// Lines starting with this block a domain rather than a keyword
+pub const SITE_PREFIX: &str = "site:";
+
+/// Lines starting with this subscribe to a blocklist shared by URL;
+/// commented out (`# include URL`) the subscription is kept but off
+pub const INCLUDE_PREFIX: &str = "include ";
+const DISABLED_INCLUDE_PREFIX: &str = "# include ";
+
+/// Rules ending in this and a date (`world cup until:2026-07-20`) are
+/// dropped once the day is over
+pub const UNTIL_PREFIX: &str = "until:";
+
+/// A failed download of a shared list is tried again after this long
+const SUBSCRIPTION_RETRY: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Default)]
 pub struct Blocklist {
     keywords: HashSet<String>,
+    /// Domains from `site:` lines; their subdomains are blocked too
+    sites: HashSet<String>,
+    /// Shared lists from `include` lines, in file order
+    subscriptions: Vec<Subscription>,
+    /// Last days of temporary rules, by keyword or `site:` entry
+    expires: HashMap<String, NaiveDate>,
     last_modified: Option<SystemTime>,
 }
 
+/// A blocklist shared by URL, merged with the local rules while enabled.
+/// Downloads are kept next to the database, so it works offline.
+#[derive(Default)]
+struct Subscription {
+    url: String,
+    enabled: bool,
+    /// Its keywords and sites, as of the last download
+    rules: Blocklist,
+    /// When the last download was saved
+    fetched: Option<SystemTime>,
+}
+
+/// What `SharedBlocklist::subscriptions` reports about a shared list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubscriptionStatus {
+    pub url: String,
+    pub enabled: bool,
+    pub rules: usize,
+    /// `None` until the first download
+    pub fetched: Option<SystemTime>,
+}
+
+impl Subscription {
+    fn load(url: &str, enabled: bool) -> Self {
+        let mut subscription = Self {
+            url: url.to_string(),
+            enabled,
+            ..Self::default()
+        };
+        if enabled {
+            let path = subscription_path(url);
+            if let Ok(content) = fs::read_to_string(&path) {
+                subscription.rules = Blocklist::parse_shared(&content);
+                subscription.fetched = modified(&path);
+            }
+        }
+        subscription
+    }
+
+    fn status(&self) -> SubscriptionStatus {
+        SubscriptionStatus {
+            url: self.url.clone(),
+            enabled: self.enabled,
+            rules: self.rules.len(),
+            fetched: self.fetched,
+        }
+    }
+
+    /// Whether it's time for a fresh download
+    fn is_due(&self, max_age: Duration) -> bool {
+        self.enabled
+            && self
+                .fetched
+                .and_then(|fetched| fetched.elapsed().ok())
+                .is_none_or(|age| age >= max_age)
+    }
+}
+
+/// Where a shared list's last download is kept
+fn subscription_path(url: &str) -> PathBuf {
+    let name: String = url
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .take(150)
+        .collect();
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("beatcheck")
+        .join("blocklists")
+        .join(format!("{}.txt", name))
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).ok().and_then(|m| m.modified().ok())
+}
+
+/// Download a shared list and save it where `Blocklist::load` finds it,
+/// returning how many rules it has. One without any (an error page, say)
+/// is refused, keeping the last good copy.
+async fn download_subscription(client: &reqwest::Client, url: &str) -> Result<usize> {
+    let text = client.get(url).send().await?.error_for_status()?.text().await?;
+    let rules = Blocklist::parse_shared(&text).len();
+    if rules == 0 {
+        return Err(AppError::Config(format!("No blocklist rules at {}", url)));
+    }
+    let path = subscription_path(url);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, text)?;
+    Ok(rules)
+}
+
 impl Blocklist {
     pub fn load() -> Self {
         let path = Self::blocklist_path();
-        let mut keywords = HashSet::new();
-        let mut last_modified = None;
+        let mut blocklist = Self::default();
 
         match fs::read_to_string(&path) {
             Ok(content) => {
+                blocklist = Self::parse(&content);
                 // Capture file modification time
-                if let Ok(metadata) = fs::metadata(&path) {
-                    last_modified = metadata.modified().ok();
-                }
-
-                for line in content.lines() {
-                    if let Some(normalized) = Self::normalize_keyword(line) {
-                        keywords.insert(normalized);
-                    }
-                }
+                blocklist.last_modified = modified(&path);
             }
             Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
                 // Missing file is fine, return empty blocklist
@@ -40,23 +155,72 @@ impl Blocklist {
             }
         }
 
-        Self {
-            keywords,
-            last_modified,
+        blocklist
+    }
+
+    /// The rules in blocklist.txt's text, with its shared lists' last
+    /// downloads
+    fn parse(content: &str) -> Self {
+        let mut blocklist = Self::default();
+        let today = Local::now().date_naive();
+        for line in content.lines() {
+            let Some(entry) = Self::normalize_entry(line) else {
+                continue;
+            };
+            let (entry, until) = split_until(&entry);
+            if let Some(until) = until {
+                if until < today {
+                    continue;
+                }
+                blocklist.expires.insert(entry.to_string(), until);
+            }
+            if let Some(site) = entry.strip_prefix(SITE_PREFIX) {
+                blocklist.sites.insert(site.to_string());
+            } else if let Some(url) = entry.strip_prefix(INCLUDE_PREFIX) {
+                blocklist.subscriptions.push(Subscription::load(url, true));
+            } else if let Some(url) = entry.strip_prefix(DISABLED_INCLUDE_PREFIX) {
+                blocklist.subscriptions.push(Subscription::load(url, false));
+            } else {
+                blocklist.keywords.insert(entry.to_string());
+            }
+        }
+        blocklist
+    }
+
+    /// A shared list's keywords and sites; it can't include others
+    fn parse_shared(content: &str) -> Self {
+        let mut blocklist = Self::default();
+        let today = Local::now().date_naive();
+        for entry in content.lines().filter_map(Self::normalize_entry) {
+            let (entry, until) = split_until(&entry);
+            if until.is_some_and(|until| until < today) {
+                continue;
+            }
+            if let Some(site) = entry.strip_prefix(SITE_PREFIX) {
+                blocklist.sites.insert(site.to_string());
+            } else if !entry.starts_with(INCLUDE_PREFIX) && !entry.starts_with(DISABLED_INCLUDE_PREFIX) {
+                blocklist.keywords.insert(entry.to_string());
+            }
         }
+        blocklist
     }
 
-    pub fn reload(&mut self) {
+    /// Read the file again if it, or a shared list's download, changed
+    /// since the last load; returns whether it did
+    pub fn reload(&mut self) -> bool {
         // Get file metadata to check modification time
-        let path = Self::blocklist_path();
-        let current_mtime = fs::metadata(&path)
-            .ok()
-            .and_then(|m| m.modified().ok());
+        let current_mtime = modified(&Self::blocklist_path());
+        let downloaded = self
+            .subscriptions
+            .iter()
+            .any(|s| s.enabled && modified(&subscription_path(&s.url)) != s.fetched);
 
-        // Only reload if file changed (or first load)
-        if current_mtime != self.last_modified {
-            *self = Self::load();
+        // Only reload if something changed (or first load)
+        if current_mtime == self.last_modified && !downloaded {
+            return false;
         }
+        *self = Self::load();
+        true
     }
 
     #[allow(dead_code)]
@@ -69,6 +233,61 @@ impl Blocklist {
         self.keywords.is_empty()
     }
 
+    /// Rules in force: the local ones and the enabled shared lists'
+    pub fn len(&self) -> usize {
+        self.keywords.len()
+            + self.sites.len()
+            + self
+                .subscriptions
+                .iter()
+                .filter(|s| s.enabled)
+                .map(|s| s.rules.len())
+                .sum::<usize>()
+    }
+
+    /// Every rule as written in blocklist.txt: keywords, then `site:`
+    /// entries (each with its `until:` date if it has one), then `include`
+    /// lines for the shared lists
+    pub fn entries(&self) -> Vec<String> {
+        let with_until = |entry: String| match self.expires.get(&entry) {
+            Some(until) => format!("{} {}{}", entry, UNTIL_PREFIX, until),
+            None => entry,
+        };
+        let mut keywords: Vec<String> = self.keywords.iter().cloned().collect();
+        keywords.sort();
+        let mut sites: Vec<String> = self.sites.iter().map(|site| format!("{}{}", SITE_PREFIX, site)).collect();
+        sites.sort();
+        keywords.extend(sites);
+        let mut keywords: Vec<String> = keywords.into_iter().map(with_until).collect();
+        keywords.extend(self.subscriptions.iter().map(|s| subscription_entry(&s.url, s.enabled)));
+        keywords
+    }
+
+    /// Whether an article should be filtered: a blocked keyword in its
+    /// title or content, or a link to a blocked site, in the local rules
+    /// or an enabled shared list
+    pub fn blocks(&self, title: &str, content: Option<&str>, url: &str) -> bool {
+        self.contains_blocked_site(url)
+            || self.contains_blocked_keyword(title, content)
+            || self
+                .subscriptions
+                .iter()
+                .any(|s| s.enabled && s.rules.blocks(title, content, url))
+    }
+
+    /// Whether the URL is on a blocked site or one of its subdomains
+    pub fn contains_blocked_site(&self, url: &str) -> bool {
+        if self.sites.is_empty() {
+            return false;
+        }
+        let Some(host) = url::Url::parse(url).ok().and_then(|u| u.host_str().map(str::to_lowercase)) else {
+            return false;
+        };
+        self.sites
+            .iter()
+            .any(|site| host == *site || host.ends_with(&format!(".{}", site)))
+    }
+
     /// Check if any blocked keyword appears as a whole word in the title or content.
     /// Returns true if a keyword is found (article should be filtered).
     pub fn contains_blocked_keyword(&self, title: &str, content: Option<&str>) -> bool {
@@ -92,15 +311,37 @@ impl Blocklist {
         false
     }
 
-    /// Check if any keyword appears as a whole word in the given text.
+    /// Check if any keyword appears in the given text, the way its rule says
     fn check_text_for_keywords(&self, text: &str) -> bool {
         // Convert text to lowercase for case-insensitive matching
         let text_lower = text.to_lowercase();
+        // Stems of the text's words, worked out once for any `~` rules
+        let mut text_stems: Option<Vec<String>> = None;
 
         for keyword in &self.keywords {
-            // Build regex with word boundaries: \bkeyword\b
+            let (matching, words) = KeywordMatch::split(keyword);
             // Escape the keyword to handle special regex characters
-            let pattern = format!(r"\b{}\b", regex::escape(keyword));
+            let escaped = regex::escape(words);
+            let pattern = match matching {
+                KeywordMatch::Word => format!(r"\b{}\b", escaped),
+                KeywordMatch::Prefix => format!(r"\b{}", escaped),
+                KeywordMatch::Suffix => format!(r"{}\b", escaped),
+                KeywordMatch::Substring => escaped,
+                KeywordMatch::Stem => {
+                    let stems = text_stems.get_or_insert_with(|| {
+                        text_lower
+                            .split(|c: char| !(c.is_alphanumeric() || c == '-'))
+                            .filter(|word| !word.is_empty())
+                            .map(stem)
+                            .collect()
+                    });
+                    let wanted: Vec<String> = words.split(' ').map(stem).collect();
+                    if stems.windows(wanted.len()).any(|window| window == wanted.as_slice()) {
+                        return true;
+                    }
+                    continue;
+                }
+            };
 
             if let Ok(re) = Regex::new(&pattern) {
                 if re.is_match(&text_lower) {
@@ -112,14 +353,120 @@ impl Blocklist {
         false
     }
 
-    fn blocklist_path() -> PathBuf {
+    /// Add a keyword or `site:` entry to blocklist.txt, returning it as
+    /// stored, or `None` if it was already there
+    pub fn add_entry(entry: &str) -> Result<Option<String>> {
+        add_to_file(&Self::blocklist_path(), entry)
+    }
+
+    /// Take an entry out of blocklist.txt; returns whether it was there
+    pub fn remove_entry(entry: &str) -> Result<bool> {
+        remove_from_file(&Self::blocklist_path(), entry)
+    }
+
+    /// Take the rules whose `until:` date has passed out of blocklist.txt;
+    /// returns how many there were
+    pub fn remove_expired(today: NaiveDate) -> Result<usize> {
+        remove_expired_from_file(&Self::blocklist_path(), today)
+    }
+
+    pub fn blocklist_path() -> PathBuf {
         dirs::config_dir()
             .unwrap_or_else(|| PathBuf::from(".config"))
             .join("beatcheck")
             .join("blocklist.txt")
     }
 
+    /// A blocklist.txt line in its stored form: a normalized keyword,
+    /// `site:` and a bare domain (a pasted URL is cut down to its host), or
+    /// `include` and a shared list's URL (commented out when it's off).
+    /// Other comment lines are `None`.
+    pub fn normalize_entry(line: &str) -> Option<String> {
+        let trimmed = line.trim();
+        if !trimmed.starts_with('#') {
+            if let Some((rule, until)) = trimmed.rsplit_once(UNTIL_PREFIX) {
+                let Ok(until) = NaiveDate::parse_from_str(until.trim(), "%Y-%m-%d") else {
+                    tracing::warn!("Expiry isn't a YYYY-MM-DD date, rejecting: {}", trimmed);
+                    return None;
+                };
+                let rule = Self::normalize_entry(rule)?;
+                if rule.starts_with(INCLUDE_PREFIX) {
+                    tracing::warn!("Shared lists don't expire, rejecting: {}", trimmed);
+                    return None;
+                }
+                return Some(format!("{} {}{}", rule, UNTIL_PREFIX, until));
+            }
+        }
+        if let Some(comment) = trimmed.strip_prefix('#') {
+            return starts_with_ignore_case(comment.trim_start(), INCLUDE_PREFIX)
+                .and_then(Self::normalize_include)
+                .map(|url| subscription_entry(&url, false));
+        }
+        if let Some(url) = starts_with_ignore_case(trimmed, INCLUDE_PREFIX) {
+            return Self::normalize_include(url).map(|url| subscription_entry(&url, true));
+        }
+        match starts_with_ignore_case(trimmed, SITE_PREFIX) {
+            Some(site) => Self::normalize_site(site).map(|site| format!("{}{}", SITE_PREFIX, site)),
+            None => Self::normalize_keyword(trimmed),
+        }
+    }
+
+    /// `normalize_entry` for a rule being added, which may also end in
+    /// "for N days" (or weeks, or months) to set its `until:` date
+    pub fn normalize_new_entry(entry: &str, today: NaiveDate) -> Option<String> {
+        let lasting = Regex::new(r"(?i)^(.+?)\s+for\s+(\d+)\s*(d|days?|w|weeks?|m|months?)$").unwrap();
+        let Some(lasting) = lasting.captures(entry.trim()) else {
+            return Self::normalize_entry(entry);
+        };
+        let count: u64 = lasting[2].parse().ok()?;
+        let days = match lasting[3].chars().next() {
+            Some('w' | 'W') => count * 7,
+            Some('m' | 'M') => count * 30,
+            _ => count,
+        };
+        let until = today.checked_add_days(chrono::Days::new(days))?;
+        Self::normalize_entry(&format!("{} {}{}", &lasting[1], UNTIL_PREFIX, until))
+    }
+
+    fn normalize_include(url: &str) -> Option<String> {
+        match url::Url::parse(url.trim()) {
+            Ok(url) if ["http", "https"].contains(&url.scheme()) => Some(url.to_string()),
+            _ => {
+                tracing::warn!("Not an http(s) URL, rejecting: include {}", url.trim());
+                None
+            }
+        }
+    }
+
+    fn normalize_site(site: &str) -> Option<String> {
+        let site = site.trim().to_lowercase();
+        let host = url::Url::parse(&site)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_string))
+            .unwrap_or_else(|| site.trim_end_matches('/').to_string());
+        let host = host.strip_prefix("www.").unwrap_or(&host);
+        let valid = host.contains('.')
+            && !host.starts_with('.')
+            && !host.ends_with('.')
+            && host.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.');
+        if !valid {
+            tracing::warn!("Not a domain, rejecting: site:{}", site);
+            return None;
+        }
+        Some(host.to_string())
+    }
+
     fn normalize_keyword(line: &str) -> Option<String> {
+        // Matching markers go back on once the words are normalized
+        let line = line.trim();
+        let (stemmed, line) = line.strip_prefix('~').map_or((false, line), |rest| (true, rest));
+        let (leading, line) = line.strip_prefix('*').map_or((false, line), |rest| (true, rest));
+        let (trailing, line) = line.strip_suffix('*').map_or((false, line), |rest| (true, rest));
+        if stemmed && (leading || trailing) {
+            tracing::warn!("Keyword can't be both stemmed (~) and partial (*), rejecting: {}", line);
+            return None;
+        }
+
         // Trim whitespace
         let trimmed = line.trim();
 
@@ -168,10 +515,397 @@ impl Blocklist {
             return None;
         }
 
-        Some(normalized)
+        Some(format!(
+            "{}{}{}{}",
+            if stemmed { "~" } else { "" },
+            if leading { "*" } else { "" },
+            normalized,
+            if trailing { "*" } else { "" }
+        ))
     }
 }
 
+/// How a keyword rule matches, from its markers: `crypto` whole words
+/// only, `crypto*` words starting with it, `*coin` words ending with it,
+/// `*coin*` anywhere, even inside a word, and `~election` any form of the
+/// word ("elections", "election's")
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeywordMatch {
+    Word,
+    Prefix,
+    Suffix,
+    Substring,
+    Stem,
+}
+
+impl KeywordMatch {
+    /// The rule's kind of match and its words
+    pub fn split(keyword: &str) -> (Self, &str) {
+        if let Some(words) = keyword.strip_prefix('~') {
+            return (KeywordMatch::Stem, words);
+        }
+        match (keyword.strip_prefix('*'), keyword.strip_suffix('*')) {
+            (Some(_), Some(_)) if keyword.len() > 1 => (KeywordMatch::Substring, &keyword[1..keyword.len() - 1]),
+            (Some(words), None) => (KeywordMatch::Suffix, words),
+            (None, Some(words)) => (KeywordMatch::Prefix, words),
+            _ => (KeywordMatch::Word, keyword),
+        }
+    }
+}
+
+/// A word with common English inflections cut off, so "elections",
+/// "voting" and "voted" come out as "election", "vot" and "vot" (as does
+/// "vote")
+fn stem(word: &str) -> String {
+    if let Some(root) = word.strip_suffix("ies").filter(|root| root.len() > 1) {
+        return format!("{}y", root);
+    }
+    let mut stem = word;
+    if stem.len() > 3 && stem.ends_with('s') && !["ss", "us", "is"].iter().any(|end| stem.ends_with(end)) {
+        stem = &stem[..stem.len() - 1];
+    } else if let Some(root) = ["ing", "ed"]
+        .iter()
+        .find_map(|suffix| stem.strip_suffix(suffix))
+        .filter(|root| root.chars().count() >= 3)
+    {
+        stem = root;
+        // "running" -> "run"
+        let mut chars = stem.chars().rev();
+        if let (Some(last), Some(before)) = (chars.next(), chars.next()) {
+            if before == last && last.is_ascii_alphabetic() && !"lszaeiou".contains(last) {
+                stem = &stem[..stem.len() - 1];
+            }
+        }
+    }
+    if stem.len() > 3 {
+        stem = stem.strip_suffix('e').unwrap_or(stem);
+    }
+    stem.to_string()
+}
+
+/// One blocklist for the fetcher, the daemon and the UI alike. Clones
+/// share it; `watch` keeps it in step with blocklist.txt, so an edit
+/// applies everywhere within `BLOCKLIST_POLL`.
+#[derive(Clone)]
+pub struct SharedBlocklist {
+    blocklist: Arc<RwLock<Blocklist>>,
+    /// Bumped each time the rules are reloaded
+    generation: Arc<AtomicU64>,
+}
+
+impl SharedBlocklist {
+    pub fn load() -> Self {
+        prune_expired();
+        Self::from(Blocklist::load())
+    }
+
+    /// Pick up edits to blocklist.txt; returns whether there were any
+    pub fn reload(&self) -> bool {
+        let changed = self.blocklist.write().unwrap_or_else(|e| e.into_inner()).reload();
+        if changed {
+            self.generation.fetch_add(1, Ordering::Relaxed);
+        }
+        changed
+    }
+
+    /// Check blocklist.txt for edits every `interval` in the background,
+    /// until the last handle is dropped. With `refresh`, shared lists are
+    /// downloaded again once their copy is that old (and straight away
+    /// when newly included).
+    pub fn watch(&self, interval: Duration, refresh: Option<Duration>) {
+        let blocklist: Weak<RwLock<Blocklist>> = Arc::downgrade(&self.blocklist);
+        let generation = self.generation.clone();
+        tokio::spawn(async move {
+            let mut ticks = tokio::time::interval(interval);
+            let mut attempts: HashMap<String, Instant> = HashMap::new();
+            let mut pruned = Local::now().date_naive();
+            ticks.tick().await;
+            loop {
+                ticks.tick().await;
+                // Temporary rules run out at midnight
+                let today = Local::now().date_naive();
+                if today != pruned {
+                    pruned = today;
+                    prune_expired();
+                }
+                let Some(blocklist) = blocklist.upgrade() else {
+                    return;
+                };
+                let shared = SharedBlocklist {
+                    blocklist,
+                    generation: generation.clone(),
+                };
+                if shared.reload() {
+                    tracing::info!("Reloaded the blocklist: {} rules", shared.rule_count());
+                }
+                let Some(max_age) = refresh else {
+                    continue;
+                };
+                // Failed downloads wait a while before the next try
+                let due: Vec<String> = shared
+                    .due_subscriptions(max_age)
+                    .into_iter()
+                    .filter(|url| attempts.get(url).is_none_or(|at| at.elapsed() >= SUBSCRIPTION_RETRY))
+                    .collect();
+                for url in &due {
+                    attempts.insert(url.clone(), Instant::now());
+                }
+                for (url, result) in shared.download(due).await {
+                    match result {
+                        Ok(rules) => tracing::info!("Downloaded shared blocklist {}: {} rules", url, rules),
+                        Err(e) => tracing::warn!("Couldn't download shared blocklist {}: {}", url, e),
+                    }
+                }
+            }
+        });
+    }
+
+    /// Download the enabled shared lists whose copy is at least `max_age`
+    /// old (or missing), then apply them; each one's rule count or error
+    pub async fn refresh_subscriptions(&self, max_age: Duration) -> Vec<(String, Result<usize>)> {
+        let due = self.due_subscriptions(max_age);
+        self.download(due).await
+    }
+
+    fn due_subscriptions(&self, max_age: Duration) -> Vec<String> {
+        self.blocklist
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .subscriptions
+            .iter()
+            .filter(|s| s.is_due(max_age))
+            .map(|s| s.url.clone())
+            .collect()
+    }
+
+    async fn download(&self, urls: Vec<String>) -> Vec<(String, Result<usize>)> {
+        if urls.is_empty() {
+            return Vec::new();
+        }
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .user_agent("beatcheck/1.2.0")
+            .build()
+            .expect("Failed to create HTTP client");
+        let mut results = Vec::new();
+        for url in urls {
+            let result = download_subscription(&client, &url).await;
+            results.push((url, result));
+        }
+        self.reload();
+        results
+    }
+
+    /// Every shared list, enabled or not
+    pub fn subscriptions(&self) -> Vec<SubscriptionStatus> {
+        self.blocklist
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .subscriptions
+            .iter()
+            .map(Subscription::status)
+            .collect()
+    }
+
+    /// Turn a shared list on or off, keeping its line (and download)
+    pub fn set_subscription_enabled(&self, url: &str, enabled: bool) -> Result<bool> {
+        let entry = format!("{}{}", INCLUDE_PREFIX, url.trim());
+        let entry = Blocklist::normalize_entry(&entry).ok_or_else(|| invalid_entry(&entry))?;
+        let url = entry.strip_prefix(INCLUDE_PREFIX).unwrap_or_default();
+        if !self.subscriptions().iter().any(|s| s.url == url) {
+            return Ok(false);
+        }
+        self.add(&subscription_entry(url, enabled))?;
+        Ok(true)
+    }
+
+    /// Changes with every reload, so a holder can tell it missed one
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Relaxed)
+    }
+
+    pub fn rule_count(&self) -> usize {
+        self.blocklist.read().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    pub fn entries(&self) -> Vec<String> {
+        self.blocklist.read().unwrap_or_else(|e| e.into_inner()).entries()
+    }
+
+    pub fn blocks(&self, title: &str, content: Option<&str>, url: &str) -> bool {
+        self.blocklist
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .blocks(title, content, url)
+    }
+
+    /// Add an entry to blocklist.txt and apply it straight away; returns
+    /// the entry as stored, or `None` if it was already listed
+    pub fn add(&self, entry: &str) -> Result<Option<String>> {
+        let added = Blocklist::add_entry(entry)?;
+        self.reload();
+        Ok(added)
+    }
+
+    /// Take an entry out of blocklist.txt and apply that straight away;
+    /// a shared list's download goes too
+    pub fn remove(&self, entry: &str) -> Result<bool> {
+        let removed = Blocklist::remove_entry(entry)?;
+        let url = Blocklist::normalize_entry(entry).and_then(|e| {
+            e.strip_prefix(INCLUDE_PREFIX)
+                .or_else(|| e.strip_prefix(DISABLED_INCLUDE_PREFIX))
+                .map(str::to_string)
+        });
+        if let Some(url) = url.filter(|_| removed) {
+            fs::remove_file(subscription_path(&url)).ok();
+        }
+        self.reload();
+        Ok(removed)
+    }
+
+    /// Swap one entry for another, leaving the old one in place if the new
+    /// one isn't valid
+    pub fn replace(&self, old: &str, new: &str) -> Result<Option<String>> {
+        let new = Blocklist::normalize_new_entry(new, Local::now().date_naive()).ok_or_else(|| invalid_entry(new))?;
+        Blocklist::remove_entry(old)?;
+        self.add(&new)
+    }
+}
+
+fn invalid_entry(entry: &str) -> AppError {
+    AppError::Config(format!(
+        "\"{}\" isn't a blocklist entry: use letters, numbers, spaces and hyphens, site:example.com or include URL",
+        entry.trim()
+    ))
+}
+
+fn subscription_entry(url: &str, enabled: bool) -> String {
+    let prefix = if enabled { INCLUDE_PREFIX } else { DISABLED_INCLUDE_PREFIX };
+    format!("{}{}", prefix, url)
+}
+
+/// The rest of `text` after `prefix`, ignoring case
+fn starts_with_ignore_case<'a>(text: &'a str, prefix: &str) -> Option<&'a str> {
+    text.get(..prefix.len())
+        .filter(|start| start.eq_ignore_ascii_case(prefix))
+        .map(|_| &text[prefix.len()..])
+}
+
+/// A normalized entry without its `until:` date, and the date
+fn split_until(entry: &str) -> (&str, Option<NaiveDate>) {
+    match entry.rsplit_once(&format!(" {}", UNTIL_PREFIX)) {
+        Some((rule, until)) => (rule, NaiveDate::parse_from_str(until, "%Y-%m-%d").ok()),
+        None => (entry, None),
+    }
+}
+
+/// Lines for the same rule: a shared list's, on or off, or the same entry
+/// whatever its `until:` date
+fn same_rule(a: &str, b: &str) -> bool {
+    let key = |entry: &str| {
+        let (rule, _) = split_until(entry);
+        rule.strip_prefix("# ").unwrap_or(rule).to_string()
+    };
+    key(a) == key(b)
+}
+
+fn prune_expired() {
+    match Blocklist::remove_expired(Local::now().date_naive()) {
+        Ok(0) => {}
+        Ok(removed) => tracing::info!("Removed {} expired blocklist rules", removed),
+        Err(e) => tracing::warn!("Couldn't remove expired blocklist rules: {}", e),
+    }
+}
+
+fn read_blocklist(path: &Path) -> Result<String> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn add_to_file(path: &Path, entry: &str) -> Result<Option<String>> {
+    let entry = Blocklist::normalize_new_entry(entry, Local::now().date_naive()).ok_or_else(|| invalid_entry(entry))?;
+    let mut content = read_blocklist(path)?;
+    if content.lines().any(|line| Blocklist::normalize_entry(line).as_deref() == Some(entry.as_str())) {
+        return Ok(None);
+    }
+    // A shared list turned on or off, or a rule with a new expiry, keeps
+    // its place
+    if content
+        .lines()
+        .any(|line| Blocklist::normalize_entry(line).is_some_and(|e| same_rule(&e, &entry)))
+    {
+        let lines: Vec<&str> = content
+            .lines()
+            .map(|line| match Blocklist::normalize_entry(line) {
+                Some(e) if same_rule(&e, &entry) => entry.as_str(),
+                _ => line,
+            })
+            .collect();
+        fs::write(path, lines.join("\n") + "\n")?;
+        return Ok(Some(entry));
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    content.push_str(&entry);
+    content.push('\n');
+    fs::write(path, content)?;
+    Ok(Some(entry))
+}
+
+/// Rewrites the file without the entry's lines (a shared list's whether
+/// on or off), leaving the rest as written
+fn remove_from_file(path: &Path, entry: &str) -> Result<bool> {
+    let entry = Blocklist::normalize_entry(entry).ok_or_else(|| invalid_entry(entry))?;
+    let content = read_blocklist(path)?;
+    let kept: Vec<&str> = content
+        .lines()
+        .filter(|line| !Blocklist::normalize_entry(line).is_some_and(|e| same_rule(&e, &entry)))
+        .collect();
+    if kept.len() == content.lines().count() {
+        return Ok(false);
+    }
+    let mut content = kept.join("\n");
+    if !content.is_empty() {
+        content.push('\n');
+    }
+    fs::write(path, content)?;
+    Ok(true)
+}
+
+impl From<Blocklist> for SharedBlocklist {
+    fn from(blocklist: Blocklist) -> Self {
+        Self {
+            blocklist: Arc::new(RwLock::new(blocklist)),
+            generation: Arc::new(AtomicU64::new(0)),
+        }
+    }
+}
+
+/// Rewrites the file without rules that ran out before `today`
+fn remove_expired_from_file(path: &Path, today: NaiveDate) -> Result<usize> {
+    let content = read_blocklist(path)?;
+    let expired = |line: &&str| {
+        Blocklist::normalize_entry(line).is_some_and(|entry| split_until(&entry).1.is_some_and(|until| until < today))
+    };
+    let kept: Vec<&str> = content.lines().filter(|line| !expired(line)).collect();
+    let removed = content.lines().count() - kept.len();
+    if removed > 0 {
+        let mut content = kept.join("\n");
+        if !content.is_empty() {
+            content.push('\n');
+        }
+        fs::write(path, content)?;
+    }
+    Ok(removed)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -238,10 +972,7 @@ mod tests {
     #[test]
     fn test_contains_blocked_keyword_in_title() {
         // Create blocklist with "bitcoin" keyword
-        let mut blocklist = Blocklist {
-            keywords: HashSet::new(),
-            last_modified: None,
-        };
+        let mut blocklist = Blocklist::default();
         blocklist.keywords.insert("bitcoin".to_string());
 
         // Should match case-insensitively
@@ -255,10 +986,7 @@ mod tests {
 
     #[test]
     fn test_contains_blocked_keyword_in_content() {
-        let mut blocklist = Blocklist {
-            keywords: HashSet::new(),
-            last_modified: None,
-        };
+        let mut blocklist = Blocklist::default();
         blocklist.keywords.insert("crypto".to_string());
 
         // Should match in content even if not in title
@@ -276,10 +1004,7 @@ mod tests {
 
     #[test]
     fn test_word_boundary_respects_partial() {
-        let mut blocklist = Blocklist {
-            keywords: HashSet::new(),
-            last_modified: None,
-        };
+        let mut blocklist = Blocklist::default();
         blocklist.keywords.insert("crypto".to_string());
 
         // Should NOT match when keyword is part of a larger word
@@ -295,10 +1020,7 @@ mod tests {
 
     #[test]
     fn test_word_boundary_matches_whole() {
-        let mut blocklist = Blocklist {
-            keywords: HashSet::new(),
-            last_modified: None,
-        };
+        let mut blocklist = Blocklist::default();
         blocklist.keywords.insert("crypto".to_string());
 
         // Should match when keyword appears as whole word
@@ -313,14 +1035,203 @@ mod tests {
 
     #[test]
     fn test_empty_blocklist_matches_nothing() {
-        let blocklist = Blocklist {
-            keywords: HashSet::new(),
-            last_modified: None,
-        };
+        let blocklist = Blocklist::default();
 
         // Empty blocklist should never match
         assert!(!blocklist.contains_blocked_keyword("Bitcoin news", None));
         assert!(!blocklist.contains_blocked_keyword("Crypto markets", Some("cryptocurrency")));
         assert!(!blocklist.contains_blocked_keyword("Any content", Some("Any other content")));
     }
+
+    #[test]
+    fn test_shared_blocklist_clones_see_one_list() {
+        let shared = SharedBlocklist::from(Blocklist::default());
+        let fetcher_copy = shared.clone();
+        assert!(!fetcher_copy.blocks("Crypto news", None, "https://example.com/"));
+
+        shared.blocklist.write().unwrap().keywords.insert("crypto".to_string());
+        assert!(fetcher_copy.blocks("Crypto news", None, "https://example.com/"));
+        assert_eq!(fetcher_copy.rule_count(), 1);
+    }
+
+    #[test]
+    fn test_normalize_site_entries() {
+        assert_eq!(Blocklist::normalize_entry("Site:WWW.Example.com"), Some("site:example.com".to_string()));
+        assert_eq!(
+            Blocklist::normalize_entry("site:https://news.example.co.uk/story/1"),
+            Some("site:news.example.co.uk".to_string())
+        );
+        assert_eq!(Blocklist::normalize_entry("site:localhost"), None);
+        assert_eq!(Blocklist::normalize_entry("site:bad_host.com"), None);
+        assert_eq!(Blocklist::normalize_entry(" Crypto "), Some("crypto".to_string()));
+    }
+
+    #[test]
+    fn test_blocked_sites_include_subdomains() {
+        let mut blocklist = Blocklist::default();
+        blocklist.sites.insert("example.com".to_string());
+
+        assert!(blocklist.blocks("Anything", None, "https://example.com/a"));
+        assert!(blocklist.blocks("Anything", None, "https://blog.Example.com/a"));
+        assert!(!blocklist.blocks("Anything", None, "https://notexample.com/a"));
+        assert!(!blocklist.blocks("Anything", None, "not a url"));
+        assert_eq!(blocklist.entries(), ["site:example.com"]);
+    }
+
+    #[test]
+    fn test_add_and_remove_keep_other_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("beatcheck").join("blocklist.txt");
+
+        assert_eq!(add_to_file(&path, "Crypto").unwrap(), Some("crypto".to_string()));
+        assert_eq!(add_to_file(&path, "crypto").unwrap(), None);
+        assert!(add_to_file(&path, "no@good").is_err());
+
+        fs::write(&path, "# muted topics\nCrypto\nsite:example.com").unwrap();
+        assert_eq!(add_to_file(&path, "site:www.example.com").unwrap(), None);
+        assert_eq!(add_to_file(&path, "nft").unwrap(), Some("nft".to_string()));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "# muted topics\nCrypto\nsite:example.com\nnft\n");
+
+        assert!(remove_from_file(&path, "crypto").unwrap());
+        assert!(!remove_from_file(&path, "crypto").unwrap());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "# muted topics\nsite:example.com\nnft\n");
+    }
+
+    #[test]
+    fn test_include_lines_and_comments() {
+        assert_eq!(
+            Blocklist::normalize_entry("Include https://team.example/noise.txt"),
+            Some("include https://team.example/noise.txt".to_string())
+        );
+        assert_eq!(
+            Blocklist::normalize_entry("#include https://team.example/noise.txt"),
+            Some("# include https://team.example/noise.txt".to_string())
+        );
+        assert_eq!(Blocklist::normalize_entry("# noise from the sales team"), None);
+        assert_eq!(Blocklist::normalize_entry("include ftp://team.example/noise.txt"), None);
+
+        // A shared list can't pull in others
+        let shared = Blocklist::parse_shared("# team list\ncrypto\nsite:spam.example\ninclude https://more.example/x.txt\n");
+        assert_eq!(shared.entries(), ["crypto", "site:spam.example"]);
+    }
+
+    #[test]
+    fn test_disabled_subscriptions_dont_block() {
+        let mut team = Blocklist::default();
+        team.keywords.insert("crypto".to_string());
+        let mut blocklist = Blocklist::default();
+        blocklist.subscriptions.push(Subscription {
+            url: "https://team.example/noise.txt".to_string(),
+            enabled: true,
+            rules: team,
+            fetched: Some(SystemTime::now()),
+        });
+
+        assert!(blocklist.blocks("Crypto news", None, "https://example.com/"));
+        assert_eq!(blocklist.len(), 1);
+        assert!(!blocklist.subscriptions[0].is_due(Duration::from_secs(3600)));
+        assert!(blocklist.subscriptions[0].is_due(Duration::ZERO));
+
+        blocklist.subscriptions[0].enabled = false;
+        assert!(!blocklist.blocks("Crypto news", None, "https://example.com/"));
+        assert_eq!(blocklist.len(), 0);
+        assert!(!blocklist.subscriptions[0].is_due(Duration::ZERO));
+        assert_eq!(blocklist.entries(), ["# include https://team.example/noise.txt"]);
+    }
+
+    #[test]
+    fn test_toggling_a_subscription_keeps_its_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("blocklist.txt");
+        fs::write(&path, "crypto\ninclude https://team.example/noise.txt\nnft\n").unwrap();
+
+        let off = add_to_file(&path, "# include https://team.example/noise.txt").unwrap();
+        assert_eq!(off.as_deref(), Some("# include https://team.example/noise.txt"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "crypto\n# include https://team.example/noise.txt\nnft\n");
+
+        // Removing it takes the line whether on or off
+        assert!(remove_from_file(&path, "include https://team.example/noise.txt").unwrap());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "crypto\nnft\n");
+    }
+
+    #[test]
+    fn test_match_markers_normalize() {
+        assert_eq!(Blocklist::normalize_entry("~Elections"), Some("~elections".to_string()));
+        assert_eq!(Blocklist::normalize_entry("*Coin*"), Some("*coin*".to_string()));
+        assert_eq!(Blocklist::normalize_entry("crypto*"), Some("crypto*".to_string()));
+        assert_eq!(Blocklist::normalize_entry("~*coin"), None);
+        assert_eq!(Blocklist::normalize_entry("*"), None);
+        assert_eq!(KeywordMatch::split("*coin*"), (KeywordMatch::Substring, "coin"));
+        assert_eq!(KeywordMatch::split("*coin"), (KeywordMatch::Suffix, "coin"));
+        assert_eq!(KeywordMatch::split("ai"), (KeywordMatch::Word, "ai"));
+    }
+
+    #[test]
+    fn test_partial_and_stemmed_rules() {
+        let mut blocklist = Blocklist::default();
+        for keyword in ["ai", "crypto*", "*coin", "*nft*", "~election", "~vote"] {
+            blocklist.keywords.insert(keyword.to_string());
+        }
+
+        // Whole words by default: "ai" isn't "air"
+        assert!(!blocklist.contains_blocked_keyword("Air travel rebounds", None));
+        assert!(blocklist.contains_blocked_keyword("AI models", None));
+
+        assert!(blocklist.contains_blocked_keyword("Cryptocurrency slump", None));
+        assert!(blocklist.contains_blocked_keyword("Dogecoin rallies", None));
+        assert!(!blocklist.contains_blocked_keyword("Coinbase earnings", None));
+        assert!(blocklist.contains_blocked_keyword("Unftoken launch", None));
+
+        assert!(blocklist.contains_blocked_keyword("Midterm elections ahead", None));
+        assert!(blocklist.contains_blocked_keyword("The election's fallout", None));
+        assert!(!blocklist.contains_blocked_keyword("Electric cars", None));
+        assert!(blocklist.contains_blocked_keyword("Senate voted", None));
+        assert!(blocklist.contains_blocked_keyword("Voting rights", None));
+    }
+
+    #[test]
+    fn test_stem_inflections() {
+        assert_eq!(stem("elections"), stem("election"));
+        assert_eq!(stem("parties"), stem("party"));
+        assert_eq!(stem("running"), stem("run"));
+        assert_eq!(stem("voted"), stem("votes"));
+        assert_eq!(stem("taxes"), stem("tax"));
+        assert_ne!(stem("bus"), stem("bu"));
+        assert_eq!(stem("ai"), "ai");
+        // Not cut inside a character
+        assert_eq!(stem("丸丸丸ing"), "丸丸丸");
+        assert_eq!(stem("丸ing"), "丸ing");
+        assert_eq!(stem("cafés"), "café");
+    }
+
+    #[test]
+    fn test_temporary_rules() {
+        let today = NaiveDate::from_ymd_opt(2026, 6, 10).unwrap();
+        assert_eq!(
+            Blocklist::normalize_new_entry("World Cup for 30 days", today),
+            Some("world cup until:2026-07-10".to_string())
+        );
+        assert_eq!(
+            Blocklist::normalize_new_entry("site:fifa.example for 2w", today),
+            Some("site:fifa.example until:2026-06-24".to_string())
+        );
+        assert_eq!(Blocklist::normalize_entry("crypto until:someday"), None);
+        assert_eq!(Blocklist::normalize_entry("include https://team.example/x.txt until:2026-07-10"), None);
+
+        let blocklist = Blocklist::parse("world cup until:2099-07-10\nolympics until:2000-08-01\n");
+        assert!(blocklist.contains_blocked_keyword("World Cup final", None));
+        assert!(!blocklist.contains_blocked_keyword("Olympics open", None));
+        assert_eq!(blocklist.entries(), ["world cup until:2099-07-10"]);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("blocklist.txt");
+        fs::write(&path, "crypto\nworld cup until:2026-06-10\nolympics until:2026-06-09\n").unwrap();
+        assert_eq!(remove_expired_from_file(&path, today).unwrap(), 1);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "crypto\nworld cup until:2026-06-10\n");
+
+        // A new expiry replaces the old one in place, and removal ignores it
+        assert!(add_to_file(&path, "world cup until:2026-07-01").unwrap().is_some());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "crypto\nworld cup until:2026-07-01\n");
+        assert!(remove_from_file(&path, "World Cup").unwrap());
+    }
 }
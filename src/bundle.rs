@@ -0,0 +1,126 @@
+//! Travel bundles: the feeds plus only the unread and starred articles,
+//! with their text and summaries, as one JSON file to carry to another
+//! machine's database (a laptop before a flight)
+
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{AppError, Result};
+use crate::models::{Article, Feed, NewArticle, Summary};
+
+/// Bumped when a bundle written now couldn't be read by older versions
+pub const BUNDLE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bundle {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub feeds: Vec<Feed>,
+    pub articles: Vec<BundledArticle>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundledArticle {
+    #[serde(flatten)]
+    pub article: Article,
+    #[serde(default)]
+    pub summary: Option<Summary>,
+}
+
+impl Bundle {
+    pub fn new(feeds: Vec<Feed>, articles: Vec<BundledArticle>) -> Self {
+        Self {
+            version: BUNDLE_VERSION,
+            exported_at: Utc::now(),
+            feeds,
+            articles,
+        }
+    }
+
+    /// A bundle written by `write`, refusing ones from a newer version
+    pub fn read(path: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| AppError::Config(format!("Can't read bundle {}: {}", path.display(), e)))?;
+        let bundle: Bundle = serde_json::from_str(&json)
+            .map_err(|e| AppError::Config(format!("{} isn't a beatcheck bundle: {}", path.display(), e)))?;
+        if bundle.version > BUNDLE_VERSION {
+            return Err(AppError::Config(format!(
+                "{} was written by a newer beatcheck (bundle version {})",
+                path.display(),
+                bundle.version
+            )));
+        }
+        Ok(bundle)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+}
+
+/// The bundled article as one to store under `feed_id` on this machine.
+/// Bylines, mentions and links are worked out again by the next fetch.
+pub fn new_article(article: &Article, feed_id: i64) -> NewArticle {
+    NewArticle {
+        feed_id,
+        guid: article.guid.clone(),
+        title: article.title.clone(),
+        url: article.url.clone(),
+        author: article.author.clone(),
+        content: article.content.clone(),
+        content_text: article.content_text.clone(),
+        published_at: article.published_at,
+        comments_url: article.comments_url.clone(),
+        enclosure: article.enclosure.clone(),
+        extras: article.extras.clone(),
+        tags: article.tags.clone(),
+        entities: Vec::new(),
+        authors: Vec::new(),
+        links: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn article() -> Article {
+        serde_json::from_value(serde_json::json!({
+            "id": 7, "feed_id": 1, "guid": "g", "title": "Chips", "url": "https://example.com/a",
+            "author": null, "content": "<p>Fabs</p>", "content_text": "Fabs", "published_at": null,
+            "fetched_at": "2026-03-02T10:00:00Z", "feed_title": "Wire", "is_starred": true,
+            "is_pinned": false, "reading_minutes": null, "is_queued": false, "is_read": false,
+            "full_text": "Fabs expand", "comments_url": null, "discussion": null, "enclosure": null,
+            "playback_secs": null, "is_played": false, "extras": null, "archive_path": null,
+            "tags": ["chips"]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_bundle_reads_back_and_refuses_newer_versions() {
+        let dir = std::env::temp_dir().join(format!("beatcheck-bundle-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("trip.json");
+
+        let bundled = BundledArticle {
+            article: article(),
+            summary: None,
+        };
+        Bundle::new(Vec::new(), vec![bundled]).write(&path).unwrap();
+        let bundle = Bundle::read(&path).unwrap();
+        assert_eq!(bundle.version, BUNDLE_VERSION);
+        let article = &bundle.articles[0].article;
+        assert_eq!((article.id, article.full_text.as_deref()), (7, Some("Fabs expand")));
+        assert!(article.is_starred);
+
+        let mut newer = bundle;
+        newer.version = BUNDLE_VERSION + 1;
+        newer.write(&path).unwrap();
+        assert!(Bundle::read(&path).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
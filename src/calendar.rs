@@ -0,0 +1,139 @@
+//! Articles per day laid out as a calendar heatmap, overall or for one
+//! feed, for seeing when a story broke or a feed went quiet.
+
+use chrono::{Datelike, Duration, Local, NaiveDate};
+
+use crate::db::Repository;
+use crate::error::Result;
+
+/// Weeks shown in the calendar, ending with this one
+pub const CALENDAR_WEEKS: usize = 26;
+
+/// Shades from a quiet day to the busiest one
+const SHADES: [char; 4] = ['░', '▒', '▓', '█'];
+
+/// Article counts for every day from the Monday `weeks` weeks back through
+/// today, with a day picked out
+#[derive(Debug, Clone, PartialEq)]
+pub struct Calendar {
+    /// The feed counted (id, title), or every feed
+    pub feed: Option<(i64, String)>,
+    /// Monday of the first week shown
+    pub start: NaiveDate,
+    pub today: NaiveDate,
+    /// One per day from `start` through `today`
+    pub counts: Vec<usize>,
+    pub selected: NaiveDate,
+}
+
+impl Calendar {
+    /// Lay out `counts` ((day, articles), in any order) over the `weeks`
+    /// weeks ending with `today`'s, with `today` picked
+    pub fn build(feed: Option<(i64, String)>, counts: &[(NaiveDate, usize)], today: NaiveDate, weeks: usize) -> Self {
+        let monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+        let start = monday - Duration::weeks(weeks.saturating_sub(1) as i64);
+        let mut days = vec![0; (today - start).num_days() as usize + 1];
+        for (day, articles) in counts {
+            if let Some(count) = usize::try_from((*day - start).num_days()).ok().and_then(|i| days.get_mut(i)) {
+                *count += articles;
+            }
+        }
+        Self {
+            feed,
+            start,
+            today,
+            counts: days,
+            selected: today,
+        }
+    }
+
+    /// Articles on `day`; 0 outside the calendar
+    pub fn count(&self, day: NaiveDate) -> usize {
+        usize::try_from((day - self.start).num_days())
+            .ok()
+            .and_then(|i| self.counts.get(i).copied())
+            .unwrap_or(0)
+    }
+
+    pub fn weeks(&self) -> usize {
+        self.counts.len().div_ceil(7)
+    }
+
+    /// The day in `week` (from 0) and `weekday` (0 is Monday), if it's not
+    /// after today
+    pub fn day_at(&self, week: usize, weekday: usize) -> Option<NaiveDate> {
+        let day = self.start + Duration::days((week * 7 + weekday) as i64);
+        (day <= self.today).then_some(day)
+    }
+
+    /// How busy `day` was: `·` for none, then four shades up to the busiest
+    pub fn shade(&self, day: NaiveDate) -> char {
+        let max = self.counts.iter().copied().max().unwrap_or(0);
+        match self.count(day) {
+            0 => '·',
+            count => SHADES[((count - 1) * SHADES.len() / max).min(SHADES.len() - 1)],
+        }
+    }
+
+    /// Move the picked day by `days`, staying inside the calendar
+    pub fn move_by(&mut self, days: i64) {
+        let day = self.selected + Duration::days(days);
+        if day >= self.start && day <= self.today {
+            self.selected = day;
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+}
+
+/// The calendar of the last `weeks` weeks, for one feed or all of them
+pub async fn load_calendar(repository: &Repository, feed: Option<(i64, String)>, weeks: usize) -> Result<Calendar> {
+    let today = Local::now().date_naive();
+    let calendar = Calendar::build(feed, &[], today, weeks);
+    let counts = repository
+        .articles_per_day(calendar.feed.as_ref().map(|(id, _)| *id), calendar.start)
+        .await?;
+    Ok(Calendar::build(calendar.feed, &counts, today, weeks))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, d).unwrap()
+    }
+
+    #[test]
+    fn test_calendar_starts_on_monday_and_places_days() {
+        // 2026-03-11 is a Wednesday
+        let counts = vec![(day(11), 8), (day(2), 2), (day(2), 1), (day(1), 5)];
+        let calendar = Calendar::build(None, &counts, day(11), 2);
+        assert_eq!(calendar.start, day(2));
+        assert_eq!(calendar.counts.len(), 10);
+        assert_eq!((calendar.count(day(2)), calendar.count(day(1))), (3, 0));
+        assert_eq!(calendar.weeks(), 2);
+        assert_eq!(calendar.day_at(1, 2), Some(day(11)));
+        assert_eq!(calendar.day_at(1, 3), None);
+        assert_eq!(calendar.total(), 11);
+    }
+
+    #[test]
+    fn test_shades_scale_to_busiest_day_and_moves_stay_inside() {
+        let counts = vec![(day(2), 1), (day(3), 4), (day(4), 8)];
+        let mut calendar = Calendar::build(None, &counts, day(11), 2);
+        assert_eq!(calendar.shade(day(5)), '·');
+        assert_eq!(calendar.shade(day(2)), '░');
+        assert_eq!(calendar.shade(day(3)), '▒');
+        assert_eq!(calendar.shade(day(4)), '█');
+
+        calendar.move_by(1);
+        assert_eq!(calendar.selected, day(11));
+        calendar.move_by(-7);
+        assert_eq!(calendar.selected, day(4));
+        calendar.move_by(-7);
+        assert_eq!(calendar.selected, day(4));
+    }
+}
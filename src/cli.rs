@@ -0,0 +1,1225 @@
+//! Argument parsing and output for `beatcheck feeds ...`, `beatcheck watch ...`,
+//! `beatcheck authors ...`, `beatcheck tags ...`, `beatcheck autotag ...`,
+//! `beatcheck collections ...`, `beatcheck bundle ...`, `beatcheck backup ...`, `beatcheck summarize ...`,
+//! `beatcheck fulltext ...` and `beatcheck db ...`
+
+use std::path::PathBuf;
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::blocklist::SubscriptionStatus;
+use crate::db::{AuditFilter, DbStats, FeedBulkUpdate, FeedRemoval, Query, TableSize};
+use crate::error::{AppError, Result};
+use crate::export::PacketFormat;
+use crate::feed::OpmlHead;
+use crate::models::{
+    is_reader_view_template, ApiToken, AuditEvent, Feed, FeedPriority, ReadingMode, SourceInfo, TagRule, TokenScope,
+};
+use crate::watchlist::{Timeline, TIMELINE_DAYS};
+
+pub const FEEDS_USAGE: &str = "\
+Usage:
+  beatcheck feeds list [--by-volume] [--unread] [--search TEXT]
+  beatcheck feeds bulk [SELECT...] [CHANGE...]
+  beatcheck feeds export FILE [SELECT...] [--title TEXT] [--owner NAME]
+                     [--owner-email EMAIL]
+  beatcheck feeds archived
+  beatcheck feeds restore ID
+  beatcheck feeds move ID
+
+`feeds list --search TEXT` lists feeds whose title, URL, beat, contact
+or notes contain TEXT. `feeds export` writes the selected feeds (every
+feed if none are selected) as OPML, grouped by folder; the owner defaults
+to [export] owner_name and owner_email. When a feed's URL answers 404
+three times in a row, its site is searched for the feed's new address,
+shown as [moved? URL] by `feeds list`; `feeds move` switches to it.
+[repaired] marks feeds whose last fetch was malformed XML (bare &s, HTML
+entities, control characters) that only parsed once fixed up.
+
+Select feeds (combined with AND):
+  ID[,ID...]          feed ids (see `feeds list`)
+  --all               every feed
+  --match TEXT        title or URL contains TEXT
+  --in-folder NAME    feeds in folder NAME
+  --tagged TAG        feeds tagged TAG
+
+Changes (applied in one transaction):
+  --folder NAME       move to folder NAME
+  --no-folder         take out of their folder
+  --tag TAG           add a tag (repeatable)
+  --untag TAG         remove a tag (repeatable)
+  --interval MINUTES  fetch at most every MINUTES (0: global interval)
+  --pause / --resume  stop or restart refreshing
+  --reading MODE      show articles in full, as teasers (first
+                      paragraph) or as headlines only
+  --priority LEVEL    high (sorted up, highlighted, announced),
+                      normal or low (sorted down, dimmed, quiet)
+  --beat TEXT         what the source covers
+  --contact TEXT      who to ask there
+  --reliability N     1 (doubtful) to 5 (rock solid), 0 to clear
+  --notes TEXT        free-form notes on the source
+                      (an empty TEXT clears the field)
+  --reader-url URL    fetch and open articles through a reader view,
+                      e.g. https://r.example/{url} ({url_encoded} for a
+                      query string; empty goes direct again)
+  --delete            unsubscribe, deleting every article
+  --delete-keep-starred
+                      unsubscribe, moving starred articles to \"Orphaned\"
+  --archive           hide the feeds but keep their articles";
+
+pub const WATCH_USAGE: &str = "\
+Usage:
+  beatcheck watch [list] [--days N]   coverage per day of every watched entity
+  beatcheck watch add LIST NAME...    watch people, companies or tickers
+  beatcheck watch remove LIST [NAME...]
+                                      stop watching NAMEs, or drop LIST
+
+Quote names with spaces (\"Jensen Huang\"); tickers go without the $.";
+
+pub const AUTHORS_USAGE: &str = "\
+Usage:
+  beatcheck authors [list]           followed and muted authors
+  beatcheck authors follow NAME...   mark and announce their articles
+  beatcheck authors mute NAME...     drop their articles, in every feed
+  beatcheck authors clear NAME...    forget the rule
+
+Names match bylines case-insensitively; quote names with spaces.";
+
+pub const BLOCKLIST_USAGE: &str = "\
+Usage:
+  beatcheck blocklist [list]          keywords and sites whose articles are dropped
+  beatcheck blocklist add ENTRY...    block a keyword (whole words, any case; crypto*,
+                                      *coin or *nft* for part of a word, ~election
+                                      for any form of it) or site:example.com
+                                      (subdomains too); end it with \"for 30 days\"
+                                      (or weeks, months) to have it run out
+  beatcheck blocklist remove ENTRY...
+  beatcheck blocklist subscribe URL   merge in a shared list, downloaded again
+                                      every [blocklist] refresh_hours
+  beatcheck blocklist unsubscribe URL
+  beatcheck blocklist enable|disable URL
+                                      turn a shared list on or off
+  beatcheck blocklist refresh         download every shared list now
+
+Quote keywords with spaces. The rules live in blocklist.txt; a running
+beatcheck picks up changes within seconds. Mute authors with beatcheck authors.";
+
+pub const TAGS_USAGE: &str = "\
+Usage:
+  beatcheck tags [list]               article tags with how many articles use each
+  beatcheck tags rename OLD NEW       rename a tag (merging into NEW if it exists)
+  beatcheck tags merge INTO FROM...   fold FROM tags into INTO
+  beatcheck tags delete TAG...        take tags off every article
+
+Each change applies to every article in one transaction.";
+
+/// What `beatcheck tags` was asked to do
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TagCommand {
+    List,
+    /// Retag articles tagged with any of `from` as `into`
+    Merge { from: Vec<String>, into: String },
+    Delete(Vec<String>),
+}
+
+pub const COLLECTIONS_USAGE: &str = "\
+Usage:
+  beatcheck collections [list]                  collections with their article counts
+  beatcheck collections show NAME               a collection's articles, in order
+  beatcheck collections add NAME ID...          append articles (creating NAME)
+  beatcheck collections remove NAME [ID...]     take articles out, or drop NAME
+  beatcheck collections move NAME ID POSITION   reorder (positions count from 1)
+  beatcheck collections export NAME [--format md|html|epub]
+                                                one document with summaries and notes
+
+Quote names with spaces (\"Tuesday column research\").";
+
+/// What `beatcheck collections` was asked to do
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CollectionCommand {
+    List,
+    Show(String),
+    Add(String, Vec<i64>),
+    /// No ids drops the collection
+    Remove(String, Vec<i64>),
+    Move { name: String, id: i64, position: usize },
+    Export(String, PacketFormat),
+}
+
+pub const URLS_USAGE: &str = "\
+Usage:
+  beatcheck urls [sync]    sync the subscriptions with the urls_file setting now
+  beatcheck urls export    print the subscriptions as a urls file, to start one";
+
+pub const SUBSCRIPTIONS_USAGE: &str = "\
+Usage:
+  beatcheck subscriptions [check]  how the feeds differ from [[subscriptions]] in the config
+  beatcheck subscriptions sync     add, rename, file and tag feeds to match now
+  beatcheck subscriptions prune    archive feeds [[subscriptions]] doesn't list";
+
+pub const LINKS_USAGE: &str = "\
+Usage:
+  beatcheck links SITE|URL         articles linking to a site (and its subdomains)
+                                   or to a page (and pages under it)
+  beatcheck links --from ID        where an article links to
+  beatcheck links --top [--days N] sites linked from the most articles (default 7 days)";
+
+/// What `beatcheck links` was asked to do
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinksCommand {
+    To(String),
+    From(i64),
+    Top(usize),
+}
+
+pub const BUNDLE_USAGE: &str = "\
+Usage:
+  beatcheck bundle export FILE [--days N]  feeds plus unread (fetched in the last N days)
+                                           and starred articles, for another machine
+  beatcheck bundle import FILE             add a bundle's feeds and articles here";
+
+/// What `beatcheck bundle` was asked to do
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BundleCommand {
+    Export(PathBuf, Option<u32>),
+    Import(PathBuf),
+}
+
+pub const BACKUP_USAGE: &str = "\
+Usage:
+  beatcheck backup export FILE  settings (without API keys and tokens), feeds and every
+                                article with its read/starred state, tags, note and summary
+  beatcheck backup import FILE  restore a backup here, alongside what's already stored
+
+Settings are restored to config.toml, or beside it as config.restored.toml
+when there is one already.";
+
+/// What `beatcheck backup` was asked to do
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BackupCommand {
+    Export(PathBuf),
+    Import(PathBuf),
+}
+
+pub const TOKEN_USAGE: &str = "\
+Usage:
+  beatcheck token create --scope read|read-write [--user NAME] [--name LABEL]
+                          an API token for the server (--user may be left out
+                          when there is one account); shown only once
+  beatcheck token [list]  tokens with their scope and when each was last used
+  beatcheck token revoke ID...
+
+Read tokens may only read: GET requests and GraphQL queries.";
+
+/// What `beatcheck token` was asked to do
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenCommand {
+    Create {
+        scope: TokenScope,
+        user: Option<String>,
+        name: Option<String>,
+    },
+    List,
+    Revoke(Vec<i64>),
+}
+
+pub const AUTOTAG_USAGE: &str = "\
+Usage:
+  beatcheck autotag [list]             rules with how many articles each has tagged
+  beatcheck autotag add TAG QUERY...   tag new articles matching a search
+  beatcheck autotag enable ID...
+  beatcheck autotag disable ID...
+  beatcheck autotag remove ID...       (tags already added stay)
+
+QUERY is in the search language, e.g.
+  beatcheck autotag add vuln 'folder:Security re:\"CVE-\\d+\"'";
+
+/// What `beatcheck autotag` was asked to do
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AutotagCommand {
+    List,
+    Add { tag: String, query: String },
+    SetEnabled(Vec<i64>, bool),
+    Remove(Vec<i64>),
+}
+
+pub const SUMMARIZE_USAGE: &str = "\
+Usage:
+  beatcheck summarize SELECT... [--jobs N]
+  beatcheck summarize --failed [--jobs N]
+
+Summarizes every unread article without a summary in the selected feeds
+(ids, --all, --match TEXT, --in-folder NAME, --tagged TAG, as for
+`feeds bulk`), N at a time (default: [summaries] concurrency).
+--failed retries every article whose summary failed instead.";
+
+/// What `beatcheck summarize` was asked to do
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SummarizeArgs {
+    pub selector: FeedSelector,
+    /// Retry failed summaries rather than summarize the selected feeds
+    pub failed: bool,
+    pub jobs: Option<usize>,
+}
+
+pub const FULLTEXT_USAGE: &str = "\
+Usage:
+  beatcheck fulltext [SELECT...] [--jobs N]
+
+Fetches the page text of every unread article that doesn't have it yet in
+the selected feeds (ids, --all, --match TEXT, --in-folder NAME, --tagged TAG,
+as for `feeds bulk`), N at a time (default: 4) and one request per site at
+once. Articles an interrupted run didn't get to go first; with no
+selection, only those are fetched.";
+
+pub const DB_USAGE: &str = "\
+Usage:
+  beatcheck db stats       the database's size, and each table's rows, size and growth
+  beatcheck db maintain    vacuum free pages, update the query planner's statistics
+                           and record table sizes (daemon mode does this every
+                           [maintenance] interval_hours)";
+
+pub const AUDIT_USAGE: &str = "\
+Usage:
+  beatcheck audit [ACTION] [--search TEXT] [--days N] [--limit N]
+
+Feeds added and removed, bulk read and delete, imports and what was sent to
+Raindrop, newest first. ACTION narrows to feed, feed.remove, articles, import,
+push and so on; --search matches a URL, title or file.";
+
+/// Entries `beatcheck audit` shows without `--limit`
+pub const AUDIT_LIMIT: usize = 50;
+
+/// Parse the arguments after `audit` into a filter and how many entries to show
+pub fn parse_audit_args(args: &[String]) -> Result<(AuditFilter, usize)> {
+    let mut filter = AuditFilter::default();
+    let mut limit = AUDIT_LIMIT;
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        let mut value = |flag: &str| {
+            rest.next()
+                .cloned()
+                .ok_or_else(|| AppError::Config(format!("{} needs a value", flag)))
+        };
+        match arg.as_str() {
+            "--search" => filter.text = Some(value(arg)?),
+            "--days" => {
+                let days: i64 = value(arg)?
+                    .parse()
+                    .ok()
+                    .filter(|&d| d > 0)
+                    .ok_or_else(|| AppError::Config("--days needs a number above 0".to_string()))?;
+                filter.since = Some(Utc::now() - chrono::Duration::days(days));
+            }
+            "--limit" => {
+                limit = value(arg)?
+                    .parse()
+                    .ok()
+                    .filter(|&n| n > 0)
+                    .ok_or_else(|| AppError::Config("--limit needs a number above 0".to_string()))?
+            }
+            action if !action.starts_with("--") && filter.action.is_none() => filter.action = Some(action.to_string()),
+            _ => return Err(AppError::Config(AUDIT_USAGE.to_string())),
+        }
+    }
+    Ok((filter, limit))
+}
+
+/// One line per entry for `beatcheck audit`, in local time
+pub fn format_audit_event(event: &AuditEvent) -> String {
+    let at = event.at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M");
+    let mut line = format!("{}  {:<16}", at, event.action);
+    for part in [&event.subject, &event.detail].into_iter().flatten() {
+        line.push_str("  ");
+        line.push_str(part);
+    }
+    line
+}
+
+/// Parallel fetches `beatcheck fulltext` makes without `--jobs`
+pub const FULLTEXT_JOBS: usize = 4;
+
+/// What `beatcheck fulltext` was asked to do
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FullTextArgs {
+    pub selector: FeedSelector,
+    pub jobs: Option<usize>,
+}
+
+/// What `feeds export` was asked to write
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OpmlExportArgs {
+    pub path: PathBuf,
+    pub selector: FeedSelector,
+    pub head: OpmlHead,
+}
+
+/// Which feeds a bulk operation applies to
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeedSelector {
+    pub ids: Vec<i64>,
+    pub all: bool,
+    pub matching: Option<String>,
+    pub folder: Option<String>,
+    pub tag: Option<String>,
+}
+
+impl FeedSelector {
+    fn is_empty(&self) -> bool {
+        self.ids.is_empty() && !self.all && self.matching.is_none() && self.folder.is_none() && self.tag.is_none()
+    }
+
+    pub fn select<'a>(&self, feeds: &'a [Feed]) -> Vec<&'a Feed> {
+        let matching = self.matching.as_ref().map(|text| text.to_lowercase());
+        feeds
+            .iter()
+            .filter(|f| self.ids.is_empty() || self.ids.contains(&f.id))
+            .filter(|f| {
+                matching.as_ref().is_none_or(|text| {
+                    f.display_title().to_lowercase().contains(text) || f.url.to_lowercase().contains(text)
+                })
+            })
+            .filter(|f| self.folder.as_ref().is_none_or(|folder| f.folder.as_ref() == Some(folder)))
+            .filter(|f| self.tag.as_ref().is_none_or(|tag| f.tags.contains(tag)))
+            .collect()
+    }
+}
+
+/// Parse the arguments after `feeds bulk`
+pub fn parse_bulk_args(args: &[String]) -> Result<(FeedSelector, FeedBulkUpdate)> {
+    let mut selector = FeedSelector::default();
+    let mut update = FeedBulkUpdate::default();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        if parse_selector_arg(&mut selector, arg, &mut args)? {
+            continue;
+        }
+        let mut value = |flag: &str| {
+            args.next()
+                .cloned()
+                .ok_or_else(|| AppError::Config(format!("{} needs a value", flag)))
+        };
+        match arg.as_str() {
+            "--folder" => update.folder = Some(Some(value(arg)?).filter(|f| !f.trim().is_empty())),
+            "--no-folder" => update.folder = Some(None),
+            "--tag" => update.add_tags.push(normalize_tag(&value(arg)?)?),
+            "--untag" => update.remove_tags.push(normalize_tag(&value(arg)?)?),
+            "--interval" => {
+                let minutes: u32 = value(arg)?
+                    .parse()
+                    .map_err(|_| AppError::Config("--interval needs a number of minutes".to_string()))?;
+                update.refresh_minutes = Some((minutes > 0).then_some(minutes));
+            }
+            "--pause" => update.paused = Some(true),
+            "--resume" => update.paused = Some(false),
+            "--reading" => {
+                let mode = value(arg)?;
+                update.reading = Some(ReadingMode::parse(&mode).ok_or_else(|| {
+                    AppError::Config(format!("--reading is full, teaser or headlines, not {}", mode))
+                })?);
+            }
+            "--priority" => {
+                let level = value(arg)?;
+                update.priority = Some(FeedPriority::parse(&level).ok_or_else(|| {
+                    AppError::Config(format!("--priority is high, normal or low, not {}", level))
+                })?);
+            }
+            "--beat" => update.beat = Some(non_empty(value(arg)?)),
+            "--contact" => update.contact = Some(non_empty(value(arg)?)),
+            "--notes" => update.notes = Some(non_empty(value(arg)?)),
+            "--reader-url" => {
+                let template = non_empty(value(arg)?);
+                if template.as_deref().is_some_and(|t| !is_reader_view_template(t)) {
+                    return Err(AppError::Config(
+                        "--reader-url needs an http(s) URL with {url} or {url_encoded} in it".to_string(),
+                    ));
+                }
+                update.reader_url = Some(template);
+            }
+            "--reliability" => {
+                let rating: u8 = value(arg)?
+                    .parse()
+                    .ok()
+                    .filter(|&r| r <= SourceInfo::MAX_RELIABILITY)
+                    .ok_or_else(|| AppError::Config("--reliability needs a number from 0 to 5".to_string()))?;
+                update.reliability = Some((rating > 0).then_some(rating));
+            }
+            "--delete" => update.remove = Some(FeedRemoval::DeleteAll),
+            "--delete-keep-starred" => update.remove = Some(FeedRemoval::KeepStarred),
+            "--archive" => update.remove = Some(FeedRemoval::Archive),
+            other => return Err(AppError::Config(format!("Unknown option {}", other))),
+        }
+    }
+
+    check_selected(&selector)?;
+    if update == FeedBulkUpdate::default() {
+        return Err(AppError::Config("Nothing to change".to_string()));
+    }
+    Ok((selector, update))
+}
+
+fn non_empty(text: String) -> Option<String> {
+    Some(text.trim().to_string()).filter(|t| !t.is_empty())
+}
+
+/// Parse the arguments after `summarize`: feeds to take unread articles
+/// from (or `--failed`), and `--jobs N` when given
+pub fn parse_summarize_args(args: &[String]) -> Result<SummarizeArgs> {
+    let mut parsed = SummarizeArgs::default();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        if parse_selector_arg(&mut parsed.selector, arg, &mut args)? {
+            continue;
+        }
+        match arg.as_str() {
+            "--failed" => parsed.failed = true,
+            "--jobs" => parsed.jobs = Some(parse_jobs(args.next())?),
+            other => return Err(AppError::Config(format!("Unknown option {}", other))),
+        }
+    }
+
+    if parsed.failed {
+        if parsed.selector != FeedSelector::default() {
+            return Err(AppError::Config("--failed doesn't take a feed selection".to_string()));
+        }
+    } else {
+        check_selected(&parsed.selector)?;
+    }
+    Ok(parsed)
+}
+
+/// Parse the arguments after `fulltext`: feeds to take unread articles
+/// from (none to only finish an interrupted run), and `--jobs N`
+pub fn parse_fulltext_args(args: &[String]) -> Result<FullTextArgs> {
+    let mut parsed = FullTextArgs::default();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        if parse_selector_arg(&mut parsed.selector, arg, &mut args)? {
+            continue;
+        }
+        match arg.as_str() {
+            "--jobs" => parsed.jobs = Some(parse_jobs(args.next())?),
+            other => return Err(AppError::Config(format!("Unknown option {}", other))),
+        }
+    }
+    Ok(parsed)
+}
+
+/// Parse the arguments after `feeds export`: the file, then the feeds to
+/// include (all of them if none are selected) and what the head says
+pub fn parse_opml_export_args(args: &[String]) -> Result<OpmlExportArgs> {
+    let (path, rest) = args
+        .split_first()
+        .filter(|(path, _)| !path.starts_with("--"))
+        .ok_or_else(|| AppError::Config("feeds export needs a file to write".to_string()))?;
+    let mut parsed = OpmlExportArgs {
+        path: PathBuf::from(path),
+        ..Default::default()
+    };
+    let mut rest = rest.iter();
+
+    while let Some(arg) = rest.next() {
+        if parse_selector_arg(&mut parsed.selector, arg, &mut rest)? {
+            continue;
+        }
+        let mut value = |flag: &str| {
+            rest.next()
+                .cloned()
+                .ok_or_else(|| AppError::Config(format!("{} needs a value", flag)))
+        };
+        match arg.as_str() {
+            "--title" => parsed.head.title = Some(value(arg)?),
+            "--owner" => parsed.head.owner_name = Some(value(arg)?),
+            "--owner-email" => parsed.head.owner_email = Some(value(arg)?),
+            other => return Err(AppError::Config(format!("Unknown option {}", other))),
+        }
+    }
+    Ok(parsed)
+}
+
+fn parse_jobs(value: Option<&String>) -> Result<usize> {
+    value
+        .and_then(|n| n.parse().ok())
+        .filter(|&n| n > 0)
+        .ok_or_else(|| AppError::Config("--jobs needs a number above 0".to_string()))
+}
+
+/// Apply `arg` to `selector` if it's a feed id list or selection flag,
+/// taking its value from `rest`. Returns false for anything else.
+fn parse_selector_arg<'a>(
+    selector: &mut FeedSelector,
+    arg: &str,
+    rest: &mut impl Iterator<Item = &'a String>,
+) -> Result<bool> {
+    let mut value = |flag: &str| {
+        rest.next()
+            .cloned()
+            .ok_or_else(|| AppError::Config(format!("{} needs a value", flag)))
+    };
+    match arg {
+        "--all" => selector.all = true,
+        "--match" => selector.matching = Some(value(arg)?),
+        "--in-folder" => selector.folder = Some(value(arg)?),
+        "--tagged" => selector.tag = Some(normalize_tag(&value(arg)?)?),
+        ids if !ids.starts_with("--") => {
+            for id in ids.split(',').filter(|id| !id.is_empty()) {
+                let id = id
+                    .parse()
+                    .map_err(|_| AppError::Config(format!("Invalid feed id: {}", id)))?;
+                selector.ids.push(id);
+            }
+        }
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+fn check_selected(selector: &FeedSelector) -> Result<()> {
+    if selector.is_empty() {
+        return Err(AppError::Config(
+            "Select feeds with ids, --match, --in-folder, --tagged or --all".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Parse the arguments after `tags`
+pub fn parse_tags_args(args: &[String]) -> Result<TagCommand> {
+    let tags = |args: &[String]| args.iter().map(|t| normalize_tag(t)).collect::<Result<Vec<_>>>();
+    match args.first().map(String::as_str) {
+        None | Some("list") if args.len() <= 1 => Ok(TagCommand::List),
+        Some("rename") if args.len() == 3 => {
+            let mut names = tags(&args[1..])?;
+            let into = names.pop().unwrap();
+            Ok(TagCommand::Merge { from: names, into })
+        }
+        Some("merge") if args.len() >= 3 => {
+            let mut names = tags(&args[1..])?;
+            let into = names.remove(0);
+            Ok(TagCommand::Merge { from: names, into })
+        }
+        Some("delete") if args.len() >= 2 => Ok(TagCommand::Delete(tags(&args[1..])?)),
+        _ => Err(AppError::Config(TAGS_USAGE.to_string())),
+    }
+}
+
+/// Parse the arguments after `collections`
+pub fn parse_collections_args(args: &[String]) -> Result<CollectionCommand> {
+    let usage = || AppError::Config(COLLECTIONS_USAGE.to_string());
+    let ids = |args: &[String]| -> Result<Vec<i64>> {
+        args.iter()
+            .map(|id| id.parse().map_err(|_| AppError::Config(format!("Not an article id: {}", id))))
+            .collect()
+    };
+    match args.first().map(String::as_str) {
+        None | Some("list") if args.len() <= 1 => Ok(CollectionCommand::List),
+        Some("show") if args.len() == 2 => Ok(CollectionCommand::Show(args[1].clone())),
+        Some("add") if args.len() >= 3 => Ok(CollectionCommand::Add(args[1].clone(), ids(&args[2..])?)),
+        Some("remove") if args.len() >= 2 => Ok(CollectionCommand::Remove(args[1].clone(), ids(&args[2..])?)),
+        Some("move") if args.len() == 4 => {
+            let id = ids(&args[2..3])?[0];
+            let position = args[3]
+                .parse()
+                .ok()
+                .filter(|p| *p > 0)
+                .ok_or_else(|| AppError::Config(format!("Not a position: {}", args[3])))?;
+            Ok(CollectionCommand::Move { name: args[1].clone(), id, position })
+        }
+        Some("export") if args.len() == 2 => Ok(CollectionCommand::Export(args[1].clone(), PacketFormat::Markdown)),
+        Some("export") if args.len() == 4 && args[2] == "--format" => {
+            Ok(CollectionCommand::Export(args[1].clone(), PacketFormat::parse(&args[3])?))
+        }
+        _ => Err(usage()),
+    }
+}
+
+pub fn parse_links_args(args: &[String]) -> Result<LinksCommand> {
+    let usage = || AppError::Config(LINKS_USAGE.to_string());
+    match args.first().map(String::as_str) {
+        Some("--from") if args.len() == 2 => args[1]
+            .parse()
+            .map(LinksCommand::From)
+            .map_err(|_| AppError::Config(format!("Not an article id: {}", args[1]))),
+        Some("--top") if args.len() == 1 => Ok(LinksCommand::Top(7)),
+        Some("--top") if args.len() == 3 && args[1] == "--days" => args[2]
+            .parse()
+            .ok()
+            .filter(|&days| days > 0)
+            .map(LinksCommand::Top)
+            .ok_or_else(|| AppError::Config("--days needs a number of days".to_string())),
+        Some(target) if args.len() == 1 && !target.starts_with("--") => Ok(LinksCommand::To(target.to_string())),
+        _ => Err(usage()),
+    }
+}
+
+/// Parse the arguments after `bundle`
+pub fn parse_bundle_args(args: &[String]) -> Result<BundleCommand> {
+    let usage = || AppError::Config(BUNDLE_USAGE.to_string());
+    match (args.first().map(String::as_str), args.get(1)) {
+        (Some("import"), Some(path)) if args.len() == 2 => Ok(BundleCommand::Import(PathBuf::from(path))),
+        (Some("export"), Some(path)) if args.len() == 2 => Ok(BundleCommand::Export(PathBuf::from(path), None)),
+        (Some("export"), Some(path)) if args.len() == 4 && args[2] == "--days" => args[3]
+            .parse()
+            .ok()
+            .filter(|&days| days > 0)
+            .map(|days| BundleCommand::Export(PathBuf::from(path), Some(days)))
+            .ok_or_else(|| AppError::Config("--days needs a number of days".to_string())),
+        _ => Err(usage()),
+    }
+}
+
+/// Parse the arguments after `backup`
+pub fn parse_backup_args(args: &[String]) -> Result<BackupCommand> {
+    match (args.first().map(String::as_str), args.get(1)) {
+        (Some("export"), Some(path)) if args.len() == 2 => Ok(BackupCommand::Export(PathBuf::from(path))),
+        (Some("import"), Some(path)) if args.len() == 2 => Ok(BackupCommand::Import(PathBuf::from(path))),
+        _ => Err(AppError::Config(BACKUP_USAGE.to_string())),
+    }
+}
+
+/// Parse the arguments after `token`. Creating needs a scope, so a
+/// read-write token is never made by accident.
+pub fn parse_token_args(args: &[String]) -> Result<TokenCommand> {
+    let usage = || AppError::Config(TOKEN_USAGE.to_string());
+    match args.first().map(String::as_str) {
+        None | Some("list") if args.len() <= 1 => Ok(TokenCommand::List),
+        Some("create") => {
+            let (mut scope, mut user, mut name) = (None, None, None);
+            let mut rest = args[1..].iter();
+            while let Some(arg) = rest.next() {
+                let value = rest
+                    .next()
+                    .cloned()
+                    .ok_or_else(|| AppError::Config(format!("{} needs a value", arg)))?;
+                match arg.as_str() {
+                    "--scope" => {
+                        scope = Some(TokenScope::parse(&value).ok_or_else(|| {
+                            AppError::Config(format!("Unknown scope {} (read or read-write)", value))
+                        })?)
+                    }
+                    "--user" => user = Some(value),
+                    "--name" => name = Some(value),
+                    other => return Err(AppError::Config(format!("Unknown option {}", other))),
+                }
+            }
+            let scope = scope.ok_or_else(|| AppError::Config("token create needs --scope read or read-write".to_string()))?;
+            Ok(TokenCommand::Create { scope, user, name })
+        }
+        Some("revoke") if args.len() >= 2 => args[1..]
+            .iter()
+            .map(|id| id.parse().map_err(|_| AppError::Config(format!("Not a token id: {}", id))))
+            .collect::<Result<_>>()
+            .map(TokenCommand::Revoke),
+        _ => Err(usage()),
+    }
+}
+
+/// One line per token for `token list`
+pub fn format_api_token(token: &ApiToken) -> String {
+    let used = token
+        .last_used_at
+        .map_or("never used".to_string(), |at| format!("last used {}", at.format("%Y-%m-%d %H:%M")));
+    format!(
+        "{:>4}  {}  {}  {}  (created {}, {})",
+        token.id,
+        token.username,
+        token.scope.as_str(),
+        token.name,
+        token.created_at.format("%Y-%m-%d"),
+        used
+    )
+}
+
+/// Parse the arguments after `autotag`; the query must parse
+pub fn parse_autotag_args(args: &[String]) -> Result<AutotagCommand> {
+    let usage = || AppError::Config(AUTOTAG_USAGE.to_string());
+    let ids = |args: &[String]| -> Result<Vec<i64>> {
+        if args.is_empty() {
+            return Err(usage());
+        }
+        args.iter()
+            .map(|id| id.parse().map_err(|_| AppError::Config(format!("Not a rule id: {}", id))))
+            .collect()
+    };
+    match args.first().map(String::as_str) {
+        None | Some("list") if args.len() <= 1 => Ok(AutotagCommand::List),
+        Some("add") if args.len() >= 3 => {
+            let query = args[2..].join(" ");
+            Query::parse(&query).map_err(|e| AppError::Config(format!("Bad query at {}", e)))?;
+            Ok(AutotagCommand::Add { tag: normalize_tag(&args[1])?, query })
+        }
+        Some("enable") => Ok(AutotagCommand::SetEnabled(ids(&args[1..])?, true)),
+        Some("disable") => Ok(AutotagCommand::SetEnabled(ids(&args[1..])?, false)),
+        Some("remove") => Ok(AutotagCommand::Remove(ids(&args[1..])?)),
+        _ => Err(usage()),
+    }
+}
+
+/// One line per rule for `autotag list`
+pub fn format_tag_rule(rule: &TagRule) -> String {
+    let mut line = format!("{:>4}  #{}  {}  ({} hits", rule.id, rule.tag, rule.query, rule.hits);
+    if let Some(last) = rule.last_hit_at {
+        line.push_str(&format!(", last {}", last.format("%Y-%m-%d")));
+    }
+    line.push(')');
+    if !rule.enabled {
+        line.push_str("  [disabled]");
+    }
+    line
+}
+
+/// Tags are lowercase and can't contain commas (they're stored joined by one)
+fn normalize_tag(tag: &str) -> Result<String> {
+    let tag = tag.trim().to_lowercase();
+    if tag.is_empty() || tag.contains(',') {
+        return Err(AppError::Config(format!("Invalid tag: {:?}", tag)));
+    }
+    Ok(tag)
+}
+
+/// Busiest feeds first; feeds without stats yet go last
+pub fn sort_by_volume(feeds: &mut [&Feed]) {
+    feeds.sort_by(|a, b| b.posts_per_day.unwrap_or(-1.0).total_cmp(&a.posts_per_day.unwrap_or(-1.0)));
+}
+
+/// Posting volume and how long ago the newest post was, e.g. "2.5/day, last 3h ago"
+fn format_volume(feed: &Feed, now: DateTime<Utc>) -> Option<String> {
+    let per_day = feed.posts_per_day?;
+    let Some(last) = feed.last_post_at else {
+        return Some("no posts".to_string());
+    };
+    let age = now - last;
+    let age = if age.num_days() >= 1 {
+        format!("{}d", age.num_days())
+    } else if age.num_hours() >= 1 {
+        format!("{}h", age.num_hours())
+    } else {
+        format!("{}m", age.num_minutes().max(0))
+    };
+    Some(format!("{:.1}/day, last {} ago", per_day, age))
+}
+
+/// One line per feed for `feeds list`
+pub fn format_feed_line(feed: &Feed) -> String {
+    let folder = feed.folder.as_ref().map(|f| format!("{}/", f)).unwrap_or_default();
+    let mut line = format!("{:>5}  {}{}", feed.id, folder, feed.display_title());
+    if feed.unread > 0 {
+        line.push_str(&format!("  [{} unread]", feed.unread));
+    }
+    if feed.paused {
+        line.push_str("  [paused]");
+    }
+    if let Some(minutes) = feed.refresh_minutes {
+        line.push_str(&format!("  [every {}m]", minutes));
+    }
+    if feed.reading != ReadingMode::Full {
+        line.push_str(&format!("  [{}]", feed.reading.as_str()));
+    }
+    if feed.priority != FeedPriority::Normal {
+        line.push_str(&format!("  [{} priority]", feed.priority.as_str()));
+    }
+    if let Some(beat) = &feed.source.beat {
+        line.push_str(&format!("  [beat: {}]", beat));
+    }
+    if feed.reader_url.is_some() {
+        line.push_str("  [reader view]");
+    }
+    if let Some(url) = &feed.moved_to {
+        line.push_str(&format!("  [moved? {}]", url));
+    }
+    if feed.repaired {
+        line.push_str("  [repaired]");
+    }
+    if let Some(rating) = feed.source.reliability {
+        line.push_str(&format!("  [reliability {}/{}]", rating, SourceInfo::MAX_RELIABILITY));
+    }
+    for tag in &feed.tags {
+        line.push_str(&format!("  #{}", tag));
+    }
+    if let Some(volume) = format_volume(feed, Utc::now()) {
+        line.push_str(&format!("  ({})", volume));
+    }
+    line.push_str(&format!("  {}", feed.url));
+    line
+}
+
+/// Contact and notes under a feed's line in `feeds list`, indented
+pub fn format_source_lines(feed: &Feed) -> Vec<String> {
+    let mut lines = Vec::new();
+    if let Some(contact) = &feed.source.contact {
+        lines.push(format!("       contact: {}", contact));
+    }
+    if let Some(notes) = &feed.source.notes {
+        lines.extend(notes.lines().map(|line| format!("       | {}", line)));
+    }
+    lines
+}
+
+/// `--days N` after `watch list`, defaulting to `TIMELINE_DAYS`
+pub fn parse_watch_days(args: &[String]) -> Result<usize> {
+    match args.iter().position(|a| a == "--days") {
+        Some(i) => args
+            .get(i + 1)
+            .and_then(|d| d.parse().ok())
+            .filter(|&d| d > 0)
+            .ok_or_else(|| AppError::Config("--days needs a number of days".to_string())),
+        None => Ok(TIMELINE_DAYS),
+    }
+}
+
+/// One line per watched entity for `watch list`, names padded to `width`
+pub fn format_timeline(timeline: &Timeline, width: usize) -> String {
+    let mut line = format!(
+        "{:<width$}  {}  {:>3} today",
+        timeline.entity,
+        timeline.sparkline(),
+        timeline.today()
+    );
+    if timeline.is_spike() {
+        line.push_str(&format!("  SPIKE (usually {:.1}/day)", timeline.baseline()));
+    }
+    line.push_str(&format!("  [{}]", timeline.lists.join(", ")));
+    line
+}
+
+/// A size in bytes as B, KB, MB or GB
+pub fn format_bytes(bytes: i64) -> String {
+    let size = bytes.unsigned_abs() as f64;
+    let sign = if bytes < 0 { "-" } else { "" };
+    match size {
+        s if s >= 1024.0 * 1024.0 * 1024.0 => format!("{}{:.1} GB", sign, s / (1024.0 * 1024.0 * 1024.0)),
+        s if s >= 1024.0 * 1024.0 => format!("{}{:.1} MB", sign, s / (1024.0 * 1024.0)),
+        s if s >= 1024.0 => format!("{}{:.0} KB", sign, s / 1024.0),
+        s => format!("{}{} B", sign, s),
+    }
+}
+
+/// A table's rows and size, padded to `width`, and its growth since `since`
+fn table_line(table: &TableSize, width: usize, since: Option<NaiveDate>) -> String {
+    let mut line = format!(
+        "{:<width$}  {:>9} rows  {:>9}",
+        table.name,
+        table.rows,
+        format_bytes(table.bytes)
+    );
+    if let (Some(since), Some(previous)) = (since, table.previous_bytes) {
+        let growth = table.bytes - previous;
+        let sign = if growth > 0 { "+" } else { "" };
+        line.push_str(&format!("  {}{} since {}", sign, format_bytes(growth), since.format("%b %-d")));
+    }
+    line
+}
+
+/// A shared list's line in `blocklist list`
+pub fn format_subscription(subscription: &SubscriptionStatus) -> String {
+    let state = match (subscription.enabled, subscription.fetched) {
+        (false, _) => "off".to_string(),
+        (true, None) => "not downloaded yet".to_string(),
+        (true, Some(fetched)) => format!(
+            "{} rules, downloaded {}",
+            subscription.rules,
+            DateTime::<chrono::Local>::from(fetched).format("%b %-d %H:%M")
+        ),
+    };
+    format!("include {}  ({})", subscription.url, state)
+}
+
+/// The `db stats` report: the totals, then a line per table
+pub fn format_db_stats(stats: &DbStats) -> String {
+    let mut lines = vec![format!(
+        "{} in total, {} of it free pages",
+        format_bytes(stats.file_bytes),
+        format_bytes(stats.free_bytes)
+    )];
+    let width = stats.tables.iter().map(|t| t.name.len()).max().unwrap_or(0);
+    lines.extend(stats.tables.iter().map(|table| table_line(table, width, stats.since)));
+    if stats.since.is_none() {
+        lines.push("Growth shows once `beatcheck db maintain` has recorded sizes on an earlier day".to_string());
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_parse_bulk_args() {
+        let (selector, update) = parse_bulk_args(&args("3,4 7 --match verge --folder Tech --pause --tag News")).unwrap();
+        assert_eq!(selector.ids, [3, 4, 7]);
+        assert_eq!(selector.matching.as_deref(), Some("verge"));
+        assert_eq!(update.folder, Some(Some("Tech".to_string())));
+        assert_eq!(update.paused, Some(true));
+        assert_eq!(update.add_tags, ["news"]);
+
+        let (_, update) = parse_bulk_args(&args("--all --interval 0 --no-folder")).unwrap();
+        assert_eq!((update.refresh_minutes, update.folder), (Some(None), Some(None)));
+
+        let (_, update) = parse_bulk_args(&args("--tagged old --archive")).unwrap();
+        assert_eq!(update.remove, Some(FeedRemoval::Archive));
+
+        let (_, update) = parse_bulk_args(&args("--in-folder Deals --reading headlines")).unwrap();
+        assert_eq!(update.reading, Some(ReadingMode::Headlines));
+
+        let (_, update) = parse_bulk_args(&args("--match verge --priority high")).unwrap();
+        assert_eq!(update.priority, Some(FeedPriority::High));
+
+        let mut source = args("12 --beat chips --reliability 4 --notes");
+        source.push("Wire copy; check with their desk".to_string());
+        let (_, update) = parse_bulk_args(&source).unwrap();
+        assert_eq!(update.beat, Some(Some("chips".to_string())));
+        assert_eq!(update.reliability, Some(Some(4)));
+        assert_eq!(update.notes, Some(Some("Wire copy; check with their desk".to_string())));
+
+        let (_, update) = parse_bulk_args(&[args("12 --reliability 0 --contact"), vec![String::new()]].concat()).unwrap();
+        assert_eq!((update.reliability, update.contact), (Some(None), Some(None)));
+
+        let (_, update) = parse_bulk_args(&args("--tagged paywall --reader-url https://r.example/{url}")).unwrap();
+        assert_eq!(update.reader_url, Some(Some("https://r.example/{url}".to_string())));
+        let (_, update) = parse_bulk_args(&[args("12 --reader-url"), vec![String::new()]].concat()).unwrap();
+        assert_eq!(update.reader_url, Some(None));
+    }
+
+    #[test]
+    fn test_parse_bulk_args_errors() {
+        for bad in [
+            "--pause",
+            "--all",
+            "--all --folder",
+            "--all --interval soon",
+            "x1 --pause",
+            "--all --bogus",
+            "--all --reading skim",
+            "--all --priority urgent",
+            "--all --reliability 6",
+            "--all --reader-url https://r.example/",
+            "--all --reader-url file:///{url}",
+        ] {
+            assert!(parse_bulk_args(&args(bad)).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_parse_tags_args() {
+        assert_eq!(parse_tags_args(&[]).unwrap(), TagCommand::List);
+        assert_eq!(
+            parse_tags_args(&args("rename ML Machine-Learning")).unwrap(),
+            TagCommand::Merge { from: vec!["ml".to_string()], into: "machine-learning".to_string() }
+        );
+        assert_eq!(
+            parse_tags_args(&args("merge ai a.i. llm")).unwrap(),
+            TagCommand::Merge { from: vec!["a.i.".to_string(), "llm".to_string()], into: "ai".to_string() }
+        );
+        assert_eq!(parse_tags_args(&args("delete x y")).unwrap(), TagCommand::Delete(args("x y")));
+        for bad in ["rename ml", "merge ai", "delete", "list extra", "rename a,b c"] {
+            assert!(parse_tags_args(&args(bad)).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_parse_collections_args() {
+        assert_eq!(parse_collections_args(&[]).unwrap(), CollectionCommand::List);
+        assert_eq!(
+            parse_collections_args(&args("add Research 4 7")).unwrap(),
+            CollectionCommand::Add("Research".to_string(), vec![4, 7])
+        );
+        assert_eq!(
+            parse_collections_args(&args("remove Research")).unwrap(),
+            CollectionCommand::Remove("Research".to_string(), Vec::new())
+        );
+        assert_eq!(
+            parse_collections_args(&args("move Research 7 1")).unwrap(),
+            CollectionCommand::Move { name: "Research".to_string(), id: 7, position: 1 }
+        );
+        assert_eq!(
+            parse_collections_args(&args("export Research --format epub")).unwrap(),
+            CollectionCommand::Export("Research".to_string(), PacketFormat::Epub)
+        );
+        for bad in ["add Research", "add Research x", "move Research 7 0", "export Research --format pdf", "show"] {
+            assert!(parse_collections_args(&args(bad)).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_parse_links_args() {
+        assert_eq!(parse_links_args(&args("gao.gov")).unwrap(), LinksCommand::To("gao.gov".to_string()));
+        assert_eq!(parse_links_args(&args("--from 12")).unwrap(), LinksCommand::From(12));
+        assert_eq!(parse_links_args(&args("--top")).unwrap(), LinksCommand::Top(7));
+        assert_eq!(parse_links_args(&args("--top --days 30")).unwrap(), LinksCommand::Top(30));
+        for bad in ["", "--from x", "--top --days 0", "gao.gov nist.gov", "--to"] {
+            assert!(parse_links_args(&args(bad)).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_parse_bundle_args() {
+        assert_eq!(
+            parse_bundle_args(&args("export trip.json")).unwrap(),
+            BundleCommand::Export(PathBuf::from("trip.json"), None)
+        );
+        assert_eq!(
+            parse_bundle_args(&args("export trip.json --days 3")).unwrap(),
+            BundleCommand::Export(PathBuf::from("trip.json"), Some(3))
+        );
+        assert_eq!(parse_bundle_args(&args("import trip.json")).unwrap(), BundleCommand::Import(PathBuf::from("trip.json")));
+        for bad in ["", "export", "import a b", "export a --days 0", "sync a"] {
+            assert!(parse_bundle_args(&args(bad)).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_parse_autotag_args() {
+        assert_eq!(parse_autotag_args(&[]).unwrap(), AutotagCommand::List);
+        assert_eq!(
+            parse_autotag_args(&args(r"add Vuln folder:Security re:CVE-\d+")).unwrap(),
+            AutotagCommand::Add { tag: "vuln".to_string(), query: r"folder:Security re:CVE-\d+".to_string() }
+        );
+        assert_eq!(parse_autotag_args(&args("disable 2 3")).unwrap(), AutotagCommand::SetEnabled(vec![2, 3], false));
+        assert_eq!(parse_autotag_args(&args("remove 4")).unwrap(), AutotagCommand::Remove(vec![4]));
+        let error = parse_autotag_args(&args("add vuln fold:Security")).unwrap_err().to_string();
+        assert!(error.contains("Bad query at column 1"), "{}", error);
+        for bad in ["add vuln", "enable", "remove x", "bogus"] {
+            assert!(parse_autotag_args(&args(bad)).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_parse_summarize_args() {
+        let parsed = parse_summarize_args(&args("--in-folder Tech --jobs 4")).unwrap();
+        assert_eq!(parsed.selector.folder.as_deref(), Some("Tech"));
+        assert_eq!(parsed.jobs, Some(4));
+        let parsed = parse_summarize_args(&args("12")).unwrap();
+        assert_eq!((parsed.selector.ids, parsed.jobs), (vec![12], None));
+        let parsed = parse_summarize_args(&args("--failed --jobs 1")).unwrap();
+        assert!(parsed.failed);
+        assert_eq!(parsed.jobs, Some(1));
+
+        for bad in ["", "--jobs 2", "--all --jobs 0", "--all --jobs", "--all --pause", "--failed --all"] {
+            assert!(parse_summarize_args(&args(bad)).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_parse_fulltext_args() {
+        let parsed = parse_fulltext_args(&args("--tagged News --jobs 8")).unwrap();
+        assert_eq!((parsed.selector.tag.as_deref(), parsed.jobs), (Some("news"), Some(8)));
+        // Nothing selected: only an interrupted run is finished
+        assert_eq!(parse_fulltext_args(&[]).unwrap(), FullTextArgs::default());
+        for bad in ["--jobs 0", "--all --jobs", "--failed"] {
+            assert!(parse_fulltext_args(&args(bad)).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_parse_backup_args() {
+        assert_eq!(parse_backup_args(&args("export all.beatcheck")).unwrap(), BackupCommand::Export(PathBuf::from("all.beatcheck")));
+        assert_eq!(parse_backup_args(&args("import all.beatcheck")).unwrap(), BackupCommand::Import(PathBuf::from("all.beatcheck")));
+        for bad in ["", "export", "import a b", "restore a"] {
+            assert!(parse_backup_args(&args(bad)).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_parse_audit_args() {
+        let (filter, limit) = parse_audit_args(&args("")).unwrap();
+        assert_eq!((filter, limit), (AuditFilter::default(), AUDIT_LIMIT));
+
+        let (filter, limit) = parse_audit_args(&args("feed --search verge --days 7 --limit 5")).unwrap();
+        assert_eq!(filter.action.as_deref(), Some("feed"));
+        assert_eq!(filter.text.as_deref(), Some("verge"));
+        assert!(filter.since.is_some_and(|since| since < Utc::now() - chrono::Duration::days(6)));
+        assert_eq!(limit, 5);
+
+        for bad in ["feed import", "--days 0", "--limit x", "--search", "--since 2026-01-01"] {
+            assert!(parse_audit_args(&args(bad)).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_parse_token_args() {
+        assert_eq!(parse_token_args(&args("")).unwrap(), TokenCommand::List);
+        assert_eq!(
+            parse_token_args(&args("create --scope read --name dashboard")).unwrap(),
+            TokenCommand::Create {
+                scope: TokenScope::Read,
+                user: None,
+                name: Some("dashboard".to_string()),
+            }
+        );
+        assert_eq!(
+            parse_token_args(&args("create --user alice --scope read-write")).unwrap(),
+            TokenCommand::Create {
+                scope: TokenScope::ReadWrite,
+                user: Some("alice".to_string()),
+                name: None,
+            }
+        );
+        assert_eq!(parse_token_args(&args("revoke 2 5")).unwrap(), TokenCommand::Revoke(vec![2, 5]));
+        for bad in ["create", "create --scope admin", "create --scope", "create --scope read --ttl 1", "revoke", "revoke x"] {
+            assert!(parse_token_args(&args(bad)).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_parse_opml_export_args() {
+        let parsed = parse_opml_export_args(&args("ai.opml --in-folder AI --owner Leo")).unwrap();
+        assert_eq!(parsed.path, PathBuf::from("ai.opml"));
+        assert_eq!(parsed.selector.folder.as_deref(), Some("AI"));
+        assert_eq!(parsed.head.owner_name.as_deref(), Some("Leo"));
+        assert_eq!(parse_opml_export_args(&args("all.opml")).unwrap().selector, FeedSelector::default());
+        for bad in ["", "--in-folder AI", "x.opml --owner", "x.opml --bogus"] {
+            assert!(parse_opml_export_args(&args(bad)).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_format_db_stats() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(3 * 1024 * 1024 / 2), "1.5 MB");
+        assert_eq!(format_bytes(-2048), "-2 KB");
+
+        let table = |name: &str, bytes, previous_bytes| TableSize {
+            name: name.to_string(),
+            rows: 10,
+            bytes,
+            previous_bytes,
+        };
+        let stats = DbStats {
+            file_bytes: 2 * 1024 * 1024 * 1024,
+            free_bytes: 4096,
+            tables: vec![table("articles", 1024 * 1024, Some(512 * 1024)), table("feeds", 8192, None)],
+            since: NaiveDate::from_ymd_opt(2026, 3, 2),
+        };
+        let report = format_db_stats(&stats);
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines[0], "2.0 GB in total, 4 KB of it free pages");
+        assert_eq!(lines[1], "articles         10 rows     1.0 MB  +512 KB since Mar 2");
+        assert_eq!(lines[2], "feeds            10 rows       8 KB");
+    }
+
+    #[test]
+    fn test_format_subscription() {
+        let mut subscription = SubscriptionStatus {
+            url: "https://team.example/noise.txt".to_string(),
+            enabled: true,
+            rules: 0,
+            fetched: None,
+        };
+        assert_eq!(
+            format_subscription(&subscription),
+            "include https://team.example/noise.txt  (not downloaded yet)"
+        );
+        subscription.rules = 12;
+        subscription.fetched = Some(std::time::SystemTime::now());
+        assert!(format_subscription(&subscription).contains("(12 rules, downloaded "));
+        subscription.enabled = false;
+        assert!(format_subscription(&subscription).ends_with("(off)"));
+    }
+}
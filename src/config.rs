@@ -1,7 +1,10 @@
+use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use std::time::Duration;
 
 use crate::error::{AppError, Result};
+use crate::schedule::TimeWindow;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -16,6 +19,749 @@ pub struct Config {
 
     #[serde(default)]
     pub default_tags: Vec<String>,
+
+    /// The feed list as code: when set, feeds are added and renamed, filed
+    /// and tagged to match at startup, and any others pointed out
+    #[serde(default)]
+    pub subscriptions: Vec<Subscription>,
+
+    /// A newsboat-style urls file (a feed URL per line, then its tags),
+    /// watched and synced into the subscriptions
+    #[serde(default)]
+    pub urls_file: Option<PathBuf>,
+
+    #[serde(default)]
+    pub server: ServerConfig,
+
+    #[serde(default)]
+    pub content: ContentConfig,
+
+    #[serde(default)]
+    pub http_cache: HttpCacheConfig,
+
+    #[serde(default)]
+    pub maintenance: MaintenanceConfig,
+
+    #[serde(default)]
+    pub blocklist: BlocklistConfig,
+
+    #[serde(default)]
+    pub discussions: DiscussionsConfig,
+
+    #[serde(default)]
+    pub deletions: DeletionsConfig,
+
+    #[serde(default)]
+    pub podcast: PodcastConfig,
+
+    #[serde(default)]
+    pub archive: ArchiveConfig,
+
+    #[serde(default)]
+    pub export: ExportConfig,
+
+    #[serde(default)]
+    pub notes: NotesConfig,
+
+    #[serde(default)]
+    pub templates: TemplatesConfig,
+
+    #[serde(default)]
+    pub tagging: TaggingConfig,
+
+    #[serde(default)]
+    pub summaries: SummariesConfig,
+
+    #[serde(default)]
+    pub redaction: RedactionConfig,
+
+    #[serde(default)]
+    pub schedule: ScheduleConfig,
+
+    #[serde(default)]
+    pub focus: FocusConfig,
+
+    /// Rules embargoing new articles, e.g. press releases
+    #[serde(default)]
+    pub embargo: Vec<EmbargoRule>,
+
+    /// Steps run on the selected article with one key
+    #[serde(default)]
+    pub macros: Vec<MacroConfig>,
+
+    /// Summarize with a model on this machine or network instead of Claude
+    #[serde(default)]
+    pub local_llm: Option<LocalLlmConfig>,
+
+    /// Summarize with OpenAI's API, on its own or as a fallback
+    #[serde(default)]
+    pub openai: Option<OpenAiConfig>,
+
+    /// Strict local mode: summaries only from `local_llm` (whose client
+    /// can't reach the internet), no browser cookies for page scraping,
+    /// and no Raindrop or HN/Lobsters requests
+    #[serde(default)]
+    pub local_only: bool,
+}
+
+/// What to strip from article text before it goes to a cloud model
+/// (local models get it as is)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RedactionConfig {
+    #[serde(default)]
+    pub emails: bool,
+    #[serde(default)]
+    pub phone_numbers: bool,
+    /// Regular expressions; matches become "[redacted]"
+    #[serde(default)]
+    pub patterns: Vec<String>,
+    /// Append a JSON line per cloud request (when, where, what for, a hash
+    /// of the text and what was redacted) to this file
+    #[serde(default)]
+    pub audit_log: Option<PathBuf>,
+}
+
+/// A local model served by Ollama or llama.cpp (`llama-server`), both
+/// through their OpenAI-compatible API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalLlmConfig {
+    /// Ollama listens on http://127.0.0.1:11434, llama-server on :8080
+    #[serde(default = "default_local_llm_url")]
+    pub url: String,
+    pub model: String,
+}
+
+fn default_local_llm_url() -> String {
+    "http://127.0.0.1:11434".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenAiConfig {
+    pub api_key: String,
+    #[serde(default = "default_openai_model")]
+    pub model: String,
+    /// Another OpenAI-compatible service can go here
+    #[serde(default = "default_openai_url")]
+    pub url: String,
+}
+
+fn default_openai_model() -> String {
+    "gpt-4o-mini".to_string()
+}
+
+fn default_openai_url() -> String {
+    "https://api.openai.com".to_string()
+}
+
+/// A source of summaries, as named in `[summaries] providers`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderKind {
+    #[serde(alias = "anthropic")]
+    Claude,
+    OpenAi,
+    /// `[local_llm]`
+    #[serde(alias = "ollama")]
+    Local,
+}
+
+/// Limits on summary requests to Claude
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummariesConfig {
+    /// Articles summarized at once by `beatcheck summarize`
+    #[serde(default = "default_summary_concurrency")]
+    pub concurrency: usize,
+
+    /// Most requests started per minute, across everything (0: no limit)
+    #[serde(default)]
+    pub requests_per_minute: u32,
+
+    /// Where an article's stored summary and suggested tags go with it
+    #[serde(default)]
+    pub embed: EmbedConfig,
+
+    /// Providers to try in order, each taking over when the one before it
+    /// fails (e.g. ["claude", "openai", "local"]). Empty: every configured
+    /// one, `[local_llm]` first.
+    #[serde(default)]
+    pub providers: Vec<ProviderKind>,
+}
+
+impl Default for SummariesConfig {
+    fn default() -> Self {
+        Self {
+            concurrency: default_summary_concurrency(),
+            requests_per_minute: 0,
+            embed: EmbedConfig::default(),
+            providers: Vec::new(),
+        }
+    }
+}
+
+/// Destinations that include the summary and tags (all of them by default)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbedConfig {
+    /// PDF exports
+    #[serde(default = "default_true")]
+    pub pdf: bool,
+    /// Archived copies of starred articles
+    #[serde(default = "default_true")]
+    pub archive: bool,
+    /// Notes in the vault
+    #[serde(default = "default_true")]
+    pub notes: bool,
+    /// Emailed articles
+    #[serde(default = "default_true")]
+    pub email: bool,
+    /// Raindrop bookmarks: the summary's first point as the excerpt, all
+    /// of it (and the tags) as the note
+    #[serde(default = "default_true")]
+    pub raindrop: bool,
+}
+
+impl Default for EmbedConfig {
+    fn default() -> Self {
+        Self {
+            pdf: true,
+            archive: true,
+            notes: true,
+            email: true,
+            raindrop: true,
+        }
+    }
+}
+
+fn default_summary_concurrency() -> usize {
+    2
+}
+
+/// Suggested article tags. Keyword extraction always runs at ingest.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TaggingConfig {
+    /// Also ask Claude for tags when summarizing an article, replacing the
+    /// extracted ones. Costs a second (small) request per summary.
+    #[serde(default)]
+    pub llm: bool,
+}
+
+/// Custom export formats (see "Export Templates" in the README). Template
+/// paths are relative to the config directory.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TemplatesConfig {
+    /// HTML document for PDF exports and archived articles
+    pub html: Option<PathBuf>,
+    /// Markdown note, including its front-matter
+    pub note: Option<PathBuf>,
+    /// Entry appended to the daily note
+    pub daily: Option<PathBuf>,
+    /// Brief copied to the clipboard (`C`)
+    pub brief: Option<PathBuf>,
+    /// Pattern for note file names, e.g. "{{published}} {{title}}"
+    pub note_filename: Option<String>,
+    /// Pattern for PDF and archive file names (without extension)
+    pub export_filename: Option<String>,
+}
+
+/// Markdown notes in an Obsidian or Logseq vault
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotesConfig {
+    /// Vault root; note export is off until this is set
+    pub vault: Option<PathBuf>,
+
+    /// Folder (relative to the vault) for article notes; "pages" for Logseq
+    #[serde(default = "default_notes_folder")]
+    pub folder: String,
+
+    /// Folder for daily notes ("" is the vault root); "journals" for Logseq
+    #[serde(default)]
+    pub daily_folder: String,
+
+    /// strftime pattern for daily note names; "%Y_%m_%d" for Logseq
+    #[serde(default = "default_daily_format")]
+    pub daily_format: String,
+}
+
+impl Default for NotesConfig {
+    fn default() -> Self {
+        Self {
+            vault: None,
+            folder: default_notes_folder(),
+            daily_folder: String::new(),
+            daily_format: default_daily_format(),
+        }
+    }
+}
+
+fn default_notes_folder() -> String {
+    "Clippings".to_string()
+}
+
+fn default_daily_format() -> String {
+    "%Y-%m-%d".to_string()
+}
+
+/// Exporting single articles to files
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportConfig {
+    /// Where exported files go (defaults to ~/Documents/beatcheck)
+    pub dir: Option<PathBuf>,
+
+    /// HTML-to-PDF converter. `{input}` is an HTML copy of the article and
+    /// `{output}` the PDF to write, e.g. "typst compile {input} {output}"
+    /// for a typst wrapper.
+    #[serde(default = "default_pdf_command")]
+    pub pdf_command: String,
+
+    /// Program that takes text to copy on stdin, e.g. "wl-copy". Unset
+    /// tries wl-copy, xclip, xsel and pbcopy, then the terminal (OSC 52).
+    #[serde(default)]
+    pub clipboard_command: Option<String>,
+
+    /// HTML-to-EPUB converter for collection packets, with `{input}` and
+    /// `{output}` as for `pdf_command`
+    #[serde(default = "default_epub_command")]
+    pub epub_command: String,
+
+    /// Who exported OPML files say they're from (`ownerName`, `ownerEmail`)
+    #[serde(default)]
+    pub owner_name: Option<String>,
+    #[serde(default)]
+    pub owner_email: Option<String>,
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            pdf_command: default_pdf_command(),
+            clipboard_command: None,
+            epub_command: default_epub_command(),
+            owner_name: None,
+            owner_email: None,
+        }
+    }
+}
+
+impl ExportConfig {
+    pub fn dir(&self) -> PathBuf {
+        self.dir.clone().unwrap_or_else(|| {
+            dirs::document_dir()
+                .or_else(dirs::home_dir)
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("beatcheck")
+        })
+    }
+}
+
+fn default_pdf_command() -> String {
+    "weasyprint --quiet {input} {output}".to_string()
+}
+
+fn default_epub_command() -> String {
+    "pandoc --quiet --from html --to epub3 {input} -o {output}".to_string()
+}
+
+/// Local snapshots of starred articles
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ArchiveConfig {
+    /// When an article is starred, save its page with images inlined as a
+    /// standalone HTML file, so it outlives the original link
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Where snapshots go (defaults to an `archive` folder next to the database)
+    pub dir: Option<PathBuf>,
+}
+
+impl ArchiveConfig {
+    pub fn dir(&self) -> PathBuf {
+        self.dir.clone().unwrap_or_else(|| {
+            dirs::data_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("beatcheck")
+                .join("archive")
+        })
+    }
+}
+
+/// Podcast playback
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PodcastConfig {
+    /// Player command line. `{url}` is the enclosure, `{start}` the resume
+    /// position in seconds and `{ipc}` a socket path for mpv's JSON IPC, which
+    /// is how the playback position gets tracked.
+    #[serde(default = "default_player")]
+    pub player: String,
+}
+
+impl Default for PodcastConfig {
+    fn default() -> Self {
+        Self {
+            player: default_player(),
+        }
+    }
+}
+
+fn default_player() -> String {
+    "mpv --no-video --no-terminal --start={start} --input-ipc-server={ipc} {url}".to_string()
+}
+
+/// When the daemon and `--refresh` may refresh and announce things, in
+/// local time
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScheduleConfig {
+    /// No coverage-spike or followed-author announcements in these windows
+    #[serde(default)]
+    pub quiet_hours: Vec<TimeWindow>,
+    /// Skip scheduled refreshes during quiet hours too
+    #[serde(default)]
+    pub quiet_refresh: bool,
+    /// Scheduled refreshes only inside these windows (any time if empty)
+    #[serde(default)]
+    pub refresh_windows: Vec<TimeWindow>,
+}
+
+impl ScheduleConfig {
+    pub fn is_quiet(&self, now: NaiveDateTime) -> bool {
+        self.quiet_hours.iter().any(|w| w.contains(now))
+    }
+
+    /// Whether a scheduled refresh may run at `now`
+    pub fn may_refresh(&self, now: NaiveDateTime) -> bool {
+        let in_window = self.refresh_windows.is_empty() || self.refresh_windows.iter().any(|w| w.contains(now));
+        in_window && !(self.quiet_refresh && self.is_quiet(now))
+    }
+}
+
+/// Reading-time goals and nudges. Time counts while an article is on
+/// screen and keys are being pressed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FocusConfig {
+    /// Minutes of reading a day to aim for, shown against today's total
+    #[serde(default)]
+    pub daily_goal_minutes: Option<u32>,
+    /// Point it out after this many minutes without a break
+    #[serde(default)]
+    pub nudge_minutes: Option<u32>,
+}
+
+/// A feed declared in `[[subscriptions]]`. Leaving out `title` or `folder`
+/// means the feed's own title and no folder.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Subscription {
+    pub url: String,
+    pub title: Option<String>,
+    pub folder: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Hold back new articles matching `query` (in the search language) until
+/// their publish date if the feed dates them ahead, or `hours` after they
+/// arrive if that's later
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbargoRule {
+    pub query: String,
+    #[serde(default)]
+    pub hours: u32,
+}
+
+/// A named macro: `steps` like "star", "tag column" or "read", run in order
+/// when `key` ("K", "ctrl+k", "F5") is pressed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MacroConfig {
+    pub name: String,
+    pub key: String,
+    pub steps: Vec<String>,
+}
+
+/// Hacker News / Lobsters discussion lookups
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DiscussionsConfig {
+    /// After each refresh, look up recent article URLs on Hacker News (via
+    /// Algolia) and Lobsters. Off by default since it sends every URL to them.
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+/// Watching saved articles for being taken down
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeletionsConfig {
+    /// After each refresh, re-request the pages of starred articles and of
+    /// those from priority feeds and followed authors, and flag any that
+    /// have gone (404/410, or a redirect to the site's front page)
+    #[serde(default)]
+    pub enabled: bool,
+    /// Hours between checks of one article
+    #[serde(default = "default_deletion_recheck_hours")]
+    pub recheck_hours: u32,
+}
+
+impl Default for DeletionsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            recheck_hours: default_deletion_recheck_hours(),
+        }
+    }
+}
+
+fn default_deletion_recheck_hours() -> u32 {
+    24
+}
+
+/// The on-disk cache of article pages and images, shared by full-text
+/// fetches and archiving
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpCacheConfig {
+    /// Keep responses on disk and reuse them while fresh (per
+    /// Cache-Control), revalidating with ETag/Last-Modified after that
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Oldest entries go once the cache grows past this
+    #[serde(default = "default_http_cache_mb")]
+    pub max_mb: u64,
+    /// Where entries go (defaults to an `http-cache` folder next to the database)
+    pub dir: Option<PathBuf>,
+}
+
+impl Default for HttpCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_mb: default_http_cache_mb(),
+            dir: None,
+        }
+    }
+}
+
+impl HttpCacheConfig {
+    pub fn dir(&self) -> PathBuf {
+        self.dir.clone().unwrap_or_else(|| {
+            dirs::data_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("beatcheck")
+                .join("http-cache")
+        })
+    }
+}
+
+fn default_http_cache_mb() -> u64 {
+    256
+}
+
+/// Database upkeep (`beatcheck db maintain`) on a schedule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceConfig {
+    /// Hours between runs in daemon mode (`--serve`); 0 turns them off
+    #[serde(default = "default_maintenance_hours")]
+    pub interval_hours: u32,
+}
+
+impl Default for MaintenanceConfig {
+    fn default() -> Self {
+        Self {
+            interval_hours: default_maintenance_hours(),
+        }
+    }
+}
+
+fn default_maintenance_hours() -> u32 {
+    24
+}
+
+/// Shared blocklists (`include URL` lines in blocklist.txt)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlocklistConfig {
+    /// Hours before a shared list is downloaded again; 0 only downloads on
+    /// `beatcheck blocklist refresh`
+    #[serde(default = "default_blocklist_refresh_hours")]
+    pub refresh_hours: u32,
+}
+
+impl Default for BlocklistConfig {
+    fn default() -> Self {
+        Self {
+            refresh_hours: default_blocklist_refresh_hours(),
+        }
+    }
+}
+
+impl BlocklistConfig {
+    pub fn refresh(&self) -> Option<Duration> {
+        (self.refresh_hours > 0).then(|| Duration::from_secs(u64::from(self.refresh_hours) * 3600))
+    }
+}
+
+fn default_blocklist_refresh_hours() -> u32 {
+    24
+}
+
+/// Settings for fetching full article pages (for summaries)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentConfig {
+    /// Check each site's robots.txt before scraping an article page
+    #[serde(default)]
+    pub respect_robots: bool,
+
+    /// Least time between requests to one site, in milliseconds, however
+    /// many articles are being fetched at once
+    #[serde(default = "default_domain_interval_ms")]
+    pub domain_interval_ms: u64,
+
+    /// Per-site scraping rules
+    #[serde(default)]
+    pub sites: Vec<SiteRule>,
+}
+
+impl Default for ContentConfig {
+    fn default() -> Self {
+        Self {
+            respect_robots: false,
+            domain_interval_ms: default_domain_interval_ms(),
+            sites: Vec::new(),
+        }
+    }
+}
+
+fn default_domain_interval_ms() -> u64 {
+    1000
+}
+
+impl ContentConfig {
+    /// The rule for `host`, matching the domain itself or any subdomain
+    pub fn site_rule(&self, host: &str) -> Option<&SiteRule> {
+        let host = host.strip_prefix("www.").unwrap_or(host);
+        self.sites.iter().find(|rule| {
+            let domain = rule.domain.strip_prefix("www.").unwrap_or(&rule.domain);
+            host == domain || host.ends_with(&format!(".{}", domain))
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SiteRule {
+    pub domain: String,
+
+    /// CSS selector for the "next page" link of multi-page articles; without
+    /// one, `rel="next"` links are followed
+    pub next_page: Option<String>,
+
+    /// Most pages to stitch together for one article
+    #[serde(default = "default_max_pages")]
+    pub max_pages: usize,
+}
+
+fn default_max_pages() -> usize {
+    10
+}
+
+/// Settings for `beatcheck --serve`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerConfig {
+    #[serde(default = "default_listen")]
+    pub listen: String,
+
+    /// Serve the browser interface at `/` alongside the JSON API
+    #[serde(default = "default_true")]
+    pub web_ui: bool,
+
+    /// Require a user account (see `--add-user`) for every request. Each
+    /// account gets its own subscriptions and read/star state.
+    #[serde(default)]
+    pub auth: bool,
+
+    /// URL prefix when mounted under a path by a reverse proxy, e.g. "/rss"
+    #[serde(default)]
+    pub base_path: String,
+
+    /// Answer the Wallabag API under /api and /oauth, so Wallabag apps and
+    /// browser extensions can save links to the read-later queue
+    #[serde(default)]
+    pub wallabag: bool,
+
+    /// Answer GraphQL queries at /api/graphql, for dashboards that want
+    /// articles filtered several ways with their feeds in one request
+    #[serde(default)]
+    pub graphql: bool,
+
+    /// Proxy addresses or CIDR ranges whose X-Forwarded-For header is
+    /// trusted to carry the real client address
+    #[serde(default)]
+    pub trusted_proxies: Vec<String>,
+
+    /// Requests a minute each client address may make, in bursts of up to
+    /// as many (0 disables the limit)
+    #[serde(default = "default_rate_limit")]
+    pub rate_limit_per_minute: u32,
+
+    /// What happens to remote images in article content served to the
+    /// web UI and API clients
+    #[serde(default)]
+    pub images: RemoteImages,
+
+    /// Serve HTTPS directly instead of plain HTTP
+    pub tls: Option<TlsConfig>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            listen: default_listen(),
+            web_ui: true,
+            auth: false,
+            base_path: String::new(),
+            wallabag: false,
+            graphql: false,
+            trusted_proxies: Vec::new(),
+            rate_limit_per_minute: default_rate_limit(),
+            images: RemoteImages::default(),
+            tls: None,
+        }
+    }
+}
+
+impl ServerConfig {
+    /// `base_path` with a leading slash and no trailing one ("" for the root)
+    pub fn normalized_base_path(&self) -> String {
+        let trimmed = self.base_path.trim().trim_matches('/');
+        if trimmed.is_empty() {
+            String::new()
+        } else {
+            format!("/{}", trimmed)
+        }
+    }
+}
+
+/// `[server] images`: remote images (tracking pixels included) load from
+/// their own servers, are removed, or are fetched through this server so
+/// those servers never see the reader's address or when they read
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RemoteImages {
+    #[default]
+    Load,
+    Strip,
+    Proxy,
+}
+
+/// PEM certificate chain and private key for the server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TlsConfig {
+    pub cert: PathBuf,
+    pub key: PathBuf,
+}
+
+fn default_listen() -> String {
+    "127.0.0.1:7878".to_string()
+}
+
+fn default_rate_limit() -> u32 {
+    600
+}
+
+fn default_true() -> bool {
+    true
 }
 
 fn default_db_path() -> String {
@@ -38,12 +784,37 @@ impl Default for Config {
             raindrop_token: None,
             refresh_interval_minutes: default_refresh_interval(),
             default_tags: vec!["rss".to_string()],
+            subscriptions: Vec::new(),
+            urls_file: None,
+            server: ServerConfig::default(),
+            content: ContentConfig::default(),
+            http_cache: HttpCacheConfig::default(),
+            maintenance: MaintenanceConfig::default(),
+            blocklist: BlocklistConfig::default(),
+            discussions: DiscussionsConfig::default(),
+            deletions: DeletionsConfig::default(),
+            podcast: PodcastConfig::default(),
+            archive: ArchiveConfig::default(),
+            export: ExportConfig::default(),
+            notes: NotesConfig::default(),
+            templates: TemplatesConfig::default(),
+            tagging: TaggingConfig::default(),
+            summaries: SummariesConfig::default(),
+            redaction: RedactionConfig::default(),
+            schedule: ScheduleConfig::default(),
+            focus: FocusConfig::default(),
+            embargo: Vec::new(),
+            macros: Vec::new(),
+            local_llm: None,
+            openai: None,
+            local_only: false,
         }
     }
 }
 
 impl Config {
     /// Parse config from a TOML string
+    #[allow(dead_code, clippy::should_implement_trait)]
     pub fn from_str(content: &str) -> Result<Self> {
         let config: Config = toml::from_str(content)?;
         Ok(config)
@@ -51,7 +822,7 @@ impl Config {
 
     /// Serialize config to a TOML string
     pub fn to_string(&self) -> Result<String> {
-        toml::to_string_pretty(self).map_err(|e| AppError::Config(e.to_string()).into())
+        toml::to_string_pretty(self).map_err(|e| AppError::Config(e.to_string()))
     }
 
     pub fn load() -> Result<Self> {
@@ -73,6 +844,11 @@ impl Config {
         if let Ok(token) = std::env::var("RAINDROP_TOKEN") {
             config.raindrop_token = Some(token);
         }
+        // Only where [openai] is set up, so a key in the environment doesn't
+        // start sending articles to OpenAI
+        if let (Ok(key), Some(openai)) = (std::env::var("OPENAI_API_KEY"), config.openai.as_mut()) {
+            openai.api_key = key;
+        }
 
         Ok(config)
     }
@@ -217,6 +993,7 @@ refresh_interval_minutes = "not a number"
             raindrop_token: None,
             refresh_interval_minutes: 45,
             default_tags: vec!["a".to_string(), "b".to_string()],
+            ..Config::default()
         };
 
         let toml = config.to_string().unwrap();
@@ -235,6 +1012,7 @@ refresh_interval_minutes = "not a number"
             raindrop_token: Some("token456".to_string()),
             refresh_interval_minutes: 120,
             default_tags: vec!["tag1".to_string(), "tag2".to_string(), "tag3".to_string()],
+            ..Config::default()
         };
 
         let toml = original.to_string().unwrap();
@@ -302,6 +1080,149 @@ default_tags = ["日本語", "émoji", "🎉"]
         assert_eq!(config.default_tags[2], "🎉");
     }
 
+    // ==================== Schedule ====================
+
+    #[test]
+    fn test_parse_schedule_section() {
+        let toml = r#"
+[schedule]
+quiet_hours = ["23:00-07:00"]
+quiet_refresh = true
+refresh_windows = ["mon-fri 06:00-20:00", "weekends 09:00-12:00"]
+"#;
+        let config = Config::from_str(toml).unwrap();
+        let at = |day: u32, hour: u32| {
+            // 2026-03-06 is a Friday
+            chrono::NaiveDate::from_ymd_opt(2026, 3, day).unwrap().and_hms_opt(hour, 0, 0).unwrap()
+        };
+        let schedule = &config.schedule;
+        assert!(schedule.is_quiet(at(6, 23)));
+        assert!(schedule.may_refresh(at(6, 19)));
+        assert!(!schedule.may_refresh(at(7, 14)));
+        assert!(schedule.may_refresh(at(7, 10)));
+        // A window that overlaps quiet hours loses to them
+        assert!(!schedule.may_refresh(at(6, 6)));
+        assert!(Config::to_string(&config).unwrap().contains("mon-fri 06:00-20:00"));
+
+        assert!(Config::from_str("[schedule]\nquiet_hours = [\"late\"]").is_err());
+        assert!(Config::from_str("").unwrap().schedule.may_refresh(at(6, 3)));
+    }
+
+    // ==================== Server ====================
+
+    #[test]
+    fn test_server_defaults_when_section_missing() {
+        let config = Config::from_str("").unwrap();
+        assert_eq!(config.server.listen, "127.0.0.1:7878");
+        assert!(config.server.web_ui);
+        assert!(!config.server.auth);
+    }
+
+    #[test]
+    fn test_parse_server_section() {
+        let toml = r#"
+[server]
+listen = "0.0.0.0:8080"
+web_ui = false
+auth = true
+images = "proxy"
+"#;
+
+        let config = Config::from_str(toml).unwrap();
+        assert_eq!(config.server.listen, "0.0.0.0:8080");
+        assert!(!config.server.web_ui);
+        assert!(config.server.auth);
+        assert_eq!(config.server.images, RemoteImages::Proxy);
+        assert_eq!(Config::default().server.images, RemoteImages::Load);
+    }
+
+    #[test]
+    fn test_parse_server_proxy_and_tls() {
+        let toml = r#"
+[server]
+base_path = "rss/"
+trusted_proxies = ["127.0.0.1", "10.0.0.0/8"]
+
+[server.tls]
+cert = "/etc/beatcheck/cert.pem"
+key = "/etc/beatcheck/key.pem"
+"#;
+
+        let config = Config::from_str(toml).unwrap();
+        assert_eq!(config.server.normalized_base_path(), "/rss");
+        assert_eq!(config.server.trusted_proxies.len(), 2);
+        let tls = config.server.tls.unwrap();
+        assert_eq!(tls.cert, PathBuf::from("/etc/beatcheck/cert.pem"));
+        assert_eq!(tls.key, PathBuf::from("/etc/beatcheck/key.pem"));
+        assert_eq!(Config::default().server.normalized_base_path(), "");
+    }
+
+    // ==================== Local mode ====================
+
+    #[test]
+    fn test_local_llm_section() {
+        let config = Config::from_str("").unwrap();
+        assert!(config.local_llm.is_none() && !config.local_only);
+
+        let config = Config::from_str("local_only = true\n\n[local_llm]\nmodel = \"llama3.1\"\n").unwrap();
+        let local = config.local_llm.unwrap();
+        assert_eq!((local.url.as_str(), local.model.as_str()), ("http://127.0.0.1:11434", "llama3.1"));
+        assert!(config.local_only);
+    }
+
+    #[test]
+    fn test_summary_embed_defaults_on() {
+        let config: Config = toml::from_str("[summaries.embed]\nraindrop = false").unwrap();
+        assert!(!config.summaries.embed.raindrop);
+        assert!(config.summaries.embed.pdf && config.summaries.embed.email);
+        assert!(Config::default().summaries.embed.notes);
+    }
+
+    #[test]
+    fn test_provider_chain() {
+        let config = Config::from_str(
+            "[summaries]\nproviders = [\"anthropic\", \"openai\", \"ollama\"]\n\n[openai]\napi_key = \"sk-test\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            config.summaries.providers,
+            [ProviderKind::Claude, ProviderKind::OpenAi, ProviderKind::Local]
+        );
+        let openai = config.openai.unwrap();
+        assert_eq!((openai.model.as_str(), openai.url.as_str()), ("gpt-4o-mini", "https://api.openai.com"));
+        assert!(Config::from_str("[summaries]\nproviders = [\"gemini\"]").is_err());
+    }
+
+    // ==================== Content ====================
+
+    #[test]
+    fn test_content_section() {
+        assert!(!Config::from_str("").unwrap().content.respect_robots);
+
+        let config = Config::from_str("[content]\nrespect_robots = true\n").unwrap();
+        assert!(config.content.respect_robots);
+    }
+
+    #[test]
+    fn test_site_rules_match_subdomains() {
+        let toml = r#"
+[[content.sites]]
+domain = "longreads.example"
+next_page = "a.next-page"
+
+[[content.sites]]
+domain = "other.example"
+max_pages = 3
+"#;
+
+        let content = Config::from_str(toml).unwrap().content;
+        let rule = content.site_rule("www.longreads.example").unwrap();
+        assert_eq!(rule.next_page.as_deref(), Some("a.next-page"));
+        assert_eq!(rule.max_pages, 10);
+        assert_eq!(content.site_rule("mag.other.example").unwrap().max_pages, 3);
+        assert!(content.site_rule("notother.example").is_none());
+    }
+
     #[test]
     fn test_config_path_contains_beatcheck() {
         let path = Config::config_path();
@@ -1,4 +1,8 @@
 mod schema;
 mod repository;
+mod query;
 
-pub use repository::Repository;
+pub use query::{Query, FIELDS as QUERY_FIELDS};
+pub use repository::{
+    ArticleBatch, ArticleFilter, AuditFilter, DbStats, FeedBulkUpdate, FeedRemoval, ListedFeed, Pipeline, Repository, TableSize,
+};
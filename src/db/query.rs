@@ -0,0 +1,601 @@
+//! The search language, e.g. `feed:verge tag:ai -title:rumor after:2025-01-01 "open source"`.
+//!
+//! Terms are ANDed; `OR` joins alternatives, `-` negates a term or group and
+//! parentheses group. A bare word or "quoted phrase" matches title, text or
+//! author. Field terms are listed in `FIELDS`; a value with spaces is quoted
+//! (`author:"Kara Swisher"`). `re:PATTERN` is a regular expression over
+//! title and text, for exact patterns like `re:"CVE-\d{4}-\d+"`.
+
+use std::fmt;
+use std::time::Duration;
+
+use chrono::NaiveDate;
+use regex::{Regex, RegexBuilder};
+use rusqlite::types::Value;
+
+use crate::feed::normalize_link_target;
+
+/// Field names, for error messages and help
+pub const FIELDS: &str = "feed, folder, source, beat, tag, title, author, entity, site, links, after, before, is, has, re";
+
+/// Longest `re:` pattern, in characters
+const REGEX_MAX_PATTERN: usize = 256;
+/// Compiled size allowed for a `re:` pattern
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+/// Text matched against a `re:` pattern, per field of each article
+pub const REGEX_MAX_TEXT: usize = 256 * 1024;
+/// A query with a `re:` term is abandoned after this long
+pub const REGEX_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// A parsed search, ready to add to an article query
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    expr: Expr,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+    Not(Box<Expr>),
+    Term(Term),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Term {
+    /// Title, text content or author contains it
+    Text(String),
+    /// Feed title or URL contains it
+    Feed(String),
+    /// The feed's folder is exactly this, ignoring case
+    Folder(String),
+    /// The feed's beat, contact or notes contain it
+    Source(String),
+    /// The feed's beat contains it
+    Beat(String),
+    Tag(String),
+    Title(String),
+    Author(String),
+    Entity(String),
+    /// Article URL contains it
+    Site(String),
+    /// The content links to this domain (or a subdomain), or to this URL
+    /// or a page under it
+    Links(String),
+    After(NaiveDate),
+    Before(NaiveDate),
+    Is(Flag),
+    Has(Extra),
+    /// Title or text matches the pattern
+    Regex(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Flag {
+    Starred,
+    Pinned,
+    Read,
+    Unread,
+    Queued,
+    /// Held back by an embargo that hasn't lifted yet
+    Embargoed,
+    /// Taken down from its site since it was saved
+    Removed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Extra {
+    Summary,
+    Discussion,
+    Audio,
+    /// Changed on a re-fetch, with the earlier version kept
+    Revisions,
+}
+
+/// Why a query didn't parse; `column` counts characters from 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryError {
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "column {}: {}", self.column, self.message)
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    Minus,
+    Or,
+    Word(String),
+    Phrase(String),
+    /// `name:value`, the value unquoted
+    Field(String, String),
+}
+
+impl Query {
+    pub fn parse(input: &str) -> Result<Self, QueryError> {
+        let tokens = tokenize(input)?;
+        if tokens.is_empty() {
+            return Err(QueryError {
+                column: 1,
+                message: "empty query".to_string(),
+            });
+        }
+        let mut parser = Parser { tokens, pos: 0, end: input.chars().count() + 1 };
+        let expr = parser.sequence()?;
+        if let Some((column, _)) = parser.peek() {
+            return Err(QueryError {
+                column,
+                message: "unmatched ')'".to_string(),
+            });
+        }
+        Ok(Self { expr })
+    }
+
+    /// A WHERE condition over `ARTICLE_SELECT`'s aliases, binding its values
+    /// as numbered parameters after the ones already in `values`. Read, star
+    /// and queue flags and tags are the local ones, or server user `user_id`'s.
+    pub fn to_sql(&self, values: &mut Vec<Value>, user_id: Option<i64>) -> String {
+        expr_sql(&self.expr, values, user_id)
+    }
+
+    /// Whether running it needs the `regexp` SQL function (and its time limit)
+    pub fn uses_regex(&self) -> bool {
+        fn walk(expr: &Expr) -> bool {
+            match expr {
+                Expr::And(terms) | Expr::Or(terms) => terms.iter().any(walk),
+                Expr::Not(inner) => walk(inner),
+                Expr::Term(term) => matches!(term, Term::Regex(_)),
+            }
+        }
+        walk(&self.expr)
+    }
+
+    /// Articles linking to `target`, as `links:` matches it
+    pub fn links_to(target: &str) -> Self {
+        Self {
+            expr: Expr::Term(Term::Links(target.to_string())),
+        }
+    }
+
+    /// Whether it asks for embargoed articles, which listings otherwise hide
+    pub fn shows_embargoed(&self) -> bool {
+        fn walk(expr: &Expr) -> bool {
+            match expr {
+                Expr::And(terms) | Expr::Or(terms) => terms.iter().any(walk),
+                Expr::Not(inner) => walk(inner),
+                Expr::Term(term) => matches!(term, Term::Is(Flag::Embargoed)),
+            }
+        }
+        walk(&self.expr)
+    }
+}
+
+/// Compile a `re:` pattern within the length and size limits
+pub fn compile_regex(pattern: &str) -> Result<Regex, String> {
+    if pattern.chars().count() > REGEX_MAX_PATTERN {
+        return Err(format!("pattern is longer than {} characters", REGEX_MAX_PATTERN));
+    }
+    RegexBuilder::new(pattern)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .build()
+        .map_err(|e| match e {
+            regex::Error::CompiledTooBig(_) => "pattern is too complex".to_string(),
+            // The last line says what's wrong; the rest repeats the pattern
+            e => e.to_string().lines().last().unwrap_or_default().trim_start_matches("error: ").to_string(),
+        })
+}
+
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, QueryError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let column = i + 1;
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        match c {
+            '(' => {
+                tokens.push((column, Token::Open));
+                i += 1;
+            }
+            ')' => {
+                tokens.push((column, Token::Close));
+                i += 1;
+            }
+            '-' if chars.get(i + 1).is_some_and(|n| !n.is_whitespace()) => {
+                tokens.push((column, Token::Minus));
+                i += 1;
+            }
+            // A dash on its own means nothing
+            '-' => i += 1,
+            '"' => {
+                let (phrase, next) = read_phrase(&chars, i)?;
+                tokens.push((column, Token::Phrase(phrase)));
+                i = next;
+            }
+            _ => {
+                let start = i;
+                while i < chars.len() && !chars[i].is_whitespace() && !matches!(chars[i], '(' | ')' | '"') {
+                    if chars[i] == ':' {
+                        break;
+                    }
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                if i < chars.len() && chars[i] == ':' {
+                    // name:"quoted value" or name:value
+                    i += 1;
+                    let value = if chars.get(i) == Some(&'"') {
+                        let (value, next) = read_phrase(&chars, i)?;
+                        i = next;
+                        value
+                    } else {
+                        let start = i;
+                        while i < chars.len() && !chars[i].is_whitespace() && !matches!(chars[i], '(' | ')' | '"') {
+                            i += 1;
+                        }
+                        chars[start..i].iter().collect()
+                    };
+                    tokens.push((column, Token::Field(word.to_lowercase(), value)));
+                } else if word == "OR" {
+                    tokens.push((column, Token::Or));
+                } else {
+                    tokens.push((column, Token::Word(word)));
+                }
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// The phrase starting at the quote at `start`, and the index after its
+/// closing quote
+fn read_phrase(chars: &[char], start: usize) -> Result<(String, usize), QueryError> {
+    match chars[start + 1..].iter().position(|&c| c == '"') {
+        Some(len) => Ok((chars[start + 1..start + 1 + len].iter().collect(), start + len + 2)),
+        None => Err(QueryError {
+            column: start + 1,
+            message: "unclosed quote".to_string(),
+        }),
+    }
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    /// Column just past the input, for errors at the end
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<(usize, &Token)> {
+        self.tokens.get(self.pos).map(|(column, token)| (*column, token))
+    }
+
+    /// Terms up to the end or a closing parenthesis, ANDed
+    fn sequence(&mut self) -> Result<Expr, QueryError> {
+        let mut terms = Vec::new();
+        while let Some((_, token)) = self.peek() {
+            if *token == Token::Close {
+                break;
+            }
+            terms.push(self.alternatives()?);
+        }
+        Ok(match terms.len() {
+            1 => terms.pop().unwrap(),
+            _ => Expr::And(terms),
+        })
+    }
+
+    fn alternatives(&mut self) -> Result<Expr, QueryError> {
+        let mut options = vec![self.unary()?];
+        while let Some((column, Token::Or)) = self.peek() {
+            self.pos += 1;
+            if matches!(self.peek(), None | Some((_, Token::Close | Token::Or))) {
+                return Err(QueryError {
+                    column,
+                    message: "OR needs a term on both sides".to_string(),
+                });
+            }
+            options.push(self.unary()?);
+        }
+        Ok(match options.len() {
+            1 => options.pop().unwrap(),
+            _ => Expr::Or(options),
+        })
+    }
+
+    fn unary(&mut self) -> Result<Expr, QueryError> {
+        let Some((column, token)) = self.peek() else {
+            return Err(QueryError {
+                column: self.end,
+                message: "expected a term".to_string(),
+            });
+        };
+        let token = token.clone();
+        self.pos += 1;
+        match token {
+            Token::Minus => Ok(Expr::Not(Box::new(self.unary()?))),
+            Token::Open => {
+                let inner = self.sequence()?;
+                match self.peek() {
+                    Some((_, Token::Close)) => {
+                        self.pos += 1;
+                        if inner == Expr::And(Vec::new()) {
+                            return Err(QueryError {
+                                column,
+                                message: "empty parentheses".to_string(),
+                            });
+                        }
+                        Ok(inner)
+                    }
+                    _ => Err(QueryError {
+                        column,
+                        message: "unclosed '('".to_string(),
+                    }),
+                }
+            }
+            Token::Close => Err(QueryError {
+                column,
+                message: "unmatched ')'".to_string(),
+            }),
+            Token::Or => Err(QueryError {
+                column,
+                message: "OR needs a term on both sides".to_string(),
+            }),
+            Token::Word(word) | Token::Phrase(word) => Ok(Expr::Term(Term::Text(word))),
+            Token::Field(name, value) => field_term(&name, value, column).map(Expr::Term),
+        }
+    }
+}
+
+fn field_term(name: &str, value: String, column: usize) -> Result<Term, QueryError> {
+    let error = |message: String| QueryError { column, message };
+    if value.trim().is_empty() {
+        return Err(error(format!("{}: needs a value", name)));
+    }
+    let date = |value: &str| {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .map_err(|_| error(format!("{}: expects a date like 2025-01-31, not \"{}\"", name, value)))
+    };
+    Ok(match name {
+        "feed" => Term::Feed(value),
+        "folder" => Term::Folder(value),
+        "source" => Term::Source(value),
+        "beat" => Term::Beat(value),
+        "tag" => Term::Tag(value.trim_start_matches('#').to_lowercase()),
+        "title" => Term::Title(value),
+        "author" | "by" => Term::Author(value),
+        "entity" => Term::Entity(value.trim_start_matches('$').to_string()),
+        "site" | "url" => Term::Site(value),
+        "links" | "cites" => Term::Links(value),
+        "after" => Term::After(date(&value)?),
+        "before" => Term::Before(date(&value)?),
+        "is" => Term::Is(match value.to_lowercase().as_str() {
+            "starred" => Flag::Starred,
+            "pinned" => Flag::Pinned,
+            "read" => Flag::Read,
+            "unread" => Flag::Unread,
+            "queued" => Flag::Queued,
+            "embargoed" => Flag::Embargoed,
+            "removed" => Flag::Removed,
+            _ => {
+                return Err(error(format!(
+                    "is:{} isn't one of starred, pinned, read, unread, queued, embargoed, removed",
+                    value
+                )))
+            }
+        }),
+        "has" => Term::Has(match value.to_lowercase().as_str() {
+            "summary" => Extra::Summary,
+            "discussion" => Extra::Discussion,
+            "audio" => Extra::Audio,
+            "revisions" => Extra::Revisions,
+            _ => return Err(error(format!("has:{} isn't one of summary, discussion, audio, revisions", value))),
+        }),
+        "re" => {
+            compile_regex(&value).map_err(|e| error(format!("re: {}", e)))?;
+            Term::Regex(value)
+        }
+        _ => return Err(error(format!("unknown field \"{}\" (fields: {})", name, FIELDS))),
+    })
+}
+
+fn expr_sql(expr: &Expr, values: &mut Vec<Value>, user_id: Option<i64>) -> String {
+    match expr {
+        Expr::And(terms) if terms.is_empty() => "1".to_string(),
+        Expr::And(terms) => join(terms, " AND ", values, user_id),
+        Expr::Or(options) => join(options, " OR ", values, user_id),
+        Expr::Not(inner) => format!("NOT {}", expr_sql(inner, values, user_id)),
+        Expr::Term(term) => term_sql(term, values, user_id),
+    }
+}
+
+fn join(exprs: &[Expr], separator: &str, values: &mut Vec<Value>, user_id: Option<i64>) -> String {
+    let parts: Vec<String> = exprs.iter().map(|e| expr_sql(e, values, user_id)).collect();
+    format!("({})", parts.join(separator))
+}
+
+/// A term as server user `user_id` sees it, where that differs: read,
+/// starred and queued from their own state, never pinned (pins belong to
+/// the local reader), and their own tags besides the article's
+fn user_term_sql(term: &Term, user_id: i64, values: &mut Vec<Value>) -> Option<String> {
+    let (condition, set) = match term {
+        Term::Is(Flag::Starred) => ("s.is_starred = 1", true),
+        Term::Is(Flag::Read) => ("s.is_read = 1", true),
+        Term::Is(Flag::Unread) => ("s.is_read = 1", false),
+        Term::Is(Flag::Queued) => ("s.queued_at IS NOT NULL", true),
+        Term::Is(Flag::Pinned) => return Some("0".to_string()),
+        Term::Tag(tag) => {
+            values.push(tag.clone().into());
+            let tag = values.len();
+            values.push(user_id.into());
+            return Some(format!(
+                "(a.id IN (SELECT article_id FROM article_tags WHERE tag = ?{tag}) OR \
+                 a.id IN (SELECT article_id FROM user_article_tags WHERE tag = ?{tag} AND user_id = ?{}))",
+                values.len()
+            ));
+        }
+        _ => return None,
+    };
+    values.push(user_id.into());
+    let exists = format!(
+        "EXISTS (SELECT 1 FROM user_article_state s WHERE s.user_id = ?{} AND s.article_id = a.id AND {})",
+        values.len(),
+        condition
+    );
+    Some(if set { exists } else { format!("NOT {}", exists) })
+}
+
+fn term_sql(term: &Term, values: &mut Vec<Value>, user_id: Option<i64>) -> String {
+    if let Some(sql) = user_id.and_then(|user_id| user_term_sql(term, user_id, values)) {
+        return sql;
+    }
+    let mut bind = |value: String| {
+        values.push(value.into());
+        values.len()
+    };
+    let contains = |s: &str| format!("%{}%", s);
+    match term {
+        Term::Text(text) => {
+            let n = bind(contains(text));
+            format!("(a.title LIKE ?{n} OR decompress(a.content_text) LIKE ?{n} OR a.author LIKE ?{n})")
+        }
+        Term::Feed(feed) => {
+            let n = bind(contains(feed));
+            format!("(COALESCE(f.custom_title, f.title) LIKE ?{n} OR f.url LIKE ?{n})")
+        }
+        Term::Folder(folder) => format!("f.folder = ?{} COLLATE NOCASE", bind(folder.clone())),
+        Term::Source(text) => {
+            let n = bind(contains(text));
+            format!("(f.beat LIKE ?{n} OR f.contact LIKE ?{n} OR f.notes LIKE ?{n})")
+        }
+        Term::Beat(beat) => format!("COALESCE(f.beat, '') LIKE ?{}", bind(contains(beat))),
+        Term::Tag(tag) => format!(
+            "a.id IN (SELECT article_id FROM article_tags WHERE tag = ?{})",
+            bind(tag.clone())
+        ),
+        Term::Title(title) => format!("a.title LIKE ?{}", bind(contains(title))),
+        Term::Author(author) => format!("COALESCE(a.author, '') LIKE ?{}", bind(contains(author))),
+        Term::Entity(entity) => format!(
+            "a.id IN (SELECT article_id FROM article_entities WHERE name = ?{} COLLATE NOCASE)",
+            bind(entity.clone())
+        ),
+        Term::Site(site) => format!("a.url LIKE ?{}", bind(contains(site))),
+        Term::Links(target) => {
+            let target = normalize_link_target(target);
+            if target.contains('/') {
+                format!(
+                    "a.id IN (SELECT article_id FROM article_links WHERE page LIKE ?{})",
+                    bind(format!("{}%", target))
+                )
+            } else {
+                let n = bind(target);
+                format!("a.id IN (SELECT article_id FROM article_links WHERE domain = ?{n} OR domain LIKE '%.' || ?{n})")
+            }
+        }
+        Term::After(day) => format!(
+            "datetime(COALESCE(a.published_at, a.fetched_at)) >= datetime(?{})",
+            bind(day.to_string())
+        ),
+        Term::Before(day) => format!(
+            "datetime(COALESCE(a.published_at, a.fetched_at)) < datetime(?{})",
+            bind(day.to_string())
+        ),
+        Term::Is(flag) => match flag {
+            Flag::Starred => "a.is_starred = 1",
+            Flag::Pinned => "a.is_pinned = 1",
+            Flag::Read => "a.is_read = 1",
+            Flag::Unread => "a.is_read = 0",
+            Flag::Queued => "a.queued_at IS NOT NULL",
+            Flag::Embargoed => "datetime(a.embargo_until) > datetime('now')",
+            Flag::Removed => "a.removed_at IS NOT NULL",
+        }
+        .to_string(),
+        Term::Regex(pattern) => {
+            let n = bind(pattern.clone());
+            format!("(regexp(?{n}, a.title) OR regexp(?{n}, decompress(a.content_text)))")
+        }
+        Term::Has(extra) => match extra {
+            Extra::Summary => "EXISTS (SELECT 1 FROM summaries WHERE article_id = a.id)",
+            Extra::Discussion => "d.site IS NOT NULL",
+            Extra::Audio => "a.enclosure_url IS NOT NULL",
+            Extra::Revisions => "EXISTS (SELECT 1 FROM article_revisions WHERE article_id = a.id)",
+        }
+        .to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(input: &str) -> String {
+        Query::parse(input).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_parse_fields_negation_and_phrases() {
+        let query = Query::parse("feed:verge tag:#AI -title:rumor after:2025-01-01 \"open source\"").unwrap();
+        let terms = match query.expr {
+            Expr::And(terms) => terms,
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(terms.len(), 5);
+        assert_eq!(terms[1], Expr::Term(Term::Tag("ai".to_string())));
+        assert_eq!(terms[2], Expr::Not(Box::new(Expr::Term(Term::Title("rumor".to_string())))));
+        assert_eq!(terms[4], Expr::Term(Term::Text("open source".to_string())));
+
+        let query = Query::parse("author:\"Kara Swisher\" (tag:ai OR tag:ml)").unwrap();
+        let mut values = Vec::new();
+        assert_eq!(
+            query.to_sql(&mut values, None),
+            "(COALESCE(a.author, '') LIKE ?1 AND \
+             (a.id IN (SELECT article_id FROM article_tags WHERE tag = ?2) OR \
+             a.id IN (SELECT article_id FROM article_tags WHERE tag = ?3)))"
+        );
+        assert_eq!(values[0], Value::from("%Kara Swisher%".to_string()));
+        assert_eq!(
+            Query::parse("folder:Security").unwrap().expr,
+            Expr::Term(Term::Folder("Security".to_string()))
+        );
+        let mut values = Vec::new();
+        assert_eq!(
+            Query::parse("source:\"press office\"").unwrap().to_sql(&mut values, None),
+            "(f.beat LIKE ?1 OR f.contact LIKE ?1 OR f.notes LIKE ?1)"
+        );
+        assert_eq!(values, [Value::from("%press office%".to_string())]);
+    }
+
+    #[test]
+    fn test_parse_errors_point_at_the_problem() {
+        assert_eq!(error("tag:ai \"open"), "column 8: unclosed quote");
+        assert_eq!(error("fed:verge"), format!("column 1: unknown field \"fed\" (fields: {})", FIELDS));
+        assert_eq!(error("after:yesterday"), "column 1: after: expects a date like 2025-01-31, not \"yesterday\"");
+        assert_eq!(error("(tag:ai"), "column 1: unclosed '('");
+        assert_eq!(error("tag:ai)"), "column 7: unmatched ')'");
+        assert_eq!(error("rust OR"), "column 6: OR needs a term on both sides");
+        assert_eq!(
+            error("is:new"),
+            "column 1: is:new isn't one of starred, pinned, read, unread, queued, embargoed, removed"
+        );
+        assert_eq!(error("  "), "column 1: empty query");
+        assert_eq!(error("tag:ai re:\"v(1\""), "column 8: re: unclosed group");
+        assert!(error(&format!("re:{}", "a".repeat(300))).contains("longer than 256"));
+        assert!(Query::parse(r"re:CVE-\d{4}-\d+").unwrap().uses_regex());
+        assert!(Query::parse("feed:wire (is:embargoed OR is:starred)").unwrap().shows_embargoed());
+        // A hyphen inside a word, or on its own, is just text
+        assert!(Query::parse("covid-19 - vaccine").is_ok());
+    }
+}
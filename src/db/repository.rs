@@ -1,12 +1,257 @@
-use chrono::{DateTime, Utc};
-use rusqlite::{params, OptionalExtension, Row};
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, OptionalExtension, Row};
 use tokio_rusqlite::Connection;
 
-use crate::error::Result;
-use crate::models::{Article, Feed, NewArticle, NewFeed, Summary};
+use crate::error::{AppError, Result};
+use crate::feed::link_target;
+use crate::models::{
+    estimate_reading_minutes, ApiToken, Article, ArticleRevision, AuditEvent, AuthorCount, AuthorRule, Collection, Discussion, Enclosure, EntityCount, EntityKind, Feed,
+    FeedPriority, NewArticle, NewFeed, ReadingMode, SourceInfo, Summary, SummaryFailure, TagRule, TokenScope, User,
+    ORPHANED_FEED_URL, SAVED_FEED_URL,
+};
+
+use super::query::{compile_regex, Query, REGEX_MAX_TEXT, REGEX_TIMEOUT};
+use super::schema::{migrate, SCHEMA};
+
+/// Automatic retries of a failed summary before it's left for the user
+const SUMMARY_RETRY_LIMIT: u32 = 4;
+
+/// Wait before the first automatic retry; each later one waits 4x longer
+const SUMMARY_RETRY_BASE_MINUTES: i64 = 5;
+
+/// Opened articles remembered in the history view
+const HISTORY_LIMIT: usize = 200;
+
+/// Earlier versions kept per article; the oldest go first
+const REVISIONS_KEPT: usize = 20;
+
+/// Article text shorter than this is stored uncompressed
+const COMPRESS_MIN_BYTES: usize = 256;
+
+/// zstd level for stored article text: fast, and most of the gain
+const COMPRESS_LEVEL: i32 = 3;
+
+/// The tables behind every article query: `a`, its feed `f`, playback `pb`
+/// and the most-discussed of its discussions `d`
+macro_rules! article_from {
+    () => {
+        r#"FROM articles a
+           JOIN feeds f ON a.feed_id = f.id AND f.archived_at IS NULL
+           LEFT JOIN playback pb ON pb.article_id = a.id
+           LEFT JOIN discussions d ON d.article_id = a.id AND d.site =
+               (SELECT site FROM discussions WHERE article_id = a.id
+                ORDER BY points + comments DESC LIMIT 1)"#
+    };
+}
+
+/// Column list shared by every article query; must stay in sync with `article_from_row`.
+const ARTICLE_SELECT: &str = concat!(r#"SELECT a.id, a.feed_id, a.guid, a.title, a.url, a.author, decompress(a.content),
+                              decompress(a.content_text), a.published_at, a.fetched_at,
+                              COALESCE(f.custom_title, f.title) as feed_title, a.is_starred, a.is_pinned,
+                              a.reading_minutes, a.queued_at IS NOT NULL, a.is_read,
+                              decompress(a.full_text), a.comments_url,
+                              d.site, d.url, d.points, d.comments,
+                              a.enclosure_url, a.enclosure_type, a.enclosure_duration,
+                              pb.position_secs, COALESCE(pb.is_played, 0), a.extras,
+                              a.archive_path,
+                              (SELECT GROUP_CONCAT(tag, ',') FROM article_tags WHERE article_id = a.id),
+                              EXISTS (SELECT 1 FROM authors w JOIN author_rules r ON r.name = w.name
+                                      WHERE w.article_id = a.id AND r.rule = 'follow'),
+                              a.embargo_until,
+                              (SELECT COUNT(*) FROM article_revisions WHERE article_id = a.id),
+                              a.removed_at
+                       "#, article_from!());
+
+/// Column list shared by every feed query; must stay in sync with `feed_from_row`.
+const FEED_SELECT: &str = r#"SELECT f.id, f.title, f.url, f.site_url, f.description, f.last_fetched,
+                           f.created_at, f.updated_at, f.retry_after, f.custom_title,
+                           f.folder, f.refresh_minutes, f.paused,
+                           (SELECT GROUP_CONCAT(tag, ',') FROM
+                               (SELECT tag FROM feed_tags WHERE feed_id = f.id ORDER BY tag)),
+                           f.posts_per_day, f.last_post_at, f.unread_count, f.reading, f.priority,
+                           f.beat, f.contact, f.reliability, f.notes, f.reader_url,
+                           f.missing_count, f.moved_to, f.repaired
+                    FROM feeds f"#;
+
+/// Articles whose embargo (if any) has lifted. Needs `a`.
+const NOT_EMBARGOED: &str = "(a.embargo_until IS NULL OR datetime(a.embargo_until) <= datetime('now'))";
+
+/// Articles the server user `?{n}` can see: in a feed they follow, or a
+/// link they saved themselves. Needs `a`.
+const USER_VISIBLE: &str = "(a.feed_id IN (SELECT feed_id FROM user_feeds WHERE user_id = ?{n}) \
+     OR EXISTS (SELECT 1 FROM user_article_state v \
+     WHERE v.user_id = ?{n} AND v.article_id = a.id AND v.saved_at IS NOT NULL))";
+
+/// Articles the server user `?{n}` hasn't deleted for themselves. Needs `a`.
+const NOT_HIDDEN: &str = "NOT EXISTS (SELECT 1 FROM user_article_state h \
+     WHERE h.user_id = ?{n} AND h.article_id = a.id AND h.is_hidden = 1)";
+
+/// Feeds sort by the name the user sees
+const FEED_ORDER: &str = "COALESCE(f.custom_title, f.title) COLLATE NOCASE";
+
+/// When an article sorts: when it came back from a snooze or its embargo
+/// lifts, or else its publish date, moved half a day later for a
+/// high-priority feed and half a day earlier for a low one. Needs `a` and `f`.
+macro_rules! article_sort_date {
+    () => {
+        r#"COALESCE(datetime(a.surfaced_at), datetime(a.embargo_until),
+                    datetime(a.published_at, CASE f.priority WHEN 'high' THEN '+12 hours'
+                             WHEN 'low' THEN '-12 hours' ELSE '+0 hours' END), '')"#
+    };
+}
+
+/// Display order for article lists. NULL publish dates sort last (COALESCE to
+/// the empty string) and the id breaks ties so keyset pagination is stable.
+const ARTICLE_ORDER: &str = concat!(article_sort_date!(), " DESC, a.fetched_at DESC, a.id DESC");
+
+/// Optional restrictions for article listings
+#[derive(Debug, Clone, Default)]
+pub struct ArticleFilter {
+    pub feed_id: Option<i64>,
+    /// Only articles from these feeds (a folder, say)
+    pub feed_ids: Option<Vec<i64>>,
+    /// Only articles published, or if undated fetched, since then
+    pub since: Option<DateTime<Utc>>,
+    /// ...and before then
+    pub until: Option<DateTime<Utc>>,
+    pub starred_only: bool,
+    pub unread_only: bool,
+    /// Case-insensitive substring match on title, text content or author
+    pub text: Option<String>,
+    /// Limit to this server user's subscriptions and report their read/star
+    /// state instead of the local one
+    pub user_id: Option<i64>,
+    /// Only articles with this suggested tag
+    pub tag: Option<String>,
+    /// Only articles mentioning this entity (any kind, case-insensitive)
+    pub entity: Option<String>,
+    /// Only articles with this author in the byline (case-insensitive)
+    pub author: Option<String>,
+    /// Only articles without a summary yet
+    pub unsummarized: bool,
+    /// Only articles matching this search
+    pub query: Option<Query>,
+}
+
+/// Which audit log entries `get_audit_log` returns
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuditFilter {
+    /// Actions starting with this: `feed` covers `feed.add` and `feed.remove`
+    pub action: Option<String>,
+    /// Case-insensitive substring of the subject or detail (a feed's URL or title)
+    pub text: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+}
+
+/// Changes `bulk_update_feeds` applies to every selected feed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeedBulkUpdate {
+    /// `Some(None)` takes the feeds out of their folder
+    pub folder: Option<Option<String>>,
+    pub add_tags: Vec<String>,
+    pub remove_tags: Vec<String>,
+    /// `Some(None)` goes back to the global refresh interval
+    pub refresh_minutes: Option<Option<u32>>,
+    pub paused: Option<bool>,
+    pub reading: Option<ReadingMode>,
+    pub priority: Option<FeedPriority>,
+    /// `Some(None)` clears the field
+    pub beat: Option<Option<String>>,
+    pub contact: Option<Option<String>>,
+    pub reliability: Option<Option<u8>>,
+    pub notes: Option<Option<String>>,
+    /// `Some(None)` opens and fetches articles directly again
+    pub reader_url: Option<Option<String>>,
+    /// Remove the feeds instead; the other changes are ignored
+    pub remove: Option<FeedRemoval>,
+}
+
+/// A feed as the urls file lists it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListedFeed {
+    pub id: i64,
+    pub tags: Vec<String>,
+    /// Shown instead of the feed's own title
+    pub title: Option<String>,
+}
+
+/// What `update_articles` does to every selected article
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArticleBatch {
+    Read(bool),
+    /// Unstarring also unpins
+    Starred(bool),
+    /// Add this tag, as if typed by the user (kept when retagging)
+    Tag(String),
+    /// Delete, remembering them so a refresh doesn't bring them back
+    Delete,
+}
+
+/// What happens to a feed's articles when it is removed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedRemoval {
+    /// Delete the feed and every article in it
+    DeleteAll,
+    /// Move starred articles to the "Orphaned" pseudo-feed, delete the rest
+    KeepStarred,
+    /// Hide the feed and its articles but keep them, so it can be restored
+    Archive,
+}
+
+/// Long-running work whose progress is journaled, so a crash part way
+/// through neither loses nor redoes it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pipeline {
+    /// Feeds in the running refresh whose articles aren't stored yet
+    Refresh,
+    /// Articles queued for a summary
+    Summary,
+    /// Articles queued for a full-text fetch
+    FullText,
+}
+
+impl Pipeline {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Refresh => "refresh",
+            Self::Summary => "summary",
+            Self::FullText => "fulltext",
+        }
+    }
+}
+
+/// How big the database is, overall and per table (with its indexes)
+#[derive(Debug, Clone, Default)]
+pub struct DbStats {
+    pub file_bytes: i64,
+    /// Unused pages, handed back by the next vacuum
+    pub free_bytes: i64,
+    /// Largest first
+    pub tables: Vec<TableSize>,
+    /// When the sizes the tables are compared with were recorded: the last
+    /// maintenance run at least a week ago, or else the earliest one
+    pub since: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableSize {
+    pub name: String,
+    pub rows: i64,
+    pub bytes: i64,
+    /// Its size at `DbStats::since`
+    pub previous_bytes: Option<i64>,
+}
 
-use super::schema::SCHEMA;
+/// Table sizes recorded by maintenance are kept this long
+const SIZE_HISTORY_DAYS: i64 = 90;
 
+/// Audit log entries are kept this long
+const AUDIT_HISTORY_DAYS: i64 = 365;
+
+#[derive(Clone)]
 pub struct Repository {
     conn: Connection,
 }
@@ -20,7 +265,16 @@ impl Repository {
             conn.busy_timeout(std::time::Duration::from_secs(5))?;
             // Enable WAL mode for better concurrency
             conn.execute_batch("PRAGMA journal_mode=WAL;")?;
+            // Lets `maintain` give free pages back a few at a time; a database
+            // made before this switches over at its next full vacuum
+            conn.execute_batch("PRAGMA auto_vacuum = INCREMENTAL;")?;
             conn.execute_batch(SCHEMA)?;
+            // Before migrating: a migration compresses the stored text
+            register_compression(conn)?;
+            migrate(conn)?;
+            register_regexp(conn)?;
+            backfill_reading_minutes(conn)?;
+            backfill_feed_stats(conn)?;
             Ok(())
         })
         .await?;
@@ -30,15 +284,22 @@ impl Repository {
 
     // Feed operations
 
+    /// Add a feed. Subscribing again to an archived feed's URL restores it.
     pub async fn insert_feed(&self, feed: NewFeed) -> Result<i64> {
         let id = self
             .conn
             .call(move |conn| {
-                conn.execute(
-                    "INSERT INTO feeds (title, url, site_url, description) VALUES (?1, ?2, ?3, ?4)",
+                let id = conn.query_row(
+                    r#"INSERT INTO feeds (title, url, site_url, description) VALUES (?1, ?2, ?3, ?4)
+                       ON CONFLICT(url) DO UPDATE SET archived_at = NULL
+                       RETURNING id"#,
                     params![feed.title, feed.url, feed.site_url, feed.description],
+                    |row| row.get(0),
                 )?;
-                Ok(conn.last_insert_rowid())
+                if !feed.url.starts_with("beatcheck:") {
+                    audit(conn, "feed.add", Some(&feed.url), Some(&feed.title))?;
+                }
+                Ok(id)
             })
             .await?;
         Ok(id)
@@ -48,9 +309,28 @@ impl Repository {
         let feeds = self
             .conn
             .call(|conn| {
-                let mut stmt = conn.prepare(
-                    "SELECT id, title, url, site_url, description, last_fetched, created_at, updated_at FROM feeds ORDER BY title",
-                )?;
+                let mut stmt = conn.prepare(&format!(
+                    "{} WHERE f.archived_at IS NULL ORDER BY {}",
+                    FEED_SELECT, FEED_ORDER
+                ))?;
+                let feeds = stmt
+                    .query_map([], |row| Ok(feed_from_row(row)))?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(feeds)
+            })
+            .await?;
+        Ok(feeds)
+    }
+
+    /// Feeds removed with `FeedRemoval::Archive`, most recently archived first
+    pub async fn get_archived_feeds(&self) -> Result<Vec<Feed>> {
+        let feeds = self
+            .conn
+            .call(|conn| {
+                let mut stmt = conn.prepare(&format!(
+                    "{} WHERE f.archived_at IS NOT NULL ORDER BY f.archived_at DESC",
+                    FEED_SELECT
+                ))?;
                 let feeds = stmt
                     .query_map([], |row| Ok(feed_from_row(row)))?
                     .collect::<std::result::Result<Vec<_>, _>>()?;
@@ -60,31 +340,323 @@ impl Repository {
         Ok(feeds)
     }
 
-    pub async fn update_feed_last_fetched(&self, id: i64) -> Result<()> {
+    /// Bring an archived feed back. Returns false if it wasn't archived.
+    pub async fn restore_feed(&self, id: i64) -> Result<bool> {
+        let restored = self
+            .conn
+            .call(move |conn| {
+                let changed = conn.execute(
+                    "UPDATE feeds SET archived_at = NULL WHERE id = ?1 AND archived_at IS NOT NULL",
+                    params![id],
+                )?;
+                Ok(changed > 0)
+            })
+            .await?;
+        Ok(restored)
+    }
+
+    /// Record a successful fetch, and whether its XML needed repairs, and
+    /// recompute the feed's posting stats. A URL proposed after earlier
+    /// 404s is dropped, since this one works.
+    pub async fn update_feed_last_fetched(&self, id: i64, repaired: bool) -> Result<()> {
+        self.conn
+            .call(move |conn| {
+                conn.execute(
+                    r#"UPDATE feeds SET last_fetched = datetime('now'), updated_at = datetime('now'), retry_after = NULL,
+                                        missing_count = 0, moved_to = NULL, repaired = ?2
+                       WHERE id = ?1"#,
+                    params![id, repaired],
+                )?;
+                update_feed_stats(conn, id)?;
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Rename a feed for display; `None` goes back to the feed's own title.
+    /// Metadata refreshes and OPML imports never touch this.
+    pub async fn set_feed_custom_title(&self, id: i64, title: Option<String>) -> Result<()> {
+        self.conn
+            .call(move |conn| {
+                conn.execute(
+                    "UPDATE feeds SET custom_title = ?1 WHERE id = ?2",
+                    params![title, id],
+                )?;
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Store re-fetched feed metadata (the URL is left alone). With
+    /// `keep_title` the stored title is not touched. Returns whether
+    /// anything changed.
+    pub async fn update_feed_metadata(&self, id: i64, meta: NewFeed, keep_title: bool) -> Result<bool> {
+        let changed = self
+            .conn
+            .call(move |conn| {
+                let changed = conn.execute(
+                    r#"UPDATE feeds SET title = CASE WHEN ?5 THEN title ELSE ?1 END,
+                                        description = ?2,
+                                        site_url = COALESCE(?3, site_url),
+                                        updated_at = datetime('now')
+                       WHERE id = ?4
+                         AND ((NOT ?5 AND title IS NOT ?1) OR description IS NOT ?2
+                              OR (?3 IS NOT NULL AND site_url IS NOT ?3))"#,
+                    params![meta.title, meta.description, meta.site_url, id, keep_title],
+                )?;
+                Ok(changed > 0)
+            })
+            .await?;
+        Ok(changed)
+    }
+
+    /// Record a 429/503 from the feed's host and skip it until `until`
+    pub async fn record_feed_throttled(&self, id: i64, until: DateTime<Utc>) -> Result<()> {
         self.conn
             .call(move |conn| {
                 conn.execute(
-                    "UPDATE feeds SET last_fetched = datetime('now'), updated_at = datetime('now') WHERE id = ?1",
+                    r#"UPDATE feeds SET retry_after = ?1,
+                                        throttled_count = throttled_count + 1,
+                                        last_throttled_at = datetime('now')
+                       WHERE id = ?2"#,
+                    params![until.to_rfc3339(), id],
+                )?;
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Record a 404 or 410 from the feed's URL, with the URL rediscovered
+    /// from its site if there is one. Returns the 404s in a row so far.
+    pub async fn record_feed_missing(&self, id: i64, moved_to: Option<String>) -> Result<u32> {
+        let count = self
+            .conn
+            .call(move |conn| {
+                // The feed may have been removed while it was fetching
+                Ok(conn
+                    .query_row(
+                        r#"UPDATE feeds SET missing_count = missing_count + 1,
+                                            moved_to = COALESCE(?1, moved_to)
+                           WHERE id = ?2
+                           RETURNING missing_count"#,
+                        params![moved_to, id],
+                        |row| row.get(0),
+                    )
+                    .optional()?
+                    .unwrap_or(0))
+            })
+            .await?;
+        Ok(count)
+    }
+
+    /// Switch the feed to the URL proposed for it, keeping its articles.
+    /// Returns the new URL, or `None` if nothing was proposed.
+    pub async fn accept_feed_move(&self, id: i64) -> Result<Option<String>> {
+        let moved = self
+            .conn
+            .call(move |conn| {
+                let tx = conn.transaction()?;
+                let (url, moved_to): (String, Option<String>) = tx.query_row(
+                    "SELECT url, moved_to FROM feeds WHERE id = ?1",
                     params![id],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )?;
+                let Some(moved_to) = moved_to else {
+                    return Ok(Ok(None));
+                };
+                let taken: bool =
+                    tx.query_row("SELECT EXISTS (SELECT 1 FROM feeds WHERE url = ?1)", params![moved_to], |row| row.get(0))?;
+                if taken {
+                    return Ok(Err(moved_to));
+                }
+                tx.execute(
+                    r#"UPDATE feeds SET url = ?1, moved_to = NULL, missing_count = 0, retry_after = NULL,
+                                        updated_at = datetime('now')
+                       WHERE id = ?2"#,
+                    params![moved_to, id],
                 )?;
+                audit(&tx, "feed.move", Some(&url), Some(&moved_to))?;
+                tx.commit()?;
+                Ok(Ok(Some(moved_to)))
+            })
+            .await?;
+        moved.map_err(|url| AppError::Config(format!("Already subscribed to {}", url)))
+    }
+
+    /// Forget the URL proposed for the feed; another run of 404s proposes
+    /// one again
+    pub async fn dismiss_feed_move(&self, id: i64) -> Result<()> {
+        self.conn
+            .call(move |conn| {
+                conn.execute("UPDATE feeds SET moved_to = NULL, missing_count = 0 WHERE id = ?1", params![id])?;
                 Ok(())
             })
             .await?;
         Ok(())
     }
 
-    pub async fn delete_feed(&self, id: i64) -> Result<()> {
+    pub async fn remove_feed(&self, id: i64, removal: FeedRemoval) -> Result<()> {
         self.conn
             .call(move |conn| {
-                conn.execute("DELETE FROM feeds WHERE id = ?1", params![id])?;
+                let tx = conn.transaction()?;
+                remove_feed_rows(&tx, id, removal, None)?;
+                tx.commit()?;
                 Ok(())
             })
             .await?;
         Ok(())
     }
 
+    /// Make the feeds match the urls file: give each listed feed the file's
+    /// tags and name (a name the file gave going away clears it, one given
+    /// in the app stays), and archive feeds that came from the file but
+    /// are no longer in it. Returns the ids of listed feeds that changed,
+    /// and how many were archived.
+    pub async fn sync_listed_feeds(&self, listed: Vec<ListedFeed>) -> Result<(Vec<i64>, usize)> {
+        let synced = self
+            .conn
+            .call(move |conn| {
+                let tx = conn.transaction()?;
+                let mut changed = Vec::new();
+                for feed in &listed {
+                    let (tags, title): (Option<String>, Option<String>) = tx.query_row(
+                        r#"SELECT (SELECT GROUP_CONCAT(tag, ',') FROM
+                                      (SELECT tag FROM feed_tags WHERE feed_id = f.id ORDER BY tag)),
+                                  CASE WHEN ?2 IS NOT NULL THEN ?2
+                                       WHEN f.custom_title IS f.urls_file_title THEN NULL
+                                       ELSE f.custom_title END
+                           FROM feeds f WHERE f.id = ?1"#,
+                        params![feed.id, feed.title],
+                        |row| Ok((row.get(0)?, row.get(1)?)),
+                    )?;
+                    let mut wanted = feed.tags.clone();
+                    wanted.sort();
+                    let tags_changed = tags.unwrap_or_default() != wanted.join(",");
+                    let title_changed = tx.execute(
+                        "UPDATE feeds SET custom_title = ?1 WHERE id = ?2 AND custom_title IS NOT ?1",
+                        params![title, feed.id],
+                    )? > 0;
+                    tx.execute(
+                        "UPDATE feeds SET from_urls_file = 1, urls_file_title = ?1 WHERE id = ?2",
+                        params![feed.title, feed.id],
+                    )?;
+                    if tags_changed {
+                        tx.execute("DELETE FROM feed_tags WHERE feed_id = ?1", params![feed.id])?;
+                        for tag in &feed.tags {
+                            tx.execute(
+                                "INSERT OR IGNORE INTO feed_tags (feed_id, tag) VALUES (?1, ?2)",
+                                params![feed.id, tag],
+                            )?;
+                        }
+                    }
+                    if tags_changed || title_changed {
+                        changed.push(feed.id);
+                    }
+                }
+
+                let ids: Vec<i64> = listed.iter().map(|f| f.id).collect();
+                let dropped: Vec<i64> = tx
+                    .prepare("SELECT id FROM feeds WHERE from_urls_file = 1 AND archived_at IS NULL")?
+                    .query_map([], |row| row.get(0))?
+                    .collect::<std::result::Result<Vec<i64>, _>>()?
+                    .into_iter()
+                    .filter(|id| !ids.contains(id))
+                    .collect();
+                for &id in &dropped {
+                    remove_feed_rows(&tx, id, FeedRemoval::Archive, Some("no longer in the urls file"))?;
+                }
+                tx.commit()?;
+                Ok((changed, dropped.len()))
+            })
+            .await?;
+        Ok(synced)
+    }
+
+    /// Apply `update` to every feed in `ids` in one transaction. Returns how
+    /// many of the feeds existed.
+    pub async fn bulk_update_feeds(&self, ids: Vec<i64>, update: FeedBulkUpdate) -> Result<usize> {
+        let count = self
+            .conn
+            .call(move |conn| {
+                let tx = conn.transaction()?;
+                let mut count = 0;
+                for id in ids {
+                    let exists = tx
+                        .query_row("SELECT 1 FROM feeds WHERE id = ?1", params![id], |_| Ok(()))
+                        .optional()?
+                        .is_some();
+                    if !exists {
+                        continue;
+                    }
+                    count += 1;
+
+                    if let Some(removal) = update.remove {
+                        remove_feed_rows(&tx, id, removal, None)?;
+                        continue;
+                    }
+                    if let Some(folder) = &update.folder {
+                        tx.execute("UPDATE feeds SET folder = ?1 WHERE id = ?2", params![folder, id])?;
+                    }
+                    if let Some(minutes) = update.refresh_minutes {
+                        tx.execute(
+                            "UPDATE feeds SET refresh_minutes = ?1 WHERE id = ?2",
+                            params![minutes, id],
+                        )?;
+                    }
+                    if let Some(paused) = update.paused {
+                        tx.execute("UPDATE feeds SET paused = ?1 WHERE id = ?2", params![paused, id])?;
+                    }
+                    if let Some(reading) = update.reading {
+                        let reading = (reading != ReadingMode::Full).then_some(reading.as_str());
+                        tx.execute("UPDATE feeds SET reading = ?1 WHERE id = ?2", params![reading, id])?;
+                    }
+                    if let Some(priority) = update.priority {
+                        let priority = (priority != FeedPriority::Normal).then_some(priority.as_str());
+                        tx.execute("UPDATE feeds SET priority = ?1 WHERE id = ?2", params![priority, id])?;
+                    }
+                    if let Some(beat) = &update.beat {
+                        tx.execute("UPDATE feeds SET beat = ?1 WHERE id = ?2", params![beat, id])?;
+                    }
+                    if let Some(contact) = &update.contact {
+                        tx.execute("UPDATE feeds SET contact = ?1 WHERE id = ?2", params![contact, id])?;
+                    }
+                    if let Some(reliability) = update.reliability {
+                        tx.execute("UPDATE feeds SET reliability = ?1 WHERE id = ?2", params![reliability, id])?;
+                    }
+                    if let Some(notes) = &update.notes {
+                        tx.execute("UPDATE feeds SET notes = ?1 WHERE id = ?2", params![notes, id])?;
+                    }
+                    if let Some(reader_url) = &update.reader_url {
+                        tx.execute("UPDATE feeds SET reader_url = ?1 WHERE id = ?2", params![reader_url, id])?;
+                    }
+                    for tag in &update.add_tags {
+                        tx.execute(
+                            "INSERT OR IGNORE INTO feed_tags (feed_id, tag) VALUES (?1, ?2)",
+                            params![id, tag],
+                        )?;
+                    }
+                    for tag in &update.remove_tags {
+                        tx.execute(
+                            "DELETE FROM feed_tags WHERE feed_id = ?1 AND tag = ?2",
+                            params![id, tag],
+                        )?;
+                    }
+                }
+                tx.commit()?;
+                Ok(count)
+            })
+            .await?;
+        Ok(count)
+    }
+
     // Article operations
 
+    /// Insert or update an article, identified by (feed_id, guid) so feeds
+    /// reusing simple GUIDs like "1" don't overwrite each other. Returns the
+    /// article id, or 0 if it was deleted and must not come back.
     pub async fn upsert_article(&self, article: NewArticle) -> Result<i64> {
         let id = self
             .conn
@@ -100,16 +672,48 @@ impl Repository {
                     return Ok(0); // Skip deleted articles
                 }
 
-                conn.execute(
-                    r#"INSERT INTO articles (feed_id, guid, title, url, author, content, content_text, published_at)
-                       VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                // What it said before, to keep if this fetch changed it
+                let previous: Option<(String, Option<String>)> = conn
+                    .query_row(
+                        "SELECT title, decompress(content_text) FROM articles WHERE feed_id = ?1 AND guid = ?2",
+                        params![article.feed_id, article.guid],
+                        |row| Ok((row.get(0)?, row.get(1)?)),
+                    )
+                    .optional()?;
+
+                let extras = article
+                    .extras
+                    .as_ref()
+                    .and_then(|extras| serde_json::to_string(extras).ok());
+                let reading_minutes = article
+                    .content_text
+                    .as_deref()
+                    .or(article.content.as_deref())
+                    .map(estimate_reading_minutes);
+
+                // Keep the larger reading time: a full-content estimate beats the feed teaser.
+                // A fetch without content keeps the text from before, so a
+                // later edit is still caught.
+                // RETURNING gives the row's id on update too (last_insert_rowid
+                // would report whichever article was inserted last).
+                let id = conn.query_row(
+                    r#"INSERT INTO articles (feed_id, guid, title, url, author, content, content_text, published_at, reading_minutes, comments_url,
+                                            enclosure_url, enclosure_type, enclosure_duration, extras)
+                       VALUES (?1, ?2, ?3, ?4, ?5, compress(?6), compress(?7), ?8, ?9, ?10, ?11, ?12, ?13, ?14)
                        ON CONFLICT(feed_id, guid) DO UPDATE SET
                            title = excluded.title,
                            url = excluded.url,
                            author = excluded.author,
-                           content = excluded.content,
-                           content_text = excluded.content_text,
-                           published_at = excluded.published_at"#,
+                           content = COALESCE(excluded.content, articles.content),
+                           content_text = COALESCE(excluded.content_text, articles.content_text),
+                           published_at = excluded.published_at,
+                           comments_url = excluded.comments_url,
+                           enclosure_url = excluded.enclosure_url,
+                           enclosure_type = excluded.enclosure_type,
+                           enclosure_duration = excluded.enclosure_duration,
+                           extras = excluded.extras,
+                           reading_minutes = NULLIF(MAX(COALESCE(articles.reading_minutes, 0), COALESCE(excluded.reading_minutes, 0)), 0)
+                       RETURNING id"#,
                     params![
                         article.feed_id,
                         article.guid,
@@ -119,283 +723,3246 @@ impl Repository {
                         article.content,
                         article.content_text,
                         article.published_at.map(|dt| dt.to_rfc3339()),
+                        reading_minutes,
+                        article.comments_url,
+                        article.enclosure.as_ref().map(|e| e.url.clone()),
+                        article.enclosure.as_ref().and_then(|e| e.mime_type.clone()),
+                        article.enclosure.as_ref().and_then(|e| e.duration_secs),
+                        extras,
                     ],
+                    |row| row.get(0),
                 )?;
-                Ok(conn.last_insert_rowid())
-            })
-            .await?;
-        Ok(id)
-    }
 
-    pub async fn get_all_articles_sorted(&self) -> Result<Vec<Article>> {
-        let articles = self
-            .conn
-            .call(|conn| {
-                let mut stmt = conn.prepare(
-                    r#"SELECT a.id, a.feed_id, a.guid, a.title, a.url, a.author, a.content,
-                              a.content_text, a.published_at, a.fetched_at,
-                              f.title as feed_title
-                       FROM articles a
-                       JOIN feeds f ON a.feed_id = f.id
-                       ORDER BY a.published_at DESC NULLS LAST, a.fetched_at DESC"#,
-                )?;
-                let articles = stmt
-                    .query_map([], |row| Ok(article_from_row(row)))?
-                    .collect::<std::result::Result<Vec<_>, _>>()?;
-                Ok(articles)
+                if let Some((title, text)) = previous {
+                    if is_revised(&title, text.as_deref(), &article) {
+                        conn.execute(
+                            "INSERT INTO article_revisions (article_id, title, content_text) VALUES (?1, ?2, ?3)",
+                            params![id, title, text],
+                        )?;
+                        conn.execute(
+                            r#"DELETE FROM article_revisions WHERE article_id = ?1 AND id NOT IN
+                                   (SELECT id FROM article_revisions WHERE article_id = ?1 ORDER BY id DESC LIMIT ?2)"#,
+                            params![id, REVISIONS_KEPT as i64],
+                        )?;
+                    }
+                }
+
+                // Tags are kept from first ingest (or an AI pass) on re-fetches
+                let tagged = conn
+                    .query_row("SELECT 1 FROM article_tags WHERE article_id = ?1", params![id], |_| Ok(()))
+                    .optional()?
+                    .is_some();
+                if !tagged {
+                    insert_article_tags(conn, id, &article.tags)?;
+                }
+                // Entities follow the current text
+                conn.execute("DELETE FROM article_entities WHERE article_id = ?1", params![id])?;
+                for entity in &article.entities {
+                    conn.execute(
+                        "INSERT OR IGNORE INTO article_entities (article_id, name, kind) VALUES (?1, ?2, ?3)",
+                        params![id, entity.name, entity.kind.as_str()],
+                    )?;
+                }
+                // And links, unless this fetch came without content
+                if article.content.is_some() {
+                    conn.execute("DELETE FROM article_links WHERE article_id = ?1", params![id])?;
+                    for url in &article.links {
+                        let Some((domain, page)) = link_target(url) else {
+                            continue;
+                        };
+                        conn.execute(
+                            "INSERT OR IGNORE INTO article_links (article_id, url, domain, page) VALUES (?1, ?2, ?3, ?4)",
+                            params![id, url, domain, page],
+                        )?;
+                    }
+                }
+                // As do authors, so a corrected byline replaces the old one
+                conn.execute("DELETE FROM authors WHERE article_id = ?1", params![id])?;
+                for name in &article.authors {
+                    conn.execute(
+                        "INSERT OR IGNORE INTO authors (article_id, name) VALUES (?1, ?2)",
+                        params![id, name],
+                    )?;
+                }
+                Ok(id)
             })
             .await?;
-        Ok(articles)
+        Ok(id)
     }
 
-    pub async fn delete_article(&self, id: i64) -> Result<()> {
+    /// Replace an article's suggested tags
+    pub async fn set_article_tags(&self, id: i64, tags: Vec<String>) -> Result<()> {
         self.conn
             .call(move |conn| {
-                // Record the article's feed_id and guid before deleting (to prevent re-adding)
-                conn.execute(
-                    r#"INSERT OR IGNORE INTO deleted_articles (feed_id, guid)
-                       SELECT feed_id, guid FROM articles WHERE id = ?1"#,
-                    params![id],
-                )?;
-                // Delete related data first
-                conn.execute("DELETE FROM summaries WHERE article_id = ?1", params![id])?;
-                conn.execute(
-                    "DELETE FROM saved_to_raindrop WHERE article_id = ?1",
-                    params![id],
-                )?;
-                // Delete the article
-                conn.execute("DELETE FROM articles WHERE id = ?1", params![id])?;
+                let tx = conn.transaction()?;
+                // Tags from auto-tag rules stay
+                tx.execute("DELETE FROM article_tags WHERE article_id = ?1 AND rule_id IS NULL", params![id])?;
+                insert_article_tags(&tx, id, &tags)?;
+                tx.commit()?;
                 Ok(())
             })
             .await?;
         Ok(())
     }
 
-    pub async fn undelete_article(&self, feed_id: i64, guid: &str) -> Result<()> {
-        let guid = guid.to_string();
-        self.conn
+    /// Retag every article tagged with one of `from` as `into`, in one
+    /// transaction. Renames and merges alike; articles that already have
+    /// `into` just lose the old tag. Returns how many articles changed.
+    pub async fn merge_tags(&self, from: Vec<String>, into: String) -> Result<usize> {
+        let changed = self
+            .conn
             .call(move |conn| {
-                conn.execute(
-                    "DELETE FROM deleted_articles WHERE feed_id = ?1 AND guid = ?2",
-                    params![feed_id, guid],
-                )?;
-                Ok(())
+                let tx = conn.transaction()?;
+                let mut changed = 0;
+                for tag in from.iter().filter(|t| **t != into) {
+                    tx.execute(
+                        r#"INSERT OR IGNORE INTO article_tags (article_id, tag)
+                           SELECT article_id, ?2 FROM article_tags WHERE tag = ?1"#,
+                        params![tag, into],
+                    )?;
+                    changed += tx.execute("DELETE FROM article_tags WHERE tag = ?1", params![tag])?;
+                }
+                tx.commit()?;
+                Ok(changed)
             })
             .await?;
-        Ok(())
+        Ok(changed)
     }
 
-    pub async fn delete_old_articles(&self, days: i64) -> Result<usize> {
-        let deleted = self
+    /// Take `tags` off every article, in one transaction. Returns how many
+    /// taggings were removed.
+    pub async fn delete_tags(&self, tags: Vec<String>) -> Result<usize> {
+        let removed = self
             .conn
             .call(move |conn| {
-                // Delete summaries and raindrop entries for old articles first
-                conn.execute(
-                    r#"DELETE FROM summaries WHERE article_id IN (
-                        SELECT id FROM articles
-                        WHERE published_at < datetime('now', '-' || ?1 || ' days')
-                           OR (published_at IS NULL AND fetched_at < datetime('now', '-' || ?1 || ' days'))
-                    )"#,
-                    params![days],
-                )?;
-                conn.execute(
-                    r#"DELETE FROM saved_to_raindrop WHERE article_id IN (
-                        SELECT id FROM articles
-                        WHERE published_at < datetime('now', '-' || ?1 || ' days')
-                           OR (published_at IS NULL AND fetched_at < datetime('now', '-' || ?1 || ' days'))
-                    )"#,
-                    params![days],
-                )?;
-                // Delete old articles (using published_at, fallback to fetched_at if null)
-                let deleted = conn.execute(
-                    r#"DELETE FROM articles
-                       WHERE published_at < datetime('now', '-' || ?1 || ' days')
-                          OR (published_at IS NULL AND fetched_at < datetime('now', '-' || ?1 || ' days'))"#,
-                    params![days],
-                )?;
-                Ok(deleted)
+                let tx = conn.transaction()?;
+                let mut removed = 0;
+                for tag in &tags {
+                    removed += tx.execute("DELETE FROM article_tags WHERE tag = ?1", params![tag])?;
+                }
+                tx.commit()?;
+                Ok(removed)
             })
             .await?;
-        Ok(deleted)
+        Ok(removed)
     }
 
-    pub async fn compact_database(&self, days: i64) -> Result<usize> {
-        let result = self
+    /// The most used article tags with their article counts, busiest first,
+    /// optionally as a server user sees them: on articles they can see, with
+    /// their own tags
+    pub async fn tag_counts(&self, user_id: Option<i64>, limit: usize) -> Result<Vec<(String, usize)>> {
+        let counts = self
             .conn
             .call(move |conn| {
-                // Delete old articles first
-                conn.execute(
-                    r#"DELETE FROM summaries WHERE article_id IN (
-                        SELECT id FROM articles
-                        WHERE published_at < datetime('now', '-' || ?1 || ' days')
-                           OR (published_at IS NULL AND fetched_at < datetime('now', '-' || ?1 || ' days'))
-                    )"#,
-                    params![days],
-                )?;
-                conn.execute(
-                    r#"DELETE FROM saved_to_raindrop WHERE article_id IN (
-                        SELECT id FROM articles
-                        WHERE published_at < datetime('now', '-' || ?1 || ' days')
-                           OR (published_at IS NULL AND fetched_at < datetime('now', '-' || ?1 || ' days'))
-                    )"#,
-                    params![days],
-                )?;
-                let old_deleted = conn.execute(
-                    r#"DELETE FROM articles
-                       WHERE published_at < datetime('now', '-' || ?1 || ' days')
-                          OR (published_at IS NULL AND fetched_at < datetime('now', '-' || ?1 || ' days'))"#,
-                    params![days],
-                )?;
+                let mut stmt = conn.prepare(&format!(
+                    r#"SELECT t.tag, COUNT(DISTINCT t.article_id) FROM (
+                           SELECT tag, article_id FROM article_tags
+                           UNION ALL SELECT tag, article_id FROM user_article_tags WHERE user_id = ?2
+                       ) t
+                       JOIN articles a ON a.id = t.article_id
+                       JOIN feeds f ON f.id = a.feed_id AND f.archived_at IS NULL
+                       WHERE ?2 IS NULL OR ({} AND {})
+                       GROUP BY t.tag ORDER BY COUNT(DISTINCT t.article_id) DESC, t.tag LIMIT ?1"#,
+                    USER_VISIBLE.replace("?{n}", "?2"),
+                    NOT_HIDDEN.replace("?{n}", "?2")
+                ))?;
+                let counts = stmt
+                    .query_map(params![limit as i64, user_id], |row| {
+                        Ok((row.get(0)?, row.get::<_, i64>(1)? as usize))
+                    })?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(counts)
+            })
+            .await?;
+        Ok(counts)
+    }
 
-                // Clean up old deleted_articles tracking entries
-                conn.execute(
-                    "DELETE FROM deleted_articles WHERE deleted_at < datetime('now', '-' || ?1 || ' days')",
-                    params![days],
+    /// Entities mentioned by articles published since `since`, with how many
+    /// articles mention each, most mentioned first. Optionally one kind only
+    /// and limited to a server user's subscriptions.
+    pub async fn recent_entities(
+        &self,
+        user_id: Option<i64>,
+        since: DateTime<Utc>,
+        kind: Option<EntityKind>,
+        limit: usize,
+    ) -> Result<Vec<EntityCount>> {
+        let counts = self
+            .conn
+            .call(move |conn| {
+                let mut stmt = conn.prepare(
+                    r#"SELECT e.name, e.kind, COUNT(DISTINCT e.article_id) FROM article_entities e
+                       JOIN articles a ON a.id = e.article_id
+                       JOIN feeds f ON f.id = a.feed_id AND f.archived_at IS NULL
+                       WHERE COALESCE(a.published_at, a.fetched_at) >= ?2
+                         AND (?3 IS NULL OR e.kind = ?3)
+                         AND (?4 IS NULL OR a.feed_id IN (SELECT feed_id FROM user_feeds WHERE user_id = ?4))
+                       GROUP BY e.name COLLATE NOCASE, e.kind
+                       ORDER BY COUNT(DISTINCT e.article_id) DESC, e.name LIMIT ?1"#,
                 )?;
-
-                // Vacuum to reclaim space
-                conn.execute("VACUUM", [])?;
-
-                Ok(old_deleted)
+                let counts = stmt
+                    .query_map(
+                        params![limit as i64, since.to_rfc3339(), kind.map(EntityKind::as_str), user_id],
+                        |row| {
+                            let kind: String = row.get(1)?;
+                            Ok((row.get(0)?, kind, row.get::<_, i64>(2)? as usize))
+                        },
+                    )?
+                    .collect::<std::result::Result<Vec<(String, String, usize)>, _>>()?;
+                Ok(counts)
             })
             .await?;
-        Ok(result)
+        Ok(counts
+            .into_iter()
+            .filter_map(|(name, kind, articles)| {
+                Some(EntityCount {
+                    name,
+                    kind: EntityKind::parse(&kind)?,
+                    articles,
+                })
+            })
+            .collect())
     }
 
-    // Summary operations
-
-    pub async fn get_summary(&self, article_id: i64) -> Result<Option<Summary>> {
-        let summary = self
+    /// Authors of articles published since `since`, with how many articles
+    /// and feeds carry each byline, most prolific first. Optionally limited
+    /// to a server user's subscriptions.
+    pub async fn recent_authors(
+        &self,
+        user_id: Option<i64>,
+        since: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<AuthorCount>> {
+        let counts = self
             .conn
             .call(move |conn| {
                 let mut stmt = conn.prepare(
-                    "SELECT id, article_id, content, model_version, generated_at FROM summaries WHERE article_id = ?1",
+                    r#"SELECT w.name, COUNT(DISTINCT w.article_id), COUNT(DISTINCT a.feed_id),
+                              (SELECT rule FROM author_rules WHERE name = w.name) FROM authors w
+                       JOIN articles a ON a.id = w.article_id
+                       JOIN feeds f ON f.id = a.feed_id AND f.archived_at IS NULL
+                       WHERE COALESCE(a.published_at, a.fetched_at) >= ?2
+                         AND (?3 IS NULL OR a.feed_id IN (SELECT feed_id FROM user_feeds WHERE user_id = ?3))
+                       GROUP BY w.name
+                       ORDER BY COUNT(DISTINCT w.article_id) DESC, w.name LIMIT ?1"#,
                 )?;
-                let summary = stmt
-                    .query_row(params![article_id], |row| Ok(summary_from_row(row)))
-                    .optional()?;
-                Ok(summary)
+                let counts = stmt
+                    .query_map(params![limit as i64, since.to_rfc3339(), user_id], |row| {
+                        Ok(AuthorCount {
+                            name: row.get(0)?,
+                            articles: row.get::<_, i64>(1)? as usize,
+                            feeds: row.get::<_, i64>(2)? as usize,
+                            rule: row.get::<_, Option<String>>(3)?.as_deref().and_then(AuthorRule::parse),
+                        })
+                    })?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(counts)
             })
             .await?;
-        Ok(summary)
+        Ok(counts)
     }
 
-    pub async fn save_summary(&self, article_id: i64, content: String, model: String) -> Result<()> {
+    /// Follow or mute an author, or clear the rule with `None`
+    pub async fn set_author_rule(&self, name: &str, rule: Option<AuthorRule>) -> Result<()> {
+        let name = name.trim().to_string();
         self.conn
             .call(move |conn| {
-                conn.execute(
-                    r#"INSERT INTO summaries (article_id, content, model_version)
-                       VALUES (?1, ?2, ?3)
-                       ON CONFLICT(article_id) DO UPDATE SET
-                           content = excluded.content,
-                           model_version = excluded.model_version,
-                           generated_at = datetime('now')"#,
-                    params![article_id, content, model],
-                )?;
+                match rule {
+                    Some(rule) => conn.execute(
+                        r#"INSERT INTO author_rules (name, rule) VALUES (?1, ?2)
+                           ON CONFLICT(name) DO UPDATE SET rule = excluded.rule"#,
+                        params![name, rule.as_str()],
+                    )?,
+                    None => conn.execute("DELETE FROM author_rules WHERE name = ?1", params![name])?,
+                };
                 Ok(())
             })
             .await?;
         Ok(())
     }
 
-    // Raindrop tracking
+    /// Every followed or muted author, by name
+    pub async fn get_author_rules(&self) -> Result<Vec<(String, AuthorRule)>> {
+        let rules = self
+            .conn
+            .call(|conn| {
+                let mut stmt = conn.prepare("SELECT name, rule FROM author_rules ORDER BY name")?;
+                let rules = stmt
+                    .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(rules)
+            })
+            .await?;
+        Ok(rules
+            .into_iter()
+            .filter_map(|(name, rule)| Some((name, AuthorRule::parse(&rule)?)))
+            .collect())
+    }
 
-    pub async fn mark_saved_to_raindrop(
-        &self,
-        article_id: i64,
-        raindrop_id: i64,
-        tags: Vec<String>,
-    ) -> Result<()> {
-        let tags_json = serde_json::to_string(&tags)?;
-        self.conn
+    /// Articles first fetched since `since` that a followed author wrote:
+    /// (author, title), newest first
+    pub async fn new_from_followed_authors(&self, since: DateTime<Utc>) -> Result<Vec<(String, String)>> {
+        let articles = self
+            .conn
             .call(move |conn| {
-                conn.execute(
-                    "INSERT OR REPLACE INTO saved_to_raindrop (article_id, raindrop_id, tags) VALUES (?1, ?2, ?3)",
-                    params![article_id, raindrop_id, tags_json],
+                let mut stmt = conn.prepare(&format!(
+                    r#"SELECT w.name, a.title FROM articles a
+                       JOIN authors w ON w.article_id = a.id
+                       JOIN author_rules r ON r.name = w.name AND r.rule = 'follow'
+                       JOIN feeds f ON f.id = a.feed_id AND f.archived_at IS NULL
+                       WHERE datetime(a.fetched_at) >= datetime(?1) AND f.priority IS NOT 'low' AND {}
+                       GROUP BY a.id ORDER BY a.fetched_at DESC, a.id DESC"#,
+                    NOT_EMBARGOED
+                ))?;
+                let articles = stmt
+                    .query_map(params![since.to_rfc3339()], |row| Ok((row.get(0)?, row.get(1)?)))?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(articles)
+            })
+            .await?;
+        Ok(articles)
+    }
+
+    /// Feed and article titles of articles from high-priority feeds stored
+    /// since `since`, newest first
+    pub async fn new_from_priority_feeds(&self, since: DateTime<Utc>) -> Result<Vec<(String, String)>> {
+        let articles = self
+            .conn
+            .call(move |conn| {
+                let mut stmt = conn.prepare(&format!(
+                    r#"SELECT COALESCE(f.custom_title, f.title), a.title FROM articles a
+                       JOIN feeds f ON f.id = a.feed_id AND f.archived_at IS NULL
+                       WHERE datetime(a.fetched_at) >= datetime(?1) AND f.priority = 'high' AND a.is_read = 0
+                         AND {}
+                       ORDER BY a.fetched_at DESC, a.id DESC"#,
+                    NOT_EMBARGOED
+                ))?;
+                let articles = stmt
+                    .query_map(params![since.to_rfc3339()], |row| Ok((row.get(0)?, row.get(1)?)))?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(articles)
+            })
+            .await?;
+        Ok(articles)
+    }
+
+    /// How many articles each feed got since `since`, leaving out embargoed
+    /// ones, as (feed id, count)
+    pub async fn count_new_articles_by_feed(&self, since: DateTime<Utc>) -> Result<Vec<(i64, usize)>> {
+        let counts = self
+            .conn
+            .call(move |conn| {
+                let mut stmt = conn.prepare(&format!(
+                    r#"SELECT a.feed_id, COUNT(*) FROM articles a
+                       WHERE datetime(a.fetched_at) >= datetime(?1) AND {}
+                       GROUP BY a.feed_id ORDER BY a.feed_id"#,
+                    NOT_EMBARGOED
+                ))?;
+                let counts = stmt
+                    .query_map(params![since.to_rfc3339()], |row| {
+                        Ok((row.get(0)?, row.get::<_, i64>(1)? as usize))
+                    })?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(counts)
+            })
+            .await?;
+        Ok(counts)
+    }
+
+    // Auto-tag rules
+
+    /// Save a rule tagging new articles that match `query` with `tag`.
+    /// Returns its id.
+    pub async fn add_tag_rule(&self, query: String, tag: String) -> Result<i64> {
+        let id = self
+            .conn
+            .call(move |conn| {
+                conn.execute("INSERT INTO tag_rules (query, tag) VALUES (?1, ?2)", params![query, tag])?;
+                Ok(conn.last_insert_rowid())
+            })
+            .await?;
+        Ok(id)
+    }
+
+    /// Every auto-tag rule, oldest first
+    pub async fn get_tag_rules(&self) -> Result<Vec<TagRule>> {
+        let rules = self
+            .conn
+            .call(|conn| {
+                let mut stmt =
+                    conn.prepare("SELECT id, query, tag, enabled, hits, last_hit_at FROM tag_rules ORDER BY id")?;
+                let rules = stmt
+                    .query_map([], |row| {
+                        Ok(TagRule {
+                            id: row.get(0)?,
+                            query: row.get(1)?,
+                            tag: row.get(2)?,
+                            enabled: row.get(3)?,
+                            hits: row.get::<_, i64>(4)? as u64,
+                            last_hit_at: row.get::<_, Option<String>>(5)?.and_then(|s| parse_datetime(&s)),
+                        })
+                    })?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(rules)
+            })
+            .await?;
+        Ok(rules)
+    }
+
+    /// Turn a rule on or off. Returns false if there's no such rule.
+    pub async fn set_tag_rule_enabled(&self, id: i64, enabled: bool) -> Result<bool> {
+        let updated = self
+            .conn
+            .call(move |conn| Ok(conn.execute("UPDATE tag_rules SET enabled = ?2 WHERE id = ?1", params![id, enabled])?))
+            .await?;
+        Ok(updated > 0)
+    }
+
+    /// Delete a rule. Tags it already added stay on their articles. Returns
+    /// false if there's no such rule.
+    pub async fn delete_tag_rule(&self, id: i64) -> Result<bool> {
+        let deleted = self
+            .conn
+            .call(move |conn| {
+                let tx = conn.transaction()?;
+                tx.execute("UPDATE article_tags SET rule_id = NULL WHERE rule_id = ?1", params![id])?;
+                let deleted = tx.execute("DELETE FROM tag_rules WHERE id = ?1", params![id])?;
+                tx.commit()?;
+                Ok(deleted)
+            })
+            .await?;
+        Ok(deleted > 0)
+    }
+
+    /// Run the enabled rules over articles first fetched since `since`,
+    /// tagging the matches and counting each rule's hits. Returns how many
+    /// tags were added. A rule whose query no longer parses is skipped.
+    pub async fn apply_tag_rules(&self, since: DateTime<Utc>) -> Result<usize> {
+        let rules: Vec<(TagRule, Query)> = self
+            .get_tag_rules()
+            .await?
+            .into_iter()
+            .filter(|rule| rule.enabled)
+            .filter_map(|rule| match Query::parse(&rule.query) {
+                Ok(query) => Some((rule, query)),
+                Err(e) => {
+                    tracing::warn!("Skipping tag rule {}: bad query at {}", rule.id, e);
+                    None
+                }
+            })
+            .collect();
+        if rules.is_empty() {
+            return Ok(0);
+        }
+        let added = self
+            .conn
+            .call(move |conn| {
+                let tx = conn.transaction()?;
+                let mut added = 0;
+                for (rule, query) in &rules {
+                    let mut values: Vec<Value> = vec![since.to_rfc3339().into(), rule.tag.clone().into(), rule.id.into()];
+                    let condition = query.to_sql(&mut values, None);
+                    let tagged = tx.execute(
+                        &format!(
+                            r#"INSERT OR IGNORE INTO article_tags (article_id, tag, rule_id)
+                               SELECT a.id, ?2, ?3 FROM articles a
+                               JOIN feeds f ON a.feed_id = f.id AND f.archived_at IS NULL
+                               LEFT JOIN discussions d ON d.article_id = a.id AND d.site =
+                                   (SELECT site FROM discussions WHERE article_id = a.id
+                                    ORDER BY points + comments DESC LIMIT 1)
+                               WHERE datetime(a.fetched_at) >= datetime(?1) AND {}"#,
+                            condition
+                        ),
+                        params_from_iter(values),
+                    )?;
+                    if tagged > 0 {
+                        tx.execute(
+                            "UPDATE tag_rules SET hits = hits + ?2, last_hit_at = datetime('now') WHERE id = ?1",
+                            params![rule.id, tagged as i64],
+                        )?;
+                    }
+                    added += tagged;
+                }
+                tx.commit()?;
+                Ok(added)
+            })
+            .await?;
+        Ok(added)
+    }
+
+    // Watchlist operations
+
+    /// Watch `entities` on `list`, creating the list if needed. Returns how
+    /// many weren't already on it; their past coverage is counted right away.
+    pub async fn add_to_watchlist(&self, list: &str, entities: Vec<String>) -> Result<usize> {
+        let list = list.to_string();
+        let added = self
+            .conn
+            .call(move |conn| {
+                let tx = conn.transaction()?;
+                let mut added = 0;
+                for entity in &entities {
+                    added += tx.execute(
+                        "INSERT OR IGNORE INTO watchlist_entities (list, entity) VALUES (?1, ?2)",
+                        params![list, entity],
+                    )?;
+                }
+                record_entity_coverage(&tx, None)?;
+                tx.commit()?;
+                Ok(added)
+            })
+            .await?;
+        Ok(added)
+    }
+
+    /// Stop watching `entities` on `list`, or drop the whole list when
+    /// `entities` is empty. Returns how many entries were removed.
+    pub async fn remove_from_watchlist(&self, list: &str, entities: Vec<String>) -> Result<usize> {
+        let list = list.to_string();
+        let removed = self
+            .conn
+            .call(move |conn| {
+                if entities.is_empty() {
+                    return Ok(conn.execute("DELETE FROM watchlist_entities WHERE list = ?1", params![list])?);
+                }
+                let mut removed = 0;
+                for entity in &entities {
+                    removed += conn.execute(
+                        "DELETE FROM watchlist_entities WHERE list = ?1 AND entity = ?2",
+                        params![list, entity],
+                    )?;
+                }
+                Ok(removed)
+            })
+            .await?;
+        Ok(removed)
+    }
+
+    /// Every (list, entity) pair, by list
+    pub async fn get_watchlists(&self) -> Result<Vec<(String, String)>> {
+        let watched = self
+            .conn
+            .call(|conn| {
+                let mut stmt = conn.prepare("SELECT list, entity FROM watchlist_entities ORDER BY list, entity")?;
+                let watched = stmt
+                    .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(watched)
+            })
+            .await?;
+        Ok(watched)
+    }
+
+    // Collection operations
+
+    /// Every collection with how many articles it holds, by name
+    pub async fn get_collections(&self) -> Result<Vec<Collection>> {
+        let collections = self
+            .conn
+            .call(|conn| {
+                let mut stmt = conn.prepare(
+                    r#"SELECT c.id, c.name, COUNT(ca.article_id), c.created_at
+                       FROM collections c
+                       LEFT JOIN collection_articles ca ON ca.collection_id = c.id
+                       GROUP BY c.id
+                       ORDER BY c.name COLLATE NOCASE"#,
                 )?;
-                Ok(())
+                let collections = stmt
+                    .query_map([], |row| {
+                        Ok(Collection {
+                            id: row.get(0)?,
+                            name: row.get(1)?,
+                            article_count: row.get::<_, i64>(2)? as usize,
+                            created_at: parse_datetime(&row.get::<_, String>(3)?).unwrap_or_else(Utc::now),
+                        })
+                    })?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(collections)
+            })
+            .await?;
+        Ok(collections)
+    }
+
+    /// Append `article_ids` to the collection `name` (any case), creating it
+    /// if needed. Returns how many weren't already in it.
+    pub async fn add_to_collection(&self, name: &str, article_ids: Vec<i64>) -> Result<usize> {
+        let name = name.trim().to_string();
+        let added = self
+            .conn
+            .call(move |conn| {
+                let tx = conn.transaction()?;
+                tx.execute("INSERT OR IGNORE INTO collections (name) VALUES (?1)", params![name])?;
+                let id: i64 = tx.query_row("SELECT id FROM collections WHERE name = ?1", params![name], |row| {
+                    row.get(0)
+                })?;
+                let mut added = 0;
+                for article_id in &article_ids {
+                    added += tx.execute(
+                        r#"INSERT OR IGNORE INTO collection_articles (collection_id, article_id, position)
+                           SELECT ?1, ?2, COALESCE(MAX(position), 0) + 1 FROM collection_articles WHERE collection_id = ?1"#,
+                        params![id, article_id],
+                    )?;
+                }
+                tx.commit()?;
+                Ok(added)
+            })
+            .await?;
+        Ok(added)
+    }
+
+    /// Take `article_ids` out of the collection `name`, or drop the whole
+    /// collection when `article_ids` is empty. Returns how many articles
+    /// were taken out.
+    pub async fn remove_from_collection(&self, name: &str, article_ids: Vec<i64>) -> Result<usize> {
+        let name = name.trim().to_string();
+        let removed = self
+            .conn
+            .call(move |conn| {
+                let tx = conn.transaction()?;
+                let Some(id) = tx
+                    .query_row("SELECT id FROM collections WHERE name = ?1", params![name], |row| row.get::<_, i64>(0))
+                    .optional()?
+                else {
+                    return Ok(0);
+                };
+                let removed = if article_ids.is_empty() {
+                    let removed = tx.execute("DELETE FROM collection_articles WHERE collection_id = ?1", params![id])?;
+                    tx.execute("DELETE FROM collections WHERE id = ?1", params![id])?;
+                    removed
+                } else {
+                    let mut removed = 0;
+                    for article_id in &article_ids {
+                        removed += tx.execute(
+                            "DELETE FROM collection_articles WHERE collection_id = ?1 AND article_id = ?2",
+                            params![id, article_id],
+                        )?;
+                    }
+                    removed
+                };
+                tx.commit()?;
+                Ok(removed)
+            })
+            .await?;
+        Ok(removed)
+    }
+
+    /// Move an article to `position` (from 1; past the end moves it last) in
+    /// the collection `name`. False if it isn't in the collection.
+    pub async fn move_in_collection(&self, name: &str, article_id: i64, position: usize) -> Result<bool> {
+        let name = name.trim().to_string();
+        let moved = self
+            .conn
+            .call(move |conn| {
+                let tx = conn.transaction()?;
+                let mut ids: Vec<(i64, i64)> = {
+                    let mut stmt = tx.prepare(
+                        r#"SELECT ca.collection_id, ca.article_id FROM collection_articles ca
+                           JOIN collections c ON c.id = ca.collection_id
+                           WHERE c.name = ?1 ORDER BY ca.position"#,
+                    )?;
+                    let ids = stmt
+                        .query_map(params![name], |row| Ok((row.get(0)?, row.get(1)?)))?
+                        .collect::<std::result::Result<Vec<_>, _>>()?;
+                    ids
+                };
+                let Some(from) = ids.iter().position(|(_, id)| *id == article_id) else {
+                    return Ok(false);
+                };
+                let entry = ids.remove(from);
+                ids.insert(position.saturating_sub(1).min(ids.len()), entry);
+                for (index, (collection_id, id)) in ids.iter().enumerate() {
+                    tx.execute(
+                        "UPDATE collection_articles SET position = ?1 WHERE collection_id = ?2 AND article_id = ?3",
+                        params![index as i64 + 1, collection_id, id],
+                    )?;
+                }
+                tx.commit()?;
+                Ok(true)
+            })
+            .await?;
+        Ok(moved)
+    }
+
+    /// The articles in the collection `name`, in its order
+    pub async fn get_collection_articles(&self, name: &str) -> Result<Vec<Article>> {
+        let name = name.trim().to_string();
+        let articles = self
+            .conn
+            .call(move |conn| {
+                let sql = format!(
+                    r#"{} JOIN collection_articles ca ON ca.article_id = a.id
+                       JOIN collections c ON c.id = ca.collection_id
+                       WHERE c.name = ?1 ORDER BY ca.position"#,
+                    ARTICLE_SELECT
+                );
+                let mut stmt = conn.prepare(&sql)?;
+                let articles = stmt
+                    .query_map(params![name], |row| Ok(article_from_row(row)))?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(articles)
+            })
+            .await?;
+        Ok(articles)
+    }
+
+    /// Links from an article's content to other sites, in order
+    pub async fn get_article_links(&self, article_id: i64) -> Result<Vec<String>> {
+        let links = self
+            .conn
+            .call(move |conn| {
+                let mut stmt = conn.prepare("SELECT url FROM article_links WHERE article_id = ?1 ORDER BY rowid")?;
+                let links = stmt
+                    .query_map(params![article_id], |row| row.get(0))?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(links)
+            })
+            .await?;
+        Ok(links)
+    }
+
+    /// Sites linked from the most articles published (or fetched) since
+    /// `since`: (domain, articles), most first
+    pub async fn most_linked_domains(&self, since: DateTime<Utc>, limit: usize) -> Result<Vec<(String, usize)>> {
+        let domains = self
+            .conn
+            .call(move |conn| {
+                let mut stmt = conn.prepare(
+                    r#"SELECT l.domain, COUNT(DISTINCT l.article_id) AS n FROM article_links l
+                       JOIN articles a ON a.id = l.article_id
+                       WHERE datetime(COALESCE(a.published_at, a.fetched_at)) >= datetime(?1)
+                       GROUP BY l.domain ORDER BY n DESC, l.domain LIMIT ?2"#,
+                )?;
+                let domains = stmt
+                    .query_map(params![since.to_rfc3339(), limit as i64], |row| {
+                        Ok((row.get(0)?, row.get::<_, i64>(1)? as usize))
+                    })?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(domains)
+            })
+            .await?;
+        Ok(domains)
+    }
+
+    /// Earlier versions of an article, newest first
+    pub async fn get_revisions(&self, article_id: i64) -> Result<Vec<ArticleRevision>> {
+        let revisions = self
+            .conn
+            .call(move |conn| {
+                let mut stmt = conn.prepare(
+                    r#"SELECT id, article_id, title, content_text, replaced_at FROM article_revisions
+                       WHERE article_id = ?1 ORDER BY id DESC"#,
+                )?;
+                let revisions = stmt
+                    .query_map(params![article_id], |row| {
+                        Ok(ArticleRevision {
+                            id: row.get(0)?,
+                            article_id: row.get(1)?,
+                            title: row.get(2)?,
+                            content_text: row.get(3)?,
+                            replaced_at: parse_datetime(&row.get::<_, String>(4)?).unwrap_or_else(Utc::now),
+                        })
+                    })?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(revisions)
+            })
+            .await?;
+        Ok(revisions)
+    }
+
+    /// Articles per local day (published, or if undated fetched) from
+    /// `since` on, for one feed or all of them
+    pub async fn articles_per_day(&self, feed_id: Option<i64>, since: NaiveDate) -> Result<Vec<(NaiveDate, usize)>> {
+        let counts = self
+            .conn
+            .call(move |conn| {
+                let sql = format!(
+                    r#"SELECT date(COALESCE(a.published_at, a.fetched_at), 'localtime') AS day, COUNT(*)
+                       FROM articles a
+                       WHERE day >= ?1 AND (?2 IS NULL OR a.feed_id = ?2) AND {}
+                       GROUP BY day"#,
+                    NOT_EMBARGOED
+                );
+                let mut stmt = conn.prepare(&sql)?;
+                let counts = stmt
+                    .query_map(params![since.to_string(), feed_id], |row| {
+                        Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+                    })?
+                    .filter_map(|row| {
+                        row.map(|(day, count)| day.parse().ok().map(|day| (day, count as usize)))
+                            .transpose()
+                    })
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(counts)
             })
             .await?;
+        Ok(counts)
+    }
+
+    /// Count watched entities' articles per day from `since` on, so the
+    /// counts outlive the articles. A day's count only ever goes up.
+    pub async fn update_entity_coverage(&self, since: NaiveDate) -> Result<()> {
+        self.conn
+            .call(move |conn| Ok(record_entity_coverage(conn, Some(since))?))
+            .await?;
         Ok(())
     }
 
-    pub async fn is_saved_to_raindrop(&self, article_id: i64) -> Result<bool> {
-        let exists = self
+    /// Daily coverage of watched entities from `since` on: (entity, day, articles)
+    pub async fn get_entity_coverage(&self, since: NaiveDate) -> Result<Vec<(String, NaiveDate, usize)>> {
+        let coverage = self
             .conn
             .call(move |conn| {
-                let count: i64 = conn.query_row(
-                    "SELECT COUNT(*) FROM saved_to_raindrop WHERE article_id = ?1",
-                    params![article_id],
-                    |row| row.get(0),
+                let mut stmt = conn.prepare(
+                    r#"SELECT entity, day, articles FROM entity_coverage
+                       WHERE day >= ?1 AND entity IN (SELECT entity FROM watchlist_entities)
+                       ORDER BY day"#,
                 )?;
-                Ok(count > 0)
+                let coverage = stmt
+                    .query_map(params![since.to_string()], |row| {
+                        let day: String = row.get(1)?;
+                        Ok((row.get(0)?, day, row.get::<_, i64>(2)? as usize))
+                    })?
+                    .collect::<std::result::Result<Vec<(String, String, usize)>, _>>()?;
+                Ok(coverage)
             })
             .await?;
-        Ok(exists)
+        Ok(coverage
+            .into_iter()
+            .filter_map(|(entity, day, articles)| Some((entity, day.parse().ok()?, articles)))
+            .collect())
     }
-}
 
-fn parse_datetime(s: &str) -> Option<DateTime<Utc>> {
-    // Try RFC3339 first (e.g., "2026-01-11T12:34:56+00:00")
-    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
-        return Some(dt.with_timezone(&Utc));
+    /// (id, feed id, title, date) of every article published (or fetched,
+    /// when undated) since `since`, optionally only a server user's feeds
+    pub async fn headlines_since(
+        &self,
+        since: DateTime<Utc>,
+        user_id: Option<i64>,
+    ) -> Result<Vec<(i64, i64, String, DateTime<Utc>)>> {
+        let headlines = self
+            .conn
+            .call(move |conn| {
+                let mut stmt = conn.prepare(
+                    r#"SELECT a.id, a.feed_id, a.title, COALESCE(a.published_at, a.fetched_at) FROM articles a
+                       JOIN feeds f ON f.id = a.feed_id AND f.archived_at IS NULL
+                       WHERE datetime(COALESCE(a.published_at, a.fetched_at)) >= datetime(?1)
+                         AND (?2 IS NULL OR a.feed_id IN (SELECT feed_id FROM user_feeds WHERE user_id = ?2))"#,
+                )?;
+                let rows = stmt
+                    .query_map(params![since.to_rfc3339(), user_id], |row| {
+                        let at: String = row.get(3)?;
+                        Ok((row.get(0)?, row.get(1)?, row.get(2)?, at))
+                    })?
+                    .collect::<std::result::Result<Vec<(i64, i64, String, String)>, _>>()?;
+                Ok(rows)
+            })
+            .await?;
+        Ok(headlines
+            .into_iter()
+            .filter_map(|(id, feed_id, title, at)| Some((id, feed_id, title, parse_datetime(&at)?)))
+            .collect())
     }
-    // Try SQLite datetime format (e.g., "2026-01-11 12:34:56")
-    if let Ok(naive) = chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S") {
-        return Some(naive.and_utc());
+
+    /// Load one page of articles in display order (newest first).
+    ///
+    /// Uses keyset pagination: `after` is the id of the last article of the
+    /// previous page, so each page is an index-friendly range scan rather
+    /// than an ever-growing OFFSET.
+    pub async fn get_articles_page(&self, limit: usize, after: Option<i64>) -> Result<Vec<Article>> {
+        self.list_articles(&ArticleFilter::default(), limit, after).await
     }
-    None
-}
 
-fn feed_from_row(row: &Row) -> Feed {
-    Feed {
-        id: row.get(0).unwrap(),
-        title: row.get(1).unwrap(),
-        url: row.get(2).unwrap(),
-        site_url: row.get(3).unwrap(),
-        description: row.get(4).unwrap(),
-        last_fetched: row
-            .get::<_, Option<String>>(5)
-            .unwrap()
-            .and_then(|s| parse_datetime(&s)),
-        created_at: row
-            .get::<_, String>(6)
-            .ok()
-            .and_then(|s| parse_datetime(&s))
-            .unwrap_or_else(Utc::now),
-        updated_at: row
-            .get::<_, String>(7)
-            .ok()
-            .and_then(|s| parse_datetime(&s))
-            .unwrap_or_else(Utc::now),
+    /// Like `get_articles_page`, restricted by `filter`
+    pub async fn list_articles(
+        &self,
+        filter: &ArticleFilter,
+        limit: usize,
+        after: Option<i64>,
+    ) -> Result<Vec<Article>> {
+        let filter = filter.clone();
+        let uses_regex = filter.query.as_ref().is_some_and(Query::uses_regex);
+        let articles = self
+            .conn
+            .call(move |conn| {
+                let (sql, values) = article_list_sql(&filter, limit, after);
+                // A regex scan over every article's text is cut off rather
+                // than left to hold the connection
+                if uses_regex {
+                    let started = std::time::Instant::now();
+                    conn.progress_handler(10_000, Some(move || started.elapsed() > REGEX_TIMEOUT));
+                }
+                let rows = conn.prepare(&sql).and_then(|mut stmt| {
+                    stmt.query_map(params_from_iter(values), |row| Ok(article_from_row(row)))?
+                        .collect::<std::result::Result<Vec<_>, _>>()
+                });
+                if uses_regex {
+                    conn.progress_handler(0, None::<fn() -> bool>);
+                }
+                let mut articles = match rows {
+                    Err(rusqlite::Error::SqliteFailure(e, _)) if e.code == rusqlite::ErrorCode::OperationInterrupted => {
+                        return Ok(None);
+                    }
+                    rows => rows?,
+                };
+                if let Some(user_id) = filter.user_id {
+                    apply_user_state(conn, user_id, &mut articles)?;
+                }
+                Ok(Some(articles))
+            })
+            .await?;
+        articles.ok_or_else(|| {
+            AppError::Config(format!(
+                "Regex search took longer than {}s; narrow it down with other terms",
+                REGEX_TIMEOUT.as_secs()
+            ))
+        })
     }
-}
 
-fn article_from_row(row: &Row) -> Article {
-    Article {
-        id: row.get(0).unwrap(),
-        feed_id: row.get(1).unwrap(),
-        guid: row.get(2).unwrap(),
-        title: row.get(3).unwrap(),
-        url: row.get(4).unwrap(),
-        author: row.get(5).unwrap(),
-        content: row.get(6).unwrap(),
-        content_text: row.get(7).unwrap(),
-        published_at: row
-            .get::<_, Option<String>>(8)
-            .unwrap()
-            .and_then(|s| parse_datetime(&s)),
-        fetched_at: row
-            .get::<_, String>(9)
-            .ok()
-            .and_then(|s| parse_datetime(&s))
-            .unwrap_or_else(Utc::now),
-        feed_title: row.get(10).unwrap(),
+    pub async fn get_article(&self, id: i64) -> Result<Option<Article>> {
+        let article = self
+            .conn
+            .call(move |conn| {
+                let sql = format!("{} WHERE a.id = ?1", ARTICLE_SELECT);
+                let article = conn
+                    .query_row(&sql, params![id], |row| Ok(article_from_row(row)))
+                    .optional()?;
+                Ok(article)
+            })
+            .await?;
+        Ok(article)
     }
-}
 
-fn summary_from_row(row: &Row) -> Summary {
-    Summary {
+    /// An article as seen by a server user: `None` unless they subscribe to
+    /// its feed and haven't deleted it
+    pub async fn get_article_for_user(&self, id: i64, user_id: i64) -> Result<Option<Article>> {
+        let article = self
+            .conn
+            .call(move |conn| {
+                let sql = format!(
+                    "{} WHERE a.id = ?1 AND {} AND {}",
+                    ARTICLE_SELECT,
+                    USER_VISIBLE.replace("?{n}", "?2"),
+                    NOT_HIDDEN.replace("?{n}", "?2")
+                );
+                let mut article = conn
+                    .query_row(&sql, params![id, user_id], |row| Ok(article_from_row(row)))
+                    .optional()?;
+                if let Some(article) = article.as_mut() {
+                    apply_user_state(conn, user_id, std::slice::from_mut(article))?;
+                }
+                Ok(article)
+            })
+            .await?;
+        Ok(article)
+    }
+
+    pub async fn set_user_read(&self, user_id: i64, id: i64, read: bool) -> Result<()> {
+        self.conn
+            .call(move |conn| {
+                conn.execute(
+                    r#"INSERT INTO user_article_state (user_id, article_id, is_read) VALUES (?1, ?2, ?3)
+                       ON CONFLICT(user_id, article_id) DO UPDATE SET is_read = excluded.is_read"#,
+                    params![user_id, id, read],
+                )?;
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Delete an article for a server user only, or bring it back
+    pub async fn set_user_hidden(&self, user_id: i64, id: i64, hidden: bool) -> Result<()> {
+        self.conn
+            .call(move |conn| {
+                conn.execute(
+                    r#"INSERT INTO user_article_state (user_id, article_id, is_hidden) VALUES (?1, ?2, ?3)
+                       ON CONFLICT(user_id, article_id) DO UPDATE SET is_hidden = excluded.is_hidden"#,
+                    params![user_id, id, hidden],
+                )?;
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// A link the server user saved: theirs to see and in their queue, even
+    /// if they had deleted it
+    pub async fn save_for_user(&self, user_id: i64, id: i64) -> Result<()> {
+        self.conn
+            .call(move |conn| {
+                conn.execute(
+                    r#"INSERT INTO user_article_state (user_id, article_id, saved_at, queued_at)
+                       VALUES (?1, ?2, datetime('now'), datetime('now'))
+                       ON CONFLICT(user_id, article_id) DO UPDATE SET
+                           saved_at = COALESCE(saved_at, excluded.saved_at),
+                           queued_at = COALESCE(queued_at, excluded.queued_at),
+                           is_hidden = 0"#,
+                    params![user_id, id],
+                )?;
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Add to the server user's own tags on an article
+    pub async fn add_user_tags(&self, user_id: i64, id: i64, tags: Vec<String>) -> Result<()> {
+        self.conn
+            .call(move |conn| {
+                let tx = conn.transaction()?;
+                for tag in &tags {
+                    tx.execute(
+                        "INSERT OR IGNORE INTO user_article_tags (user_id, article_id, tag) VALUES (?1, ?2, ?3)",
+                        params![user_id, id, tag],
+                    )?;
+                }
+                tx.commit()?;
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_user_starred(&self, user_id: i64, id: i64, starred: bool) -> Result<()> {
+        self.conn
+            .call(move |conn| {
+                conn.execute(
+                    r#"INSERT INTO user_article_state (user_id, article_id, is_starred) VALUES (?1, ?2, ?3)
+                       ON CONFLICT(user_id, article_id) DO UPDATE SET is_starred = excluded.is_starred"#,
+                    params![user_id, id, starred],
+                )?;
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_read(&self, id: i64, read: bool) -> Result<()> {
+        self.conn
+            .call(move |conn| {
+                conn.execute("UPDATE articles SET is_read = ?1 WHERE id = ?2", params![read, id])?;
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// A feed's unread articles, from the counter triggers keep on the feed row
+    pub async fn feed_unread_count(&self, feed_id: i64) -> Result<u32> {
+        let count = self
+            .conn
+            .call(move |conn| {
+                let count = conn
+                    .query_row("SELECT unread_count FROM feeds WHERE id = ?1", params![feed_id], |row| row.get(0))
+                    .optional()?;
+                Ok(count.unwrap_or(0))
+            })
+            .await?;
+        Ok(count)
+    }
+
+    pub async fn count_articles(&self) -> Result<usize> {
+        let count = self
+            .conn
+            .call(|conn| {
+                let count: i64 = conn.query_row(
+                    "SELECT COUNT(*) FROM articles a JOIN feeds f ON a.feed_id = f.id AND f.archived_at IS NULL",
+                    [],
+                    |row| row.get(0),
+                )?;
+                Ok(count as usize)
+            })
+            .await?;
+        Ok(count)
+    }
+
+    /// Starred articles, pinned ones first in their manual order, then newest first
+    pub async fn get_starred_articles(&self) -> Result<Vec<Article>> {
+        let articles = self
+            .conn
+            .call(|conn| {
+                let sql = format!(
+                    "{} WHERE a.is_starred = 1 ORDER BY a.is_pinned DESC, a.pin_order ASC, {}",
+                    ARTICLE_SELECT, ARTICLE_ORDER
+                );
+                let mut stmt = conn.prepare(&sql)?;
+                let articles = stmt
+                    .query_map([], |row| Ok(article_from_row(row)))?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(articles)
+            })
+            .await?;
+        Ok(articles)
+    }
+
+    /// Unread and starred articles for a travel bundle, newest first;
+    /// unread ones only if fetched since `since`
+    pub async fn get_bundle_articles(&self, since: Option<DateTime<Utc>>) -> Result<Vec<Article>> {
+        let articles = self
+            .conn
+            .call(move |conn| {
+                let sql = format!(
+                    r#"{} WHERE a.is_starred = 1
+                          OR (a.is_read = 0 AND a.snooze_until IS NULL
+                              AND (?1 IS NULL OR datetime(a.fetched_at) >= datetime(?1)))
+                       ORDER BY {}"#,
+                    ARTICLE_SELECT, ARTICLE_ORDER
+                );
+                let mut stmt = conn.prepare(&sql)?;
+                let articles = stmt
+                    .query_map(params![since.map(|s| s.to_rfc3339())], |row| Ok(article_from_row(row)))?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(articles)
+            })
+            .await?;
+        Ok(articles)
+    }
+
+    /// Every stored article, for a backup
+    pub async fn get_backup_articles(&self) -> Result<Vec<Article>> {
+        let articles = self
+            .conn
+            .call(move |conn| {
+                let sql = format!("{} ORDER BY {}", ARTICLE_SELECT, ARTICLE_ORDER);
+                let mut stmt = conn.prepare(&sql)?;
+                let articles = stmt
+                    .query_map([], |row| Ok(article_from_row(row)))?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(articles)
+            })
+            .await?;
+        Ok(articles)
+    }
+
+    /// Unread articles in `feed_ids` whose page text hasn't been fetched,
+    /// newest first
+    pub async fn articles_without_full_text(&self, feed_ids: Vec<i64>) -> Result<Vec<Article>> {
+        let articles = self
+            .conn
+            .call(move |conn| {
+                let ids: Vec<String> = feed_ids.iter().map(i64::to_string).collect();
+                let sql = format!(
+                    "{} WHERE a.is_read = 0 AND a.full_text IS NULL AND a.feed_id IN ({}) ORDER BY {}",
+                    ARTICLE_SELECT,
+                    ids.join(","),
+                    ARTICLE_ORDER
+                );
+                let mut stmt = conn.prepare(&sql)?;
+                let articles = stmt
+                    .query_map([], |row| Ok(article_from_row(row)))?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(articles)
+            })
+            .await?;
+        Ok(articles)
+    }
+
+    /// Star or unstar an article. Unstarring also unpins it.
+    pub async fn set_starred(&self, id: i64, starred: bool) -> Result<()> {
+        self.conn
+            .call(move |conn| {
+                if starred {
+                    conn.execute("UPDATE articles SET is_starred = 1 WHERE id = ?1", params![id])?;
+                } else {
+                    conn.execute(
+                        "UPDATE articles SET is_starred = 0, is_pinned = 0, pin_order = NULL WHERE id = ?1",
+                        params![id],
+                    )?;
+                }
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Pin an article to the top of the starred list (starring it if needed),
+    /// appending it after the existing pins, or unpin it.
+    pub async fn set_pinned(&self, id: i64, pinned: bool) -> Result<()> {
+        self.conn
+            .call(move |conn| {
+                if pinned {
+                    conn.execute(
+                        r#"UPDATE articles SET is_starred = 1, is_pinned = 1,
+                               pin_order = (SELECT COALESCE(MAX(pin_order), 0) + 1 FROM articles WHERE is_pinned = 1)
+                           WHERE id = ?1 AND is_pinned = 0"#,
+                        params![id],
+                    )?;
+                } else {
+                    conn.execute(
+                        "UPDATE articles SET is_pinned = 0, pin_order = NULL WHERE id = ?1",
+                        params![id],
+                    )?;
+                }
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Swap a pinned article with its neighbour in the manual order.
+    /// Returns false if it is not pinned or is already at that end of the list.
+    pub async fn move_pinned(&self, id: i64, up: bool) -> Result<bool> {
+        let moved = self
+            .conn
+            .call(move |conn| {
+                let tx = conn.transaction()?;
+                let order: Option<i64> = tx
+                    .query_row(
+                        "SELECT pin_order FROM articles WHERE id = ?1 AND is_pinned = 1",
+                        params![id],
+                        |row| row.get(0),
+                    )
+                    .optional()?
+                    .flatten();
+                let Some(order) = order else {
+                    return Ok(false);
+                };
+
+                let neighbour_sql = if up {
+                    "SELECT id, pin_order FROM articles WHERE is_pinned = 1 AND pin_order < ?1 ORDER BY pin_order DESC LIMIT 1"
+                } else {
+                    "SELECT id, pin_order FROM articles WHERE is_pinned = 1 AND pin_order > ?1 ORDER BY pin_order ASC LIMIT 1"
+                };
+                let neighbour: Option<(i64, i64)> = tx
+                    .query_row(neighbour_sql, params![order], |row| Ok((row.get(0)?, row.get(1)?)))
+                    .optional()?;
+                let Some((neighbour_id, neighbour_order)) = neighbour else {
+                    return Ok(false);
+                };
+
+                tx.execute("UPDATE articles SET pin_order = ?1 WHERE id = ?2", params![neighbour_order, id])?;
+                tx.execute("UPDATE articles SET pin_order = ?1 WHERE id = ?2", params![order, neighbour_id])?;
+                tx.commit()?;
+                Ok(true)
+            })
+            .await?;
+        Ok(moved)
+    }
+
+    /// Articles with an HN/Lobsters thread, most discussed first
+    pub async fn get_discussed_articles(&self, limit: usize) -> Result<Vec<Article>> {
+        let articles = self
+            .conn
+            .call(move |conn| {
+                let sql = format!(
+                    "{} WHERE d.site IS NOT NULL AND a.snooze_until IS NULL AND {} ORDER BY d.points + d.comments DESC, {} LIMIT ?1",
+                    ARTICLE_SELECT, NOT_EMBARGOED, ARTICLE_ORDER
+                );
+                let mut stmt = conn.prepare(&sql)?;
+                let articles = stmt
+                    .query_map(params![limit as i64], |row| Ok(article_from_row(row)))?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(articles)
+            })
+            .await?;
+        Ok(articles)
+    }
+
+    /// Recent articles (id, url) due a discussion lookup: never checked, or
+    /// last checked over `recheck_hours` ago while still within `max_age_hours`
+    pub async fn articles_needing_discussion_lookup(
+        &self,
+        max_age_hours: u32,
+        recheck_hours: u32,
+        limit: usize,
+    ) -> Result<Vec<(i64, String)>> {
+        let articles = self
+            .conn
+            .call(move |conn| {
+                let mut stmt = conn.prepare(
+                    r#"SELECT id, url FROM articles
+                       WHERE url != ''
+                         AND datetime(COALESCE(published_at, fetched_at)) >= datetime('now', '-' || ?1 || ' hours')
+                         AND (discussions_checked_at IS NULL
+                              OR discussions_checked_at < datetime('now', '-' || ?2 || ' hours'))
+                       ORDER BY discussions_checked_at IS NOT NULL, COALESCE(published_at, fetched_at) DESC
+                       LIMIT ?3"#,
+                )?;
+                let rows = stmt
+                    .query_map(params![max_age_hours, recheck_hours, limit as i64], |row| {
+                        Ok((row.get(0)?, row.get(1)?))
+                    })?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(rows)
+            })
+            .await?;
+        Ok(articles)
+    }
+
+    /// Store the threads found for an article and mark it checked
+    pub async fn save_discussions(&self, article_id: i64, discussions: Vec<Discussion>) -> Result<()> {
+        self.conn
+            .call(move |conn| {
+                let tx = conn.transaction()?;
+                for discussion in &discussions {
+                    tx.execute(
+                        r#"INSERT INTO discussions (article_id, site, url, points, comments)
+                           VALUES (?1, ?2, ?3, ?4, ?5)
+                           ON CONFLICT(article_id, site) DO UPDATE SET
+                               url = excluded.url,
+                               points = excluded.points,
+                               comments = excluded.comments"#,
+                        params![
+                            article_id,
+                            discussion.site,
+                            discussion.url,
+                            discussion.points,
+                            discussion.comments
+                        ],
+                    )?;
+                }
+                tx.execute(
+                    "UPDATE articles SET discussions_checked_at = datetime('now') WHERE id = ?1",
+                    params![article_id],
+                )?;
+                tx.commit()?;
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Saved articles (id, url) due a check that their page is still up:
+    /// starred ones and those from priority feeds or followed authors, not
+    /// already found gone, never checked or last checked over
+    /// `recheck_hours` ago
+    pub async fn articles_needing_removal_check(&self, recheck_hours: u32, limit: usize) -> Result<Vec<(i64, String)>> {
+        let articles = self
+            .conn
+            .call(move |conn| {
+                let mut stmt = conn.prepare(
+                    r#"SELECT a.id, a.url FROM articles a
+                       JOIN feeds f ON f.id = a.feed_id AND f.archived_at IS NULL
+                       WHERE a.url != '' AND a.removed_at IS NULL
+                         AND (a.is_starred = 1 OR f.priority = 'high'
+                              OR EXISTS (SELECT 1 FROM authors w JOIN author_rules r ON r.name = w.name
+                                         WHERE w.article_id = a.id AND r.rule = 'follow'))
+                         AND (a.removal_checked_at IS NULL
+                              OR a.removal_checked_at <= datetime('now', '-' || ?1 || ' hours'))
+                       ORDER BY a.removal_checked_at IS NOT NULL, a.removal_checked_at, a.is_starred DESC
+                       LIMIT ?2"#,
+                )?;
+                let rows = stmt
+                    .query_map(params![recheck_hours, limit as i64], |row| Ok((row.get(0)?, row.get(1)?)))?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(rows)
+            })
+            .await?;
+        Ok(articles)
+    }
+
+    /// Mark an article checked, and taken down if `removed`
+    pub async fn save_removal_check(&self, article_id: i64, removed: bool) -> Result<()> {
+        self.conn
+            .call(move |conn| {
+                conn.execute(
+                    r#"UPDATE articles SET removal_checked_at = datetime('now'),
+                           removed_at = CASE WHEN ?2 THEN COALESCE(removed_at, datetime('now')) END
+                       WHERE id = ?1"#,
+                    params![article_id, removed],
+                )?;
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Remember where playback of an episode stopped. Finishing it marks it
+    /// played and resets the position so a replay starts from the top.
+    pub async fn save_playback(&self, article_id: i64, position_secs: u32, finished: bool) -> Result<()> {
+        self.conn
+            .call(move |conn| {
+                conn.execute(
+                    r#"INSERT INTO playback (article_id, position_secs, is_played, updated_at)
+                       VALUES (?1, ?2, ?3, datetime('now'))
+                       ON CONFLICT(article_id) DO UPDATE SET
+                           position_secs = excluded.position_secs,
+                           is_played = MAX(playback.is_played, excluded.is_played),
+                           updated_at = excluded.updated_at"#,
+                    params![article_id, if finished { 0 } else { position_secs }, finished],
+                )?;
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Read-later queue, oldest addition first
+    pub async fn get_queued_articles(&self) -> Result<Vec<Article>> {
+        let articles = self
+            .conn
+            .call(|conn| {
+                let sql = format!(
+                    "{} WHERE a.queued_at IS NOT NULL ORDER BY a.queued_at ASC, a.id ASC",
+                    ARTICLE_SELECT
+                );
+                let mut stmt = conn.prepare(&sql)?;
+                let articles = stmt
+                    .query_map([], |row| Ok(article_from_row(row)))?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(articles)
+            })
+            .await?;
+        Ok(articles)
+    }
+
+    /// Add an article to the end of the read-later queue, or remove it
+    pub async fn set_queued(&self, id: i64, queued: bool) -> Result<()> {
+        self.conn
+            .call(move |conn| {
+                if queued {
+                    conn.execute(
+                        "UPDATE articles SET queued_at = datetime('now') WHERE id = ?1 AND queued_at IS NULL",
+                        params![id],
+                    )?;
+                } else {
+                    conn.execute("UPDATE articles SET queued_at = NULL WHERE id = ?1", params![id])?;
+                }
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Put an article at the top of the history, dropping the oldest past
+    /// `HISTORY_LIMIT`
+    pub async fn record_opened(&self, id: i64) -> Result<()> {
+        self.conn
+            .call(move |conn| {
+                conn.execute(
+                    r#"INSERT INTO history (article_id, seq)
+                       VALUES (?1, (SELECT COALESCE(MAX(seq), 0) + 1 FROM history))
+                       ON CONFLICT(article_id) DO UPDATE SET seq = excluded.seq, opened_at = datetime('now')"#,
+                    params![id],
+                )?;
+                conn.execute(
+                    r#"DELETE FROM history WHERE article_id NOT IN
+                           (SELECT article_id FROM history ORDER BY seq DESC LIMIT ?1)"#,
+                    params![HISTORY_LIMIT as i64],
+                )?;
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// The history: opened articles, the latest first
+    pub async fn get_history_articles(&self) -> Result<Vec<Article>> {
+        let articles = self
+            .conn
+            .call(|conn| {
+                let sql = format!(
+                    "{} JOIN history h ON h.article_id = a.id ORDER BY h.seq DESC",
+                    ARTICLE_SELECT
+                );
+                let mut stmt = conn.prepare(&sql)?;
+                let articles = stmt
+                    .query_map([], |row| Ok(article_from_row(row)))?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(articles)
+            })
+            .await?;
+        Ok(articles)
+    }
+
+    /// Add to the time spent reading an article on `day`
+    pub async fn add_reading_time(&self, id: i64, day: NaiveDate, seconds: u64) -> Result<()> {
+        self.conn
+            .call(move |conn| {
+                conn.execute(
+                    r#"INSERT INTO reading_time (article_id, day, seconds) VALUES (?1, ?2, ?3)
+                       ON CONFLICT(article_id, day) DO UPDATE SET seconds = seconds + excluded.seconds"#,
+                    params![id, day.to_string(), seconds as i64],
+                )?;
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Seconds spent reading on `day`, across articles
+    pub async fn reading_seconds_on(&self, day: NaiveDate) -> Result<u64> {
+        let seconds = self
+            .conn
+            .call(move |conn| {
+                let seconds: i64 = conn.query_row(
+                    "SELECT COALESCE(SUM(seconds), 0) FROM reading_time WHERE day = ?1",
+                    params![day.to_string()],
+                    |row| row.get(0),
+                )?;
+                Ok(seconds)
+            })
+            .await?;
+        Ok(seconds as u64)
+    }
+
+    /// Seconds spent reading an article, every day together
+    pub async fn article_reading_seconds(&self, id: i64) -> Result<u64> {
+        let seconds = self
+            .conn
+            .call(move |conn| {
+                let seconds: i64 = conn.query_row(
+                    "SELECT COALESCE(SUM(seconds), 0) FROM reading_time WHERE article_id = ?1",
+                    params![id],
+                    |row| row.get(0),
+                )?;
+                Ok(seconds)
+            })
+            .await?;
+        Ok(seconds as u64)
+    }
+
+    /// Hold an article back until `until`, or lift its embargo with `None`
+    pub async fn set_embargo(&self, id: i64, until: Option<DateTime<Utc>>) -> Result<()> {
+        self.conn
+            .call(move |conn| {
+                conn.execute(
+                    "UPDATE articles SET embargo_until = ?1 WHERE id = ?2",
+                    params![until.map(|u| u.to_rfc3339()), id],
+                )?;
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Embargo articles first fetched since `since` that match a rule:
+    /// until the feed's publish date when that's still ahead, or `hours`
+    /// after fetching if that's later. Articles already under an embargo
+    /// keep theirs. Returns how many were embargoed.
+    pub async fn apply_embargo_rules(&self, since: DateTime<Utc>, rules: Vec<(Query, u32)>) -> Result<usize> {
+        if rules.is_empty() {
+            return Ok(0);
+        }
+        let embargoed = self
+            .conn
+            .call(move |conn| {
+                let tx = conn.transaction()?;
+                let mut embargoed = 0;
+                for (query, hours) in &rules {
+                    let mut values: Vec<Value> = vec![since.to_rfc3339().into(), format!("+{} hours", hours).into()];
+                    let condition = query.to_sql(&mut values, None);
+                    embargoed += tx.execute(
+                        &format!(
+                            r#"UPDATE articles SET embargo_until = until FROM (
+                                   SELECT a.id AS article_id,
+                                          MAX(datetime(a.fetched_at, ?2), COALESCE(datetime(a.published_at), '')) AS until
+                                   FROM articles a
+                                   JOIN feeds f ON a.feed_id = f.id AND f.archived_at IS NULL
+                                   LEFT JOIN discussions d ON d.article_id = a.id AND d.site =
+                                       (SELECT site FROM discussions WHERE article_id = a.id
+                                        ORDER BY points + comments DESC LIMIT 1)
+                                   WHERE datetime(a.fetched_at) >= datetime(?1) AND a.embargo_until IS NULL AND {}
+                               )
+                               WHERE articles.id = article_id AND until > datetime('now')"#,
+                            condition
+                        ),
+                        params_from_iter(values),
+                    )?;
+                }
+                tx.commit()?;
+                Ok(embargoed)
+            })
+            .await?;
+        Ok(embargoed)
+    }
+
+    /// Articles whose embargo ended after `after` and by `until`
+    pub async fn count_embargoes_lifted(&self, after: DateTime<Utc>, until: DateTime<Utc>) -> Result<usize> {
+        let count = self
+            .conn
+            .call(move |conn| {
+                let count: i64 = conn.query_row(
+                    r#"SELECT COUNT(*) FROM articles
+                       WHERE datetime(embargo_until) > datetime(?1) AND datetime(embargo_until) <= datetime(?2)"#,
+                    params![after.to_rfc3339(), until.to_rfc3339()],
+                    |row| row.get(0),
+                )?;
+                Ok(count)
+            })
+            .await?;
+        Ok(count as usize)
+    }
+
+    /// Hide an article until `until`, when `wake_snoozed` brings it back
+    pub async fn snooze_article(&self, id: i64, until: DateTime<Utc>) -> Result<()> {
+        self.conn
+            .call(move |conn| {
+                conn.execute(
+                    "UPDATE articles SET snooze_until = ?1, surfaced_at = NULL WHERE id = ?2",
+                    params![until.to_rfc3339(), id],
+                )?;
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Bring back the articles whose snooze ends by `now`, unread and sorted
+    /// as if just published. Returns how many came back.
+    pub async fn wake_snoozed(&self, now: DateTime<Utc>) -> Result<usize> {
+        let woken = self
+            .conn
+            .call(move |conn| {
+                let woken = conn.execute(
+                    r#"UPDATE articles SET snooze_until = NULL, surfaced_at = datetime(?1), is_read = 0
+                       WHERE snooze_until IS NOT NULL AND datetime(snooze_until) <= datetime(?1)"#,
+                    params![now.to_rfc3339()],
+                )?;
+                Ok(woken)
+            })
+            .await?;
+        Ok(woken)
+    }
+
+    /// Number of queued articles and their total estimated reading time in minutes
+    pub async fn queue_stats(&self) -> Result<(usize, u32)> {
+        let stats = self
+            .conn
+            .call(|conn| {
+                let stats = conn.query_row(
+                    "SELECT COUNT(*), COALESCE(SUM(reading_minutes), 0) FROM articles WHERE queued_at IS NOT NULL",
+                    [],
+                    |row| Ok((row.get::<_, i64>(0)? as usize, row.get::<_, i64>(1)? as u32)),
+                )?;
+                Ok(stats)
+            })
+            .await?;
+        Ok(stats)
+    }
+
+    /// Record a better reading-time estimate (e.g. from fetched full content)
+    /// Store the scraped full article text (kept across feed refreshes)
+    pub async fn save_full_text(&self, id: i64, text: String) -> Result<()> {
+        self.conn
+            .call(move |conn| {
+                conn.execute(
+                    "UPDATE articles SET full_text = compress(?1) WHERE id = ?2",
+                    params![text, id],
+                )?;
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Record where the article's archived snapshot was written
+    pub async fn set_archive_path(&self, id: i64, path: String) -> Result<()> {
+        self.conn
+            .call(move |conn| {
+                conn.execute(
+                    "UPDATE articles SET archive_path = ?1 WHERE id = ?2",
+                    params![path, id],
+                )?;
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update_reading_minutes(&self, id: i64, minutes: u32) -> Result<()> {
+        self.conn
+            .call(move |conn| {
+                conn.execute(
+                    "UPDATE articles SET reading_minutes = ?1 WHERE id = ?2",
+                    params![minutes, id],
+                )?;
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Apply `batch` to every article in `ids` in one transaction. Returns
+    /// how many of the articles existed.
+    pub async fn update_articles(&self, ids: Vec<i64>, batch: ArticleBatch) -> Result<usize> {
+        let count = self
+            .conn
+            .call(move |conn| {
+                let tx = conn.transaction()?;
+                let mut count = 0;
+                for id in ids {
+                    count += match &batch {
+                        ArticleBatch::Read(read) => {
+                            tx.execute("UPDATE articles SET is_read = ?1 WHERE id = ?2", params![read, id])?
+                        }
+                        ArticleBatch::Starred(true) => {
+                            tx.execute("UPDATE articles SET is_starred = 1 WHERE id = ?1", params![id])?
+                        }
+                        ArticleBatch::Starred(false) => tx.execute(
+                            "UPDATE articles SET is_starred = 0, is_pinned = 0, pin_order = NULL WHERE id = ?1",
+                            params![id],
+                        )?,
+                        ArticleBatch::Tag(tag) => {
+                            tx.execute(
+                                "INSERT OR IGNORE INTO article_tags (article_id, tag) SELECT id, ?2 FROM articles WHERE id = ?1",
+                                params![id, tag],
+                            )?;
+                            tx.query_row("SELECT COUNT(*) FROM articles WHERE id = ?1", params![id], |row| {
+                                row.get::<_, usize>(0)
+                            })?
+                        }
+                        ArticleBatch::Delete => {
+                            tx.execute(
+                                r#"INSERT OR IGNORE INTO deleted_articles (feed_id, guid)
+                                   SELECT feed_id, guid FROM articles WHERE id = ?1"#,
+                                params![id],
+                            )?;
+                            delete_articles_in(&tx, "?1", params![id])?
+                        }
+                    };
+                }
+                // Single changes aren't worth a trail; bulk and destructive ones are
+                let action = match batch {
+                    ArticleBatch::Read(true) if count > 1 => Some("articles.read"),
+                    ArticleBatch::Read(false) if count > 1 => Some("articles.unread"),
+                    ArticleBatch::Delete if count > 0 => Some("articles.delete"),
+                    _ => None,
+                };
+                if let Some(action) = action {
+                    audit(&tx, action, None, Some(&format!("{} articles", count)))?;
+                }
+                tx.commit()?;
+                Ok(count)
+            })
+            .await?;
+        Ok(count)
+    }
+
+    pub async fn delete_article(&self, id: i64) -> Result<()> {
+        self.conn
+            .call(move |conn| {
+                // Record the article's feed_id and guid before deleting (to prevent re-adding)
+                conn.execute(
+                    r#"INSERT OR IGNORE INTO deleted_articles (feed_id, guid)
+                       SELECT feed_id, guid FROM articles WHERE id = ?1"#,
+                    params![id],
+                )?;
+                delete_articles_in(conn, "?1", params![id])?;
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    pub async fn undelete_article(&self, feed_id: i64, guid: &str) -> Result<()> {
+        let guid = guid.to_string();
+        self.conn
+            .call(move |conn| {
+                conn.execute(
+                    "DELETE FROM deleted_articles WHERE feed_id = ?1 AND guid = ?2",
+                    params![feed_id, guid],
+                )?;
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete_old_articles(&self, days: i64) -> Result<usize> {
+        let deleted = self
+            .conn
+            .call(move |conn| Ok(delete_expired_articles(conn, days)?))
+            .await?;
+        Ok(deleted)
+    }
+
+    pub async fn compact_database(&self, days: i64) -> Result<usize> {
+        let result = self
+            .conn
+            .call(move |conn| {
+                // Delete old articles first
+                let old_deleted = delete_expired_articles(conn, days)?;
+
+                // Clean up old deleted_articles tracking entries
+                conn.execute(
+                    "DELETE FROM deleted_articles WHERE deleted_at < datetime('now', '-' || ?1 || ' days')",
+                    params![days],
+                )?;
+
+                // Vacuum to reclaim space
+                conn.execute("VACUUM", [])?;
+
+                Ok(old_deleted)
+            })
+            .await?;
+        Ok(result)
+    }
+
+    /// Routine upkeep: give free pages back (a full vacuum the first time,
+    /// to switch to incremental vacuuming), refresh the planner's statistics,
+    /// record each table's size for `db_stats` and fold the WAL back into
+    /// the database. Returns the size before and after, in bytes.
+    pub async fn maintain(&self) -> Result<(i64, i64)> {
+        let sizes = self
+            .conn
+            .call(|conn| {
+                let before = database_bytes(conn)?.0;
+                let auto_vacuum: i64 = conn.query_row("PRAGMA auto_vacuum", [], |row| row.get(0))?;
+                if auto_vacuum == 2 {
+                    conn.execute_batch("PRAGMA incremental_vacuum;")?;
+                } else {
+                    conn.execute_batch("PRAGMA auto_vacuum = INCREMENTAL; VACUUM;")?;
+                }
+                conn.execute_batch("ANALYZE;")?;
+
+                for (name, bytes) in table_bytes(conn)? {
+                    conn.execute(
+                        r#"INSERT INTO db_sizes (day, name, bytes) VALUES (date('now'), ?1, ?2)
+                           ON CONFLICT(day, name) DO UPDATE SET bytes = excluded.bytes"#,
+                        params![name, bytes],
+                    )?;
+                }
+                conn.execute(
+                    "DELETE FROM db_sizes WHERE day < date('now', '-' || ?1 || ' days')",
+                    params![SIZE_HISTORY_DAYS],
+                )?;
+                conn.execute(
+                    "DELETE FROM audit_log WHERE at < datetime('now', '-' || ?1 || ' days')",
+                    params![AUDIT_HISTORY_DAYS],
+                )?;
+
+                conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+                Ok((before, database_bytes(conn)?.0))
+            })
+            .await?;
+        Ok(sizes)
+    }
+
+    /// Sizes and row counts of every table, against the sizes recorded by
+    /// an earlier `maintain`
+    pub async fn db_stats(&self) -> Result<DbStats> {
+        let stats = self
+            .conn
+            .call(|conn| {
+                let (file_bytes, free_bytes) = database_bytes(conn)?;
+                let since: Option<String> = conn.query_row(
+                    r#"SELECT COALESCE((SELECT MAX(day) FROM db_sizes WHERE day <= date('now', '-7 days')),
+                                       (SELECT MIN(day) FROM db_sizes WHERE day < date('now')))"#,
+                    [],
+                    |row| row.get(0),
+                )?;
+                let mut tables = Vec::new();
+                for (name, bytes) in table_bytes(conn)? {
+                    let rows = conn.query_row(
+                        &format!("SELECT COUNT(*) FROM \"{}\"", name.replace('"', "\"\"")),
+                        [],
+                        |row| row.get(0),
+                    )?;
+                    let previous_bytes = conn
+                        .query_row(
+                            "SELECT bytes FROM db_sizes WHERE day = ?1 AND name = ?2",
+                            params![since, name],
+                            |row| row.get(0),
+                        )
+                        .optional()?;
+                    tables.push(TableSize {
+                        name,
+                        rows,
+                        bytes,
+                        previous_bytes,
+                    });
+                }
+                Ok(DbStats {
+                    file_bytes,
+                    free_bytes,
+                    tables,
+                    since: since.and_then(|day| NaiveDate::parse_from_str(&day, "%Y-%m-%d").ok()),
+                })
+            })
+            .await?;
+        Ok(stats)
+    }
+
+    // User operations (server mode)
+
+    /// Create an account, subscribed to every feed that already exists
+    pub async fn create_user(&self, username: &str, password_hash: &str) -> Result<i64> {
+        let username = username.to_string();
+        let password_hash = password_hash.to_string();
+        let id = self
+            .conn
+            .call(move |conn| {
+                let tx = conn.transaction()?;
+                tx.execute(
+                    "INSERT INTO users (username, password_hash) VALUES (?1, ?2)",
+                    params![username, password_hash],
+                )?;
+                let id = tx.last_insert_rowid();
+                tx.execute(
+                    "INSERT INTO user_feeds (user_id, feed_id) SELECT ?1, id FROM feeds",
+                    params![id],
+                )?;
+                tx.commit()?;
+                Ok(id)
+            })
+            .await?;
+        Ok(id)
+    }
+
+    pub async fn get_user_by_name(&self, username: &str) -> Result<Option<User>> {
+        let username = username.to_string();
+        let user = self
+            .conn
+            .call(move |conn| {
+                let user = conn
+                    .query_row(
+                        "SELECT id, username, password_hash FROM users WHERE username = ?1",
+                        params![username],
+                        |row| Ok(user_from_row(row)),
+                    )
+                    .optional()?;
+                Ok(user)
+            })
+            .await?;
+        Ok(user)
+    }
+
+    pub async fn get_user_by_token_hash(&self, token_hash: &str) -> Result<Option<User>> {
+        let token_hash = token_hash.to_string();
+        let user = self
+            .conn
+            .call(move |conn| {
+                let user = conn
+                    .query_row(
+                        "SELECT id, username, password_hash FROM users WHERE token_hash = ?1",
+                        params![token_hash],
+                        |row| Ok(user_from_row(row)),
+                    )
+                    .optional()?;
+                Ok(user)
+            })
+            .await?;
+        Ok(user)
+    }
+
+    /// Store an OAuth token (`kind` is "access" or "refresh") for a user,
+    /// dropping any that have expired
+    pub async fn add_oauth_token(
+        &self,
+        user_id: i64,
+        token_hash: &str,
+        kind: &'static str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<()> {
+        let token_hash = token_hash.to_string();
+        self.conn
+            .call(move |conn| {
+                conn.execute("DELETE FROM oauth_tokens WHERE expires_at < datetime('now')", [])?;
+                conn.execute(
+                    "INSERT INTO oauth_tokens (token_hash, user_id, kind, expires_at) VALUES (?1, ?2, ?3, datetime(?4))",
+                    params![token_hash, user_id, kind, expires_at.to_rfc3339()],
+                )?;
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// The owner of an unexpired OAuth token of this kind
+    pub async fn get_user_by_oauth_token(&self, token_hash: &str, kind: &'static str) -> Result<Option<User>> {
+        let token_hash = token_hash.to_string();
+        let user = self
+            .conn
+            .call(move |conn| {
+                let user = conn
+                    .query_row(
+                        "SELECT u.id, u.username, u.password_hash FROM oauth_tokens t JOIN users u ON u.id = t.user_id
+                         WHERE t.token_hash = ?1 AND t.kind = ?2 AND t.expires_at >= datetime('now')",
+                        params![token_hash, kind],
+                        |row| Ok(user_from_row(row)),
+                    )
+                    .optional()?;
+                Ok(user)
+            })
+            .await?;
+        Ok(user)
+    }
+
+    pub async fn delete_oauth_token(&self, token_hash: &str) -> Result<()> {
+        let token_hash = token_hash.to_string();
+        self.conn
+            .call(move |conn| {
+                conn.execute("DELETE FROM oauth_tokens WHERE token_hash = ?1", params![token_hash])?;
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Store a named API token for a user (only its hash). Returns its id.
+    pub async fn add_api_token(&self, user_id: i64, name: &str, token_hash: &str, scope: TokenScope) -> Result<i64> {
+        let (name, token_hash) = (name.to_string(), token_hash.to_string());
+        let id = self
+            .conn
+            .call(move |conn| {
+                conn.execute(
+                    "INSERT INTO api_tokens (user_id, name, token_hash, scope) VALUES (?1, ?2, ?3, ?4)",
+                    params![user_id, name, token_hash, scope.as_str()],
+                )?;
+                Ok(conn.last_insert_rowid())
+            })
+            .await?;
+        Ok(id)
+    }
+
+    /// The owner of an API token and what it may do, noting that it was used
+    pub async fn get_user_by_api_token(&self, token_hash: &str) -> Result<Option<(User, TokenScope)>> {
+        let token_hash = token_hash.to_string();
+        let found = self
+            .conn
+            .call(move |conn| {
+                let found = conn
+                    .query_row(
+                        "SELECT u.id, u.username, u.password_hash, t.scope FROM api_tokens t JOIN users u ON u.id = t.user_id
+                         WHERE t.token_hash = ?1",
+                        params![token_hash],
+                        |row| Ok((user_from_row(row), row.get::<_, String>(3)?)),
+                    )
+                    .optional()?;
+                if found.is_some() {
+                    conn.execute(
+                        "UPDATE api_tokens SET last_used_at = datetime('now') WHERE token_hash = ?1",
+                        params![token_hash],
+                    )?;
+                }
+                Ok(found)
+            })
+            .await?;
+        // An unknown scope can only be narrower than read-write
+        Ok(found.map(|(user, scope)| (user, TokenScope::parse(&scope).unwrap_or(TokenScope::Read))))
+    }
+
+    pub async fn list_api_tokens(&self) -> Result<Vec<ApiToken>> {
+        let tokens = self
+            .conn
+            .call(|conn| {
+                let mut stmt = conn.prepare(
+                    "SELECT t.id, u.username, t.name, t.scope, t.created_at, t.last_used_at
+                     FROM api_tokens t JOIN users u ON u.id = t.user_id ORDER BY u.username, t.id",
+                )?;
+                let tokens = stmt
+                    .query_map([], |row| {
+                        Ok(ApiToken {
+                            id: row.get(0)?,
+                            username: row.get(1)?,
+                            name: row.get(2)?,
+                            scope: TokenScope::parse(&row.get::<_, String>(3)?).unwrap_or(TokenScope::Read),
+                            created_at: row.get::<_, String>(4).ok().and_then(|s| parse_datetime(&s)).unwrap_or_else(Utc::now),
+                            last_used_at: row.get::<_, Option<String>>(5)?.and_then(|s| parse_datetime(&s)),
+                        })
+                    })?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(tokens)
+            })
+            .await?;
+        Ok(tokens)
+    }
+
+    /// Revoke an API token. Returns whether there was one.
+    pub async fn delete_api_token(&self, id: i64) -> Result<bool> {
+        let deleted = self
+            .conn
+            .call(move |conn| Ok(conn.execute("DELETE FROM api_tokens WHERE id = ?1", params![id])?))
+            .await?;
+        Ok(deleted > 0)
+    }
+
+    /// Replace a user's API token (only its hash is stored)
+    pub async fn set_user_token_hash(&self, user_id: i64, token_hash: &str) -> Result<()> {
+        let token_hash = token_hash.to_string();
+        self.conn
+            .call(move |conn| {
+                conn.execute(
+                    "UPDATE users SET token_hash = ?1 WHERE id = ?2",
+                    params![token_hash, user_id],
+                )?;
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_usernames(&self) -> Result<Vec<String>> {
+        let names = self
+            .conn
+            .call(|conn| {
+                let mut stmt = conn.prepare("SELECT username FROM users ORDER BY username")?;
+                let names = stmt.query_map([], |row| row.get(0))?.collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(names)
+            })
+            .await?;
+        Ok(names)
+    }
+
+    pub async fn count_users(&self) -> Result<usize> {
+        let count = self
+            .conn
+            .call(|conn| {
+                let count: i64 = conn.query_row("SELECT COUNT(*) FROM users", [], |row| row.get(0))?;
+                Ok(count as usize)
+            })
+            .await?;
+        Ok(count)
+    }
+
+    pub async fn get_user_feeds(&self, user_id: i64) -> Result<Vec<Feed>> {
+        let feeds = self
+            .conn
+            .call(move |conn| {
+                let mut stmt = conn.prepare(&format!(
+                    "{} JOIN user_feeds uf ON uf.feed_id = f.id WHERE uf.user_id = ?1 AND f.archived_at IS NULL ORDER BY {}",
+                    FEED_SELECT, FEED_ORDER
+                ))?;
+                let feeds = stmt
+                    .query_map(params![user_id], |row| Ok(feed_from_row(row)))?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(feeds)
+            })
+            .await?;
+        Ok(feeds)
+    }
+
+    pub async fn get_feed(&self, id: i64) -> Result<Option<Feed>> {
+        let feed = self
+            .conn
+            .call(move |conn| {
+                let feed = conn
+                    .query_row(&format!("{} WHERE f.id = ?1", FEED_SELECT), params![id], |row| Ok(feed_from_row(row)))
+                    .optional()?;
+                Ok(feed)
+            })
+            .await?;
+        Ok(feed)
+    }
+
+    pub async fn get_feed_by_url(&self, url: &str) -> Result<Option<Feed>> {
+        let url = url.to_string();
+        let feed = self
+            .conn
+            .call(move |conn| {
+                let feed = conn
+                    .query_row(
+                        &format!("{} WHERE f.url = ?1 AND f.archived_at IS NULL", FEED_SELECT),
+                        params![url],
+                        |row| Ok(feed_from_row(row)),
+                    )
+                    .optional()?;
+                Ok(feed)
+            })
+            .await?;
+        Ok(feed)
+    }
+
+    /// The paused "Saved" pseudo-feed that imported read-later links live
+    /// in, created on first use
+    pub async fn saved_feed_id(&self) -> Result<i64> {
+        let id = self
+            .conn
+            .call(|conn| {
+                conn.execute(
+                    "INSERT OR IGNORE INTO feeds (title, url, paused) VALUES ('Saved', ?1, 1)",
+                    params![SAVED_FEED_URL],
+                )?;
+                let id = conn.query_row("SELECT id FROM feeds WHERE url = ?1", params![SAVED_FEED_URL], |row| {
+                    row.get(0)
+                })?;
+                Ok(id)
+            })
+            .await?;
+        Ok(id)
+    }
+
+    /// An article already stored for `url`, in any feed
+    pub async fn article_id_by_url(&self, url: &str) -> Result<Option<i64>> {
+        let url = url.to_string();
+        let id = self
+            .conn
+            .call(move |conn| {
+                let id = conn
+                    .query_row(
+                        "SELECT id FROM articles WHERE url = ?1 ORDER BY id LIMIT 1",
+                        params![url],
+                        |row| row.get(0),
+                    )
+                    .optional()?;
+                Ok(id)
+            })
+            .await?;
+        Ok(id)
+    }
+
+    /// An article stored for `url` in one of the feeds a server user
+    /// subscribes to (ones they deleted included)
+    pub async fn user_article_id_by_url(&self, user_id: i64, url: &str) -> Result<Option<i64>> {
+        let url = url.to_string();
+        let id = self
+            .conn
+            .call(move |conn| {
+                let sql = format!(
+                    "SELECT a.id FROM articles a WHERE a.url = ?1 AND {} ORDER BY a.id LIMIT 1",
+                    USER_VISIBLE.replace("?{n}", "?2")
+                );
+                let id = conn.query_row(&sql, params![url, user_id], |row| row.get(0)).optional()?;
+                Ok(id)
+            })
+            .await?;
+        Ok(id)
+    }
+
+    pub async fn subscribe_user(&self, user_id: i64, feed_id: i64) -> Result<()> {
+        self.conn
+            .call(move |conn| {
+                conn.execute(
+                    "INSERT OR IGNORE INTO user_feeds (user_id, feed_id) VALUES (?1, ?2)",
+                    params![user_id, feed_id],
+                )?;
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Drop a user's subscription; returns false if they weren't subscribed.
+    /// The feed itself (and other users' subscriptions) are left alone.
+    pub async fn unsubscribe_user(&self, user_id: i64, feed_id: i64) -> Result<bool> {
+        let removed = self
+            .conn
+            .call(move |conn| {
+                let removed = conn.execute(
+                    "DELETE FROM user_feeds WHERE user_id = ?1 AND feed_id = ?2",
+                    params![user_id, feed_id],
+                )?;
+                Ok(removed > 0)
+            })
+            .await?;
+        Ok(removed)
+    }
+
+    // Summary operations
+
+    pub async fn get_summary(&self, article_id: i64) -> Result<Option<Summary>> {
+        let summary = self
+            .conn
+            .call(move |conn| {
+                let mut stmt = conn.prepare(
+                    "SELECT id, article_id, content, model_version, generated_at, provider FROM summaries WHERE article_id = ?1",
+                )?;
+                let summary = stmt
+                    .query_row(params![article_id], |row| Ok(summary_from_row(row)))
+                    .optional()?;
+                Ok(summary)
+            })
+            .await?;
+        Ok(summary)
+    }
+
+    /// A summary already generated for content with this hash, if any
+    pub async fn find_summary_by_hash(&self, content_hash: String) -> Result<Option<Summary>> {
+        let summary = self
+            .conn
+            .call(move |conn| {
+                let mut stmt = conn.prepare(
+                    "SELECT id, article_id, content, model_version, generated_at, provider FROM summaries
+                     WHERE content_hash = ?1 ORDER BY generated_at DESC LIMIT 1",
+                )?;
+                let summary = stmt
+                    .query_row(params![content_hash], |row| Ok(summary_from_row(row)))
+                    .optional()?;
+                Ok(summary)
+            })
+            .await?;
+        Ok(summary)
+    }
+
+    pub async fn save_summary(
+        &self,
+        article_id: i64,
+        content: String,
+        model: String,
+        provider: Option<String>,
+        content_hash: Option<String>,
+    ) -> Result<()> {
+        self.conn
+            .call(move |conn| {
+                conn.execute(
+                    r#"INSERT INTO summaries (article_id, content, model_version, provider, content_hash)
+                       VALUES (?1, ?2, ?3, ?4, ?5)
+                       ON CONFLICT(article_id) DO UPDATE SET
+                           content = excluded.content,
+                           model_version = excluded.model_version,
+                           provider = excluded.provider,
+                           content_hash = excluded.content_hash,
+                           generated_at = datetime('now')"#,
+                    params![article_id, content, model, provider, content_hash],
+                )?;
+                conn.execute("DELETE FROM summary_failures WHERE article_id = ?1", params![article_id])?;
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Note that summarizing an article failed. With `retry` (the error
+    /// was transient) it's retried automatically, backing off each time,
+    /// until `SUMMARY_RETRY_LIMIT` attempts have failed.
+    pub async fn record_summary_failure(&self, article_id: i64, error: String, retry: bool) -> Result<()> {
+        self.conn
+            .call(move |conn| {
+                let attempts: u32 = conn
+                    .query_row(
+                        "SELECT attempts FROM summary_failures WHERE article_id = ?1",
+                        params![article_id],
+                        |row| row.get(0),
+                    )
+                    .optional()?
+                    .map_or(1, |attempts: u32| attempts + 1);
+                let now = Utc::now();
+                let next_retry_at = (retry && attempts <= SUMMARY_RETRY_LIMIT).then(|| {
+                    let wait = SUMMARY_RETRY_BASE_MINUTES << (2 * (attempts - 1));
+                    (now + chrono::Duration::minutes(wait)).to_rfc3339()
+                });
+                conn.execute(
+                    r#"INSERT INTO summary_failures (article_id, error, attempts, failed_at, next_retry_at)
+                       VALUES (?1, ?2, ?3, ?4, ?5)
+                       ON CONFLICT(article_id) DO UPDATE SET
+                           error = excluded.error,
+                           attempts = excluded.attempts,
+                           failed_at = excluded.failed_at,
+                           next_retry_at = excluded.next_retry_at"#,
+                    params![article_id, error, attempts, now.to_rfc3339(), next_retry_at],
+                )?;
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Failed summaries, most recent failure first
+    pub async fn get_summary_failures(&self) -> Result<Vec<SummaryFailure>> {
+        let failures = self
+            .conn
+            .call(|conn| {
+                let mut stmt = conn.prepare(
+                    r#"SELECT sf.article_id, a.title, sf.error, sf.attempts, sf.failed_at, sf.next_retry_at
+                       FROM summary_failures sf
+                       JOIN articles a ON a.id = sf.article_id
+                       ORDER BY sf.failed_at DESC"#,
+                )?;
+                let failures = stmt
+                    .query_map([], |row| {
+                        Ok(SummaryFailure {
+                            article_id: row.get(0)?,
+                            title: row.get(1)?,
+                            error: row.get(2)?,
+                            attempts: row.get(3)?,
+                            failed_at: row
+                                .get::<_, String>(4)
+                                .ok()
+                                .and_then(|s| parse_datetime(&s))
+                                .unwrap_or_else(Utc::now),
+                            next_retry_at: row
+                                .get::<_, Option<String>>(5)?
+                                .and_then(|s| parse_datetime(&s)),
+                        })
+                    })?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(failures)
+            })
+            .await?;
+        Ok(failures)
+    }
+
+    // Journal operations
+
+    /// Note `ids` (feeds or articles) as queued in `pipeline`
+    pub async fn journal_add(&self, pipeline: Pipeline, ids: Vec<i64>) -> Result<()> {
+        self.conn
+            .call(move |conn| {
+                let tx = conn.transaction()?;
+                for id in ids {
+                    tx.execute(
+                        "INSERT OR IGNORE INTO journal (pipeline, item_id) VALUES (?1, ?2)",
+                        params![pipeline.as_str(), id],
+                    )?;
+                }
+                tx.commit()?;
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Note one item of `pipeline` as finished, whatever the outcome
+    pub async fn journal_done(&self, pipeline: Pipeline, id: i64) -> Result<()> {
+        self.conn
+            .call(move |conn| {
+                conn.execute(
+                    "DELETE FROM journal WHERE pipeline = ?1 AND item_id = ?2",
+                    params![pipeline.as_str(), id],
+                )?;
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Items queued in `pipeline` and not finished yet
+    pub async fn journal_pending(&self, pipeline: Pipeline) -> Result<Vec<i64>> {
+        let ids = self
+            .conn
+            .call(move |conn| {
+                let mut stmt = conn.prepare("SELECT item_id FROM journal WHERE pipeline = ?1 ORDER BY item_id")?;
+                let ids = stmt
+                    .query_map(params![pipeline.as_str()], |row| row.get(0))?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(ids)
+            })
+            .await?;
+        Ok(ids)
+    }
+
+    pub async fn journal_clear(&self, pipeline: Pipeline) -> Result<()> {
+        self.conn
+            .call(move |conn| {
+                conn.execute("DELETE FROM journal WHERE pipeline = ?1", params![pipeline.as_str()])?;
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    // Annotation operations
+
+    pub async fn get_annotation(&self, article_id: i64) -> Result<Option<String>> {
+        let note = self
+            .conn
+            .call(move |conn| {
+                let note = conn
+                    .query_row("SELECT note FROM annotations WHERE article_id = ?1", params![article_id], |row| {
+                        row.get(0)
+                    })
+                    .optional()?;
+                Ok(note)
+            })
+            .await?;
+        Ok(note)
+    }
+
+    /// Every article's note, by article id
+    pub async fn get_annotations(&self) -> Result<HashMap<i64, String>> {
+        let notes = self
+            .conn
+            .call(move |conn| {
+                let mut stmt = conn.prepare("SELECT article_id, note FROM annotations")?;
+                let notes = stmt
+                    .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                    .collect::<std::result::Result<HashMap<_, _>, _>>()?;
+                Ok(notes)
+            })
+            .await?;
+        Ok(notes)
+    }
+
+    /// Save the note on an article; a blank one removes it
+    pub async fn set_annotation(&self, article_id: i64, note: String) -> Result<()> {
+        self.conn
+            .call(move |conn| {
+                if note.trim().is_empty() {
+                    conn.execute("DELETE FROM annotations WHERE article_id = ?1", params![article_id])?;
+                } else {
+                    conn.execute(
+                        "INSERT INTO annotations (article_id, note) VALUES (?1, ?2)
+                         ON CONFLICT(article_id) DO UPDATE SET note = excluded.note, updated_at = datetime('now')",
+                        params![article_id, note.trim_end()],
+                    )?;
+                }
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    // Settings operations
+
+    pub async fn get_setting(&self, key: &str) -> Result<Option<String>> {
+        let key = key.to_string();
+        let value = self
+            .conn
+            .call(move |conn| {
+                let value = conn
+                    .query_row("SELECT value FROM settings WHERE key = ?1", params![key], |row| row.get(0))
+                    .optional()?;
+                Ok(value)
+            })
+            .await?;
+        Ok(value)
+    }
+
+    pub async fn set_setting(&self, key: &str, value: String) -> Result<()> {
+        let key = key.to_string();
+        self.conn
+            .call(move |conn| {
+                conn.execute(
+                    "INSERT INTO settings (key, value) VALUES (?1, ?2)
+                     ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+                    params![key, value],
+                )?;
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Articles whose failed summary is due an automatic retry by `now`
+    pub async fn due_summary_retries(&self, now: DateTime<Utc>) -> Result<Vec<i64>> {
+        let ids = self
+            .conn
+            .call(move |conn| {
+                let mut stmt = conn.prepare(
+                    r#"SELECT article_id FROM summary_failures
+                       WHERE next_retry_at IS NOT NULL AND next_retry_at <= ?1
+                       ORDER BY next_retry_at"#,
+                )?;
+                let ids = stmt
+                    .query_map(params![now.to_rfc3339()], |row| row.get(0))?
+                    .collect::<std::result::Result<Vec<i64>, _>>()?;
+                Ok(ids)
+            })
+            .await?;
+        Ok(ids)
+    }
+
+    // Audit log
+
+    /// Note something done outside the repository's own writes (an import,
+    /// say) in the audit log
+    pub async fn record_audit(&self, action: &str, subject: Option<String>, detail: Option<String>) -> Result<()> {
+        let action = action.to_string();
+        self.conn
+            .call(move |conn| Ok(audit(conn, &action, subject.as_deref(), detail.as_deref())?))
+            .await?;
+        Ok(())
+    }
+
+    /// Audit log entries matching `filter`, newest first
+    pub async fn get_audit_log(&self, filter: AuditFilter, limit: usize) -> Result<Vec<AuditEvent>> {
+        let entries = self
+            .conn
+            .call(move |conn| {
+                let mut stmt = conn.prepare(
+                    r#"SELECT id, at, action, subject, detail FROM audit_log
+                       WHERE (?1 IS NULL OR action = ?1 OR action LIKE ?1 || '.%')
+                         AND (?2 IS NULL OR subject LIKE '%' || ?2 || '%' OR detail LIKE '%' || ?2 || '%')
+                         AND (?3 IS NULL OR datetime(at) >= datetime(?3))
+                       ORDER BY id DESC LIMIT ?4"#,
+                )?;
+                let entries = stmt
+                    .query_map(
+                        params![filter.action, filter.text, filter.since.map(|d| d.to_rfc3339()), limit as i64],
+                        |row| {
+                            Ok(AuditEvent {
+                                id: row.get(0)?,
+                                at: parse_datetime(&row.get::<_, String>(1)?).unwrap_or_else(Utc::now),
+                                action: row.get(2)?,
+                                subject: row.get(3)?,
+                                detail: row.get(4)?,
+                            })
+                        },
+                    )?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(entries)
+            })
+            .await?;
+        Ok(entries)
+    }
+
+    // Raindrop tracking
+
+    pub async fn mark_saved_to_raindrop(
+        &self,
+        article_id: i64,
+        raindrop_id: i64,
+        tags: Vec<String>,
+    ) -> Result<()> {
+        let tags_json = serde_json::to_string(&tags)?;
+        self.conn
+            .call(move |conn| {
+                conn.execute(
+                    "INSERT OR REPLACE INTO saved_to_raindrop (article_id, raindrop_id, tags) VALUES (?1, ?2, ?3)",
+                    params![article_id, raindrop_id, tags_json],
+                )?;
+                let url: Option<String> = conn
+                    .query_row("SELECT url FROM articles WHERE id = ?1", params![article_id], |row| row.get(0))
+                    .optional()?;
+                let detail = match tags.is_empty() {
+                    true => format!("raindrop {}", raindrop_id),
+                    false => format!("raindrop {}, tags {}", raindrop_id, tags.join(", ")),
+                };
+                audit(conn, "push.raindrop", url.as_deref(), Some(&detail))?;
+                Ok(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    pub async fn is_saved_to_raindrop(&self, article_id: i64) -> Result<bool> {
+        let exists = self
+            .conn
+            .call(move |conn| {
+                let count: i64 = conn.query_row(
+                    "SELECT COUNT(*) FROM saved_to_raindrop WHERE article_id = ?1",
+                    params![article_id],
+                    |row| row.get(0),
+                )?;
+                Ok(count > 0)
+            })
+            .await?;
+        Ok(exists)
+    }
+}
+
+/// Delete articles older than `days` (by published_at, falling back to
+/// fetched_at) along with their summaries, Raindrop records and per-user
+/// state. Articles starred or queued (locally or by any server user), noted,
+/// snoozed, collected or in the history, and those of archived feeds, are kept
+/// regardless of age; one back from a snooze or out of an embargo ages from
+/// when it came back.
+fn delete_expired_articles(conn: &rusqlite::Connection, days: i64) -> rusqlite::Result<usize> {
+    const EXPIRED: &str = r#"SELECT id FROM articles
+        WHERE is_starred = 0 AND queued_at IS NULL AND snooze_until IS NULL
+          AND id NOT IN (SELECT article_id FROM user_article_state WHERE is_starred = 1 OR queued_at IS NOT NULL)
+          AND id NOT IN (SELECT article_id FROM annotations)
+          AND id NOT IN (SELECT article_id FROM history)
+          AND id NOT IN (SELECT article_id FROM collection_articles)
+          AND feed_id NOT IN (SELECT id FROM feeds WHERE archived_at IS NOT NULL)
+          AND (surfaced_at IS NULL OR surfaced_at < datetime('now', '-' || ?1 || ' days'))
+          AND (embargo_until IS NULL OR datetime(embargo_until) < datetime('now', '-' || ?1 || ' days'))
+          AND (published_at < datetime('now', '-' || ?1 || ' days')
+           OR (published_at IS NULL AND fetched_at < datetime('now', '-' || ?1 || ' days')))"#;
+
+    delete_articles_in(conn, EXPIRED, params![days])
+}
+
+/// Delete the articles whose ids `select_ids` returns, and the rows that hang
+/// off them. Returns how many articles went.
+fn delete_articles_in(
+    conn: &rusqlite::Connection,
+    select_ids: &str,
+    params: &[&dyn rusqlite::ToSql],
+) -> rusqlite::Result<usize> {
+    for table in [
+        "summaries",
+        "summary_failures",
+        "saved_to_raindrop",
+        "user_article_state",
+        "user_article_tags",
+        "discussions",
+        "playback",
+        "article_tags",
+        "article_entities",
+        "authors",
+        "annotations",
+        "history",
+        "collection_articles",
+        "article_revisions",
+        "article_links",
+    ] {
+        conn.execute(
+            &format!("DELETE FROM {} WHERE article_id IN ({})", table, select_ids),
+            params,
+        )?;
+    }
+    conn.execute(&format!("DELETE FROM articles WHERE id IN ({})", select_ids), params)
+}
+
+/// Swap the local read/star/queue flags on `articles` for `user_id`'s own
+/// state, and add their own tags. Pins belong to the local reader, so
+/// they're cleared.
+fn apply_user_state(
+    conn: &rusqlite::Connection,
+    user_id: i64,
+    articles: &mut [Article],
+) -> rusqlite::Result<()> {
+    let mut stmt = conn.prepare(
+        r#"SELECT is_read, is_starred, queued_at IS NOT NULL FROM user_article_state
+           WHERE user_id = ?1 AND article_id = ?2"#,
+    )?;
+    let mut tags =
+        conn.prepare("SELECT tag FROM user_article_tags WHERE user_id = ?1 AND article_id = ?2 ORDER BY tag")?;
+    for article in articles.iter_mut() {
+        let (is_read, is_starred, is_queued) = stmt
+            .query_row(params![user_id, article.id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .optional()?
+            .unwrap_or((false, false, false));
+        article.is_read = is_read;
+        article.is_starred = is_starred;
+        article.is_pinned = false;
+        article.is_queued = is_queued;
+        for tag in tags.query_map(params![user_id, article.id], |row| row.get::<_, String>(0))? {
+            let tag = tag?;
+            if !article.tags.contains(&tag) {
+                article.tags.push(tag);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Upsert per-day article counts for every watched entity, from `since`
+/// (all stored articles when `None`)
+fn record_entity_coverage(conn: &rusqlite::Connection, since: Option<NaiveDate>) -> rusqlite::Result<()> {
+    conn.execute(
+        r#"INSERT INTO entity_coverage (entity, day, articles)
+           SELECT w.entity, date(COALESCE(a.published_at, a.fetched_at)) AS day, COUNT(DISTINCT a.id)
+           FROM (SELECT DISTINCT entity FROM watchlist_entities) w
+           JOIN article_entities e ON e.name = w.entity COLLATE NOCASE
+           JOIN articles a ON a.id = e.article_id
+           WHERE ?1 IS NULL OR date(COALESCE(a.published_at, a.fetched_at)) >= ?1
+           GROUP BY w.entity, day
+           ON CONFLICT(entity, day) DO UPDATE SET articles = MAX(articles, excluded.articles)"#,
+        params![since.map(|d| d.to_string())],
+    )?;
+    Ok(())
+}
+
+/// `compress(text)` and `decompress(value)`, for the article text columns.
+/// Text past `COMPRESS_MIN_BYTES` is stored as a zstd BLOB (shorter text
+/// stays TEXT, where the frame overhead isn't worth it); `decompress`
+/// gives back TEXT for either, so reads and searches go through it.
+fn register_compression(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+    use rusqlite::functions::FunctionFlags;
+    use rusqlite::types::{Value, ValueRef};
+
+    let flags = FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC;
+    conn.create_scalar_function("compress", 1, flags, |ctx| {
+        Ok(match ctx.get_raw(0) {
+            ValueRef::Text(text) if text.len() >= COMPRESS_MIN_BYTES => {
+                let packed = zstd::bulk::compress(text, COMPRESS_LEVEL)
+                    .map_err(|e| rusqlite::Error::UserFunctionError(Box::new(e)))?;
+                if packed.len() < text.len() {
+                    Value::Blob(packed)
+                } else {
+                    Value::Text(String::from_utf8_lossy(text).into_owned())
+                }
+            }
+            value => value.into(),
+        })
+    })?;
+    conn.create_scalar_function("decompress", 1, flags, |ctx| {
+        Ok(match ctx.get_raw(0) {
+            ValueRef::Blob(packed) => {
+                let text = zstd::stream::decode_all(packed)
+                    .map_err(|e| rusqlite::Error::UserFunctionError(Box::new(e)))?;
+                Value::Text(String::from_utf8_lossy(&text).into_owned())
+            }
+            value => value.into(),
+        })
+    })
+}
+
+/// `regexp(pattern, text)`, for `re:` search terms. Patterns are compiled
+/// once per statement; text past `REGEX_MAX_TEXT` bytes isn't searched.
+fn register_regexp(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+    use rusqlite::functions::FunctionFlags;
+    use rusqlite::types::ValueRef;
+
+    conn.create_scalar_function(
+        "regexp",
+        2,
+        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let regex = ctx.get_or_create_aux(0, |pattern| -> std::result::Result<_, String> {
+                compile_regex(pattern.as_str().map_err(|e| e.to_string())?)
+            })?;
+            let text = match ctx.get_raw(1) {
+                ValueRef::Text(bytes) => bytes,
+                _ => return Ok(false),
+            };
+            let mut end = text.len().min(REGEX_MAX_TEXT);
+            let text = loop {
+                match std::str::from_utf8(&text[..end]) {
+                    Ok(text) => break text,
+                    // Cut mid-character: back up to the character's start
+                    Err(e) if e.error_len().is_none() => end = e.valid_up_to(),
+                    Err(e) => return Err(rusqlite::Error::UserFunctionError(Box::new(e))),
+                }
+            };
+            Ok(regex.is_match(text))
+        },
+    )
+}
+
+fn insert_article_tags(conn: &rusqlite::Connection, id: i64, tags: &[String]) -> rusqlite::Result<()> {
+    for tag in tags {
+        conn.execute(
+            "INSERT OR IGNORE INTO article_tags (article_id, tag) VALUES (?1, ?2)",
+            params![id, tag],
+        )?;
+    }
+    Ok(())
+}
+
+/// How far back `posts_per_day` looks, in days
+const POSTING_WINDOW_DAYS: i64 = 30;
+
+/// Recompute a feed's average posts per day and newest post. The rate is
+/// taken over the window's articles, divided by the days they actually span
+/// (at least one), so a busy feed that only keeps a day of items still shows
+/// its real volume. Feeds with nothing recent get 0.
+fn update_feed_stats(conn: &rusqlite::Connection, id: i64) -> rusqlite::Result<()> {
+    conn.execute(
+        r#"UPDATE feeds SET
+               last_post_at = (SELECT MAX(datetime(COALESCE(published_at, fetched_at)))
+                               FROM articles WHERE feed_id = ?1),
+               posts_per_day = (SELECT COALESCE(COUNT(*) / MAX(julianday('now')
+                                    - julianday(MIN(datetime(COALESCE(published_at, fetched_at)))), 1.0), 0)
+                                FROM articles WHERE feed_id = ?1
+                                  AND datetime(COALESCE(published_at, fetched_at))
+                                      >= datetime('now', '-' || ?2 || ' days'))
+           WHERE id = ?1"#,
+        params![id, POSTING_WINDOW_DAYS],
+    )?;
+    Ok(())
+}
+
+/// Compute posting stats for feeds that haven't been fetched since they were tracked
+fn backfill_feed_stats(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+    let mut stmt = conn.prepare("SELECT id FROM feeds WHERE posts_per_day IS NULL")?;
+    let pending = stmt
+        .query_map([], |row| row.get::<_, i64>(0))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    for id in pending {
+        update_feed_stats(conn, id)?;
+    }
+    Ok(())
+}
+
+/// The database's size and how much of it is free pages, in bytes
+fn database_bytes(conn: &rusqlite::Connection) -> rusqlite::Result<(i64, i64)> {
+    let page_size: i64 = conn.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+    let pages: i64 = conn.query_row("PRAGMA page_count", [], |row| row.get(0))?;
+    let free: i64 = conn.query_row("PRAGMA freelist_count", [], |row| row.get(0))?;
+    Ok((pages * page_size, free * page_size))
+}
+
+/// Bytes each table takes, its indexes included, largest first. SQLite's
+/// own tables are left out.
+fn table_bytes(conn: &rusqlite::Connection) -> rusqlite::Result<Vec<(String, i64)>> {
+    let mut stmt = conn.prepare(
+        r#"SELECT COALESCE(m.tbl_name, s.name) AS tbl, SUM(s.pgsize)
+           FROM dbstat s LEFT JOIN sqlite_schema m ON m.name = s.name
+           GROUP BY tbl HAVING tbl NOT LIKE 'sqlite\_%' ESCAPE '\'
+           ORDER BY 2 DESC, tbl"#,
+    )?;
+    let sizes = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect();
+    sizes
+}
+
+/// Estimate reading time for articles stored before it was tracked
+fn backfill_reading_minutes(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+    let mut stmt = conn.prepare(
+        "SELECT id, decompress(COALESCE(content_text, content)) FROM articles WHERE reading_minutes IS NULL AND COALESCE(content_text, content) IS NOT NULL",
+    )?;
+    let pending = stmt
+        .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    for (id, text) in pending {
+        conn.execute(
+            "UPDATE articles SET reading_minutes = ?1 WHERE id = ?2",
+            params![estimate_reading_minutes(&text), id],
+        )?;
+    }
+    Ok(())
+}
+
+fn parse_datetime(s: &str) -> Option<DateTime<Utc>> {
+    // Try RFC3339 first (e.g., "2026-01-11T12:34:56+00:00")
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    // Try SQLite datetime format (e.g., "2026-01-11 12:34:56")
+    if let Ok(naive) = chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S") {
+        return Some(naive.and_utc());
+    }
+    None
+}
+
+/// Articles starred locally or by any server user
+const STARRED: &str =
+    "is_starred = 1 OR id IN (SELECT article_id FROM user_article_state WHERE is_starred = 1)";
+
+/// Remove a feed as `removal` says. Call inside a transaction.
+fn remove_feed_rows(
+    conn: &rusqlite::Connection,
+    id: i64,
+    removal: FeedRemoval,
+    reason: Option<&str>,
+) -> rusqlite::Result<()> {
+    let feed: Option<(String, String)> = conn
+        .query_row(
+            "SELECT url, COALESCE(custom_title, title) FROM feeds WHERE id = ?1",
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+    if let Some((url, title)) = feed {
+        let (action, how) = match removal {
+            FeedRemoval::Archive => ("feed.archive", "archived"),
+            FeedRemoval::KeepStarred => ("feed.remove", "removed, starred articles kept"),
+            FeedRemoval::DeleteAll => ("feed.remove", "removed with its articles"),
+        };
+        let detail = match reason {
+            Some(reason) => format!("{}: {} ({})", title, how, reason),
+            None => format!("{}: {}", title, how),
+        };
+        audit(conn, action, Some(&url), Some(&detail))?;
+    }
+
+    match removal {
+        FeedRemoval::Archive => {
+            conn.execute(
+                "UPDATE feeds SET archived_at = datetime('now') WHERE id = ?1",
+                params![id],
+            )?;
+            return Ok(());
+        }
+        FeedRemoval::KeepStarred => {
+            conn.execute(
+                "INSERT OR IGNORE INTO feeds (title, url, paused) VALUES ('Orphaned', ?1, 1)",
+                params![ORPHANED_FEED_URL],
+            )?;
+            let orphanage: i64 = conn.query_row(
+                "SELECT id FROM feeds WHERE url = ?1",
+                params![ORPHANED_FEED_URL],
+                |row| row.get(0),
+            )?;
+            if orphanage == id {
+                // Removing the orphanage itself only clears out what isn't starred
+                delete_articles_in(
+                    conn,
+                    &format!("SELECT id FROM articles WHERE feed_id = ?1 AND NOT ({})", STARRED),
+                    params![id],
+                )?;
+                return Ok(());
+            }
+            // GUIDs are only unique per feed, so qualify them with the old one
+            conn.execute(
+                &format!(
+                    "UPDATE articles SET feed_id = ?1, guid = ?2 || ':' || guid WHERE feed_id = ?2 AND ({})",
+                    STARRED
+                ),
+                params![orphanage, id],
+            )?;
+        }
+        FeedRemoval::DeleteAll => {}
+    }
+
+    delete_articles_in(conn, "SELECT id FROM articles WHERE feed_id = ?1", params![id])?;
+    conn.execute("DELETE FROM deleted_articles WHERE feed_id = ?1", params![id])?;
+    delete_feed_rows(conn, id)
+}
+
+/// Note an operation in the audit log, inside the caller's transaction
+/// when there is one
+fn audit(conn: &rusqlite::Connection, action: &str, subject: Option<&str>, detail: Option<&str>) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO audit_log (action, subject, detail) VALUES (?1, ?2, ?3)",
+        params![action, subject, detail],
+    )?;
+    Ok(())
+}
+
+/// Remove a feed and the rows that hang off it
+fn delete_feed_rows(conn: &rusqlite::Connection, id: i64) -> rusqlite::Result<()> {
+    conn.execute("DELETE FROM user_feeds WHERE feed_id = ?1", params![id])?;
+    conn.execute("DELETE FROM feed_tags WHERE feed_id = ?1", params![id])?;
+    conn.execute("DELETE FROM feeds WHERE id = ?1", params![id])?;
+    Ok(())
+}
+
+fn feed_from_row(row: &Row) -> Feed {
+    Feed {
+        id: row.get(0).unwrap(),
+        title: row.get(1).unwrap(),
+        url: row.get(2).unwrap(),
+        site_url: row.get(3).unwrap(),
+        description: row.get(4).unwrap(),
+        last_fetched: row
+            .get::<_, Option<String>>(5)
+            .unwrap()
+            .and_then(|s| parse_datetime(&s)),
+        created_at: row
+            .get::<_, String>(6)
+            .ok()
+            .and_then(|s| parse_datetime(&s))
+            .unwrap_or_else(Utc::now),
+        updated_at: row
+            .get::<_, String>(7)
+            .ok()
+            .and_then(|s| parse_datetime(&s))
+            .unwrap_or_else(Utc::now),
+        retry_after: row
+            .get::<_, Option<String>>(8)
+            .unwrap()
+            .and_then(|s| parse_datetime(&s)),
+        custom_title: row.get(9).unwrap(),
+        folder: row.get(10).unwrap(),
+        refresh_minutes: row.get(11).unwrap(),
+        paused: row.get(12).unwrap(),
+        tags: row
+            .get::<_, Option<String>>(13)
+            .unwrap()
+            .map(|tags| tags.split(',').map(str::to_string).collect())
+            .unwrap_or_default(),
+        posts_per_day: row.get(14).unwrap(),
+        last_post_at: row
+            .get::<_, Option<String>>(15)
+            .unwrap()
+            .and_then(|s| parse_datetime(&s)),
+        unread: row.get(16).unwrap(),
+        reading: row
+            .get::<_, Option<String>>(17)
+            .unwrap()
+            .and_then(|s| ReadingMode::parse(&s))
+            .unwrap_or_default(),
+        priority: row
+            .get::<_, Option<String>>(18)
+            .unwrap()
+            .and_then(|s| FeedPriority::parse(&s))
+            .unwrap_or_default(),
+        source: SourceInfo {
+            beat: row.get(19).unwrap(),
+            contact: row.get(20).unwrap(),
+            reliability: row.get(21).unwrap(),
+            notes: row.get(22).unwrap(),
+        },
+        reader_url: row.get(23).unwrap(),
+        missing_count: row.get(24).unwrap(),
+        moved_to: row.get(25).unwrap(),
+        repaired: row.get(26).unwrap(),
+    }
+}
+
+/// The query behind `list_articles`, with its parameters: `?1` is the
+/// article to continue after and `?2` the limit
+fn article_list_sql(filter: &ArticleFilter, limit: usize, after: Option<i64>) -> (String, Vec<Value>) {
+    let mut clauses = vec![concat!(
+        "(?1 IS NULL OR (",
+        article_sort_date!(),
+        ", a.fetched_at, a.id) < (SELECT ",
+        article_sort_date!(),
+        ", a.fetched_at, a.id FROM articles a JOIN feeds f ON f.id = a.feed_id WHERE a.id = ?1))"
+    )
+    .to_string()];
+    let mut values: Vec<Value> = vec![after.into(), (limit as i64).into()];
+
+    // Snoozed articles stay out of every listing until they're back,
+    // and embargoed ones unless searched for with `is:embargoed`
+    clauses.push("a.snooze_until IS NULL".to_string());
+    if !filter.query.as_ref().is_some_and(Query::shows_embargoed) {
+        clauses.push(NOT_EMBARGOED.to_string());
+    }
+    if let Some(feed_id) = filter.feed_id {
+        values.push(feed_id.into());
+        clauses.push(format!("a.feed_id = ?{}", values.len()));
+    }
+    if let Some(feed_ids) = &filter.feed_ids {
+        let ids: Vec<String> = feed_ids.iter().map(i64::to_string).collect();
+        clauses.push(format!("a.feed_id IN ({})", ids.join(",")));
+    }
+    if let Some(since) = filter.since {
+        values.push(since.to_rfc3339().into());
+        clauses.push(format!(
+            "datetime(COALESCE(a.published_at, a.fetched_at)) >= datetime(?{})",
+            values.len()
+        ));
+    }
+    if let Some(until) = filter.until {
+        values.push(until.to_rfc3339().into());
+        clauses.push(format!(
+            "datetime(COALESCE(a.published_at, a.fetched_at)) < datetime(?{})",
+            values.len()
+        ));
+    }
+    if let Some(user_id) = filter.user_id {
+        values.push(user_id.into());
+        let n = values.len();
+        clauses.push(USER_VISIBLE.replace("?{n}", &format!("?{n}")));
+        clauses.push(NOT_HIDDEN.replace("?{n}", &format!("?{n}")));
+        if filter.starred_only {
+            clauses.push(format!(
+                "EXISTS (SELECT 1 FROM user_article_state s WHERE s.user_id = ?{n} AND s.article_id = a.id AND s.is_starred = 1)"
+            ));
+        }
+        if filter.unread_only {
+            clauses.push(format!(
+                "NOT EXISTS (SELECT 1 FROM user_article_state s WHERE s.user_id = ?{n} AND s.article_id = a.id AND s.is_read = 1)"
+            ));
+        }
+    } else {
+        if filter.starred_only {
+            clauses.push("a.is_starred = 1".to_string());
+        }
+        if filter.unread_only {
+            clauses.push("a.is_read = 0".to_string());
+        }
+    }
+    if let Some(tag) = &filter.tag {
+        values.push(tag.clone().into());
+        let tag = values.len();
+        match filter.user_id {
+            // An account's own tags count too
+            Some(user_id) => {
+                values.push(user_id.into());
+                clauses.push(format!(
+                    "(a.id IN (SELECT article_id FROM article_tags WHERE tag = ?{tag}) OR \
+                     a.id IN (SELECT article_id FROM user_article_tags WHERE tag = ?{tag} AND user_id = ?{}))",
+                    values.len()
+                ));
+            }
+            None => clauses.push(format!("a.id IN (SELECT article_id FROM article_tags WHERE tag = ?{tag})")),
+        }
+    }
+    if let Some(entity) = &filter.entity {
+        values.push(entity.clone().into());
+        clauses.push(format!(
+            "a.id IN (SELECT article_id FROM article_entities WHERE name = ?{} COLLATE NOCASE)",
+            values.len()
+        ));
+    }
+    if let Some(author) = &filter.author {
+        values.push(author.clone().into());
+        clauses.push(format!(
+            "a.id IN (SELECT article_id FROM authors WHERE name = ?{})",
+            values.len()
+        ));
+    } else {
+        // Muted authors stay out of view unless asked for by name
+        clauses.push(
+            r#"NOT EXISTS (SELECT 1 FROM authors w JOIN author_rules r ON r.name = w.name
+                           WHERE w.article_id = a.id AND r.rule = 'mute')"#
+                .to_string(),
+        );
+    }
+    if filter.unsummarized {
+        clauses.push("NOT EXISTS (SELECT 1 FROM summaries WHERE article_id = a.id)".to_string());
+    }
+    if let Some(query) = &filter.query {
+        clauses.push(query.to_sql(&mut values, filter.user_id));
+    }
+    if let Some(text) = filter.text.as_ref().filter(|t| !t.trim().is_empty()) {
+        values.push(format!("%{}%", text.trim()).into());
+        let n = values.len();
+        clauses.push(format!(
+            "(a.title LIKE ?{n} OR decompress(a.content_text) LIKE ?{n} OR a.author LIKE ?{n})"
+        ));
+    }
+
+    // Sorting happens on ids alone, so only the page's articles have their
+    // text read (and decompressed) rather than every match
+    let sql = format!(
+        "{select} WHERE a.id IN (SELECT a.id {from} WHERE {clauses} ORDER BY {order} LIMIT ?2) ORDER BY {order}",
+        select = ARTICLE_SELECT,
+        from = article_from!(),
+        clauses = clauses.join(" AND "),
+        order = ARTICLE_ORDER
+    );
+    (sql, values)
+}
+
+fn article_from_row(row: &Row) -> Article {
+    Article {
+        id: row.get(0).unwrap(),
+        feed_id: row.get(1).unwrap(),
+        guid: row.get(2).unwrap(),
+        title: row.get(3).unwrap(),
+        url: row.get(4).unwrap(),
+        author: row.get(5).unwrap(),
+        content: row.get(6).unwrap(),
+        content_text: row.get(7).unwrap(),
+        published_at: row
+            .get::<_, Option<String>>(8)
+            .unwrap()
+            .and_then(|s| parse_datetime(&s)),
+        fetched_at: row
+            .get::<_, String>(9)
+            .ok()
+            .and_then(|s| parse_datetime(&s))
+            .unwrap_or_else(Utc::now),
+        feed_title: row.get(10).unwrap(),
+        is_starred: row.get(11).unwrap(),
+        is_pinned: row.get(12).unwrap(),
+        reading_minutes: row.get(13).unwrap(),
+        is_queued: row.get(14).unwrap(),
+        is_read: row.get(15).unwrap(),
+        full_text: row.get(16).unwrap(),
+        comments_url: row.get(17).unwrap(),
+        discussion: row.get::<_, Option<String>>(18).unwrap().map(|site| Discussion {
+            site,
+            url: row.get(19).unwrap(),
+            points: row.get(20).unwrap(),
+            comments: row.get(21).unwrap(),
+        }),
+        enclosure: row.get::<_, Option<String>>(22).unwrap().map(|url| Enclosure {
+            url,
+            mime_type: row.get(23).unwrap(),
+            duration_secs: row.get(24).unwrap(),
+        }),
+        playback_secs: row.get(25).unwrap(),
+        is_played: row.get(26).unwrap(),
+        extras: row
+            .get::<_, Option<String>>(27)
+            .unwrap()
+            .and_then(|json| serde_json::from_str(&json).ok()),
+        archive_path: row.get(28).unwrap(),
+        tags: row
+            .get::<_, Option<String>>(29)
+            .unwrap()
+            .map(|tags| tags.split(',').map(str::to_string).collect())
+            .unwrap_or_default(),
+        by_followed_author: row.get(30).unwrap(),
+        embargo_until: row
+            .get::<_, Option<String>>(31)
+            .unwrap()
+            .and_then(|s| parse_datetime(&s)),
+        revisions: row.get(32).unwrap(),
+        removed_at: row
+            .get::<_, Option<String>>(33)
+            .unwrap()
+            .and_then(|s| parse_datetime(&s)),
+    }
+}
+
+/// Whether a re-fetch changed an article's title or text, ignoring
+/// whitespace. Text that's missing now (a feed dropping its content) or
+/// only just appeared isn't an edit.
+fn is_revised(title: &str, text: Option<&str>, article: &NewArticle) -> bool {
+    fn same_words(a: &str, b: &str) -> bool {
+        a.split_whitespace().eq(b.split_whitespace())
+    }
+    if !same_words(title, &article.title) {
+        return true;
+    }
+    match (text, article.content_text.as_deref()) {
+        (Some(old), Some(new)) if !new.trim().is_empty() => !same_words(old, new),
+        _ => false,
+    }
+}
+
+fn user_from_row(row: &Row) -> User {
+    User {
+        id: row.get(0).unwrap(),
+        username: row.get(1).unwrap(),
+        password_hash: row.get(2).unwrap(),
+    }
+}
+
+fn summary_from_row(row: &Row) -> Summary {
+    Summary {
         id: row.get(0).unwrap(),
         article_id: row.get(1).unwrap(),
         content: row.get(2).unwrap(),
@@ -405,5 +3972,1661 @@ fn summary_from_row(row: &Row) -> Summary {
             .ok()
             .and_then(|s| parse_datetime(&s))
             .unwrap_or_else(Utc::now),
+        provider: row.get(5).unwrap(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Entity;
+    use tempfile::TempDir;
+
+    async fn test_repo() -> (Repository, TempDir) {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("test.db");
+        let repo = Repository::new(path.to_str().unwrap()).await.unwrap();
+        (repo, dir)
+    }
+
+    async fn insert_test_feed(repo: &Repository, url: &str) -> i64 {
+        repo.insert_feed(NewFeed {
+            title: "Test Feed".to_string(),
+            url: url.to_string(),
+            site_url: None,
+            description: None,
+        })
+        .await
+        .unwrap()
+    }
+
+    fn new_article(feed_id: i64, guid: &str, published_at: Option<&str>) -> NewArticle {
+        NewArticle {
+            feed_id,
+            guid: guid.to_string(),
+            title: format!("Article {}", guid),
+            url: format!("https://example.com/{}", guid),
+            author: None,
+            content: None,
+            content_text: None,
+            published_at: published_at.and_then(parse_datetime),
+            comments_url: None,
+            enclosure: None,
+            extras: None,
+            tags: Vec::new(),
+            entities: Vec::new(),
+            authors: Vec::new(),
+            links: Vec::new(),
+        }
+    }
+
+    // ==================== Upserts ====================
+
+    #[tokio::test]
+    async fn test_refetch_with_changed_text_keeps_revision() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://example.com/feed").await;
+        let version = |title: &str, text: Option<&str>| {
+            let mut article = new_article(feed_id, "story", None);
+            article.title = title.to_string();
+            article.content_text = text.map(str::to_string);
+            article
+        };
+
+        let id = repo.upsert_article(version("Layoffs", Some("200 workers were laid off."))).await.unwrap();
+        // Unchanged, reflowed, or with the text dropped: not edits
+        for unchanged in [
+            version("Layoffs", Some("200 workers were laid off.")),
+            version("Layoffs", Some("200 workers  were\nlaid off.")),
+            version("Layoffs", None),
+        ] {
+            repo.upsert_article(unchanged).await.unwrap();
+        }
+        assert!(repo.get_revisions(id).await.unwrap().is_empty());
+
+        repo.upsert_article(version("Layoffs", Some("1,200 workers were laid off."))).await.unwrap();
+        repo.upsert_article(version("Layoffs at Acme", Some("1,200 workers were laid off."))).await.unwrap();
+        let revisions = repo.get_revisions(id).await.unwrap();
+        assert_eq!(revisions.len(), 2);
+        assert_eq!(revisions[0].title, "Layoffs");
+        assert!(revisions[1].content_text.as_deref().unwrap().starts_with("200 workers"));
+        assert_eq!(repo.get_article(id).await.unwrap().unwrap().revisions, 2);
+
+        let edited = ArticleFilter {
+            query: Some(Query::parse("has:revisions").unwrap()),
+            ..Default::default()
+        };
+        assert_eq!(repo.list_articles(&edited, 10, None).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_urls_file_sets_tags_and_names_and_archives_dropped_feeds() {
+        let (repo, _dir) = test_repo().await;
+        let wire = insert_test_feed(&repo, "https://wire.example/rss").await;
+        let blog = insert_test_feed(&repo, "https://blog.example/feed").await;
+        let by_hand = insert_test_feed(&repo, "https://other.example/feed").await;
+        repo.set_feed_custom_title(wire, Some("My wire".to_string())).await.unwrap();
+        let listing = |id: i64, tags: &[&str], title: Option<&str>| ListedFeed {
+            id,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            title: title.map(str::to_string),
+        };
+
+        let (changed, archived) = repo
+            .sync_listed_feeds(vec![listing(wire, &["news"], None), listing(blog, &[], Some("Blog"))])
+            .await
+            .unwrap();
+        assert_eq!((changed, archived), (vec![wire, blog], 0));
+        let feed = |id: i64| {
+            let repo = repo.clone();
+            async move { repo.get_all_feeds().await.unwrap().into_iter().find(|f| f.id == id) }
+        };
+        // A name given in the app stays until the file names the feed
+        let synced = feed(wire).await.unwrap();
+        assert_eq!((synced.tags, synced.custom_title.as_deref()), (vec!["news".to_string()], Some("My wire")));
+        assert_eq!(feed(blog).await.unwrap().custom_title.as_deref(), Some("Blog"));
+
+        // Unchanged listings aren't changes; the blog's name going away clears it
+        let (changed, _) = repo
+            .sync_listed_feeds(vec![listing(wire, &["news"], None), listing(blog, &[], None)])
+            .await
+            .unwrap();
+        assert_eq!(changed, [blog]);
+        assert_eq!(feed(blog).await.unwrap().custom_title, None);
+
+        // Dropped from the file: archived, but feeds added by hand stay
+        let (_, archived) = repo.sync_listed_feeds(vec![listing(wire, &["news"], None)]).await.unwrap();
+        assert_eq!(archived, 1);
+        assert!(feed(blog).await.is_none());
+        assert!(feed(by_hand).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_query_flags_follow_the_user() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://example.com/feed").await;
+        let id = repo.upsert_article(new_article(feed_id, "story", None)).await.unwrap();
+        let one = repo.create_user("one", "x").await.unwrap();
+        let two = repo.create_user("two", "x").await.unwrap();
+        for user in [one, two] {
+            repo.subscribe_user(user, feed_id).await.unwrap();
+        }
+        repo.set_user_starred(one, id, true).await.unwrap();
+        repo.set_user_read(two, id, true).await.unwrap();
+        repo.set_queued(id, true).await.unwrap();
+
+        let found = |search: &str, user_id: Option<i64>| {
+            let filter = ArticleFilter {
+                query: Some(Query::parse(search).unwrap()),
+                user_id,
+                ..Default::default()
+            };
+            let repo = repo.clone();
+            async move { repo.list_articles(&filter, 10, None).await.unwrap().len() }
+        };
+        assert_eq!(found("is:starred", Some(one)).await, 1);
+        assert_eq!(found("is:starred", Some(two)).await, 0);
+        assert_eq!(found("is:starred", None).await, 0);
+        assert_eq!(found("is:unread", Some(one)).await, 1);
+        assert_eq!(found("-is:read", Some(two)).await, 0);
+        assert_eq!(found("is:read", None).await, 0);
+        // The local reader's queue isn't an account's
+        assert_eq!(found("is:queued", None).await, 1);
+        assert_eq!(found("is:queued", Some(one)).await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_delete_article_removes_its_rows() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://example.com/feed").await;
+        let mut article = new_article(feed_id, "story", None);
+        article.content = Some("<p>See the report.</p>".to_string());
+        article.content_text = Some("First draft.".to_string());
+        article.links = vec!["https://gao.gov/reports/1".to_string()];
+        let id = repo.upsert_article(article.clone()).await.unwrap();
+        article.content_text = Some("Second draft.".to_string());
+        repo.upsert_article(article).await.unwrap();
+        repo.set_annotation(id, "Follow up".to_string()).await.unwrap();
+        repo.add_to_collection("Column", vec![id]).await.unwrap();
+        assert_eq!(repo.get_article_links(id).await.unwrap().len(), 1);
+        assert_eq!(repo.get_revisions(id).await.unwrap().len(), 1);
+
+        repo.delete_article(id).await.unwrap();
+        let left = repo
+            .conn
+            .call(move |conn| {
+                let mut left = 0;
+                for table in ["article_links", "article_revisions", "annotations", "collection_articles"] {
+                    let sql = format!("SELECT COUNT(*) FROM {} WHERE article_id = ?1", table);
+                    left += conn.query_row(&sql, params![id], |row| row.get::<_, i64>(0))?;
+                }
+                Ok(left)
+            })
+            .await
+            .unwrap();
+        assert_eq!(left, 0);
+    }
+
+    #[tokio::test]
+    async fn test_links_stored_and_searched_by_site_or_page() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://example.com/feed").await;
+        let citing = |guid: &str, links: &[&str]| {
+            let mut article = new_article(feed_id, guid, None);
+            article.content = Some("<p>See the report.</p>".to_string());
+            article.links = links.iter().map(|l| l.to_string()).collect();
+            article
+        };
+        let both = repo
+            .upsert_article(citing("both", &["https://www.gao.gov/reports/123", "https://nist.gov/ai"]))
+            .await
+            .unwrap();
+        repo.upsert_article(citing("sub", &["https://data.gao.gov/set"])).await.unwrap();
+        repo.upsert_article(citing("none", &[])).await.unwrap();
+
+        let found = |search: &str| {
+            let filter = ArticleFilter {
+                query: Some(Query::parse(search).unwrap()),
+                ..Default::default()
+            };
+            let repo = repo.clone();
+            async move {
+                let mut guids: Vec<String> =
+                    repo.list_articles(&filter, 10, None).await.unwrap().into_iter().map(|a| a.guid).collect();
+                guids.sort();
+                guids
+            }
+        };
+        assert_eq!(found("links:gao.gov").await, ["both", "sub"]);
+        assert_eq!(found("links:https://gao.gov/reports/123").await, ["both"]);
+        assert_eq!(found("links:gao.gov/reports/124").await, Vec::<String>::new());
+        assert_eq!(found("links:nist.gov -links:data.gao.gov").await, ["both"]);
+
+        assert_eq!(
+            repo.get_article_links(both).await.unwrap(),
+            ["https://www.gao.gov/reports/123", "https://nist.gov/ai"]
+        );
+        let since = Utc::now() - chrono::Duration::days(1);
+        assert_eq!(
+            repo.most_linked_domains(since, 10).await.unwrap(),
+            [("data.gao.gov".to_string(), 1), ("gao.gov".to_string(), 1), ("nist.gov".to_string(), 1)]
+        );
+
+        // A re-fetch without content keeps the links
+        repo.upsert_article(new_article(feed_id, "both", None)).await.unwrap();
+        assert_eq!(repo.get_article_links(both).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_starred_articles_checked_for_take_downs() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://example.com/feed").await;
+        let starred = repo.upsert_article(new_article(feed_id, "starred", None)).await.unwrap();
+        let other = repo.upsert_article(new_article(feed_id, "other", None)).await.unwrap();
+        repo.set_starred(starred, true).await.unwrap();
+
+        let due = repo.articles_needing_removal_check(24, 10).await.unwrap();
+        assert_eq!(due.iter().map(|(id, _)| *id).collect::<Vec<_>>(), [starred]);
+
+        // Still up: not due again until the re-check interval passes
+        repo.save_removal_check(starred, false).await.unwrap();
+        assert!(repo.articles_needing_removal_check(24, 10).await.unwrap().is_empty());
+        assert_eq!(repo.articles_needing_removal_check(0, 10).await.unwrap().len(), 1);
+
+        repo.save_removal_check(starred, true).await.unwrap();
+        assert!(repo.get_article(starred).await.unwrap().unwrap().removed_at.is_some());
+        assert!(repo.get_article(other).await.unwrap().unwrap().removed_at.is_none());
+        // Gone for good, so no more checks
+        assert!(repo.articles_needing_removal_check(0, 10).await.unwrap().is_empty());
+        let removed = ArticleFilter {
+            query: Some(Query::parse("is:removed").unwrap()),
+            ..Default::default()
+        };
+        assert_eq!(repo.list_articles(&removed, 10, None).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_bundle_takes_unread_and_starred_articles() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://example.com/feed").await;
+        let unread = repo.upsert_article(new_article(feed_id, "unread", None)).await.unwrap();
+        let read = repo.upsert_article(new_article(feed_id, "read", None)).await.unwrap();
+        let starred = repo.upsert_article(new_article(feed_id, "starred", None)).await.unwrap();
+        repo.set_read(read, true).await.unwrap();
+        repo.set_read(starred, true).await.unwrap();
+        repo.set_starred(starred, true).await.unwrap();
+
+        let mut ids: Vec<i64> = repo.get_bundle_articles(None).await.unwrap().iter().map(|a| a.id).collect();
+        ids.sort();
+        assert_eq!(ids, [unread, starred]);
+
+        // Unread articles fetched before the cut-off stay behind; starred ones don't
+        let later = Utc::now() + chrono::Duration::hours(1);
+        let ids: Vec<i64> = repo.get_bundle_articles(Some(later)).await.unwrap().iter().map(|a| a.id).collect();
+        assert_eq!(ids, [starred]);
+    }
+
+    #[tokio::test]
+    async fn test_articles_without_full_text() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://example.com/feed").await;
+        let other_feed = insert_test_feed(&repo, "https://other.example/feed").await;
+        let waiting = repo.upsert_article(new_article(feed_id, "waiting", None)).await.unwrap();
+        let fetched = repo.upsert_article(new_article(feed_id, "fetched", None)).await.unwrap();
+        let read = repo.upsert_article(new_article(feed_id, "read", None)).await.unwrap();
+        repo.upsert_article(new_article(other_feed, "elsewhere", None)).await.unwrap();
+        repo.save_full_text(fetched, "Text".to_string()).await.unwrap();
+        repo.set_read(read, true).await.unwrap();
+
+        let ids: Vec<i64> = repo
+            .articles_without_full_text(vec![feed_id])
+            .await
+            .unwrap()
+            .iter()
+            .map(|a| a.id)
+            .collect();
+        assert_eq!(ids, [waiting]);
+    }
+
+    #[tokio::test]
+    async fn test_article_text_stored_compressed() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://example.com/feed").await;
+        let text = "The fab expansion continues on schedule. ".repeat(40);
+        let mut article = new_article(feed_id, "long", None);
+        article.content = Some(format!("<p>{}</p>", text));
+        article.content_text = Some(text.clone());
+        let long = repo.upsert_article(article).await.unwrap();
+        let short = repo.upsert_article(new_article(feed_id, "short", None)).await.unwrap();
+        repo.save_full_text(long, format!("{}Sources say.", text)).await.unwrap();
+
+        let stored = repo
+            .conn
+            .call(move |conn| {
+                let types = conn.query_row(
+                    "SELECT typeof(content), typeof(content_text), typeof(full_text) FROM articles WHERE id = ?1",
+                    params![long],
+                    |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?)),
+                )?;
+                Ok(types)
+            })
+            .await
+            .unwrap();
+        assert_eq!(stored, ("blob".to_string(), "blob".to_string(), "blob".to_string()));
+
+        let article = repo.get_article(long).await.unwrap().unwrap();
+        assert_eq!(article.content_text.as_deref(), Some(text.as_str()));
+        assert!(article.full_text.unwrap().ends_with("Sources say."));
+        assert_eq!(repo.get_article(short).await.unwrap().unwrap().title, "Article short");
+
+        let filter = ArticleFilter {
+            query: Some(Query::parse("schedule").unwrap()),
+            ..Default::default()
+        };
+        let found: Vec<i64> = repo.list_articles(&filter, 10, None).await.unwrap().iter().map(|a| a.id).collect();
+        assert_eq!(found, [long]);
+    }
+
+    #[tokio::test]
+    async fn test_list_views_use_indexes() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://example.com/feed").await;
+        let views = [
+            ArticleFilter {
+                unread_only: true,
+                ..Default::default()
+            },
+            ArticleFilter {
+                feed_id: Some(feed_id),
+                ..Default::default()
+            },
+            ArticleFilter {
+                feed_id: Some(feed_id),
+                unread_only: true,
+                ..Default::default()
+            },
+            ArticleFilter {
+                starred_only: true,
+                ..Default::default()
+            },
+        ];
+        for filter in views {
+            let (sql, values) = article_list_sql(&filter, 50, None);
+            let plan = repo
+                .conn
+                .call(move |conn| {
+                    let mut stmt = conn.prepare(&format!("EXPLAIN QUERY PLAN {}", sql))?;
+                    let plan = stmt
+                        .query_map(params_from_iter(values), |row| row.get::<_, String>(3))?
+                        .collect::<std::result::Result<Vec<_>, _>>()?;
+                    Ok(plan)
+                })
+                .await
+                .unwrap();
+            // Neither the page nor the articles to sort come from a full scan
+            assert!(
+                plan.iter().all(|step| step != "SCAN a"),
+                "{:?} scans every article: {:?}",
+                filter,
+                plan
+            );
+            assert!(plan.iter().any(|step| step == "SEARCH a USING INTEGER PRIMARY KEY (rowid=?)"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_maintain_records_sizes_for_growth() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://example.com/feed").await;
+        for guid in ["1", "2", "3"] {
+            repo.upsert_article(new_article(feed_id, guid, None)).await.unwrap();
+        }
+
+        let (before, after) = repo.maintain().await.unwrap();
+        assert!(before > 0 && after > 0);
+        let stats = repo.db_stats().await.unwrap();
+        let articles = stats.tables.iter().find(|t| t.name == "articles").unwrap();
+        assert_eq!(articles.rows, 3);
+        assert!(stats.tables.iter().all(|t| !t.name.starts_with("sqlite_")));
+        // Only today's sizes so far: nothing to compare with
+        assert_eq!(stats.since, None);
+
+        repo.conn
+            .call(|conn| {
+                conn.execute(
+                    "UPDATE db_sizes SET day = date('now', '-8 days'), bytes = 1024 WHERE name = 'articles'",
+                    [],
+                )?;
+                Ok(())
+            })
+            .await
+            .unwrap();
+        let stats = repo.db_stats().await.unwrap();
+        assert_eq!(stats.since, Some((Utc::now() - chrono::Duration::days(8)).date_naive()));
+        let articles = stats.tables.iter().find(|t| t.name == "articles").unwrap();
+        assert_eq!(articles.previous_bytes, Some(1024));
+        assert_eq!(stats.tables.iter().find(|t| t.name == "feeds").unwrap().previous_bytes, None);
+    }
+
+    #[tokio::test]
+    async fn test_same_guid_in_two_feeds_kept_apart() {
+        let (repo, _dir) = test_repo().await;
+        let first_feed = insert_test_feed(&repo, "https://one.example/feed").await;
+        let second_feed = insert_test_feed(&repo, "https://two.example/feed").await;
+
+        let first = repo.upsert_article(new_article(first_feed, "1", None)).await.unwrap();
+        let second = repo.upsert_article(new_article(second_feed, "1", None)).await.unwrap();
+        assert_ne!(first, second);
+
+        // Re-fetching the first feed updates its own row and reports its id
+        let mut updated = new_article(first_feed, "1", None);
+        updated.title = "Retitled".to_string();
+        assert_eq!(repo.upsert_article(updated).await.unwrap(), first);
+
+        assert_eq!(repo.get_article(first).await.unwrap().unwrap().title, "Retitled");
+        assert_eq!(repo.get_article(second).await.unwrap().unwrap().title, "Article 1");
+        assert_eq!(repo.count_articles().await.unwrap(), 2);
+    }
+
+    // ==================== Feed metadata ====================
+
+    #[tokio::test]
+    async fn test_update_feed_metadata() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://example.com/feed").await;
+        let meta = |title: &str| NewFeed {
+            title: title.to_string(),
+            url: "https://ignored.example/".to_string(),
+            site_url: Some("https://example.com/".to_string()),
+            description: Some("Tagline".to_string()),
+        };
+
+        assert!(repo.update_feed_metadata(feed_id, meta("Renamed"), true).await.unwrap());
+        let feed = &repo.get_all_feeds().await.unwrap()[0];
+        assert_eq!(feed.title, "Test Feed");
+        assert_eq!(feed.description.as_deref(), Some("Tagline"));
+        assert_eq!(feed.url, "https://example.com/feed");
+
+        assert!(repo.update_feed_metadata(feed_id, meta("Renamed"), false).await.unwrap());
+        assert_eq!(repo.get_all_feeds().await.unwrap()[0].title, "Renamed");
+        // Nothing new the second time
+        assert!(!repo.update_feed_metadata(feed_id, meta("Renamed"), false).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_custom_title_survives_metadata_refresh() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://example.com/feed").await;
+        repo.upsert_article(new_article(feed_id, "1", None)).await.unwrap();
+        repo.set_feed_custom_title(feed_id, Some("John".to_string())).await.unwrap();
+
+        let meta = NewFeed {
+            title: "John's Blog".to_string(),
+            url: "https://example.com/feed".to_string(),
+            site_url: None,
+            description: None,
+        };
+        repo.update_feed_metadata(feed_id, meta, false).await.unwrap();
+
+        let feed = &repo.get_all_feeds().await.unwrap()[0];
+        assert_eq!((feed.title.as_str(), feed.display_title()), ("John's Blog", "John"));
+        let article = &repo.get_articles_page(10, None).await.unwrap()[0];
+        assert_eq!(article.feed_title.as_deref(), Some("John"));
+
+        repo.set_feed_custom_title(feed_id, None).await.unwrap();
+        assert_eq!(repo.get_all_feeds().await.unwrap()[0].display_title(), "John's Blog");
+    }
+
+    #[tokio::test]
+    async fn test_bulk_update_feeds() {
+        let (repo, _dir) = test_repo().await;
+        let a = insert_test_feed(&repo, "https://a.example/feed").await;
+        let b = insert_test_feed(&repo, "https://b.example/feed").await;
+        let c = insert_test_feed(&repo, "https://c.example/feed").await;
+
+        let update = FeedBulkUpdate {
+            folder: Some(Some("Tech".to_string())),
+            add_tags: vec!["news".to_string(), "daily".to_string()],
+            refresh_minutes: Some(Some(120)),
+            paused: Some(true),
+            reading: Some(ReadingMode::Headlines),
+            ..Default::default()
+        };
+        assert_eq!(repo.bulk_update_feeds(vec![a, b, 999], update).await.unwrap(), 2);
+
+        let feeds = repo.get_all_feeds().await.unwrap();
+        let feed = |id| feeds.iter().find(|f| f.id == id).unwrap();
+        assert_eq!(feed(a).folder.as_deref(), Some("Tech"));
+        assert_eq!(feed(a).tags, ["daily", "news"]);
+        assert_eq!((feed(b).refresh_minutes, feed(b).paused), (Some(120), true));
+        assert_eq!((feed(c).folder.as_deref(), feed(c).paused), (None, false));
+        assert!(!feed(b).is_due(Utc::now()));
+        assert_eq!((feed(a).reading, feed(c).reading), (ReadingMode::Headlines, ReadingMode::Full));
+
+        let update = FeedBulkUpdate {
+            folder: Some(None),
+            remove_tags: vec!["news".to_string()],
+            paused: Some(false),
+            reader_url: Some(Some("https://r.example/?u={url_encoded}".to_string())),
+            ..Default::default()
+        };
+        repo.bulk_update_feeds(vec![a], update).await.unwrap();
+        let reader = repo.get_feed(a).await.unwrap().unwrap();
+        assert_eq!(reader.reader_view("https://a.example/x?y=1"), "https://r.example/?u=https%3A%2F%2Fa.example%2Fx%3Fy%3D1");
+        assert_eq!(repo.get_feed(c).await.unwrap().unwrap().reader_view("https://c.example/x"), "https://c.example/x");
+        let delete = FeedBulkUpdate {
+            remove: Some(FeedRemoval::DeleteAll),
+            ..Default::default()
+        };
+        repo.bulk_update_feeds(vec![b, c], delete).await.unwrap();
+
+        let feeds = repo.get_all_feeds().await.unwrap();
+        assert_eq!(feeds.len(), 1);
+        assert_eq!((feeds[0].folder.as_deref(), feeds[0].paused), (None, false));
+        assert_eq!(feeds[0].tags, ["daily"]);
+    }
+
+    // ==================== Removing feeds ====================
+
+    #[tokio::test]
+    async fn test_remove_feed_keeping_starred() {
+        let (repo, _dir) = test_repo().await;
+        let a = insert_test_feed(&repo, "https://a.example/feed").await;
+        let b = insert_test_feed(&repo, "https://b.example/feed").await;
+        let starred_a = repo.upsert_article(new_article(a, "1", None)).await.unwrap();
+        repo.upsert_article(new_article(a, "2", None)).await.unwrap();
+        let starred_b = repo.upsert_article(new_article(b, "1", None)).await.unwrap();
+        repo.set_starred(starred_a, true).await.unwrap();
+        repo.set_starred(starred_b, true).await.unwrap();
+
+        repo.remove_feed(a, FeedRemoval::KeepStarred).await.unwrap();
+        // Same GUID from another feed must not collide in the orphanage
+        repo.remove_feed(b, FeedRemoval::KeepStarred).await.unwrap();
+
+        let feeds = repo.get_all_feeds().await.unwrap();
+        assert_eq!(feeds.len(), 1);
+        assert!(feeds[0].is_orphanage() && feeds[0].paused);
+        let starred = repo.get_starred_articles().await.unwrap();
+        assert_eq!(starred.len(), 2);
+        assert!(starred.iter().all(|article| article.feed_id == feeds[0].id));
+        assert_eq!(repo.count_articles().await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_saved_feed_and_article_by_url() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://a.example/feed").await;
+        let id = repo.upsert_article(new_article(feed_id, "1", None)).await.unwrap();
+
+        let saved = repo.saved_feed_id().await.unwrap();
+        assert_eq!(repo.saved_feed_id().await.unwrap(), saved);
+        let feeds = repo.get_all_feeds().await.unwrap();
+        let feed = feeds.iter().find(|f| f.id == saved).unwrap();
+        assert!(feed.is_local() && feed.paused);
+        assert_eq!(repo.article_id_by_url("https://example.com/1").await.unwrap(), Some(id));
+        assert_eq!(repo.article_id_by_url("https://example.com/2").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_archived_feed_is_hidden_until_restored() {
+        let (repo, _dir) = test_repo().await;
+        let a = insert_test_feed(&repo, "https://a.example/feed").await;
+        let b = insert_test_feed(&repo, "https://b.example/feed").await;
+        repo.upsert_article(new_article(a, "old", Some("2000-01-01T00:00:00Z"))).await.unwrap();
+        repo.upsert_article(new_article(b, "1", None)).await.unwrap();
+
+        repo.remove_feed(a, FeedRemoval::Archive).await.unwrap();
+        let feeds = repo.get_all_feeds().await.unwrap();
+        assert_eq!(feeds.iter().map(|f| f.id).collect::<Vec<_>>(), [b]);
+        assert_eq!(repo.count_articles().await.unwrap(), 1);
+        assert_eq!(repo.get_archived_feeds().await.unwrap()[0].id, a);
+        // Expiry leaves archived articles alone
+        assert_eq!(repo.delete_old_articles(30).await.unwrap(), 0);
+
+        // Subscribing to the URL again brings it back with its articles
+        let restored = repo
+            .insert_feed(NewFeed {
+                title: "Again".to_string(),
+                url: "https://a.example/feed".to_string(),
+                site_url: None,
+                description: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(restored, a);
+        assert_eq!(repo.count_articles().await.unwrap(), 2);
+        assert!(!repo.restore_feed(a).await.unwrap());
+
+        repo.remove_feed(a, FeedRemoval::DeleteAll).await.unwrap();
+        assert_eq!(repo.count_articles().await.unwrap(), 1);
+        assert!(repo.get_archived_feeds().await.unwrap().is_empty());
+    }
+
+    // ==================== Pagination ====================
+
+    #[tokio::test]
+    async fn test_pages_cover_all_articles_in_order() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://example.com/feed").await;
+
+        for day in 1..=9 {
+            let date = format!("2026-01-0{}T12:00:00+00:00", day);
+            repo.upsert_article(new_article(feed_id, &day.to_string(), Some(&date)))
+                .await
+                .unwrap();
+        }
+        // Undated articles sort after everything else
+        repo.upsert_article(new_article(feed_id, "undated", None)).await.unwrap();
+
+        let mut seen = Vec::new();
+        let mut after = None;
+        loop {
+            let page = repo.get_articles_page(3, after).await.unwrap();
+            if page.is_empty() {
+                break;
+            }
+            after = page.last().map(|a| a.id);
+            seen.extend(page.into_iter().map(|a| a.guid));
+        }
+
+        assert_eq!(seen, vec!["9", "8", "7", "6", "5", "4", "3", "2", "1", "undated"]);
+        assert_eq!(repo.count_articles().await.unwrap(), 10);
+    }
+
+    #[tokio::test]
+    async fn test_page_with_equal_dates_uses_id_tiebreak() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://example.com/feed").await;
+
+        for guid in ["a", "b", "c", "d"] {
+            repo.upsert_article(new_article(feed_id, guid, Some("2026-01-01T00:00:00+00:00")))
+                .await
+                .unwrap();
+        }
+
+        let first = repo.get_articles_page(2, None).await.unwrap();
+        let second = repo
+            .get_articles_page(2, first.last().map(|a| a.id))
+            .await
+            .unwrap();
+
+        let guids: Vec<_> = first.iter().chain(second.iter()).map(|a| a.guid.as_str()).collect();
+        assert_eq!(guids, vec!["d", "c", "b", "a"]);
+    }
+
+    #[tokio::test]
+    async fn test_priority_shifts_sort_order_and_announces() {
+        let (repo, _dir) = test_repo().await;
+        let beat = insert_test_feed(&repo, "https://beat.example/feed").await;
+        let normal = insert_test_feed(&repo, "https://example.com/feed").await;
+        let noise = insert_test_feed(&repo, "https://links.example/feed").await;
+        for (feed_id, priority) in [(beat, FeedPriority::High), (noise, FeedPriority::Low)] {
+            let update = FeedBulkUpdate { priority: Some(priority), ..Default::default() };
+            repo.bulk_update_feeds(vec![feed_id], update).await.unwrap();
+        }
+        let since = Utc::now() - chrono::Duration::minutes(1);
+        let articles = [(beat, "beat", 2), (normal, "normal-new", 9), (normal, "normal", 4), (noise, "noise", 20)];
+        for (feed_id, guid, hour) in articles {
+            let date = format!("2026-01-01T{:02}:00:00+00:00", hour);
+            repo.upsert_article(new_article(feed_id, guid, Some(&date))).await.unwrap();
+        }
+
+        // Pages agree with the shifted order
+        let first = repo.get_articles_page(2, None).await.unwrap();
+        let second = repo.get_articles_page(2, first.last().map(|a| a.id)).await.unwrap();
+        let guids: Vec<_> = first.iter().chain(second.iter()).map(|a| a.guid.as_str()).collect();
+        assert_eq!(guids, ["beat", "normal-new", "noise", "normal"]);
+
+        let news = repo.new_from_priority_feeds(since).await.unwrap();
+        assert_eq!(news, [("Test Feed".to_string(), "Article beat".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn test_count_new_articles_by_feed() {
+        let (repo, _dir) = test_repo().await;
+        let wire = insert_test_feed(&repo, "https://example.com/feed").await;
+        let other = insert_test_feed(&repo, "https://other.example/feed").await;
+        repo.upsert_article(new_article(wire, "before", None)).await.unwrap();
+        let since = Utc::now() + chrono::Duration::seconds(1);
+        assert!(repo.count_new_articles_by_feed(since).await.unwrap().is_empty());
+
+        let since = Utc::now() - chrono::Duration::minutes(1);
+        for (feed_id, guid) in [(wire, "a"), (other, "b"), (wire, "c")] {
+            repo.upsert_article(new_article(feed_id, guid, None)).await.unwrap();
+        }
+        assert_eq!(repo.count_new_articles_by_feed(since).await.unwrap(), [(wire, 3), (other, 1)]);
+    }
+
+    #[tokio::test]
+    async fn test_snoozed_article_hidden_then_back_on_top_unread() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://example.com/feed").await;
+        for (guid, days) in [("old", 10), ("new", 1)] {
+            let date = (Utc::now() - chrono::Duration::days(days)).to_rfc3339();
+            repo.upsert_article(new_article(feed_id, guid, Some(&date))).await.unwrap();
+        }
+        let guids = |articles: Vec<Article>| articles.into_iter().map(|a| a.guid).collect::<Vec<_>>();
+        let old = repo.get_articles_page(10, None).await.unwrap()[1].id;
+        repo.set_read(old, true).await.unwrap();
+
+        let until = Utc::now() + chrono::Duration::hours(3);
+        repo.snooze_article(old, until).await.unwrap();
+        assert_eq!(guids(repo.get_articles_page(10, None).await.unwrap()), ["new"]);
+        assert_eq!(repo.wake_snoozed(Utc::now()).await.unwrap(), 0);
+        // Kept past the retention window while snoozed, and for a while after
+        assert_eq!(repo.delete_old_articles(7).await.unwrap(), 0);
+
+        assert_eq!(repo.wake_snoozed(until).await.unwrap(), 1);
+        assert_eq!(repo.delete_old_articles(7).await.unwrap(), 0);
+        let articles = repo.get_articles_page(10, None).await.unwrap();
+        assert!(!articles[0].is_read);
+        assert_eq!(guids(articles), ["old", "new"]);
+    }
+
+    #[tokio::test]
+    async fn test_embargoed_until_lifted_by_hand_or_rule() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://example.com/feed").await;
+        let since = Utc::now() - chrono::Duration::minutes(1);
+        let tomorrow = (Utc::now() + chrono::Duration::days(1)).to_rfc3339();
+        for (guid, published) in [("free", None), ("press", None), ("embargo", Some(tomorrow.as_str()))] {
+            repo.upsert_article(new_article(feed_id, guid, published)).await.unwrap();
+        }
+        let guids = |articles: Vec<Article>| {
+            let mut guids: Vec<String> = articles.into_iter().map(|a| a.guid).collect();
+            guids.sort();
+            guids
+        };
+        let embargoed = ArticleFilter {
+            query: Some(Query::parse("is:embargoed").unwrap()),
+            ..Default::default()
+        };
+
+        // A rule holds matches until the publish date, or `hours` after fetching
+        let rules = vec![(Query::parse("title:embargo").unwrap(), 0), (Query::parse("title:press").unwrap(), 2)];
+        assert_eq!(repo.apply_embargo_rules(since, rules.clone()).await.unwrap(), 2);
+        assert_eq!(repo.apply_embargo_rules(since, rules).await.unwrap(), 0);
+        assert_eq!(guids(repo.get_articles_page(10, None).await.unwrap()), ["free"]);
+        assert_eq!(guids(repo.list_articles(&embargoed, 10, None).await.unwrap()), ["embargo", "press"]);
+
+        // Lifting one by hand, and the other's time passing
+        let press = repo.list_articles(&embargoed, 10, None).await.unwrap();
+        let press = press.iter().find(|a| a.guid == "press").unwrap().id;
+        repo.set_embargo(press, None).await.unwrap();
+        assert_eq!(guids(repo.get_articles_page(10, None).await.unwrap()), ["free", "press"]);
+        let later = Utc::now() + chrono::Duration::days(2);
+        assert_eq!(repo.count_embargoes_lifted(Utc::now(), later).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_collections_keep_order_and_articles() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://example.com/feed").await;
+        let old = (Utc::now() - chrono::Duration::days(30)).to_rfc3339();
+        let mut ids = Vec::new();
+        for guid in ["a", "b", "c"] {
+            ids.push(repo.upsert_article(new_article(feed_id, guid, Some(&old))).await.unwrap());
+        }
+        let guids = |articles: Vec<Article>| articles.into_iter().map(|a| a.guid).collect::<Vec<_>>();
+
+        assert_eq!(repo.add_to_collection("Tuesday column", vec![ids[2], ids[0]]).await.unwrap(), 2);
+        assert_eq!(repo.add_to_collection("tuesday COLUMN", vec![ids[0], ids[1]]).await.unwrap(), 1);
+        assert_eq!(guids(repo.get_collection_articles("Tuesday column").await.unwrap()), ["c", "a", "b"]);
+        let collections = repo.get_collections().await.unwrap();
+        assert_eq!((collections[0].name.as_str(), collections[0].article_count), ("Tuesday column", 3));
+
+        assert!(repo.move_in_collection("Tuesday column", ids[1], 1).await.unwrap());
+        assert!(!repo.move_in_collection("Tuesday column", 999, 1).await.unwrap());
+        assert_eq!(guids(repo.get_collection_articles("Tuesday column").await.unwrap()), ["b", "c", "a"]);
+
+        // Collected articles outlive the retention window
+        assert_eq!(repo.delete_old_articles(7).await.unwrap(), 0);
+        assert_eq!(repo.remove_from_collection("Tuesday column", vec![ids[2]]).await.unwrap(), 1);
+        assert_eq!(repo.delete_old_articles(7).await.unwrap(), 1);
+        assert_eq!(repo.remove_from_collection("Tuesday column", Vec::new()).await.unwrap(), 2);
+        assert!(repo.get_collections().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_articles_per_day_and_day_range() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://example.com/feed").await;
+        let other_id = insert_test_feed(&repo, "https://example.com/other").await;
+        for (feed, guid, published) in [
+            (feed_id, "a", "2026-03-02T10:00:00Z"),
+            (feed_id, "b", "2026-03-02T11:00:00Z"),
+            (other_id, "c", "2026-03-02T12:00:00Z"),
+            (feed_id, "d", "2026-03-04T09:00:00Z"),
+            (feed_id, "old", "2026-02-01T09:00:00Z"),
+        ] {
+            repo.upsert_article(new_article(feed, guid, Some(published))).await.unwrap();
+        }
+        let since = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        let day = |d| NaiveDate::from_ymd_opt(2026, 3, d).unwrap().and_hms_opt(0, 0, 0).unwrap().and_utc();
+
+        let mut counts = repo.articles_per_day(None, since).await.unwrap();
+        counts.sort();
+        let total: usize = counts.iter().map(|(_, n)| n).sum();
+        assert_eq!(total, 4);
+        let mine: usize = repo.articles_per_day(Some(feed_id), since).await.unwrap().iter().map(|(_, n)| n).sum();
+        assert_eq!(mine, 3);
+
+        // A day's articles, from its midnight to the next
+        let filter = ArticleFilter {
+            since: Some(day(2)),
+            until: Some(day(3)),
+            feed_id: Some(feed_id),
+            ..Default::default()
+        };
+        let mut found: Vec<String> = repo.list_articles(&filter, 10, None).await.unwrap().into_iter().map(|a| a.guid).collect();
+        found.sort();
+        assert_eq!(found, ["a", "b"]);
+    }
+
+    #[tokio::test]
+    async fn test_history_latest_first_and_capped() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://example.com/feed").await;
+        let mut ids = Vec::new();
+        for n in 0..=HISTORY_LIMIT {
+            let id = repo.upsert_article(new_article(feed_id, &n.to_string(), None)).await.unwrap();
+            repo.record_opened(id).await.unwrap();
+            ids.push(id);
+        }
+        // Opening one again moves it to the top; the first opened fell off
+        repo.record_opened(ids[5]).await.unwrap();
+
+        let history = repo.get_history_articles().await.unwrap();
+        assert_eq!(history.len(), HISTORY_LIMIT);
+        assert_eq!(history[0].id, ids[5]);
+        assert_eq!(history[1].id, ids[HISTORY_LIMIT]);
+        assert!(history.iter().all(|a| a.id != ids[0]));
+
+        repo.delete_article(ids[5]).await.unwrap();
+        assert_eq!(repo.get_history_articles().await.unwrap()[0].id, ids[HISTORY_LIMIT]);
+    }
+
+    #[tokio::test]
+    async fn test_reading_time_by_article_and_day() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://example.com/feed").await;
+        let first = repo.upsert_article(new_article(feed_id, "1", None)).await.unwrap();
+        let second = repo.upsert_article(new_article(feed_id, "2", None)).await.unwrap();
+        let day = NaiveDate::from_ymd_opt(2026, 3, 4).unwrap();
+        let next = day.succ_opt().unwrap();
+        for (id, on, seconds) in [(first, day, 30), (first, day, 45), (second, day, 60), (first, next, 20)] {
+            repo.add_reading_time(id, on, seconds).await.unwrap();
+        }
+
+        assert_eq!(repo.reading_seconds_on(day).await.unwrap(), 135);
+        assert_eq!(repo.article_reading_seconds(first).await.unwrap(), 95);
+
+        // The day's total outlives the article
+        repo.delete_article(second).await.unwrap();
+        assert_eq!(repo.reading_seconds_on(day).await.unwrap(), 135);
+    }
+
+    // ==================== Rate limiting ====================
+
+    #[tokio::test]
+    async fn test_throttle_recorded_and_cleared_by_successful_fetch() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://example.com/feed").await;
+        let until = parse_datetime("2030-01-01T00:00:00+00:00").unwrap();
+
+        repo.record_feed_throttled(feed_id, until).await.unwrap();
+        assert_eq!(repo.get_all_feeds().await.unwrap()[0].retry_after, Some(until));
+
+        repo.update_feed_last_fetched(feed_id, true).await.unwrap();
+        let feed = &repo.get_all_feeds().await.unwrap()[0];
+        assert_eq!((feed.retry_after, feed.repaired), (None, true));
+        // Each fetch says again whether it needed repairs
+        repo.update_feed_last_fetched(feed_id, false).await.unwrap();
+        assert!(!repo.get_all_feeds().await.unwrap()[0].repaired);
+    }
+
+    #[tokio::test]
+    async fn test_moved_feed_proposed_then_accepted() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://example.com/feed").await;
+        let other = insert_test_feed(&repo, "https://other.example/rss.xml").await;
+        repo.upsert_article(new_article(feed_id, "kept", None)).await.unwrap();
+
+        assert_eq!(repo.record_feed_missing(feed_id, None).await.unwrap(), 1);
+        let moved = Some("https://example.com/rss.xml".to_string());
+        assert_eq!(repo.record_feed_missing(feed_id, moved.clone()).await.unwrap(), 2);
+        // A later 404 without a lookup keeps the proposal
+        assert_eq!(repo.record_feed_missing(feed_id, None).await.unwrap(), 3);
+        assert_eq!(repo.get_feed(feed_id).await.unwrap().unwrap().moved_to, moved);
+
+        assert_eq!(repo.accept_feed_move(feed_id).await.unwrap(), moved);
+        let feed = repo.get_feed(feed_id).await.unwrap().unwrap();
+        assert_eq!((feed.url.as_str(), feed.moved_to, feed.missing_count), ("https://example.com/rss.xml", None, 0));
+        assert_eq!(repo.count_articles().await.unwrap(), 1);
+        assert_eq!(repo.accept_feed_move(feed_id).await.unwrap(), None);
+        let audit = repo.get_audit_log(AuditFilter::default(), 10).await.unwrap();
+        assert_eq!(audit[0].action, "feed.move");
+        assert_eq!(audit[0].subject.as_deref(), Some("https://example.com/feed"));
+
+        // Never onto a feed that's already there; a working fetch or a
+        // dismissal drops the proposal
+        repo.record_feed_missing(other, Some("https://example.com/rss.xml".to_string())).await.unwrap();
+        assert!(matches!(repo.accept_feed_move(other).await, Err(AppError::Config(_))));
+        repo.dismiss_feed_move(other).await.unwrap();
+        assert_eq!(repo.get_feed(other).await.unwrap().unwrap().moved_to, None);
+        repo.record_feed_missing(other, Some("https://other.example/feed".to_string())).await.unwrap();
+        repo.update_feed_last_fetched(other, false).await.unwrap();
+        let feed = repo.get_feed(other).await.unwrap().unwrap();
+        assert_eq!((feed.moved_to, feed.missing_count), (None, 0));
+    }
+
+    // ==================== Posting stats ====================
+
+    #[tokio::test]
+    async fn test_posting_stats_recomputed_on_fetch() {
+        let (repo, _dir) = test_repo().await;
+        let busy = insert_test_feed(&repo, "https://busy.example/feed").await;
+        let quiet = insert_test_feed(&repo, "https://quiet.example/feed").await;
+        let days_ago = |days: i64| (Utc::now() - chrono::Duration::days(days)).to_rfc3339();
+        for (i, days) in [0, 1, 2, 4].into_iter().enumerate() {
+            let article = new_article(busy, &i.to_string(), Some(&days_ago(days)));
+            repo.upsert_article(article).await.unwrap();
+        }
+        let old = new_article(quiet, "old", Some("2020-01-01T00:00:00+00:00"));
+        repo.upsert_article(old).await.unwrap();
+
+        repo.update_feed_last_fetched(busy, false).await.unwrap();
+        repo.update_feed_last_fetched(quiet, false).await.unwrap();
+
+        let feeds = repo.get_all_feeds().await.unwrap();
+        let feed = |id| feeds.iter().find(|f| f.id == id).unwrap();
+        // Four posts over the four days they span
+        assert!((feed(busy).posts_per_day.unwrap() - 1.0).abs() < 0.01);
+        assert!(Utc::now() - feed(busy).last_post_at.unwrap() < chrono::Duration::minutes(1));
+        assert_eq!(feed(quiet).posts_per_day, Some(0.0));
+        assert_eq!(feed(quiet).last_post_at, parse_datetime("2020-01-01T00:00:00+00:00"));
+    }
+
+    // ==================== Tags & entities ====================
+
+    #[tokio::test]
+    async fn test_tags_filter_count_and_survive_refetch() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://example.com/feed").await;
+        let mut first = new_article(feed_id, "1", None);
+        first.tags = vec!["rust".to_string(), "compilers".to_string()];
+        let first_id = repo.upsert_article(first.clone()).await.unwrap();
+        let mut second = new_article(feed_id, "2", None);
+        second.tags = vec!["rust".to_string()];
+        repo.upsert_article(second).await.unwrap();
+
+        let counts = repo.tag_counts(None, 10).await.unwrap();
+        assert_eq!(counts, vec![("rust".to_string(), 2), ("compilers".to_string(), 1)]);
+
+        let filter = ArticleFilter {
+            tag: Some("compilers".to_string()),
+            ..Default::default()
+        };
+        let tagged = repo.list_articles(&filter, 10, None).await.unwrap();
+        assert_eq!(tagged.len(), 1);
+        assert_eq!(tagged[0].tags, vec!["compilers", "rust"]);
+
+        // Tags set later (e.g. by the LLM) aren't overwritten by a refetch
+        repo.set_article_tags(first_id, vec!["llvm".to_string()]).await.unwrap();
+        repo.upsert_article(first).await.unwrap();
+        let article = repo.get_article(first_id).await.unwrap().unwrap();
+        assert_eq!(article.tags, vec!["llvm"]);
+    }
+
+    #[tokio::test]
+    async fn test_merge_and_delete_tags() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://example.com/feed").await;
+        let mut first = new_article(feed_id, "1", None);
+        first.tags = vec!["ml".to_string(), "ai".to_string()];
+        let first_id = repo.upsert_article(first).await.unwrap();
+        let mut second = new_article(feed_id, "2", None);
+        second.tags = vec!["a.i.".to_string(), "misc".to_string()];
+        let second_id = repo.upsert_article(second).await.unwrap();
+
+        // Merging into a tag an article already has doesn't duplicate it
+        let changed = repo
+            .merge_tags(vec!["ml".to_string(), "a.i.".to_string(), "ai".to_string()], "ai".to_string())
+            .await
+            .unwrap();
+        assert_eq!(changed, 2);
+        let counts = repo.tag_counts(None, 10).await.unwrap();
+        assert_eq!(counts, vec![("ai".to_string(), 2), ("misc".to_string(), 1)]);
+
+        assert_eq!(repo.delete_tags(vec!["misc".to_string(), "none".to_string()]).await.unwrap(), 1);
+        assert_eq!(repo.get_article(first_id).await.unwrap().unwrap().tags, vec!["ai"]);
+        assert_eq!(repo.get_article(second_id).await.unwrap().unwrap().tags, vec!["ai"]);
+    }
+
+    #[tokio::test]
+    async fn test_tag_rules_tag_new_articles_and_count_hits() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://example.com/feed").await;
+        repo.bulk_update_feeds(
+            vec![feed_id],
+            FeedBulkUpdate {
+                folder: Some(Some("Security".to_string())),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        let old = repo.upsert_article(new_article(feed_id, "CVE-2020-1", None)).await.unwrap();
+        let since = Utc::now() - chrono::Duration::seconds(1);
+        // Only articles fetched after `since` are new
+        repo.conn
+            .call(move |conn| Ok(conn.execute("UPDATE articles SET fetched_at = '2020-01-01 00:00:00'", [])?))
+            .await
+            .unwrap();
+        let cve = repo.upsert_article(new_article(feed_id, "CVE-2026-1234", None)).await.unwrap();
+        let other = repo.upsert_article(new_article(feed_id, "patch notes", None)).await.unwrap();
+
+        let vuln = repo.add_tag_rule(r#"folder:security re:"CVE-\d+""#.to_string(), "vuln".to_string()).await.unwrap();
+        let off = repo.add_tag_rule("folder:security".to_string(), "sec".to_string()).await.unwrap();
+        repo.set_tag_rule_enabled(off, false).await.unwrap();
+        assert_eq!(repo.apply_tag_rules(since).await.unwrap(), 1);
+        assert_eq!(repo.get_article(cve).await.unwrap().unwrap().tags, vec!["vuln"]);
+        assert!(repo.get_article(other).await.unwrap().unwrap().tags.is_empty());
+        assert!(repo.get_article(old).await.unwrap().unwrap().tags.is_empty());
+
+        // Already tagged, so no second hit
+        repo.apply_tag_rules(since).await.unwrap();
+        let rules = repo.get_tag_rules().await.unwrap();
+        assert_eq!((rules[0].id, rules[0].hits, rules[0].last_hit_at.is_some()), (vuln, 1, true));
+        assert_eq!((rules[1].enabled, rules[1].hits), (false, 0));
+
+        // Retagging (e.g. by the LLM) keeps the rule's tag
+        repo.set_article_tags(cve, vec!["security".to_string()]).await.unwrap();
+        assert_eq!(repo.get_article(cve).await.unwrap().unwrap().tags, vec!["security", "vuln"]);
+        assert!(repo.delete_tag_rule(vuln).await.unwrap());
+        assert!(!repo.delete_tag_rule(vuln).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_recent_entities_and_entity_filter() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://example.com/feed").await;
+        let nvidia = Entity {
+            name: "Nvidia".to_string(),
+            kind: EntityKind::Organization,
+        };
+        let recent = (Utc::now() - chrono::Duration::days(1)).to_rfc3339();
+        for guid in ["1", "2"] {
+            let mut article = new_article(feed_id, guid, Some(&recent));
+            article.entities = vec![nvidia.clone()];
+            repo.upsert_article(article).await.unwrap();
+        }
+        let mut old = new_article(feed_id, "old", Some("2020-01-01T00:00:00+00:00"));
+        let intel = Entity {
+            name: "Intel".to_string(),
+            kind: EntityKind::Organization,
+        };
+        old.entities = vec![nvidia.clone(), intel];
+        repo.upsert_article(old).await.unwrap();
+
+        let since = Utc::now() - chrono::Duration::days(7);
+        let counts = repo.recent_entities(None, since, None, 10).await.unwrap();
+        assert_eq!(counts.len(), 1);
+        assert_eq!((counts[0].name.as_str(), counts[0].articles), ("Nvidia", 2));
+        let tickers = repo.recent_entities(None, since, Some(EntityKind::Ticker), 10).await.unwrap();
+        assert!(tickers.is_empty());
+
+        let filter = ArticleFilter {
+            entity: Some("nvidia".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(repo.list_articles(&filter, 10, None).await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_filter_by_feed_group_and_date() {
+        let (repo, _dir) = test_repo().await;
+        let first = insert_test_feed(&repo, "https://example.com/feed").await;
+        let second = insert_test_feed(&repo, "https://other.example/feed").await;
+        let third = insert_test_feed(&repo, "https://third.example/feed").await;
+        let now = Utc::now();
+        let an_hour_ago = (now - chrono::Duration::hours(1)).with_timezone(&chrono::FixedOffset::east_opt(-5 * 3600).unwrap());
+        repo.upsert_article(new_article(first, "new", Some(&an_hour_ago.to_rfc3339()))).await.unwrap();
+        repo.upsert_article(new_article(second, "old", Some("2020-01-01T00:00:00+00:00"))).await.unwrap();
+        repo.upsert_article(new_article(third, "other", Some(&now.to_rfc3339()))).await.unwrap();
+
+        let group = ArticleFilter {
+            feed_ids: Some(vec![first, second]),
+            ..Default::default()
+        };
+        assert_eq!(repo.list_articles(&group, 10, None).await.unwrap().len(), 2);
+
+        let today = ArticleFilter {
+            since: Some(now - chrono::Duration::hours(2)),
+            ..group
+        };
+        let articles = repo.list_articles(&today, 10, None).await.unwrap();
+        assert_eq!(articles.iter().map(|a| a.guid.as_str()).collect::<Vec<_>>(), ["new"]);
+    }
+
+    #[tokio::test]
+    async fn test_recent_authors_across_feeds_and_author_filter() {
+        let (repo, _dir) = test_repo().await;
+        let first = insert_test_feed(&repo, "https://example.com/feed").await;
+        let second = insert_test_feed(&repo, "https://other.example/feed").await;
+        let recent = (Utc::now() - chrono::Duration::days(1)).to_rfc3339();
+        for (feed_id, guid, authors) in [
+            (first, "1", vec!["Jane Doe", "John Roe"]),
+            (second, "2", vec!["jane doe"]),
+            (second, "3", vec!["John Roe"]),
+        ] {
+            let mut article = new_article(feed_id, guid, Some(&recent));
+            article.authors = authors.into_iter().map(String::from).collect();
+            repo.upsert_article(article).await.unwrap();
+        }
+
+        let since = Utc::now() - chrono::Duration::days(7);
+        let counts = repo.recent_authors(None, since, 10).await.unwrap();
+        assert_eq!(counts.len(), 2);
+        assert_eq!((counts[0].name.as_str(), counts[0].articles, counts[0].feeds), ("Jane Doe", 2, 2));
+
+        let filter = ArticleFilter {
+            author: Some("JANE DOE".to_string()),
+            ..Default::default()
+        };
+        let articles = repo.list_articles(&filter, 10, None).await.unwrap();
+        assert_eq!(articles.len(), 2);
+
+        // A re-fetch with a corrected byline replaces the old authors
+        let mut fixed = new_article(first, "1", Some(&recent));
+        fixed.authors = vec!["John Roe".to_string()];
+        repo.upsert_article(fixed).await.unwrap();
+        assert_eq!(repo.list_articles(&filter, 10, None).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_followed_authors_marked_and_muted_hidden() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://example.com/feed").await;
+        let since = Utc::now() - chrono::Duration::minutes(1);
+        for (guid, author) in [("1", "Jane Doe"), ("2", "Spam Bot"), ("3", "John Roe")] {
+            let mut article = new_article(feed_id, guid, None);
+            article.authors = vec![author.to_string()];
+            repo.upsert_article(article).await.unwrap();
+        }
+        repo.set_author_rule("jane doe", Some(AuthorRule::Follow)).await.unwrap();
+        repo.set_author_rule("Spam Bot", Some(AuthorRule::Mute)).await.unwrap();
+
+        let articles = repo.list_articles(&ArticleFilter::default(), 10, None).await.unwrap();
+        let titles: Vec<(&str, bool)> = articles.iter().map(|a| (a.title.as_str(), a.by_followed_author)).collect();
+        assert_eq!(titles.len(), 2);
+        assert!(titles.contains(&("Article 1", true)));
+        assert!(titles.contains(&("Article 3", false)));
+        // Still reachable by name
+        let filter = ArticleFilter {
+            author: Some("Spam Bot".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(repo.list_articles(&filter, 10, None).await.unwrap().len(), 1);
+
+        let news = repo.new_from_followed_authors(since).await.unwrap();
+        assert_eq!(news, [("Jane Doe".to_string(), "Article 1".to_string())]);
+        assert!(repo.new_from_followed_authors(Utc::now() + chrono::Duration::minutes(1)).await.unwrap().is_empty());
+
+        repo.set_author_rule("Spam Bot", None).await.unwrap();
+        assert_eq!(repo.get_author_rules().await.unwrap(), [("jane doe".to_string(), AuthorRule::Follow)]);
+    }
+
+    #[tokio::test]
+    async fn test_search_query_filters_articles() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://theverge.example/feed").await;
+        for (guid, published, tags) in [
+            ("ai-1", "2025-02-01T00:00:00+00:00", vec!["ai"]),
+            ("rumor-2", "2025-02-02T00:00:00+00:00", vec!["ai"]),
+            ("old-3", "2024-06-01T00:00:00+00:00", vec!["ai"]),
+            ("chips-4", "2025-03-01T00:00:00+00:00", vec!["chips"]),
+        ] {
+            let mut article = new_article(feed_id, guid, Some(published));
+            article.tags = tags.into_iter().map(String::from).collect();
+            repo.upsert_article(article).await.unwrap();
+        }
+
+        let search = |q: &str| {
+            let filter = ArticleFilter {
+                query: Some(Query::parse(q).unwrap()),
+                ..Default::default()
+            };
+            let repo = repo.clone();
+            async move {
+                let mut titles: Vec<String> = repo
+                    .list_articles(&filter, 10, None)
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .map(|a| a.title)
+                    .collect();
+                titles.sort();
+                titles
+            }
+        };
+        assert_eq!(search("feed:verge tag:ai -title:rumor after:2025-01-01").await, ["Article ai-1"]);
+        assert_eq!(search("tag:chips OR title:old").await, ["Article chips-4", "Article old-3"]);
+        assert!(search("has:discussion is:starred").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_regex_search_over_title_and_text() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://example.com/feed").await;
+        for (guid, text) in [("cve", "Patched CVE-2024-3094 in xz"), ("version", "Rust 1.80.0 is out"), ("none", "Nothing")] {
+            let mut article = new_article(feed_id, guid, None);
+            article.content_text = Some(text.to_string());
+            repo.upsert_article(article).await.unwrap();
+        }
+        let search = |q: &str| ArticleFilter {
+            query: Some(Query::parse(q).unwrap()),
+            ..Default::default()
+        };
+
+        let found = repo.list_articles(&search(r"re:CVE-\d{4}-\d+"), 10, None).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].title, "Article cve");
+        let found = repo.list_articles(&search(r#"re:"\b1\.\d+\.\d+\b" OR re:^Article\snone$"#), 10, None).await.unwrap();
+        assert_eq!(found.len(), 2);
+        assert!(repo.list_articles(&search("-re:Article"), 10, None).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_watchlist_coverage_outlives_articles() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://example.com/feed").await;
+        let mut article = new_article(feed_id, "1", Some("2026-03-02T09:00:00+00:00"));
+        article.entities = vec![Entity {
+            name: "Nvidia".to_string(),
+            kind: EntityKind::Organization,
+        }];
+        let id = repo.upsert_article(article).await.unwrap();
+
+        // Adding counts what's already stored
+        assert_eq!(repo.add_to_watchlist("chips", vec!["NVIDIA".to_string()]).await.unwrap(), 1);
+        assert_eq!(repo.add_to_watchlist("chips", vec!["nvidia".to_string()]).await.unwrap(), 0);
+        let since = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        let day = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        let expected = vec![("NVIDIA".to_string(), day, 1)];
+        assert_eq!(repo.get_entity_coverage(since).await.unwrap(), expected);
+
+        // Recounting after the article is gone keeps the day's count
+        repo.delete_article(id).await.unwrap();
+        repo.update_entity_coverage(since).await.unwrap();
+        assert_eq!(repo.get_entity_coverage(since).await.unwrap(), expected);
+
+        assert_eq!(repo.remove_from_watchlist("chips", Vec::new()).await.unwrap(), 1);
+        assert!(repo.get_entity_coverage(since).await.unwrap().is_empty());
+    }
+
+    // ==================== Summaries ====================
+
+    #[tokio::test]
+    async fn test_summary_found_by_content_hash() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://example.com/feed").await;
+        let first = repo.upsert_article(new_article(feed_id, "1", None)).await.unwrap();
+        let duplicate = repo.upsert_article(new_article(feed_id, "2", None)).await.unwrap();
+        let unsummarized = ArticleFilter {
+            unsummarized: true,
+            ..Default::default()
+        };
+
+        repo.save_summary(first, "• Stocks rose".to_string(), "model".to_string(), Some("openai".to_string()), Some("abc".to_string()))
+            .await
+            .unwrap();
+        let pending = repo.list_articles(&unsummarized, 10, None).await.unwrap();
+        assert_eq!(pending.iter().map(|a| a.id).collect::<Vec<_>>(), [duplicate]);
+        let found = repo.find_summary_by_hash("abc".to_string()).await.unwrap().unwrap();
+        assert_eq!(found.article_id, first);
+        assert_eq!(found.content, "• Stocks rose");
+        assert!(repo.find_summary_by_hash("def".to_string()).await.unwrap().is_none());
+
+        // Copied to the duplicate, which then has its own cached summary
+        assert_eq!(found.provider.as_deref(), Some("openai"));
+        repo.save_summary(duplicate, found.content, found.model_version, found.provider, Some("abc".to_string()))
+            .await
+            .unwrap();
+        assert!(repo.get_summary(duplicate).await.unwrap().is_some());
+        repo.delete_article(first).await.unwrap();
+        assert!(repo.find_summary_by_hash("abc".to_string()).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_summary_failures_back_off_and_clear_on_success() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://example.com/feed").await;
+        let flaky = repo.upsert_article(new_article(feed_id, "flaky", None)).await.unwrap();
+        let refused = repo.upsert_article(new_article(feed_id, "refused", None)).await.unwrap();
+
+        repo.record_summary_failure(flaky, "overloaded".to_string(), true).await.unwrap();
+        repo.record_summary_failure(refused, "invalid api key".to_string(), false).await.unwrap();
+        let failures = repo.get_summary_failures().await.unwrap();
+        assert_eq!(failures.len(), 2);
+        let first = failures.iter().find(|f| f.article_id == flaky).unwrap();
+        assert_eq!((first.attempts, first.title.as_str()), (1, "Article flaky"));
+        assert!(failures.iter().find(|f| f.article_id == refused).unwrap().next_retry_at.is_none());
+
+        // Not due yet; the second wait is longer than the first
+        assert!(repo.due_summary_retries(Utc::now()).await.unwrap().is_empty());
+        let later = Utc::now() + chrono::Duration::hours(1);
+        assert_eq!(repo.due_summary_retries(later).await.unwrap(), [flaky]);
+        repo.record_summary_failure(flaky, "overloaded".to_string(), true).await.unwrap();
+        let failures = repo.get_summary_failures().await.unwrap();
+        let second = failures.iter().find(|f| f.article_id == flaky).unwrap();
+        assert_eq!(second.attempts, 2);
+        assert!(second.next_retry_at.unwrap() - second.failed_at > chrono::Duration::minutes(15));
+
+        // Retries run out
+        for _ in 0..SUMMARY_RETRY_LIMIT {
+            repo.record_summary_failure(flaky, "overloaded".to_string(), true).await.unwrap();
+        }
+        let far = Utc::now() + chrono::Duration::days(365);
+        assert!(repo.due_summary_retries(far).await.unwrap().is_empty());
+
+        repo.save_summary(flaky, "• Done".to_string(), "model".to_string(), None, None).await.unwrap();
+        repo.delete_article(refused).await.unwrap();
+        assert!(repo.get_summary_failures().await.unwrap().is_empty());
+    }
+
+    // ==================== Journal ====================
+
+    #[tokio::test]
+    async fn test_journal_tracks_pending_items_per_pipeline() {
+        let (repo, _dir) = test_repo().await;
+        repo.journal_add(Pipeline::Refresh, vec![3, 1, 2]).await.unwrap();
+        repo.journal_add(Pipeline::Summary, vec![1, 1]).await.unwrap();
+        repo.journal_done(Pipeline::Refresh, 2).await.unwrap();
+        assert_eq!(repo.journal_pending(Pipeline::Refresh).await.unwrap(), vec![1, 3]);
+        assert_eq!(repo.journal_pending(Pipeline::Summary).await.unwrap(), vec![1]);
+
+        repo.journal_clear(Pipeline::Refresh).await.unwrap();
+        assert!(repo.journal_pending(Pipeline::Refresh).await.unwrap().is_empty());
+        assert_eq!(repo.journal_pending(Pipeline::Summary).await.unwrap(), vec![1]);
+    }
+
+    // ==================== Unread counts ====================
+
+    #[tokio::test]
+    async fn test_unread_counts_follow_ingest_reads_and_moves() {
+        let (repo, _dir) = test_repo().await;
+        let a = insert_test_feed(&repo, "https://a.example/feed").await;
+        let b = insert_test_feed(&repo, "https://b.example/feed").await;
+        let first = repo.upsert_article(new_article(a, "1", None)).await.unwrap();
+        let second = repo.upsert_article(new_article(a, "2", None)).await.unwrap();
+        repo.upsert_article(new_article(b, "1", None)).await.unwrap();
+        // Re-ingesting an article doesn't count it twice
+        repo.upsert_article(new_article(a, "2", None)).await.unwrap();
+        assert_eq!(repo.feed_unread_count(a).await.unwrap(), 2);
+
+        repo.set_read(first, true).await.unwrap();
+        repo.set_read(first, true).await.unwrap();
+        assert_eq!(repo.feed_unread_count(a).await.unwrap(), 1);
+        repo.delete_article(second).await.unwrap();
+        assert_eq!(repo.feed_unread_count(a).await.unwrap(), 0);
+        repo.set_read(first, false).await.unwrap();
+
+        // Starred articles keep counting in the orphanage
+        repo.set_starred(first, true).await.unwrap();
+        repo.remove_feed(a, FeedRemoval::KeepStarred).await.unwrap();
+        let feeds = repo.get_all_feeds().await.unwrap();
+        let counts: Vec<(bool, u32)> = feeds.iter().map(|f| (f.is_orphanage(), f.unread)).collect();
+        assert!(counts.contains(&(true, 1)) && counts.contains(&(false, 1)));
+    }
+
+    #[tokio::test]
+    async fn test_update_articles_in_batch() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://a.example/feed").await;
+        let mut ids = Vec::new();
+        for guid in ["1", "2", "3"] {
+            ids.push(repo.upsert_article(new_article(feed_id, guid, None)).await.unwrap());
+        }
+        let first_two = ids[..2].to_vec();
+
+        assert_eq!(repo.update_articles(first_two.clone(), ArticleBatch::Read(true)).await.unwrap(), 2);
+        assert_eq!(repo.feed_unread_count(feed_id).await.unwrap(), 1);
+        repo.update_articles(first_two.clone(), ArticleBatch::Starred(true)).await.unwrap();
+        assert_eq!(repo.get_starred_articles().await.unwrap().len(), 2);
+        let tagged = repo.update_articles(vec![ids[0], 999], ArticleBatch::Tag("later".to_string())).await.unwrap();
+        assert_eq!(tagged, 1);
+        assert_eq!(repo.get_article(ids[0]).await.unwrap().unwrap().tags, ["later"]);
+
+        assert_eq!(repo.update_articles(first_two, ArticleBatch::Delete).await.unwrap(), 2);
+        assert_eq!(repo.count_articles().await.unwrap(), 1);
+        // Deleted articles stay gone when the feed is fetched again
+        assert_eq!(repo.upsert_article(new_article(feed_id, "1", None)).await.unwrap(), 0);
+    }
+
+    // ==================== Settings ====================
+
+    #[tokio::test]
+    async fn test_settings_overwrite() {
+        let (repo, _dir) = test_repo().await;
+        assert_eq!(repo.get_setting("panes").await.unwrap(), None);
+        repo.set_setting("panes", "a".to_string()).await.unwrap();
+        repo.set_setting("panes", "b".to_string()).await.unwrap();
+        assert_eq!(repo.get_setting("panes").await.unwrap().as_deref(), Some("b"));
+    }
+
+    // ==================== Annotations ====================
+
+    #[tokio::test]
+    async fn test_annotations_keep_articles_past_retention() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://example.com/feed").await;
+        let old = "2020-01-01T00:00:00+00:00";
+        let noted = repo.upsert_article(new_article(feed_id, "noted", Some(old))).await.unwrap();
+        let plain = repo.upsert_article(new_article(feed_id, "plain", Some(old))).await.unwrap();
+
+        repo.set_annotation(noted, "Follow up with the source\n\n".to_string()).await.unwrap();
+        repo.set_annotation(plain, "draft".to_string()).await.unwrap();
+        repo.set_annotation(plain, "  ".to_string()).await.unwrap();
+        assert_eq!(repo.get_annotation(noted).await.unwrap().as_deref(), Some("Follow up with the source"));
+        assert_eq!(repo.get_annotation(plain).await.unwrap(), None);
+
+        assert_eq!(repo.delete_old_articles(7).await.unwrap(), 1);
+        assert!(repo.get_article(noted).await.unwrap().is_some());
+    }
+
+    // ==================== Discussions ====================
+
+    #[tokio::test]
+    async fn test_discussions_rank_articles_and_mark_them_checked() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://example.com/feed").await;
+        let now = Utc::now().to_rfc3339();
+        let quiet = repo.upsert_article(new_article(feed_id, "quiet", Some(&now))).await.unwrap();
+        let busy = repo.upsert_article(new_article(feed_id, "busy", Some(&now))).await.unwrap();
+        repo.upsert_article(new_article(feed_id, "old", Some("2020-01-01T00:00:00+00:00")))
+            .await
+            .unwrap();
+
+        let due = repo.articles_needing_discussion_lookup(48, 6, 10).await.unwrap();
+        assert_eq!(due.len(), 2);
+
+        let thread = |site: &str, points, comments| Discussion {
+            site: site.to_string(),
+            url: format!("https://{}.example/1", site),
+            points,
+            comments,
+        };
+        repo.save_discussions(quiet, vec![thread("hn", 5, 1)]).await.unwrap();
+        repo.save_discussions(busy, vec![thread("hn", 10, 2), thread("lobsters", 40, 30)])
+            .await
+            .unwrap();
+
+        let discussed = repo.get_discussed_articles(10).await.unwrap();
+        let ids: Vec<i64> = discussed.iter().map(|a| a.id).collect();
+        assert_eq!(ids, vec![busy, quiet]);
+        // The best thread represents the article
+        assert_eq!(discussed[0].discussion.as_ref().unwrap().site, "lobsters");
+
+        assert!(repo.articles_needing_discussion_lookup(48, 6, 10).await.unwrap().is_empty());
+    }
+
+    // ==================== Podcasts ====================
+
+    #[tokio::test]
+    async fn test_playback_position_and_played_flag() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://example.com/feed").await;
+        let mut episode = new_article(feed_id, "ep1", None);
+        episode.enclosure = Some(Enclosure {
+            url: "https://cdn.example/ep1.mp3".to_string(),
+            mime_type: Some("audio/mpeg".to_string()),
+            duration_secs: Some(1800),
+        });
+        let id = repo.upsert_article(episode).await.unwrap();
+
+        repo.save_playback(id, 600, false).await.unwrap();
+        let article = repo.get_article(id).await.unwrap().unwrap();
+        assert_eq!(article.enclosure.unwrap().duration_secs, Some(1800));
+        assert_eq!((article.playback_secs, article.is_played), (Some(600), false));
+
+        // Finishing resets the position; a later partial replay keeps it played
+        repo.save_playback(id, 1790, true).await.unwrap();
+        repo.save_playback(id, 30, false).await.unwrap();
+        let article = repo.get_article(id).await.unwrap().unwrap();
+        assert_eq!((article.playback_secs, article.is_played), (Some(30), true));
+    }
+
+    // ==================== Starring & pinning ====================
+
+    #[tokio::test]
+    async fn test_pinned_articles_lead_starred_list_in_manual_order() {
+        let (repo, _dir) = test_repo().await;
+        let feed_id = insert_test_feed(&repo, "https://example.com/feed").await;
+
+        let mut ids = Vec::new();
+        for day in 1..=4 {
+            let date = format!("2026-01-0{}T12:00:00+00:00", day);
+            ids.push(
+                repo.upsert_article(new_article(feed_id, &day.to_string(), Some(&date)))
+                    .await
+                    .unwrap(),
+            );
+        }
+
+        repo.set_starred(ids[3], true).await.unwrap();
+        repo.set_pinned(ids[0], true).await.unwrap();
+        repo.set_pinned(ids[1], true).await.unwrap();
+
+        let guids = |articles: Vec<Article>| articles.into_iter().map(|a| a.guid).collect::<Vec<_>>();
+        assert_eq!(guids(repo.get_starred_articles().await.unwrap()), vec!["1", "2", "4"]);
+
+        // Move the second pin above the first; the top pin can't move further up
+        assert!(repo.move_pinned(ids[1], true).await.unwrap());
+        assert!(!repo.move_pinned(ids[1], true).await.unwrap());
+        assert_eq!(guids(repo.get_starred_articles().await.unwrap()), vec!["2", "1", "4"]);
+
+        // Unstarring drops the pin as well
+        repo.set_starred(ids[1], false).await.unwrap();
+        assert_eq!(guids(repo.get_starred_articles().await.unwrap()), vec!["1", "4"]);
+    }
+
+    // ==================== Users ====================
+
+    #[tokio::test]
+    async fn test_user_sees_only_subscribed_feeds_and_own_state() {
+        let (repo, _dir) = test_repo().await;
+        let shared = insert_test_feed(&repo, "https://example.com/shared").await;
+        let article = repo.upsert_article(new_article(shared, "s1", None)).await.unwrap();
+
+        let alice = repo.create_user("alice", "hash").await.unwrap();
+        let bob = repo.create_user("bob", "hash").await.unwrap();
+
+        // Feeds added after the accounts exist need an explicit subscription
+        let private = insert_test_feed(&repo, "https://example.com/private").await;
+        repo.upsert_article(new_article(private, "p1", None)).await.unwrap();
+        repo.subscribe_user(alice, private).await.unwrap();
+
+        let scoped = |user_id| ArticleFilter {
+            user_id: Some(user_id),
+            ..Default::default()
+        };
+        assert_eq!(repo.list_articles(&scoped(alice), 10, None).await.unwrap().len(), 2);
+        assert_eq!(repo.list_articles(&scoped(bob), 10, None).await.unwrap().len(), 1);
+        assert_eq!(repo.get_user_feeds(bob).await.unwrap().len(), 1);
+
+        // Read/star state is per user and separate from the local flags
+        repo.set_user_read(alice, article, true).await.unwrap();
+        repo.set_user_starred(alice, article, true).await.unwrap();
+
+        let alice_view = repo.get_article_for_user(article, alice).await.unwrap().unwrap();
+        let bob_view = repo.get_article_for_user(article, bob).await.unwrap().unwrap();
+        assert!(alice_view.is_read && alice_view.is_starred);
+        assert!(!bob_view.is_read && !bob_view.is_starred);
+        assert!(!repo.get_article(article).await.unwrap().unwrap().is_read);
+
+        let bob_unread = ArticleFilter {
+            unread_only: true,
+            ..scoped(bob)
+        };
+        assert_eq!(repo.list_articles(&bob_unread, 10, None).await.unwrap().len(), 1);
+
+        assert!(repo.unsubscribe_user(bob, shared).await.unwrap());
+        assert!(repo.get_article_for_user(article, bob).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_audit_log_records_removals_bulk_changes_and_pushes() {
+        let (repo, _dir) = test_repo().await;
+        let kept = insert_test_feed(&repo, "https://example.com/feed").await;
+        let gone = insert_test_feed(&repo, "https://gone.example/feed").await;
+        let mut ids = Vec::new();
+        for guid in ["a", "b"] {
+            ids.push(repo.upsert_article(new_article(kept, guid, None)).await.unwrap());
+        }
+        repo.update_articles(ids.clone(), ArticleBatch::Read(true)).await.unwrap();
+        // One article isn't a bulk change
+        repo.update_articles(vec![ids[0]], ArticleBatch::Read(false)).await.unwrap();
+        repo.mark_saved_to_raindrop(ids[1], 77, vec!["chips".to_string()]).await.unwrap();
+        repo.remove_feed(gone, FeedRemoval::Archive).await.unwrap();
+        repo.record_audit("import.opml", Some("subs.opml".to_string()), None).await.unwrap();
+
+        let all = repo.get_audit_log(AuditFilter::default(), 50).await.unwrap();
+        let actions: Vec<_> = all.iter().map(|e| e.action.as_str()).collect();
+        assert_eq!(actions, ["import.opml", "feed.archive", "push.raindrop", "articles.read", "feed.add", "feed.add"]);
+        assert_eq!(all[1].subject.as_deref(), Some("https://gone.example/feed"));
+        assert_eq!(all[1].detail.as_deref(), Some("Test Feed: archived"));
+        assert_eq!(all[2].subject.as_deref(), Some("https://example.com/b"));
+        assert_eq!(all[2].detail.as_deref(), Some("raindrop 77, tags chips"));
+
+        let feeds = AuditFilter { action: Some("feed".to_string()), ..Default::default() };
+        assert_eq!(repo.get_audit_log(feeds, 50).await.unwrap().len(), 3);
+        let gone_feed = AuditFilter { text: Some("gone.example".to_string()), ..Default::default() };
+        assert_eq!(repo.get_audit_log(gone_feed, 50).await.unwrap().len(), 2);
+        // "feed" doesn't match "feeds.something" or a bare prefix of a word
+        let partial = AuditFilter { action: Some("fee".to_string()), ..Default::default() };
+        assert!(repo.get_audit_log(partial, 50).await.unwrap().is_empty());
+        assert_eq!(repo.get_audit_log(AuditFilter::default(), 2).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_api_tokens_carry_scope_and_revoke() {
+        let (repo, _dir) = test_repo().await;
+        let alice = repo.create_user("alice", "hash").await.unwrap();
+        let id = repo.add_api_token(alice, "dashboard", "read-hash", TokenScope::Read).await.unwrap();
+        repo.add_api_token(alice, "sync", "write-hash", TokenScope::ReadWrite).await.unwrap();
+        assert_eq!(repo.get_usernames().await.unwrap(), ["alice"]);
+
+        let (user, scope) = repo.get_user_by_api_token("read-hash").await.unwrap().unwrap();
+        assert_eq!((user.username.as_str(), scope), ("alice", TokenScope::Read));
+        assert!(repo.get_user_by_api_token("other").await.unwrap().is_none());
+
+        let tokens = repo.list_api_tokens().await.unwrap();
+        let names: Vec<_> = tokens.iter().map(|t| (t.name.as_str(), t.last_used_at.is_some())).collect();
+        assert_eq!(names, [("dashboard", true), ("sync", false)]);
+
+        assert!(repo.delete_api_token(id).await.unwrap());
+        assert!(!repo.delete_api_token(id).await.unwrap());
+        assert!(repo.get_user_by_api_token("read-hash").await.unwrap().is_none());
     }
 }
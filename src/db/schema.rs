@@ -11,8 +11,6 @@ CREATE TABLE IF NOT EXISTS feeds (
     updated_at TEXT NOT NULL DEFAULT (datetime('now'))
 );
 
-CREATE INDEX IF NOT EXISTS idx_feeds_url ON feeds(url);
-
 -- articles table
 CREATE TABLE IF NOT EXISTS articles (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -30,9 +28,7 @@ CREATE TABLE IF NOT EXISTS articles (
     UNIQUE(feed_id, guid)
 );
 
-CREATE INDEX IF NOT EXISTS idx_articles_feed_id ON articles(feed_id);
 CREATE INDEX IF NOT EXISTS idx_articles_published_at ON articles(published_at DESC);
-CREATE INDEX IF NOT EXISTS idx_articles_is_read ON articles(is_read);
 
 -- summaries table
 CREATE TABLE IF NOT EXISTS summaries (
@@ -62,6 +58,451 @@ CREATE TABLE IF NOT EXISTS deleted_articles (
     deleted_at TEXT NOT NULL DEFAULT (datetime('now')),
     UNIQUE(feed_id, guid)
 );
-
-CREATE INDEX IF NOT EXISTS idx_deleted_articles_feed_guid ON deleted_articles(feed_id, guid);
 "#;
+
+/// Incremental schema changes applied on top of `SCHEMA`, oldest first.
+/// Entry N brings the database to `PRAGMA user_version` N + 1; never edit or
+/// reorder an entry once released, only append new ones.
+pub const MIGRATIONS: &[&str] = &[
+    // 1: pinned starred articles with a manual sort order
+    r#"
+    ALTER TABLE articles ADD COLUMN is_pinned INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE articles ADD COLUMN pin_order INTEGER;
+    CREATE INDEX IF NOT EXISTS idx_articles_is_starred ON articles(is_starred);
+    "#,
+    // 2: read-later queue and estimated reading time
+    r#"
+    ALTER TABLE articles ADD COLUMN reading_minutes INTEGER;
+    ALTER TABLE articles ADD COLUMN queued_at TEXT;
+    CREATE INDEX IF NOT EXISTS idx_articles_queued_at ON articles(queued_at);
+    "#,
+    // 3: server accounts with their own subscriptions and read/star state
+    r#"
+    CREATE TABLE IF NOT EXISTS users (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        username TEXT NOT NULL UNIQUE,
+        password_hash TEXT NOT NULL,
+        token_hash TEXT UNIQUE,
+        created_at TEXT NOT NULL DEFAULT (datetime('now'))
+    );
+    CREATE TABLE IF NOT EXISTS user_feeds (
+        user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+        feed_id INTEGER NOT NULL REFERENCES feeds(id) ON DELETE CASCADE,
+        PRIMARY KEY (user_id, feed_id)
+    );
+    CREATE TABLE IF NOT EXISTS user_article_state (
+        user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+        article_id INTEGER NOT NULL REFERENCES articles(id) ON DELETE CASCADE,
+        is_read INTEGER NOT NULL DEFAULT 0,
+        is_starred INTEGER NOT NULL DEFAULT 0,
+        PRIMARY KEY (user_id, article_id)
+    );
+    CREATE INDEX IF NOT EXISTS idx_user_feeds_feed_id ON user_feeds(feed_id);
+    CREATE INDEX IF NOT EXISTS idx_user_article_state_article_id ON user_article_state(article_id);
+    "#,
+    // 4: back off from hosts that answer 429/503 with Retry-After
+    r#"
+    ALTER TABLE feeds ADD COLUMN retry_after TEXT;
+    ALTER TABLE feeds ADD COLUMN throttled_count INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE feeds ADD COLUMN last_throttled_at TEXT;
+    "#,
+    // 5: full article text scraped from the site (all pages stitched together)
+    r#"
+    ALTER TABLE articles ADD COLUMN full_text TEXT;
+    "#,
+    // 6: per-post comment feeds
+    r#"
+    ALTER TABLE articles ADD COLUMN comments_url TEXT;
+    "#,
+    // 7: Hacker News / Lobsters threads about articles
+    r#"
+    CREATE TABLE IF NOT EXISTS discussions (
+        article_id INTEGER NOT NULL REFERENCES articles(id) ON DELETE CASCADE,
+        site TEXT NOT NULL,
+        url TEXT NOT NULL,
+        points INTEGER NOT NULL DEFAULT 0,
+        comments INTEGER NOT NULL DEFAULT 0,
+        PRIMARY KEY (article_id, site)
+    );
+    ALTER TABLE articles ADD COLUMN discussions_checked_at TEXT;
+    "#,
+    // 8: podcast enclosures and per-episode playback position
+    r#"
+    ALTER TABLE articles ADD COLUMN enclosure_url TEXT;
+    ALTER TABLE articles ADD COLUMN enclosure_type TEXT;
+    ALTER TABLE articles ADD COLUMN enclosure_duration INTEGER;
+    CREATE TABLE IF NOT EXISTS playback (
+        article_id INTEGER PRIMARY KEY REFERENCES articles(id) ON DELETE CASCADE,
+        position_secs INTEGER NOT NULL DEFAULT 0,
+        is_played INTEGER NOT NULL DEFAULT 0,
+        updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+    );
+    "#,
+    // 9: feed extension data (dc:*, itunes:*, geo, custom namespaces) as JSON
+    r#"
+    ALTER TABLE articles ADD COLUMN extras TEXT;
+    "#,
+    // 10: local snapshot of starred articles
+    r#"
+    ALTER TABLE articles ADD COLUMN archive_path TEXT;
+    "#,
+    // 11: user-chosen feed names, kept apart from the feed's own title
+    r#"
+    ALTER TABLE feeds ADD COLUMN custom_title TEXT;
+    "#,
+    // 12: feed folders, tags, per-feed refresh interval and pausing
+    r#"
+    ALTER TABLE feeds ADD COLUMN folder TEXT;
+    ALTER TABLE feeds ADD COLUMN refresh_minutes INTEGER;
+    ALTER TABLE feeds ADD COLUMN paused INTEGER NOT NULL DEFAULT 0;
+    CREATE TABLE IF NOT EXISTS feed_tags (
+        feed_id INTEGER NOT NULL REFERENCES feeds(id) ON DELETE CASCADE,
+        tag TEXT NOT NULL,
+        PRIMARY KEY (feed_id, tag)
+    );
+    "#,
+    // 13: archived feeds, hidden everywhere but kept with their articles
+    r#"
+    ALTER TABLE feeds ADD COLUMN archived_at TEXT;
+    "#,
+    // 14: posting-frequency stats, recomputed after each fetch
+    r#"
+    ALTER TABLE feeds ADD COLUMN posts_per_day REAL;
+    ALTER TABLE feeds ADD COLUMN last_post_at TEXT;
+    "#,
+    // 15: suggested article tags
+    r#"
+    CREATE TABLE IF NOT EXISTS article_tags (
+        article_id INTEGER NOT NULL REFERENCES articles(id) ON DELETE CASCADE,
+        tag TEXT NOT NULL,
+        PRIMARY KEY (article_id, tag)
+    );
+    CREATE INDEX IF NOT EXISTS idx_article_tags_tag ON article_tags(tag);
+    "#,
+    // 16: people, organizations and tickers mentioned by each article
+    r#"
+    CREATE TABLE IF NOT EXISTS article_entities (
+        article_id INTEGER NOT NULL REFERENCES articles(id) ON DELETE CASCADE,
+        name TEXT NOT NULL,
+        kind TEXT NOT NULL,
+        PRIMARY KEY (article_id, kind, name)
+    );
+    CREATE INDEX IF NOT EXISTS idx_article_entities_name ON article_entities(name COLLATE NOCASE);
+    "#,
+    // 17: entity watchlists, and their daily coverage kept past article retention
+    r#"
+    CREATE TABLE IF NOT EXISTS watchlist_entities (
+        list TEXT NOT NULL,
+        entity TEXT NOT NULL COLLATE NOCASE,
+        PRIMARY KEY (list, entity)
+    );
+    CREATE TABLE IF NOT EXISTS entity_coverage (
+        entity TEXT NOT NULL COLLATE NOCASE,
+        day TEXT NOT NULL,
+        articles INTEGER NOT NULL,
+        PRIMARY KEY (entity, day)
+    );
+    "#,
+    // 18: summaries keyed by content hash, shared by duplicate articles
+    r#"
+    ALTER TABLE summaries ADD COLUMN content_hash TEXT;
+    CREATE INDEX IF NOT EXISTS idx_summaries_content_hash ON summaries(content_hash);
+    "#,
+    // 19: failed summaries, kept for review and retry
+    r#"
+    CREATE TABLE IF NOT EXISTS summary_failures (
+        article_id INTEGER PRIMARY KEY REFERENCES articles(id),
+        error TEXT NOT NULL,
+        attempts INTEGER NOT NULL DEFAULT 1,
+        failed_at TEXT NOT NULL,
+        next_retry_at TEXT
+    );
+    "#,
+    // 20: which provider wrote each summary, now that several can
+    r#"
+    ALTER TABLE summaries ADD COLUMN provider TEXT;
+    "#,
+    // 21: normalized byline names, for browsing a reporter across feeds
+    r#"
+    CREATE TABLE IF NOT EXISTS authors (
+        article_id INTEGER NOT NULL REFERENCES articles(id) ON DELETE CASCADE,
+        name TEXT NOT NULL COLLATE NOCASE,
+        PRIMARY KEY (article_id, name)
+    );
+    CREATE INDEX IF NOT EXISTS idx_authors_name ON authors(name);
+    "#,
+    // 22: followed and muted authors
+    r#"
+    CREATE TABLE IF NOT EXISTS author_rules (
+        name TEXT PRIMARY KEY COLLATE NOCASE,
+        rule TEXT NOT NULL
+    );
+    "#,
+    // 23: auto-tag rules, and which rule added a tag so retagging keeps it
+    r#"
+    CREATE TABLE IF NOT EXISTS tag_rules (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        query TEXT NOT NULL,
+        tag TEXT NOT NULL,
+        enabled INTEGER NOT NULL DEFAULT 1,
+        hits INTEGER NOT NULL DEFAULT 0,
+        last_hit_at TEXT
+    );
+    ALTER TABLE article_tags ADD COLUMN rule_id INTEGER;
+    "#,
+    // 24: progress of refreshes and summary batches, to resume after a crash
+    r#"
+    CREATE TABLE IF NOT EXISTS journal (
+        pipeline TEXT NOT NULL,
+        item_id INTEGER NOT NULL,
+        PRIMARY KEY (pipeline, item_id)
+    );
+    "#,
+    // 25: TUI state kept between sessions, such as the pane layout
+    r#"
+    CREATE TABLE IF NOT EXISTS settings (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+    "#,
+    // 26: unread articles per feed, kept up to date by triggers so the feed
+    // list never has to count them
+    r#"
+    ALTER TABLE feeds ADD COLUMN unread_count INTEGER NOT NULL DEFAULT 0;
+    UPDATE feeds SET unread_count =
+        (SELECT COUNT(*) FROM articles a WHERE a.feed_id = feeds.id AND a.is_read = 0);
+    CREATE TRIGGER IF NOT EXISTS articles_unread_insert AFTER INSERT ON articles
+    WHEN NEW.is_read = 0
+    BEGIN
+        UPDATE feeds SET unread_count = unread_count + 1 WHERE id = NEW.feed_id;
+    END;
+    CREATE TRIGGER IF NOT EXISTS articles_unread_delete AFTER DELETE ON articles
+    WHEN OLD.is_read = 0
+    BEGIN
+        UPDATE feeds SET unread_count = unread_count - 1 WHERE id = OLD.feed_id;
+    END;
+    CREATE TRIGGER IF NOT EXISTS articles_unread_update AFTER UPDATE OF is_read, feed_id ON articles
+    WHEN (OLD.is_read = 0) != (NEW.is_read = 0) OR OLD.feed_id != NEW.feed_id
+    BEGIN
+        UPDATE feeds SET unread_count = unread_count - (OLD.is_read = 0) WHERE id = OLD.feed_id;
+        UPDATE feeds SET unread_count = unread_count + (NEW.is_read = 0) WHERE id = NEW.feed_id;
+    END;
+    "#,
+    // 27: the reader's own notes on articles
+    r#"
+    CREATE TABLE IF NOT EXISTS annotations (
+        article_id INTEGER PRIMARY KEY,
+        note TEXT NOT NULL,
+        updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+    );
+    "#,
+    // 28: how much of each feed's articles to show; NULL shows them in full
+    r#"
+    ALTER TABLE feeds ADD COLUMN reading TEXT;
+    "#,
+    // 29: how much each feed's articles stand out; NULL is normal
+    r#"
+    ALTER TABLE feeds ADD COLUMN priority TEXT;
+    "#,
+    // 30: snoozed articles, hidden until snooze_until; once back they sort
+    // from surfaced_at
+    r#"
+    ALTER TABLE articles ADD COLUMN snooze_until TEXT;
+    ALTER TABLE articles ADD COLUMN surfaced_at TEXT;
+    "#,
+    // 31: the articles most recently opened, whatever their read flag
+    r#"
+    CREATE TABLE IF NOT EXISTS history (
+        article_id INTEGER PRIMARY KEY,
+        -- Counts up with every opening, which timestamps can't be trusted to
+        seq INTEGER NOT NULL,
+        opened_at TEXT NOT NULL DEFAULT (datetime('now'))
+    );
+    CREATE INDEX IF NOT EXISTS idx_history_seq ON history(seq);
+    "#,
+    // 32: seconds spent reading each article, by local day; kept after the
+    // article goes so the daily totals stay
+    r#"
+    CREATE TABLE IF NOT EXISTS reading_time (
+        article_id INTEGER NOT NULL,
+        day TEXT NOT NULL,
+        seconds INTEGER NOT NULL,
+        PRIMARY KEY (article_id, day)
+    );
+    CREATE INDEX IF NOT EXISTS idx_reading_time_day ON reading_time(day);
+    "#,
+    // 33: source notes on feeds: beat, contact, reliability (1-5) and notes
+    r#"
+    ALTER TABLE feeds ADD COLUMN beat TEXT;
+    ALTER TABLE feeds ADD COLUMN contact TEXT;
+    ALTER TABLE feeds ADD COLUMN reliability INTEGER;
+    ALTER TABLE feeds ADD COLUMN notes TEXT;
+    "#,
+    // 34: embargoed articles stay out of listings and announcements until
+    // embargo_until, then sort from it
+    r#"
+    ALTER TABLE articles ADD COLUMN embargo_until TEXT;
+    "#,
+    // 35: named, ordered collections of articles gathered for a piece of
+    // work; collected articles are kept past the retention window
+    r#"
+    CREATE TABLE IF NOT EXISTS collections (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+        created_at TEXT NOT NULL DEFAULT (datetime('now'))
+    );
+    CREATE TABLE IF NOT EXISTS collection_articles (
+        collection_id INTEGER NOT NULL REFERENCES collections(id) ON DELETE CASCADE,
+        article_id INTEGER NOT NULL,
+        position INTEGER NOT NULL,
+        PRIMARY KEY (collection_id, article_id)
+    );
+    CREATE INDEX IF NOT EXISTS idx_collection_articles_article ON collection_articles(article_id);
+    "#,
+    // 36: earlier versions of articles whose title or text changed on a
+    // re-fetch, and when each was replaced
+    r#"
+    CREATE TABLE IF NOT EXISTS article_revisions (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        article_id INTEGER NOT NULL,
+        title TEXT NOT NULL,
+        content_text TEXT,
+        replaced_at TEXT NOT NULL DEFAULT (datetime('now'))
+    );
+    CREATE INDEX IF NOT EXISTS idx_article_revisions_article ON article_revisions(article_id);
+    "#,
+    // 37: saved articles whose page has been taken down, and when each was
+    // last checked
+    r#"
+    ALTER TABLE articles ADD COLUMN removed_at TEXT;
+    ALTER TABLE articles ADD COLUMN removal_checked_at TEXT;
+    "#,
+    // 38: links from article content to other sites, by domain, for
+    // finding who cites what
+    r#"
+    CREATE TABLE IF NOT EXISTS article_links (
+        article_id INTEGER NOT NULL,
+        url TEXT NOT NULL,
+        domain TEXT NOT NULL,
+        page TEXT NOT NULL,
+        PRIMARY KEY (article_id, url)
+    );
+    CREATE INDEX IF NOT EXISTS idx_article_links_domain ON article_links(domain);
+    CREATE INDEX IF NOT EXISTS idx_article_links_page ON article_links(page);
+    "#,
+    // 39: feeds subscribed through the urls file, which it may archive,
+    // and the name it gave each
+    r#"
+    ALTER TABLE feeds ADD COLUMN from_urls_file INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE feeds ADD COLUMN urls_file_title TEXT;
+    "#,
+    // 40: article text stored zstd-compressed (see `register_compression`)
+    r#"
+    UPDATE articles SET content = compress(content), content_text = compress(content_text),
+                        full_text = compress(full_text);
+    "#,
+    // 41: indexes for the list views. A feed's articles by date, and unread
+    // ones on their own (most are read, so this stays small); these replace
+    // the single-column feed and read-state indexes. The url and
+    // (feed_id, guid) indexes duplicated their UNIQUE constraints.
+    r#"
+    DROP INDEX IF EXISTS idx_articles_feed_id;
+    DROP INDEX IF EXISTS idx_articles_is_read;
+    DROP INDEX IF EXISTS idx_feeds_url;
+    DROP INDEX IF EXISTS idx_deleted_articles_feed_guid;
+    CREATE INDEX IF NOT EXISTS idx_articles_feed_published ON articles(feed_id, published_at DESC);
+    CREATE INDEX IF NOT EXISTS idx_articles_unread ON articles(feed_id, published_at) WHERE is_read = 0;
+    "#,
+    // 42: table sizes recorded by each maintenance run, to report growth
+    r#"
+    CREATE TABLE IF NOT EXISTS db_sizes (
+        day TEXT NOT NULL,
+        name TEXT NOT NULL,
+        bytes INTEGER NOT NULL,
+        PRIMARY KEY (day, name)
+    );
+    "#,
+    // 43: short-lived tokens handed to Wallabag apps by the OAuth endpoint
+    r#"
+    CREATE TABLE IF NOT EXISTS oauth_tokens (
+        token_hash TEXT PRIMARY KEY,
+        user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+        kind TEXT NOT NULL,
+        expires_at TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS idx_oauth_tokens_user_id ON oauth_tokens(user_id);
+    "#,
+    // 44: named API tokens, each read-only or read-write
+    r#"
+    CREATE TABLE IF NOT EXISTS api_tokens (
+        id INTEGER PRIMARY KEY,
+        user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+        name TEXT NOT NULL,
+        token_hash TEXT NOT NULL UNIQUE,
+        scope TEXT NOT NULL,
+        created_at TEXT NOT NULL DEFAULT (datetime('now')),
+        last_used_at TEXT
+    );
+    "#,
+    // 45: feeds added and removed, bulk changes, imports and pushes to
+    // other services, for finding out where something went
+    r#"
+    CREATE TABLE IF NOT EXISTS audit_log (
+        id INTEGER PRIMARY KEY,
+        at TEXT NOT NULL DEFAULT (datetime('now')),
+        action TEXT NOT NULL,
+        subject TEXT,
+        detail TEXT
+    );
+    CREATE INDEX IF NOT EXISTS idx_audit_log_at ON audit_log(at);
+    "#,
+    // 46: per-feed reader view template for fetching and opening articles
+    r#"
+    ALTER TABLE feeds ADD COLUMN reader_url TEXT;
+    "#,
+    // 47: 404s in a row, and the URL rediscovered from the site after them
+    r#"
+    ALTER TABLE feeds ADD COLUMN missing_count INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE feeds ADD COLUMN moved_to TEXT;
+    "#,
+    // 48: whether the feed's last fetch only parsed after fixing its XML
+    r#"
+    ALTER TABLE feeds ADD COLUMN repaired INTEGER NOT NULL DEFAULT 0;
+    "#,
+    // 49: articles a server user deleted, gone for them only
+    r#"
+    ALTER TABLE user_article_state ADD COLUMN is_hidden INTEGER NOT NULL DEFAULT 0;
+    "#,
+    // 50: a server user's own read-later queue, saved links and tags. Saves
+    // used to be shared through a subscription to the Saved feed; each
+    // account keeps the saved articles it had touched.
+    r#"
+    ALTER TABLE user_article_state ADD COLUMN queued_at TEXT;
+    ALTER TABLE user_article_state ADD COLUMN saved_at TEXT;
+    CREATE TABLE IF NOT EXISTS user_article_tags (
+        user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+        article_id INTEGER NOT NULL,
+        tag TEXT NOT NULL,
+        PRIMARY KEY (user_id, article_id, tag)
+    );
+    CREATE INDEX IF NOT EXISTS idx_user_article_tags_tag ON user_article_tags(tag);
+    UPDATE user_article_state SET saved_at = datetime('now')
+        WHERE article_id IN (SELECT a.id FROM articles a JOIN feeds f ON f.id = a.feed_id
+                             WHERE f.url = 'beatcheck:saved');
+    DELETE FROM user_feeds WHERE feed_id IN (SELECT id FROM feeds WHERE url = 'beatcheck:saved');
+    "#,
+];
+
+/// Apply any migrations newer than the database's `user_version`
+pub fn migrate(conn: &mut rusqlite::Connection) -> rusqlite::Result<()> {
+    let version: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration)?;
+        tx.pragma_update(None, "user_version", index + 1)?;
+        tx.commit()?;
+    }
+
+    Ok(())
+}
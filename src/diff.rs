@@ -0,0 +1,186 @@
+//! Word-level differences between two versions of an article's text, for
+//! seeing what an outlet changed in a story after publishing it.
+
+/// Edit distance past which a diff isn't worth working out word by word;
+/// the changed stretch is shown as all removed, then all added
+const MAX_EDITS: usize = 4000;
+
+/// A run of words both versions share, or that only one has. Paragraph
+/// breaks come through as `"\n"` words.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    Same(String),
+    Removed(String),
+    Added(String),
+}
+
+impl Change {
+    fn text_mut(&mut self) -> &mut String {
+        match self {
+            Change::Same(text) | Change::Removed(text) | Change::Added(text) => text,
+        }
+    }
+}
+
+/// Words of `text`, with a `"\n"` between paragraphs
+fn words(text: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    for paragraph in text.split("\n\n").filter(|p| !p.trim().is_empty()) {
+        if !words.is_empty() {
+            words.push("\n");
+        }
+        words.extend(paragraph.split_whitespace());
+    }
+    words
+}
+
+/// What changed from `old` to `new`, word by word, as runs in reading order
+pub fn word_diff(old: &str, new: &str) -> Vec<Change> {
+    let (a, b) = (words(old), words(new));
+
+    // Most edits touch a few places, so leave the shared ends out of the search
+    let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let (middle_a, middle_b) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+
+    let mut edits: Vec<Change> = a[..prefix].iter().map(|w| Change::Same(w.to_string())).collect();
+    match shortest_edit(middle_a, middle_b) {
+        Some(middle) => edits.extend(middle),
+        None => {
+            edits.extend(middle_a.iter().map(|w| Change::Removed(w.to_string())));
+            edits.extend(middle_b.iter().map(|w| Change::Added(w.to_string())));
+        }
+    }
+    edits.extend(a[a.len() - suffix..].iter().map(|w| Change::Same(w.to_string())));
+    merge_runs(edits)
+}
+
+/// Join neighbouring words of the same kind into one run
+fn merge_runs(edits: Vec<Change>) -> Vec<Change> {
+    let mut runs: Vec<Change> = Vec::new();
+    for edit in edits {
+        match runs.last_mut() {
+            Some(last) if std::mem::discriminant(last) == std::mem::discriminant(&edit) => {
+                let mut edit = edit;
+                let text = last.text_mut();
+                if !text.ends_with('\n') && !edit.text_mut().starts_with('\n') {
+                    text.push(' ');
+                }
+                text.push_str(edit.text_mut());
+            }
+            _ => runs.push(edit),
+        }
+    }
+    runs
+}
+
+/// Myers' O((N+M)D) shortest edit script from `a` to `b`, word by word;
+/// `None` if it takes more than `MAX_EDITS` edits
+fn shortest_edit(a: &[&str], b: &[&str]) -> Option<Vec<Change>> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max = (n + m) as usize;
+    let offset = max as isize + 1;
+    let mut v = vec![0isize; 2 * max + 3];
+    // The furthest x on each diagonal k in -d..=d, before round d
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+
+    let at = |k: isize| (k + offset) as usize;
+    let mut found = None;
+    'rounds: for d in 0..=max.min(MAX_EDITS) as isize {
+        trace.push(v[at(-d)..=at(d)].to_vec());
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d || (k != d && v[at(k - 1)] < v[at(k + 1)]) {
+                v[at(k + 1)]
+            } else {
+                v[at(k - 1)] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[at(k)] = x;
+            if x >= n && y >= m {
+                found = Some(d);
+                break 'rounds;
+            }
+        }
+    }
+    let rounds = found?;
+
+    // Walk back from the end, one edit per round
+    let mut edits = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for d in (1..=rounds).rev() {
+        let before = &trace[d as usize];
+        let get = |k: isize| before[(k + d) as usize];
+        let k = x - y;
+        let prev_k = if k == -d || (k != d && get(k - 1) < get(k + 1)) { k + 1 } else { k - 1 };
+        let prev_x = get(prev_k);
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+            edits.push(Change::Same(a[x as usize].to_string()));
+        }
+        if x == prev_x {
+            edits.push(Change::Added(b[prev_y as usize].to_string()));
+        } else {
+            edits.push(Change::Removed(a[prev_x as usize].to_string()));
+        }
+        (x, y) = (prev_x, prev_y);
+    }
+    while x > 0 && y > 0 {
+        x -= 1;
+        y -= 1;
+        edits.push(Change::Same(a[x as usize].to_string()));
+    }
+    edits.reverse();
+    Some(edits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_diff_marks_changed_words() {
+        let diff = word_diff(
+            "The company said it laid off 200 workers.\n\nShares fell.",
+            "The company said it laid off 1,200 workers in March.\n\nShares fell.",
+        );
+        assert_eq!(
+            diff,
+            [
+                Change::Same("The company said it laid off".into()),
+                Change::Removed("200 workers.".into()),
+                Change::Added("1,200 workers in March.".into()),
+                Change::Same("\nShares fell.".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_word_diff_edits_in_several_places() {
+        let diff = word_diff("a b c d e f g", "a x c d f g h");
+        assert_eq!(
+            diff,
+            [
+                Change::Same("a".into()),
+                Change::Removed("b".into()),
+                Change::Added("x".into()),
+                Change::Same("c d".into()),
+                Change::Removed("e".into()),
+                Change::Same("f g".into()),
+                Change::Added("h".into()),
+            ]
+        );
+        assert_eq!(word_diff("same  text", "same text"), [Change::Same("same text".into())]);
+        assert!(word_diff("", "").is_empty());
+    }
+}
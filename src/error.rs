@@ -32,11 +32,39 @@ pub enum AppError {
     #[error("Claude API error: {0}")]
     ClaudeApi(String),
 
+    #[error("OpenAI API error: {0}")]
+    OpenAiApi(String),
+
+    #[error("Local model error: {0}")]
+    LocalLlm(String),
+
+    /// Overloaded, rate limited or down for now (HTTP 429 or 5xx)
+    #[error("Service unavailable: {0}")]
+    Unavailable(String),
+
     #[error("Raindrop API error: {0}")]
     RaindropApi(String),
 
+    #[error("Rate limited until {0}")]
+    RateLimited(chrono::DateTime<chrono::Utc>),
+
+    /// The URL answered 404 Not Found or 410 Gone
+    #[error("Not found: {0}")]
+    NotFound(String),
+
     #[error("{0}")]
     Other(#[from] anyhow::Error),
 }
 
+impl AppError {
+    /// Whether the same request might succeed if tried again later
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Unavailable(_) | Self::RateLimited(_) => true,
+            Self::Http(e) => e.is_timeout() || e.is_connect(),
+            _ => false,
+        }
+    }
+}
+
 pub type Result<T> = std::result::Result<T, AppError>;
@@ -0,0 +1,86 @@
+use std::io::Write;
+use std::process::Stdio;
+
+use anyhow::anyhow;
+use base64::Engine;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::error::Result;
+
+/// Clipboard tools tried in order when `export.clipboard_command` isn't set
+const CLIPBOARD_COMMANDS: &[&str] = &["wl-copy", "xclip -selection clipboard", "xsel --clipboard --input", "pbcopy"];
+
+/// Copies text to the system clipboard through an external tool, or failing
+/// that an OSC 52 escape, which most terminals (and tmux, over SSH) accept
+#[derive(Debug, Clone)]
+pub struct Clipboard {
+    command: Option<String>,
+}
+
+impl Clipboard {
+    pub fn new(command: Option<&str>) -> Self {
+        Self {
+            command: command.map(str::to_string),
+        }
+    }
+
+    /// Copy `text`, returning which tool took it
+    pub async fn copy(&self, text: &str) -> Result<String> {
+        if let Some(command) = &self.command {
+            pipe_to(command, text).await?;
+            return Ok(command.clone());
+        }
+        for command in CLIPBOARD_COMMANDS {
+            if pipe_to(command, text).await.is_ok() {
+                return Ok(command.to_string());
+            }
+        }
+        osc52(text)?;
+        Ok("terminal".to_string())
+    }
+}
+
+async fn pipe_to(command: &str, text: &str) -> Result<()> {
+    let args: Vec<&str> = command.split_whitespace().collect();
+    let (program, rest) = args
+        .split_first()
+        .ok_or_else(|| anyhow!("export.clipboard_command is empty"))?;
+
+    let mut child = Command::new(program)
+        .args(rest)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes()).await?;
+    }
+    let status = child.wait().await?;
+    if !status.success() {
+        return Err(anyhow!("{} exited with {}", program, status).into());
+    }
+    Ok(())
+}
+
+/// Ask the terminal to set the clipboard
+fn osc52(text: &str) -> Result<()> {
+    let mut stdout = std::io::stdout();
+    write!(stdout, "{}", osc52_sequence(text))?;
+    stdout.flush()?;
+    Ok(())
+}
+
+fn osc52_sequence(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", base64::engine::general_purpose::STANDARD.encode(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_osc52_sequence() {
+        assert_eq!(osc52_sequence("hi"), "\x1b]52;c;aGk=\x07");
+    }
+}
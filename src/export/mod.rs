@@ -0,0 +1,372 @@
+//! Writing individual articles out of the database into files
+
+mod clipboard;
+mod notes;
+mod packet;
+mod pdf;
+mod template;
+
+pub use clipboard::Clipboard;
+pub use notes::NoteExporter;
+pub use packet::{PacketExporter, PacketFormat, PacketItem};
+pub use pdf::PdfExporter;
+pub use template::{Context, Escape, Template, Value};
+
+use std::path::Path;
+
+use chrono::Utc;
+use regex::Regex;
+
+use crate::config::{Config, TemplatesConfig};
+use crate::error::{AppError, Result};
+use crate::models::{Article, Summary};
+
+const DEFAULT_HTML_TEMPLATE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{{title}}</title>
+<style>
+body { max-width: 42em; margin: 2em auto; padding: 0 1em; font: 18px/1.6 Georgia, serif; color: #222; }
+img, video { max-width: 100%; height: auto; }
+.byline { color: #777; font: 14px sans-serif; }
+.summary { background: #f6f6f6; padding: 0.5em 1em 0.5em 2em; font-size: 16px; }
+.tags { color: #777; font: 14px sans-serif; }
+</style>
+</head>
+<body>
+<h1>{{title}}</h1>
+<p class="byline"><a href="{{url}}">{{url}}</a>{{#if feed}} · {{feed}}{{/if}}{{#if author}} · {{author}}{{/if}}{{#if published}} · {{published}}{{/if}}{{#each notes}} · {{this}}{{/each}}</p>
+{{#if summary_points}}<ul class="summary">
+{{#each summary_points}}<li>{{this}}</li>
+{{/each}}</ul>
+{{/if}}{{#if tags}}<p class="tags">{{#each tags}}#{{this}} {{/each}}</p>
+{{/if}}{{{body}}}
+</body>
+</html>
+"#;
+
+const DEFAULT_NOTE_TEMPLATE: &str = r#"---
+title: {{json title}}
+source: {{json url}}
+{{#if author}}author: {{json author}}
+{{/if}}{{#if feed}}feed: {{json feed}}
+{{/if}}{{#if published}}published: {{published}}
+{{/if}}saved: {{saved}}
+tags: {{json tags}}
+{{#if summary}}summary: |
+{{indent summary}}
+{{/if}}---
+
+# {{title}}
+
+{{#if summary}}## Summary
+
+{{summary}}
+
+{{/if}}{{#if body}}## Article
+
+{{body}}
+{{/if}}"#;
+
+const DEFAULT_DAILY_TEMPLATE: &str = "- [[{{name}}]] ([source]({{url}}))\n{{#each summary_points}}    - {{this}}\n{{/each}}";
+
+const DEFAULT_BRIEF_TEMPLATE: &str = "*{{title}}*{{#if feed}} ({{feed}}){{/if}}\n{{url}}\n{{#if published}}Published {{published}}\n{{/if}}{{#each summary_points}}• {{this}}\n{{/each}}";
+
+const DEFAULT_NOTE_FILENAME: &str = "{{title}}";
+const DEFAULT_EXPORT_FILENAME: &str = "{{id}}-{{slug}}";
+
+/// Output formats for every export, built in or loaded from `[templates]`
+#[derive(Debug, Clone)]
+pub struct Templates {
+    /// Standalone HTML document (PDF export and the starred archive)
+    pub html: Template,
+    /// Markdown note for the notes vault
+    pub note: Template,
+    /// Entry appended to the daily note
+    pub daily: Template,
+    /// Plain-text brief copied to the clipboard
+    pub brief: Template,
+    pub note_filename: Template,
+    /// File name (without extension) for PDF and archive files
+    pub export_filename: Template,
+}
+
+impl Default for Templates {
+    fn default() -> Self {
+        let parse = |source, escape| Template::parse(source, escape).expect("built-in template");
+        Self {
+            html: parse(DEFAULT_HTML_TEMPLATE, Escape::Html),
+            note: parse(DEFAULT_NOTE_TEMPLATE, Escape::None),
+            daily: parse(DEFAULT_DAILY_TEMPLATE, Escape::None),
+            brief: parse(DEFAULT_BRIEF_TEMPLATE, Escape::None),
+            note_filename: parse(DEFAULT_NOTE_FILENAME, Escape::None),
+            export_filename: parse(DEFAULT_EXPORT_FILENAME, Escape::None),
+        }
+    }
+}
+
+impl Templates {
+    /// Built-in templates, replaced by any configured in `[templates]`.
+    /// Relative template paths are resolved against the config directory.
+    pub fn load(config: &TemplatesConfig) -> Result<Self> {
+        let mut templates = Self::default();
+        let config_dir = Config::config_path()
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let read = |path: &Path, escape| -> Result<Template> {
+            let path = config_dir.join(path);
+            let source = std::fs::read_to_string(&path).map_err(|e| {
+                AppError::Config(format!("Failed to read template {}: {}", path.display(), e))
+            })?;
+            Template::parse(&source, escape)
+        };
+
+        if let Some(path) = &config.html {
+            templates.html = read(path, Escape::Html)?;
+        }
+        if let Some(path) = &config.note {
+            templates.note = read(path, Escape::None)?;
+        }
+        if let Some(path) = &config.daily {
+            templates.daily = read(path, Escape::None)?;
+        }
+        if let Some(path) = &config.brief {
+            templates.brief = read(path, Escape::None)?;
+        }
+        if let Some(pattern) = &config.note_filename {
+            templates.note_filename = Template::parse(pattern, Escape::None)?;
+        }
+        if let Some(pattern) = &config.export_filename {
+            templates.export_filename = Template::parse(pattern, Escape::None)?;
+        }
+        Ok(templates)
+    }
+}
+
+/// What goes out with an article besides its text: the stored summary and
+/// suggested tags, for destinations set to include them
+#[derive(Debug, Clone, Default)]
+pub struct Embed {
+    pub summary: Option<Summary>,
+    pub tags: Vec<String>,
+}
+
+/// The values every export template can use; callers add `body`, `tags`,
+/// and format-specific ones
+pub fn article_context(article: &Article, summary: Option<&Summary>) -> Context {
+    let summary = summary.map(|s| s.content.trim().to_string()).unwrap_or_default();
+    let points: Vec<String> = summary
+        .lines()
+        .map(|line| line.trim().trim_start_matches(['-', '*', '•']).trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    Context::from([
+        ("id", Value::from(article.id.to_string())),
+        ("title", Value::from(article.title.as_str())),
+        ("slug", Value::from(slug(&article.title))),
+        ("url", Value::from(article.url.as_str())),
+        ("author", Value::from(article.author.clone().unwrap_or_default())),
+        ("feed", Value::from(article.feed_title.clone().unwrap_or_default())),
+        (
+            "published",
+            Value::from(
+                article
+                    .published_at
+                    .map(|d| d.format("%Y-%m-%d").to_string())
+                    .unwrap_or_default(),
+            ),
+        ),
+        ("saved", Value::from(Utc::now().format("%Y-%m-%d").to_string())),
+        ("summary", Value::from(summary)),
+        ("summary_points", Value::from(points)),
+        ("tags", Value::List(Vec::new())),
+        ("notes", Value::List(Vec::new())),
+        ("body", Value::from("")),
+    ])
+}
+
+/// Lowercase ASCII-ish words joined by dashes, at most 60 characters
+fn slug(title: &str) -> String {
+    let slug: String = title
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect();
+    let slug: Vec<&str> = slug.split('-').filter(|s| !s.is_empty()).collect();
+    let slug: String = slug.join("-").chars().take(60).collect();
+    slug.trim_end_matches('-').to_string()
+}
+
+/// A rendered file name pattern with path separators and characters that
+/// break file names or wikilinks removed
+pub fn sanitize_file_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '|' => '-',
+            '*' | '?' | '"' | '<' | '>' | '#' | '^' | '[' | ']' => ' ',
+            c if c.is_control() => ' ',
+            c => c,
+        })
+        .collect();
+    let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+    let name: String = name.chars().take(120).collect();
+    let name = name.trim_matches(|c: char| c == '.' || c.is_whitespace());
+    if name.is_empty() {
+        "Untitled".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// File name for a PDF or archive copy, from `export_filename`
+pub fn export_file_name(templates: &Templates, article: &Article, ext: &str) -> String {
+    let stem = templates.export_filename.render(&article_context(article, None));
+    format!("{}.{}", sanitize_file_name(&stem), ext)
+}
+
+/// Drop scripts, embeds, forms, page furniture, event handlers and srcset
+/// (which would pull remote images past the `src`)
+pub fn clean_html(html: &str) -> String {
+    let blocks = Regex::new(
+        r"(?is)<(script|style|noscript|iframe|object|embed|form|nav|footer|aside)\b.*?</(script|style|noscript|iframe|object|embed|form|nav|footer|aside)\s*>",
+    )
+    .unwrap();
+    let void = Regex::new(r"(?is)<(script|link|meta|input|source)\b[^>]*>").unwrap();
+    let handlers = Regex::new(r#"(?i)\s(on[a-z]+|srcset|sizes)\s*=\s*("[^"]*"|'[^']*'|[^\s>]+)"#).unwrap();
+
+    let html = blocks.replace_all(html, "");
+    let html = void.replace_all(&html, "");
+    handlers.replace_all(&html, "").into_owned()
+}
+
+/// The article's best available body as HTML: the stored full text (as
+/// paragraphs) if it was fetched, otherwise the feed's content
+pub fn article_body_html(article: &Article) -> String {
+    if let Some(text) = &article.full_text {
+        return text
+            .split("\n\n")
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(|p| format!("<p>{}</p>", escape_html(p).replace('\n', "<br>")))
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+    match (&article.content, &article.content_text) {
+        (Some(html), _) => clean_html(html),
+        (None, Some(text)) => format!("<p>{}</p>", escape_html(text)),
+        (None, None) => String::new(),
+    }
+}
+
+/// The `html` template filled in for `article` with an HTML `body` and
+/// whatever `embed` carries. `notes` are appended to the byline (e.g. when
+/// the copy was made).
+pub fn html_document(
+    templates: &Templates,
+    article: &Article,
+    embed: &Embed,
+    body: &str,
+    notes: &[String],
+) -> String {
+    let mut context = article_context(article, embed.summary.as_ref());
+    context.insert("tags", Value::from(embed.tags.clone()));
+    context.insert("body", Value::from(body));
+    context.insert("notes", Value::from(notes.to_vec()));
+    templates.html.render(&context)
+}
+
+/// The `brief` template filled in for `article` and its stored summary
+pub fn brief(templates: &Templates, article: &Article, embed: &Embed) -> String {
+    let mut context = article_context(article, embed.summary.as_ref());
+    context.insert("tags", Value::from(embed.tags.clone()));
+    templates.brief.render(&context)
+}
+
+pub fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_file_name() {
+        assert_eq!(sanitize_file_name("Why \"Rust\" wins: a #take"), "Why Rust wins- a take");
+        assert_eq!(sanitize_file_name("../etc/passwd"), "-etc-passwd");
+        assert_eq!(sanitize_file_name("  ...  "), "Untitled");
+    }
+
+    #[test]
+    fn test_html_document_embeds_summary_and_tags() {
+        let article: Article = serde_json::from_value(serde_json::json!({
+            "id": 1, "feed_id": 1, "guid": "g", "title": "Chips", "url": "https://example.com/a",
+            "author": null, "content": null, "content_text": null, "published_at": null,
+            "fetched_at": "2026-01-01T00:00:00Z", "feed_title": null, "is_starred": false,
+            "is_pinned": false, "reading_minutes": null, "is_queued": false, "is_read": false,
+            "full_text": null, "comments_url": null, "discussion": null, "enclosure": null,
+            "playback_secs": null, "is_played": false, "extras": null, "archive_path": null,
+            "tags": []
+        }))
+        .unwrap();
+        let templates = Templates::default();
+        let embed = Embed {
+            summary: Some(Summary {
+                id: 1,
+                article_id: 1,
+                content: "• Fabs <expand>\n• Prices rise".to_string(),
+                model_version: "test".to_string(),
+                provider: None,
+                generated_at: Utc::now(),
+            }),
+            tags: vec!["semiconductors".to_string()],
+        };
+        let html = html_document(&templates, &article, &embed, "<p>Body</p>", &[]);
+        assert!(html.contains("<li>Fabs &lt;expand&gt;</li>\n<li>Prices rise</li>"), "{}", html);
+        assert!(html.contains("#semiconductors"));
+
+        let bare = html_document(&templates, &article, &Embed::default(), "<p>Body</p>", &[]);
+        assert!(!bare.contains("class=\"summary\"") && !bare.contains("class=\"tags\""));
+    }
+
+    #[test]
+    fn test_brief_lists_summary_points() {
+        let article: Article = serde_json::from_value(serde_json::json!({
+            "id": 1, "feed_id": 1, "guid": "g", "title": "Chips", "url": "https://example.com/a",
+            "author": null, "content": null, "content_text": null, "published_at": "2026-03-02T09:00:00Z",
+            "fetched_at": "2026-03-02T10:00:00Z", "feed_title": "Wire", "is_starred": false,
+            "is_pinned": false, "reading_minutes": null, "is_queued": false, "is_read": false,
+            "full_text": null, "comments_url": null, "discussion": null, "enclosure": null,
+            "playback_secs": null, "is_played": false, "extras": null, "archive_path": null,
+            "tags": []
+        }))
+        .unwrap();
+        let embed = Embed {
+            summary: Some(Summary {
+                id: 1,
+                article_id: 1,
+                content: "- Fabs expand\n- Prices rise".to_string(),
+                model_version: "test".to_string(),
+                provider: None,
+                generated_at: Utc::now(),
+            }),
+            tags: Vec::new(),
+        };
+        assert_eq!(
+            brief(&Templates::default(), &article, &embed),
+            "*Chips* (Wire)\nhttps://example.com/a\nPublished 2026-03-02\n• Fabs expand\n• Prices rise\n"
+        );
+    }
+
+    #[test]
+    fn test_clean_html_strips_active_content() {
+        let html = r#"<p onclick="steal()">Hi</p><script>alert(1)</script><img src="a.png" srcset="a-2x.png 2x"><iframe src="ad"></iframe><footer>f</footer>"#;
+        assert_eq!(clean_html(html), r#"<p>Hi</p><img src="a.png">"#);
+    }
+}
@@ -0,0 +1,207 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use chrono::{DateTime, Local, Utc};
+use tokio::io::AsyncWriteExt;
+
+use super::{article_context, sanitize_file_name, Embed, Templates, Value};
+use crate::config::NotesConfig;
+use crate::error::{AppError, Result};
+use crate::models::{Article, Summary};
+
+/// Files articles into an Obsidian or Logseq vault as Markdown notes and
+/// links each one from that day's daily note
+#[derive(Clone)]
+pub struct NoteExporter {
+    vault: PathBuf,
+    folder: String,
+    daily_folder: String,
+    daily_format: String,
+    tags: Vec<String>,
+    templates: Arc<Templates>,
+}
+
+impl NoteExporter {
+    pub fn new(config: &NotesConfig, default_tags: &[String], templates: Arc<Templates>) -> Option<Self> {
+        let vault = config.vault.clone()?;
+        Some(Self {
+            vault,
+            folder: config.folder.clone(),
+            daily_folder: config.daily_folder.clone(),
+            daily_format: config.daily_format.clone(),
+            tags: default_tags.to_vec(),
+            templates,
+        })
+    }
+
+    /// Write (or overwrite) the article's note, with `embed`'s summary and
+    /// tags, and add it to today's daily note; returns the note's path
+    pub async fn write(&self, article: &Article, embed: &Embed) -> Result<PathBuf> {
+        if !self.vault.is_dir() {
+            return Err(AppError::Config(format!(
+                "Notes vault {} does not exist",
+                self.vault.display()
+            )));
+        }
+
+        let name = sanitize_file_name(&self.templates.note_filename.render(&article_context(article, None)));
+        let dir = self.vault.join(&self.folder);
+        tokio::fs::create_dir_all(&dir).await?;
+        let path = dir.join(format!("{}.md", name));
+        let summary = embed.summary.as_ref();
+        let note = render_note(&self.templates, article, summary, &self.tags(article, &embed.tags), Utc::now());
+        tokio::fs::write(&path, note).await?;
+
+        self.append_daily(&name, article, summary).await?;
+        Ok(path)
+    }
+
+    /// Configured default tags, the item's own categories and `suggested`,
+    /// as tag-safe words
+    fn tags(&self, article: &Article, suggested: &[String]) -> Vec<String> {
+        let categories = article.extras.iter().flat_map(|x| x.categories.iter());
+        let mut tags: Vec<String> = Vec::new();
+        for tag in self.tags.iter().chain(categories).chain(suggested) {
+            let tag: String = tag
+                .trim()
+                .chars()
+                .map(|c| if c.is_whitespace() { '-' } else { c })
+                .filter(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '/'))
+                .collect();
+            if !tag.is_empty() && !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        tags
+    }
+
+    async fn append_daily(&self, name: &str, article: &Article, summary: Option<&Summary>) -> Result<()> {
+        let dir = self.vault.join(&self.daily_folder);
+        tokio::fs::create_dir_all(&dir).await?;
+        let path = dir.join(format!("{}.md", Local::now().format(&self.daily_format)));
+        let entry = daily_entry(&self.templates, name, article, summary);
+
+        // Skip if this article is already linked from today's note
+        let existing = tokio::fs::read_to_string(&path).await.unwrap_or_default();
+        if existing.contains(&format!("[[{}]]", name)) {
+            return Ok(());
+        }
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .await?;
+        let separator = if existing.is_empty() || existing.ends_with('\n') { "" } else { "\n" };
+        file.write_all(format!("{}{}", separator, entry).as_bytes()).await?;
+        Ok(())
+    }
+}
+
+/// The `note` template filled in for `article`, with a Markdown body
+fn render_note(
+    templates: &Templates,
+    article: &Article,
+    summary: Option<&Summary>,
+    tags: &[String],
+    saved: DateTime<Utc>,
+) -> String {
+    let mut context = article_context(article, summary);
+    context.insert("tags", Value::from(tags.to_vec()));
+    context.insert("saved", Value::from(saved.format("%Y-%m-%d").to_string()));
+    context.insert("body", Value::from(article_markdown(article)));
+    templates.note.render(&context)
+}
+
+fn article_markdown(article: &Article) -> String {
+    if let Some(text) = &article.full_text {
+        return text.trim().to_string();
+    }
+    match (&article.content, &article.content_text) {
+        (Some(html), _) => html2text::from_read(super::clean_html(html).as_bytes(), 100)
+            .map(|text| text.trim().to_string())
+            .unwrap_or_default(),
+        (None, Some(text)) => text.trim().to_string(),
+        (None, None) => String::new(),
+    }
+}
+
+/// The `daily` template: by default one bullet linking the note, with the
+/// summary's points nested under it
+fn daily_entry(templates: &Templates, name: &str, article: &Article, summary: Option<&Summary>) -> String {
+    let mut context = article_context(article, summary);
+    context.insert("name", Value::from(name));
+    templates.daily.render(&context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn article() -> Article {
+        Article {
+            id: 7,
+            feed_id: 1,
+            guid: "g".to_string(),
+            title: "Why \"Rust\" wins: a #take".to_string(),
+            url: "https://example.com/rust".to_string(),
+            author: Some("Ann".to_string()),
+            content: Some("<p>Body <b>text</b></p><script>x()</script>".to_string()),
+            content_text: None,
+            published_at: DateTime::from_timestamp(1_767_225_600, 0),
+            fetched_at: Utc::now(),
+            feed_title: Some("Example".to_string()),
+            is_starred: true,
+            is_pinned: false,
+            reading_minutes: None,
+            is_queued: false,
+            is_read: false,
+            full_text: None,
+            comments_url: None,
+            discussion: None,
+            enclosure: None,
+            playback_secs: None,
+            is_played: false,
+            extras: None,
+            archive_path: None,
+            tags: Vec::new(),
+            by_followed_author: false,
+            embargo_until: None,
+            revisions: 0,
+            removed_at: None,
+        }
+    }
+
+    fn summary() -> Summary {
+        Summary {
+            id: 1,
+            article_id: 7,
+            content: "- Memory safety\n- Fast builds".to_string(),
+            model_version: "test".to_string(),
+            provider: None,
+            generated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_render_note_front_matter() {
+        let saved = DateTime::from_timestamp(1_767_312_000, 0).unwrap();
+        let note = render_note(&Templates::default(), &article(), Some(&summary()), &["rss".to_string()], saved);
+        assert!(note.starts_with("---\ntitle: \"Why \\\"Rust\\\" wins: a #take\"\n"));
+        assert!(note.contains("source: \"https://example.com/rust\"\n"));
+        assert!(note.contains("published: 2026-01-01\nsaved: 2026-01-02\n"));
+        assert!(note.contains("tags: [\"rss\"]\n"));
+        assert!(note.contains("summary: |\n  - Memory safety\n  - Fast builds\n---\n"));
+        assert!(note.contains("## Article\n\nBody "));
+        assert!(!note.contains("x()"));
+    }
+
+    #[test]
+    fn test_daily_entry_nests_summary_points() {
+        let entry = daily_entry(&Templates::default(), "Note", &article(), Some(&summary()));
+        assert_eq!(
+            entry,
+            "- [[Note]] ([source](https://example.com/rust))\n    - Memory safety\n    - Fast builds\n"
+        );
+    }
+}
@@ -0,0 +1,270 @@
+use std::path::PathBuf;
+
+use chrono::Utc;
+
+use super::pdf::convert;
+use super::{article_body_html, article_context, escape_html, sanitize_file_name, Value};
+use crate::error::{AppError, Result};
+use crate::models::{Article, Summary};
+
+/// One article in a briefing packet, with what the reader has on it
+#[derive(Debug, Clone)]
+pub struct PacketItem {
+    pub article: Article,
+    pub summary: Option<Summary>,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PacketFormat {
+    Markdown,
+    Html,
+    Epub,
+}
+
+impl PacketFormat {
+    /// `md`/`markdown`, `html` or `epub`
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.to_lowercase().as_str() {
+            "md" | "markdown" => Ok(PacketFormat::Markdown),
+            "html" => Ok(PacketFormat::Html),
+            "epub" => Ok(PacketFormat::Epub),
+            _ => Err(AppError::Config(format!("Unknown packet format \"{}\": use md, html or epub", name))),
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            PacketFormat::Markdown => "md",
+            PacketFormat::Html => "html",
+            PacketFormat::Epub => "epub",
+        }
+    }
+}
+
+/// Writes a collection out as one document: each article in order with its
+/// summary, the reader's note and the text. EPUB goes through an external
+/// converter (pandoc by default) from the HTML version.
+#[derive(Clone)]
+pub struct PacketExporter {
+    epub_command: String,
+    dir: PathBuf,
+}
+
+impl PacketExporter {
+    pub fn new(epub_command: &str, dir: PathBuf) -> Self {
+        Self {
+            epub_command: epub_command.to_string(),
+            dir,
+        }
+    }
+
+    /// Write `<dir>/<name>.<ext>` and return its path
+    pub async fn export(&self, name: &str, items: &[PacketItem], format: PacketFormat) -> Result<PathBuf> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let output = self
+            .dir
+            .join(format!("{}.{}", sanitize_file_name(name), format.extension()));
+        match format {
+            PacketFormat::Markdown => tokio::fs::write(&output, packet_markdown(name, items)).await?,
+            PacketFormat::Html => tokio::fs::write(&output, packet_html(name, items)).await?,
+            PacketFormat::Epub => {
+                let input = std::env::temp_dir().join(format!("beatcheck-packet-{}.html", std::process::id()));
+                tokio::fs::write(&input, packet_html(name, items)).await?;
+                let result = convert(&self.epub_command, "export.epub_command", &input, &output).await;
+                let _ = tokio::fs::remove_file(&input).await;
+                result?;
+            }
+        }
+        Ok(output)
+    }
+}
+
+/// "Feed · Author · 2026-03-02", leaving out what's unknown
+fn byline(article: &Article) -> String {
+    let context = article_context(article, None);
+    ["feed", "author", "published"]
+        .iter()
+        .filter_map(|key| match context.get(key) {
+            Some(Value::Text(text)) if !text.is_empty() => Some(text.clone()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join(" · ")
+}
+
+fn summary_points(item: &PacketItem) -> Vec<String> {
+    match article_context(&item.article, item.summary.as_ref()).remove("summary_points") {
+        Some(Value::List(points)) => points,
+        _ => Vec::new(),
+    }
+}
+
+/// The article's stored text: page text if fetched, else the feed's
+fn body_text(article: &Article) -> Option<&str> {
+    article
+        .full_text
+        .as_deref()
+        .or(article.content_text.as_deref())
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+}
+
+fn heading(items: &[PacketItem]) -> String {
+    let count = match items.len() {
+        1 => "1 article".to_string(),
+        n => format!("{} articles", n),
+    };
+    format!("{} · {}", count, Utc::now().format("%Y-%m-%d"))
+}
+
+pub fn packet_markdown(name: &str, items: &[PacketItem]) -> String {
+    let mut out = format!("# {}\n\n{}\n", name, heading(items));
+    for (n, item) in items.iter().enumerate() {
+        let article = &item.article;
+        out.push_str(&format!("\n## {}. {}\n\n", n + 1, article.title));
+        let byline = byline(article);
+        if !byline.is_empty() {
+            out.push_str(&format!("{}\n", byline));
+        }
+        out.push_str(&format!("<{}>\n", article.url));
+        let points = summary_points(item);
+        if !points.is_empty() {
+            out.push('\n');
+            for point in points {
+                out.push_str(&format!("- {}\n", point));
+            }
+        }
+        if let Some(note) = item.note.as_deref().map(str::trim).filter(|n| !n.is_empty()) {
+            out.push_str("\n> **Note:** ");
+            out.push_str(&note.lines().collect::<Vec<_>>().join("\n> "));
+            out.push('\n');
+        }
+        if let Some(text) = body_text(article) {
+            out.push_str(&format!("\n{}\n", text));
+        }
+    }
+    out
+}
+
+pub fn packet_html(name: &str, items: &[PacketItem]) -> String {
+    let mut out = format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body {{ max-width: 42em; margin: 2em auto; padding: 0 1em; font: 18px/1.6 Georgia, serif; color: #222; }}
+img, video {{ max-width: 100%; height: auto; }}
+.byline, .contents {{ color: #777; font: 14px sans-serif; }}
+.summary {{ background: #f6f6f6; padding: 0.5em 1em 0.5em 2em; font-size: 16px; }}
+.note {{ border-left: 3px solid #e0b000; margin: 1em 0; padding: 0 1em; font-style: italic; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<p class="byline">{heading}</p>
+<ol class="contents">
+"#,
+        title = escape_html(name),
+        heading = escape_html(&heading(items)),
+    );
+    for (n, item) in items.iter().enumerate() {
+        out.push_str(&format!(
+            "<li><a href=\"#article-{}\">{}</a></li>\n",
+            n + 1,
+            escape_html(&item.article.title)
+        ));
+    }
+    out.push_str("</ol>\n");
+
+    for (n, item) in items.iter().enumerate() {
+        let article = &item.article;
+        out.push_str(&format!(
+            "<h2 id=\"article-{}\">{}</h2>\n<p class=\"byline\"><a href=\"{}\">{}</a>",
+            n + 1,
+            escape_html(&article.title),
+            escape_html(&article.url),
+            escape_html(&article.url)
+        ));
+        let byline = byline(article);
+        if !byline.is_empty() {
+            out.push_str(&format!(" · {}", escape_html(&byline)));
+        }
+        out.push_str("</p>\n");
+        let points = summary_points(item);
+        if !points.is_empty() {
+            out.push_str("<ul class=\"summary\">\n");
+            for point in points {
+                out.push_str(&format!("<li>{}</li>\n", escape_html(&point)));
+            }
+            out.push_str("</ul>\n");
+        }
+        if let Some(note) = item.note.as_deref().map(str::trim).filter(|n| !n.is_empty()) {
+            out.push_str(&format!(
+                "<div class=\"note\"><p>{}</p></div>\n",
+                escape_html(note).replace("\n\n", "</p><p>").replace('\n', "<br>")
+            ));
+        }
+        out.push_str(&article_body_html(article));
+        out.push('\n');
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: i64, title: &str, summary: Option<&str>, note: Option<&str>) -> PacketItem {
+        let article: Article = serde_json::from_value(serde_json::json!({
+            "id": id, "feed_id": 1, "guid": title, "title": title, "url": format!("https://example.com/{}", id),
+            "author": null, "content": null, "content_text": "Body text", "published_at": "2026-03-02T09:00:00Z",
+            "fetched_at": "2026-03-02T10:00:00Z", "feed_title": "Wire", "is_starred": false,
+            "is_pinned": false, "reading_minutes": null, "is_queued": false, "is_read": false,
+            "full_text": null, "comments_url": null, "discussion": null, "enclosure": null,
+            "playback_secs": null, "is_played": false, "extras": null, "archive_path": null,
+            "tags": []
+        }))
+        .unwrap();
+        PacketItem {
+            article,
+            summary: summary.map(|content| Summary {
+                id,
+                article_id: id,
+                content: content.to_string(),
+                model_version: "test".to_string(),
+                provider: None,
+                generated_at: Utc::now(),
+            }),
+            note: note.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_packet_markdown_in_order_with_summaries_and_notes() {
+        let items = [
+            item(2, "Fabs expand", Some("- Two new fabs\n- In Ohio"), Some("Ask about jobs\nand water")),
+            item(1, "Prices rise", None, None),
+        ];
+        let markdown = packet_markdown("Tuesday column", &items);
+        assert!(markdown.starts_with("# Tuesday column\n\n2 articles · "), "{}", markdown);
+        assert!(markdown.contains(
+            "## 1. Fabs expand\n\nWire · 2026-03-02\n<https://example.com/2>\n\n- Two new fabs\n- In Ohio\n\n> **Note:** Ask about jobs\n> and water\n\nBody text\n"
+        ));
+        assert!(markdown.find("## 1. Fabs expand").unwrap() < markdown.find("## 2. Prices rise").unwrap());
+    }
+
+    #[test]
+    fn test_packet_html_escapes_and_links_contents() {
+        let items = [item(1, "<Chips>", Some("- Fabs & more"), Some("Check <this>"))];
+        let html = packet_html("Research", &items);
+        assert!(html.contains("<li><a href=\"#article-1\">&lt;Chips&gt;</a></li>"));
+        assert!(html.contains("<h2 id=\"article-1\">&lt;Chips&gt;</h2>"));
+        assert!(html.contains("<li>Fabs &amp; more</li>"));
+        assert!(html.contains("<div class=\"note\"><p>Check &lt;this&gt;</p></div>"));
+        assert!(PacketFormat::parse("EPUB").is_ok() && PacketFormat::parse("pdf").is_err());
+    }
+}
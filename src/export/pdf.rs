@@ -0,0 +1,100 @@
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use tokio::process::Command;
+
+use super::{Embed, Templates};
+use crate::error::{AppError, Result};
+use crate::models::Article;
+
+/// Renders articles to PDF by handing an HTML copy to an external converter
+/// (WeasyPrint by default)
+#[derive(Clone)]
+pub struct PdfExporter {
+    template: String,
+    dir: PathBuf,
+    templates: Arc<Templates>,
+}
+
+impl PdfExporter {
+    pub fn new(template: &str, dir: PathBuf, templates: Arc<Templates>) -> Self {
+        Self {
+            template: template.to_string(),
+            dir,
+            templates,
+        }
+    }
+
+    /// Write `<dir>/<export_filename>.pdf`, with whatever `embed` carries
+    /// above the article, and return its path
+    pub async fn export(&self, article: &Article, embed: &Embed) -> Result<PathBuf> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let output = self.dir.join(super::export_file_name(&self.templates, article, "pdf"));
+
+        let input = std::env::temp_dir().join(format!(
+            "beatcheck-export-{}-{}.html",
+            std::process::id(),
+            article.id
+        ));
+        let html = super::html_document(&self.templates, article, embed, &super::article_body_html(article), &[]);
+        tokio::fs::write(&input, html).await?;
+
+        let result = convert(&self.template, "export.pdf_command", &input, &output).await;
+        let _ = tokio::fs::remove_file(&input).await;
+        result.map(|()| output)
+    }
+}
+
+/// Run the converter command `template` (the `setting` it comes from, for
+/// errors) from `input` to `output`
+pub(super) async fn convert(template: &str, setting: &str, input: &Path, output: &Path) -> Result<()> {
+    let args = converter_args(template, input, output);
+    let (program, rest) = args
+        .split_first()
+        .ok_or_else(|| AppError::Config(format!("{} is empty", setting)))?;
+
+    let result = Command::new(program)
+        .args(rest)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .await?;
+    if !result.status.success() {
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        let reason = stderr.lines().last().unwrap_or("no output").trim().to_string();
+        return Err(anyhow!("{} exited with {}: {}", program, result.status, reason).into());
+    }
+    Ok(())
+}
+
+/// Split the template on whitespace and fill in each argument separately, so
+/// paths with spaces stay one argument
+fn converter_args(template: &str, input: &Path, output: &Path) -> Vec<String> {
+    let input = input.to_string_lossy();
+    let output = output.to_string_lossy();
+    template
+        .split_whitespace()
+        .map(|arg| arg.replace("{input}", &input).replace("{output}", &output))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_converter_args_keep_paths_whole() {
+        let args = converter_args(
+            "weasyprint --quiet {input} {output}",
+            Path::new("/tmp/in.html"),
+            Path::new("/home/me/My Papers/1-story.pdf"),
+        );
+        assert_eq!(
+            args,
+            ["weasyprint", "--quiet", "/tmp/in.html", "/home/me/My Papers/1-story.pdf"]
+        );
+    }
+}
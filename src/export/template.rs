@@ -0,0 +1,279 @@
+//! A small Handlebars-style template language for export formats:
+//!
+//! - `{{name}}` inserts a value (HTML-escaped in HTML templates);
+//!   `{{{name}}}` inserts it unescaped
+//! - `{{json name}}` inserts it as a JSON string or array, which is also
+//!   valid YAML, for front-matter
+//! - `{{indent name}}` inserts it with every line indented two spaces
+//! - `{{#if name}}...{{else}}...{{/if}}` tests for a non-empty value
+//! - `{{#each name}}...{{this}}...{{/each}}` repeats over a list
+
+use std::collections::BTreeMap;
+
+use crate::error::{AppError, Result};
+
+/// Values a template can refer to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Text(String),
+    List(Vec<String>),
+}
+
+impl Value {
+    fn is_truthy(&self) -> bool {
+        match self {
+            Value::Text(text) => !text.is_empty(),
+            Value::List(items) => !items.is_empty(),
+        }
+    }
+
+    fn as_text(&self) -> String {
+        match self {
+            Value::Text(text) => text.clone(),
+            Value::List(items) => items.join(", "),
+        }
+    }
+}
+
+impl From<String> for Value {
+    fn from(text: String) -> Self {
+        Value::Text(text)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(text: &str) -> Self {
+        Value::Text(text.to_string())
+    }
+}
+
+impl From<Vec<String>> for Value {
+    fn from(items: Vec<String>) -> Self {
+        Value::List(items)
+    }
+}
+
+pub type Context = BTreeMap<&'static str, Value>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Escape {
+    None,
+    Html,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    Text(String),
+    Var { name: String, helper: Option<String>, raw: bool },
+    If { name: String, then: Vec<Node>, otherwise: Vec<Node> },
+    Each { name: String, body: Vec<Node> },
+}
+
+/// A parsed template, checked for unknown blocks and unbalanced tags up front
+#[derive(Debug, Clone)]
+pub struct Template {
+    nodes: Vec<Node>,
+    escape: Escape,
+}
+
+impl Template {
+    pub fn parse(source: &str, escape: Escape) -> Result<Self> {
+        let mut tokens = tokenize(source)?.into_iter();
+        let (nodes, end) = parse_nodes(&mut tokens)?;
+        if let Some(end) = end {
+            return Err(template_error(format!("unexpected {{{{{}}}}}", end)));
+        }
+        Ok(Self { nodes, escape })
+    }
+
+    pub fn render(&self, context: &Context) -> String {
+        let mut out = String::new();
+        render_nodes(&self.nodes, context, None, self.escape, &mut out);
+        out
+    }
+}
+
+fn template_error(message: String) -> AppError {
+    AppError::Config(format!("Template error: {}", message))
+}
+
+#[derive(Debug)]
+enum Token {
+    Text(String),
+    /// Tag contents, and whether it used triple braces
+    Tag(String, bool),
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut rest = source;
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            tokens.push(Token::Text(rest[..start].to_string()));
+        }
+        let raw = rest[start..].starts_with("{{{");
+        let (open, close) = if raw { ("{{{", "}}}") } else { ("{{", "}}") };
+        let after = &rest[start + open.len()..];
+        let end = after
+            .find(close)
+            .ok_or_else(|| template_error(format!("unclosed {} tag", open)))?;
+        tokens.push(Token::Tag(after[..end].trim().to_string(), raw));
+        rest = &after[end + close.len()..];
+    }
+    if !rest.is_empty() {
+        tokens.push(Token::Text(rest.to_string()));
+    }
+    Ok(tokens)
+}
+
+/// Parse until the end of input or a closing tag (`/if`, `/each`, `else`),
+/// which is returned so the enclosing block can check it
+fn parse_nodes(tokens: &mut std::vec::IntoIter<Token>) -> Result<(Vec<Node>, Option<String>)> {
+    let mut nodes = Vec::new();
+    while let Some(token) = tokens.next() {
+        let (tag, raw) = match token {
+            Token::Text(text) => {
+                nodes.push(Node::Text(text));
+                continue;
+            }
+            Token::Tag(tag, raw) => (tag, raw),
+        };
+
+        if tag.starts_with('/') || tag == "else" {
+            return Ok((nodes, Some(tag)));
+        }
+        if let Some(block) = tag.strip_prefix('#') {
+            let (kind, name) = block.split_once(char::is_whitespace).unwrap_or((block, ""));
+            let name = name.trim().to_string();
+            if name.is_empty() {
+                return Err(template_error(format!("{{{{#{}}}}} needs a name", kind)));
+            }
+            match kind {
+                "if" => {
+                    let (then, end) = parse_nodes(tokens)?;
+                    let otherwise = match end.as_deref() {
+                        Some("/if") => Vec::new(),
+                        Some("else") => match parse_nodes(tokens)? {
+                            (otherwise, Some(end)) if end == "/if" => otherwise,
+                            _ => return Err(template_error("unclosed {{#if}}".to_string())),
+                        },
+                        _ => return Err(template_error("unclosed {{#if}}".to_string())),
+                    };
+                    nodes.push(Node::If { name, then, otherwise });
+                }
+                "each" => match parse_nodes(tokens)? {
+                    (body, Some(end)) if end == "/each" => nodes.push(Node::Each { name, body }),
+                    _ => return Err(template_error("unclosed {{#each}}".to_string())),
+                },
+                _ => return Err(template_error(format!("unknown block {{{{#{}}}}}", kind))),
+            }
+            continue;
+        }
+
+        let (helper, name) = match tag.split_once(char::is_whitespace) {
+            Some((helper, name)) => (Some(helper.to_string()), name.trim().to_string()),
+            None => (None, tag),
+        };
+        if let Some(helper) = helper.as_deref().filter(|h| !matches!(*h, "json" | "indent")) {
+            return Err(template_error(format!("unknown helper \"{}\"", helper)));
+        }
+        nodes.push(Node::Var { name, helper, raw });
+    }
+    Ok((nodes, None))
+}
+
+fn lookup<'a>(name: &str, context: &'a Context, this: Option<&'a Value>) -> Option<&'a Value> {
+    if name == "this" {
+        this
+    } else {
+        context.get(name)
+    }
+}
+
+fn render_nodes(nodes: &[Node], context: &Context, this: Option<&Value>, escape: Escape, out: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Var { name, helper, raw } => {
+                let Some(value) = lookup(name, context, this) else {
+                    continue;
+                };
+                let text = match helper.as_deref() {
+                    Some("json") => match value {
+                        Value::Text(text) => serde_json::to_string(text).unwrap_or_default(),
+                        Value::List(items) => serde_json::to_string(items).unwrap_or_default(),
+                    },
+                    Some(_) => value
+                        .as_text()
+                        .lines()
+                        .map(|line| format!("  {}", line))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                    None => value.as_text(),
+                };
+                match escape {
+                    Escape::Html if !raw => out.push_str(&super::escape_html(&text)),
+                    _ => out.push_str(&text),
+                }
+            }
+            Node::If { name, then, otherwise } => {
+                let truthy = lookup(name, context, this).is_some_and(Value::is_truthy);
+                render_nodes(if truthy { then } else { otherwise }, context, this, escape, out);
+            }
+            Node::Each { name, body } => {
+                let items = match lookup(name, context, this) {
+                    Some(Value::List(items)) => items.clone(),
+                    Some(Value::Text(text)) if !text.is_empty() => vec![text.clone()],
+                    _ => Vec::new(),
+                };
+                for item in items {
+                    render_nodes(body, context, Some(&Value::Text(item)), escape, out);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> Context {
+        Context::from([
+            ("title", Value::from("Tom & \"Jerry\"")),
+            ("author", Value::from("")),
+            ("tags", Value::from(vec!["rss".to_string(), "cats".to_string()])),
+            ("summary", Value::from("- one\n- two")),
+        ])
+    }
+
+    #[test]
+    fn test_variables_helpers_and_escaping() {
+        let template = Template::parse("{{title}}|{{{title}}}", Escape::Html).unwrap();
+        assert_eq!(template.render(&context()), "Tom &amp; &quot;Jerry&quot;|Tom & \"Jerry\"");
+
+        let template =
+            Template::parse("{{json title}} {{json tags}} {{missing}}\n{{indent summary}}", Escape::None).unwrap();
+        assert_eq!(
+            template.render(&context()),
+            "\"Tom & \\\"Jerry\\\"\" [\"rss\",\"cats\"] \n  - one\n  - two"
+        );
+    }
+
+    #[test]
+    fn test_if_else_and_each_blocks() {
+        let template = Template::parse(
+            "{{#if author}}by {{author}}{{else}}anon{{/if}}:{{#each tags}} #{{this}}{{/each}}{{#if tags}}!{{/if}}",
+            Escape::None,
+        )
+        .unwrap();
+        assert_eq!(template.render(&context()), "anon: #rss #cats!");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for source in ["{{#if title}}open", "{{title", "{{/each}}", "{{#loop x}}{{/loop}}", "{{upper title}}"] {
+            assert!(Template::parse(source, Escape::None).is_err(), "{}", source);
+        }
+    }
+}
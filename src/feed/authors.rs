@@ -0,0 +1,100 @@
+//! Clean up feed bylines ("By Jane Doe and John Roe", "jane@example.com
+//! (Jane Doe)") into one name per author, so a reporter's articles can be
+//! found across feeds.
+
+/// Name suffixes that a comma split would otherwise turn into authors
+const SUFFIXES: &[&str] = &["jr", "sr", "ii", "iii", "iv", "phd", "md"];
+
+/// Each distinct author named by `bylines`, in order. Email addresses and a
+/// leading "By" are dropped, and a byline naming several people is split on
+/// commas, semicolons, "&" and "and".
+pub fn normalize_authors(bylines: &[String]) -> Vec<String> {
+    let mut authors: Vec<String> = Vec::new();
+    for byline in bylines {
+        for name in split_byline(&strip_emails(byline)) {
+            if !authors.iter().any(|a| a.eq_ignore_ascii_case(&name)) {
+                authors.push(name);
+            }
+        }
+    }
+    authors
+}
+
+/// RSS wants "email (Name)"; others write "Name <email>" or just the address
+fn strip_emails(byline: &str) -> String {
+    if let (Some(open), Some(close)) = (byline.find('('), byline.rfind(')')) {
+        if open < close && byline[..open].contains('@') {
+            return byline[open + 1..close].to_string();
+        }
+    }
+    let mut text = String::new();
+    let mut in_angle = false;
+    for c in byline.chars() {
+        match c {
+            '<' => in_angle = true,
+            '>' => in_angle = false,
+            _ if !in_angle => text.push(c),
+            _ => {}
+        }
+    }
+    text.split_whitespace()
+        .filter(|word| !word.contains('@'))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn split_byline(byline: &str) -> Vec<String> {
+    byline
+        .split([',', ';', '&', '|', '\n'])
+        .flat_map(|part| {
+            // " and " between names, whatever the case; not "Anderson"
+            let words: Vec<&str> = part.split_whitespace().collect();
+            words
+                .split(|w| w.eq_ignore_ascii_case("and"))
+                .map(|run| run.join(" "))
+                .collect::<Vec<_>>()
+        })
+        .filter_map(|part| clean_name(&part))
+        .collect()
+}
+
+fn clean_name(part: &str) -> Option<String> {
+    let mut name = part.trim();
+    for prefix in ["by ", "by:", "written by "] {
+        if name.len() > prefix.len() && name[..prefix.len()].eq_ignore_ascii_case(prefix) {
+            name = name[prefix.len()..].trim_start();
+        }
+    }
+    let name = name.trim_matches(|c: char| !c.is_alphanumeric() && c != '.');
+    let bare = name.trim_end_matches('.').to_ascii_lowercase();
+    if bare.is_empty() || !name.chars().any(char::is_alphabetic) || SUFFIXES.contains(&bare.as_str()) {
+        return None;
+    }
+    Some(name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalize(bylines: &[&str]) -> Vec<String> {
+        normalize_authors(&bylines.iter().map(|b| b.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_normalize_strips_emails_and_by() {
+        assert_eq!(normalize(&["jane@example.com (Jane Doe)"]), ["Jane Doe"]);
+        assert_eq!(normalize(&["Jane Doe <jane@example.com>"]), ["Jane Doe"]);
+        assert_eq!(normalize(&["BY Jane Doe"]), ["Jane Doe"]);
+        assert_eq!(normalize(&["editor@example.com"]), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_normalize_splits_multiple_authors() {
+        assert_eq!(
+            normalize(&["By Jane Doe, John Roe and Ann Anderson", "jane doe"]),
+            ["Jane Doe", "John Roe", "Ann Anderson"]
+        );
+        assert_eq!(normalize(&["Martin Luther King, Jr. & Bo"]), ["Martin Luther King", "Bo"]);
+    }
+}
@@ -0,0 +1,122 @@
+//! Read-later backlogs from Pocket's HTML export and browsers' bookmark
+//! files, which share the old Netscape bookmark markup: a link per item
+//! with its save time and tags as attributes.
+
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use scraper::{Html, Selector};
+
+use crate::error::Result;
+
+/// One saved link
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bookmark {
+    pub url: String,
+    pub title: String,
+    pub saved_at: Option<DateTime<Utc>>,
+    pub tags: Vec<String>,
+    pub shelf: Shelf,
+}
+
+/// Where a link was saved
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shelf {
+    /// Pocket's "Unread" list
+    ToRead,
+    /// Pocket's "Read Archive"
+    Read,
+    /// A browser bookmark
+    Bookmarked,
+}
+
+pub fn parse_bookmarks_file(path: &Path) -> Result<Vec<Bookmark>> {
+    let content = std::fs::read_to_string(path)?;
+    Ok(parse_bookmarks(&content))
+}
+
+/// Every http(s) link in the file, in order. Pocket puts `time_added` and
+/// `tags` on each link; browsers write `ADD_DATE` and (Firefox) `TAGS`.
+pub fn parse_bookmarks(html: &str) -> Vec<Bookmark> {
+    let document = Html::parse_document(html);
+    let selector = Selector::parse("h1, a[href]").unwrap();
+
+    let mut bookmarks = Vec::new();
+    let mut shelf = Shelf::Bookmarked;
+    for element in document.select(&selector) {
+        let text = element.text().collect::<String>().trim().to_string();
+        if element.value().name() == "h1" {
+            shelf = match text.to_lowercase().as_str() {
+                "unread" => Shelf::ToRead,
+                "read archive" => Shelf::Read,
+                _ => Shelf::Bookmarked,
+            };
+            continue;
+        }
+        let attr = |name: &str| element.value().attr(name);
+        let Some(url) = attr("href").map(str::trim).filter(|u| u.starts_with("http://") || u.starts_with("https://"))
+        else {
+            continue;
+        };
+        let saved_at = attr("time_added")
+            .or(attr("add_date"))
+            .and_then(|t| t.trim().parse::<i64>().ok())
+            .and_then(|secs| DateTime::from_timestamp(secs, 0));
+        let tags = attr("tags")
+            .unwrap_or_default()
+            .split(',')
+            .map(|t| t.trim().to_lowercase())
+            .filter(|t| !t.is_empty())
+            .collect();
+        bookmarks.push(Bookmark {
+            url: url.to_string(),
+            title: if text.is_empty() { url.to_string() } else { text },
+            saved_at,
+            tags,
+            shelf,
+        });
+    }
+    bookmarks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pocket_export() {
+        let html = r#"<!DOCTYPE html><html><head><title>Pocket Export</title></head><body>
+            <h1>Unread</h1>
+            <ul><li><a href="https://example.com/a" time_added="1700000000" tags="Rust,compilers">Rust news</a></li></ul>
+            <h1>Read Archive</h1>
+            <ul><li><a href="https://example.com/b" time_added="1600000000" tags="">https://example.com/b</a></li></ul>
+            </body></html>"#;
+        let bookmarks = parse_bookmarks(html);
+        assert_eq!(bookmarks.len(), 2);
+        assert_eq!(bookmarks[0].title, "Rust news");
+        assert_eq!(bookmarks[0].tags, ["rust", "compilers"]);
+        assert_eq!(bookmarks[0].saved_at.unwrap().timestamp(), 1_700_000_000);
+        assert_eq!(bookmarks[0].shelf, Shelf::ToRead);
+        assert_eq!(bookmarks[1].shelf, Shelf::Read);
+        assert!(bookmarks[1].tags.is_empty());
+    }
+
+    #[test]
+    fn test_parse_browser_bookmarks() {
+        let html = r#"<!DOCTYPE NETSCAPE-Bookmark-file-1>
+            <TITLE>Bookmarks</TITLE><H1>Bookmarks Menu</H1>
+            <DL><p>
+                <DT><H3 ADD_DATE="1500000000">Reading</H3>
+                <DL><p>
+                    <DT><A HREF="https://example.com/essay" ADD_DATE="1650000000" TAGS="longread">An essay</A>
+                    <DT><A HREF="place:sort=8">Recent tags</A>
+                    <DT><A HREF="javascript:void(0)">Bookmarklet</A>
+                </DL><p>
+            </DL>"#;
+        let bookmarks = parse_bookmarks(html);
+        assert_eq!(bookmarks.len(), 1);
+        assert_eq!(bookmarks[0].url, "https://example.com/essay");
+        assert_eq!(bookmarks[0].tags, ["longread"]);
+        assert_eq!(bookmarks[0].shelf, Shelf::Bookmarked);
+    }
+}
@@ -0,0 +1,143 @@
+use crate::models::{Feed, NewFeed};
+
+/// Why a new feed looks like one we already have
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateKind {
+    /// The feed URLs are the same once normalized
+    SameUrl,
+    /// Different feed URLs, but both feeds point at the same site
+    SameSite,
+}
+
+/// An existing subscription a new feed collides with
+#[derive(Debug, Clone, Copy)]
+pub struct Duplicate<'a> {
+    pub feed: &'a Feed,
+    pub kind: DuplicateKind,
+}
+
+impl Duplicate<'_> {
+    /// Status line for the user, e.g. "Already subscribed: The Verge"
+    pub fn describe(&self) -> String {
+        match self.kind {
+            DuplicateKind::SameUrl => format!("Already subscribed: {}", self.feed.display_title()),
+            DuplicateKind::SameSite => format!("Same site as {}", self.feed.display_title()),
+        }
+    }
+}
+
+/// Comparable form of a feed or site URL: scheme, "www.", default ports,
+/// fragments and trailing slashes don't count, and the host is lowercased.
+/// Unparseable input is only trimmed.
+pub fn normalize_url(url: &str) -> String {
+    let Ok(parsed) = url::Url::parse(url.trim()) else {
+        return url.trim().trim_end_matches('/').to_string();
+    };
+    let host = parsed.host_str().unwrap_or("").to_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    let port = parsed.port().map(|p| format!(":{}", p)).unwrap_or_default();
+    let path = parsed.path().trim_end_matches('/');
+    match parsed.query() {
+        Some(query) => format!("{}{}{}?{}", host, port, path, query),
+        None => format!("{}{}{}", host, port, path),
+    }
+}
+
+/// The subscription `url` (a feed URL as typed or as found) duplicates, if any
+pub fn find_by_url<'a>(feeds: &'a [Feed], url: &str) -> Option<&'a Feed> {
+    let url = normalize_url(url);
+    feeds.iter().find(|f| normalize_url(&f.url) == url)
+}
+
+/// Check a discovered feed against existing subscriptions, by feed URL first
+/// and then by the site it belongs to
+pub fn find_duplicate<'a>(feeds: &'a [Feed], new_feed: &NewFeed) -> Option<Duplicate<'a>> {
+    if let Some(feed) = find_by_url(feeds, &new_feed.url) {
+        return Some(Duplicate {
+            feed,
+            kind: DuplicateKind::SameUrl,
+        });
+    }
+
+    let site = normalize_url(new_feed.site_url.as_deref()?);
+    feeds
+        .iter()
+        .find(|f| f.site_url.as_deref().is_some_and(|s| normalize_url(s) == site))
+        .map(|feed| Duplicate {
+            feed,
+            kind: DuplicateKind::SameSite,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn feed(url: &str, site_url: Option<&str>) -> Feed {
+        Feed {
+            id: 1,
+            title: "Existing".to_string(),
+            url: url.to_string(),
+            site_url: site_url.map(str::to_string),
+            description: None,
+            last_fetched: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            retry_after: None,
+            custom_title: None,
+            folder: None,
+            refresh_minutes: None,
+            paused: false,
+            tags: Vec::new(),
+            posts_per_day: None,
+            last_post_at: None,
+            unread: 0,
+            reading: Default::default(),
+            priority: Default::default(),
+            source: Default::default(),
+            reader_url: None,
+            missing_count: 0,
+            moved_to: None,
+            repaired: false,
+        }
+    }
+
+    fn new_feed(url: &str, site_url: Option<&str>) -> NewFeed {
+        NewFeed {
+            title: "New".to_string(),
+            url: url.to_string(),
+            site_url: site_url.map(str::to_string),
+            description: None,
+        }
+    }
+
+    #[test]
+    fn test_normalize_url_ignores_cosmetic_differences() {
+        let expected = "example.com/feed";
+        for url in [
+            "https://example.com/feed",
+            "http://www.Example.com/feed/",
+            "https://example.com:443/feed#top",
+            " https://EXAMPLE.com/feed ",
+        ] {
+            assert_eq!(normalize_url(url), expected, "{}", url);
+        }
+        assert_eq!(normalize_url("https://example.com/feed?tag=rust"), "example.com/feed?tag=rust");
+        assert_eq!(normalize_url("https://example.com:8080/"), "example.com:8080");
+    }
+
+    #[test]
+    fn test_find_duplicate_by_url_then_site() {
+        let feeds = vec![feed("https://www.example.com/rss/", Some("https://example.com/"))];
+
+        let dup = find_duplicate(&feeds, &new_feed("http://example.com/rss", None)).unwrap();
+        assert_eq!(dup.kind, DuplicateKind::SameUrl);
+
+        let other = new_feed("https://example.com/atom.xml", Some("http://www.example.com"));
+        assert_eq!(find_duplicate(&feeds, &other).unwrap().kind, DuplicateKind::SameSite);
+
+        let unrelated = new_feed("https://other.example/feed", Some("https://other.example"));
+        assert!(find_duplicate(&feeds, &unrelated).is_none());
+    }
+}
@@ -0,0 +1,390 @@
+//! Named entities for incoming articles: people, organizations and stock
+//! tickers, found with capitalization and context heuristics rather than a
+//! trained model, so they come for free at ingest.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::models::{Entity, EntityKind};
+
+/// Entities kept per article
+pub const MAX_ENTITIES: usize = 8;
+
+/// Longest capitalized run, in words, taken as one name
+const MAX_NAME_WORDS: usize = 4;
+
+/// `$NVDA`, or an exchange listing like "(NASDAQ: NVDA)"
+static TICKER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\$([A-Z]{1,5}(?:\.[A-Z])?)\b|\((?:NASDAQ|Nasdaq|NYSE|AMEX|LSE|TSX)\s*:\s*([A-Z]{1,5}(?:\.[A-Z])?)\)")
+        .unwrap()
+});
+
+/// Capitalized words that start sentences or name dates, not entities
+const COMMON_WORDS: &[&str] = &[
+    "a", "after", "also", "an", "and", "as", "at", "before", "but", "by", "during", "each", "every",
+    "for", "from", "he", "her", "here", "his", "how", "however", "i", "if", "in", "it", "its",
+    "last", "meanwhile", "my", "next", "no", "not", "now", "of", "on", "one", "or", "our",
+    "she", "so", "some", "still", "that", "the", "their", "there", "these", "they", "this",
+    "those", "to", "today", "tomorrow", "too", "under", "update", "updated", "we", "what", "when",
+    "where", "which", "while", "who", "why", "with", "yes", "yesterday", "you", "your", "monday",
+    "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday", "january", "february",
+    "march", "april", "may", "june", "july", "august", "september", "october", "november",
+    "december",
+];
+
+/// All-caps words that are jargon rather than organizations
+const COMMON_ACRONYMS: &[&str] = &[
+    "AI", "AM", "API", "CEO", "CFO", "COO", "CPU", "CTO", "EU", "FAQ", "GPU", "HTML", "HTTP",
+    "IPO", "OK", "PC", "PDF", "PM", "RSS", "TV", "UK", "UN", "URL", "US", "USA", "USB", "VR",
+];
+
+/// Words before a name that mark it as a person (dropped from the name)
+const PERSON_TITLES: &[&str] = &[
+    "mr", "mrs", "ms", "dr", "prof", "professor", "sen", "senator", "rep", "gov", "governor",
+    "president", "judge", "ceo", "chancellor", "minister", "secretary", "chairman", "chairwoman",
+];
+
+/// Last words that mark a name as an organization. Legal suffixes are
+/// dropped from the name so "Nvidia Corp" and "Nvidia" are one entity.
+const ORG_SUFFIXES: &[(&str, bool)] = &[
+    ("inc", true),
+    ("corp", true),
+    ("corporation", true),
+    ("co", true),
+    ("ltd", true),
+    ("llc", true),
+    ("plc", true),
+    ("gmbh", true),
+    ("ag", true),
+    ("company", false),
+    ("group", false),
+    ("labs", false),
+    ("technologies", false),
+    ("systems", false),
+    ("holdings", false),
+    ("bank", false),
+    ("university", false),
+    ("institute", false),
+    ("foundation", false),
+    ("agency", false),
+    ("association", false),
+    ("capital", false),
+    ("ventures", false),
+    ("motors", false),
+    ("airlines", false),
+    ("studios", false),
+    ("commission", false),
+    ("department", false),
+];
+
+/// Verbs that follow a person's name in reporting ("Huang said")
+const SPEECH_VERBS: &[&str] = &["said", "says", "told", "wrote", "added", "argued", "announced"];
+
+/// Abbreviations whose trailing period doesn't end a sentence
+const ABBREVIATIONS: &[&str] = &["mr", "mrs", "ms", "dr", "prof", "sen", "rep", "gov", "inc", "corp", "co", "ltd", "jr", "sr", "st", "vs"];
+
+/// Joining words allowed inside a name ("Bank of America", "AT & T")
+const CONNECTORS: &[&str] = &["of", "&", "de", "van", "von", "der"];
+
+#[derive(Debug)]
+struct Word {
+    text: String,
+    sentence_start: bool,
+}
+
+#[derive(Debug, Default)]
+struct Candidate {
+    name: String,
+    words: usize,
+    mentions: usize,
+    kind: Option<EntityKind>,
+    /// Opens a sentence, so the capital may not mean a name
+    sentence_start: bool,
+}
+
+/// Up to `limit` entities mentioned in an article, most mentioned first.
+/// Names found in the text count an extra mention if the title has them.
+pub fn extract_entities(title: &str, text: Option<&str>, limit: usize) -> Vec<Entity> {
+    let text = text.unwrap_or("");
+    let mut candidates: HashMap<(String, Option<EntityKind>), Candidate> = HashMap::new();
+
+    for caps in TICKER.captures_iter(title).chain(TICKER.captures_iter(text)) {
+        let symbol = caps.get(1).or(caps.get(2)).map_or("", |m| m.as_str());
+        let candidate = candidates
+            .entry((symbol.to_string(), Some(EntityKind::Ticker)))
+            .or_insert_with(|| Candidate {
+                name: symbol.to_string(),
+                words: 1,
+                kind: Some(EntityKind::Ticker),
+                ..Default::default()
+            });
+        candidate.mentions += 1;
+    }
+
+    // Headlines in Title Case capitalize everything, so only scan them
+    // when they're written as a sentence
+    let mut sources = vec![text];
+    if !is_title_case(title) {
+        sources.push(title);
+    }
+    let mut names: Vec<Candidate> = Vec::new();
+    for source in sources {
+        for line in source.lines() {
+            // html2text renders links as numbered footnotes ("[1]: https://...")
+            if line.trim_start().starts_with('[') && line.contains("]: ") {
+                continue;
+            }
+            for segment in segments(line) {
+                scan_segment(&segment, &mut names);
+            }
+        }
+    }
+
+    // Single words that are part of a longer name ("Huang" after "Jensen
+    // Huang") count toward it, and make a two- or three-word name a person
+    let (multi, single): (Vec<Candidate>, Vec<Candidate>) = names.into_iter().partition(|c| c.words > 1);
+    let mut merged: HashMap<String, Candidate> = HashMap::new();
+    for name in multi {
+        let entry = merged.entry(name.name.to_lowercase()).or_insert_with(|| Candidate {
+            name: name.name.clone(),
+            words: name.words,
+            ..Default::default()
+        });
+        entry.mentions += 1;
+        entry.kind = entry.kind.or(name.kind);
+    }
+    for name in single {
+        let word = name.name.to_lowercase();
+        let covering = merged
+            .values_mut()
+            .find(|c| c.kind != Some(EntityKind::Organization) && c.name.to_lowercase().split(' ').any(|w| w == word));
+        match covering {
+            Some(person) => {
+                person.mentions += 1;
+                if person.words <= 3 {
+                    person.kind = person.kind.or(Some(EntityKind::Person));
+                }
+            }
+            // A lone capitalized word opening a sentence is usually just that
+            None if name.sentence_start && name.kind.is_none() => {}
+            None => {
+                let entry = merged.entry(word).or_insert_with(|| Candidate {
+                    name: name.name.clone(),
+                    words: 1,
+                    ..Default::default()
+                });
+                entry.mentions += 1;
+                entry.kind = entry.kind.or(name.kind);
+            }
+        }
+    }
+
+    let title_lower = title.to_lowercase();
+    for mut candidate in merged.into_values() {
+        // A symbol we already have as a ticker
+        if candidates.contains_key(&(candidate.name.clone(), Some(EntityKind::Ticker))) {
+            continue;
+        }
+        if title_lower.contains(&candidate.name.to_lowercase()) {
+            candidate.mentions += 1;
+        }
+        // Without a hint, a repeated name is most likely an organization
+        let kind = match candidate.kind {
+            Some(kind) => kind,
+            None if candidate.mentions >= 2 => EntityKind::Organization,
+            None => continue,
+        };
+        candidates.insert((candidate.name.to_lowercase(), Some(kind)), Candidate { kind: Some(kind), ..candidate });
+    }
+
+    let mut found: Vec<Candidate> = candidates.into_values().collect();
+    found.sort_by(|a, b| b.mentions.cmp(&a.mentions).then_with(|| a.name.cmp(&b.name)));
+    found
+        .into_iter()
+        .take(limit)
+        .filter_map(|c| Some(Entity { name: c.name, kind: c.kind? }))
+        .collect()
+}
+
+/// Whether most words in `title` are capitalized
+fn is_title_case(title: &str) -> bool {
+    let words: Vec<&str> = title
+        .split_whitespace()
+        .filter(|w| w.chars().count() > 3)
+        .collect();
+    let capitalized = words.iter().filter(|w| w.starts_with(|c: char| c.is_uppercase())).count();
+    words.len() >= 2 && capitalized * 4 >= words.len() * 3
+}
+
+/// Split `line` into comma/period-free stretches of words, noting which
+/// words start a sentence
+fn segments(line: &str) -> Vec<Vec<Word>> {
+    let mut segments = Vec::new();
+    let mut current: Vec<Word> = Vec::new();
+    let mut word = String::new();
+    let mut sentence_start = true;
+
+    // The trailing '.' ends the last segment
+    for c in line.chars().chain(std::iter::once('.')) {
+        if c.is_alphanumeric() || matches!(c, '\'' | '\u{2019}' | '-') {
+            word.push(c);
+            continue;
+        }
+        let ended = std::mem::take(&mut word);
+        let cleaned = clean_word(&ended);
+        if !cleaned.is_empty() {
+            current.push(Word {
+                text: cleaned.to_string(),
+                sentence_start,
+            });
+            sentence_start = false;
+        }
+        if c == '&' {
+            current.push(Word {
+                text: "&".to_string(),
+                sentence_start: false,
+            });
+        } else if c == '.' && ABBREVIATIONS.contains(&cleaned.to_lowercase().as_str()) {
+            // "Mr. Smith" and "Acme Inc. said" stay in one piece
+        } else if !c.is_whitespace() {
+            if !current.is_empty() {
+                segments.push(std::mem::take(&mut current));
+            }
+            if matches!(c, '.' | '!' | '?' | ':') {
+                sentence_start = true;
+            }
+        }
+    }
+    segments
+}
+
+/// Drop quotes, dashes and possessive "'s" around a word
+fn clean_word(word: &str) -> &str {
+    let word = word.trim_matches(|c| c == '\'' || c == '\u{2019}' || c == '-');
+    word.strip_suffix("'s")
+        .or_else(|| word.strip_suffix("\u{2019}s"))
+        .unwrap_or(word)
+}
+
+fn is_capitalized(word: &str) -> bool {
+    word.chars().count() >= 2 && word.starts_with(|c: char| c.is_uppercase())
+}
+
+/// Collect each run of capitalized words in one segment as a name
+fn scan_segment(segment: &[Word], names: &mut Vec<Candidate>) {
+    let mut i = 0;
+    while i < segment.len() {
+        if !is_capitalized(&segment[i].text) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        let mut end = i + 1;
+        while end < segment.len() {
+            let word = segment[end].text.as_str();
+            let joins = CONNECTORS.contains(&word)
+                && segment.get(end + 1).is_some_and(|next| is_capitalized(&next.text));
+            if !(is_capitalized(word) || joins) {
+                break;
+            }
+            end += 1;
+        }
+        let next = segment.get(end).map(|w| w.text.to_lowercase());
+        if let Some(name) = classify(&segment[start..end], next.as_deref()) {
+            names.push(name);
+        }
+        i = end;
+    }
+}
+
+/// Turn a capitalized run into a candidate name, using the words around
+/// it to tell people from organizations where possible
+fn classify(run: &[Word], next: Option<&str>) -> Option<Candidate> {
+    let mut words: Vec<&str> = run.iter().map(|w| w.text.as_str()).collect();
+    let mut kind = None;
+
+    while let Some(first) = words.first() {
+        let lower = first.to_lowercase();
+        if PERSON_TITLES.contains(&lower.as_str()) {
+            kind = Some(EntityKind::Person);
+        } else if !COMMON_WORDS.contains(&lower.as_str()) {
+            break;
+        }
+        words.remove(0);
+    }
+    if let Some(last) = words.last() {
+        let lower = last.to_lowercase();
+        if let Some((_, legal)) = ORG_SUFFIXES.iter().find(|(suffix, _)| *suffix == lower) {
+            kind = Some(EntityKind::Organization);
+            if *legal && words.len() > 1 {
+                words.pop();
+            }
+        }
+    }
+    while words.last().is_some_and(|w| CONNECTORS.contains(w)) {
+        words.pop();
+    }
+
+    if words.is_empty() || words.len() > MAX_NAME_WORDS {
+        return None;
+    }
+    if words.len() == 1 && COMMON_ACRONYMS.contains(&words[0]) {
+        return None;
+    }
+    let speaks = next.is_some_and(|n| SPEECH_VERBS.contains(&n));
+    if kind.is_none() && (2..=3).contains(&words.len()) && speaks {
+        kind = Some(EntityKind::Person);
+    }
+    let sentence_start = run.iter().find(|w| w.text == words[0]).is_some_and(|w| w.sentence_start);
+
+    Some(Candidate {
+        name: words.join(" "),
+        words: words.len(),
+        mentions: 1,
+        kind,
+        sentence_start: sentence_start && !speaks,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find<'a>(entities: &'a [Entity], name: &str) -> Option<&'a Entity> {
+        entities.iter().find(|e| e.name == name)
+    }
+
+    #[test]
+    fn test_people_companies_and_tickers() {
+        let title = "Nvidia Beats Estimates as Data Center Sales Soar";
+        let text = "Nvidia Corp. reported record revenue on Wednesday. Chief executive \
+                    Jensen Huang said demand was strong. Huang expects more growth, and \
+                    shares of $NVDA rose 4%. Rival Advanced Micro Devices (NASDAQ: AMD) \
+                    fell. Analysts at Bank of America raised their target for Nvidia.";
+        let entities = extract_entities(title, Some(text), MAX_ENTITIES);
+
+        assert_eq!(entities[0], Entity { name: "Nvidia".to_string(), kind: EntityKind::Organization });
+        assert_eq!(find(&entities, "Jensen Huang").unwrap().kind, EntityKind::Person);
+        assert_eq!(find(&entities, "NVDA").unwrap().kind, EntityKind::Ticker);
+        assert_eq!(find(&entities, "AMD").unwrap().kind, EntityKind::Ticker);
+        assert!(find(&entities, "Huang").is_none());
+        assert!(find(&entities, "Wednesday").is_none());
+        // Mentioned once with nothing marking what it is
+        assert!(find(&entities, "Bank of America").is_none());
+    }
+
+    #[test]
+    fn test_titles_suffixes_and_sentence_starts() {
+        let text = "Sen. Maria Cantwell questioned the deal. Apparently nobody asked \
+                    Acme Labs, which the AI startup Acme Labs owns.";
+        let entities = extract_entities("Senate looks at merger", Some(text), MAX_ENTITIES);
+
+        assert_eq!(find(&entities, "Maria Cantwell").unwrap().kind, EntityKind::Person);
+        assert_eq!(find(&entities, "Acme Labs").unwrap().kind, EntityKind::Organization);
+        assert!(find(&entities, "Apparently").is_none());
+        assert!(find(&entities, "AI").is_none());
+        assert!(extract_entities("", None, MAX_ENTITIES).is_empty());
+    }
+}
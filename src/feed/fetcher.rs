@@ -1,99 +1,251 @@
-use std::time::Duration;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
 
+use chrono::{DateTime, Utc};
 use feed_rs::parser;
-use futures::stream::{self, StreamExt};
+use futures::stream::{self, Stream, StreamExt};
 use regex::Regex;
-use reqwest::Client;
+use reqwest::header::RETRY_AFTER;
+use reqwest::{Client, ClientBuilder, StatusCode};
+
+use crate::blocklist::SharedBlocklist;
+use crate::error::{AppError, Result};
+use crate::metrics::metrics;
+use crate::models::{ArticleExtras, Comment, Enclosure, Feed, NewArticle, NewFeed};
+use super::authors::normalize_authors;
+use super::entities::{extract_entities, MAX_ENTITIES};
+use super::links::{outbound_links, MAX_LINKS};
+use super::keywords::{suggest_tags, MAX_SUGGESTED_TAGS};
+use super::repair::repair_xml;
+use super::stream::{item_date, EarlyStop, ItemSplitter, BATCH_ITEMS};
+
+/// Longest back-off we'll honour from a Retry-After header
+const MAX_RETRY_AFTER: chrono::Duration = chrono::Duration::days(7);
+
+/// 404s in a row before looking on the feed's site for where it went
+const MOVED_AFTER_MISSES: u32 = 3;
+
+/// One feed's articles, as fetched
+#[derive(Debug, Default)]
+pub struct FetchedFeed {
+    pub articles: Vec<NewArticle>,
+    /// The XML wasn't well-formed and was repaired before parsing
+    pub repaired: bool,
+}
+
+impl FetchedFeed {
+    /// Take in the next batch of a feed read in batches
+    fn add(&mut self, batch: FetchedFeed) {
+        self.articles.extend(batch.articles);
+        self.repaired |= batch.repaired;
+    }
+}
 
-use crate::error::Result;
-use crate::models::{Feed, NewArticle, NewFeed};
+/// How one feed's fetch went in `refresh_all`
+#[derive(Debug)]
+pub enum FeedRefresh {
+    Fetched(FetchedFeed),
+    /// The host asked us to back off; try again after this
+    Throttled(DateTime<Utc>),
+    /// The feed's URL answered 404 or 410; after a few in a row, with the
+    /// feed URL found on its site, when that's a different one
+    Missing(Option<String>),
+    Failed,
+}
 
 #[derive(Clone)]
 pub struct FeedFetcher {
     client: Client,
+    /// Articles matching it are dropped as they're parsed
+    blocklist: Option<SharedBlocklist>,
 }
 
 impl FeedFetcher {
     pub fn new() -> Self {
-        let client = Client::builder()
+        Self::restricted(|builder| builder)
+    }
+
+    /// A fetcher whose HTTP client `restrict` limits, for URLs that come
+    /// from someone else (`services::restrict_to_public`)
+    pub fn restricted(restrict: impl FnOnce(ClientBuilder) -> ClientBuilder) -> Self {
+        let builder = Client::builder()
             .timeout(Duration::from_secs(30))
             .connect_timeout(Duration::from_secs(10))
-            .user_agent("beatcheck/1.2.0")
-            .build()
-            .expect("Failed to create HTTP client");
+            .user_agent("beatcheck/1.2.0");
+        let client = restrict(builder).build().expect("Failed to create HTTP client");
+
+        Self { client, blocklist: None }
+    }
+
+    /// Leave out articles with blocked keywords or sites from every fetch
+    pub fn with_blocklist(mut self, blocklist: SharedBlocklist) -> Self {
+        self.blocklist = Some(blocklist);
+        self
+    }
 
-        Self { client }
+    pub async fn fetch_feed(&self, feed_id: i64, url: &str, since: Option<DateTime<Utc>>) -> Result<FetchedFeed> {
+        let started = Instant::now();
+        let mut result = self.fetch_and_parse(feed_id, url, since).await;
+        if let (Ok(fetched), Some(blocklist)) = (&mut result, &self.blocklist) {
+            fetched.articles.retain(|article| {
+                let content = article.content_text.as_deref().or(article.content.as_deref());
+                !blocklist.blocks(&article.title, content, &article.url)
+            });
+        }
+        match &result {
+            Err(AppError::RateLimited(_)) => metrics().record_throttled(),
+            _ => metrics().record_fetch(result.is_ok(), started.elapsed()),
+        }
+        result
     }
 
-    pub async fn fetch_feed(&self, feed_id: i64, url: &str) -> Result<Vec<NewArticle>> {
+    async fn fetch_and_parse(&self, feed_id: i64, url: &str, since: Option<DateTime<Utc>>) -> Result<FetchedFeed> {
         let response = self.client.get(url).send().await?;
 
+        let status = response.status();
+        if status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::SERVICE_UNAVAILABLE {
+            let retry_after = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| parse_retry_after(v, Utc::now()));
+            if let Some(until) = retry_after {
+                return Err(AppError::RateLimited(until));
+            }
+        }
+
+        if status == StatusCode::NOT_FOUND || status == StatusCode::GONE {
+            return Err(AppError::NotFound(format!("HTTP {}", status)));
+        }
         if !response.status().is_success() {
             return Err(anyhow::anyhow!("Failed to fetch feed: HTTP {}", response.status()).into());
         }
 
+        // Large feeds (podcast archives) are parsed a batch of items at a
+        // time as they download, and once they reach what's already stored
+        // the rest is left undownloaded
+        let mut response = response;
+        let mut splitter = ItemSplitter::new();
+        let mut early_stop = EarlyStop::new(since);
+        let mut batch = Vec::new();
+        let mut fetched = FetchedFeed::default();
+        'download: while let Some(chunk) = response.chunk().await? {
+            for item in splitter.push(&chunk) {
+                if early_stop.stop_at(item_date(&item)) {
+                    tracing::debug!("Stopped reading {} at already-stored items", url);
+                    break 'download;
+                }
+                batch.push(item);
+                if batch.len() == BATCH_ITEMS {
+                    fetched.add(parse_articles(feed_id, &splitter.document(&batch))?);
+                    batch.clear();
+                }
+            }
+        }
+        if !batch.is_empty() || fetched.articles.is_empty() {
+            fetched.add(parse_articles(feed_id, &splitter.document(&batch))?);
+        }
+        if let Some(document) = splitter.into_unsplit() {
+            // No items found; let the parser judge the document as a whole
+            fetched = parse_articles(feed_id, &document)?;
+        }
+
+        Ok(fetched)
+    }
+
+    /// Download a post's comment feed, oldest comment first
+    pub async fn fetch_comments(&self, url: &str) -> Result<Vec<Comment>> {
+        let response = self.client.get(url).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("Failed to fetch comments: HTTP {}", response.status()).into());
+        }
+
         let bytes = response.bytes().await?;
-        let feed = parser::parse(&bytes[..])?;
+        let feed = parse_repaired(&bytes[..])?;
 
-        let articles: Vec<NewArticle> = feed
+        let mut comments: Vec<Comment> = feed
             .entries
             .into_iter()
             .map(|entry| {
-                // Try content first, then fall back to summary
-                let content_html = entry
+                let html = entry
                     .content
                     .as_ref()
-                    .and_then(|c| c.body.as_ref())
-                    .or_else(|| entry.summary.as_ref().map(|s| &s.content));
-
-                let content_text = content_html.and_then(|html| {
-                    html2text::from_read(html.as_bytes(), 80).ok()
-                });
-
-                NewArticle {
-                    feed_id,
-                    guid: entry.id,
-                    title: entry
-                        .title
-                        .map(|t| t.content)
-                        .unwrap_or_else(|| "Untitled".to_string()),
-                    url: entry
-                        .links
-                        .first()
-                        .map(|l| l.href.clone())
-                        .unwrap_or_default(),
+                    .and_then(|c| c.body.clone())
+                    .or_else(|| entry.summary.map(|s| s.content))
+                    .unwrap_or_default();
+                let text = html2text::from_read(html.as_bytes(), 80).unwrap_or(html);
+                Comment {
                     author: entry.authors.first().map(|a| a.name.clone()),
-                    content: content_html.cloned(),
-                    content_text,
                     published_at: entry.published.or(entry.updated),
+                    text: text.trim().to_string(),
                 }
             })
             .collect();
+        comments.sort_by_key(|c| c.published_at);
 
-        Ok(articles)
+        Ok(comments)
     }
 
-    /// Refresh all feeds concurrently with rate limiting
-    pub async fn refresh_all(&self, feeds: Vec<Feed>) -> Vec<(i64, Vec<NewArticle>)> {
-        let results: Vec<_> = stream::iter(feeds)
-            .map(|feed| async move {
-                match self.fetch_feed(feed.id, &feed.url).await {
-                    Ok(articles) => {
-                        tracing::debug!("Fetched {} articles from {}", articles.len(), feed.title);
-                        Some((feed.id, articles))
+    /// Refresh feeds concurrently with rate limiting, yielding each one's
+    /// outcome as soon as it's in so it can be stored right away. Paused
+    /// feeds, ones still inside a Retry-After window and ones fetched more
+    /// recently than their own interval are skipped.
+    pub fn refresh_all(&self, feeds: Vec<Feed>) -> impl Stream<Item = (i64, FeedRefresh)> + '_ {
+        let now = Utc::now();
+        let (due, waiting): (Vec<_>, Vec<_>) = feeds
+            .into_iter()
+            .partition(|f| f.is_due(now));
+        for feed in &waiting {
+            tracing::debug!("Skipping {} (paused, rate limited or not due)", feed.url);
+        }
+
+        stream::iter(due)
+            .map(move |feed| async move {
+                let outcome = match self.fetch_feed(feed.id, &feed.url, feed.last_post_at).await {
+                    Ok(fetched) => {
+                        tracing::debug!("Fetched {} articles from {}", fetched.articles.len(), feed.title);
+                        if fetched.repaired {
+                            tracing::debug!("{} needed repairs to parse", feed.url);
+                        }
+                        FeedRefresh::Fetched(fetched)
+                    }
+                    Err(AppError::RateLimited(until)) => {
+                        tracing::info!("{} is rate limiting us; retrying after {}", feed.url, until);
+                        FeedRefresh::Throttled(until)
+                    }
+                    Err(AppError::NotFound(e)) => {
+                        tracing::debug!("{} is missing ({})", feed.url, e);
+                        FeedRefresh::Missing(self.find_moved_feed(&feed).await)
                     }
                     Err(e) => {
                         tracing::debug!("Failed to fetch {}: {}", feed.url, e);
-                        None
+                        FeedRefresh::Failed
                     }
-                }
+                };
+                (feed.id, outcome)
             })
             .buffer_unordered(5) // Max 5 concurrent fetches
-            .filter_map(|r| async { r })
-            .collect()
-            .await;
+    }
 
-        results
+    /// After enough 404s in a row, look on the feed's site for where it
+    /// went: sites move from /feed to /rss.xml all the time. Nothing is
+    /// looked up while an earlier proposal is waiting.
+    async fn find_moved_feed(&self, feed: &Feed) -> Option<String> {
+        if feed.missing_count + 1 < MOVED_AFTER_MISSES || feed.moved_to.is_some() {
+            return None;
+        }
+        let page = rediscovery_page(&feed.url, feed.site_url.as_deref())?;
+        match self.discover_feed(&page).await {
+            Ok(found) if found.url != feed.url => {
+                tracing::info!("{} seems to have moved to {}", feed.url, found.url);
+                Some(found.url)
+            }
+            Ok(_) => None,
+            Err(e) => {
+                tracing::debug!("No feed found at {}: {}", page, e);
+                None
+            }
+        }
     }
 
     /// Discover and create a feed from a URL
@@ -117,20 +269,8 @@ impl FeedFetcher {
         let bytes = response.bytes().await?;
 
         // Try parsing as RSS/Atom feed first
-        if let Ok(feed) = parser::parse(&bytes[..]) {
-            let title = feed
-                .title
-                .map(|t| t.content)
-                .unwrap_or_else(|| "Untitled Feed".to_string());
-            let description = feed.description.map(|d| d.content);
-            let site_url = feed.links.first().map(|l| l.href.clone());
-
-            return Ok(NewFeed {
-                title,
-                url: final_url,
-                site_url,
-                description,
-            });
+        if let Ok(feed) = parse_repaired(&bytes[..]) {
+            return Ok(feed_metadata(feed, final_url));
         }
 
         // If content looks like HTML, search for feed links
@@ -141,20 +281,8 @@ impl FeedFetcher {
                 let feed_response = self.client.get(&feed_url).send().await?;
                 if feed_response.status().is_success() {
                     let feed_bytes = feed_response.bytes().await?;
-                    if let Ok(feed) = parser::parse(&feed_bytes[..]) {
-                        let title = feed
-                            .title
-                            .map(|t| t.content)
-                            .unwrap_or_else(|| "Untitled Feed".to_string());
-                        let description = feed.description.map(|d| d.content);
-                        let site_url = feed.links.first().map(|l| l.href.clone());
-
-                        return Ok(NewFeed {
-                            title,
-                            url: feed_url,
-                            site_url,
-                            description,
-                        });
+                    if let Ok(feed) = parse_repaired(&feed_bytes[..]) {
+                        return Ok(feed_metadata(feed, feed_url));
                     }
                 }
             }
@@ -163,6 +291,18 @@ impl FeedFetcher {
         Err(anyhow::anyhow!("Could not find RSS/Atom feed at this URL").into())
     }
 
+    /// Re-read the feed-level title, description and site link of an
+    /// existing subscription (its URL is kept as is)
+    pub async fn fetch_metadata(&self, url: &str) -> Result<NewFeed> {
+        let response = self.client.get(url).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("Failed to fetch feed: HTTP {}", response.status()).into());
+        }
+        let bytes = response.bytes().await?;
+        let feed = parse_repaired(&bytes[..])?;
+        Ok(feed_metadata(feed, url.to_string()))
+    }
+
     /// Search HTML for RSS/Atom feed links
     #[cfg_attr(test, allow(dead_code))]
     pub(crate) fn find_feed_link(&self, html: &str, base_url: &str) -> Option<String> {
@@ -204,6 +344,257 @@ impl FeedFetcher {
     }
 }
 
+/// Feed-level metadata. The site link is the first one that isn't the
+/// Atom `rel="self"` link back to the feed.
+/// The articles in a feed document (or a batch of its items)
+fn parse_articles(feed_id: i64, xml: &[u8]) -> Result<FetchedFeed> {
+    let repaired = repair_xml(xml);
+    let xml = repaired.as_deref().unwrap_or(xml);
+    let feed = parser::parse(xml)?;
+
+    // feed-rs drops most namespaced elements (wfw:commentRss, geo, custom
+    // namespaces), so read them from the raw XML; items are matched to
+    // entries by position
+    let raw_elements = item_elements(&String::from_utf8_lossy(xml));
+    let mut raw_elements = (raw_elements.len() == feed.entries.len()).then_some(raw_elements);
+
+    let articles: Vec<NewArticle> = feed
+        .entries
+        .into_iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            // Try content first, then fall back to summary
+            let content_html = entry
+                .content
+                .as_ref()
+                .and_then(|c| c.body.as_ref())
+                .or_else(|| entry.summary.as_ref().map(|s| &s.content));
+
+            let content_text = content_html.and_then(|html| {
+                html2text::from_read(html.as_bytes(), 80).ok()
+            });
+            let enclosure = audio_enclosure(&entry);
+            let elements = raw_elements
+                .as_mut()
+                .map(|all| std::mem::take(&mut all[index]))
+                .unwrap_or_default();
+            let comments_url = entry
+                .links
+                .iter()
+                .find(|l| l.rel.as_deref() == Some("replies"))
+                .map(|l| l.href.clone())
+                .or_else(|| comment_feed(&elements));
+            let extras = ArticleExtras {
+                authors: entry.authors.iter().map(|a| a.name.clone()).collect(),
+                categories: entry
+                    .categories
+                    .iter()
+                    .map(|c| c.label.clone().unwrap_or_else(|| c.term.clone()))
+                    .collect(),
+                elements,
+            };
+
+            let title = entry
+                .title
+                .map(|t| t.content)
+                .unwrap_or_else(|| "Untitled".to_string());
+            let tags = suggest_tags(&title, content_text.as_deref(), &extras.categories, MAX_SUGGESTED_TAGS);
+            let entities = extract_entities(&title, content_text.as_deref(), MAX_ENTITIES);
+            let bylines: Vec<String> = extras.authors.iter().chain(extras.dc_creators()).cloned().collect();
+            let authors = normalize_authors(&bylines);
+            let url = entry
+                .links
+                .first()
+                .map(|l| l.href.clone())
+                .unwrap_or_default();
+            let links = content_html
+                .map(|html| outbound_links(html, &url, MAX_LINKS))
+                .unwrap_or_default();
+
+            NewArticle {
+                feed_id,
+                guid: entry.id,
+                title,
+                url,
+                author: if authors.is_empty() {
+                    entry.authors.first().map(|a| a.name.clone())
+                } else {
+                    Some(authors.join(", "))
+                },
+                content: content_html.cloned(),
+                content_text,
+                published_at: entry.published.or(entry.updated),
+                comments_url,
+                enclosure,
+                extras: (!extras.is_empty()).then_some(extras),
+                tags,
+                entities,
+                authors,
+                links,
+            }
+        })
+        .collect();
+
+    Ok(FetchedFeed {
+        articles,
+        repaired: repaired.is_some(),
+    })
+}
+
+/// Parse a feed document, repairing it first if it isn't well-formed
+fn parse_repaired(xml: &[u8]) -> std::result::Result<feed_rs::model::Feed, parser::ParseFeedError> {
+    match repair_xml(xml) {
+        Some(fixed) => parser::parse(&fixed[..]),
+        None => parser::parse(xml),
+    }
+}
+
+fn feed_metadata(feed: feed_rs::model::Feed, url: String) -> NewFeed {
+    let title = feed
+        .title
+        .map(|t| t.content.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| "Untitled Feed".to_string());
+    let description = feed
+        .description
+        .map(|d| d.content.trim().to_string())
+        .filter(|d| !d.is_empty());
+    let site_url = feed
+        .links
+        .iter()
+        .find(|l| l.rel.as_deref() != Some("self"))
+        .or(feed.links.first())
+        .map(|l| l.href.clone());
+
+    NewFeed {
+        title,
+        url,
+        site_url,
+        description,
+    }
+}
+
+/// The entry's first audio file: an RSS `<enclosure>` (which feed-rs files
+/// under media) or an Atom `rel="enclosure"` link
+fn audio_enclosure(entry: &feed_rs::model::Entry) -> Option<Enclosure> {
+    let is_audio = |mime: &str| mime.starts_with("audio/");
+
+    for media in &entry.media {
+        for content in &media.content {
+            let (Some(url), Some(mime)) = (&content.url, &content.content_type) else {
+                continue;
+            };
+            let mime = mime.to_string();
+            if is_audio(&mime) {
+                let duration = content.duration.or(media.duration);
+                return Some(Enclosure {
+                    url: url.to_string(),
+                    mime_type: Some(mime),
+                    duration_secs: duration.map(|d| d.as_secs() as u32),
+                });
+            }
+        }
+    }
+
+    entry
+        .links
+        .iter()
+        .find(|l| l.rel.as_deref() == Some("enclosure") && l.media_type.as_deref().is_some_and(is_audio))
+        .map(|l| Enclosure {
+            url: l.href.clone(),
+            mime_type: l.media_type.clone(),
+            duration_secs: None,
+        })
+}
+
+/// Namespaced leaf elements ("dc:creator", "georss:point") of each `<item>`
+/// or `<entry>` in a feed document, in order. Attributes of empty elements
+/// are recorded as "name@attr".
+fn item_elements(xml: &str) -> Vec<BTreeMap<String, Vec<String>>> {
+    let item_re = Regex::new(r"(?s)<(?:item|entry)[\s>].*?</(?:item|entry)>").unwrap();
+    // Text-only content, so wrappers like media:group don't swallow their children
+    let element_re = Regex::new(
+        r"(?s)<([\w-]+:[\w.-]+)(?:\s[^>]*[^/])?>((?:<!\[CDATA\[.*?\]\]>|[^<])*)</([\w-]+:[\w.-]+)\s*>",
+    )
+    .unwrap();
+    let empty_re = Regex::new(r#"<([\w-]+:[\w.-]+)((?:\s+[\w:.-]+\s*=\s*(?:"[^"]*"|'[^']*'))*)\s*/>"#).unwrap();
+    let attr_re = Regex::new(r#"([\w:.-]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap();
+
+    item_re
+        .find_iter(xml)
+        .map(|item| {
+            let mut elements: BTreeMap<String, Vec<String>> = BTreeMap::new();
+            for cap in element_re.captures_iter(item.as_str()) {
+                if cap[1] != cap[3] {
+                    continue;
+                }
+                let value = xml_text(&cap[2]);
+                if !value.is_empty() {
+                    elements.entry(cap[1].to_string()).or_default().push(value);
+                }
+            }
+            for cap in empty_re.captures_iter(item.as_str()) {
+                for attr in attr_re.captures_iter(&cap[2]) {
+                    if attr[1].starts_with("xmlns") {
+                        continue;
+                    }
+                    let value = attr.get(2).or(attr.get(3)).map_or("", |m| m.as_str());
+                    elements
+                        .entry(format!("{}@{}", &cap[1], &attr[1]))
+                        .or_default()
+                        .push(xml_text(value));
+                }
+            }
+            elements
+        })
+        .collect()
+}
+
+/// The post's `wfw:commentRss` URL, whatever prefix the feed binds it to
+fn comment_feed(elements: &BTreeMap<String, Vec<String>>) -> Option<String> {
+    elements
+        .iter()
+        .find(|(name, _)| name.ends_with(":commentRss"))
+        .and_then(|(_, values)| values.first().cloned())
+}
+
+/// Element text with CDATA markers removed and the predefined entities decoded
+fn xml_text(raw: &str) -> String {
+    let raw = raw.trim();
+    if let Some(cdata) = raw.strip_prefix("<![CDATA[").and_then(|r| r.strip_suffix("]]>")) {
+        return cdata.trim().to_string();
+    }
+    raw.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// The page to look for a moved feed on: its site, or else the root of
+/// the feed's host
+fn rediscovery_page(feed_url: &str, site_url: Option<&str>) -> Option<String> {
+    if let Some(site) = site_url.filter(|site| *site != feed_url) {
+        return Some(site.to_string());
+    }
+    let mut root = url::Url::parse(feed_url).ok()?;
+    root.set_path("/");
+    root.set_query(None);
+    root.set_fragment(None);
+    (root.as_str() != feed_url).then(|| root.to_string())
+}
+
+/// Parse a Retry-After value (delay in seconds or an HTTP date) into the time
+/// to retry, capped at `MAX_RETRY_AFTER` from `now`
+pub(crate) fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    let until = match value.parse::<u64>() {
+        Ok(seconds) => now + chrono::Duration::seconds(seconds.min(i64::MAX as u64) as i64),
+        Err(_) => DateTime::parse_from_rfc2822(value).ok()?.with_timezone(&Utc),
+    };
+    Some(until.clamp(now, now + MAX_RETRY_AFTER))
+}
+
 impl Default for FeedFetcher {
     fn default() -> Self {
         Self::new()
@@ -218,6 +609,133 @@ mod tests {
         FeedFetcher::new()
     }
 
+    // ==================== parse_retry_after tests ====================
+
+    #[test]
+    fn test_comment_feeds_by_item() {
+        let xml = r#"<rss xmlns:wfw="http://wellformedweb.org/CommentAPI/"><channel>
+            <item><title>One</title><wfw:commentRss>https://blog.example/1/feed?a=1&amp;b=2</wfw:commentRss></item>
+            <item><title>Two</title></item>
+            <item><wfw:commentRss><![CDATA[ https://blog.example/3/feed ]]></wfw:commentRss></item>
+        </channel></rss>"#;
+        let comment_feeds: Vec<Option<String>> = item_elements(xml).iter().map(comment_feed).collect();
+        assert_eq!(
+            comment_feeds,
+            vec![
+                Some("https://blog.example/1/feed?a=1&b=2".to_string()),
+                None,
+                Some("https://blog.example/3/feed".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_item_elements_keep_namespaced_extensions() {
+        let xml = r#"<feed xmlns="http://www.w3.org/2005/Atom"><entry>
+            <title>Quake</title>
+            <georss:point>45.256 -71.92</georss:point>
+            <my:rating scale="5">4</my:rating>
+            <itunes:image href="https://cdn.example/art.jpg"/>
+            <media:group><media:title>Clip &amp; more</media:title><media:content url="https://cdn.example/v.mp4"/></media:group>
+        </entry></feed>"#;
+        let elements = &item_elements(xml)[0];
+
+        assert_eq!(elements["georss:point"], vec!["45.256 -71.92"]);
+        assert_eq!(elements["my:rating"], vec!["4"]);
+        assert_eq!(elements["itunes:image@href"], vec!["https://cdn.example/art.jpg"]);
+        assert_eq!(elements["media:title"], vec!["Clip & more"]);
+        assert_eq!(elements["media:content@url"], vec!["https://cdn.example/v.mp4"]);
+        assert!(!elements.contains_key("media:group"));
+    }
+
+    #[test]
+    fn test_audio_enclosure_with_itunes_duration() {
+        let xml = r#"<rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd"><channel>
+            <title>Show</title>
+            <item><title>Episode 1</title>
+                <enclosure url="https://cdn.example/ep1.mp3" length="1000" type="audio/mpeg"/>
+                <itunes:duration>1:02:03</itunes:duration>
+            </item>
+            <item><title>Cover art</title>
+                <enclosure url="https://cdn.example/cover.jpg" length="10" type="image/jpeg"/>
+            </item>
+        </channel></rss>"#;
+        let feed = parser::parse(xml.as_bytes()).unwrap();
+
+        assert_eq!(
+            audio_enclosure(&feed.entries[0]),
+            Some(Enclosure {
+                url: "https://cdn.example/ep1.mp3".to_string(),
+                mime_type: Some("audio/mpeg".to_string()),
+                duration_secs: Some(3723),
+            })
+        );
+        assert_eq!(audio_enclosure(&feed.entries[1]), None);
+    }
+
+    #[test]
+    fn test_feed_metadata_skips_self_link() {
+        let xml = r#"<feed xmlns="http://www.w3.org/2005/Atom">
+            <title> Rebranded Blog </title>
+            <subtitle>New tagline</subtitle>
+            <link rel="self" href="https://example.com/atom.xml"/>
+            <link rel="alternate" href="https://example.com/"/>
+        </feed>"#;
+        let feed = parser::parse(xml.as_bytes()).unwrap();
+        let meta = feed_metadata(feed, "https://example.com/atom.xml".to_string());
+
+        assert_eq!(meta.title, "Rebranded Blog");
+        assert_eq!(meta.description.as_deref(), Some("New tagline"));
+        assert_eq!(meta.site_url.as_deref(), Some("https://example.com/"));
+        assert_eq!(meta.url, "https://example.com/atom.xml");
+    }
+
+    #[test]
+    fn test_retry_after_seconds() {
+        let now = Utc::now();
+        assert_eq!(parse_retry_after("120", now), Some(now + chrono::Duration::seconds(120)));
+    }
+
+    #[test]
+    fn test_retry_after_http_date() {
+        let now = DateTime::parse_from_rfc3339("2026-01-11T12:00:00Z").unwrap().with_timezone(&Utc);
+        let until = parse_retry_after("Sun, 11 Jan 2026 13:30:00 GMT", now).unwrap();
+        assert_eq!(until - now, chrono::Duration::minutes(90));
+    }
+
+    #[test]
+    fn test_retry_after_is_clamped() {
+        let now = DateTime::parse_from_rfc3339("2026-01-11T12:00:00Z").unwrap().with_timezone(&Utc);
+        // A date in the past means "now"; absurd delays are capped
+        assert_eq!(parse_retry_after("Sat, 10 Jan 2026 00:00:00 GMT", now), Some(now));
+        assert_eq!(parse_retry_after("999999999", now), Some(now + MAX_RETRY_AFTER));
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn test_malformed_feed_parsed_after_repairs() {
+        let xml = "<rss version=\"2.0\"><channel><title>Q&A</title><item><title>Caf&eacute; &mdash; open\u{b}</title>\
+                   <link>https://x.example/?a=1&b=2</link></item></channel></rss>";
+        let fetched = parse_articles(1, xml.as_bytes()).unwrap();
+        assert!(fetched.repaired);
+        assert_eq!(fetched.articles[0].title, "Café — open");
+        assert_eq!(fetched.articles[0].url, "https://x.example/?a=1&b=2");
+
+        let fine = xml.replace("Q&A", "Q&amp;A").replace("&eacute;", "é").replace("&mdash;", "—");
+        let fine = fine.replace('\u{b}', "").replace("&b", "&amp;b");
+        assert!(!parse_articles(1, fine.as_bytes()).unwrap().repaired);
+    }
+
+    #[test]
+    fn test_rediscovery_page() {
+        let feed = "https://example.com/blog/feed?format=rss";
+        assert_eq!(rediscovery_page(feed, Some("https://example.com/blog")).as_deref(), Some("https://example.com/blog"));
+        assert_eq!(rediscovery_page(feed, None).as_deref(), Some("https://example.com/"));
+        assert_eq!(rediscovery_page(feed, Some(feed)).as_deref(), Some("https://example.com/"));
+        assert_eq!(rediscovery_page("https://example.com/", None), None);
+        assert_eq!(rediscovery_page("not a url", None), None);
+    }
+
     // ==================== resolve_url tests ====================
 
     #[test]
@@ -0,0 +1,181 @@
+//! Suggested tags for incoming articles: the feed's own categories, then
+//! key phrases picked out with RAKE (Rapid Automatic Keyword Extraction).
+
+use std::collections::HashMap;
+
+/// Tags kept per article
+pub const MAX_SUGGESTED_TAGS: usize = 5;
+
+/// Longest phrase, in words, that can become a tag
+const MAX_PHRASE_WORDS: usize = 3;
+
+/// Words that split phrases and never appear in one
+const STOPWORDS: &[&str] = &[
+    "about", "above", "after", "again", "against", "all", "also", "although", "among", "and",
+    "another", "any", "are", "around", "because", "been", "before", "being", "below", "between",
+    "both", "but", "can", "cannot", "could", "did", "does", "doing", "done", "down", "during",
+    "each", "either", "even", "ever", "every", "few", "for", "from", "further", "get", "gets",
+    "getting", "got", "had", "has", "have", "having", "her", "here", "hers", "herself", "him",
+    "himself", "his", "how", "however", "into", "its", "itself", "just", "know", "last", "least",
+    "less", "let", "like", "likely", "made", "make", "makes", "making", "many", "may", "might",
+    "more", "most", "much", "must", "near", "need", "never", "new", "next", "nor", "not", "now",
+    "off", "often", "once", "one", "only", "onto", "other", "others", "our", "ours", "ourselves",
+    "out", "over", "own", "per", "perhaps", "put", "rather", "read", "really", "said", "same",
+    "say", "says", "see", "seen", "several", "she", "should", "since", "some", "still", "such",
+    "take", "than", "that", "the", "their", "theirs", "them", "themselves", "then", "there",
+    "these", "they", "thing", "things", "this", "those", "though", "through", "thus", "time",
+    "too", "two", "under", "until", "upon", "use", "used", "uses", "using", "very", "via", "want",
+    "was", "way", "ways", "well", "were", "what", "when", "where", "whether", "which", "while",
+    "who", "whom", "whose", "why", "will", "with", "within", "without", "would", "year", "years",
+    "yet", "you", "your", "yours", "yourself", "yourselves", "http", "https", "www", "com",
+];
+
+/// Up to `limit` tags: the item's categories first, then key phrases from
+/// the title and text
+pub fn suggest_tags(title: &str, text: Option<&str>, categories: &[String], limit: usize) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    let candidates = categories
+        .iter()
+        .filter_map(|c| normalize_tag(c))
+        .chain(extract_keywords(title, text.unwrap_or(""), limit));
+    for tag in candidates {
+        if tags.len() == limit {
+            break;
+        }
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// The `limit` best-scoring key phrases, lowercased. The title counts
+/// twice, and a phrase has to occur at least twice to qualify, so one-off
+/// word runs in the body don't crowd out what the article is about.
+pub fn extract_keywords(title: &str, text: &str, limit: usize) -> Vec<String> {
+    let mut phrases: Vec<Vec<String>> = Vec::new();
+    for source in [title, title, text] {
+        for line in source.lines() {
+            // html2text renders links as numbered footnotes ("[1]: https://...")
+            if line.trim_start().starts_with('[') && line.contains("]: ") {
+                continue;
+            }
+            split_phrases(line, &mut phrases);
+        }
+    }
+
+    // RAKE word score: degree (total length of the phrases a word is in) over frequency
+    let mut frequency: HashMap<&str, usize> = HashMap::new();
+    let mut degree: HashMap<&str, usize> = HashMap::new();
+    let mut occurrences: HashMap<&[String], usize> = HashMap::new();
+    for phrase in &phrases {
+        for word in phrase {
+            *frequency.entry(word).or_default() += 1;
+            *degree.entry(word).or_default() += phrase.len();
+        }
+        *occurrences.entry(phrase.as_slice()).or_default() += 1;
+    }
+
+    let mut scored: Vec<(f64, usize, String)> = occurrences
+        .into_iter()
+        .filter(|(_, count)| *count >= 2)
+        .map(|(phrase, count)| {
+            let score: f64 = phrase
+                .iter()
+                .map(|w| degree[w.as_str()] as f64 / frequency[w.as_str()] as f64)
+                .sum();
+            (score, count, phrase.join(" "))
+        })
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0).then(b.1.cmp(&a.1)).then(a.2.cmp(&b.2)));
+
+    let mut keywords: Vec<String> = Vec::new();
+    for (_, _, phrase) in scored {
+        if keywords.len() == limit {
+            break;
+        }
+        // Skip a single word already covered by a better phrase
+        if keywords.iter().any(|k| k.split(' ').any(|w| w == phrase)) {
+            continue;
+        }
+        keywords.push(phrase);
+    }
+    keywords
+}
+
+/// Split `line` into runs of content words, breaking at stopwords,
+/// punctuation, numbers and very short words
+fn split_phrases(line: &str, phrases: &mut Vec<Vec<String>>) {
+    phrases.extend(content_runs(line).into_iter().filter(|run| run.len() <= MAX_PHRASE_WORDS));
+}
+
+/// Runs of lowercased content words in `line`, of any length
+pub fn content_runs(line: &str) -> Vec<Vec<String>> {
+    let mut runs = Vec::new();
+    let mut current: Vec<String> = Vec::new();
+    let mut word = String::new();
+    // The trailing '.' ends the last run
+    for c in line.chars().chain(std::iter::once('.')) {
+        if c.is_alphanumeric() || matches!(c, '\'' | '\u{2019}' | '-') {
+            word.push(c);
+            continue;
+        }
+        if !word.is_empty() {
+            let raw = std::mem::take(&mut word).to_lowercase().replace('\u{2019}', "'");
+            let raw = raw.trim_matches(|c| c == '\'' || c == '-');
+            let cleaned = raw.strip_suffix("'s").unwrap_or(raw);
+            if is_content_word(cleaned) {
+                current.push(cleaned.to_string());
+            } else if !current.is_empty() {
+                runs.push(std::mem::take(&mut current));
+            }
+        }
+        if !c.is_whitespace() && !current.is_empty() {
+            runs.push(std::mem::take(&mut current));
+        }
+    }
+    runs
+}
+
+fn is_content_word(word: &str) -> bool {
+    word.chars().count() >= 3 && word.chars().any(char::is_alphabetic) && !STOPWORDS.contains(&word)
+}
+
+/// Tags are lowercase, single-spaced and can't contain commas
+fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.replace(',', " ").split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+    (!tag.is_empty()).then_some(tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_keywords_prefers_repeated_phrases() {
+        let title = "Rust compiler gets faster incremental builds";
+        let text = "The Rust compiler team shipped faster incremental builds this week. \
+                    Incremental builds now reuse more work, and the compiler caches \
+                    macro expansion. Some users reported a bug.";
+        let keywords = extract_keywords(title, text, 3);
+        assert_eq!(keywords[0], "faster incremental builds");
+        assert!(keywords.contains(&"rust compiler".to_string()), "{:?}", keywords);
+        // Mentioned once, outside the title
+        assert!(!keywords.iter().any(|k| k.contains("macro")));
+    }
+
+    #[test]
+    fn test_suggest_tags_puts_categories_first_and_dedupes() {
+        let categories = vec!["Apple, Inc".to_string(), "apple, inc".to_string()];
+        let tags = suggest_tags("Vision Pro review", Some("The Vision Pro."), &categories, 2);
+        assert_eq!(tags, ["apple inc", "vision pro review"]);
+        assert!(suggest_tags("", None, &[], 5).is_empty());
+    }
+
+    #[test]
+    fn test_link_footnotes_and_possessives_ignored() {
+        let text = "Mozilla's browser update.\n[1]: https://example.com/post\n[2]: https://example.com/post";
+        let keywords = extract_keywords("Mozilla's browser update", text, 5);
+        assert_eq!(keywords, ["mozilla browser update"]);
+    }
+}
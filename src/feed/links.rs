@@ -0,0 +1,109 @@
+//! Outbound links in incoming articles, for tracing who cites a report or
+//! a site.
+
+use scraper::{Html, Selector};
+use url::Url;
+
+/// Links kept per article
+pub const MAX_LINKS: usize = 100;
+
+/// Absolute http(s) links in `html` to other sites than `page_url`'s, in
+/// order and without repeats or fragments
+pub fn outbound_links(html: &str, page_url: &str, limit: usize) -> Vec<String> {
+    let base = Url::parse(page_url).ok();
+    let own = base.as_ref().and_then(link_domain_of);
+    let anchors = Selector::parse("a[href]").unwrap();
+    let mut links: Vec<String> = Vec::new();
+    for anchor in Html::parse_fragment(html).select(&anchors) {
+        let href = anchor.value().attr("href").unwrap_or_default().trim();
+        let Some(mut url) = base.as_ref().and_then(|b| b.join(href).ok()).or_else(|| Url::parse(href).ok())
+        else {
+            continue;
+        };
+        if !matches!(url.scheme(), "http" | "https") {
+            continue;
+        }
+        url.set_fragment(None);
+        let Some(domain) = link_domain_of(&url) else {
+            continue;
+        };
+        if own.as_ref() == Some(&domain) {
+            continue;
+        }
+        let url = url.to_string();
+        if !links.contains(&url) {
+            links.push(url);
+            if links.len() == limit {
+                break;
+            }
+        }
+    }
+    links
+}
+
+/// The site a link points at (lowercased, without `www.`), and that with
+/// the path and query after it, as `links:` searches match them
+pub fn link_target(url: &str) -> Option<(String, String)> {
+    let url = Url::parse(url).ok()?;
+    let domain = link_domain_of(&url)?;
+    let mut page = format!("{}{}", domain, url.path().to_lowercase());
+    if let Some(query) = url.query() {
+        page.push('?');
+        page.push_str(query);
+    }
+    Some((domain, page))
+}
+
+/// A `links:` target as it's matched: lowercased, without the scheme,
+/// `www.` or a trailing slash
+pub fn normalize_link_target(target: &str) -> String {
+    let target = target.to_lowercase();
+    let target = target.split_once("://").map_or(target.as_str(), |(_, rest)| rest);
+    target.strip_prefix("www.").unwrap_or(target).trim_end_matches('/').to_string()
+}
+
+/// Whether `url` is a link `links:target` finds: to the site or a
+/// subdomain, or to the page or one under it
+pub fn links_to(url: &str, target: &str) -> bool {
+    let target = normalize_link_target(target);
+    match link_target(url) {
+        Some((_, page)) if target.contains('/') => page.starts_with(&target),
+        Some((domain, _)) => domain == target || domain.ends_with(&format!(".{}", target)),
+        None => false,
+    }
+}
+
+fn link_domain_of(url: &Url) -> Option<String> {
+    let host = url.host_str()?.to_lowercase();
+    Some(host.strip_prefix("www.").map(str::to_string).unwrap_or(host))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outbound_links_resolved_deduped_and_off_site() {
+        let html = r##"<p>Per <a href="https://www.gao.gov/reports/123#summary">the GAO</a>,
+            <a href="/about">us</a>, <a href="https://example.com/other">us again</a>,
+            <a href="#top">top</a>, <a href="mailto:tips@example.com">tips</a>,
+            <a href="//cdn.news.org/chart.png">chart</a> and
+            <a href="https://gao.gov/reports/123">the report</a>.</p>"##;
+        let links = outbound_links(html, "https://www.example.com/story", MAX_LINKS);
+        assert_eq!(links, ["https://www.gao.gov/reports/123", "https://cdn.news.org/chart.png", "https://gao.gov/reports/123"]);
+        assert_eq!(outbound_links(html, "https://www.example.com/story", 1).len(), 1);
+    }
+
+    #[test]
+    fn test_link_target_drops_www_and_scheme() {
+        let target = |url| link_target(url).unwrap();
+        assert_eq!(target("https://WWW.GAO.gov/Reports/1?id=2#x"), ("gao.gov".into(), "gao.gov/reports/1?id=2".into()));
+        assert_eq!(target("http://data.gao.gov").1, "data.gao.gov/");
+        assert_eq!(link_target("not a url"), None);
+
+        assert!(links_to("https://data.gao.gov/x", "gao.gov"));
+        assert!(links_to("https://www.gao.gov/reports/123", "https://gao.gov/Reports/"));
+        assert!(!links_to("https://notgao.gov/x", "gao.gov"));
+        assert!(!links_to("https://gao.gov/reports/124", "gao.gov/reports/123"));
+    }
+}
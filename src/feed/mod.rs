@@ -1,5 +1,24 @@
 mod opml;
 mod fetcher;
+mod recommend;
+mod duplicates;
+mod keywords;
+mod entities;
+mod authors;
+mod links;
+mod urls;
+mod bookmarks;
+mod starred;
+mod stream;
+mod repair;
 
-pub use opml::{export_opml_file, parse_opml_file};
-pub use fetcher::FeedFetcher;
+pub use opml::{export_opml_file, export_opml_string, parse_opml_file, OpmlHead};
+pub use fetcher::{FeedFetcher, FeedRefresh};
+pub use recommend::{candidate_domains, discover_recommendations, FeedRecommendation};
+pub use duplicates::{find_by_url, find_duplicate, DuplicateKind};
+pub use keywords::{content_runs, MAX_SUGGESTED_TAGS};
+pub use authors::normalize_authors;
+pub use links::{link_target, links_to, normalize_link_target};
+pub use urls::{format_urls, parse_urls};
+pub use bookmarks::{parse_bookmarks_file, Shelf};
+pub use starred::{archive_files, parse_starred_file};
@@ -1,8 +1,12 @@
+use chrono::Utc;
 use opml::{Outline, OPML};
 use std::path::Path;
 
 use crate::error::{AppError, Result};
-use crate::models::{Feed, NewFeed};
+use crate::models::{Feed, NewFeed, SourceInfo};
+
+/// Namespace for the source attributes on exported outlines
+const SOURCE_NAMESPACE: &str = "https://github.com/leolaporte/beatcheck/opml";
 
 pub fn parse_opml_file(path: &Path) -> Result<Vec<NewFeed>> {
     let content = std::fs::read_to_string(path)?;
@@ -38,35 +42,107 @@ fn collect_feeds(outlines: &[Outline], feeds: &mut Vec<NewFeed>) {
     }
 }
 
-pub fn export_opml_file(path: &Path, feeds: &[Feed]) -> Result<()> {
+/// What an exported file says about itself in its `<head>`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OpmlHead {
+    /// "BeatCheck Feeds" if not given
+    pub title: Option<String>,
+    pub owner_name: Option<String>,
+    pub owner_email: Option<String>,
+}
+
+/// Write `feeds` out as OPML, those in a folder nested under an outline
+/// for it (in the order folders first appear)
+pub fn export_opml_file(path: &Path, feeds: &[&Feed], head: &OpmlHead) -> Result<()> {
+    std::fs::write(path, export_opml_string(feeds, head)?)?;
+    Ok(())
+}
+
+/// `feeds` as an OPML document, as `export_opml_file` writes it
+pub fn export_opml_string(feeds: &[&Feed], head: &OpmlHead) -> Result<String> {
     let mut opml = OPML::default();
     opml.head = Some(opml::Head {
-        title: Some("BeatCheck Feeds".to_string()),
+        title: Some(head.title.clone().unwrap_or_else(|| "BeatCheck Feeds".to_string())),
+        date_created: Some(Utc::now().to_rfc2822()),
+        owner_name: head.owner_name.clone(),
+        owner_email: head.owner_email.clone(),
         ..Default::default()
     });
 
-    for feed in feeds {
+    // The opml crate only writes standard attributes, so outlines carrying
+    // source notes get a placeholder xmlUrl that is swapped for the real one
+    // plus `beatcheck:` attributes once the document is serialized
+    let feeds: Vec<&Feed> = feeds.iter().copied().filter(|f| !f.is_local()).collect();
+    for (index, feed) in feeds.iter().enumerate() {
+        let xml_url = if feed.source.is_empty() {
+            feed.url.clone()
+        } else {
+            placeholder(index)
+        };
         let outline = Outline {
-            text: feed.title.clone(),
+            text: feed.display_title().to_string(),
             r#type: Some("rss".to_string()),
-            xml_url: Some(feed.url.clone()),
+            xml_url: Some(xml_url),
             html_url: feed.site_url.clone(),
             description: feed.description.clone(),
             ..Default::default()
         };
-        opml.body.outlines.push(outline);
+        let Some(folder) = &feed.folder else {
+            opml.body.outlines.push(outline);
+            continue;
+        };
+        match opml.body.outlines.iter_mut().find(|o| o.xml_url.is_none() && o.text == *folder) {
+            Some(group) => group.outlines.push(outline),
+            None => opml.body.outlines.push(Outline {
+                text: folder.clone(),
+                title: Some(folder.clone()),
+                outlines: vec![outline],
+                ..Default::default()
+            }),
+        }
     }
 
-    let content = opml.to_string().map_err(|e| AppError::OpmlParse(e.to_string()))?;
-    std::fs::write(path, content)?;
+    let mut content = opml.to_string().map_err(|e| AppError::OpmlParse(e.to_string()))?;
+    if feeds.iter().any(|f| !f.source.is_empty()) {
+        content = content.replacen("<opml ", &format!("<opml xmlns:beatcheck=\"{}\" ", SOURCE_NAMESPACE), 1);
+        for (index, feed) in feeds.iter().enumerate().filter(|(_, f)| !f.source.is_empty()) {
+            let attributes = format!("xmlUrl=\"{}\"{}", escape_attribute(&feed.url), source_attributes(&feed.source));
+            content = content.replacen(&format!("xmlUrl=\"{}\"", placeholder(index)), &attributes, 1);
+        }
+    }
+    Ok(content)
+}
 
-    Ok(())
+fn placeholder(index: usize) -> String {
+    format!("beatcheck-source-{}", index)
+}
+
+/// ` beatcheck:beat="..."` and so on, for the fields that are set
+fn source_attributes(source: &SourceInfo) -> String {
+    let reliability = source.reliability.map(|r| r.to_string());
+    [
+        ("beat", source.beat.as_ref()),
+        ("contact", source.contact.as_ref()),
+        ("reliability", reliability.as_ref()),
+        ("notes", source.notes.as_ref()),
+    ]
+    .into_iter()
+    .filter_map(|(name, value)| value.map(|v| format!(" beatcheck:{}=\"{}\"", name, escape_attribute(v))))
+    .collect()
+}
+
+fn escape_attribute(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\n', "&#10;")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::Utc;
     use std::io::Write;
     use tempfile::NamedTempFile;
 
@@ -80,6 +156,22 @@ mod tests {
             last_fetched: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            retry_after: None,
+            custom_title: None,
+            folder: None,
+            refresh_minutes: None,
+            paused: false,
+            tags: Vec::new(),
+            posts_per_day: None,
+            last_post_at: None,
+            unread: 0,
+            reading: Default::default(),
+            priority: Default::default(),
+            source: Default::default(),
+            reader_url: None,
+            missing_count: 0,
+            moved_to: None,
+            repaired: false,
         }
     }
 
@@ -224,7 +316,7 @@ mod tests {
 
     #[test]
     fn test_export_opml() {
-        let feeds = vec![
+        let feeds = [
             make_feed(1, "Feed One", "https://one.example.com/feed"),
             make_feed(2, "Feed Two", "https://two.example.com/feed"),
         ];
@@ -232,7 +324,7 @@ mod tests {
         let temp_file = NamedTempFile::new().unwrap();
         let path = temp_file.path().to_path_buf();
 
-        export_opml_file(&path, &feeds).unwrap();
+        export_opml_file(&path, &feeds.iter().collect::<Vec<_>>(), &OpmlHead::default()).unwrap();
 
         // Read back and verify
         let content = std::fs::read_to_string(&path).unwrap();
@@ -242,6 +334,34 @@ mod tests {
         assert!(content.contains("BeatCheck Feeds"));
     }
 
+    #[test]
+    fn test_export_opml_source_attributes() {
+        let mut feed = make_feed(1, "Wire", "https://wire.example.com/feed?a=1&b=2");
+        feed.source = SourceInfo {
+            beat: Some("chips".to_string()),
+            reliability: Some(4),
+            notes: Some("Fast but \"thin\"\nCall the desk".to_string()),
+            ..Default::default()
+        };
+        let feeds = [feed, make_feed(2, "Plain", "https://plain.example.com/feed")];
+
+        let temp_file = NamedTempFile::new().unwrap();
+        export_opml_file(temp_file.path(), &feeds.iter().collect::<Vec<_>>(), &OpmlHead::default()).unwrap();
+
+        let content = std::fs::read_to_string(temp_file.path()).unwrap();
+        assert!(content.contains(&format!("xmlns:beatcheck=\"{}\"", SOURCE_NAMESPACE)));
+        assert!(content.contains(
+            "xmlUrl=\"https://wire.example.com/feed?a=1&amp;b=2\" beatcheck:beat=\"chips\" beatcheck:reliability=\"4\" \
+             beatcheck:notes=\"Fast but &quot;thin&quot;&#10;Call the desk\""
+        ));
+        assert!(!content.contains("beatcheck-source-"));
+
+        // Still an OPML file other readers (and this one) can import
+        let imported = parse_opml_file(temp_file.path()).unwrap();
+        let urls: Vec<_> = imported.iter().map(|f| f.url.as_str()).collect();
+        assert_eq!(urls, ["https://wire.example.com/feed?a=1&b=2", "https://plain.example.com/feed"]);
+    }
+
     #[test]
     fn test_export_empty_feeds() {
         let feeds: Vec<Feed> = vec![];
@@ -249,16 +369,43 @@ mod tests {
         let temp_file = NamedTempFile::new().unwrap();
         let path = temp_file.path().to_path_buf();
 
-        export_opml_file(&path, &feeds).unwrap();
+        export_opml_file(&path, &feeds.iter().collect::<Vec<_>>(), &OpmlHead::default()).unwrap();
 
         let content = std::fs::read_to_string(&path).unwrap();
         assert!(content.contains("<body"));
         assert!(content.contains("BeatCheck Feeds"));
     }
 
+    #[test]
+    fn test_export_folder_with_head() {
+        let mut one = make_feed(1, "Import AI", "https://importai.example.com/feed");
+        one.folder = Some("AI".to_string());
+        let mut two = make_feed(2, "Chips", "https://chips.example.com/feed");
+        two.folder = Some("AI".to_string());
+        let head = OpmlHead {
+            title: Some("AI reading".to_string()),
+            owner_name: Some("Leo".to_string()),
+            owner_email: None,
+        };
+
+        let temp_file = NamedTempFile::new().unwrap();
+        export_opml_file(temp_file.path(), &[&one, &two], &head).unwrap();
+
+        let content = std::fs::read_to_string(temp_file.path()).unwrap();
+        let opml = OPML::from_str(&content).unwrap();
+        let exported_head = opml.head.unwrap();
+        assert_eq!(exported_head.title.as_deref(), Some("AI reading"));
+        assert_eq!(exported_head.owner_name.as_deref(), Some("Leo"));
+        assert!(exported_head.date_created.is_some());
+        assert_eq!(opml.body.outlines.len(), 1);
+        assert_eq!(opml.body.outlines[0].text, "AI");
+        assert_eq!(opml.body.outlines[0].outlines.len(), 2);
+        assert_eq!(parse_opml_file(temp_file.path()).unwrap().len(), 2);
+    }
+
     #[test]
     fn test_roundtrip_export_import() {
-        let original_feeds = vec![
+        let original_feeds = [
             make_feed(1, "Ars Technica", "https://feeds.arstechnica.com/arstechnica/index"),
             make_feed(2, "Hacker News", "https://news.ycombinator.com/rss"),
         ];
@@ -267,7 +414,7 @@ mod tests {
         let path = temp_file.path().to_path_buf();
 
         // Export
-        export_opml_file(&path, &original_feeds).unwrap();
+        export_opml_file(&path, &original_feeds.iter().collect::<Vec<_>>(), &OpmlHead::default()).unwrap();
 
         // Import
         let imported = parse_opml_file(&path).unwrap();
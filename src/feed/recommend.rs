@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+
+use futures::stream::{self, StreamExt};
+
+use crate::models::{Article, Feed, NewFeed};
+
+use super::FeedFetcher;
+
+/// A site that shows up in starred articles but has no subscription
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DomainCandidate {
+    pub domain: String,
+    pub starred_count: usize,
+    pub authors: Vec<String>,
+}
+
+/// A discovered feed for a candidate site, ready to subscribe to
+#[derive(Debug, Clone)]
+pub struct FeedRecommendation {
+    pub domain: String,
+    pub starred_count: usize,
+    pub authors: Vec<String>,
+    pub feed: NewFeed,
+}
+
+/// Host of a URL, lowercased and without a leading "www."
+pub fn normalize_domain(url: &str) -> Option<String> {
+    let parsed = url::Url::parse(url).ok()?;
+    let host = parsed.host_str()?.to_lowercase();
+    Some(host.strip_prefix("www.").unwrap_or(&host).to_string())
+}
+
+/// Two hosts belong to the same site if one is the other or a subdomain of it
+/// (so feeds.arstechnica.com covers articles on arstechnica.com)
+fn is_same_site(a: &str, b: &str) -> bool {
+    a == b || a.ends_with(&format!(".{}", b)) || b.ends_with(&format!(".{}", a))
+}
+
+/// Rank the sites behind starred articles that no subscribed feed covers,
+/// most-starred first, keeping at most `limit` of them.
+pub fn candidate_domains(starred: &[Article], feeds: &[Feed], limit: usize) -> Vec<DomainCandidate> {
+    let subscribed: Vec<String> = feeds
+        .iter()
+        .flat_map(|f| [Some(f.url.as_str()), f.site_url.as_deref()])
+        .flatten()
+        .filter_map(normalize_domain)
+        .collect();
+
+    let mut by_domain: HashMap<String, DomainCandidate> = HashMap::new();
+    for article in starred {
+        let Some(domain) = normalize_domain(&article.url) else {
+            continue;
+        };
+        if subscribed.iter().any(|s| is_same_site(s, &domain)) {
+            continue;
+        }
+
+        let candidate = by_domain.entry(domain.clone()).or_insert_with(|| DomainCandidate {
+            domain,
+            starred_count: 0,
+            authors: Vec::new(),
+        });
+        candidate.starred_count += 1;
+        if let Some(author) = article.author.as_ref().filter(|a| !a.trim().is_empty()) {
+            if !candidate.authors.contains(author) {
+                candidate.authors.push(author.clone());
+            }
+        }
+    }
+
+    let mut candidates: Vec<_> = by_domain.into_values().collect();
+    candidates.sort_by(|a, b| {
+        b.starred_count
+            .cmp(&a.starred_count)
+            .then_with(|| a.domain.cmp(&b.domain))
+    });
+    candidates.truncate(limit);
+    candidates
+}
+
+/// Run feed discovery on each candidate site's home page and keep the ones
+/// that expose a feed we aren't already subscribed to.
+pub async fn discover_recommendations(
+    fetcher: &FeedFetcher,
+    candidates: Vec<DomainCandidate>,
+    feeds: &[Feed],
+) -> Vec<FeedRecommendation> {
+    let mut recommendations: Vec<FeedRecommendation> = stream::iter(candidates)
+        .map(|candidate| async move {
+            let url = format!("https://{}/", candidate.domain);
+            match fetcher.discover_feed(&url).await {
+                Ok(feed) => Some(FeedRecommendation {
+                    domain: candidate.domain,
+                    starred_count: candidate.starred_count,
+                    authors: candidate.authors,
+                    feed,
+                }),
+                Err(e) => {
+                    tracing::debug!("No feed found for {}: {}", candidate.domain, e);
+                    None
+                }
+            }
+        })
+        .buffer_unordered(5) // Same concurrency as refresh_all
+        .filter_map(|r| async { r })
+        .collect()
+        .await;
+
+    recommendations.retain(|r| !feeds.iter().any(|f| f.url == r.feed.url));
+    recommendations.sort_by(|a, b| {
+        b.starred_count
+            .cmp(&a.starred_count)
+            .then_with(|| a.domain.cmp(&b.domain))
+    });
+    recommendations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn starred(url: &str, author: Option<&str>) -> Article {
+        Article {
+            id: 0,
+            feed_id: 1,
+            guid: url.to_string(),
+            title: "Starred".to_string(),
+            url: url.to_string(),
+            author: author.map(|a| a.to_string()),
+            content: None,
+            content_text: None,
+            published_at: None,
+            fetched_at: Utc::now(),
+            feed_title: None,
+            is_starred: true,
+            is_pinned: false,
+            reading_minutes: None,
+            is_queued: false,
+            is_read: false,
+            full_text: None,
+            comments_url: None,
+            discussion: None,
+            enclosure: None,
+            playback_secs: None,
+            is_played: false,
+            extras: None,
+            archive_path: None,
+            tags: Vec::new(),
+            by_followed_author: false,
+            embargo_until: None,
+            revisions: 0,
+            removed_at: None,
+        }
+    }
+
+    fn feed(url: &str, site_url: Option<&str>) -> Feed {
+        Feed {
+            id: 1,
+            title: "Feed".to_string(),
+            url: url.to_string(),
+            site_url: site_url.map(|s| s.to_string()),
+            description: None,
+            last_fetched: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            retry_after: None,
+            custom_title: None,
+            folder: None,
+            refresh_minutes: None,
+            paused: false,
+            tags: Vec::new(),
+            posts_per_day: None,
+            last_post_at: None,
+            unread: 0,
+            reading: Default::default(),
+            priority: Default::default(),
+            source: Default::default(),
+            reader_url: None,
+            missing_count: 0,
+            moved_to: None,
+            repaired: false,
+        }
+    }
+
+    // ==================== normalize_domain ====================
+
+    #[test]
+    fn test_normalize_domain_strips_www_and_case() {
+        assert_eq!(
+            normalize_domain("https://WWW.Example.com/post/1"),
+            Some("example.com".to_string())
+        );
+        assert_eq!(normalize_domain("not a url"), None);
+    }
+
+    // ==================== candidate_domains ====================
+
+    #[test]
+    fn test_candidates_ranked_by_star_count() {
+        let articles = vec![
+            starred("https://a.com/1", Some("Ann")),
+            starred("https://b.com/1", None),
+            starred("https://a.com/2", Some("Ann")),
+            starred("https://a.com/3", Some("Bob")),
+            starred("https://b.com/2", None),
+            starred("https://c.com/1", None),
+        ];
+
+        let candidates = candidate_domains(&articles, &[], 10);
+
+        let domains: Vec<_> = candidates.iter().map(|c| c.domain.as_str()).collect();
+        assert_eq!(domains, vec!["a.com", "b.com", "c.com"]);
+        assert_eq!(candidates[0].starred_count, 3);
+        assert_eq!(candidates[0].authors, vec!["Ann", "Bob"]);
+    }
+
+    #[test]
+    fn test_candidates_skip_subscribed_sites() {
+        let articles = vec![
+            starred("https://arstechnica.com/story", None),
+            starred("https://www.theverge.com/story", None),
+            starred("https://new.example.org/story", None),
+        ];
+        let feeds = vec![
+            feed("https://feeds.arstechnica.com/arstechnica/index", None),
+            feed("https://rss.example.net/verge", Some("https://theverge.com")),
+        ];
+
+        let candidates = candidate_domains(&articles, &feeds, 10);
+
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].domain, "new.example.org");
+    }
+
+    #[test]
+    fn test_candidates_respect_limit() {
+        let articles: Vec<_> = (0..5)
+            .map(|i| starred(&format!("https://site{}.com/", i), None))
+            .collect();
+
+        assert_eq!(candidate_domains(&articles, &[], 2).len(), 2);
+    }
+}
@@ -0,0 +1,218 @@
+//! Fixing feeds that are almost XML. feed-rs doesn't reject a bare `&`, an
+//! HTML entity like `&nbsp;` or a stray control character; it quietly
+//! drops the text around it, often a whole title. So documents are checked
+//! before parsing, and the ones that aren't well-formed are repaired first.
+
+use std::collections::HashSet;
+
+use regex::Regex;
+
+/// HTML's Latin-1 entities, `&nbsp;` (U+00A0) to `&yuml;` (U+00FF) in order
+const LATIN1_ENTITIES: [&str; 96] = [
+    "nbsp", "iexcl", "cent", "pound", "curren", "yen", "brvbar", "sect", "uml", "copy", "ordf", "laquo", "not", "shy",
+    "reg", "macr", "deg", "plusmn", "sup2", "sup3", "acute", "micro", "para", "middot", "cedil", "sup1", "ordm",
+    "raquo", "frac14", "frac12", "frac34", "iquest", "Agrave", "Aacute", "Acirc", "Atilde", "Auml", "Aring", "AElig",
+    "Ccedil", "Egrave", "Eacute", "Ecirc", "Euml", "Igrave", "Iacute", "Icirc", "Iuml", "ETH", "Ntilde", "Ograve",
+    "Oacute", "Ocirc", "Otilde", "Ouml", "times", "Oslash", "Ugrave", "Uacute", "Ucirc", "Uuml", "Yacute", "THORN",
+    "szlig", "agrave", "aacute", "acirc", "atilde", "auml", "aring", "aelig", "ccedil", "egrave", "eacute", "ecirc",
+    "euml", "igrave", "iacute", "icirc", "iuml", "eth", "ntilde", "ograve", "oacute", "ocirc", "otilde", "ouml",
+    "divide", "oslash", "ugrave", "uacute", "ucirc", "uuml", "yacute", "thorn", "yuml",
+];
+
+/// The other HTML entities that turn up in feeds: typography, arrows, the
+/// euro
+const OTHER_ENTITIES: [(&str, u32); 46] = [
+    ("OElig", 338), ("oelig", 339), ("Scaron", 352), ("scaron", 353), ("Yuml", 376), ("fnof", 402), ("circ", 710),
+    ("tilde", 732), ("ensp", 8194), ("emsp", 8195), ("thinsp", 8201), ("zwnj", 8204), ("zwj", 8205), ("lrm", 8206),
+    ("rlm", 8207), ("ndash", 8211), ("mdash", 8212), ("lsquo", 8216), ("rsquo", 8217), ("sbquo", 8218),
+    ("ldquo", 8220), ("rdquo", 8221), ("bdquo", 8222), ("dagger", 8224), ("Dagger", 8225), ("bull", 8226),
+    ("hellip", 8230), ("permil", 8240), ("prime", 8242), ("Prime", 8243), ("lsaquo", 8249), ("rsaquo", 8250),
+    ("oline", 8254), ("frasl", 8260), ("euro", 8364), ("trade", 8482), ("larr", 8592), ("uarr", 8593),
+    ("rarr", 8594), ("darr", 8595), ("harr", 8596), ("minus", 8722), ("infin", 8734), ("ne", 8800), ("le", 8804),
+    ("ge", 8805),
+];
+
+/// The entities XML knows without a DTD
+const XML_ENTITIES: [&str; 5] = ["amp", "lt", "gt", "quot", "apos"];
+
+/// Longest entity name looked for after a `&`
+const MAX_ENTITY_NAME: usize = 32;
+
+/// `xml` made well-formed, or `None` if it already was (as far as bare
+/// ampersands, entities and control characters go). HTML entities become
+/// character references, unknown ones and bare `&`s are escaped so they
+/// show as typed, and control characters XML doesn't allow are dropped.
+/// CDATA sections and comments only lose their control characters.
+pub(crate) fn repair_xml(xml: &[u8]) -> Option<Vec<u8>> {
+    let declared = declared_entities(xml);
+    let mut out = Vec::with_capacity(xml.len());
+    let mut changed = false;
+    let mut i = 0;
+    while i < xml.len() {
+        let rest = &xml[i..];
+        let verbatim_end = if rest.starts_with(b"<![CDATA[") {
+            Some(b"]]>".as_slice())
+        } else if rest.starts_with(b"<!--") {
+            Some(b"-->".as_slice())
+        } else {
+            None
+        };
+        if let Some(end) = verbatim_end {
+            let len = find(rest, end).map_or(rest.len(), |at| at + end.len());
+            for &byte in &rest[..len] {
+                if is_control(byte) {
+                    changed = true;
+                } else {
+                    out.push(byte);
+                }
+            }
+            i += len;
+            continue;
+        }
+
+        match xml[i] {
+            b'&' => {
+                let (len, replacement) = match entity_at(rest, &declared) {
+                    Entity::Valid(len) => (len, None),
+                    Entity::Html(len, code) => (len, Some(format!("&#{};", code))),
+                    Entity::Illegal(len) => (len, Some(String::new())),
+                    Entity::Bare => (1, Some("&amp;".to_string())),
+                };
+                match replacement {
+                    Some(replacement) => {
+                        out.extend_from_slice(replacement.as_bytes());
+                        changed = true;
+                    }
+                    None => out.extend_from_slice(&rest[..len]),
+                }
+                i += len;
+            }
+            byte if is_control(byte) => {
+                changed = true;
+                i += 1;
+            }
+            byte => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    changed.then_some(out)
+}
+
+/// What follows a `&`
+#[derive(Debug, PartialEq, Eq)]
+enum Entity {
+    /// An XML entity or a character reference to an allowed character,
+    /// this many bytes long
+    Valid(usize),
+    /// An HTML entity standing for this character
+    Html(usize, u32),
+    /// A character reference to a character XML doesn't allow
+    Illegal(usize),
+    /// Not an entity: a bare ampersand, or a name nobody declared
+    Bare,
+}
+
+fn entity_at(rest: &[u8], declared: &HashSet<String>) -> Entity {
+    let Some(end) = rest.iter().take(MAX_ENTITY_NAME + 2).position(|&b| b == b';') else {
+        return Entity::Bare;
+    };
+    let Ok(name) = std::str::from_utf8(&rest[1..end]) else {
+        return Entity::Bare;
+    };
+    let len = end + 1;
+    if let Some(number) = name.strip_prefix('#') {
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hex) if !hex.is_empty() && hex.bytes().all(|b| b.is_ascii_hexdigit()) => u32::from_str_radix(hex, 16),
+            None if !number.is_empty() && number.bytes().all(|b| b.is_ascii_digit()) => number.parse(),
+            _ => return Entity::Bare,
+        };
+        return match code {
+            Ok(code) if is_xml_char(code) => Entity::Valid(len),
+            _ => Entity::Illegal(len),
+        };
+    }
+    let is_name = name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'));
+    if !is_name {
+        return Entity::Bare;
+    }
+    if XML_ENTITIES.contains(&name) || declared.contains(name) {
+        return Entity::Valid(len);
+    }
+    match html_entity(name) {
+        Some(code) => Entity::Html(len, code),
+        None => Entity::Bare,
+    }
+}
+
+fn html_entity(name: &str) -> Option<u32> {
+    if let Some(index) = LATIN1_ENTITIES.iter().position(|&entity| entity == name) {
+        return Some(0xA0 + index as u32);
+    }
+    OTHER_ENTITIES.iter().find(|(entity, _)| *entity == name).map(|&(_, code)| code)
+}
+
+/// Entities the document's DOCTYPE defines for itself
+fn declared_entities(xml: &[u8]) -> HashSet<String> {
+    if find(xml, b"<!ENTITY").is_none() {
+        return HashSet::new();
+    }
+    let entity = Regex::new(r"<!ENTITY\s+([A-Za-z][\w.-]*)").unwrap();
+    entity
+        .captures_iter(&String::from_utf8_lossy(xml))
+        .map(|cap| cap[1].to_string())
+        .collect()
+}
+
+/// Control characters XML 1.0 doesn't allow anywhere, even escaped
+fn is_control(byte: u8) -> bool {
+    byte < 0x20 && !matches!(byte, b'\t' | b'\n' | b'\r')
+}
+
+fn is_xml_char(code: u32) -> bool {
+    match code {
+        0x9 | 0xA | 0xD => true,
+        0xFFFE | 0xFFFF => false,
+        _ => code >= 0x20 && char::from_u32(code).is_some(),
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repair(xml: &str) -> Option<String> {
+        repair_xml(xml.as_bytes()).map(|fixed| String::from_utf8(fixed).unwrap())
+    }
+
+    #[test]
+    fn test_repairs_entities_ampersands_and_control_characters() {
+        assert_eq!(
+            repair("<title>Q&A: caf&eacute; &mdash; 5 &lt; 6 &amp; more&#8230;</title>").as_deref(),
+            Some("<title>Q&amp;A: caf&#233; &#8212; 5 &lt; 6 &amp; more&#8230;</title>")
+        );
+        assert_eq!(
+            repair("<link>https://x.example/?a=1&b=2&bogus;</link>").as_deref(),
+            Some("<link>https://x.example/?a=1&amp;b=2&amp;bogus;</link>")
+        );
+        assert_eq!(repair("<t>a\u{b}b&#0;c&#x1F;&#x41;</t>").as_deref(), Some("<t>abc&#x41;</t>"));
+        assert_eq!(repair("<t>&nbsp;&yuml;</t>").as_deref(), Some("<t>&#160;&#255;</t>"));
+        assert_eq!(LATIN1_ENTITIES[LATIN1_ENTITIES.len() - 1], "yuml");
+    }
+
+    #[test]
+    fn test_leaves_well_formed_xml_and_cdata_alone() {
+        assert_eq!(repair("<t a=\"1 &amp; 2\">caf&#xE9;\r\n\t&quot;x&apos;</t>"), None);
+        assert_eq!(repair("<d><![CDATA[Q&A &nbsp; <b>]]><!-- a & b --></d>"), None);
+        // Only the control character goes from CDATA
+        assert_eq!(repair("<d><![CDATA[a&b\u{0}]]></d>").as_deref(), Some("<d><![CDATA[a&b]]></d>"));
+        let declared = "<!DOCTYPE rss [<!ENTITY brand \"BeatCheck\">]><t>&brand;</t>";
+        assert_eq!(repair(declared), None);
+    }
+}
@@ -0,0 +1,167 @@
+//! Starred articles exported from NetNewsWire and Reeder. Both write JSON,
+//! either a JSON Feed (`items`) or a plain list of articles, with field
+//! names varying by app and version; an exported archive is a folder
+//! holding that beside the subscriptions as OPML.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::error::{AppError, Result};
+
+/// One starred article
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StarredItem {
+    pub url: String,
+    pub title: String,
+    pub author: Option<String>,
+    pub published_at: Option<DateTime<Utc>>,
+    /// The feed it came from, when the export says
+    pub feed_url: Option<String>,
+}
+
+pub fn parse_starred_file(path: &Path) -> Result<Vec<StarredItem>> {
+    let content = std::fs::read_to_string(path)?;
+    parse_starred(&content)
+}
+
+/// Every starred article with an http(s) link, in order. The list is the
+/// document itself or its `items`, `starred` or `articles`; a `feed_url`
+/// beside it (as in a JSON Feed) applies to articles that don't name one.
+pub fn parse_starred(json: &str) -> Result<Vec<StarredItem>> {
+    let document: Value =
+        serde_json::from_str(json).map_err(|e| AppError::Config(format!("Not a starred-items export: {}", e)))?;
+    let (items, feed_url) = match &document {
+        Value::Array(items) => (items, None),
+        Value::Object(fields) => {
+            let items = ["items", "starred", "articles"]
+                .iter()
+                .find_map(|key| fields.get(*key).and_then(Value::as_array))
+                .ok_or_else(|| AppError::Config("Not a starred-items export: no list of items".to_string()))?;
+            (items, feed_link(&document))
+        }
+        _ => return Err(AppError::Config("Not a starred-items export".to_string())),
+    };
+
+    Ok(items
+        .iter()
+        .filter_map(|item| {
+            let url = text(item, &["url", "link", "external_url", "externalURL", "href"])
+                .filter(|u| u.starts_with("http://") || u.starts_with("https://"))?;
+            Some(StarredItem {
+                title: text(item, &["title", "name"]).unwrap_or_else(|| url.clone()),
+                author: author(item),
+                published_at: date(item),
+                feed_url: feed_link(item).or_else(|| feed_url.clone()),
+                url,
+            })
+        })
+        .collect())
+}
+
+/// The first of `keys` holding non-blank text
+fn text(item: &Value, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .filter_map(|key| item.get(*key)?.as_str())
+        .map(str::trim)
+        .find(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+/// `author` as text or `{"name": ...}`, or the first of JSON Feed's `authors`
+fn author(item: &Value) -> Option<String> {
+    let name = |value: &Value| value.as_str().or_else(|| value.get("name")?.as_str()).map(str::trim).map(str::to_string);
+    item.get("author")
+        .and_then(name)
+        .or_else(|| item.get("authors")?.as_array()?.first().and_then(name))
+        .filter(|name| !name.is_empty())
+}
+
+/// The publication date as RFC 3339, RFC 2822 or Unix seconds
+fn date(item: &Value) -> Option<DateTime<Utc>> {
+    ["date_published", "datePublished", "published", "pubDate", "date"]
+        .iter()
+        .filter_map(|key| item.get(*key))
+        .find_map(|value| match value {
+            Value::Number(secs) => DateTime::from_timestamp(secs.as_f64()? as i64, 0),
+            Value::String(date) => DateTime::parse_from_rfc3339(date.trim())
+                .or_else(|_| DateTime::parse_from_rfc2822(date.trim()))
+                .ok()
+                .map(|d| d.with_timezone(&Utc)),
+            _ => None,
+        })
+}
+
+/// The feed's URL, given as text or as a `feed` object
+fn feed_link(item: &Value) -> Option<String> {
+    text(item, &["feed_url", "feedURL", "feedUrl"]).or_else(|| match item.get("feed")? {
+        Value::String(url) => Some(url.trim().to_string()).filter(|u| !u.is_empty()),
+        feed @ Value::Object(_) => text(feed, &["feed_url", "feedURL", "feedUrl", "url"]),
+        _ => None,
+    })
+}
+
+/// The OPML and JSON files in an exported archive folder, each sorted by name
+pub fn archive_files(dir: &Path) -> Result<(Vec<PathBuf>, Vec<PathBuf>)> {
+    let (mut opml, mut json) = (Vec::new(), Vec::new());
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        match path.extension().and_then(|ext| ext.to_str()).map(str::to_lowercase).as_deref() {
+            Some("opml") => opml.push(path),
+            Some("json") => json.push(path),
+            _ => {}
+        }
+    }
+    opml.sort();
+    json.sort();
+    Ok((opml, json))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_json_feed_export() {
+        let json = r#"{
+            "version": "https://jsonfeed.org/version/1.1",
+            "title": "Starred",
+            "feed_url": "https://example.com/feed.json",
+            "items": [
+                {"id": "1", "url": "https://example.com/chips", "title": "Chips",
+                 "authors": [{"name": "Ada"}], "date_published": "2026-03-02T09:00:00Z"},
+                {"id": "2", "url": "https://other.example/fabs", "feed_url": "https://other.example/rss"},
+                {"id": "3", "content_text": "no link"}
+            ]
+        }"#;
+        let items = parse_starred(json).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].title, "Chips");
+        assert_eq!(items[0].author.as_deref(), Some("Ada"));
+        assert_eq!(items[0].published_at.unwrap().to_rfc3339(), "2026-03-02T09:00:00+00:00");
+        assert_eq!(items[0].feed_url.as_deref(), Some("https://example.com/feed.json"));
+        assert_eq!(items[1].title, "https://other.example/fabs");
+        assert_eq!(items[1].feed_url.as_deref(), Some("https://other.example/rss"));
+    }
+
+    #[test]
+    fn test_parse_article_list_export() {
+        let json = r#"[
+            {"title": "Wafers", "link": "https://example.com/wafers", "author": "Bo",
+             "date": 1772442000, "feed": {"title": "Wire", "url": "https://example.com/rss"}},
+            {"title": "Old", "link": "https://example.com/old", "pubDate": "Mon, 02 Mar 2026 09:00:00 GMT"},
+            {"title": "Note", "link": "note://local"}
+        ]"#;
+        let items = parse_starred(json).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].author.as_deref(), Some("Bo"));
+        assert_eq!(items[0].published_at.unwrap().timestamp(), 1_772_442_000);
+        assert_eq!(items[0].feed_url.as_deref(), Some("https://example.com/rss"));
+        assert!(items[1].published_at.is_some());
+        assert_eq!(items[1].feed_url, None);
+
+        assert!(parse_starred("{\"title\": \"no items\"}").is_err());
+        assert!(parse_starred("not json").is_err());
+    }
+}
@@ -0,0 +1,231 @@
+//! Reading a feed document as it downloads: the items are cut out of the
+//! byte stream one by one, so a podcast archive with thousands of entries
+//! can be parsed in batches, and left undownloaded once the items reach
+//! what's already stored.
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+
+/// Items parsed at a time
+pub const BATCH_ITEMS: usize = 100;
+
+/// A feed this short is always read to the end, so edits to recent
+/// articles are still picked up
+const MIN_ITEMS_BEFORE_STOP: usize = 50;
+
+/// Splits a feed document into its head (everything before the first
+/// `<item>` or `<entry>`) and the items, as the bytes arrive. Whatever sits
+/// between items, and the closing tags, are dropped; `document` puts a
+/// batch back together as a feed of its own.
+#[derive(Debug, Default)]
+pub struct ItemSplitter {
+    /// Bytes not yet handed out as the head or an item
+    buffer: Vec<u8>,
+    head: Option<Vec<u8>>,
+    /// "item" or "entry", once the first one is found
+    tag: Option<&'static str>,
+}
+
+impl ItemSplitter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Take in the next chunk of the download, returning every item it completed
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<Vec<u8>> {
+        self.buffer.extend_from_slice(chunk);
+        if self.head.is_none() {
+            let Some((start, tag)) = ["item", "entry"]
+                .into_iter()
+                .filter_map(|tag| find_open_tag(&self.buffer, tag).map(|at| (at, tag)))
+                .min()
+            else {
+                return Vec::new();
+            };
+            self.head = Some(self.buffer.drain(..start).collect());
+            self.tag = Some(tag);
+        }
+        let Some(tag) = self.tag else {
+            return Vec::new();
+        };
+
+        let close = format!("</{}>", tag);
+        let mut items = Vec::new();
+        while let Some(start) = find_open_tag(&self.buffer, tag) {
+            let Some(end) = find(&self.buffer[start..], close.as_bytes()).map(|at| start + at + close.len()) else {
+                // Keep the partial item (and drop what came before it)
+                self.buffer.drain(..start);
+                break;
+            };
+            items.push(self.buffer[start..end].to_vec());
+            self.buffer.drain(..end);
+        }
+        items
+    }
+
+    /// The document so far when no items were found in it, to parse whole
+    pub fn into_unsplit(self) -> Option<Vec<u8>> {
+        self.head.is_none().then_some(self.buffer)
+    }
+
+    /// `items` as a feed document of their own: the head, the items, and
+    /// closing tags for whatever the head opened
+    pub fn document(&self, items: &[Vec<u8>]) -> Vec<u8> {
+        let head = self.head.as_deref().unwrap_or_default();
+        let closing: &[u8] = if find(head, b"<rdf:RDF").is_some() {
+            b"</rdf:RDF>"
+        } else if self.tag == Some("entry") {
+            b"</feed>"
+        } else {
+            b"</channel></rss>"
+        };
+        let mut document = Vec::with_capacity(head.len() + items.iter().map(Vec::len).sum::<usize>() + closing.len());
+        document.extend_from_slice(head);
+        for item in items {
+            document.extend_from_slice(item);
+        }
+        document.extend_from_slice(closing);
+        document
+    }
+}
+
+/// Where `<tag` opens an element (followed by `>` or whitespace)
+fn find_open_tag(haystack: &[u8], tag: &str) -> Option<usize> {
+    let open = format!("<{}", tag);
+    let mut from = 0;
+    while let Some(at) = find(&haystack[from..], open.as_bytes()).map(|at| from + at) {
+        match haystack.get(at + open.len()) {
+            Some(b'>') => return Some(at),
+            Some(c) if c.is_ascii_whitespace() => return Some(at),
+            // Not there yet; wait for more bytes
+            None => return None,
+            Some(_) => from = at + open.len(),
+        }
+    }
+    None
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+/// The item's publish (or update) date, if it has one that parses
+pub fn item_date(item: &[u8]) -> Option<DateTime<Utc>> {
+    let date_re = Regex::new(r"<(pubDate|published|updated|dc:date)(?:\s[^>]*)?>\s*([^<]+?)\s*</").unwrap();
+    let item = String::from_utf8_lossy(item);
+    let date = date_re.captures(&item)?;
+    DateTime::parse_from_rfc2822(&date[2])
+        .or_else(|_| DateTime::parse_from_rfc3339(&date[2]))
+        .ok()
+        .map(|d| d.with_timezone(&Utc))
+}
+
+/// Decides when the rest of a feed can go unread: once past
+/// `MIN_ITEMS_BEFORE_STOP` items, at the first one no newer than the
+/// newest stored article, as long as the feed runs newest first (an
+/// oldest-first feed has its new items at the end)
+#[derive(Debug)]
+pub struct EarlyStop {
+    newest_stored: Option<DateTime<Utc>>,
+    first_date: Option<DateTime<Utc>>,
+    seen: usize,
+}
+
+impl EarlyStop {
+    pub fn new(newest_stored: Option<DateTime<Utc>>) -> Self {
+        Self {
+            newest_stored,
+            first_date: None,
+            seen: 0,
+        }
+    }
+
+    /// Whether to stop before the item dated `date`
+    pub fn stop_at(&mut self, date: Option<DateTime<Utc>>) -> bool {
+        self.seen += 1;
+        let Some(date) = date else {
+            return false;
+        };
+        let first = *self.first_date.get_or_insert(date);
+        let Some(newest_stored) = self.newest_stored else {
+            return false;
+        };
+        self.seen > MIN_ITEMS_BEFORE_STOP && date <= newest_stored && date < first
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_items_split_across_chunks() {
+        let xml = br#"<?xml version="1.0"?><rss version="2.0"><channel><title>Show</title><image><url>x</url></image>
+<item><title>One</title></item>
+<item>
+<title>Two</title></item></channel></rss>"#;
+        let mut splitter = ItemSplitter::new();
+        let mut items = Vec::new();
+        for chunk in xml.chunks(7) {
+            items.extend(splitter.push(chunk));
+        }
+        assert_eq!(items, [b"<item><title>One</title></item>".to_vec(), b"<item>\n<title>Two</title></item>".to_vec()]);
+
+        let document = splitter.document(&items[1..]);
+        let feed = feed_rs::parser::parse(&document[..]).unwrap();
+        assert_eq!(feed.title.unwrap().content, "Show");
+        assert_eq!(feed.entries.len(), 1);
+        assert_eq!(feed.entries[0].title.as_ref().unwrap().content, "Two");
+    }
+
+    #[test]
+    fn test_atom_and_rdf_batches_parse() {
+        let atom = br#"<feed xmlns="http://www.w3.org/2005/Atom"><title>Blog</title><id>urn:b</id>
+<entry><id>urn:1</id><title>A</title><updated>2026-03-02T09:00:00Z</updated></entry></feed>"#;
+        let mut splitter = ItemSplitter::new();
+        let items = splitter.push(atom);
+        assert_eq!(items.len(), 1);
+        let feed = feed_rs::parser::parse(&splitter.document(&items)[..]).unwrap();
+        assert_eq!(feed.entries[0].id, "urn:1");
+        assert_eq!(item_date(&items[0]), DateTime::parse_from_rfc3339("2026-03-02T09:00:00Z").ok().map(|d| d.to_utc()));
+
+        let rdf = br#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns="http://purl.org/rss/1.0/">
+<channel rdf:about="x"><title>Old</title><items><rdf:Seq/></items></channel>
+<item rdf:about="https://old.example/1"><title>One</title><link>https://old.example/1</link></item></rdf:RDF>"#;
+        let mut splitter = ItemSplitter::new();
+        let items = splitter.push(rdf);
+        let feed = feed_rs::parser::parse(&splitter.document(&items)[..]).unwrap();
+        assert_eq!(feed.entries.len(), 1);
+
+        let mut splitter = ItemSplitter::new();
+        assert!(splitter.push(b"<rss><channel><title>Empty</title></channel></rss>").is_empty());
+        assert!(splitter.into_unsplit().is_some());
+    }
+
+    #[test]
+    fn test_early_stop_only_for_long_newest_first_feeds() {
+        let day = |d: u32| Some(DateTime::parse_from_rfc3339(&format!("2026-03-{:02}T09:00:00Z", d)).unwrap().to_utc());
+        let newest_stored = day(10);
+
+        // Newest first: read the first 50 regardless, then stop at an old one
+        let mut stop = EarlyStop::new(newest_stored);
+        assert!(!stop.stop_at(day(20)));
+        for _ in 1..MIN_ITEMS_BEFORE_STOP {
+            assert!(!stop.stop_at(day(5)));
+        }
+        assert!(!stop.stop_at(None));
+        assert!(stop.stop_at(day(9)));
+
+        // Oldest first: never
+        let mut stop = EarlyStop::new(newest_stored);
+        for _ in 0..=MIN_ITEMS_BEFORE_STOP * 2 {
+            assert!(!stop.stop_at(day(1)));
+        }
+        // Nothing stored yet: never
+        let mut stop = EarlyStop::new(None);
+        assert!(!stop.stop_at(day(20)));
+        for _ in 0..=MIN_ITEMS_BEFORE_STOP {
+            assert!(!stop.stop_at(day(1)));
+        }
+    }
+}
@@ -0,0 +1,161 @@
+//! Subscriptions kept in a newsboat-style `urls` file: one feed URL per
+//! line, then its tags, with `"~Name"` naming the feed. Lines starting
+//! with `#` are comments.
+
+use crate::models::Feed;
+
+/// One feed listed in a urls file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UrlsEntry {
+    pub url: String,
+    pub tags: Vec<String>,
+    /// From a `"~Name"` tag
+    pub title: Option<String>,
+}
+
+/// Every http(s) feed in the file, first listing of a URL winning.
+/// newsboat's `query:`, `exec:` and `filter:` lines have no place here and
+/// are skipped, as are `!` (hidden) markers.
+pub fn parse_urls(content: &str) -> Vec<UrlsEntry> {
+    let mut entries: Vec<UrlsEntry> = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut words = split_words(line).into_iter();
+        let Some(url) = words.next().filter(|u| u.starts_with("http://") || u.starts_with("https://")) else {
+            continue;
+        };
+        if entries.iter().any(|e| e.url == url) {
+            continue;
+        }
+        let mut entry = UrlsEntry {
+            url,
+            tags: Vec::new(),
+            title: None,
+        };
+        for word in words {
+            if let Some(title) = word.strip_prefix('~') {
+                entry.title = Some(title.trim().to_string()).filter(|t| !t.is_empty());
+            } else if word != "!" {
+                let tag = word.replace(',', " ").trim().to_lowercase();
+                if !tag.is_empty() && !entry.tags.contains(&tag) {
+                    entry.tags.push(tag);
+                }
+            }
+        }
+        entries.push(entry);
+    }
+    entries
+}
+
+/// Space-separated words, `"double quotes"` keeping spaces in one; a
+/// `#` outside quotes starts a comment
+fn split_words(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut chars = line.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '#' {
+            break;
+        } else if c == '"' {
+            chars.next();
+            words.push(chars.by_ref().take_while(|&c| c != '"').collect());
+        } else {
+            let mut word = String::new();
+            while let Some(&c) = chars.peek().filter(|c| !c.is_whitespace()) {
+                word.push(c);
+                chars.next();
+            }
+            words.push(word);
+        }
+    }
+    words
+}
+
+/// The feeds as a urls file, to start one from the current subscriptions
+pub fn format_urls(feeds: &[Feed]) -> String {
+    let quote = |word: &str| {
+        if word.contains(char::is_whitespace) || word.contains('#') {
+            format!("\"{}\"", word.replace('"', ""))
+        } else {
+            word.to_string()
+        }
+    };
+    let mut out = String::from("# Feeds for beatcheck: URL, then tags; \"~Name\" names the feed\n");
+    for feed in feeds {
+        let mut line = feed.url.clone();
+        for tag in &feed.tags {
+            line.push(' ');
+            line.push_str(&quote(tag));
+        }
+        if let Some(title) = &feed.custom_title {
+            line.push(' ');
+            line.push_str(&format!("\"~{}\"", title.replace('"', "")));
+        }
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_urls_tags_titles_and_comments() {
+        let content = r#"
+# Tech
+https://example.com/feed.xml tech "Open Source" "~Example Blog"
+https://news.example.org/rss   News  tech,policy  # the wire
+query:Unread:unread = "yes"
+exec:~/bin/feed.sh
+https://example.com/feed.xml duplicate
+https://hidden.example/atom ! quiet
+"#;
+        assert_eq!(
+            parse_urls(content),
+            [
+                UrlsEntry {
+                    url: "https://example.com/feed.xml".into(),
+                    tags: vec!["tech".into(), "open source".into()],
+                    title: Some("Example Blog".into()),
+                },
+                UrlsEntry {
+                    url: "https://news.example.org/rss".into(),
+                    tags: vec!["news".into(), "tech policy".into()],
+                    title: None,
+                },
+                UrlsEntry {
+                    url: "https://hidden.example/atom".into(),
+                    tags: vec!["quiet".into()],
+                    title: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_urls_reads_back() {
+        let mut feed: Feed = serde_json::from_value(serde_json::json!({
+            "id": 1, "title": "Wire", "url": "https://news.example.org/rss", "site_url": null,
+            "description": null, "last_fetched": null, "created_at": "2026-03-02T09:00:00Z",
+            "updated_at": "2026-03-02T09:00:00Z", "retry_after": null, "custom_title": "The \"Wire\"",
+            "folder": null, "refresh_minutes": null, "paused": false, "tags": ["open source", "news"]
+        }))
+        .unwrap();
+        feed.tags.sort();
+        let entries = parse_urls(&format_urls(&[feed]));
+        assert_eq!(
+            entries,
+            [UrlsEntry {
+                url: "https://news.example.org/rss".into(),
+                tags: vec!["news".into(), "open source".into()],
+                title: Some("The Wire".into()),
+            }]
+        );
+    }
+}
@@ -0,0 +1,135 @@
+//! Time spent reading, counted while an article is on screen and keys keep
+//! being pressed, so walking away from the terminal doesn't run the clock.
+//! Each stretch of use without a long break is a sitting, which `[focus]
+//! nudge_minutes` can point out once it runs long.
+
+use std::time::{Duration, Instant};
+
+/// A gap between key presses longer than this isn't counted as reading
+const IDLE_AFTER: Duration = Duration::from_secs(120);
+
+/// A gap this long ends the sitting
+const BREAK_AFTER: Duration = Duration::from_secs(10 * 60);
+
+/// Hand over an article's time at least this often while it's being read
+const FLUSH_EVERY: Duration = Duration::from_secs(30);
+
+#[derive(Debug)]
+pub struct FocusTimer {
+    last_activity: Instant,
+    last_tick: Instant,
+    sitting_since: Instant,
+    /// The article being read and its time not handed over yet
+    pending: Option<(i64, Duration)>,
+    nudged: bool,
+}
+
+impl FocusTimer {
+    pub fn new(now: Instant) -> Self {
+        Self {
+            last_activity: now,
+            last_tick: now,
+            sitting_since: now,
+            pending: None,
+            nudged: false,
+        }
+    }
+
+    /// A key was pressed
+    pub fn activity(&mut self, now: Instant) {
+        if now.duration_since(self.last_activity) >= BREAK_AFTER {
+            self.sitting_since = now;
+            self.nudged = false;
+        }
+        self.last_activity = now;
+    }
+
+    /// Count the time since the last tick toward `article`, the one on
+    /// screen. Returns an article's time to save once another article
+    /// replaces it, or every `FLUSH_EVERY` of reading.
+    pub fn tick(&mut self, now: Instant, article: Option<i64>) -> Option<(i64, Duration)> {
+        let elapsed = now.duration_since(self.last_tick);
+        self.last_tick = now;
+
+        let mut done = None;
+        if self.pending.is_some_and(|(id, _)| Some(id) != article) {
+            done = self.pending.take();
+        }
+        if let Some(id) = article.filter(|_| now.duration_since(self.last_activity) < IDLE_AFTER) {
+            let (_, time) = self.pending.get_or_insert((id, Duration::ZERO));
+            *time += elapsed;
+        }
+        if done.is_none() && self.pending.is_some_and(|(_, time)| time >= FLUSH_EVERY) {
+            done = self.pending.take();
+        }
+        done.filter(|(_, time)| !time.is_zero())
+    }
+
+    /// Time counted toward `article` but not handed over yet
+    pub fn unsaved(&self, article: i64) -> Duration {
+        match self.pending {
+            Some((id, time)) if id == article => time,
+            _ => Duration::ZERO,
+        }
+    }
+
+    /// Hand over whatever is left, when quitting
+    pub fn take_pending(&mut self) -> Option<(i64, Duration)> {
+        self.pending.take().filter(|(_, time)| !time.is_zero())
+    }
+
+    /// True once per sitting, when it has gone on for `after`
+    pub fn nudge(&mut self, now: Instant, after: Duration) -> bool {
+        let sitting = now.duration_since(self.sitting_since);
+        let going = now.duration_since(self.last_activity) < BREAK_AFTER;
+        if self.nudged || !going || sitting < after {
+            return false;
+        }
+        self.nudged = true;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECOND: Duration = Duration::from_secs(1);
+
+    #[test]
+    fn test_counts_only_while_keys_are_pressed() {
+        let start = Instant::now();
+        let mut timer = FocusTimer::new(start);
+        assert_eq!(timer.tick(start + 10 * SECOND, Some(1)), None);
+        assert_eq!(timer.unsaved(1), 10 * SECOND);
+
+        // Switching articles hands over the first one's time
+        assert_eq!(timer.tick(start + 15 * SECOND, Some(2)), Some((1, 10 * SECOND)));
+
+        // Idle for five minutes; none of it counts
+        assert_eq!(timer.tick(start + 315 * SECOND, Some(2)), None);
+        assert_eq!(timer.unsaved(2), 5 * SECOND);
+        timer.activity(start + 315 * SECOND);
+        assert_eq!(timer.tick(start + 345 * SECOND, Some(2)), Some((2, 35 * SECOND)));
+        assert_eq!(timer.take_pending(), None);
+    }
+
+    #[test]
+    fn test_nudges_once_per_sitting() {
+        let start = Instant::now();
+        let mut timer = FocusTimer::new(start);
+        let after = 45 * 60 * SECOND;
+        for minute in 1..=44 {
+            timer.activity(start + minute * 60 * SECOND);
+        }
+        assert!(!timer.nudge(start + 44 * 60 * SECOND, after));
+        timer.activity(start + 45 * 60 * SECOND);
+        assert!(timer.nudge(start + 45 * 60 * SECOND, after));
+        assert!(!timer.nudge(start + 46 * 60 * SECOND, after));
+
+        // A break starts a new sitting
+        let back = start + 60 * 60 * SECOND;
+        timer.activity(back);
+        assert!(!timer.nudge(back + 60 * SECOND, after));
+    }
+}
@@ -1,10 +1,24 @@
 pub mod ai;
+pub mod backup;
+pub mod bundle;
 pub mod app;
 pub mod blocklist;
+pub mod calendar;
+pub mod cli;
 pub mod config;
 pub mod db;
+pub mod diff;
 pub mod error;
+pub mod export;
+pub mod metrics;
 pub mod feed;
+pub mod focus;
 pub mod models;
+pub mod schedule;
+pub mod server;
 pub mod services;
+pub mod stories;
+pub mod subscriptions;
+pub mod trends;
 pub mod tui;
+pub mod watchlist;
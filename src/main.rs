@@ -10,20 +10,35 @@ use crossterm::{
 use ratatui::prelude::*;
 
 mod ai;
+mod backup;
+mod bundle;
 mod app;
 mod blocklist;
+mod calendar;
+mod cli;
 mod config;
 mod db;
+mod diff;
 mod error;
+mod export;
+mod metrics;
 mod feed;
+mod focus;
 mod models;
+mod schedule;
+mod server;
 mod services;
+mod stories;
+mod subscriptions;
+mod trends;
 mod tui;
+mod watchlist;
 
 use app::App;
 use config::Config;
 use error::Result;
-use tui::{draw, handle_key_event};
+use models::AuthorRule;
+use tui::{draw, handle_key_event, run_editor, NoteEdit};
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -95,23 +110,698 @@ async fn main() -> Result<()> {
     // Check for --refresh flag (headless refresh)
     let headless_refresh = args.len() >= 2 && args[1] == "--refresh";
 
+    // Check for --serve flag (daemon with API and web UI)
+    let serve = args.len() >= 2 && args[1] == "--serve";
+
+    // Create a server account: --add-user NAME, password read from stdin
+    if args.len() >= 3 && args[1] == "--add-user" {
+        eprint!("Password for {}: ", args[2]);
+        let mut password = String::new();
+        io::stdin().read_line(&mut password)?;
+        let token = server::add_user(&config, &args[2], password.trim_end_matches(['\r', '\n'])).await?;
+        println!("Created user {}", args[2]);
+        println!("API token (shown once): {}", token);
+        return Ok(());
+    }
+
+    // Server API tokens: token create --scope read|read-write | list | revoke ID...
+    if args.len() >= 2 && args[1] == "token" {
+        match cli::parse_token_args(&args[2..])? {
+            cli::TokenCommand::Create { scope, user, name } => {
+                let (username, token) = server::create_token(&config, user.as_deref(), name.as_deref(), scope).await?;
+                println!("Created a {} token for {}", scope.as_str(), username);
+                println!("API token (shown once): {}", token);
+            }
+            cli::TokenCommand::List => {
+                let tokens = server::list_tokens(&config).await?;
+                if tokens.is_empty() {
+                    println!("No API tokens");
+                }
+                for token in &tokens {
+                    println!("{}", cli::format_api_token(token));
+                }
+            }
+            cli::TokenCommand::Revoke(ids) => {
+                server::revoke_tokens(&config, &ids).await?;
+                println!("Revoked {} token{}", ids.len(), if ids.len() == 1 { "" } else { "s" });
+            }
+        }
+        return Ok(());
+    }
+
     // Initialize app
     let mut app = App::new(&config).await?;
 
-    // If import path provided, import OPML and exit
+    // If import path provided, import OPML (or a Pocket/bookmarks HTML export,
+    // NetNewsWire/Reeder starred JSON, or a folder of both) and exit
     if let Some(path) = import_path {
-        app.import_opml(&path).await?;
-        println!("Imported feeds from {:?}", path);
+        let extension = path.extension().and_then(|ext| ext.to_str()).map(str::to_lowercase);
+        if path.is_dir() {
+            let (opml, json) = feed::archive_files(&path)?;
+            if opml.is_empty() && json.is_empty() {
+                println!("No OPML or JSON files in {:?}", path);
+            }
+            for file in &opml {
+                let (added, skipped) = app.import_opml(file).await?;
+                println!("Imported {} feeds from {:?} ({} duplicates skipped)", added, file, skipped);
+            }
+            // Let the new feeds' articles arrive first, so starred ones are
+            // matched to them rather than stored twice
+            if !opml.is_empty() {
+                app.refresh_feeds_blocking().await?;
+            }
+            for file in json {
+                let (added, existing) = app.import_starred(&file).await?;
+                println!("Imported {} starred articles from {:?} ({} already here)", added, file, existing);
+            }
+        } else if matches!(extension.as_deref(), Some("html" | "htm")) {
+            let (added, existing) = app.import_bookmarks(&path).await?;
+            println!("Imported {} saved articles from {:?} ({} already here)", added, path, existing);
+        } else if extension.as_deref() == Some("json") {
+            let (added, existing) = app.import_starred(&path).await?;
+            println!("Imported {} starred articles from {:?} ({} already here)", added, path, existing);
+        } else {
+            let (added, skipped) = app.import_opml(&path).await?;
+            println!("Imported {} feeds from {:?} ({} duplicates skipped)", added, path, skipped);
+        }
+        return Ok(());
+    }
+
+    // Export one article: --export-pdf ID
+    if args.len() >= 3 && args[1] == "--export-pdf" {
+        let id = args[2]
+            .parse()
+            .map_err(|_| error::AppError::Config(format!("Invalid article id: {}", args[2])))?;
+        let path = app.export_pdf(id).await?;
+        println!("Exported to {}", path.display());
+        return Ok(());
+    }
+
+    // Re-read feed titles/descriptions: --refresh-metadata [FEED_ID] [--keep-titles]
+    if args.len() >= 2 && args[1] == "--refresh-metadata" {
+        let keep_titles = args[2..].iter().any(|a| a == "--keep-titles");
+        let feed_id = match args[2..].iter().find(|a| !a.starts_with("--")) {
+            Some(id) => Some(
+                id.parse()
+                    .map_err(|_| error::AppError::Config(format!("Invalid feed id: {}", id)))?,
+            ),
+            None => None,
+        };
+        let (updated, failed) = app.refresh_feed_metadata(feed_id, keep_titles).await?;
+        println!("Updated metadata for {} feeds ({} failed)", updated, failed);
+        return Ok(());
+    }
+
+    // Feed management: feeds list | bulk [SELECT...] [CHANGE...] | archived | restore ID | move ID
+    if args.len() >= 2 && args[1] == "feeds" {
+        match args.get(2).map(String::as_str) {
+            Some("list") => {
+                let unread_only = args[3..].iter().any(|a| a == "--unread");
+                let search = args[3..]
+                    .iter()
+                    .position(|a| a == "--search")
+                    .map(|i| args.get(i + 4).map(|text| text.to_lowercase()).unwrap_or_default());
+                let mut feeds: Vec<&models::Feed> = app
+                    .feeds
+                    .iter()
+                    .filter(|f| !unread_only || f.unread > 0)
+                    .filter(|f| search.as_deref().is_none_or(|text| f.matches_search(text)))
+                    .collect();
+                if args[3..].iter().any(|a| a == "--by-volume") {
+                    cli::sort_by_volume(&mut feeds);
+                }
+                for feed in feeds {
+                    println!("{}", cli::format_feed_line(feed));
+                    for line in cli::format_source_lines(feed) {
+                        println!("{}", line);
+                    }
+                }
+                let folders = app.folder_unread();
+                if folders.iter().any(|(_, unread)| *unread > 0) {
+                    println!();
+                    for (folder, unread) in folders.iter().filter(|(_, unread)| !unread_only || *unread > 0) {
+                        println!("{:>6} unread  {}/", unread, folder);
+                    }
+                }
+            }
+            Some("bulk") => {
+                let (selector, update) = cli::parse_bulk_args(&args[3..])?;
+                let ids: Vec<i64> = selector.select(&app.feeds).iter().map(|f| f.id).collect();
+                if ids.is_empty() {
+                    println!("No feeds match");
+                    return Ok(());
+                }
+                let verb = match update.remove {
+                    Some(db::FeedRemoval::Archive) => "Archived",
+                    Some(_) => "Deleted",
+                    None => "Updated",
+                };
+                let count = app.bulk_update_feeds(ids, update).await?;
+                println!("{} {} feeds", verb, count);
+            }
+            Some("export") => {
+                let parsed = cli::parse_opml_export_args(&args[3..])?;
+                let feeds = parsed.selector.select(&app.feeds);
+                let group = parsed.selector.folder.as_ref().or(parsed.selector.tag.as_ref());
+                let head = feed::OpmlHead {
+                    title: parsed.head.title.or_else(|| group.map(|group| format!("BeatCheck Feeds: {}", group))),
+                    owner_name: parsed.head.owner_name.or_else(|| config.export.owner_name.clone()),
+                    owner_email: parsed.head.owner_email.or_else(|| config.export.owner_email.clone()),
+                };
+                feed::export_opml_file(&parsed.path, &feeds, &head)?;
+                println!("Exported {} feeds to {}", feeds.len(), parsed.path.display());
+            }
+            Some("archived") => {
+                for feed in app.repository.get_archived_feeds().await? {
+                    println!("{}", cli::format_feed_line(&feed));
+                }
+            }
+            Some("restore") => {
+                let id = args
+                    .get(3)
+                    .and_then(|id| id.parse().ok())
+                    .ok_or_else(|| error::AppError::Config("feeds restore needs a feed id".to_string()))?;
+                if app.restore_feed(id).await? {
+                    println!("Restored feed {}", id);
+                } else {
+                    println!("Feed {} isn't archived", id);
+                }
+            }
+            Some("move") => {
+                let id = args
+                    .get(3)
+                    .and_then(|id| id.parse().ok())
+                    .ok_or_else(|| error::AppError::Config("feeds move needs a feed id".to_string()))?;
+                match app.accept_feed_move(id).await? {
+                    Some(url) => println!("Feed {} now fetches {}", id, url),
+                    None => println!("No new URL found for feed {}", id),
+                }
+            }
+            _ => println!("{}", cli::FEEDS_USAGE),
+        }
+        return Ok(());
+    }
+
+    // Summarize unread articles in bulk: summarize SELECT... | --failed [--jobs N]
+    if args.len() >= 2 && args[1] == "summarize" {
+        if args.len() == 2 {
+            println!("{}", cli::SUMMARIZE_USAGE);
+            return Ok(());
+        }
+        let parsed = cli::parse_summarize_args(&args[2..])?;
+        let (mut generated, mut reused, mut failed) = (0, 0, 0);
+        let jobs = parsed.jobs.unwrap_or(config.summaries.concurrency);
+        let report = |article: &models::Article, outcome: &app::BatchOutcome, done: usize, total: usize| {
+            match outcome {
+                app::BatchOutcome::Generated => generated += 1,
+                app::BatchOutcome::Reused => reused += 1,
+                app::BatchOutcome::Failed(e) => {
+                    failed += 1;
+                    println!("[{}/{}] FAILED {}: {}", done, total, article.title, e);
+                    return;
+                }
+            }
+            println!("[{}/{}] {}", done, total, article.title);
+        };
+        if parsed.failed {
+            app.retry_failed_summaries(jobs, report).await?;
+        } else {
+            let ids: Vec<i64> = parsed.selector.select(&app.feeds).iter().map(|f| f.id).collect();
+            app.summarize_unread(&ids, jobs, report).await?;
+        }
+        println!(
+            "Summarized {} articles ({} reused from duplicates), {} failed",
+            generated + reused,
+            reused,
+            failed
+        );
+        return Ok(());
+    }
+
+    // Fetch page text for a backlog: fulltext [SELECT...] [--jobs N]
+    if args.len() >= 2 && args[1] == "fulltext" {
+        if args.get(2).is_some_and(|a| a == "--help") {
+            println!("{}", cli::FULLTEXT_USAGE);
+            return Ok(());
+        }
+        let parsed = cli::parse_fulltext_args(&args[2..])?;
+        let ids: Vec<i64> = parsed.selector.select(&app.feeds).iter().map(|f| f.id).collect();
+        let started = std::time::Instant::now();
+        let (mut found, mut total) = (0, 0);
+        let report = |article: &models::Article, ok: bool, done: usize, queued: usize| {
+            total = queued;
+            if ok {
+                found += 1;
+                println!("[{}/{}] {}", done, queued, article.title);
+            } else {
+                println!("[{}/{}] no text: {}", done, queued, article.title);
+            }
+        };
+        app.fetch_full_text_backlog(&ids, parsed.jobs.unwrap_or(cli::FULLTEXT_JOBS), report)
+            .await?;
+        let minutes = started.elapsed().as_secs_f64() / 60.0;
+        println!(
+            "Fetched text for {} of {} articles in {:.1} min ({:.1} articles/min)",
+            found,
+            total,
+            minutes,
+            if minutes > 0.0 { total as f64 / minutes } else { 0.0 }
+        );
+        return Ok(());
+    }
+
+    // Terms trending in headlines over the last day, with example headlines
+    if args.len() >= 2 && args[1] == "trends" {
+        for trend in trends::load_trends(&app.repository, None, 20).await? {
+            println!(
+                "{}  ({} headlines from {} feeds, usually {:.1}/day)",
+                trend.term, trend.recent, trend.feeds, trend.baseline
+            );
+            for example in &trend.examples {
+                println!("    {}", example.title);
+            }
+        }
+        return Ok(());
+    }
+
+    // Entity watchlists: watch [list] [--days N] | add LIST NAME... | remove LIST [NAME...]
+    if args.len() >= 2 && args[1] == "watch" {
+        match args.get(2).map(String::as_str) {
+            None | Some("list") | Some("--days") => {
+                let days = cli::parse_watch_days(&args[2..])?;
+                let timelines = app.watch_timelines(days).await?;
+                let width = timelines.iter().map(|t| t.entity.chars().count()).max().unwrap_or(0);
+                for timeline in &timelines {
+                    println!("{}", cli::format_timeline(timeline, width));
+                }
+            }
+            Some("add") if args.len() >= 5 => {
+                let added = app.repository.add_to_watchlist(&args[3], args[4..].to_vec()).await?;
+                println!("Added {} to watchlist {}", added, args[3]);
+            }
+            Some("remove") if args.len() >= 4 => {
+                let removed = app.repository.remove_from_watchlist(&args[3], args[4..].to_vec()).await?;
+                println!("Removed {} from watchlist {}", removed, args[3]);
+            }
+            _ => println!("{}", cli::WATCH_USAGE),
+        }
+        return Ok(());
+    }
+
+    // Followed and muted authors: authors [list] | follow NAME... | mute NAME... | clear NAME...
+    if args.len() >= 2 && args[1] == "authors" {
+        let rule = match args.get(2).map(String::as_str) {
+            None | Some("list") => {
+                for (name, rule) in app.repository.get_author_rules().await? {
+                    println!("{:<6}  {}", rule.as_str(), name);
+                }
+                return Ok(());
+            }
+            Some("follow") => Some(AuthorRule::Follow),
+            Some("mute") => Some(AuthorRule::Mute),
+            Some("clear") => None,
+            _ => {
+                println!("{}", cli::AUTHORS_USAGE);
+                return Ok(());
+            }
+        };
+        if args.len() < 4 {
+            println!("{}", cli::AUTHORS_USAGE);
+            return Ok(());
+        }
+        for name in &args[3..] {
+            app.repository.set_author_rule(name, rule).await?;
+        }
+        println!("Updated {} authors", args.len() - 3);
+        return Ok(());
+    }
+
+    // Keyword and site blocklist: blocklist [list] | add ENTRY... | remove ENTRY...
+    if args.len() >= 2 && args[1] == "blocklist" {
+        let blocklist = app.blocklist();
+        match args.get(2).map(String::as_str) {
+            None | Some("list") => {
+                let subscriptions = blocklist.subscriptions();
+                for entry in blocklist.entries() {
+                    if !subscriptions.iter().any(|s| entry.ends_with(&s.url)) {
+                        println!("{}", entry);
+                    }
+                }
+                for subscription in &subscriptions {
+                    println!("{}", cli::format_subscription(subscription));
+                }
+            }
+            Some("add") if args.len() >= 4 => {
+                for entry in &args[3..] {
+                    match blocklist.add(entry)? {
+                        Some(entry) => println!("Blocked {}", entry),
+                        None => println!("Already blocked: {}", entry),
+                    }
+                }
+            }
+            Some("remove") if args.len() >= 4 => {
+                for entry in &args[3..] {
+                    if blocklist.remove(entry)? {
+                        println!("Unblocked {}", entry);
+                    } else {
+                        println!("Not in the blocklist: {}", entry);
+                    }
+                }
+            }
+            Some("subscribe") if args.len() == 4 => {
+                match blocklist.add(&format!("include {}", args[3]))? {
+                    Some(entry) => println!("Subscribed: {}", entry),
+                    None => println!("Already subscribed to {}", args[3]),
+                }
+                print_blocklist_downloads(blocklist.refresh_subscriptions(config.blocklist.refresh().unwrap_or(Duration::MAX)).await);
+            }
+            Some("unsubscribe") if args.len() == 4 => {
+                if blocklist.remove(&format!("include {}", args[3]))? {
+                    println!("Unsubscribed from {}", args[3]);
+                } else {
+                    println!("Not subscribed to {}", args[3]);
+                }
+            }
+            Some(toggle @ ("enable" | "disable")) if args.len() == 4 => {
+                if blocklist.set_subscription_enabled(&args[3], toggle == "enable")? {
+                    println!("{}d {}", if toggle == "enable" { "Enable" } else { "Disable" }, args[3]);
+                } else {
+                    println!("Not subscribed to {}", args[3]);
+                }
+            }
+            Some("refresh") if args.len() == 3 => {
+                print_blocklist_downloads(blocklist.refresh_subscriptions(Duration::ZERO).await);
+            }
+            _ => println!("{}", cli::BLOCKLIST_USAGE),
+        }
+        return Ok(());
+    }
+
+    // Article tags: tags [list] | rename OLD NEW | merge INTO FROM... | delete TAG...
+    if args.len() >= 2 && args[1] == "tags" {
+        match cli::parse_tags_args(&args[2..])? {
+            cli::TagCommand::List => {
+                for (tag, articles) in app.repository.tag_counts(None, i64::MAX as usize).await? {
+                    println!("{:>6}  {}", articles, tag);
+                }
+            }
+            cli::TagCommand::Merge { from, into } => {
+                let changed = app.repository.merge_tags(from, into.clone()).await?;
+                println!("Retagged {} articles as {}", changed, into);
+            }
+            cli::TagCommand::Delete(tags) => {
+                let removed = app.repository.delete_tags(tags).await?;
+                println!("Removed {} tags from articles", removed);
+            }
+        }
+        return Ok(());
+    }
+
+    // Auto-tag rules: autotag [list] | add TAG QUERY... | enable|disable|remove ID...
+    if args.len() >= 2 && args[1] == "autotag" {
+        match cli::parse_autotag_args(&args[2..])? {
+            cli::AutotagCommand::List => {
+                for rule in app.repository.get_tag_rules().await? {
+                    println!("{}", cli::format_tag_rule(&rule));
+                }
+            }
+            cli::AutotagCommand::Add { tag, query } => {
+                let id = app.repository.add_tag_rule(query, tag.clone()).await?;
+                println!("Rule {} will tag new matching articles #{}", id, tag);
+            }
+            cli::AutotagCommand::SetEnabled(ids, enabled) => {
+                for id in ids {
+                    if !app.repository.set_tag_rule_enabled(id, enabled).await? {
+                        println!("No rule {}", id);
+                    }
+                }
+            }
+            cli::AutotagCommand::Remove(ids) => {
+                for id in ids {
+                    if !app.repository.delete_tag_rule(id).await? {
+                        println!("No rule {}", id);
+                    }
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    // The urls file: urls [sync] | export
+    if args.len() >= 2 && args[1] == "urls" {
+        match args.get(2).map(String::as_str) {
+            None | Some("sync") if args.len() <= 3 => match app.sync_urls_file().await? {
+                Some((added, updated, archived)) => {
+                    println!("{} added, {} updated, {} archived", added, updated, archived)
+                }
+                None => println!("Set urls_file in the config to keep feeds in a urls file"),
+            },
+            Some("export") if args.len() == 3 => print!("{}", feed::format_urls(&app.feeds)),
+            _ => println!("{}", cli::URLS_USAGE),
+        }
+        return Ok(());
+    }
+
+    // Config-as-code feeds: subscriptions [check] | sync | prune
+    if args.len() >= 2 && args[1] == "subscriptions" {
+        let command = args.get(2).map(String::as_str);
+        let drift = match command {
+            None | Some("check") if args.len() <= 3 => app.subscription_drift(),
+            Some("sync") | Some("prune") if args.len() == 3 => app.reconcile_subscriptions().await?,
+            _ => {
+                println!("{}", cli::SUBSCRIPTIONS_USAGE);
+                return Ok(());
+            }
+        };
+        let Some(drift) = drift else {
+            println!("No [[subscriptions]] in the config; feeds are managed in the app");
+            return Ok(());
+        };
+        let verb = if command == Some("sync") { "Added" } else { "Missing" };
+        for subscription in &drift.missing {
+            println!("{}: {}", verb, subscription.url);
+        }
+        let verb = if command == Some("sync") { "Updated" } else { "Differs" };
+        for change in &drift.changes {
+            if let Some(feed) = app.feeds.iter().find(|f| f.id == change.feed_id) {
+                println!("{}: {} ({})", verb, feed.display_title(), feed.url);
+            }
+        }
+        for feed in &drift.extras {
+            if command == Some("prune") {
+                app.repository.remove_feed(feed.id, db::FeedRemoval::Archive).await?;
+                println!("Archived: {} ({})", feed.display_title(), feed.url);
+            } else {
+                println!("Not declared: {} ({})", feed.display_title(), feed.url);
+            }
+        }
+        if drift.is_empty() {
+            println!("The feeds match [[subscriptions]]");
+        }
+        return Ok(());
+    }
+
+    // Database upkeep: db stats | maintain
+    if args.len() >= 2 && args[1] == "db" {
+        match args.get(2).map(String::as_str) {
+            Some("stats") if args.len() == 3 => println!("{}", cli::format_db_stats(&app.repository.db_stats().await?)),
+            Some("maintain") if args.len() == 3 => {
+                let (before, after) = app.repository.maintain().await?;
+                match before - after {
+                    freed if freed > 0 => println!(
+                        "{} -> {} ({} freed)",
+                        cli::format_bytes(before),
+                        cli::format_bytes(after),
+                        cli::format_bytes(freed)
+                    ),
+                    _ => println!("{}, nothing to free", cli::format_bytes(after)),
+                }
+            }
+            _ => println!("{}", cli::DB_USAGE),
+        }
+        return Ok(());
+    }
+
+    // Travel bundles: bundle export FILE [--days N] | import FILE
+    if args.len() >= 2 && args[1] == "bundle" {
+        match cli::parse_bundle_args(&args[2..])? {
+            cli::BundleCommand::Export(path, days) => {
+                let (feeds, articles) = app.export_bundle(&path, days).await?;
+                println!("Wrote {} feeds and {} articles to {}", feeds, articles, path.display());
+            }
+            cli::BundleCommand::Import(path) => {
+                let (feeds, added, existing) = app.import_bundle(&path).await?;
+                println!("Imported {} feeds and {} articles ({} already here)", feeds, added, existing);
+            }
+        }
+        return Ok(());
+    }
+
+    // The audit log: audit [ACTION] [--search TEXT] [--days N] [--limit N]
+    if args.len() >= 2 && args[1] == "audit" {
+        let (filter, limit) = cli::parse_audit_args(&args[2..])?;
+        let events = app.repository.get_audit_log(filter, limit).await?;
+        if events.is_empty() {
+            println!("Nothing in the audit log matches");
+        }
+        for event in &events {
+            println!("{}", cli::format_audit_event(event));
+        }
+        return Ok(());
+    }
+
+    // Backups: backup export FILE | import FILE
+    if args.len() >= 2 && args[1] == "backup" {
+        match cli::parse_backup_args(&args[2..])? {
+            cli::BackupCommand::Export(path) => {
+                let config_path = Config::config_path();
+                let settings = config_path.exists().then(|| std::fs::read_to_string(&config_path)).transpose()?;
+                let (feeds, articles) = app.export_backup(&path, settings.as_deref()).await?;
+                println!("Backed up {} feeds and {} articles to {}", feeds, articles, path.display());
+            }
+            cli::BackupCommand::Import(path) => {
+                let ((feeds, added, existing), settings) = app.import_backup(&path).await?;
+                println!("Restored {} feeds and {} articles ({} already here)", feeds, added, existing);
+                if let Some(settings) = settings {
+                    let written = backup::restore_config(&settings, &Config::config_path(), &Config::default().to_string()?)?;
+                    println!("Settings written to {}; add your API keys and tokens again", written.display());
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    // The link graph: links SITE|URL | --from ID | --top [--days N]
+    if args.len() >= 2 && args[1] == "links" {
+        match cli::parse_links_args(&args[2..])? {
+            cli::LinksCommand::To(target) => {
+                let filter = db::ArticleFilter {
+                    query: Some(db::Query::links_to(&target)),
+                    ..Default::default()
+                };
+                let articles = app.repository.list_articles(&filter, 200, None).await?;
+                if articles.is_empty() {
+                    println!("Nothing links to {}", target);
+                }
+                for article in articles {
+                    println!("[{}] {} ({})", article.id, article.title, article.feed_title.unwrap_or_default());
+                    for url in app.repository.get_article_links(article.id).await? {
+                        if feed::links_to(&url, &target) {
+                            println!("      -> {}", url);
+                        }
+                    }
+                }
+            }
+            cli::LinksCommand::From(id) => {
+                for url in app.repository.get_article_links(id).await? {
+                    println!("{}", url);
+                }
+            }
+            cli::LinksCommand::Top(days) => {
+                let since = chrono::Utc::now() - chrono::Duration::days(days as i64);
+                for (domain, articles) in app.repository.most_linked_domains(since, 30).await? {
+                    println!("{:>6}  {}", articles, domain);
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    // Article collections: collections [list] | show NAME | add NAME ID... | remove NAME [ID...] | move | export
+    if args.len() >= 2 && args[1] == "collections" {
+        match cli::parse_collections_args(&args[2..])? {
+            cli::CollectionCommand::List => {
+                for collection in app.repository.get_collections().await? {
+                    println!("{:>6}  {}", collection.article_count, collection.name);
+                }
+            }
+            cli::CollectionCommand::Show(name) => {
+                for (n, article) in app.repository.get_collection_articles(&name).await?.iter().enumerate() {
+                    println!("{:>3}. [{}] {}", n + 1, article.id, article.title);
+                }
+            }
+            cli::CollectionCommand::Add(name, ids) => {
+                let added = app.repository.add_to_collection(&name, ids).await?;
+                println!("Added {} articles to {}", added, name);
+            }
+            cli::CollectionCommand::Remove(name, ids) => {
+                let whole = ids.is_empty();
+                let removed = app.repository.remove_from_collection(&name, ids).await?;
+                if whole {
+                    println!("Dropped {} ({} articles)", name, removed);
+                } else {
+                    println!("Removed {} articles from {}", removed, name);
+                }
+            }
+            cli::CollectionCommand::Move { name, id, position } => {
+                if !app.repository.move_in_collection(&name, id, position).await? {
+                    println!("Article {} isn't in {}", id, name);
+                }
+            }
+            cli::CollectionCommand::Export(name, format) => {
+                let path = app.export_collection(&name, format).await?;
+                println!("Exported to {}", path.display());
+            }
+        }
         return Ok(());
     }
 
+    // Declared feeds are added and brought in line before anything runs
+    if let Some(drift) = app.reconcile_subscriptions().await? {
+        if !drift.missing.is_empty() || !drift.changes.is_empty() {
+            tracing::info!(
+                "[[subscriptions]]: {} added, {} updated",
+                drift.missing.len(),
+                drift.changes.len()
+            );
+        }
+        if headless_refresh {
+            for feed in &drift.extras {
+                println!("Not in [[subscriptions]]: {} ({})", feed.display_title(), feed.url);
+            }
+        }
+    }
+
     // If headless refresh, just refresh and exit
     if headless_refresh {
+        if let Some((added, updated, archived)) = app.sync_urls_file().await? {
+            println!("urls file: {} added, {} updated, {} archived", added, updated, archived);
+        }
+        if !app.may_refresh_now() {
+            println!("Outside the [schedule] refresh windows; not refreshing");
+            return Ok(());
+        }
         app.refresh_feeds_blocking().await?;
         println!("Refreshed {} feeds", app.feeds.len());
+        if app.is_quiet() {
+            return Ok(());
+        }
+        // Printed so cron or a systemd timer can pass the alert on
+        for timeline in app.watch_timelines(watchlist::TIMELINE_DAYS).await? {
+            if timeline.is_spike() {
+                println!("Coverage spike: {}", timeline.describe_spike());
+            }
+        }
+        for (feed, title) in &app.priority_news {
+            println!("New in {}: {}", feed, title);
+        }
+        for (author, title) in &app.followed_news {
+            println!("New from {}: {}", author, title);
+        }
+        for (title, archive) in &app.removed_news {
+            match archive {
+                Some(path) => println!("Taken down: {} (archived at {})", title, path),
+                None => println!("Taken down: {}", title),
+            }
+        }
         return Ok(());
     }
 
+    // If serving, run the daemon until stopped
+    if serve {
+        return server::run(&config, app).await;
+    }
+
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -119,6 +809,16 @@ async fn main() -> Result<()> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
+    // Back where the last session left off
+    app.restore_session().await?;
+
+    // Cached articles show right away; feeds that are due refresh in the
+    // background (unless automatic refreshes are off and none was cut short)
+    app.resume_interrupted().await?;
+    if config.refresh_interval_minutes > 0 || app.refresh_interrupted().await? {
+        app.refresh_feeds().await?;
+    }
+
     // Run the app
     let result = run_app(&mut terminal, &mut app).await;
 
@@ -138,6 +838,33 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// Report each shared blocklist download by `blocklist subscribe|refresh`
+fn print_blocklist_downloads(results: Vec<(String, Result<usize>)>) {
+    for (url, result) in results {
+        match result {
+            Ok(rules) => println!("Downloaded {}: {} rules", url, rules),
+            Err(e) => println!("Couldn't download {}: {}", url, e),
+        }
+    }
+}
+
+/// Hand the terminal to `$EDITOR` for a note, then take it back
+fn edit_outside<B: Backend>(terminal: &mut Terminal<B>, edit: &NoteEdit) -> Result<Option<String>> {
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture)?;
+
+    let text = run_editor(edit);
+
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+    terminal.clear()?;
+
+    Ok(text.unwrap_or_else(|e| {
+        tracing::warn!("Could not run {}: {}", edit.editor, e);
+        None
+    }))
+}
+
 async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()> {
     loop {
         terminal.draw(|frame| draw(frame, app))?;
@@ -148,25 +875,67 @@ async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Resul
         // Poll for completed summary results
         app.poll_summary_result().await?;
 
+        // Poll for finished retries of failed summaries
+        app.poll_summary_retries().await?;
+
         // Poll for completed refresh results
         app.poll_refresh_result().await?;
 
         // Poll for completed feed discovery results
         app.poll_discovery_result().await?;
 
+        // Poll for completed feed recommendation results
+        app.poll_recommendation_result().await?;
+
+        // Poll for answered questions
+        app.poll_answer_result();
+
+        // Announce edits to the blocklist
+        app.poll_blocklist();
+
+        // Poll for fetched comment threads
+        app.poll_comments_result();
+
+        // Poll for finished HN/Lobsters lookups
+        app.poll_discussion_result().await?;
+
+        // Poll for finished checks of saved articles' pages
+        app.poll_removal_result().await?;
+
+        // Poll for finished PDF exports
+        app.poll_export_result();
+
+        // Poll for finished starred-article snapshots
+        app.poll_archive_result().await?;
+
+        // Count time spent reading
+        app.poll_focus().await?;
+
+        // Bring back snoozed articles that are due
+        app.poll_snoozed().await?;
+
+        // Pick up edits to the urls file
+        app.poll_urls_file().await?;
+
+        // Track the podcast player's position
+        app.poll_playback().await?;
+
         // Clear bookmark status after timeout
         app.check_bookmark_status_timeout();
 
         // Poll for events with timeout to allow async operations
         if event::poll(Duration::from_millis(100))? {
             if let Event::Key(key) = event::read()? {
-                if let Some(action) =
-                    handle_key_event(key, app.tag_input_active, app.feed_input_active, app.opml_input_active, app.opml_export_active, app.show_help, app.bookmark_prefix_active)
-                {
+                app.note_activity();
+                if let Some(action) = handle_key_event(key, &app.key_context(), &app.macro_keys) {
                     let should_quit = app.handle_action(action).await?;
                     if should_quit {
                         return Ok(());
                     }
+                    if let Some(edit) = app.editor_request.take() {
+                        let text = edit_outside(terminal, &edit)?;
+                        app.save_annotation(edit.article_id, text).await?;
+                    }
                 }
             }
         }
@@ -0,0 +1,330 @@
+use std::fmt::Write;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+/// Upper bounds (seconds) of the fetch latency buckets, for feeds and
+/// article pages alike
+const FETCH_LATENCY_BUCKETS: [f64; 9] = [0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 20.0, 30.0];
+
+/// Process-wide counters, updated where the work happens and rendered by the
+/// server's `/metrics` endpoint
+pub struct Metrics {
+    fetch_success: AtomicU64,
+    fetch_failure: AtomicU64,
+    fetch_throttled: AtomicU64,
+    fetch_latency: Histogram,
+    summaries_in_flight: AtomicI64,
+    content_fetches_in_flight: AtomicI64,
+    content_fetched: AtomicU64,
+    content_unavailable: AtomicU64,
+    content_failed: AtomicU64,
+    content_latency: Histogram,
+}
+
+static METRICS: Metrics = Metrics {
+    fetch_success: AtomicU64::new(0),
+    fetch_failure: AtomicU64::new(0),
+    fetch_throttled: AtomicU64::new(0),
+    fetch_latency: Histogram::new(),
+    summaries_in_flight: AtomicI64::new(0),
+    content_fetches_in_flight: AtomicI64::new(0),
+    content_fetched: AtomicU64::new(0),
+    content_unavailable: AtomicU64::new(0),
+    content_failed: AtomicU64::new(0),
+    content_latency: Histogram::new(),
+};
+
+pub fn metrics() -> &'static Metrics {
+    &METRICS
+}
+
+impl Metrics {
+    pub fn record_fetch(&self, success: bool, elapsed: Duration) {
+        let counter = if success { &self.fetch_success } else { &self.fetch_failure };
+        counter.fetch_add(1, Ordering::Relaxed);
+        self.fetch_latency.observe(elapsed.as_secs_f64());
+    }
+
+    /// A 429/503 with Retry-After; counted apart from failures
+    pub fn record_throttled(&self) {
+        self.fetch_throttled.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count a summary as queued until the returned guard is dropped
+    pub fn summary_started(&'static self) -> InFlight {
+        InFlight::new(&self.summaries_in_flight)
+    }
+
+    /// Count a full-content fetch as queued until the returned guard is dropped
+    pub fn content_fetch_started(&'static self) -> InFlight {
+        InFlight::new(&self.content_fetches_in_flight)
+    }
+
+    /// A finished full-content fetch: `Some(true)` got the article,
+    /// `Some(false)` found none (refused, paywalled, missing), `None` failed
+    pub fn record_content_fetch(&self, fetched: Option<bool>, elapsed: Duration) {
+        let counter = match fetched {
+            Some(true) => &self.content_fetched,
+            Some(false) => &self.content_unavailable,
+            None => &self.content_failed,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        self.content_latency.observe(elapsed.as_secs_f64());
+    }
+
+    /// Prometheus text exposition format, with database-derived gauges
+    /// supplied by the caller
+    pub fn render(&self, db: &DbMetrics) -> String {
+        let mut out = String::new();
+
+        out.push_str("# HELP beatcheck_feed_fetches_total Feed fetches by outcome.\n");
+        out.push_str("# TYPE beatcheck_feed_fetches_total counter\n");
+        let _ = writeln!(
+            out,
+            "beatcheck_feed_fetches_total{{result=\"success\"}} {}",
+            self.fetch_success.load(Ordering::Relaxed)
+        );
+        let _ = writeln!(
+            out,
+            "beatcheck_feed_fetches_total{{result=\"failure\"}} {}",
+            self.fetch_failure.load(Ordering::Relaxed)
+        );
+        let _ = writeln!(
+            out,
+            "beatcheck_feed_fetches_total{{result=\"throttled\"}} {}",
+            self.fetch_throttled.load(Ordering::Relaxed)
+        );
+
+        out.push_str("# HELP beatcheck_feed_fetch_duration_seconds Time to download and parse a feed.\n");
+        out.push_str("# TYPE beatcheck_feed_fetch_duration_seconds histogram\n");
+        self.fetch_latency
+            .render(&mut out, "beatcheck_feed_fetch_duration_seconds");
+
+        out.push_str("# HELP beatcheck_summaries_in_flight Summaries waiting on the AI provider.\n");
+        out.push_str("# TYPE beatcheck_summaries_in_flight gauge\n");
+        let _ = writeln!(
+            out,
+            "beatcheck_summaries_in_flight {}",
+            self.summaries_in_flight.load(Ordering::Relaxed)
+        );
+
+        out.push_str("# HELP beatcheck_content_fetches_in_flight Full-article fetches in progress.\n");
+        out.push_str("# TYPE beatcheck_content_fetches_in_flight gauge\n");
+        let _ = writeln!(
+            out,
+            "beatcheck_content_fetches_in_flight {}",
+            self.content_fetches_in_flight.load(Ordering::Relaxed)
+        );
+
+        out.push_str("# HELP beatcheck_content_fetches_total Full-article fetches by outcome.\n");
+        out.push_str("# TYPE beatcheck_content_fetches_total counter\n");
+        for (result, counter) in [
+            ("fetched", &self.content_fetched),
+            ("unavailable", &self.content_unavailable),
+            ("failed", &self.content_failed),
+        ] {
+            let _ = writeln!(
+                out,
+                "beatcheck_content_fetches_total{{result=\"{}\"}} {}",
+                result,
+                counter.load(Ordering::Relaxed)
+            );
+        }
+
+        out.push_str("# HELP beatcheck_content_fetch_duration_seconds Time to fetch and extract an article page.\n");
+        out.push_str("# TYPE beatcheck_content_fetch_duration_seconds histogram\n");
+        self.content_latency
+            .render(&mut out, "beatcheck_content_fetch_duration_seconds");
+
+        out.push_str("# HELP beatcheck_database_size_bytes SQLite database size, including the WAL.\n");
+        out.push_str("# TYPE beatcheck_database_size_bytes gauge\n");
+        let _ = writeln!(out, "beatcheck_database_size_bytes {}", db.size_bytes);
+
+        out.push_str("# HELP beatcheck_articles Stored articles.\n");
+        out.push_str("# TYPE beatcheck_articles gauge\n");
+        let _ = writeln!(out, "beatcheck_articles {}", db.articles);
+
+        out.push_str("# HELP beatcheck_feed_last_fetched_timestamp_seconds Last successful fetch of each feed.\n");
+        out.push_str("# TYPE beatcheck_feed_last_fetched_timestamp_seconds gauge\n");
+        for feed in &db.feeds {
+            if let Some(last_fetched) = feed.last_fetched {
+                let _ = writeln!(
+                    out,
+                    "beatcheck_feed_last_fetched_timestamp_seconds{{feed_id=\"{}\",title=\"{}\"}} {}",
+                    feed.id,
+                    escape_label(&feed.title),
+                    last_fetched.timestamp()
+                );
+            }
+        }
+
+        out
+    }
+}
+
+/// Gauges read from the database at scrape time
+#[derive(Debug, Default)]
+pub struct DbMetrics {
+    pub size_bytes: u64,
+    pub articles: usize,
+    pub feeds: Vec<FeedFreshness>,
+}
+
+#[derive(Debug)]
+pub struct FeedFreshness {
+    pub id: i64,
+    pub title: String,
+    pub last_fetched: Option<DateTime<Utc>>,
+}
+
+/// Decrements its gauge when dropped, so early returns and panics are counted
+pub struct InFlight(&'static AtomicI64);
+
+impl InFlight {
+    fn new(gauge: &'static AtomicI64) -> Self {
+        gauge.fetch_add(1, Ordering::Relaxed);
+        Self(gauge)
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Fixed-bucket histogram over `FETCH_LATENCY_BUCKETS`. The sum is kept in
+/// microseconds so it fits an atomic integer.
+struct Histogram {
+    buckets: [AtomicU64; FETCH_LATENCY_BUCKETS.len()],
+    count: AtomicU64,
+    sum_micros: AtomicU64,
+}
+
+impl Histogram {
+    const fn new() -> Self {
+        Self {
+            buckets: [const { AtomicU64::new(0) }; FETCH_LATENCY_BUCKETS.len()],
+            count: AtomicU64::new(0),
+            sum_micros: AtomicU64::new(0),
+        }
+    }
+
+    fn observe(&self, seconds: f64) {
+        for (bound, bucket) in FETCH_LATENCY_BUCKETS.iter().zip(&self.buckets) {
+            if seconds <= *bound {
+                bucket.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_micros
+            .fetch_add((seconds * 1_000_000.0) as u64, Ordering::Relaxed);
+    }
+
+    fn render(&self, out: &mut String, name: &str) {
+        for (bound, bucket) in FETCH_LATENCY_BUCKETS.iter().zip(&self.buckets) {
+            let _ = writeln!(
+                out,
+                "{}_bucket{{le=\"{}\"}} {}",
+                name,
+                bound,
+                bucket.load(Ordering::Relaxed)
+            );
+        }
+        let count = self.count.load(Ordering::Relaxed);
+        let _ = writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", name, count);
+        let _ = writeln!(
+            out,
+            "{}_sum {}",
+            name,
+            self.sum_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0
+        );
+        let _ = writeln!(out, "{}_count {}", name, count);
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_buckets_are_cumulative() {
+        let histogram = Histogram::new();
+        histogram.observe(0.2);
+        histogram.observe(3.0);
+        histogram.observe(60.0);
+
+        let mut out = String::new();
+        histogram.render(&mut out, "h");
+
+        assert!(out.contains("h_bucket{le=\"0.1\"} 0\n"));
+        assert!(out.contains("h_bucket{le=\"0.25\"} 1\n"));
+        assert!(out.contains("h_bucket{le=\"5\"} 2\n"));
+        assert!(out.contains("h_bucket{le=\"30\"} 2\n"));
+        assert!(out.contains("h_bucket{le=\"+Inf\"} 3\n"));
+        assert!(out.contains("h_sum 63.2\n"));
+        assert!(out.contains("h_count 3\n"));
+    }
+
+    #[test]
+    fn test_in_flight_guard_decrements_on_drop() {
+        static GAUGE: AtomicI64 = AtomicI64::new(0);
+        let first = InFlight::new(&GAUGE);
+        let second = InFlight::new(&GAUGE);
+        assert_eq!(GAUGE.load(Ordering::Relaxed), 2);
+        drop(first);
+        drop(second);
+        assert_eq!(GAUGE.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn test_render_escapes_feed_titles() {
+        let db = DbMetrics {
+            size_bytes: 4096,
+            articles: 12,
+            feeds: vec![
+                FeedFreshness {
+                    id: 3,
+                    title: "Say \"hi\"".to_string(),
+                    last_fetched: DateTime::from_timestamp(1_700_000_000, 0),
+                },
+                FeedFreshness {
+                    id: 4,
+                    title: "Never fetched".to_string(),
+                    last_fetched: None,
+                },
+            ],
+        };
+
+        let out = Metrics {
+            fetch_success: AtomicU64::new(5),
+            fetch_failure: AtomicU64::new(1),
+            fetch_throttled: AtomicU64::new(0),
+            fetch_latency: Histogram::new(),
+            summaries_in_flight: AtomicI64::new(0),
+            content_fetches_in_flight: AtomicI64::new(0),
+            content_fetched: AtomicU64::new(7),
+            content_unavailable: AtomicU64::new(2),
+            content_failed: AtomicU64::new(0),
+            content_latency: Histogram::new(),
+        }
+        .render(&db);
+
+        assert!(out.contains("beatcheck_feed_fetches_total{result=\"failure\"} 1\n"));
+        assert!(out.contains("beatcheck_content_fetches_total{result=\"fetched\"} 7\n"));
+        assert!(out.contains("beatcheck_database_size_bytes 4096\n"));
+        assert!(out.contains(
+            "beatcheck_feed_last_fetched_timestamp_seconds{feed_id=\"3\",title=\"Say \\\"hi\\\"\"} 1700000000\n"
+        ));
+        assert!(!out.contains("Never fetched"));
+    }
+}
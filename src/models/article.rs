@@ -1,6 +1,10 @@
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use super::{Discussion, Entity};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Article {
     pub id: i64,
@@ -14,6 +18,49 @@ pub struct Article {
     pub published_at: Option<DateTime<Utc>>,
     pub fetched_at: DateTime<Utc>,
     pub feed_title: Option<String>,
+    pub is_starred: bool,
+    pub is_pinned: bool,
+    pub reading_minutes: Option<u32>,
+    pub is_queued: bool,
+    pub is_read: bool,
+    /// Full text scraped from the article page, when it has been fetched
+    pub full_text: Option<String>,
+    /// The post's comment feed (wfw:commentRss or an Atom "replies" link)
+    pub comments_url: Option<String>,
+    /// Best-scoring HN/Lobsters thread, once looked up
+    pub discussion: Option<Discussion>,
+    /// Podcast audio attached to the item
+    pub enclosure: Option<Enclosure>,
+    /// Where playback of the enclosure stopped, in seconds
+    pub playback_secs: Option<u32>,
+    /// The episode was played to the end
+    pub is_played: bool,
+    /// Extension data from the feed item
+    pub extras: Option<ArticleExtras>,
+    /// Local HTML snapshot, for starred articles when archiving is on
+    pub archive_path: Option<String>,
+    /// Suggested tags: feed categories and extracted key phrases
+    pub tags: Vec<String>,
+    /// One of the bylines is a followed author
+    #[serde(default)]
+    pub by_followed_author: bool,
+    /// Held out of listings and announcements until then
+    #[serde(default)]
+    pub embargo_until: Option<DateTime<Utc>>,
+    /// Earlier versions kept from re-fetches that changed the title or text
+    #[serde(default)]
+    pub revisions: u32,
+    /// When the page was found taken down (see `[deletions]`)
+    #[serde(default)]
+    pub removed_at: Option<DateTime<Utc>>,
+}
+
+/// An audio enclosure (RSS `<enclosure>` or Atom `rel="enclosure"` link)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Enclosure {
+    pub url: String,
+    pub mime_type: Option<String>,
+    pub duration_secs: Option<u32>,
 }
 
 #[derive(Debug, Clone)]
@@ -26,4 +73,148 @@ pub struct NewArticle {
     pub content: Option<String>,
     pub content_text: Option<String>,
     pub published_at: Option<DateTime<Utc>>,
+    pub comments_url: Option<String>,
+    pub enclosure: Option<Enclosure>,
+    pub extras: Option<ArticleExtras>,
+    /// Suggested tags, worked out at ingest
+    pub tags: Vec<String>,
+    /// People, organizations and tickers mentioned, worked out at ingest
+    pub entities: Vec<Entity>,
+    /// Byline names, one per author (see `feed::authors`)
+    pub authors: Vec<String>,
+    /// Links in the content to other sites (see `feed::links`)
+    pub links: Vec<String>,
+}
+
+/// Feed item data beyond the core fields, stored as JSON
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ArticleExtras {
+    /// Every author, where the article keeps only the first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub authors: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<String>,
+    /// Namespaced elements by qualified name as written in the feed
+    /// ("dc:creator", "itunes:episode"). Attributes of empty elements are
+    /// keyed "name@attr" ("itunes:image@href").
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub elements: BTreeMap<String, Vec<String>>,
+}
+
+#[allow(dead_code)]
+impl ArticleExtras {
+    pub fn is_empty(&self) -> bool {
+        self.authors.is_empty() && self.categories.is_empty() && self.elements.is_empty()
+    }
+
+    /// First value of a namespaced element, e.g. `get("dc:rights")`
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.elements.get(name)?.first().map(String::as_str)
+    }
+
+    /// Like `get`, but matching any prefix bound to the namespace's usual
+    /// local name (feeds don't all use the conventional prefixes)
+    fn local(&self, local_name: &str) -> Option<&str> {
+        self.elements
+            .iter()
+            .find(|(name, _)| name.split_once(':').is_some_and(|(_, local)| local == local_name))
+            .and_then(|(_, values)| values.first())
+            .map(String::as_str)
+    }
+
+    /// Dublin Core creators (`dc:creator`)
+    pub fn dc_creators(&self) -> &[String] {
+        self.elements.get("dc:creator").map_or(&[], Vec::as_slice)
+    }
+
+    pub fn itunes_episode(&self) -> Option<u32> {
+        self.get("itunes:episode")?.trim().parse().ok()
+    }
+
+    pub fn itunes_season(&self) -> Option<u32> {
+        self.get("itunes:season")?.trim().parse().ok()
+    }
+
+    pub fn itunes_explicit(&self) -> Option<bool> {
+        match self.get("itunes:explicit")?.trim().to_ascii_lowercase().as_str() {
+            "yes" | "true" | "explicit" => Some(true),
+            "no" | "false" | "clean" => Some(false),
+            _ => None,
+        }
+    }
+
+    pub fn itunes_image(&self) -> Option<&str> {
+        self.get("itunes:image@href")
+    }
+
+    /// Latitude and longitude from `georss:point` or W3C `geo:lat`/`geo:long`
+    pub fn geo_point(&self) -> Option<(f64, f64)> {
+        if let Some(point) = self.local("point") {
+            let mut coords = point.split_whitespace().map(|c| c.parse::<f64>().ok());
+            return Some((coords.next()??, coords.next()??));
+        }
+        Some((self.local("lat")?.trim().parse().ok()?, self.local("long")?.trim().parse().ok()?))
+    }
+
+    /// Comment count published by the feed (`slash:comments`)
+    pub fn comment_count(&self) -> Option<u32> {
+        self.get("slash:comments")?.trim().parse().ok()
+    }
+}
+
+/// Average silent-reading speed used for reading-time estimates
+pub const WORDS_PER_MINUTE: usize = 230;
+
+/// Estimated minutes to read `text`, rounded up; never less than one minute
+pub fn estimate_reading_minutes(text: &str) -> u32 {
+    let words = text.split_whitespace().count();
+    words.div_ceil(WORDS_PER_MINUTE).max(1) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extras_accessors() {
+        let mut extras = ArticleExtras::default();
+        for (name, value) in [
+            ("itunes:episode", "12"),
+            ("itunes:season", "3"),
+            ("itunes:explicit", "no"),
+            ("itunes:image@href", "https://cdn.example/art.jpg"),
+            ("georss:point", "45.256 -71.92"),
+            ("dc:creator", "Ann"),
+            ("dc:creator", "Bo"),
+        ] {
+            extras.elements.entry(name.to_string()).or_default().push(value.to_string());
+        }
+
+        assert_eq!((extras.itunes_season(), extras.itunes_episode()), (Some(3), Some(12)));
+        assert_eq!(extras.itunes_explicit(), Some(false));
+        assert_eq!(extras.itunes_image(), Some("https://cdn.example/art.jpg"));
+        assert_eq!(extras.geo_point(), Some((45.256, -71.92)));
+        assert_eq!(extras.dc_creators(), ["Ann", "Bo"]);
+        assert_eq!(extras.comment_count(), None);
+    }
+
+    #[test]
+    fn test_reading_minutes_rounds_up() {
+        let text = "word ".repeat(231);
+        assert_eq!(estimate_reading_minutes(&text), 2);
+        let text = "word ".repeat(230);
+        assert_eq!(estimate_reading_minutes(&text), 1);
+    }
+
+    #[test]
+    fn test_reading_minutes_minimum_one() {
+        assert_eq!(estimate_reading_minutes(""), 1);
+        assert_eq!(estimate_reading_minutes("just a teaser"), 1);
+    }
+
+    #[test]
+    fn test_reading_minutes_ignores_extra_whitespace() {
+        let text = "one\n\n  two\tthree ".repeat(230);
+        assert_eq!(estimate_reading_minutes(&text), 3);
+    }
 }
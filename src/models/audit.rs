@@ -0,0 +1,15 @@
+use chrono::{DateTime, Utc};
+
+/// Something added, removed or sent elsewhere, kept in the audit log
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEvent {
+    pub id: i64,
+    pub at: DateTime<Utc>,
+    /// `feed.add`, `feed.remove`, `feed.archive`, `articles.read`,
+    /// `articles.delete`, `import.opml` (and the other imports) or
+    /// `push.raindrop`
+    pub action: String,
+    /// The feed or article URL, or the imported file
+    pub subject: Option<String>,
+    pub detail: Option<String>,
+}
@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+/// A standing choice about one author's articles, across every feed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthorRule {
+    /// Flag their articles and announce new ones
+    Follow,
+    /// Drop their articles like blocklist matches
+    Mute,
+}
+
+impl AuthorRule {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Follow => "follow",
+            Self::Mute => "mute",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "follow" => Some(Self::Follow),
+            "mute" => Some(Self::Mute),
+            _ => None,
+        }
+    }
+}
+
+/// How many recent articles carry an author's byline, and in how many feeds
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AuthorCount {
+    pub name: String,
+    pub articles: usize,
+    pub feeds: usize,
+    pub rule: Option<AuthorRule>,
+}
@@ -0,0 +1,12 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// A named, ordered set of articles gathered for a piece of work (e.g.
+/// "Tuesday column research"), exported together as one briefing packet
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Collection {
+    pub id: i64,
+    pub name: String,
+    pub article_count: usize,
+    pub created_at: DateTime<Utc>,
+}
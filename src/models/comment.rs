@@ -0,0 +1,10 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// One entry from an article's comment feed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Comment {
+    pub author: Option<String>,
+    pub published_at: Option<DateTime<Utc>>,
+    pub text: String,
+}
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+/// A thread about an article on Hacker News or Lobsters
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Discussion {
+    /// "hn" or "lobsters"
+    pub site: String,
+    pub url: String,
+    pub points: i64,
+    pub comments: i64,
+}
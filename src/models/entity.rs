@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+/// What sort of name an entity is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntityKind {
+    Person,
+    Organization,
+    /// A stock symbol, stored without the `$`
+    Ticker,
+}
+
+impl EntityKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Person => "person",
+            Self::Organization => "organization",
+            Self::Ticker => "ticker",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "person" => Some(Self::Person),
+            "organization" | "org" => Some(Self::Organization),
+            "ticker" => Some(Self::Ticker),
+            _ => None,
+        }
+    }
+}
+
+/// A person, company or ticker an article mentions
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Entity {
+    pub name: String,
+    pub kind: EntityKind,
+}
+
+/// How many recent articles mention an entity
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EntityCount {
+    pub name: String,
+    pub kind: EntityKind,
+    pub articles: usize,
+}
@@ -1,6 +1,12 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+/// URL of the pseudo-feed that holds starred articles kept from removed feeds
+pub const ORPHANED_FEED_URL: &str = "beatcheck:orphaned";
+
+/// URL of the pseudo-feed that holds imported bookmarks and Pocket saves
+pub const SAVED_FEED_URL: &str = "beatcheck:saved";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Feed {
     pub id: i64,
@@ -11,6 +17,208 @@ pub struct Feed {
     pub last_fetched: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Don't fetch before this time; the host rate-limited us
+    pub retry_after: Option<DateTime<Utc>>,
+    /// Name the user gave the feed, shown instead of `title`
+    pub custom_title: Option<String>,
+    pub folder: Option<String>,
+    /// Minimum minutes between fetches, when longer than the global interval
+    pub refresh_minutes: Option<u32>,
+    /// Skipped by refreshes until resumed
+    pub paused: bool,
+    pub tags: Vec<String>,
+    /// Average posts per day over the last 30 days, as of the last fetch
+    pub posts_per_day: Option<f64>,
+    /// Newest article, by publication date
+    pub last_post_at: Option<DateTime<Utc>>,
+    /// Unread articles, as of when the feed was loaded
+    #[serde(default)]
+    pub unread: u32,
+    #[serde(default)]
+    pub reading: ReadingMode,
+    #[serde(default)]
+    pub priority: FeedPriority,
+    #[serde(default)]
+    pub source: SourceInfo,
+    /// Template articles are fetched and opened through, e.g. a reader
+    /// service's `https://r.example/{url}`
+    #[serde(default)]
+    pub reader_url: Option<String>,
+    /// Fetches in a row that answered 404 or 410
+    #[serde(default)]
+    pub missing_count: u32,
+    /// Feed URL found on the site after too many of those, waiting for the
+    /// user to accept it
+    #[serde(default)]
+    pub moved_to: Option<String>,
+    /// The last fetch's XML was malformed (bare `&`s, HTML entities,
+    /// control characters) and only parsed after repairs
+    #[serde(default)]
+    pub repaired: bool,
+}
+
+/// What a journalist knows about a source, kept with the feed
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceInfo {
+    pub beat: Option<String>,
+    /// Who to ask: a name, address or handle
+    pub contact: Option<String>,
+    /// 1 (doubtful) to 5 (rock solid)
+    pub reliability: Option<u8>,
+    pub notes: Option<String>,
+}
+
+impl SourceInfo {
+    pub const MAX_RELIABILITY: u8 = 5;
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether the beat, contact or notes contain `text` (lowercase)
+    pub fn contains(&self, text: &str) -> bool {
+        [&self.beat, &self.contact, &self.notes]
+            .into_iter()
+            .flatten()
+            .any(|field| field.to_lowercase().contains(text))
+    }
+}
+
+/// How much of a feed's articles to show: some feeds are read in full,
+/// others only skimmed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReadingMode {
+    /// The fetched page text once there is one, else the feed's content
+    #[default]
+    Full,
+    /// The first paragraph of the feed's own content
+    Teaser,
+    /// Title-only rows, with nothing in the reader
+    Headlines,
+}
+
+impl ReadingMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Full => "full",
+            Self::Teaser => "teaser",
+            Self::Headlines => "headlines",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "full" => Some(Self::Full),
+            "teaser" => Some(Self::Teaser),
+            "headlines" => Some(Self::Headlines),
+            _ => None,
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Self::Full => Self::Teaser,
+            Self::Teaser => Self::Headlines,
+            Self::Headlines => Self::Full,
+        }
+    }
+}
+
+/// How much a feed's articles stand out: beat feeds shouldn't be buried
+/// under link-blog noise
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedPriority {
+    /// Sorted as if half a day newer, highlighted and announced when new
+    High,
+    #[default]
+    Normal,
+    /// Sorted as if half a day older, dimmed and never announced
+    Low,
+}
+
+impl FeedPriority {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::High => "high",
+            Self::Normal => "normal",
+            Self::Low => "low",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "high" => Some(Self::High),
+            "normal" => Some(Self::Normal),
+            "low" => Some(Self::Low),
+            _ => None,
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Self::Normal => Self::High,
+            Self::High => Self::Low,
+            Self::Low => Self::Normal,
+        }
+    }
+}
+
+impl Feed {
+    pub fn display_title(&self) -> &str {
+        self.custom_title.as_deref().unwrap_or(&self.title)
+    }
+
+    /// The "Orphaned" pseudo-feed
+    pub fn is_orphanage(&self) -> bool {
+        self.url == ORPHANED_FEED_URL
+    }
+
+    /// Whether the title, URL or source notes contain `text` (lowercase)
+    pub fn matches_search(&self, text: &str) -> bool {
+        self.display_title().to_lowercase().contains(text)
+            || self.url.to_lowercase().contains(text)
+            || self.source.contains(text)
+    }
+
+    /// A pseudo-feed ("Orphaned", "Saved") with nothing to fetch
+    pub fn is_local(&self) -> bool {
+        self.is_orphanage() || self.url == SAVED_FEED_URL
+    }
+
+    /// Whether a refresh at `now` should fetch this feed: not paused, not
+    /// inside a Retry-After window, and its own interval (if any) has passed
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        let interval_passed = match (self.refresh_minutes, self.last_fetched) {
+            (Some(minutes), Some(last)) => last + chrono::Duration::minutes(i64::from(minutes)) <= now,
+            _ => true,
+        };
+        !self.paused && self.retry_after.is_none_or(|until| until <= now) && interval_passed
+    }
+
+    /// Where to fetch or open one of the feed's articles: `url` itself, or
+    /// put through the feed's reader view
+    pub fn reader_view(&self, url: &str) -> String {
+        match &self.reader_url {
+            Some(template) => reader_view_url(template, url),
+            None => url.to_string(),
+        }
+    }
+}
+
+/// Fill in a reader view template: `{url}` takes the article's URL as is,
+/// `{url_encoded}` percent-encoded for use in a query string
+fn reader_view_url(template: &str, url: &str) -> String {
+    template
+        .replace("{url_encoded}", &urlencoding::encode(url))
+        .replace("{url}", url)
+}
+
+/// Whether `template` is an http(s) URL with somewhere to put the article's
+pub fn is_reader_view_template(template: &str) -> bool {
+    (template.starts_with("https://") || template.starts_with("http://"))
+        && (template.contains("{url}") || template.contains("{url_encoded}"))
 }
 
 #[derive(Debug, Clone)]
@@ -1,7 +1,27 @@
 mod feed;
 mod article;
+mod collection;
+mod comment;
+mod discussion;
+mod entity;
+mod author;
+mod revision;
 mod summary;
+mod tag_rule;
+mod user;
+mod audit;
 
-pub use feed::{Feed, NewFeed};
-pub use article::{Article, NewArticle};
-pub use summary::{Summary, SummaryStatus};
+pub use feed::{
+    is_reader_view_template, Feed, FeedPriority, NewFeed, ReadingMode, SourceInfo, ORPHANED_FEED_URL, SAVED_FEED_URL,
+};
+pub use article::{estimate_reading_minutes, Article, ArticleExtras, Enclosure, NewArticle};
+pub use collection::Collection;
+pub use comment::Comment;
+pub use discussion::Discussion;
+pub use entity::{Entity, EntityCount, EntityKind};
+pub use author::{AuthorCount, AuthorRule};
+pub use revision::ArticleRevision;
+pub use summary::{Summary, SummaryFailure, SummaryStatus};
+pub use tag_rule::TagRule;
+pub use user::{ApiToken, TokenScope, User};
+pub use audit::AuditEvent;
@@ -0,0 +1,13 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// An article as it read before a re-fetch changed its title or text
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ArticleRevision {
+    pub id: i64,
+    pub article_id: i64,
+    pub title: String,
+    pub content_text: Option<String>,
+    /// When the feed replaced it with the next version
+    pub replaced_at: DateTime<Utc>,
+}
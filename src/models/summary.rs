@@ -7,9 +7,26 @@ pub struct Summary {
     pub article_id: i64,
     pub content: String,
     pub model_version: String,
+    /// "claude", "openai" or "local"; unknown for summaries from before
+    /// providers were recorded
+    pub provider: Option<String>,
     pub generated_at: DateTime<Utc>,
 }
 
+/// An article whose last summary attempt failed
+#[derive(Debug, Clone, Serialize)]
+pub struct SummaryFailure {
+    pub article_id: i64,
+    pub title: String,
+    /// The provider's error message
+    pub error: String,
+    pub attempts: u32,
+    pub failed_at: DateTime<Utc>,
+    /// When it's retried automatically; `None` once retries are used up, or
+    /// for errors retrying won't fix
+    pub next_retry_at: Option<DateTime<Utc>>,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum SummaryStatus {
     #[default]
@@ -0,0 +1,16 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Tag incoming articles that match a search, e.g. `folder:Security
+/// re:"CVE-\d+"` tagged `vuln`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TagRule {
+    pub id: i64,
+    /// In the search language; see `db::Query`
+    pub query: String,
+    pub tag: String,
+    pub enabled: bool,
+    /// Articles the rule has tagged
+    pub hits: u64,
+    pub last_hit_at: Option<DateTime<Utc>>,
+}
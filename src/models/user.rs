@@ -0,0 +1,45 @@
+use chrono::{DateTime, Utc};
+
+/// A server-mode account. Credentials are only ever stored hashed.
+#[derive(Debug, Clone)]
+pub struct User {
+    pub id: i64,
+    pub username: String,
+    pub password_hash: String,
+}
+
+/// What an API token may do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenScope {
+    /// Only reads: GET requests (and GraphQL queries)
+    Read,
+    ReadWrite,
+}
+
+impl TokenScope {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::ReadWrite => "read-write",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "read" | "read-only" => Some(Self::Read),
+            "read-write" | "write" => Some(Self::ReadWrite),
+            _ => None,
+        }
+    }
+}
+
+/// A named API token made with `beatcheck token create`
+#[derive(Debug, Clone)]
+pub struct ApiToken {
+    pub id: i64,
+    pub username: String,
+    pub name: String,
+    pub scope: TokenScope,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,160 @@
+//! Times of the week, for quiet hours and refresh windows in `[schedule]`:
+//! `"22:00-07:00"`, `"mon-fri 09:00-18:00"`, `"sat,sun"`.
+
+use std::fmt;
+
+use chrono::{Datelike, NaiveDateTime, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// Some days of the week, optionally only between two times of day. A
+/// window that ends before it starts runs past midnight, and belongs to
+/// the day it starts on ("fri 22:00-02:00" includes early Saturday).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct TimeWindow {
+    /// As written in the config, for saving it back
+    spec: String,
+    /// Indexed from Monday
+    days: [bool; 7],
+    /// Start and end; the whole day if `None`
+    hours: Option<(NaiveTime, NaiveTime)>,
+}
+
+impl TimeWindow {
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut parts = spec.split_whitespace();
+        let (first, second) = (parts.next(), parts.next());
+        if parts.next().is_some() {
+            return Err(format!("\"{}\": expected DAYS HH:MM-HH:MM", spec));
+        }
+        let (days, hours) = match (first, second) {
+            (None, _) => return Err("empty time window".to_string()),
+            (Some(days), Some(hours)) => (parse_days(days)?, Some(parse_hours(hours)?)),
+            (Some(only), None) if only.contains(':') => ([true; 7], Some(parse_hours(only)?)),
+            (Some(days), None) => (parse_days(days)?, None),
+        };
+        Ok(Self {
+            spec: spec.trim().to_string(),
+            days,
+            hours,
+        })
+    }
+
+    /// Whether `now` (local time) falls in the window
+    pub fn contains(&self, now: NaiveDateTime) -> bool {
+        let on = |day: Weekday| self.days[day.num_days_from_monday() as usize];
+        let (day, time) = (now.weekday(), now.time());
+        match self.hours {
+            None => on(day),
+            Some((start, end)) if start < end => on(day) && start <= time && time < end,
+            // Past midnight: the evening part today, or the morning after yesterday's start
+            Some((start, end)) => (on(day) && time >= start) || (on(day.pred()) && time < end),
+        }
+    }
+}
+
+impl TryFrom<String> for TimeWindow {
+    type Error = String;
+
+    fn try_from(spec: String) -> Result<Self, String> {
+        Self::parse(&spec)
+    }
+}
+
+impl From<TimeWindow> for String {
+    fn from(window: TimeWindow) -> Self {
+        window.spec
+    }
+}
+
+impl fmt::Display for TimeWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.spec)
+    }
+}
+
+/// "mon-fri", "sat,sun", "weekdays", "mon,wed-fri"
+fn parse_days(spec: &str) -> Result<[bool; 7], String> {
+    let mut days = [false; 7];
+    for part in spec.split(',') {
+        let range = match part.to_lowercase().as_str() {
+            "weekdays" => (Weekday::Mon, Weekday::Fri),
+            "weekends" => (Weekday::Sat, Weekday::Sun),
+            "daily" => (Weekday::Mon, Weekday::Sun),
+            _ => match part.split_once('-') {
+                Some((from, to)) => (parse_day(from)?, parse_day(to)?),
+                None => (parse_day(part)?, parse_day(part)?),
+            },
+        };
+        let mut day = range.0;
+        loop {
+            days[day.num_days_from_monday() as usize] = true;
+            if day == range.1 {
+                break;
+            }
+            day = day.succ();
+        }
+    }
+    Ok(days)
+}
+
+fn parse_day(name: &str) -> Result<Weekday, String> {
+    name.parse()
+        .map_err(|_| format!("\"{}\" isn't a day (mon, tue, ..., or weekdays, weekends)", name))
+}
+
+/// "22:00-07:00"
+fn parse_hours(spec: &str) -> Result<(NaiveTime, NaiveTime), String> {
+    let time = |s: &str| {
+        NaiveTime::parse_from_str(s, "%H:%M").map_err(|_| format!("\"{}\" isn't a time like 07:30", s))
+    };
+    let (start, end) = spec
+        .split_once('-')
+        .ok_or_else(|| format!("\"{}\": expected HH:MM-HH:MM", spec))?;
+    let (start, end) = (time(start)?, time(end)?);
+    if start == end {
+        return Err(format!("\"{}\" is empty; leave out the hours for the whole day", spec));
+    }
+    Ok((start, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    /// 2026-03-06 is a Friday
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 3, day).unwrap().and_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_windows_cover_days_and_overnight_hours() {
+        let night = TimeWindow::parse("22:00-07:00").unwrap();
+        assert!(night.contains(at(6, 23, 0)));
+        assert!(night.contains(at(7, 6, 59)));
+        assert!(!night.contains(at(7, 7, 0)));
+        assert!(!night.contains(at(6, 12, 0)));
+
+        // Friday night runs into Saturday morning, but Sunday night isn't included
+        let friday = TimeWindow::parse("fri 22:00-02:00").unwrap();
+        assert!(friday.contains(at(7, 1, 0)));
+        assert!(!friday.contains(at(9, 1, 0)));
+
+        let work = TimeWindow::parse("Mon-Fri 09:00-18:00").unwrap();
+        assert!(work.contains(at(6, 9, 0)));
+        assert!(!work.contains(at(7, 10, 0)));
+        let weekend = TimeWindow::parse("weekends").unwrap();
+        assert!(weekend.contains(at(8, 3, 0)) && !weekend.contains(at(6, 3, 0)));
+        // Wraps past Sunday
+        assert!(TimeWindow::parse("sat-mon").unwrap().contains(at(9, 12, 0)));
+        assert_eq!(weekend.to_string(), "weekends");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for bad in ["", "22:00", "25:00-07:00", "someday 09:00-10:00", "09:00-09:00", "mon 09:00-10:00 extra"] {
+            assert!(TimeWindow::parse(bad).is_err(), "{}", bad);
+        }
+    }
+}
@@ -0,0 +1,624 @@
+use axum::extract::{Path, Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::db::{self, ArticleFilter, FeedRemoval};
+use crate::error::AppError;
+use crate::feed::find_by_url;
+use crate::metrics::{metrics as process_metrics, DbMetrics, FeedFreshness};
+use crate::models::{Article, AuthorCount, EntityCount, EntityKind, Feed, NewFeed, Summary};
+use crate::trends::{load_trends, Trend};
+use crate::watchlist::{load_timelines, Timeline, TIMELINE_DAYS};
+
+use super::auth::CurrentUser;
+use super::events::Event;
+use super::ServerState;
+
+pub(super) const DEFAULT_PAGE_SIZE: usize = 50;
+pub(super) const MAX_PAGE_SIZE: usize = 500;
+/// How far back `/api/entities` looks unless `days` says otherwise
+const DEFAULT_ENTITY_DAYS: i64 = 7;
+
+/// Error response: 404 for missing resources, 400 for unusable input, 403
+/// for requests refused outright, 500 (logged) for everything else
+#[derive(Debug)]
+pub enum ApiError {
+    NotFound,
+    BadRequest(String),
+    Forbidden(String),
+    Internal(AppError),
+}
+
+impl From<AppError> for ApiError {
+    fn from(e: AppError) -> Self {
+        ApiError::Internal(e)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        match self {
+            ApiError::NotFound => (StatusCode::NOT_FOUND, "Not found").into_response(),
+            ApiError::BadRequest(message) => (StatusCode::BAD_REQUEST, message).into_response(),
+            ApiError::Forbidden(message) => (StatusCode::FORBIDDEN, message).into_response(),
+            ApiError::Internal(e) => {
+                tracing::error!("API request failed: {}", e);
+                (StatusCode::INTERNAL_SERVER_ERROR, "Internal error").into_response()
+            }
+        }
+    }
+}
+
+pub(super) type ApiResult<T> = std::result::Result<T, ApiError>;
+
+/// Present only when `server.auth` is on; `None` means the single local reader
+pub(super) type MaybeUser = Option<Extension<CurrentUser>>;
+
+#[derive(Debug, Deserialize)]
+pub struct ListParams {
+    feed_id: Option<i64>,
+    #[serde(default)]
+    starred: bool,
+    #[serde(default)]
+    unread: bool,
+    q: Option<String>,
+    tag: Option<String>,
+    entity: Option<String>,
+    author: Option<String>,
+    after: Option<i64>,
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TrendParams {
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WatchlistParams {
+    days: Option<usize>,
+}
+
+/// A watched entity's coverage, flagged when today is a spike
+#[derive(Debug, Serialize)]
+pub struct WatchedEntity {
+    #[serde(flatten)]
+    timeline: Timeline,
+    spike: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EntityParams {
+    /// person, organization or ticker
+    kind: Option<String>,
+    days: Option<i64>,
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AuthorParams {
+    days: Option<i64>,
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TagCount {
+    tag: String,
+    articles: usize,
+}
+
+/// Article without its (potentially large) content, for list views
+#[derive(Debug, Serialize)]
+pub struct ArticleListItem {
+    id: i64,
+    feed_id: i64,
+    feed_title: Option<String>,
+    title: String,
+    url: String,
+    author: Option<String>,
+    published_at: Option<DateTime<Utc>>,
+    reading_minutes: Option<u32>,
+    is_read: bool,
+    is_starred: bool,
+    tags: Vec<String>,
+}
+
+impl From<Article> for ArticleListItem {
+    fn from(a: Article) -> Self {
+        Self {
+            id: a.id,
+            feed_id: a.feed_id,
+            feed_title: a.feed_title,
+            title: a.title,
+            url: a.url,
+            author: a.author,
+            published_at: a.published_at,
+            reading_minutes: a.reading_minutes,
+            is_read: a.is_read,
+            is_starred: a.is_starred,
+            tags: a.tags,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ArticlePage {
+    articles: Vec<ArticleListItem>,
+    /// Pass as `after` to fetch the next page; absent on the last page
+    next: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ArticleDetail {
+    article: Article,
+    summary: Option<Summary>,
+    /// The article's URL, through its feed's reader view if it has one
+    open_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReadBody {
+    read: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StarBody {
+    starred: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SubscribeBody {
+    /// Feed URL or a page that links to one
+    url: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DiscoverParams {
+    /// Feed URL or a page that links to one
+    url: String,
+}
+
+/// The feed found for a URL, before subscribing
+#[derive(Debug, Serialize)]
+pub struct DiscoveredFeed {
+    title: String,
+    url: String,
+    site_url: Option<String>,
+    description: Option<String>,
+    /// The caller follows it already (under this or another variant of its URL)
+    subscribed: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UnsubscribeParams {
+    /// `delete` (default), `keep-starred` or `archive`
+    mode: Option<String>,
+}
+
+/// Prometheus scrape endpoint
+pub async fn metrics(State(state): State<ServerState>) -> ApiResult<impl IntoResponse> {
+    // SQLite in WAL mode keeps recent writes in a sidecar file
+    let size_bytes = [state.db_path.clone(), format!("{}-wal", state.db_path)]
+        .iter()
+        .filter_map(|path| std::fs::metadata(path).ok())
+        .map(|meta| meta.len())
+        .sum();
+
+    let db = DbMetrics {
+        size_bytes,
+        articles: state.repository.count_articles().await?,
+        feeds: state
+            .repository
+            .get_all_feeds()
+            .await?
+            .into_iter()
+            .map(|f| FeedFreshness {
+                id: f.id,
+                title: f.display_title().to_string(),
+                last_fetched: f.last_fetched,
+            })
+            .collect(),
+    };
+
+    Ok((
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4; charset=utf-8")],
+        process_metrics().render(&db),
+    ))
+}
+
+/// The signed-in account, or `null` when auth is off
+pub async fn current_user(user: MaybeUser) -> Json<Option<CurrentUser>> {
+    Json(user.map(|Extension(u)| u))
+}
+
+pub async fn list_feeds(
+    State(state): State<ServerState>,
+    user: MaybeUser,
+) -> ApiResult<Json<Vec<Feed>>> {
+    let feeds = match user {
+        Some(Extension(user)) => state.repository.get_user_feeds(user.id).await?,
+        None => state.repository.get_all_feeds().await?,
+    };
+    Ok(Json(feeds))
+}
+
+/// Suggested article tags, most used first, for tag browsing
+pub async fn list_tags(State(state): State<ServerState>, user: MaybeUser) -> ApiResult<Json<Vec<TagCount>>> {
+    let user_id = user.map(|Extension(u)| u.id);
+    let tags = state
+        .repository
+        .tag_counts(user_id, MAX_PAGE_SIZE)
+        .await?
+        .into_iter()
+        .map(|(tag, articles)| TagCount { tag, articles })
+        .collect();
+    Ok(Json(tags))
+}
+
+/// People, organizations and tickers in recent articles, most mentioned
+/// first, for entity browsing
+pub async fn list_entities(
+    State(state): State<ServerState>,
+    user: MaybeUser,
+    Query(params): Query<EntityParams>,
+) -> ApiResult<Json<Vec<EntityCount>>> {
+    let kind = match params.kind.as_deref() {
+        Some(kind) => Some(
+            EntityKind::parse(kind)
+                .ok_or_else(|| ApiError::BadRequest(format!("Unknown entity kind: {}", kind)))?,
+        ),
+        None => None,
+    };
+    let days = params.days.unwrap_or(DEFAULT_ENTITY_DAYS).max(1);
+    let limit = params.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+    let entities = state
+        .repository
+        .recent_entities(
+            user.map(|Extension(u)| u.id),
+            Utc::now() - chrono::Duration::days(days),
+            kind,
+            limit,
+        )
+        .await?;
+    Ok(Json(entities))
+}
+
+/// Bylines in recent articles, most prolific first, with how many feeds
+/// each author appears in
+pub async fn list_authors(
+    State(state): State<ServerState>,
+    user: MaybeUser,
+    Query(params): Query<AuthorParams>,
+) -> ApiResult<Json<Vec<AuthorCount>>> {
+    let days = params.days.unwrap_or(DEFAULT_ENTITY_DAYS).max(1);
+    let limit = params.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+    let authors = state
+        .repository
+        .recent_authors(user.map(|Extension(u)| u.id), Utc::now() - chrono::Duration::days(days), limit)
+        .await?;
+    Ok(Json(authors))
+}
+
+/// Headline terms rising fastest in the last 24 hours, with example articles
+pub async fn list_trends(
+    State(state): State<ServerState>,
+    user: MaybeUser,
+    Query(params): Query<TrendParams>,
+) -> ApiResult<Json<Vec<Trend>>> {
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+    let trends = load_trends(&state.repository, user.map(|Extension(u)| u.id), limit).await?;
+    Ok(Json(trends))
+}
+
+/// Daily coverage of every watched entity. Watchlists are shared by all
+/// accounts.
+pub async fn list_watchlists(
+    State(state): State<ServerState>,
+    Query(params): Query<WatchlistParams>,
+) -> ApiResult<Json<Vec<WatchedEntity>>> {
+    let days = params.days.unwrap_or(TIMELINE_DAYS).clamp(1, 365);
+    let watched = load_timelines(&state.repository, days)
+        .await?
+        .into_iter()
+        .map(|timeline| WatchedEntity {
+            spike: timeline.is_spike(),
+            timeline,
+        })
+        .collect();
+    Ok(Json(watched))
+}
+
+/// Find the feed at a URL, or linked from the page there, without
+/// subscribing; the bookmarklet's page shows it before subscribing. The
+/// fetcher only reaches public addresses, so the server's network can't be
+/// explored through it.
+pub async fn discover(
+    State(state): State<ServerState>,
+    user: MaybeUser,
+    Query(params): Query<DiscoverParams>,
+) -> ApiResult<Json<DiscoveredFeed>> {
+    let feed = state
+        .fetcher
+        .discover_feed(params.url.trim())
+        .await
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    Ok(Json(discovered(&state, &user, feed).await?))
+}
+
+/// A found feed, and whether the caller already follows it
+async fn discovered(state: &ServerState, user: &MaybeUser, feed: NewFeed) -> ApiResult<DiscoveredFeed> {
+    let followed = match user {
+        Some(Extension(user)) => state.repository.get_user_feeds(user.id).await?,
+        None => state.repository.get_all_feeds().await?,
+    };
+    Ok(DiscoveredFeed {
+        subscribed: find_by_url(&followed, &feed.url).is_some(),
+        title: feed.title,
+        url: feed.url,
+        site_url: feed.site_url,
+        description: feed.description,
+    })
+}
+
+/// Subscribe to a feed, adding it (and fetching its articles) if no one
+/// follows it yet
+pub async fn subscribe(
+    State(state): State<ServerState>,
+    user: MaybeUser,
+    Json(body): Json<SubscribeBody>,
+) -> ApiResult<(StatusCode, Json<Feed>)> {
+    let new_feed = state
+        .fetcher
+        .discover_feed(body.url.trim())
+        .await
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+
+    // Variants of a subscribed URL (http/https, www., trailing slash) reuse its feed
+    let feeds = state.repository.get_all_feeds().await?;
+    let mut fetched = None;
+    let feed = match find_by_url(&feeds, &new_feed.url).cloned() {
+        Some(feed) => feed,
+        None => {
+            let feed_id = state.repository.insert_feed(new_feed.clone()).await?;
+            match state.fetcher.fetch_feed(feed_id, &new_feed.url, None).await {
+                Ok(feed) => {
+                    let count = feed.articles.len();
+                    for article in feed.articles {
+                        state.repository.upsert_article(article).await?;
+                    }
+                    state.repository.update_feed_last_fetched(feed_id, feed.repaired).await?;
+                    fetched = Some(Event::Articles { feed_id, count });
+                }
+                // The refresh loop will try again
+                Err(e) => tracing::warn!("Initial fetch of {} failed: {}", new_feed.url, e),
+            }
+            state
+                .repository
+                .get_feed_by_url(&new_feed.url)
+                .await?
+                .ok_or(ApiError::NotFound)?
+        }
+    };
+
+    if let Some(Extension(user)) = user {
+        state.repository.subscribe_user(user.id, feed.id).await?;
+    }
+    // Once subscribed, so the caller's own event stream passes it on
+    if let Some(event) = fetched {
+        let _ = state.events.send(event);
+    }
+    Ok((StatusCode::CREATED, Json(feed)))
+}
+
+/// Drop a subscription. Without auth there is only one reader, so the feed
+/// itself is removed, its articles handled as `?mode=` says. With auth the
+/// feed stays for its other readers, so there's nothing for a mode to do.
+pub async fn unsubscribe(
+    State(state): State<ServerState>,
+    user: MaybeUser,
+    Path(id): Path<i64>,
+    Query(params): Query<UnsubscribeParams>,
+) -> ApiResult<StatusCode> {
+    let removal = match params.mode.as_deref() {
+        None | Some("delete") => FeedRemoval::DeleteAll,
+        Some("keep-starred") => FeedRemoval::KeepStarred,
+        Some("archive") => FeedRemoval::Archive,
+        Some(other) => return Err(ApiError::BadRequest(format!("Unknown mode: {}", other))),
+    };
+    match user {
+        Some(_) if !matches!(removal, FeedRemoval::DeleteAll) => {
+            return Err(ApiError::BadRequest("mode only applies without accounts".to_string()));
+        }
+        Some(Extension(user)) => {
+            if !state.repository.unsubscribe_user(user.id, id).await? {
+                return Err(ApiError::NotFound);
+            }
+        }
+        None => {
+            let feeds = state.repository.get_all_feeds().await?;
+            if !feeds.iter().any(|f| f.id == id) {
+                return Err(ApiError::NotFound);
+            }
+            state.repository.remove_feed(id, removal).await?;
+        }
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Look up an article the caller is allowed to see
+pub(super) async fn find_article(state: &ServerState, user: &MaybeUser, id: i64) -> ApiResult<Article> {
+    let article = match user {
+        Some(Extension(user)) => state.repository.get_article_for_user(id, user.id).await?,
+        None => state.repository.get_article(id).await?,
+    };
+    article.ok_or(ApiError::NotFound)
+}
+
+pub async fn list_articles(
+    State(state): State<ServerState>,
+    user: MaybeUser,
+    Query(params): Query<ListParams>,
+) -> ApiResult<Json<ArticlePage>> {
+    let limit = params.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+    let query = params
+        .q
+        .as_deref()
+        .filter(|q| !q.trim().is_empty())
+        .map(db::Query::parse)
+        .transpose()
+        .map_err(|e| ApiError::BadRequest(format!("Bad query at {}", e)))?;
+    let filter = ArticleFilter {
+        feed_id: params.feed_id,
+        feed_ids: None,
+        since: None,
+        until: None,
+        starred_only: params.starred,
+        unread_only: params.unread,
+        text: None,
+        user_id: user.map(|Extension(u)| u.id),
+        tag: params.tag.map(|t| t.trim().to_lowercase()),
+        entity: params.entity.map(|e| e.trim().to_string()),
+        author: params.author.map(|a| a.trim().to_string()),
+        unsummarized: false,
+        query,
+    };
+
+    let articles = state
+        .repository
+        .list_articles(&filter, limit, params.after)
+        .await
+        .map_err(|e| match e {
+            // A regex search that ran out of time
+            AppError::Config(message) => ApiError::BadRequest(message),
+            e => e.into(),
+        })?;
+    let next = if articles.len() == limit {
+        articles.last().map(|a| a.id)
+    } else {
+        None
+    };
+
+    Ok(Json(ArticlePage {
+        articles: articles.into_iter().map(ArticleListItem::from).collect(),
+        next,
+    }))
+}
+
+pub async fn get_article(
+    State(state): State<ServerState>,
+    user: MaybeUser,
+    Path(id): Path<i64>,
+) -> ApiResult<Json<ArticleDetail>> {
+    let mut article = find_article(&state, &user, id).await?;
+    state.images.apply(&mut article);
+    let summary = state.repository.get_summary(id).await?;
+    let open_url = match state.repository.get_feed(article.feed_id).await? {
+        Some(feed) => feed.reader_view(&article.url),
+        None => article.url.clone(),
+    };
+    Ok(Json(ArticleDetail { article, summary, open_url }))
+}
+
+pub async fn set_read(
+    State(state): State<ServerState>,
+    user: MaybeUser,
+    Path(id): Path<i64>,
+    Json(body): Json<ReadBody>,
+) -> ApiResult<StatusCode> {
+    find_article(&state, &user, id).await?;
+    match user {
+        Some(Extension(user)) => state.repository.set_user_read(user.id, id, body.read).await?,
+        None => state.repository.set_read(id, body.read).await?,
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn set_starred(
+    State(state): State<ServerState>,
+    user: MaybeUser,
+    Path(id): Path<i64>,
+    Json(body): Json<StarBody>,
+) -> ApiResult<StatusCode> {
+    find_article(&state, &user, id).await?;
+    match user {
+        Some(Extension(user)) => {
+            state.repository.set_user_starred(user.id, id, body.starred).await?
+        }
+        None => state.repository.set_starred(id, body.starred).await?,
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn caller(id: i64) -> MaybeUser {
+        Some(Extension(CurrentUser { id, username: format!("user{}", id) }))
+    }
+
+    async fn add_feed(state: &ServerState, url: &str) -> i64 {
+        let feed = NewFeed { title: url.to_string(), url: url.to_string(), site_url: None, description: None };
+        state.repository.insert_feed(feed).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_unsubscribe_modes_need_single_user() {
+        let (state, _dir) = super::super::test_state(true).await;
+        let user = state.repository.create_user("one", "x").await.unwrap();
+        let feed_id = add_feed(&state, "https://a.example/feed").await;
+        state.repository.subscribe_user(user, feed_id).await.unwrap();
+
+        let mode = |mode: &str| Query(UnsubscribeParams { mode: Some(mode.to_string()) });
+        let refused = unsubscribe(State(state.clone()), caller(user), Path(feed_id), mode("keep-starred")).await;
+        assert!(matches!(refused, Err(ApiError::BadRequest(_))));
+        assert_eq!(state.repository.get_user_feeds(user).await.unwrap().len(), 1);
+
+        let status = unsubscribe(State(state.clone()), caller(user), Path(feed_id), mode("delete")).await.unwrap();
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        assert!(state.repository.get_user_feeds(user).await.unwrap().is_empty());
+        // Still there for anyone else following it
+        assert_eq!(state.repository.get_all_feeds().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_discover_marks_feeds_the_caller_follows() {
+        let (state, _dir) = super::super::test_state(true).await;
+        let one = state.repository.create_user("one", "x").await.unwrap();
+        let two = state.repository.create_user("two", "x").await.unwrap();
+        let feed_id = add_feed(&state, "https://a.example/feed").await;
+        state.repository.subscribe_user(one, feed_id).await.unwrap();
+
+        // Variants of the URL count as the same feed
+        let found = || NewFeed {
+            title: "A".to_string(),
+            url: "http://www.a.example/feed/".to_string(),
+            site_url: None,
+            description: None,
+        };
+        assert!(discovered(&state, &caller(one), found()).await.unwrap().subscribed);
+        assert!(!discovered(&state, &caller(two), found()).await.unwrap().subscribed);
+        // Without accounts, every feed is the reader's
+        assert!(discovered(&state, &None, found()).await.unwrap().subscribed);
+    }
+
+    #[tokio::test]
+    async fn test_discover_and_subscribe_refuse_private_addresses() {
+        let (state, _dir) = super::super::test_state(false).await;
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        for url in [format!("http://127.0.0.1:{}/feed", port), format!("http://localhost:{}/", port)] {
+            let params = Query(DiscoverParams { url: url.clone() });
+            let refused = discover(State(state.clone()), None, params).await;
+            assert!(matches!(refused, Err(ApiError::BadRequest(_))), "{}", url);
+            let refused = subscribe(State(state.clone()), None, Json(SubscribeBody { url: url.clone() })).await;
+            assert!(matches!(refused, Err(ApiError::BadRequest(_))), "{}", url);
+        }
+        let accepted = tokio::time::timeout(std::time::Duration::from_millis(100), listener.accept()).await;
+        assert!(accepted.is_err(), "connected to the loopback address");
+        assert!(state.repository.get_all_feeds().await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,195 @@
+// BeatCheck web UI: plain DOM, no build step. All URLs are relative so the
+// UI works when served under a base path.
+
+const state = {
+  feedId: null,
+  starred: false,
+  query: "",
+  next: null,
+  selectedId: null,
+  unseen: 0,
+};
+
+const $ = (id) => document.getElementById(id);
+
+async function api(path, options = {}) {
+  const response = await fetch(path, {
+    headers: { "Content-Type": "application/json" },
+    ...options,
+  });
+  if (!response.ok) {
+    const message = await response.text();
+    throw new Error(message || `${response.status} ${response.statusText}`);
+  }
+  return response.status === 204 ? null : response.json();
+}
+
+function el(tag, attrs = {}, text = "") {
+  const node = document.createElement(tag);
+  Object.assign(node, attrs);
+  if (text) node.textContent = text;
+  return node;
+}
+
+function formatDate(iso) {
+  return iso ? new Date(iso).toLocaleString() : "";
+}
+
+function volumeHint(feed) {
+  if (feed.posts_per_day == null) return "";
+  const last = feed.last_post_at ? `, last post ${formatDate(feed.last_post_at)}` : "";
+  return `${feed.posts_per_day.toFixed(1)} posts/day${last}`;
+}
+
+async function loadFeeds() {
+  const feeds = await api("api/feeds");
+  const list = $("feed-list");
+  list.replaceChildren();
+
+  const entries = [
+    { label: "All articles", feedId: null, starred: false },
+    { label: "★ Starred", feedId: null, starred: true },
+    ...feeds.map((f) => ({ label: f.title, feedId: f.id, starred: false, hint: volumeHint(f) })),
+  ];
+
+  for (const entry of entries) {
+    const item = el("li", { title: entry.hint || "" }, entry.label);
+    if (entry.feedId === state.feedId && entry.starred === state.starred) {
+      item.classList.add("active");
+    }
+    item.onclick = () => {
+      state.feedId = entry.feedId;
+      state.starred = entry.starred;
+      loadFeeds();
+      loadArticles(true);
+    };
+    list.appendChild(item);
+  }
+}
+
+async function loadArticles(reset) {
+  const params = new URLSearchParams();
+  if (state.feedId !== null) params.set("feed_id", state.feedId);
+  if (state.starred) params.set("starred", "true");
+  if ($("unread-only").checked) params.set("unread", "true");
+  if (state.query) params.set("q", state.query);
+  if (!reset && state.next !== null) params.set("after", state.next);
+
+  const page = await api(`api/articles?${params}`);
+  const list = $("article-list");
+  if (reset) list.replaceChildren();
+
+  for (const article of page.articles) {
+    const item = el("li");
+    item.dataset.id = article.id;
+    item.classList.toggle("read", article.is_read);
+    item.appendChild(el("span", { className: "title" }, article.title));
+    const meta = [article.feed_title, formatDate(article.published_at)];
+    if (article.reading_minutes) meta.push(`${article.reading_minutes} min`);
+    item.appendChild(el("span", { className: "meta" }, meta.filter(Boolean).join(" · ")));
+    if (article.is_starred) item.classList.add("starred");
+    item.onclick = () => openArticle(article.id);
+    list.appendChild(item);
+  }
+
+  state.next = page.next;
+  $("load-more").hidden = page.next === null;
+  if (reset) {
+    state.unseen = 0;
+    $("new-articles").hidden = true;
+  }
+}
+
+// Refreshes push events instead of the page polling: new articles in the
+// list being viewed show up at once, unless the reader has scrolled down
+// it, when a button offers them instead.
+function listenForArticles() {
+  const events = new EventSource("api/events");
+  events.addEventListener("articles", (event) => {
+    const { feed_id, count } = JSON.parse(event.data);
+    if (state.starred || (state.feedId !== null && state.feedId !== feed_id)) return;
+    if ($("articles").scrollTop === 0 && !state.query) {
+      loadArticles(true);
+      return;
+    }
+    state.unseen += count;
+    const button = $("new-articles");
+    button.textContent = `${state.unseen} new article${state.unseen === 1 ? "" : "s"}`;
+    button.hidden = false;
+  });
+  events.addEventListener("refreshed", () => loadFeeds());
+}
+
+async function openArticle(id) {
+  state.selectedId = id;
+  const { article, summary, open_url } = await api(`api/articles/${id}`);
+  const reader = $("reader");
+  reader.replaceChildren();
+
+  reader.appendChild(el("h2", {}, article.title));
+  reader.appendChild(
+    el("p", { className: "meta" }, [article.feed_title, article.author, formatDate(article.published_at)]
+      .filter(Boolean)
+      .join(" · "))
+  );
+
+  const actions = el("div", { className: "actions" });
+  const star = el("button", {}, article.is_starred ? "Unstar" : "Star");
+  star.onclick = async () => {
+    await api(`api/articles/${id}/star`, {
+      method: "POST",
+      body: JSON.stringify({ starred: !article.is_starred }),
+    });
+    openArticle(id);
+    loadArticles(true);
+  };
+  const unread = el("button", {}, "Mark unread");
+  unread.onclick = async () => {
+    await api(`api/articles/${id}/read`, { method: "POST", body: JSON.stringify({ read: false }) });
+    loadArticles(true);
+  };
+  const open = el("a", { href: open_url, target: "_blank", rel: "noopener noreferrer" }, "Open original");
+  actions.append(star, unread, open);
+  reader.appendChild(actions);
+
+  if (summary) {
+    reader.appendChild(el("h3", {}, "AI Summary"));
+    reader.appendChild(el("pre", { className: "summary" }, summary.content));
+  }
+
+  // Feed HTML is untrusted: render it in a sandboxed frame with scripts disabled
+  if (article.content) {
+    const frame = el("iframe", { className: "content", sandbox: "" });
+    frame.srcdoc = article.content;
+    reader.appendChild(frame);
+  } else if (article.content_text) {
+    reader.appendChild(el("pre", { className: "content-text" }, article.content_text));
+  }
+
+  if (!article.is_read) {
+    await api(`api/articles/${id}/read`, { method: "POST", body: JSON.stringify({ read: true }) });
+    const item = document.querySelector(`#article-list li[data-id="${id}"]`);
+    if (item) item.classList.add("read");
+  }
+}
+
+$("search").onsubmit = (event) => {
+  event.preventDefault();
+  const input = $("search-input");
+  state.query = input.value.trim();
+  loadArticles(true).catch((error) => {
+    input.setCustomValidity(error.message);
+    input.reportValidity();
+  });
+};
+$("search-input").oninput = () => $("search-input").setCustomValidity("");
+$("unread-only").onchange = () => loadArticles(true);
+$("load-more").onclick = () => loadArticles(false);
+$("new-articles").onclick = () => {
+  $("articles").scrollTop = 0;
+  loadArticles(true);
+};
+
+loadFeeds();
+loadArticles(true);
+listenForArticles();
@@ -0,0 +1,70 @@
+// "Subscribe to this page": the bookmarklet opens this page with ?url=,
+// the feed found there is shown, and one click subscribes. URLs are
+// relative, as in app.js, so this works under a base path.
+
+const $ = (id) => document.getElementById(id);
+
+async function api(path, options = {}) {
+  const response = await fetch(path, {
+    headers: { "Content-Type": "application/json" },
+    ...options,
+  });
+  if (!response.ok) {
+    const message = await response.text();
+    throw new Error(message || `${response.status} ${response.statusText}`);
+  }
+  return response.json();
+}
+
+function el(tag, attrs = {}, text = "") {
+  const node = document.createElement(tag);
+  Object.assign(node, attrs);
+  if (text) node.textContent = text;
+  return node;
+}
+
+function showError(error) {
+  $("result").replaceChildren(el("p", { className: "error" }, error.message));
+}
+
+async function find(url) {
+  const result = $("result");
+  result.replaceChildren(el("p", {}, "Looking for a feed…"));
+  const feed = await api(`api/feeds/discover?url=${encodeURIComponent(url)}`);
+
+  result.replaceChildren(el("h3", {}, feed.title));
+  result.appendChild(el("p", { className: "meta" }, feed.url));
+  if (feed.description) result.appendChild(el("p", {}, feed.description));
+  if (feed.subscribed) {
+    result.appendChild(el("p", {}, "You're already subscribed."));
+    return;
+  }
+  const button = el("button", { autofocus: true }, "Subscribe");
+  button.onclick = async () => {
+    button.disabled = true;
+    try {
+      const added = await api("api/feeds", { method: "POST", body: JSON.stringify({ url: feed.url }) });
+      button.replaceWith(el("p", {}, `Subscribed to ${added.custom_title || added.title}.`));
+    } catch (error) {
+      button.disabled = false;
+      showError(error);
+    }
+  };
+  result.appendChild(button);
+  button.focus();
+}
+
+$("find").onsubmit = (event) => {
+  event.preventDefault();
+  find($("url").value.trim()).catch(showError);
+};
+
+// The bookmarklet sends the page it's clicked on back here
+const here = new URL("subscribe?url=", location.href).href;
+$("bookmarklet").href = `javascript:location.href=${JSON.stringify(here)}+encodeURIComponent(location.href)`;
+
+const url = new URLSearchParams(location.search).get("url");
+if (url) {
+  $("url").value = url;
+  find(url).catch(showError);
+}
@@ -0,0 +1,226 @@
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use axum::extract::{Request, State};
+use axum::http::{header, HeaderValue, Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use base64::Engine;
+use rand_core::{OsRng, RngCore};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::error::{AppError, Result};
+use crate::models::{TokenScope, User};
+
+use super::proxy::ClientAddr;
+use super::ServerState;
+
+/// The authenticated account, added to request extensions by `require_user`
+#[derive(Debug, Clone, Serialize)]
+pub struct CurrentUser {
+    pub id: i64,
+    pub username: String,
+}
+
+impl From<User> for CurrentUser {
+    fn from(user: User) -> Self {
+        Self {
+            id: user.id,
+            username: user.username,
+        }
+    }
+}
+
+pub fn hash_password(password: &str) -> Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| AppError::Config(format!("Failed to hash password: {}", e)))
+}
+
+pub fn verify_password(password: &str, hash: &str) -> bool {
+    PasswordHash::new(hash)
+        .map(|parsed| {
+            Argon2::default()
+                .verify_password(password.as_bytes(), &parsed)
+                .is_ok()
+        })
+        .unwrap_or(false)
+}
+
+/// A fresh random API token (64 hex characters)
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    to_hex(&bytes)
+}
+
+/// Tokens are long and random, so a plain SHA-256 is enough to store them
+pub fn hash_token(token: &str) -> String {
+    to_hex(&Sha256::digest(token.as_bytes()))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Credentials from an `Authorization` header
+#[derive(Debug, PartialEq, Eq)]
+enum Credentials {
+    Basic { username: String, password: String },
+    Bearer(String),
+}
+
+fn parse_authorization(value: &str) -> Option<Credentials> {
+    let (scheme, rest) = value.trim().split_once(' ')?;
+    let rest = rest.trim();
+    if scheme.eq_ignore_ascii_case("bearer") {
+        return Some(Credentials::Bearer(rest.to_string()));
+    }
+    if scheme.eq_ignore_ascii_case("basic") {
+        let decoded = base64::engine::general_purpose::STANDARD.decode(rest).ok()?;
+        let decoded = String::from_utf8(decoded).ok()?;
+        let (username, password) = decoded.split_once(':')?;
+        return Some(Credentials::Basic {
+            username: username.to_string(),
+            password: password.to_string(),
+        });
+    }
+    None
+}
+
+/// The account and what these credentials let it do. Only tokens made
+/// with `beatcheck token create` can be narrower than read-write.
+async fn authenticate(state: &ServerState, credentials: Credentials) -> Result<Option<(User, TokenScope)>> {
+    let user = match credentials {
+        Credentials::Bearer(token) => {
+            let token_hash = hash_token(&token);
+            if let Some(found) = state.repository.get_user_by_api_token(&token_hash).await? {
+                return Ok(Some(found));
+            }
+            match state.repository.get_user_by_token_hash(&token_hash).await? {
+                Some(user) => Some(user),
+                // A token the Wallabag OAuth endpoint handed out
+                None => state.repository.get_user_by_oauth_token(&token_hash, "access").await?,
+            }
+        }
+        Credentials::Basic { username, password } => log_in(state, &username, password).await?,
+    };
+    Ok(user.map(|user| (user, TokenScope::ReadWrite)))
+}
+
+/// Whether a request may change anything. GraphQL queries come as POSTs
+/// but only read.
+fn is_write(method: &Method, path: &str) -> bool {
+    !matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS) && path != "/api/graphql"
+}
+
+/// The account, if `password` is its password
+pub async fn log_in(state: &ServerState, username: &str, password: String) -> Result<Option<User>> {
+    let Some(user) = state.repository.get_user_by_name(username).await? else {
+        return Ok(None);
+    };
+    // Argon2 is deliberately slow; keep it off the async workers
+    let hash = user.password_hash.clone();
+    let valid = tokio::task::spawn_blocking(move || verify_password(&password, &hash))
+        .await
+        .unwrap_or(false);
+    Ok(valid.then_some(user))
+}
+
+/// Middleware: reject requests without valid Basic or Bearer credentials,
+/// and writes with a read-only token; otherwise expose the account to
+/// handlers as `Extension<CurrentUser>`.
+pub async fn require_user(State(state): State<ServerState>, mut request: Request, next: Next) -> Response {
+    let credentials = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(parse_authorization);
+
+    let user = match credentials {
+        Some(credentials) => match authenticate(&state, credentials).await {
+            Ok(Some(user)) => Some(user),
+            Ok(None) => {
+                let client = request.extensions().get::<ClientAddr>().map(|c| c.0);
+                tracing::warn!("Rejected credentials from {:?}", client);
+                None
+            }
+            Err(e) => {
+                tracing::error!("Authentication lookup failed: {}", e);
+                return (StatusCode::INTERNAL_SERVER_ERROR, "Internal error").into_response();
+            }
+        },
+        None => None,
+    };
+
+    match user {
+        Some((_, TokenScope::Read)) if is_write(request.method(), request.uri().path()) => {
+            (StatusCode::FORBIDDEN, "This token is read-only").into_response()
+        }
+        Some((user, _)) => {
+            request.extensions_mut().insert(CurrentUser::from(user));
+            next.run(request).await
+        }
+        None => {
+            let mut response = (StatusCode::UNAUTHORIZED, "Authentication required").into_response();
+            // Lets browsers prompt for a login when opening the web UI
+            response.headers_mut().insert(
+                header::WWW_AUTHENTICATE,
+                HeaderValue::from_static("Basic realm=\"BeatCheck\""),
+            );
+            response
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_password_round_trip() {
+        let hash = hash_password("hunter2").unwrap();
+        assert!(verify_password("hunter2", &hash));
+        assert!(!verify_password("hunter3", &hash));
+        assert!(!verify_password("hunter2", "not a hash"));
+    }
+
+    #[test]
+    fn test_tokens_are_random_and_hash_stably() {
+        let token = generate_token();
+        assert_eq!(token.len(), 64);
+        assert_ne!(token, generate_token());
+        assert_eq!(hash_token(&token), hash_token(&token));
+        assert_ne!(hash_token(&token), token);
+    }
+
+    #[test]
+    fn test_parse_authorization() {
+        // "alice:pa:ss" - the password may itself contain colons
+        assert_eq!(
+            parse_authorization("Basic YWxpY2U6cGE6c3M="),
+            Some(Credentials::Basic {
+                username: "alice".to_string(),
+                password: "pa:ss".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_authorization("bearer abc123"),
+            Some(Credentials::Bearer("abc123".to_string()))
+        );
+        assert_eq!(parse_authorization("Basic !!!"), None);
+        assert_eq!(parse_authorization("Digest foo"), None);
+    }
+
+    #[test]
+    fn test_is_write() {
+        assert!(!is_write(&Method::GET, "/api/articles"));
+        assert!(!is_write(&Method::HEAD, "/"));
+        assert!(!is_write(&Method::POST, "/api/graphql"));
+        assert!(is_write(&Method::POST, "/api/articles/1/read"));
+        assert!(is_write(&Method::DELETE, "/api/feeds/2"));
+        assert!(is_write(&Method::PATCH, "/api/entries/3.json"));
+    }
+}
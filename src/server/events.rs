@@ -0,0 +1,111 @@
+//! Ingest events pushed to clients over Server-Sent Events, so the web UI
+//! (and anything else listening on `/api/events`) updates as soon as a
+//! refresh stores articles instead of polling. Only this process's
+//! refreshes and subscriptions are announced, not a TUI's running beside it.
+
+use std::convert::Infallible;
+
+use axum::extract::State;
+use axum::response::sse::{self, KeepAlive, Sse};
+use axum::Extension;
+use chrono::{DateTime, Utc};
+use futures::stream::{self, Stream};
+use serde::Serialize;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::db::Repository;
+
+use super::api::MaybeUser;
+use super::ServerState;
+
+/// Events a slow client may fall behind by before it's told to reload
+const CHANNEL_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Event {
+    /// A feed got new articles
+    Articles { feed_id: i64, count: usize },
+    /// A refresh finished, so unread counts and lists may have changed.
+    /// Also sent to a client that fell behind and missed events.
+    Refreshed,
+}
+
+impl Event {
+    /// The SSE event name, for `EventSource.addEventListener`
+    fn name(&self) -> &'static str {
+        match self {
+            Event::Articles { .. } => "articles",
+            Event::Refreshed => "refreshed",
+        }
+    }
+}
+
+pub fn channel() -> broadcast::Sender<Event> {
+    broadcast::channel(CHANNEL_CAPACITY).0
+}
+
+/// Announce the articles each feed got since `since`, then that the refresh
+/// is done. Sending with no one listening isn't an error.
+pub async fn publish_refresh(repository: &Repository, events: &broadcast::Sender<Event>, since: DateTime<Utc>) {
+    match repository.count_new_articles_by_feed(since).await {
+        Ok(counts) => {
+            for (feed_id, count) in counts {
+                let _ = events.send(Event::Articles { feed_id, count });
+            }
+        }
+        Err(e) => tracing::warn!("Failed to count new articles: {}", e),
+    }
+    let _ = events.send(Event::Refreshed);
+}
+
+/// `GET /api/events`: the event stream, limited to the caller's feeds. It
+/// ends when the server shuts down, so open streams don't hold that up.
+pub async fn stream(
+    State(state): State<ServerState>,
+    user: MaybeUser,
+) -> Sse<impl Stream<Item = Result<sse::Event, Infallible>>> {
+    let user_id = user.map(|Extension(user)| user.id);
+    let receiver = state.events.subscribe();
+    let events = stream::unfold((receiver, state), move |(mut receiver, mut state)| async move {
+        loop {
+            let event = tokio::select! {
+                received = receiver.recv() => match received {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(_)) => Event::Refreshed,
+                    Err(RecvError::Closed) => return None,
+                },
+                _ = state.shutdown.wait_for(|&stopping| stopping) => return None,
+            };
+            if let (Event::Articles { feed_id, .. }, Some(user_id)) = (&event, user_id) {
+                match state.repository.get_user_feeds(user_id).await {
+                    Ok(feeds) if feeds.iter().any(|f| f.id == *feed_id) => {}
+                    Ok(_) => continue,
+                    Err(e) => {
+                        tracing::warn!("Failed to read subscriptions for events: {}", e);
+                        continue;
+                    }
+                }
+            }
+            let message = sse::Event::default().event(event.name()).json_data(&event).unwrap_or_default();
+            return Some((Ok(message), (receiver, state)));
+        }
+    });
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_json() {
+        let articles = Event::Articles { feed_id: 3, count: 2 };
+        assert_eq!(articles.name(), "articles");
+        assert_eq!(
+            serde_json::to_value(&articles).unwrap(),
+            serde_json::json!({"type": "articles", "feed_id": 3, "count": 2})
+        );
+        assert_eq!(serde_json::to_value(Event::Refreshed).unwrap(), serde_json::json!({"type": "refreshed"}));
+    }
+}
@@ -0,0 +1,703 @@
+//! A read-only GraphQL endpoint over the repository, for dashboards that want
+//! articles filtered several ways with their feeds nested in one request.
+//! It understands the query subset such clients send: one `query` operation
+//! with variables, arguments, aliases and nested selections; no fragments,
+//! directives, mutations or introspection.
+//!
+//! ```graphql
+//! query ($tag: String) {
+//!   articles(tag: $tag, feedId: 3, since: "2026-03-01", first: 20) {
+//!     id title url publishedAt
+//!     feed { title siteUrl }
+//!   }
+//! }
+//! ```
+
+use std::collections::HashMap;
+
+use axum::extract::{Query, State};
+use axum::{Extension, Json};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::db::{self, ArticleFilter};
+use crate::error::AppError;
+use crate::models::Feed;
+
+use super::api::{find_article, ApiError, ApiResult, MaybeUser, DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE};
+use super::ServerState;
+
+/// A POST body, as GraphQL clients send it
+#[derive(Debug, Deserialize)]
+pub struct GraphqlRequest {
+    query: String,
+    #[serde(default)]
+    variables: Option<Map<String, Value>>,
+}
+
+/// A GET's parameters: `variables` is JSON text
+#[derive(Debug, Deserialize)]
+pub struct GraphqlParams {
+    query: String,
+    #[serde(default)]
+    variables: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GraphqlResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<Value>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    errors: Vec<GraphqlError>,
+}
+
+#[derive(Debug, Serialize)]
+struct GraphqlError {
+    message: String,
+}
+
+/// A selected field: `alias: name(arguments) { selection }`
+#[derive(Debug, Clone, PartialEq)]
+struct Field {
+    alias: Option<String>,
+    name: String,
+    arguments: Map<String, Value>,
+    selection: Vec<Field>,
+}
+
+impl Field {
+    /// The key it answers under
+    fn key(&self) -> &str {
+        self.alias.as_deref().unwrap_or(&self.name)
+    }
+
+    fn selects(&self, name: &str) -> bool {
+        self.selection.iter().any(|f| f.name == name)
+    }
+}
+
+pub async fn get(
+    State(state): State<ServerState>,
+    user: MaybeUser,
+    Query(params): Query<GraphqlParams>,
+) -> ApiResult<Json<GraphqlResponse>> {
+    let variables = match params.variables.as_deref().filter(|v| !v.trim().is_empty()) {
+        Some(json) => match serde_json::from_str(json) {
+            Ok(variables) => variables,
+            Err(e) => return Ok(failure(format!("Bad variables: {}", e))),
+        },
+        None => Map::new(),
+    };
+    execute(&state, &user, &params.query, variables).await
+}
+
+pub async fn post(
+    State(state): State<ServerState>,
+    user: MaybeUser,
+    Json(request): Json<GraphqlRequest>,
+) -> ApiResult<Json<GraphqlResponse>> {
+    execute(&state, &user, &request.query, request.variables.unwrap_or_default()).await
+}
+
+fn failure(message: String) -> Json<GraphqlResponse> {
+    Json(GraphqlResponse {
+        data: None,
+        errors: vec![GraphqlError { message }],
+    })
+}
+
+/// Answer a query. Mistakes in it are reported GraphQL-style, in `errors`;
+/// only server failures are HTTP errors.
+async fn execute(
+    state: &ServerState,
+    user: &MaybeUser,
+    query: &str,
+    variables: Map<String, Value>,
+) -> ApiResult<Json<GraphqlResponse>> {
+    let fields = match parse(query, variables) {
+        Ok(fields) => fields,
+        Err(message) => return Ok(failure(message)),
+    };
+    let mut data = Map::new();
+    for field in &fields {
+        let value = match resolve(state, user, field).await {
+            Ok(value) => value,
+            Err(ApiError::NotFound) => Value::Null,
+            Err(ApiError::BadRequest(message)) => return Ok(failure(message)),
+            Err(e) => return Err(e),
+        };
+        match project(&value, field) {
+            Ok(value) => data.insert(field.key().to_string(), value),
+            Err(message) => return Ok(failure(message)),
+        };
+    }
+    Ok(Json(GraphqlResponse {
+        data: Some(Value::Object(data)),
+        errors: Vec::new(),
+    }))
+}
+
+// ==================== Resolving ====================
+
+const ARTICLES_ARGUMENTS: &[&str] = &[
+    "feedId", "feedIds", "tag", "entity", "author", "search", "since", "until", "starred", "unread", "first", "after",
+];
+
+/// A top-level field's value, whole; `project` then keeps what was asked for
+async fn resolve(state: &ServerState, user: &MaybeUser, field: &Field) -> ApiResult<Value> {
+    let user_id = user.as_ref().map(|Extension(u)| u.id);
+    match field.name.as_str() {
+        "articles" => {
+            check_arguments(field, ARTICLES_ARGUMENTS)?;
+            let query = string_argument(field, "search")?
+                .filter(|q| !q.trim().is_empty())
+                .map(|q| db::Query::parse(&q))
+                .transpose()
+                .map_err(|e| ApiError::BadRequest(format!("Bad search at {}", e)))?;
+            let filter = ArticleFilter {
+                feed_id: int_argument(field, "feedId")?,
+                feed_ids: match field.arguments.get("feedIds") {
+                    None | Some(Value::Null) => None,
+                    Some(Value::Array(ids)) => Some(
+                        ids.iter()
+                            .map(|id| id.as_i64().ok_or_else(|| bad_argument(field, "feedIds", "a list of ids")))
+                            .collect::<ApiResult<_>>()?,
+                    ),
+                    Some(_) => return Err(bad_argument(field, "feedIds", "a list of ids")),
+                },
+                since: date_argument(field, "since")?,
+                until: date_argument(field, "until")?,
+                starred_only: bool_argument(field, "starred")?,
+                unread_only: bool_argument(field, "unread")?,
+                text: None,
+                user_id,
+                tag: string_argument(field, "tag")?.map(|t| t.trim().to_lowercase()),
+                entity: string_argument(field, "entity")?.map(|e| e.trim().to_string()),
+                author: string_argument(field, "author")?.map(|a| a.trim().to_string()),
+                unsummarized: false,
+                query,
+            };
+            let limit = int_argument(field, "first")?.map_or(DEFAULT_PAGE_SIZE, |n| n.clamp(1, MAX_PAGE_SIZE as i64) as usize);
+            let articles = state
+                .repository
+                .list_articles(&filter, limit, int_argument(field, "after")?)
+                .await
+                .map_err(|e| match e {
+                    // A regex search that ran out of time
+                    AppError::Config(message) => ApiError::BadRequest(message),
+                    e => e.into(),
+                })?;
+
+            let feeds = if field.selects("feed") { feed_values(state.repository.get_all_feeds().await?) } else { HashMap::new() };
+            let mut values = Vec::with_capacity(articles.len());
+            for mut article in articles {
+                state.images.apply(&mut article);
+                let summary = if field.selects("summary") { state.repository.get_summary(article.id).await? } else { None };
+                let mut value = serde_json::to_value(&article).map_err(AppError::from)?;
+                value["feed"] = feeds.get(&article.feed_id).cloned().unwrap_or(Value::Null);
+                value["summary"] = serde_json::to_value(summary).map_err(AppError::from)?;
+                values.push(value);
+            }
+            Ok(Value::Array(values))
+        }
+        "article" => {
+            check_arguments(field, &["id"])?;
+            let id = int_argument(field, "id")?.ok_or_else(|| bad_argument(field, "id", "an id"))?;
+            let mut article = find_article(state, user, id).await?;
+            state.images.apply(&mut article);
+            let mut value = serde_json::to_value(&article).map_err(AppError::from)?;
+            if field.selects("feed") {
+                let feeds = feed_values(state.repository.get_all_feeds().await?);
+                value["feed"] = feeds.get(&article.feed_id).cloned().unwrap_or(Value::Null);
+            }
+            value["summary"] = serde_json::to_value(state.repository.get_summary(id).await?).map_err(AppError::from)?;
+            Ok(value)
+        }
+        "feeds" | "feed" => {
+            let feeds = match user_id {
+                Some(user_id) => state.repository.get_user_feeds(user_id).await?,
+                None => state.repository.get_all_feeds().await?,
+            };
+            if field.name == "feed" {
+                check_arguments(field, &["id"])?;
+                let id = int_argument(field, "id")?.ok_or_else(|| bad_argument(field, "id", "an id"))?;
+                let feed = feeds.into_iter().find(|f| f.id == id).ok_or(ApiError::NotFound)?;
+                return Ok(serde_json::to_value(feed).map_err(AppError::from)?);
+            }
+            check_arguments(field, &["folder", "tag"])?;
+            let folder = string_argument(field, "folder")?;
+            let tag = string_argument(field, "tag")?;
+            let feeds: Vec<Feed> = feeds
+                .into_iter()
+                .filter(|f| folder.as_ref().is_none_or(|folder| f.folder.as_ref() == Some(folder)))
+                .filter(|f| tag.as_ref().is_none_or(|tag| f.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))))
+                .collect();
+            Ok(serde_json::to_value(feeds).map_err(AppError::from)?)
+        }
+        "tags" => {
+            check_arguments(field, &["first"])?;
+            let limit = int_argument(field, "first")?.map_or(MAX_PAGE_SIZE, |n| n.clamp(1, MAX_PAGE_SIZE as i64) as usize);
+            let tags: Vec<Value> = state
+                .repository
+                .tag_counts(user_id, limit)
+                .await?
+                .into_iter()
+                .map(|(tag, articles)| serde_json::json!({ "tag": tag, "articles": articles }))
+                .collect();
+            Ok(Value::Array(tags))
+        }
+        "__typename" => Ok(Value::from("Query")),
+        other => Err(ApiError::BadRequest(format!("Unknown field `{}` on Query", other))),
+    }
+}
+
+/// Feeds as they nest under articles, by id
+fn feed_values(feeds: Vec<Feed>) -> HashMap<i64, Value> {
+    feeds
+        .into_iter()
+        .filter_map(|feed| Some((feed.id, serde_json::to_value(&feed).ok()?)))
+        .collect()
+}
+
+fn check_arguments(field: &Field, known: &[&str]) -> ApiResult<()> {
+    match field.arguments.keys().find(|name| !known.contains(&name.as_str())) {
+        Some(name) => Err(ApiError::BadRequest(format!("Unknown argument `{}` on `{}`", name, field.name))),
+        None => Ok(()),
+    }
+}
+
+fn bad_argument(field: &Field, name: &str, wanted: &str) -> ApiError {
+    ApiError::BadRequest(format!("`{}` on `{}` must be {}", name, field.name, wanted))
+}
+
+fn string_argument(field: &Field, name: &str) -> ApiResult<Option<String>> {
+    match field.arguments.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(s)) => Ok(Some(s.clone())),
+        Some(_) => Err(bad_argument(field, name, "a string")),
+    }
+}
+
+fn int_argument(field: &Field, name: &str) -> ApiResult<Option<i64>> {
+    match field.arguments.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        // IDs may come as strings
+        Some(Value::String(s)) => s.parse().map(Some).map_err(|_| bad_argument(field, name, "a number")),
+        Some(value) => value.as_i64().map(Some).ok_or_else(|| bad_argument(field, name, "a number")),
+    }
+}
+
+fn bool_argument(field: &Field, name: &str) -> ApiResult<bool> {
+    match field.arguments.get(name) {
+        None | Some(Value::Null) => Ok(false),
+        Some(Value::Bool(b)) => Ok(*b),
+        Some(_) => Err(bad_argument(field, name, "true or false")),
+    }
+}
+
+/// An RFC 3339 time or a date (midnight UTC)
+fn date_argument(field: &Field, name: &str) -> ApiResult<Option<DateTime<Utc>>> {
+    let Some(date) = string_argument(field, name)? else {
+        return Ok(None);
+    };
+    DateTime::parse_from_rfc3339(&date)
+        .map(|d| d.with_timezone(&Utc))
+        .ok()
+        .or_else(|| NaiveDate::parse_from_str(&date, "%Y-%m-%d").ok()?.and_hms_opt(0, 0, 0).map(|d| d.and_utc()))
+        .map(Some)
+        .ok_or_else(|| bad_argument(field, name, "a date (2026-03-01) or RFC 3339 time"))
+}
+
+/// Keep the fields `field` selects from `value`, in the order asked, under
+/// their aliases. GraphQL names are camelCase; the stored ones snake_case.
+fn project(value: &Value, field: &Field) -> Result<Value, String> {
+    match value {
+        Value::Array(items) => items.iter().map(|item| project(item, field)).collect::<Result<_, _>>().map(Value::Array),
+        Value::Object(object) => {
+            if field.selection.is_empty() {
+                return Err(format!("`{}` needs a selection of subfields", field.name));
+            }
+            let mut projected = Map::new();
+            for selected in &field.selection {
+                if !selected.arguments.is_empty() {
+                    return Err(format!("`{}` takes no arguments", selected.name));
+                }
+                let value = object
+                    .get(&snake_case(&selected.name))
+                    .ok_or_else(|| format!("Unknown field `{}` on `{}`", selected.name, field.name))?;
+                projected.insert(selected.key().to_string(), project(value, selected)?);
+            }
+            Ok(Value::Object(projected))
+        }
+        Value::Null => Ok(Value::Null),
+        _ if !field.selection.is_empty() => Err(format!("`{}` has no subfields", field.name)),
+        scalar => Ok(scalar.clone()),
+    }
+}
+
+fn snake_case(name: &str) -> String {
+    let mut snake = String::with_capacity(name.len() + 4);
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            snake.push('_');
+            snake.push(c.to_ascii_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+// ==================== Parsing ====================
+
+/// Deepest nesting of selections, list types and argument values; far
+/// past what dashboards send, and well short of the stack running out
+const MAX_DEPTH: usize = 32;
+
+/// The top-level fields of a query, variables substituted. Errors say what
+/// was wrong and at which byte.
+fn parse(query: &str, variables: Map<String, Value>) -> Result<Vec<Field>, String> {
+    let mut parser = Parser {
+        source: query,
+        pos: 0,
+        variables,
+    };
+    parser.document()
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    pos: usize,
+    variables: Map<String, Value>,
+}
+
+impl Parser<'_> {
+    fn document(&mut self) -> Result<Vec<Field>, String> {
+        self.skip_ignored();
+        if self.peek() != Some('{') {
+            match self.name()?.as_str() {
+                "query" => {}
+                "mutation" | "subscription" => return Err("Only queries are supported".to_string()),
+                other => return Err(format!("Expected a query, found `{}`", other)),
+            }
+            self.skip_ignored();
+            // The operation's name
+            if self.peek().is_some_and(|c| c == '_' || c.is_ascii_alphabetic()) {
+                self.name()?;
+            }
+            if self.eat('(') {
+                self.variable_definitions()?;
+            }
+        }
+        let fields = self.selection_set(0)?;
+        self.skip_ignored();
+        if self.pos < self.source.len() {
+            return Err(self.error("Only one operation per request is supported"));
+        }
+        Ok(fields)
+    }
+
+    /// `($name: Type = default ...)`, after the `(`: types aren't checked,
+    /// but defaults fill in variables that weren't given
+    fn variable_definitions(&mut self) -> Result<(), String> {
+        while !self.eat(')') {
+            self.expect('$')?;
+            let name = self.name()?;
+            self.expect(':')?;
+            self.variable_type(0)?;
+            if self.eat('=') {
+                let default = self.value(0)?;
+                self.variables.entry(name).or_insert(default);
+            }
+        }
+        Ok(())
+    }
+
+    fn variable_type(&mut self, depth: usize) -> Result<(), String> {
+        self.check_depth(depth)?;
+        if self.eat('[') {
+            self.variable_type(depth + 1)?;
+            self.expect(']')?;
+        } else {
+            self.name()?;
+        }
+        self.eat('!');
+        Ok(())
+    }
+
+    fn selection_set(&mut self, depth: usize) -> Result<Vec<Field>, String> {
+        self.check_depth(depth)?;
+        self.expect('{')?;
+        let mut fields = Vec::new();
+        while !self.eat('}') {
+            if self.source[self.pos..].starts_with("...") {
+                return Err(self.error("Fragments aren't supported"));
+            }
+            if self.peek() == Some('@') {
+                return Err(self.error("Directives aren't supported"));
+            }
+            fields.push(self.field(depth)?);
+        }
+        if fields.is_empty() {
+            return Err(self.error("Empty selection"));
+        }
+        Ok(fields)
+    }
+
+    fn field(&mut self, depth: usize) -> Result<Field, String> {
+        let mut name = self.name()?;
+        let mut alias = None;
+        if self.eat(':') {
+            alias = Some(name);
+            name = self.name()?;
+        }
+        let mut arguments = Map::new();
+        if self.eat('(') {
+            while !self.eat(')') {
+                let argument = self.name()?;
+                self.expect(':')?;
+                let value = self.value(depth + 1)?;
+                arguments.insert(argument, value);
+            }
+        }
+        self.skip_ignored();
+        let selection = if self.peek() == Some('{') { self.selection_set(depth + 1)? } else { Vec::new() };
+        Ok(Field {
+            alias,
+            name,
+            arguments,
+            selection,
+        })
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Value, String> {
+        self.check_depth(depth)?;
+        self.skip_ignored();
+        match self.peek() {
+            Some('$') => {
+                self.pos += 1;
+                let name = self.name()?;
+                Ok(self.variables.get(&name).cloned().unwrap_or(Value::Null))
+            }
+            Some('"') => self.string().map(Value::String),
+            Some('[') => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                while !self.eat(']') {
+                    items.push(self.value(depth + 1)?);
+                }
+                Ok(Value::Array(items))
+            }
+            Some('{') => {
+                self.pos += 1;
+                let mut object = Map::new();
+                while !self.eat('}') {
+                    let key = self.name()?;
+                    self.expect(':')?;
+                    object.insert(key, self.value(depth + 1)?);
+                }
+                Ok(Value::Object(object))
+            }
+            Some(c) if c == '-' || c.is_ascii_digit() => {
+                let start = self.pos;
+                let rest = &self.source[start..];
+                let len = rest
+                    .char_indices()
+                    .find(|&(i, c)| !(c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+') || (c == '-' && i == 0)))
+                    .map_or(rest.len(), |(i, _)| i);
+                self.pos += len;
+                let number = &rest[..len];
+                number
+                    .parse::<i64>()
+                    .map(Value::from)
+                    .or_else(|_| number.parse::<f64>().map(Value::from))
+                    .map_err(|_| format!("Bad number `{}` at {}", number, start))
+            }
+            // true, false, null or an enum value, which is passed on as its name
+            Some(_) => Ok(match self.name()?.as_str() {
+                "true" => Value::Bool(true),
+                "false" => Value::Bool(false),
+                "null" => Value::Null,
+                name => Value::String(name.to_string()),
+            }),
+            None => Err(self.error("Expected a value")),
+        }
+    }
+
+    /// A quoted string, after skipping to its `"`
+    fn string(&mut self) -> Result<String, String> {
+        let start = self.pos;
+        self.pos += 1;
+        let mut text = String::new();
+        let mut chars = self.source[self.pos..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += i + 1;
+                    return Ok(text);
+                }
+                '\\' => match chars.next().map(|(_, c)| c) {
+                    Some('n') => text.push('\n'),
+                    Some('t') => text.push('\t'),
+                    Some('r') => text.push('\r'),
+                    Some('b') => text.push('\u{8}'),
+                    Some('f') => text.push('\u{c}'),
+                    Some('u') => {
+                        let hex: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+                        let c = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32);
+                        text.push(c.ok_or_else(|| format!("Bad escape \\u{} in string at {}", hex, start))?);
+                    }
+                    Some(c @ ('"' | '\\' | '/')) => text.push(c),
+                    _ => return Err(format!("Bad escape in string at {}", start)),
+                },
+                '\n' => break,
+                c => text.push(c),
+            }
+        }
+        Err(format!("Unterminated string at {}", start))
+    }
+
+    fn name(&mut self) -> Result<String, String> {
+        self.skip_ignored();
+        let rest = &self.source[self.pos..];
+        let len = rest
+            .char_indices()
+            .find(|&(i, c)| !(c == '_' || c.is_ascii_alphabetic() || (i > 0 && c.is_ascii_digit())))
+            .map_or(rest.len(), |(i, _)| i);
+        if len == 0 {
+            return Err(self.error("Expected a name"));
+        }
+        self.pos += len;
+        Ok(rest[..len].to_string())
+    }
+
+    /// Whitespace, commas (insignificant in GraphQL) and `#` comments
+    fn skip_ignored(&mut self) {
+        loop {
+            let rest = &self.source[self.pos..];
+            let trimmed = rest.trim_start_matches(|c: char| c.is_whitespace() || c == ',' || c == '\u{feff}');
+            self.pos += rest.len() - trimmed.len();
+            if !trimmed.starts_with('#') {
+                return;
+            }
+            self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_ignored();
+        self.source[self.pos..].chars().next()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("Expected `{}`", c)))
+        }
+    }
+
+    fn check_depth(&self, depth: usize) -> Result<(), String> {
+        if depth > MAX_DEPTH {
+            return Err(self.error(&format!("Nested more than {} levels deep", MAX_DEPTH)));
+        }
+        Ok(())
+    }
+
+    fn error(&self, message: &str) -> String {
+        format!("{} at {}", message, self.pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variables(json: Value) -> Map<String, Value> {
+        json.as_object().cloned().unwrap()
+    }
+
+    #[test]
+    fn test_parse_query_with_variables_and_aliases() {
+        let query = r#"
+            # Dashboard: recent AI coverage
+            query Dashboard($tag: String!, $first: Int = 20, $feeds: [ID!]) {
+              recent: articles(tag: $tag, first: $first, feedIds: $feeds, since: "2026-03-01", unread: true) {
+                id, title
+                feed { title siteUrl }
+              }
+              feeds { id }
+            }
+        "#;
+        let fields = parse(query, variables(serde_json::json!({"tag": "ai", "feeds": [1, 2]}))).unwrap();
+        assert_eq!(fields.len(), 2);
+        let articles = &fields[0];
+        assert_eq!((articles.key(), articles.name.as_str()), ("recent", "articles"));
+        assert_eq!(articles.arguments["tag"], "ai");
+        assert_eq!(articles.arguments["first"], 20);
+        assert_eq!(articles.arguments["feedIds"], serde_json::json!([1, 2]));
+        assert_eq!(articles.arguments["unread"], true);
+        let names: Vec<_> = articles.selection.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["id", "title", "feed"]);
+        assert_eq!(articles.selection[2].selection.len(), 2);
+
+        let shorthand = parse(r#"{ article(id: "7") { title } }"#, Map::new()).unwrap();
+        assert_eq!(shorthand[0].arguments["id"], "7");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |query: &str| parse(query, Map::new()).unwrap_err();
+        assert_eq!(error("mutation { star(id: 1) }"), "Only queries are supported");
+        assert_eq!(error("{ articles { id }"), "Expected a name at 17");
+        assert!(error("{ ...Parts }").starts_with("Fragments"));
+        assert!(error(r#"{ articles(tag: "ai) { id } }"#).starts_with("Unterminated string"));
+        assert!(error("{ feeds { id } } { tags { tag } }").starts_with("Only one operation"));
+    }
+
+    #[tokio::test]
+    async fn test_deeply_nested_query_is_refused() {
+        let (state, _dir) = super::super::test_state(false).await;
+        let query = format!("{}{}", "{a".repeat(100_000), "}".repeat(100_000));
+        let request = GraphqlRequest { query, variables: None };
+        let Json(response) = post(State(state.clone()), None, Json(request)).await.unwrap();
+        assert_eq!(response.errors[0].message, format!("Nested more than {} levels deep at 66", MAX_DEPTH));
+
+        let arguments = format!("{{ articles(tag: {}1{}) {{ id }} }}", "[".repeat(100_000), "]".repeat(100_000));
+        assert!(parse(&arguments, Map::new()).unwrap_err().starts_with("Nested more than"));
+        let types = format!("query ($t: {}ID{}) {{ feeds {{ id }} }}", "[".repeat(1000), "]".repeat(1000));
+        assert!(parse(&types, Map::new()).unwrap_err().starts_with("Nested more than"));
+        // Just inside the limit still parses
+        let deep = format!("{}{}", "{a".repeat(MAX_DEPTH + 1), "}".repeat(MAX_DEPTH + 1));
+        assert!(parse(&deep, Map::new()).is_ok());
+    }
+
+    #[test]
+    fn test_project_keeps_selected_fields_under_aliases() {
+        let fields = parse("{ articles { id headline: title feed { siteUrl } } }", Map::new()).unwrap();
+        let value = serde_json::json!([{
+            "id": 1, "title": "Chips", "content": "<p>long</p>",
+            "feed": {"id": 3, "site_url": "https://example.com"}
+        }]);
+        assert_eq!(
+            project(&value, &fields[0]).unwrap(),
+            serde_json::json!([{"id": 1, "headline": "Chips", "feed": {"siteUrl": "https://example.com"}}])
+        );
+
+        let unknown = parse("{ articles { body } }", Map::new()).unwrap();
+        assert_eq!(project(&value, &unknown[0]).unwrap_err(), "Unknown field `body` on `articles`");
+        let bare = parse("{ articles { feed } }", Map::new()).unwrap();
+        assert!(project(&value, &bare[0]).unwrap_err().contains("needs a selection"));
+        assert_eq!(project(&Value::Null, &bare[0]).unwrap(), Value::Null);
+    }
+}
@@ -0,0 +1,278 @@
+//! Remote images in article content (`[server] images`). Newsletters are
+//! full of tracking pixels, so images can be removed, or fetched through
+//! `/api/image` and the HTTP cache so the sites serving them see this
+//! server rather than the reader, and only the first time.
+
+use std::time::Duration;
+
+use axum::extract::{Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use regex::{Captures, Regex};
+use reqwest::Client;
+use serde::Deserialize;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use url::Url;
+
+use crate::config::{Config, RemoteImages};
+use crate::db::Repository;
+use crate::error::Result;
+use crate::models::Article;
+use crate::services::HttpCache;
+
+use super::api::{ApiError, ApiResult};
+use super::ServerState;
+
+/// Where the key signing proxied image URLs is kept, so links in content
+/// a client already has keep working across restarts
+const KEY_SETTING: &str = "image_proxy_key";
+
+pub struct ImagePolicy {
+    mode: RemoteImages,
+    /// `server.base_path`, for the proxy's URLs
+    base_path: String,
+    /// Signs proxy URLs, so the proxy only fetches images the server linked to
+    key: Vec<u8>,
+    client: Client,
+    cache: HttpCache,
+}
+
+impl ImagePolicy {
+    pub async fn new(config: &Config, repository: &Repository) -> Result<Self> {
+        let mode = config.server.images;
+        let key = match mode {
+            RemoteImages::Proxy => match repository.get_setting(KEY_SETTING).await? {
+                Some(key) => key,
+                None => {
+                    let key = super::auth::generate_token();
+                    repository.set_setting(KEY_SETTING, key.clone()).await?;
+                    key
+                }
+            },
+            _ => String::new(),
+        };
+        // No cookies and no referrer: the image's server learns nothing but
+        // that this server wanted it
+        let builder = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .user_agent("beatcheck/1.2.0")
+            .referer(false);
+        Ok(Self {
+            mode,
+            base_path: config.server.normalized_base_path(),
+            key: key.into_bytes(),
+            client: super::restrict_requests(builder, config.local_only).build()?,
+            cache: HttpCache::new(&config.http_cache),
+        })
+    }
+
+    /// Rewrite the article's content for serving
+    pub fn apply(&self, article: &mut Article) {
+        if let Some(content) = &article.content {
+            article.content = Some(self.rewrite(content, &article.url));
+        }
+    }
+
+    /// `html` with its remote images removed or pointed at the proxy.
+    /// Relative sources are resolved against `page_url`; `data:` images
+    /// stay as they are.
+    pub fn rewrite(&self, html: &str, page_url: &str) -> String {
+        match self.mode {
+            RemoteImages::Load => html.to_string(),
+            RemoteImages::Strip => strip_images(html),
+            RemoteImages::Proxy => {
+                let base = Url::parse(page_url).ok();
+                proxy_images(html, |src| {
+                    let url = base.as_ref().and_then(|b| b.join(src).ok()).or_else(|| Url::parse(src).ok())?;
+                    matches!(url.scheme(), "http" | "https").then(|| self.proxy_url(url.as_str()))
+                })
+            }
+        }
+    }
+
+    fn proxy_url(&self, url: &str) -> String {
+        format!("{}/api/image?url={}&sig={}", self.base_path, urlencoding::encode(url), self.sign(url))
+    }
+
+    /// HMAC-SHA256 of `url`, shortened to 128 bits
+    fn sign(&self, url: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC takes keys of any length");
+        mac.update(url.as_bytes());
+        mac.finalize().into_bytes()[..16].iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    fn verify(&self, url: &str, sig: &str) -> bool {
+        let expected = self.sign(url);
+        // Compared in constant time, so the signature can't be guessed a
+        // character at a time
+        expected.len() == sig.len() && expected.bytes().zip(sig.bytes()).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+    }
+}
+
+fn image_source_regex() -> Regex {
+    Regex::new(r#"(?i)(<(?:img\b[^>]*?\ssrc|video\b[^>]*?\sposter)\s*=\s*)("([^"]*)"|'([^']*)'|([^\s"'>]+))"#).unwrap()
+}
+
+/// Responsive sources, backgrounds and CSS `url()`s: the places images load
+/// from besides `<img src>` and `<video poster>`, dropped in both modes
+fn remove_other_sources(html: &str) -> String {
+    let sources = Regex::new(r"(?is)<source\b[^>]*\ssrcset\s*=[^>]*>").unwrap();
+    let attributes = Regex::new(r#"(?i)\s(srcset|sizes|background)\s*=\s*("[^"]*"|'[^']*'|[^\s>]+)"#).unwrap();
+    let css = Regex::new(r#"(?i)url\(\s*(&quot;|&#39;|["'])?\s*(https?:)?//[^)]*\)"#).unwrap();
+
+    let html = sources.replace_all(html, "");
+    let html = attributes.replace_all(&html, "");
+    css.replace_all(&html, "none").into_owned()
+}
+
+fn source_value(cap: &Captures) -> String {
+    cap.get(3).or(cap.get(4)).or(cap.get(5)).map_or("", |m| m.as_str()).trim().replace("&amp;", "&")
+}
+
+fn strip_images(html: &str) -> String {
+    let html = remove_other_sources(html);
+    let images = Regex::new(r"(?is)<img\b[^>]*>").unwrap();
+    let html = images.replace_all(&html, |cap: &Captures| {
+        let inline = image_source_regex()
+            .captures(&cap[0])
+            .is_some_and(|src| source_value(&src).starts_with("data:"));
+        if inline {
+            cap[0].to_string()
+        } else {
+            String::new()
+        }
+    });
+    let posters = Regex::new(r#"(?i)\sposter\s*=\s*("[^"]*"|'[^']*'|[^\s>]+)"#).unwrap();
+    posters.replace_all(&html, "").into_owned()
+}
+
+/// Point each image at `proxy(src)`, or drop its source when there's no
+/// proxy URL for it
+fn proxy_images(html: &str, proxy: impl Fn(&str) -> Option<String>) -> String {
+    let html = remove_other_sources(html);
+    image_source_regex()
+        .replace_all(&html, |cap: &Captures| {
+            let src = source_value(cap);
+            if src.starts_with("data:") {
+                return cap[0].to_string();
+            }
+            let url = proxy(&src).unwrap_or_default();
+            format!("{}\"{}\"", &cap[1], url.replace('&', "&amp;").replace('"', "&quot;"))
+        })
+        .into_owned()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImageParams {
+    url: String,
+    sig: String,
+}
+
+/// `GET /api/image`: an image the server linked to from article content.
+/// Needs no account, since image requests from the web UI's sandboxed
+/// article frame and from Wallabag apps don't carry one; the signature
+/// keeps it from being an open proxy.
+pub async fn proxy(State(state): State<ServerState>, Query(params): Query<ImageParams>) -> ApiResult<Response> {
+    let images = &state.images;
+    if images.mode != RemoteImages::Proxy || !images.verify(&params.url, &params.sig) {
+        return Err(ApiError::NotFound);
+    }
+    let response = match images.cache.fetch(&params.url, images.client.get(&params.url)).await {
+        Ok(response) => response,
+        Err(e) => {
+            tracing::debug!("Failed to proxy image {}: {}", params.url, e);
+            return Ok(StatusCode::BAD_GATEWAY.into_response());
+        }
+    };
+    let mime = response.content_type.clone().filter(|mime| mime.starts_with("image/"));
+    let (true, Some(mime)) = (response.is_success(), mime) else {
+        return Ok(StatusCode::BAD_GATEWAY.into_response());
+    };
+    let headers = [
+        (header::CONTENT_TYPE, mime),
+        (header::CACHE_CONTROL, "private, max-age=86400".to_string()),
+        (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+        // SVGs can carry scripts
+        (header::CONTENT_SECURITY_POLICY, "default-src 'none'; style-src 'unsafe-inline'".to_string()),
+    ];
+    Ok((headers, response.body).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(mode: RemoteImages) -> ImagePolicy {
+        ImagePolicy {
+            mode,
+            base_path: "/rss".to_string(),
+            key: b"secret".to_vec(),
+            client: Client::new(),
+            cache: HttpCache::new(&Default::default()),
+        }
+    }
+
+    const HTML: &str = concat!(
+        r#"<p style="background: url('https://t.example/bg.gif')">Hi</p>"#,
+        r#"<img src="https://t.example/pixel.gif?u=1&amp;n=2" width="1">"#,
+        r#"<picture><source srcset="https://cdn.example/a.webp"><img src='/a.png' srcset="/a-2x.png 2x"></picture>"#,
+        r#"<img src="data:image/gif;base64,R0lG"><video poster=https://cdn.example/p.jpg src="v.mp4"></video>"#,
+    );
+
+    #[test]
+    fn test_strip_removes_remote_images_only() {
+        let html = policy(RemoteImages::Strip).rewrite(HTML, "https://news.example/post");
+        assert_eq!(
+            html,
+            concat!(
+                r#"<p style="background: none">Hi</p><picture></picture>"#,
+                r#"<img src="data:image/gif;base64,R0lG"><video src="v.mp4"></video>"#,
+            )
+        );
+        assert_eq!(policy(RemoteImages::Load).rewrite(HTML, "https://news.example/post"), HTML);
+    }
+
+    #[test]
+    fn test_proxy_points_images_at_signed_urls() {
+        let images = policy(RemoteImages::Proxy);
+        let html = images.rewrite(HTML, "https://news.example/post");
+        assert!(!html.contains("t.example/bg.gif") && !html.contains("srcset") && !html.contains("<source"));
+        let pixel = "https://t.example/pixel.gif?u=1&n=2";
+        let expected = format!(
+            "<img src=\"/rss/api/image?url={}&amp;sig={}\" width=\"1\">",
+            urlencoding::encode(pixel),
+            images.sign(pixel)
+        );
+        assert!(html.contains(&expected), "{}", html);
+        // Relative to the article, and posters too
+        assert!(html.contains(&images.sign("https://news.example/a.png")));
+        assert!(html.contains(&format!("poster=\"/rss/api/image?url=https%3A%2F%2Fcdn.example%2Fp.jpg&amp;sig={}\"", images.sign("https://cdn.example/p.jpg"))));
+        assert!(html.contains(r#"<img src="data:image/gif;base64,R0lG">"#));
+
+        // RFC 4231 test case 2
+        let jefe = ImagePolicy { key: b"Jefe".to_vec(), ..policy(RemoteImages::Proxy) };
+        assert_eq!(jefe.sign("what do ya want for nothing?"), "5bdcc146bf60754e6a042426089575c7");
+
+        assert!(images.verify(pixel, &images.sign(pixel)));
+        assert!(!images.verify("https://t.example/other.gif", &images.sign(pixel)));
+        assert!(!images.verify(pixel, ""));
+    }
+
+    #[tokio::test]
+    async fn test_proxy_refuses_private_addresses() {
+        let (mut state, dir) = super::super::test_state(false).await;
+        let mut config = Config::default();
+        config.server.images = RemoteImages::Proxy;
+        config.http_cache.dir = Some(dir.path().join("cache"));
+        state.images = std::sync::Arc::new(ImagePolicy::new(&config, &state.repository).await.unwrap());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/pixel.gif", listener.local_addr().unwrap());
+        let params = ImageParams { sig: state.images.sign(&url), url };
+        let response = proxy(State(state), Query(params)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+        let accepted = tokio::time::timeout(Duration::from_millis(100), listener.accept()).await;
+        assert!(accepted.is_err(), "connected to the loopback address");
+    }
+}
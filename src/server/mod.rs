@@ -0,0 +1,363 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::routing::{delete, get, post};
+use axum::{middleware, Router};
+use axum_server::tls_rustls::RustlsConfig;
+use tokio::sync::{broadcast, watch};
+
+use crate::app::App;
+use crate::config::{Config, RemoteImages};
+use crate::db::Repository;
+use crate::error::{AppError, Result};
+use crate::feed::FeedFetcher;
+use crate::models::{ApiToken, TokenScope};
+use crate::services::{restrict_to_local, restrict_to_public};
+
+use proxy::ProxyRange;
+use ratelimit::RateLimiter;
+
+mod api;
+mod auth;
+mod events;
+mod graphql;
+mod images;
+mod proxy;
+mod ratelimit;
+mod systemd;
+mod wallabag;
+mod web;
+
+/// How long in-flight TLS requests get to finish once shutdown starts
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(10);
+
+/// Shared state handed to every request handler
+#[derive(Clone)]
+pub struct ServerState {
+    pub repository: Arc<Repository>,
+    pub fetcher: Arc<FeedFetcher>,
+    pub db_path: String,
+    /// `server.auth`: requests carry an account
+    pub auth: bool,
+    /// New articles, for `/api/events`
+    pub events: broadcast::Sender<events::Event>,
+    /// Turns true once shutdown starts
+    pub shutdown: watch::Receiver<bool>,
+    /// `server.images`: what to do with remote images in content
+    pub images: Arc<images::ImagePolicy>,
+}
+
+/// Run the daemon: refresh feeds on the configured interval and serve the
+/// JSON API (plus the web UI, if enabled) until the process is stopped.
+pub async fn run(config: &Config, app: App) -> Result<()> {
+    let trusted_proxies: Arc<[ProxyRange]> = config
+        .server
+        .trusted_proxies
+        .iter()
+        .map(|s| ProxyRange::parse(s))
+        .collect::<Result<Vec<_>>>()?
+        .into();
+    let base_path = config.server.normalized_base_path();
+
+    let repository = Arc::new(Repository::new(&config.db_path).await?);
+    if config.server.auth && repository.count_users().await? == 0 {
+        tracing::warn!("Server auth is enabled but no users exist; create one with --add-user");
+    }
+    let images = Arc::new(images::ImagePolicy::new(config, &repository).await?);
+    let (stop, stopped) = watch::channel(false);
+    let state = ServerState {
+        repository,
+        // The same blocklist as the refresh loop's, watched for edits
+        fetcher: Arc::new(
+            FeedFetcher::restricted(|builder| restrict_requests(builder, config.local_only))
+                .with_blocklist(app.blocklist().clone()),
+        ),
+        db_path: config.db_path.clone(),
+        auth: config.server.auth,
+        events: events::channel(),
+        shutdown: stopped.clone(),
+        images,
+    };
+
+    spawn_refresh_loop(app, config.refresh_interval_minutes, state.repository.clone(), state.events.clone());
+    spawn_maintenance_loop(state.repository.clone(), config.maintenance.interval_hours);
+
+    let mut router = Router::new()
+        .route("/metrics", get(api::metrics))
+        .route("/api/me", get(api::current_user))
+        .route("/api/events", get(events::stream))
+        .route("/api/feeds", get(api::list_feeds).post(api::subscribe))
+        .route("/api/feeds/discover", get(api::discover))
+        .route("/api/feeds/{id}", delete(api::unsubscribe))
+        .route("/api/articles", get(api::list_articles))
+        .route("/api/tags", get(api::list_tags))
+        .route("/api/entities", get(api::list_entities))
+        .route("/api/authors", get(api::list_authors))
+        .route("/api/watchlists", get(api::list_watchlists))
+        .route("/api/trends", get(api::list_trends))
+        .route("/api/articles/{id}", get(api::get_article))
+        .route("/api/articles/{id}/read", post(api::set_read))
+        .route("/api/articles/{id}/star", post(api::set_starred));
+
+    if config.server.web_ui {
+        router = router.merge(web::routes());
+    }
+    if config.server.wallabag {
+        router = router.merge(wallabag::routes());
+    }
+    if config.server.graphql {
+        router = router.route("/api/graphql", get(graphql::get).post(graphql::post));
+    }
+    if config.server.auth {
+        router = router.layer(middleware::from_fn_with_state(state.clone(), auth::require_user));
+    }
+    // Signing in can't require being signed in
+    if config.server.wallabag {
+        router = router.merge(wallabag::token_routes());
+    }
+    // Image requests from content carry no credentials; the URLs are signed
+    if config.server.images == RemoteImages::Proxy {
+        router = router.route("/api/image", get(images::proxy));
+    }
+
+    let mut service = mount(router.with_state(state), &base_path);
+    if config.server.rate_limit_per_minute > 0 {
+        let limiter = Arc::new(RateLimiter::new(config.server.rate_limit_per_minute));
+        service = service.layer(middleware::from_fn_with_state(limiter, ratelimit::limit));
+    }
+    // Outermost, so the rate limit sees the real client address
+    let service = service
+        .layer(middleware::from_fn_with_state(trusted_proxies, proxy::resolve_client))
+        .into_make_service_with_connect_info::<SocketAddr>();
+
+    let tls_config = match &config.server.tls {
+        Some(tls) => {
+            // reqwest already links rustls with ring; serve with the same provider
+            let _ = rustls::crypto::ring::default_provider().install_default();
+            Some(RustlsConfig::from_pem_file(&tls.cert, &tls.key).await?)
+        }
+        None => None,
+    };
+
+    // Prefer a socket handed over by systemd to binding `listen` ourselves
+    let listener = match systemd::activated_listener()? {
+        Some(listener) => listener,
+        None => {
+            let listener = std::net::TcpListener::bind(&config.server.listen)?;
+            listener.set_nonblocking(true)?;
+            listener
+        }
+    };
+    let scheme = if tls_config.is_some() { "https" } else { "http" };
+    println!("Serving on {}://{}{}/", scheme, listener.local_addr()?, base_path);
+
+    systemd::notify_ready();
+    systemd::spawn_watchdog();
+    tokio::spawn(async move {
+        systemd::shutdown_signal().await;
+        let _ = stop.send(true);
+    });
+
+    match tls_config {
+        Some(tls_config) => {
+            let handle = axum_server::Handle::new();
+            let shutdown = handle.clone();
+            let mut stopping = stopped.clone();
+            tokio::spawn(async move {
+                let _ = stopping.wait_for(|&stopping| stopping).await;
+                shutdown.graceful_shutdown(Some(SHUTDOWN_GRACE));
+            });
+            axum_server::from_tcp_rustls(listener, tls_config)
+                .handle(handle)
+                .serve(service)
+                .await?;
+        }
+        None => {
+            axum::serve(tokio::net::TcpListener::from_std(listener)?, service)
+                .with_graceful_shutdown(async move {
+                    let _ = stopped.clone().wait_for(|&stopping| stopping).await;
+                })
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Serve `router` under `base_path` (see `proxy::strip_base_path`)
+fn mount(router: Router, base_path: &str) -> Router {
+    if base_path.is_empty() {
+        return router;
+    }
+    Router::new()
+        .fallback_service(router)
+        .layer(middleware::from_fn_with_state(
+            Arc::<str>::from(base_path),
+            proxy::strip_base_path,
+        ))
+}
+
+/// Create a server account, returning its API token (shown only once).
+/// The new account starts out subscribed to every existing feed.
+pub async fn add_user(config: &Config, username: &str, password: &str) -> Result<String> {
+    if username.trim().is_empty() || password.is_empty() {
+        return Err(AppError::Config("Username and password must not be empty".to_string()));
+    }
+
+    let repository = Repository::new(&config.db_path).await?;
+    if repository.get_user_by_name(username).await?.is_some() {
+        return Err(AppError::Config(format!("User already exists: {}", username)));
+    }
+
+    let user_id = repository
+        .create_user(username, &auth::hash_password(password)?)
+        .await?;
+    let token = auth::generate_token();
+    repository
+        .set_user_token_hash(user_id, &auth::hash_token(&token))
+        .await?;
+    Ok(token)
+}
+
+/// Make a named API token for `username` (or the only account, when there
+/// is just one), returning the account's name and the token (shown only once)
+pub async fn create_token(
+    config: &Config,
+    username: Option<&str>,
+    name: Option<&str>,
+    scope: TokenScope,
+) -> Result<(String, String)> {
+    let repository = Repository::new(&config.db_path).await?;
+    let username = match username {
+        Some(username) => username.to_string(),
+        None => match repository.get_usernames().await?.as_slice() {
+            [only] => only.clone(),
+            [] => return Err(AppError::Config("No accounts yet; create one with --add-user".to_string())),
+            _ => return Err(AppError::Config("Which account? Name it with --user".to_string())),
+        },
+    };
+    let user = repository
+        .get_user_by_name(&username)
+        .await?
+        .ok_or_else(|| AppError::Config(format!("No such user: {}", username)))?;
+
+    let token = auth::generate_token();
+    let name = name.map(str::to_string).unwrap_or_else(|| format!("{} token", scope.as_str()));
+    repository.add_api_token(user.id, &name, &auth::hash_token(&token), scope).await?;
+    Ok((user.username, token))
+}
+
+pub async fn list_tokens(config: &Config) -> Result<Vec<ApiToken>> {
+    Repository::new(&config.db_path).await?.list_api_tokens().await
+}
+
+/// Revoke API tokens by id, failing on the first that doesn't exist
+pub async fn revoke_tokens(config: &Config, ids: &[i64]) -> Result<()> {
+    let repository = Repository::new(&config.db_path).await?;
+    for &id in ids {
+        if !repository.delete_api_token(id).await? {
+            return Err(AppError::Config(format!("No token with id {}", id)));
+        }
+    }
+    Ok(())
+}
+
+/// Vacuum, analyze and record table sizes every `interval_hours` (0 disables)
+fn spawn_maintenance_loop(repository: Arc<Repository>, interval_hours: u32) {
+    if interval_hours == 0 {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let interval = Duration::from_secs(u64::from(interval_hours) * 3600);
+        loop {
+            tokio::time::sleep(interval).await;
+            match repository.maintain().await {
+                Ok((before, after)) => tracing::info!("Database maintenance: {} bytes -> {} bytes", before, after),
+                Err(e) => tracing::warn!("Database maintenance failed: {}", e),
+            }
+        }
+    });
+}
+
+/// Refresh all feeds now and then every `interval_minutes` (0 disables),
+/// announcing what each refresh stored on `events`
+fn spawn_refresh_loop(
+    mut app: App,
+    interval_minutes: u32,
+    repository: Arc<Repository>,
+    events: broadcast::Sender<events::Event>,
+) {
+    if interval_minutes == 0 {
+        return;
+    }
+
+    tokio::spawn(async move {
+        if let Err(e) = app.resume_interrupted().await {
+            tracing::warn!("Failed to resume interrupted summaries: {}", e);
+        }
+        let interval = Duration::from_secs(u64::from(interval_minutes) * 60);
+        loop {
+            // Pick up feeds added from the TUI or API since the last cycle
+            if let Err(e) = app.reload_feeds().await {
+                tracing::warn!("Failed to reload feeds: {}", e);
+            }
+            // and edits to the urls file
+            match app.sync_urls_file().await {
+                Ok(Some((added, updated, archived))) => {
+                    tracing::info!("urls file: {} added, {} updated, {} archived", added, updated, archived)
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Failed to sync the urls file: {}", e),
+            }
+            // Let finished summary retries (and resumed batches) make way for new ones
+            if let Err(e) = app.poll_summary_retries().await {
+                tracing::warn!("Failed to check summary retries: {}", e);
+            }
+            if !app.may_refresh_now() {
+                tracing::debug!("Outside the [schedule] refresh windows; not refreshing");
+            } else {
+                let started = chrono::Utc::now();
+                match app.refresh_feeds_blocking().await {
+                    Ok(()) => events::publish_refresh(&repository, &events, started).await,
+                    Err(e) => tracing::warn!("Scheduled refresh failed: {}", e),
+                }
+            }
+            tokio::time::sleep(interval).await;
+        }
+    });
+}
+
+/// A server over a fresh database, for handler tests
+/// Limit a client fetching URLs that callers or feeds chose: only public
+/// addresses, redirects included, so the server can't be used to reach its
+/// own network. In local-only mode, only local ones.
+fn restrict_requests(builder: reqwest::ClientBuilder, local_only: bool) -> reqwest::ClientBuilder {
+    if local_only {
+        restrict_to_local(builder)
+    } else {
+        restrict_to_public(builder)
+    }
+}
+
+#[cfg(test)]
+async fn test_state(auth: bool) -> (ServerState, tempfile::TempDir) {
+    let dir = tempfile::TempDir::new().unwrap();
+    let config = Config {
+        db_path: dir.path().join("test.db").to_string_lossy().into_owned(),
+        ..Config::default()
+    };
+    let repository = Arc::new(Repository::new(&config.db_path).await.unwrap());
+    let images = Arc::new(images::ImagePolicy::new(&config, &repository).await.unwrap());
+    let (_, shutdown) = watch::channel(false);
+    let state = ServerState {
+        repository,
+        fetcher: Arc::new(FeedFetcher::restricted(|builder| restrict_requests(builder, config.local_only))),
+        db_path: config.db_path.clone(),
+        auth,
+        events: events::channel(),
+        shutdown,
+        images,
+    };
+    (state, dir)
+}
@@ -0,0 +1,165 @@
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::{StatusCode, Uri};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Redirect, Response};
+
+use crate::error::{AppError, Result};
+
+/// The address a request really came from, after unwrapping trusted proxies
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientAddr(pub IpAddr);
+
+/// A single address or CIDR range from `server.trusted_proxies`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProxyRange {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl ProxyRange {
+    pub fn parse(s: &str) -> Result<Self> {
+        let invalid = || AppError::Config(format!("Invalid trusted proxy: {}", s));
+        let (addr, prefix) = match s.trim().split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix.parse::<u8>().map_err(|_| invalid())?)),
+            None => (s.trim(), None),
+        };
+        let network: IpAddr = addr.parse().map_err(|_| invalid())?;
+        let max = if network.is_ipv4() { 32 } else { 128 };
+        let prefix = prefix.unwrap_or(max);
+        if prefix > max {
+            return Err(invalid());
+        }
+        Ok(Self { network, prefix })
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        // Treat IPv4-mapped IPv6 peers (dual-stack sockets) as plain IPv4
+        let ip = match ip {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(ip),
+            v4 => v4,
+        };
+        match (self.network, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - u32::from(self.prefix)).unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - u32::from(self.prefix)).unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Work out the client address. X-Forwarded-For is only believed when the
+/// connection comes from a trusted proxy; hops are then read right to left
+/// and the first untrusted one is the client.
+pub fn client_ip(peer: IpAddr, forwarded_for: Option<&str>, trusted: &[ProxyRange]) -> IpAddr {
+    let is_trusted = |ip: IpAddr| trusted.iter().any(|range| range.contains(ip));
+    if !is_trusted(peer) {
+        return peer;
+    }
+
+    let hops: Vec<IpAddr> = forwarded_for
+        .unwrap_or("")
+        .split(',')
+        .filter_map(|hop| hop.trim().parse().ok())
+        .collect();
+    hops.iter()
+        .rev()
+        .copied()
+        .find(|ip| !is_trusted(*ip))
+        .or_else(|| hops.first().copied())
+        .unwrap_or(peer)
+}
+
+/// Middleware: record the resolved `ClientAddr` in request extensions
+pub async fn resolve_client(
+    State(trusted): State<Arc<[ProxyRange]>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let forwarded_for = request
+        .headers()
+        .get("x-forwarded-for")
+        .and_then(|v| v.to_str().ok());
+    let ip = client_ip(peer.ip(), forwarded_for, &trusted);
+    request.extensions_mut().insert(ClientAddr(ip));
+    next.run(request).await
+}
+
+/// Middleware: route "/base/..." as "/...", redirect the bare "/base" to
+/// "/base/" (the web UI's URLs are relative) and 404 anything outside it
+pub async fn strip_base_path(
+    State(base_path): State<Arc<str>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path();
+    if path == &*base_path {
+        return Redirect::permanent(&format!("{}/", base_path)).into_response();
+    }
+    let Some(rest) = path.strip_prefix(&*base_path).filter(|rest| rest.starts_with('/')) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    let rewritten = match request.uri().query() {
+        Some(query) => format!("{}?{}", rest, query),
+        None => rest.to_string(),
+    };
+    match rewritten.parse::<Uri>() {
+        Ok(uri) => *request.uri_mut() = uri,
+        Err(_) => return StatusCode::BAD_REQUEST.into_response(),
+    }
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranges(specs: &[&str]) -> Vec<ProxyRange> {
+        specs.iter().map(|s| ProxyRange::parse(s).unwrap()).collect()
+    }
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_proxy_range_matching() {
+        let range = ProxyRange::parse("10.0.0.0/8").unwrap();
+        assert!(range.contains(ip("10.1.2.3")));
+        assert!(range.contains(ip("::ffff:10.9.9.9")));
+        assert!(!range.contains(ip("11.0.0.1")));
+
+        let single = ProxyRange::parse("::1").unwrap();
+        assert!(single.contains(ip("::1")));
+        assert!(!single.contains(ip("::2")));
+
+        assert!(ProxyRange::parse("0.0.0.0/0").unwrap().contains(ip("8.8.8.8")));
+        assert!(ProxyRange::parse("10.0.0.0/33").is_err());
+        assert!(ProxyRange::parse("localhost").is_err());
+    }
+
+    #[test]
+    fn test_forwarded_for_ignored_from_untrusted_peer() {
+        let trusted = ranges(&["127.0.0.1"]);
+        assert_eq!(client_ip(ip("203.0.113.5"), Some("1.2.3.4"), &trusted), ip("203.0.113.5"));
+    }
+
+    #[test]
+    fn test_forwarded_for_skips_trusted_hops() {
+        let trusted = ranges(&["127.0.0.1", "10.0.0.0/8"]);
+        // Client-supplied junk on the left must not win over the real client
+        let header = "6.6.6.6, 198.51.100.7, 10.0.0.2";
+        assert_eq!(client_ip(ip("127.0.0.1"), Some(header), &trusted), ip("198.51.100.7"));
+        assert_eq!(client_ip(ip("127.0.0.1"), None, &trusted), ip("127.0.0.1"));
+        assert_eq!(client_ip(ip("127.0.0.1"), Some("10.0.0.9"), &trusted), ip("10.0.0.9"));
+    }
+}
@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::extract::{Request, State};
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+use super::proxy::ClientAddr;
+
+/// Clients tracked before idle ones are forgotten
+const MAX_CLIENTS: usize = 10_000;
+
+/// A token bucket per client address: `per_minute` requests a minute, in
+/// bursts of up to as many. Keyed by address rather than credentials, so
+/// guessing passwords or tokens is slowed down too.
+pub struct RateLimiter {
+    per_minute: u32,
+    buckets: Mutex<HashMap<IpAddr, Bucket>>,
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(per_minute: u32) -> Self {
+        Self {
+            per_minute,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Take a request from the client's allowance, or say how long until
+    /// the next one is allowed
+    fn check(&self, client: IpAddr, now: Instant) -> Result<(), Duration> {
+        let capacity = f64::from(self.per_minute);
+        let per_sec = capacity / 60.0;
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        if buckets.len() >= MAX_CLIENTS && !buckets.contains_key(&client) {
+            // Any bucket left alone for a minute is full again, the same as new
+            buckets.retain(|_, bucket| now.duration_since(bucket.updated) < Duration::from_secs(60));
+        }
+        let bucket = buckets.entry(client).or_insert(Bucket { tokens: capacity, updated: now });
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * per_sec).min(capacity);
+        bucket.updated = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / per_sec))
+        }
+    }
+}
+
+/// Middleware: answer 429 with `Retry-After` to clients over their allowance
+pub async fn limit(State(limiter): State<Arc<RateLimiter>>, request: Request, next: Next) -> Response {
+    let Some(client) = request.extensions().get::<ClientAddr>().map(|c| c.0) else {
+        return next.run(request).await;
+    };
+    match limiter.check(client, Instant::now()) {
+        Ok(()) => next.run(request).await,
+        Err(wait) => {
+            tracing::debug!("Rate limited {}", client);
+            let mut response = (StatusCode::TOO_MANY_REQUESTS, "Too many requests").into_response();
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from((wait.as_secs_f64().ceil() as u64).max(1)));
+            response
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allows_bursts_then_refills() {
+        let limiter = RateLimiter::new(3);
+        let (alice, bob): (IpAddr, IpAddr) = ("10.0.0.1".parse().unwrap(), "10.0.0.2".parse().unwrap());
+        let start = Instant::now();
+        for _ in 0..3 {
+            assert!(limiter.check(alice, start).is_ok());
+        }
+        let wait = limiter.check(alice, start).unwrap_err();
+        assert_eq!(wait.as_secs_f64().round(), 20.0);
+        // Others have their own allowance
+        assert!(limiter.check(bob, start).is_ok());
+
+        assert!(limiter.check(alice, start + Duration::from_secs(19)).is_err());
+        assert!(limiter.check(alice, start + Duration::from_secs(21)).is_ok());
+        assert!(limiter.check(alice, start + Duration::from_secs(22)).is_err());
+    }
+}
@@ -0,0 +1,73 @@
+// systemd integration: socket activation and sd_notify. Everything here is a
+// no-op when the server isn't started by systemd.
+
+use std::os::fd::FromRawFd;
+use std::time::Duration;
+
+use sd_notify::NotifyState;
+
+use crate::error::{AppError, Result};
+
+/// The listening socket passed in by a `.socket` unit, if any. systemd keeps
+/// it open across restarts, so connections queue up instead of being refused.
+pub fn activated_listener() -> Result<Option<std::net::TcpListener>> {
+    let Some(fd) = sd_notify::listen_fds()?.next() else {
+        return Ok(None);
+    };
+
+    // SAFETY: listen_fds() only yields descriptors systemd handed to this
+    // process (LISTEN_PID matched), and we take ownership of the first one
+    let listener = unsafe { std::net::TcpListener::from_raw_fd(fd) };
+    listener
+        .local_addr()
+        .map_err(|e| AppError::Config(format!("Socket from systemd is not a TCP listener: {}", e)))?;
+    listener.set_nonblocking(true)?;
+    Ok(Some(listener))
+}
+
+/// Tell systemd the server is accepting connections (for `Type=notify`)
+pub fn notify_ready() {
+    if let Err(e) = sd_notify::notify(false, &[NotifyState::Ready]) {
+        tracing::warn!("sd_notify READY failed: {}", e);
+    }
+}
+
+pub fn notify_stopping() {
+    let _ = sd_notify::notify(false, &[NotifyState::Stopping]);
+}
+
+/// Ping the watchdog at half the unit's `WatchdogSec`, if one is configured
+pub fn spawn_watchdog() {
+    let mut usec = 0;
+    if !sd_notify::watchdog_enabled(false, &mut usec) {
+        return;
+    }
+
+    let interval = Duration::from_micros(usec / 2);
+    tokio::spawn(async move {
+        loop {
+            if let Err(e) = sd_notify::notify(false, &[NotifyState::Watchdog]) {
+                tracing::warn!("sd_notify WATCHDOG failed: {}", e);
+            }
+            tokio::time::sleep(interval).await;
+        }
+    });
+}
+
+/// Resolves on SIGTERM (what `systemctl stop` sends) or Ctrl-C
+pub async fn shutdown_signal() {
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(_) => std::future::pending::<()>().await,
+        }
+    };
+
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = terminate => {}
+    }
+    notify_stopping();
+}
@@ -0,0 +1,682 @@
+//! Enough of the Wallabag API for its mobile apps and browser extensions to
+//! save links into the read-later queue and read them back: the OAuth
+//! password grant, entries (the queue plus everything saved), and tags.
+//! Entries are articles; "archived" is read.
+
+use std::collections::HashMap;
+
+use axum::body::Bytes;
+use axum::extract::{Path, Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Extension, Json, Router};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::db::{self, ArticleFilter};
+use crate::models::{Article, NewArticle, SAVED_FEED_URL};
+
+use super::api::{find_article, ApiError, ApiResult, MaybeUser};
+use super::auth::{generate_token, hash_token, log_in};
+use super::events::Event;
+use super::ServerState;
+
+/// The Wallabag release whose API this follows
+const WALLABAG_VERSION: &str = "2.6.9";
+
+const ACCESS_TOKEN_SECS: i64 = 3600;
+const REFRESH_TOKEN_DAYS: i64 = 30;
+
+const DEFAULT_PER_PAGE: usize = 30;
+const MAX_PER_PAGE: usize = 500;
+/// Entries looked at when paging; a read-later list is much shorter
+const MAX_ENTRIES: usize = 10_000;
+
+/// The token endpoint, which must be reachable without credentials
+pub fn token_routes() -> Router<ServerState> {
+    Router::new().route("/oauth/v2/token", post(token))
+}
+
+/// The API proper, behind the usual authentication. Wallabag clients ask
+/// for `.json` paths, and some leave the suffix off (except for tags, where
+/// `/api/tags` is beatcheck's own).
+pub fn routes() -> Router<ServerState> {
+    Router::new()
+        .route("/api/version", get(version))
+        .route("/api/version.json", get(version))
+        .route("/api/info", get(info))
+        .route("/api/info.json", get(info))
+        .route("/api/user", get(user_info))
+        .route("/api/user.json", get(user_info))
+        .route("/api/tags.json", get(tags))
+        .route("/api/entries", get(list_entries).post(add_entry))
+        .route("/api/entries.json", get(list_entries).post(add_entry))
+        .route("/api/entries/exists", get(entry_exists))
+        .route("/api/entries/exists.json", get(entry_exists))
+        .route("/api/entries/{entry}", get(get_entry).patch(update_entry).delete(delete_entry))
+}
+
+/// An entry as Wallabag serializes it
+#[derive(Debug, Serialize)]
+pub struct Entry {
+    id: i64,
+    url: String,
+    given_url: String,
+    origin_url: Option<String>,
+    title: String,
+    content: String,
+    is_archived: u8,
+    is_starred: u8,
+    is_public: bool,
+    created_at: String,
+    updated_at: String,
+    published_at: Option<String>,
+    published_by: Vec<String>,
+    reading_time: u32,
+    domain_name: Option<String>,
+    mimetype: &'static str,
+    language: Option<String>,
+    preview_picture: Option<String>,
+    tags: Vec<Tag>,
+    annotations: Vec<Value>,
+    user_id: Option<i64>,
+    user_name: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Tag {
+    id: usize,
+    label: String,
+    slug: String,
+}
+
+/// Wallabag's date format, e.g. 2026-03-02T09:00:00+0000
+fn wallabag_date(date: DateTime<Utc>) -> String {
+    date.format("%Y-%m-%dT%H:%M:%S%z").to_string()
+}
+
+fn tag_list(tags: &[String]) -> Vec<Tag> {
+    tags.iter()
+        .enumerate()
+        .map(|(index, tag)| Tag {
+            id: index + 1,
+            label: tag.clone(),
+            slug: tag.replace(' ', "-"),
+        })
+        .collect()
+}
+
+fn entry(state: &ServerState, mut article: Article, user: &MaybeUser) -> Entry {
+    state.images.apply(&mut article);
+    let content = article
+        .full_text
+        .clone()
+        .or(article.content.clone())
+        .unwrap_or_default();
+    Entry {
+        id: article.id,
+        domain_name: url::Url::parse(&article.url).ok().and_then(|u| u.host_str().map(str::to_string)),
+        given_url: article.url.clone(),
+        url: article.url,
+        origin_url: None,
+        title: article.title,
+        content,
+        is_archived: u8::from(article.is_read),
+        is_starred: u8::from(article.is_starred),
+        is_public: false,
+        created_at: wallabag_date(article.fetched_at),
+        updated_at: wallabag_date(article.fetched_at),
+        published_at: article.published_at.map(wallabag_date),
+        published_by: article.author.into_iter().collect(),
+        reading_time: article.reading_minutes.unwrap_or(0),
+        mimetype: "text/html",
+        language: None,
+        preview_picture: None,
+        tags: tag_list(&article.tags),
+        annotations: Vec::new(),
+        user_id: user.as_ref().map(|Extension(u)| u.id),
+        user_name: user.as_ref().map(|Extension(u)| u.username.clone()),
+    }
+}
+
+/// A request's fields, sent as a form or as JSON. Numbers and booleans
+/// become text ("1"/"0"), lists are joined with commas.
+fn fields(headers: &HeaderMap, body: &[u8]) -> ApiResult<HashMap<String, String>> {
+    let is_json = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains("json"));
+    if !is_json {
+        // A page on any site can post a form here without a CORS preflight,
+        // which JSON needs
+        if from_other_site(headers) {
+            return Err(ApiError::Forbidden("Forms from other sites aren't accepted".to_string()));
+        }
+        return Ok(url::form_urlencoded::parse(body).into_owned().collect());
+    }
+    if body.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let Value::Object(object) =
+        serde_json::from_slice(body).map_err(|e| ApiError::BadRequest(format!("Bad JSON: {}", e)))?
+    else {
+        return Err(ApiError::BadRequest("Expected a JSON object".to_string()));
+    };
+    Ok(object
+        .into_iter()
+        .filter_map(|(key, value)| {
+            let text = match value {
+                Value::String(s) => s,
+                Value::Bool(b) => if b { "1" } else { "0" }.to_string(),
+                Value::Number(n) => n.to_string(),
+                Value::Array(items) => items
+                    .iter()
+                    .filter_map(|item| item.as_str().map(str::to_string).or_else(|| item.get("label")?.as_str().map(str::to_string)))
+                    .collect::<Vec<_>>()
+                    .join(","),
+                _ => return None,
+            };
+            Some((key, text))
+        })
+        .collect())
+}
+
+/// Whether a browser sent the request from another site's page. Wallabag
+/// apps aren't browsers and send neither `Sec-Fetch-Site` nor `Origin`;
+/// browser extensions send their own `moz-extension:` style origin.
+fn from_other_site(headers: &HeaderMap) -> bool {
+    let header = |name| headers.get(name).and_then(|v: &header::HeaderValue| v.to_str().ok());
+    if let Some(site) = header(header::HeaderName::from_static("sec-fetch-site")) {
+        return matches!(site, "cross-site" | "same-site");
+    }
+    let Some(origin) = header(header::ORIGIN) else {
+        return false;
+    };
+    // "null" from sandboxed frames and data: pages counts as another site
+    let Ok(origin) = url::Url::parse(origin) else {
+        return true;
+    };
+    if !matches!(origin.scheme(), "http" | "https") {
+        return false;
+    }
+    let origin_host = match (origin.host_str(), origin.port()) {
+        (Some(host), Some(port)) => format!("{}:{}", host, port),
+        (Some(host), None) => host.to_string(),
+        (None, _) => return true,
+    };
+    header(header::HOST) != Some(origin_host.as_str())
+}
+
+/// "1"/"true" or "0"/"false"; anything else counts as not given
+fn flag(fields: &HashMap<String, String>, name: &str) -> Option<bool> {
+    match fields.get(name).map(|v| v.trim().to_lowercase()).as_deref() {
+        Some("1" | "true") => Some(true),
+        Some("0" | "false") => Some(false),
+        _ => None,
+    }
+}
+
+fn split_tags(tags: &str) -> Vec<String> {
+    tags.split(',')
+        .map(|t| t.trim().to_lowercase())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+/// An OAuth error, as clients expect it
+fn oauth_error(error: &str, description: &str) -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(json!({ "error": error, "error_description": description })),
+    )
+        .into_response()
+}
+
+/// The OAuth token endpoint: a password grant, or a refresh grant with a
+/// refresh token it handed out. The client id and secret aren't checked;
+/// any Wallabag client can sign in with the account's password. Without
+/// `server.auth` there are no accounts, and any credentials do.
+pub async fn token(State(state): State<ServerState>, headers: HeaderMap, body: Bytes) -> ApiResult<Response> {
+    let fields = fields(&headers, &body)?;
+    let field = |name: &str| fields.get(name).cloned().unwrap_or_default();
+
+    let user_id = match field("grant_type").as_str() {
+        _ if !state.auth => None,
+        "password" => match log_in(&state, &field("username"), field("password")).await? {
+            Some(user) => Some(user.id),
+            None => return Ok(oauth_error("invalid_grant", "Invalid username and password combination")),
+        },
+        "refresh_token" => {
+            let token_hash = hash_token(&field("refresh_token"));
+            match state.repository.get_user_by_oauth_token(&token_hash, "refresh").await? {
+                Some(user) => {
+                    // Each refresh token works once
+                    state.repository.delete_oauth_token(&token_hash).await?;
+                    Some(user.id)
+                }
+                None => return Ok(oauth_error("invalid_grant", "Invalid refresh token")),
+            }
+        }
+        other => return Ok(oauth_error("unsupported_grant_type", &format!("Unsupported grant type: {}", other))),
+    };
+
+    let (access_token, refresh_token) = (generate_token(), generate_token());
+    if let Some(user_id) = user_id {
+        let now = Utc::now();
+        state
+            .repository
+            .add_oauth_token(user_id, &hash_token(&access_token), "access", now + chrono::Duration::seconds(ACCESS_TOKEN_SECS))
+            .await?;
+        state
+            .repository
+            .add_oauth_token(user_id, &hash_token(&refresh_token), "refresh", now + chrono::Duration::days(REFRESH_TOKEN_DAYS))
+            .await?;
+    }
+    Ok(Json(json!({
+        "access_token": access_token,
+        "expires_in": ACCESS_TOKEN_SECS,
+        "token_type": "bearer",
+        "scope": null,
+        "refresh_token": refresh_token,
+    }))
+    .into_response())
+}
+
+pub async fn version() -> Json<&'static str> {
+    Json(WALLABAG_VERSION)
+}
+
+pub async fn info() -> Json<Value> {
+    Json(json!({ "appname": "wallabag", "version": WALLABAG_VERSION, "allowed_registration": false }))
+}
+
+pub async fn user_info(user: MaybeUser) -> Json<Value> {
+    let (id, username) = match user {
+        Some(Extension(user)) => (user.id, user.username),
+        None => (1, "beatcheck".to_string()),
+    };
+    Json(json!({ "id": id, "username": username, "name": username, "email": "" }))
+}
+
+pub async fn tags(State(state): State<ServerState>, user: MaybeUser) -> ApiResult<Json<Vec<Tag>>> {
+    let tags: Vec<String> = state
+        .repository
+        .tag_counts(user.map(|Extension(u)| u.id), MAX_PER_PAGE)
+        .await?
+        .into_iter()
+        .map(|(tag, _)| tag)
+        .collect();
+    Ok(Json(tag_list(&tags)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EntryListParams {
+    archive: Option<u8>,
+    starred: Option<u8>,
+    tags: Option<String>,
+    page: Option<usize>,
+    #[serde(rename = "perPage")]
+    per_page: Option<usize>,
+}
+
+/// The read-later queue plus everything saved, newest first
+pub async fn list_entries(
+    State(state): State<ServerState>,
+    user: MaybeUser,
+    Query(params): Query<EntryListParams>,
+) -> ApiResult<Json<Value>> {
+    let mut query = format!("(is:queued OR feed:\"{}\")", SAVED_FEED_URL);
+    for tag in split_tags(params.tags.as_deref().unwrap_or_default()) {
+        query.push_str(&format!(" tag:\"{}\"", tag));
+    }
+    let filter = ArticleFilter {
+        feed_id: None,
+        feed_ids: None,
+        since: None,
+        until: None,
+        starred_only: false,
+        unread_only: false,
+        text: None,
+        user_id: user.as_ref().map(|Extension(u)| u.id),
+        tag: None,
+        entity: None,
+        author: None,
+        unsummarized: false,
+        query: Some(db::Query::parse(&query).map_err(|e| ApiError::BadRequest(format!("Bad filter at {}", e)))?),
+    };
+    let mut articles = state.repository.list_articles(&filter, MAX_ENTRIES, None).await?;
+    // Read and starred as the caller sees them (an account has its own)
+    articles.retain(|a| {
+        params.archive.is_none_or(|archived| (archived != 0) == a.is_read)
+            && params.starred.is_none_or(|starred| (starred != 0) == a.is_starred)
+    });
+
+    let per_page = params.per_page.unwrap_or(DEFAULT_PER_PAGE).clamp(1, MAX_PER_PAGE);
+    let page = params.page.unwrap_or(1).max(1);
+    let total = articles.len();
+    let items: Vec<Entry> = articles
+        .into_iter()
+        .skip((page - 1) * per_page)
+        .take(per_page)
+        .map(|article| entry(&state, article, &user))
+        .collect();
+    Ok(Json(json!({
+        "page": page,
+        "limit": per_page,
+        "pages": total.div_ceil(per_page).max(1),
+        "total": total,
+        "_links": {},
+        "_embedded": { "items": items },
+    })))
+}
+
+/// Save a link: it joins the read-later queue, as a new article in the
+/// Saved feed unless it's stored already. With accounts, only articles the
+/// account can see count, and the save, queue and tags are the account's
+/// own. Its text is fetched when first summarized, as for other saved
+/// links, unless the client sends `content`.
+pub async fn add_entry(
+    State(state): State<ServerState>,
+    user: MaybeUser,
+    headers: HeaderMap,
+    body: Bytes,
+) -> ApiResult<Json<Entry>> {
+    let fields = fields(&headers, &body)?;
+    let url = fields
+        .get("url")
+        .map(|u| u.trim().to_string())
+        .filter(|u| u.starts_with("http://") || u.starts_with("https://"))
+        .ok_or_else(|| ApiError::BadRequest("url must be an http(s) link".to_string()))?;
+    let title = fields.get("title").map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+
+    let saved_feed = state.repository.saved_feed_id().await?;
+    // Nothing is written for an account until the article is one it can see
+    let existing = match &user {
+        Some(Extension(user)) => state.repository.user_article_id_by_url(user.id, &url).await?,
+        None => state.repository.article_id_by_url(&url).await?,
+    };
+    let is_new = existing.is_none();
+    let id = match existing {
+        Some(id) => id,
+        None => {
+            let article = NewArticle {
+                feed_id: saved_feed,
+                guid: url.clone(),
+                title: title.unwrap_or_else(|| url.clone()),
+                url: url.clone(),
+                author: fields.get("authors").map(|a| a.trim().to_string()).filter(|a| !a.is_empty()),
+                content: fields.get("content").cloned().filter(|c| !c.trim().is_empty()),
+                content_text: None,
+                published_at: fields
+                    .get("published_at")
+                    .and_then(|d| DateTime::parse_from_rfc3339(d).ok())
+                    .map(|d| d.with_timezone(&Utc)),
+                comments_url: None,
+                enclosure: None,
+                extras: None,
+                tags: Vec::new(),
+                entities: Vec::new(),
+                authors: Vec::new(),
+                links: Vec::new(),
+            };
+            let id = match state.repository.upsert_article(article.clone()).await? {
+                // Deleted before, but asked for again
+                0 => {
+                    state.repository.undelete_article(saved_feed, &url).await?;
+                    state.repository.upsert_article(article).await?
+                }
+                id => id,
+            };
+            id
+        }
+    };
+    // An account's saves and queue are its own, not the local reader's
+    match &user {
+        Some(Extension(user)) => state.repository.save_for_user(user.id, id).await?,
+        None => state.repository.set_queued(id, true).await?,
+    }
+    if is_new {
+        let _ = state.events.send(Event::Articles { feed_id: saved_feed, count: 1 });
+    }
+    apply_changes(&state, &user, id, &fields).await?;
+    Ok(Json(entry(&state, find_article(&state, &user, id).await?, &user)))
+}
+
+/// `archive`, `starred` and `tags` from a request, for this caller
+async fn apply_changes(state: &ServerState, user: &MaybeUser, id: i64, fields: &HashMap<String, String>) -> ApiResult<()> {
+    if let Some(read) = flag(fields, "archive") {
+        match user {
+            Some(Extension(user)) => state.repository.set_user_read(user.id, id, read).await?,
+            None => state.repository.set_read(id, read).await?,
+        }
+    }
+    if let Some(starred) = flag(fields, "starred") {
+        match user {
+            Some(Extension(user)) => state.repository.set_user_starred(user.id, id, starred).await?,
+            None => state.repository.set_starred(id, starred).await?,
+        }
+    }
+    if let Some(tags) = fields.get("tags") {
+        if let Some(Extension(user)) = user {
+            state.repository.add_user_tags(user.id, id, split_tags(tags)).await?;
+            return Ok(());
+        }
+        let mut all = find_article(state, user, id).await?.tags;
+        for tag in split_tags(tags) {
+            if !all.contains(&tag) {
+                all.push(tag);
+            }
+        }
+        state.repository.set_article_tags(id, all).await?;
+    }
+    Ok(())
+}
+
+/// `/api/entries/7.json` or `/api/entries/7`
+fn entry_id(entry: &str) -> ApiResult<i64> {
+    entry.trim_end_matches(".json").parse().map_err(|_| ApiError::NotFound)
+}
+
+pub async fn get_entry(
+    State(state): State<ServerState>,
+    user: MaybeUser,
+    Path(entry_path): Path<String>,
+) -> ApiResult<Json<Entry>> {
+    let article = find_article(&state, &user, entry_id(&entry_path)?).await?;
+    Ok(Json(entry(&state, article, &user)))
+}
+
+pub async fn update_entry(
+    State(state): State<ServerState>,
+    user: MaybeUser,
+    Path(entry_path): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> ApiResult<Json<Entry>> {
+    let id = entry_id(&entry_path)?;
+    find_article(&state, &user, id).await?;
+    apply_changes(&state, &user, id, &fields(&headers, &body)?).await?;
+    Ok(Json(entry(&state, find_article(&state, &user, id).await?, &user)))
+}
+
+/// Delete the article, answering with the entry as it was. With accounts
+/// it's only gone for the caller; others may follow its feed or have saved
+/// the same link.
+pub async fn delete_entry(
+    State(state): State<ServerState>,
+    user: MaybeUser,
+    Path(entry_path): Path<String>,
+) -> ApiResult<Json<Entry>> {
+    let article = find_article(&state, &user, entry_id(&entry_path)?).await?;
+    match &user {
+        Some(Extension(user)) => state.repository.set_user_hidden(user.id, article.id, true).await?,
+        None => state.repository.delete_article(article.id).await?,
+    }
+    Ok(Json(entry(&state, article, &user)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExistsParams {
+    url: Option<String>,
+    return_id: Option<u8>,
+}
+
+/// Whether a link is stored (in a feed the caller follows, with accounts):
+/// `{"exists": true}`, or its id (or null) with `return_id=1`
+pub async fn entry_exists(
+    State(state): State<ServerState>,
+    user: MaybeUser,
+    Query(params): Query<ExistsParams>,
+) -> ApiResult<Json<Value>> {
+    let url = params.url.ok_or_else(|| ApiError::BadRequest("url is required".to_string()))?;
+    let id = match &user {
+        Some(Extension(user)) => match state.repository.user_article_id_by_url(user.id, url.trim()).await? {
+            // Not if they deleted it
+            Some(id) => state.repository.get_article_for_user(id, user.id).await?.map(|a| a.id),
+            None => None,
+        },
+        None => state.repository.article_id_by_url(url.trim()).await?,
+    };
+    Ok(Json(match params.return_id {
+        Some(1) => json!({ "exists": id }),
+        _ => json!({ "exists": id.is_some() }),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::NewFeed;
+    use crate::server::auth::CurrentUser;
+
+    #[test]
+    fn test_fields_from_form_and_json() {
+        let mut headers = HeaderMap::new();
+        let form = fields(&headers, b"url=https%3A%2F%2Fexample.com%2Fa&tags=ai%2Cchips&archive=1").unwrap();
+        assert_eq!(form["url"], "https://example.com/a");
+        assert_eq!(flag(&form, "archive"), Some(true));
+        assert_eq!(split_tags(&form["tags"]), ["ai", "chips"]);
+
+        headers.insert(header::CONTENT_TYPE, "application/json".parse().unwrap());
+        let json = fields(&headers, br#"{"url": "https://example.com/b", "starred": true, "archive": 0, "tags": ["AI", {"label": "Chips"}]}"#)
+            .unwrap();
+        assert_eq!(flag(&json, "starred"), Some(true));
+        assert_eq!(flag(&json, "archive"), Some(false));
+        assert_eq!(flag(&json, "missing"), None);
+        assert_eq!(split_tags(&json["tags"]), ["ai", "chips"]);
+        assert!(fields(&headers, b"[1]").is_err());
+    }
+
+    #[test]
+    fn test_forms_from_other_sites_are_refused() {
+        let form = |pairs: &[(&str, &str)]| {
+            let mut headers = HeaderMap::new();
+            for (name, value) in pairs {
+                headers.insert(header::HeaderName::from_bytes(name.as_bytes()).unwrap(), value.parse().unwrap());
+            }
+            fields(&headers, b"url=https%3A%2F%2Fexample.com%2Fa").is_ok()
+        };
+        assert!(form(&[]));
+        assert!(form(&[("host", "reader.local:8080"), ("origin", "http://reader.local:8080")]));
+        assert!(form(&[("origin", "moz-extension://4b1c")]));
+        assert!(form(&[("sec-fetch-site", "same-origin"), ("origin", "https://evil.example")]));
+        assert!(!form(&[("sec-fetch-site", "cross-site")]));
+        assert!(!form(&[("host", "reader.local:8080"), ("origin", "https://evil.example")]));
+        assert!(!form(&[("host", "reader.local:8080"), ("origin", "null")]));
+    }
+
+    #[tokio::test]
+    async fn test_entries_are_scoped_to_the_caller() {
+        let (state, _dir) = super::super::test_state(true).await;
+        let repo = &state.repository;
+        let caller = |id| Some(Extension(CurrentUser { id, username: format!("user{}", id) }));
+        let one = repo.create_user("one", "x").await.unwrap();
+        let two = repo.create_user("two", "x").await.unwrap();
+        let feed = NewFeed {
+            title: "A".to_string(),
+            url: "https://a.example/feed".to_string(),
+            site_url: None,
+            description: None,
+        };
+        let feed_id = repo.insert_feed(feed).await.unwrap();
+        repo.subscribe_user(one, feed_id).await.unwrap();
+        let url = "https://a.example/post";
+        let followed = repo
+            .upsert_article(NewArticle {
+                feed_id,
+                guid: url.to_string(),
+                title: "Post".to_string(),
+                url: url.to_string(),
+                author: None,
+                content: None,
+                content_text: None,
+                published_at: None,
+                comments_url: None,
+                enclosure: None,
+                extras: None,
+                tags: Vec::new(),
+                entities: Vec::new(),
+                authors: Vec::new(),
+                links: Vec::new(),
+            })
+            .await
+            .unwrap();
+        let exists = |id| {
+            let params = ExistsParams { url: Some(url.to_string()), return_id: Some(1) };
+            entry_exists(State(state.clone()), caller(id), Query(params))
+        };
+        assert_eq!(exists(one).await.unwrap().0, json!({ "exists": followed }));
+        assert_eq!(exists(two).await.unwrap().0, json!({ "exists": null }));
+
+        // The other account gets its own copy in Saved; the followed one is untouched
+        let body = Bytes::from(format!(r#"{{"url": "{}", "starred": true, "tags": "column"}}"#, url));
+        let mut headers = HeaderMap::new();
+        headers.insert(header::CONTENT_TYPE, "application/json".parse().unwrap());
+        let saved = add_entry(State(state.clone()), caller(two), headers.clone(), body.clone()).await.unwrap().0.id;
+        assert_ne!(saved, followed);
+        let article = repo.get_article_for_user(followed, one).await.unwrap().unwrap();
+        assert!(!article.is_starred && !article.is_queued);
+        assert_eq!(exists(two).await.unwrap().0, json!({ "exists": saved }));
+
+        // Saves, queue and tags are the account's own: not the other
+        // account's, nor the local reader's
+        let entries = |id| {
+            let params = EntryListParams { archive: None, starred: None, tags: None, page: None, per_page: None };
+            let state = state.clone();
+            async move {
+                let list = list_entries(State(state), caller(id), Query(params)).await.unwrap().0;
+                let items = list["_embedded"]["items"].as_array().unwrap().clone();
+                items.iter().map(|item| item["id"].as_i64().unwrap()).collect::<Vec<_>>()
+            }
+        };
+        assert_eq!(entries(two).await, [saved]);
+        assert!(entries(one).await.is_empty());
+        assert!(repo.get_article_for_user(saved, one).await.unwrap().is_none());
+        let mine = repo.get_article_for_user(saved, two).await.unwrap().unwrap();
+        assert!(mine.is_queued && mine.is_starred && mine.tags == ["column"]);
+        let local = repo.get_article(saved).await.unwrap().unwrap();
+        assert!(!local.is_queued && !local.is_starred && local.tags.is_empty());
+        assert_eq!(repo.tag_counts(Some(two), 10).await.unwrap(), [("column".to_string(), 1)]);
+        assert!(repo.tag_counts(Some(one), 10).await.unwrap().is_empty());
+
+        // Deleting only hides it from the caller
+        let deleted = delete_entry(State(state.clone()), caller(two), Path(saved.to_string())).await.unwrap();
+        assert_eq!(deleted.0.id, saved);
+        assert!(repo.get_article_for_user(saved, two).await.unwrap().is_none());
+        assert!(repo.get_article(saved).await.unwrap().is_some());
+        assert_eq!(exists(two).await.unwrap().0, json!({ "exists": null }));
+        let again = add_entry(State(state.clone()), caller(two), headers, body).await.unwrap().0.id;
+        assert_eq!(again, saved);
+    }
+
+    #[test]
+    fn test_entry_ids_and_dates() {
+        assert_eq!(entry_id("7.json").ok(), Some(7));
+        assert_eq!(entry_id("7").ok(), Some(7));
+        assert!(entry_id("tags.json").is_err());
+        let date = DateTime::parse_from_rfc3339("2026-03-02T09:00:00Z").unwrap().to_utc();
+        assert_eq!(wallabag_date(date), "2026-03-02T09:00:00+0000");
+    }
+}
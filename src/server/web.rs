@@ -0,0 +1,50 @@
+use axum::http::{header, HeaderValue};
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+
+use super::ServerState;
+
+// Assets are compiled into the binary so the web UI needs no install step
+const INDEX_HTML: &str = include_str!("assets/index.html");
+const APP_JS: &str = include_str!("assets/app.js");
+const STYLE_CSS: &str = include_str!("assets/style.css");
+const SUBSCRIBE_HTML: &str = include_str!("assets/subscribe.html");
+const SUBSCRIBE_JS: &str = include_str!("assets/subscribe.js");
+
+pub fn routes() -> Router<ServerState> {
+    Router::new()
+        .route("/", get(index))
+        .route("/app.js", get(app_js))
+        .route("/style.css", get(style_css))
+        .route("/subscribe", get(subscribe))
+        .route("/subscribe.js", get(subscribe_js))
+}
+
+async fn index() -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "text/html; charset=utf-8")], INDEX_HTML)
+}
+
+async fn app_js() -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "text/javascript; charset=utf-8")], APP_JS)
+}
+
+async fn style_css() -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "text/css; charset=utf-8")], STYLE_CSS)
+}
+
+/// Where the bookmarklet lands. It's never framed, so another site can't
+/// trick a click on its Subscribe button.
+async fn subscribe() -> impl IntoResponse {
+    (
+        [
+            (header::CONTENT_TYPE, HeaderValue::from_static("text/html; charset=utf-8")),
+            (header::X_FRAME_OPTIONS, HeaderValue::from_static("DENY")),
+        ],
+        SUBSCRIBE_HTML,
+    )
+}
+
+async fn subscribe_js() -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "text/javascript; charset=utf-8")], SUBSCRIBE_JS)
+}
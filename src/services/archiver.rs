@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use base64::Engine;
+use regex::Regex;
+use reqwest::Client;
+use scraper::{Html, Selector};
+use url::Url;
+
+use crate::error::Result;
+use crate::export::{clean_html, export_file_name, html_document, Embed, Templates};
+use crate::models::Article;
+
+use super::http_cache::HttpCache;
+
+const USER_AGENT: &str =
+    "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+
+/// Images beyond this many, or larger than `MAX_IMAGE_BYTES`, keep their
+/// remote URL rather than bloating the snapshot
+const MAX_IMAGES: usize = 40;
+const MAX_IMAGE_BYTES: usize = 5 * 1024 * 1024;
+
+/// Saves self-contained HTML snapshots of starred articles
+#[derive(Clone)]
+pub struct Archiver {
+    client: Client,
+    dir: PathBuf,
+    templates: Arc<Templates>,
+    /// Pages and images kept on disk, shared with the content fetcher
+    cache: HttpCache,
+}
+
+impl Archiver {
+    pub fn new(dir: PathBuf, templates: Arc<Templates>, cache: HttpCache) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .user_agent(USER_AGENT)
+            .build()
+            .expect("Failed to create HTTP client");
+        Self {
+            client,
+            dir,
+            templates,
+            cache,
+        }
+    }
+
+    /// Snapshot the article page (or the feed's copy, if the page can't be
+    /// fetched) with its images inlined, and return the file's path
+    pub async fn archive(&self, article: &Article, embed: &Embed) -> Result<PathBuf> {
+        let page = match self.fetch_page(&article.url).await {
+            Ok(Some(html)) => Some(main_content(&html)),
+            Ok(None) => None,
+            Err(e) => {
+                tracing::debug!("Failed to fetch {} for archiving: {}", article.url, e);
+                None
+            }
+        };
+        let body = page
+            .or_else(|| article.content.clone())
+            .unwrap_or_default();
+        let body = clean_html(&body);
+
+        let base = Url::parse(&article.url).ok();
+        let mut inlined = HashMap::new();
+        for src in image_sources(&body).into_iter().take(MAX_IMAGES) {
+            let Some(url) = base.as_ref().and_then(|b| b.join(&src).ok()).or_else(|| Url::parse(&src).ok())
+            else {
+                continue;
+            };
+            match self.fetch_image(&url).await {
+                Ok(Some(data_uri)) => {
+                    inlined.insert(src, data_uri);
+                }
+                Ok(None) => {}
+                Err(e) => tracing::debug!("Failed to inline {}: {}", url, e),
+            }
+        }
+        let body = replace_image_sources(&body, &inlined);
+
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let path = self.dir.join(export_file_name(&self.templates, article, "html"));
+        let archived = format!("archived {}", chrono::Utc::now().format("%Y-%m-%d"));
+        tokio::fs::write(&path, html_document(&self.templates, article, embed, &body, &[archived])).await?;
+        Ok(path)
+    }
+
+    async fn fetch_page(&self, url: &str) -> Result<Option<String>> {
+        let response = self.cache.fetch(url, self.client.get(url)).await?;
+        Ok(response.is_success().then(|| response.text()))
+    }
+
+    /// The image as a `data:` URI, if it's an image and small enough
+    async fn fetch_image(&self, url: &Url) -> Result<Option<String>> {
+        if url.scheme() == "data" {
+            return Ok(None);
+        }
+        let response = self.cache.fetch(url.as_str(), self.client.get(url.as_str())).await?;
+        if !response.is_success() || response.body.len() > MAX_IMAGE_BYTES {
+            return Ok(None);
+        }
+        let Some(mime) = response.content_type.filter(|mime| mime.starts_with("image/")) else {
+            return Ok(None);
+        };
+        let encoded = base64::engine::general_purpose::STANDARD.encode(&response.body);
+        Ok(Some(format!("data:{};base64,{}", mime, encoded)))
+    }
+}
+
+/// Inner HTML of the page's main content element, falling back to <body>
+fn main_content(html: &str) -> String {
+    let document = Html::parse_document(html);
+    for css in ["article", "main", "[role=main]", "body"] {
+        let Ok(selector) = Selector::parse(css) else {
+            continue;
+        };
+        if let Some(element) = document.select(&selector).next() {
+            return element.inner_html();
+        }
+    }
+    html.to_string()
+}
+
+fn image_regex() -> Regex {
+    Regex::new(r#"(?i)(<img\b[^>]*?\ssrc\s*=\s*)("([^"]*)"|'([^']*)')"#).unwrap()
+}
+
+/// Distinct `<img src>` values, in document order
+fn image_sources(html: &str) -> Vec<String> {
+    let mut sources: Vec<String> = Vec::new();
+    for cap in image_regex().captures_iter(html) {
+        let src = cap.get(3).or(cap.get(4)).map_or("", |m| m.as_str()).replace("&amp;", "&");
+        if !src.is_empty() && !sources.contains(&src) {
+            sources.push(src);
+        }
+    }
+    sources
+}
+
+fn replace_image_sources(html: &str, inlined: &HashMap<String, String>) -> String {
+    image_regex()
+        .replace_all(html, |cap: &regex::Captures| {
+            let src = cap.get(3).or(cap.get(4)).map_or("", |m| m.as_str()).replace("&amp;", "&");
+            match inlined.get(&src) {
+                Some(data_uri) => format!("{}\"{}\"", &cap[1], data_uri),
+                None => cap[0].to_string(),
+            }
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_main_content_prefers_article_element() {
+        let html = "<html><body><nav>Menu</nav><article><p>Story</p></article></body></html>";
+        assert_eq!(main_content(html), "<p>Story</p>");
+        assert_eq!(main_content("<p>Bare</p>"), "<p>Bare</p>");
+    }
+
+    #[test]
+    fn test_image_sources_are_replaced_with_data_uris() {
+        let html = r#"<img src="/a.png" alt="a"><img alt='b' src='https://cdn.example/b.jpg?x=1&amp;y=2'><img src="/a.png">"#;
+        assert_eq!(image_sources(html), vec!["/a.png", "https://cdn.example/b.jpg?x=1&y=2"]);
+
+        let inlined = HashMap::from([("/a.png".to_string(), "data:image/png;base64,AAAA".to_string())]);
+        let out = replace_image_sources(html, &inlined);
+        assert_eq!(out.matches("data:image/png;base64,AAAA").count(), 2);
+        assert!(out.contains("https://cdn.example/b.jpg"));
+    }
+}
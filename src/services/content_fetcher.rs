@@ -1,70 +1,327 @@
-use std::time::Duration;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use reqwest::header::{HeaderMap, HeaderValue, COOKIE, USER_AGENT};
 use reqwest::Client;
 use rusqlite::params;
+use scraper::{Html, Selector};
 use url::Url;
 
+use crate::config::{ContentConfig, HttpCacheConfig, SiteRule};
 use crate::error::Result;
+use crate::metrics::metrics;
+
+use super::http_cache::HttpCache;
+use super::robots::RobotsRules;
 
 const USER_AGENT_STRING: &str = "Mozilla/5.0 (X11; Linux x86_64; rv:128.0) Gecko/20100101 Firefox/128.0";
 
+/// How long a site's robots.txt is trusted before fetching it again
+const ROBOTS_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+/// Retry sooner when robots.txt couldn't be read (and everything was blocked)
+const ROBOTS_ERROR_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Page limit for multi-page articles on sites without a rule
+const DEFAULT_MAX_PAGES: usize = 10;
+
+/// Blocked or paywalled fetches in a row before a site is left alone...
+const BLOCKED_THRESHOLD: u32 = 3;
+/// ...for this long
+const BLOCKED_COOLDOWN: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Request pacing and recent refusals for one site
+#[derive(Debug, Clone)]
+struct DomainState {
+    /// Earliest time the next request may start
+    next_request: Instant,
+    /// Blocked or paywalled fetches since the last good one
+    blocked: u32,
+    skip_until: Option<Instant>,
+}
+
+impl DomainState {
+    fn new(now: Instant) -> Self {
+        Self {
+            next_request: now,
+            blocked: 0,
+            skip_until: None,
+        }
+    }
+
+    /// Claim the next request slot, `interval` after the last one
+    fn reserve(&mut self, now: Instant, interval: Duration) -> Instant {
+        let start = self.next_request.max(now);
+        self.next_request = start + interval;
+        start
+    }
+
+    fn is_skipped(&self, now: Instant) -> bool {
+        self.skip_until.is_some_and(|until| until > now)
+    }
+
+    /// Count a fetch that was refused (or came back without an article),
+    /// or clear the count after one that worked. Once a site has refused
+    /// `BLOCKED_THRESHOLD` in a row, each further refusal restarts the cool-down.
+    fn record(&mut self, blocked: bool, now: Instant) {
+        if !blocked {
+            self.blocked = 0;
+            self.skip_until = None;
+            return;
+        }
+        self.blocked += 1;
+        if self.blocked >= BLOCKED_THRESHOLD {
+            self.skip_until = Some(now + BLOCKED_COOLDOWN);
+        }
+    }
+}
+
+/// How a page fetch went
+enum Page {
+    Fetched(String),
+    /// 401, 402, 403, 429 or 451: the site is refusing us
+    Refused,
+    /// Any other failure, or disallowed by robots.txt
+    Missing,
+}
+
 pub struct ContentFetcher {
     client: Client,
+    config: ContentConfig,
+    /// Send the browser's cookies for the site (off in local-only mode)
+    use_cookies: bool,
+    /// Parsed robots.txt per origin ("https://example.com"), with expiry
+    robots_cache: Mutex<HashMap<String, (RobotsRules, Instant)>>,
+    /// Pacing and refusals per site, keyed by host without "www."
+    domains: Mutex<HashMap<String, DomainState>>,
+    domain_interval: Duration,
+    /// Pages kept on disk, shared with the archiver
+    cache: HttpCache,
 }
 
 impl ContentFetcher {
-    pub fn new() -> Self {
+    pub fn new(config: &ContentConfig, use_cookies: bool, cache: HttpCache) -> Self {
         let client = Client::builder()
             .timeout(Duration::from_secs(30))
             .build()
             .expect("Failed to create HTTP client");
-        Self { client }
+        Self {
+            client,
+            config: config.clone(),
+            use_cookies,
+            robots_cache: Mutex::new(HashMap::new()),
+            domains: Mutex::new(HashMap::new()),
+            domain_interval: Duration::from_millis(config.domain_interval_ms),
+            cache,
+        }
     }
 
-    /// Fetch full article content using browser cookies
+    /// Fetch full article content using browser cookies. Articles split
+    /// across several pages are followed and stitched into one text.
+    /// Sites that keep refusing (or paywalling) pages are skipped for a
+    /// while rather than asked again.
     pub async fn fetch_full_content(&self, article_url: &str) -> Result<Option<String>> {
+        let _in_flight = metrics().content_fetch_started();
+        let started = Instant::now();
+        let result = self.fetch_article(article_url).await;
+        let outcome = result.as_ref().ok().map(Option::is_some);
+        metrics().record_content_fetch(outcome, started.elapsed());
+        result
+    }
+
+    async fn fetch_article(&self, article_url: &str) -> Result<Option<String>> {
         let url = match Url::parse(article_url) {
             Ok(u) => u,
             Err(_) => return Ok(None),
         };
 
         let domain = match url.host_str() {
-            Some(d) => d,
+            Some(d) => d.to_string(),
             None => return Ok(None),
         };
+        if self.is_skipped(&domain) {
+            tracing::debug!("Skipping {}: {} has been refusing requests", article_url, domain);
+            return Ok(None);
+        }
 
         // Get cookies for this domain from Chrome
-        let cookies = self.get_chrome_cookies(domain)?;
+        let cookies = if self.use_cookies {
+            self.get_chrome_cookies(&domain)?
+        } else {
+            String::new()
+        };
+
+        let rule = self.config.site_rule(&domain);
+        let max_pages = rule.map_or(DEFAULT_MAX_PAGES, |r| r.max_pages.max(1));
+
+        let html = match self.fetch_page(&url, &cookies).await? {
+            Page::Fetched(html) => html,
+            Page::Refused => {
+                self.record_outcome(&domain, true);
+                return Ok(None);
+            }
+            Page::Missing => return Ok(None),
+        };
+        // The first page must look like an article; later pages may be
+        // short. A page with no article on it is usually a paywall.
+        let first = self.extract_content(&html, article_url);
+        self.record_outcome(&domain, first.is_none());
+        let Some(first) = first else {
+            return Ok(None);
+        };
+
+        let mut pages = vec![first];
+        let mut visited = vec![url.clone()];
+        let mut next = find_next_page(&html, &url, rule);
+
+        while let Some(page_url) = next.take() {
+            if pages.len() >= max_pages || visited.contains(&page_url) {
+                break;
+            }
+            let Page::Fetched(html) = self.fetch_page(&page_url, &cookies).await? else {
+                break;
+            };
+            let text = html_to_text(&html);
+            if !text.is_empty() {
+                pages.push(text);
+            }
+            next = find_next_page(&html, &page_url, rule);
+            visited.push(page_url);
+        }
+
+        if pages.len() > 1 {
+            tracing::info!("Stitched {} pages for {}", pages.len(), article_url);
+        }
+        Ok(Some(pages.join("\n\n")))
+    }
+
+    /// GET one page with the browser's cookies, waiting for the site's
+    /// next request slot unless a fresh copy is cached
+    async fn fetch_page(&self, url: &Url, cookies: &str) -> Result<Page> {
+        if self.config.respect_robots && !self.robots_allows(url).await {
+            tracing::debug!("robots.txt disallows {}", url);
+            return Ok(Page::Missing);
+        }
+        if let Some(page) = self.cache.fresh(url.as_str()).await {
+            return Ok(Page::Fetched(page.text()));
+        }
+        if let Some(host) = url.host_str() {
+            self.wait_for_slot(host).await;
+        }
 
         // Build request with cookies
         let mut headers = HeaderMap::new();
         headers.insert(USER_AGENT, HeaderValue::from_static(USER_AGENT_STRING));
 
         if !cookies.is_empty() {
-            if let Ok(cookie_header) = HeaderValue::from_str(&cookies) {
+            if let Ok(cookie_header) = HeaderValue::from_str(cookies) {
                 headers.insert(COOKIE, cookie_header);
             }
         }
 
-        // Fetch the page
         let response = self
-            .client
-            .get(article_url)
-            .headers(headers)
-            .send()
+            .cache
+            .fetch(url.as_str(), self.client.get(url.as_str()).headers(headers))
             .await?;
 
-        if !response.status().is_success() {
-            tracing::debug!("Failed to fetch {}: {}", article_url, response.status());
-            return Ok(None);
+        let status = response.status;
+        if !status.is_success() {
+            tracing::debug!("Failed to fetch {}: {}", url, status);
+            return Ok(match status.as_u16() {
+                401 | 402 | 403 | 429 | 451 => Page::Refused,
+                _ => Page::Missing,
+            });
+        }
+
+        Ok(Page::Fetched(response.text()))
+    }
+
+    /// Sleep until this site's next request slot, so concurrent fetches
+    /// don't hammer one host
+    async fn wait_for_slot(&self, host: &str) {
+        let now = Instant::now();
+        let start = self
+            .domains
+            .lock()
+            .unwrap()
+            .entry(domain_key(host))
+            .or_insert_with(|| DomainState::new(now))
+            .reserve(now, self.domain_interval);
+        if start > now {
+            tokio::time::sleep(start - now).await;
+        }
+    }
+
+    fn is_skipped(&self, host: &str) -> bool {
+        self.domains
+            .lock()
+            .unwrap()
+            .get(&domain_key(host))
+            .is_some_and(|state| state.is_skipped(Instant::now()))
+    }
+
+    fn record_outcome(&self, host: &str, blocked: bool) {
+        let now = Instant::now();
+        let mut domains = self.domains.lock().unwrap();
+        let state = domains.entry(domain_key(host)).or_insert_with(|| DomainState::new(now));
+        state.record(blocked, now);
+        if blocked && state.blocked == BLOCKED_THRESHOLD {
+            tracing::info!("{} keeps refusing article fetches; skipping it for {:?}", host, BLOCKED_COOLDOWN);
         }
+    }
 
-        let html = response.text().await?;
+    /// Check the site's robots.txt (cached per origin) for `url`'s path
+    async fn robots_allows(&self, url: &Url) -> bool {
+        let origin = url.origin().ascii_serialization();
+        let path = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
 
-        // Extract readable content
-        let content = self.extract_content(&html, article_url);
+        let cached = self
+            .robots_cache
+            .lock()
+            .unwrap()
+            .get(&origin)
+            .filter(|(_, expires)| *expires > Instant::now())
+            .map(|(rules, _)| rules.clone());
+        if let Some(rules) = cached {
+            return rules.is_allowed(&path);
+        }
 
-        Ok(content)
+        let (rules, ttl) = self.fetch_robots(&origin).await;
+        let allowed = rules.is_allowed(&path);
+        self.robots_cache
+            .lock()
+            .unwrap()
+            .insert(origin, (rules, Instant::now() + ttl));
+        allowed
+    }
+
+    /// Per RFC 9309: a missing robots.txt (4xx) allows everything, while a
+    /// server error or unreachable host means stay away for now
+    async fn fetch_robots(&self, origin: &str) -> (RobotsRules, Duration) {
+        let response = self
+            .client
+            .get(format!("{}/robots.txt", origin))
+            .header(USER_AGENT, USER_AGENT_STRING)
+            .send()
+            .await;
+
+        match response {
+            Ok(r) if r.status().is_success() => match r.text().await {
+                Ok(body) => (RobotsRules::parse(&body), ROBOTS_TTL),
+                Err(_) => (RobotsRules::disallow_all(), ROBOTS_ERROR_TTL),
+            },
+            Ok(r) if r.status().is_client_error() => (RobotsRules::default(), ROBOTS_TTL),
+            Ok(r) => {
+                tracing::debug!("robots.txt for {} returned {}", origin, r.status());
+                (RobotsRules::disallow_all(), ROBOTS_ERROR_TTL)
+            }
+            Err(e) => {
+                tracing::debug!("Failed to fetch robots.txt for {}: {}", origin, e);
+                (RobotsRules::disallow_all(), ROBOTS_ERROR_TTL)
+            }
+        }
     }
 
     /// Read cookies from Chrome or Firefox for a given domain
@@ -265,23 +522,7 @@ impl ContentFetcher {
 
     /// Extract readable content from HTML using html2text
     fn extract_content(&self, html: &str, _url: &str) -> Option<String> {
-        // Use html2text to convert HTML to plain text
-        // This avoids the html5ever namespace warnings from readability
-        let text = match html2text::from_read(html.as_bytes(), 80) {
-            Ok(t) => t,
-            Err(e) => {
-                tracing::debug!("Failed to convert HTML to text: {}", e);
-                return None;
-            }
-        };
-
-        // Clean up the text - remove excessive whitespace
-        let cleaned: String = text
-            .lines()
-            .map(|l| l.trim())
-            .filter(|l| !l.is_empty())
-            .collect::<Vec<_>>()
-            .join("\n");
+        let cleaned = html_to_text(html);
 
         if cleaned.len() > 200 {
             Some(cleaned)
@@ -294,6 +535,130 @@ impl ContentFetcher {
 
 impl Default for ContentFetcher {
     fn default() -> Self {
-        Self::new()
+        Self::new(&ContentConfig::default(), true, HttpCache::new(&HttpCacheConfig::default()))
+    }
+}
+
+/// One entry per site, whether or not links use "www."
+fn domain_key(host: &str) -> String {
+    let host = host.to_ascii_lowercase();
+    host.strip_prefix("www.").map(str::to_string).unwrap_or(host)
+}
+
+/// Convert HTML to plain text with blank lines and indentation removed
+fn html_to_text(html: &str) -> String {
+    // Use html2text to convert HTML to plain text
+    // This avoids the html5ever namespace warnings from readability
+    let text = match html2text::from_read(html.as_bytes(), 80) {
+        Ok(t) => t,
+        Err(e) => {
+            tracing::debug!("Failed to convert HTML to text: {}", e);
+            return String::new();
+        }
+    };
+
+    // Clean up the text - remove excessive whitespace
+    text.lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The next page of a multi-page article: the site rule's selector if it has
+/// one, otherwise a `rel="next"` link. Only pages on the same host count.
+fn find_next_page(html: &str, page_url: &Url, rule: Option<&SiteRule>) -> Option<Url> {
+    let selector = match rule.and_then(|r| r.next_page.as_deref()) {
+        Some(css) => match Selector::parse(css) {
+            Ok(selector) => selector,
+            Err(e) => {
+                tracing::warn!("Invalid next_page selector {:?}: {}", css, e);
+                return None;
+            }
+        },
+        None => Selector::parse(r#"link[rel~="next"][href], a[rel~="next"][href]"#).ok()?,
+    };
+
+    let document = Html::parse_document(html);
+    let href = document
+        .select(&selector)
+        .find_map(|element| element.value().attr("href"))?;
+    let next = page_url.join(href.trim()).ok()?;
+
+    (next.host_str() == page_url.host_str() && next != *page_url).then_some(next)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    // ==================== find_next_page ====================
+
+    #[test]
+    fn test_next_page_from_rel_next() {
+        let html = r#"<html><head><link rel="next" href="?page=2"></head><body></body></html>"#;
+        let next = find_next_page(html, &url("https://mag.example/story"), None);
+        assert_eq!(next, Some(url("https://mag.example/story?page=2")));
+    }
+
+    #[test]
+    fn test_next_page_from_site_selector() {
+        let html = r#"<nav><a class="older" href="/a">Older</a><a class="pager-next" href="/story/2">Next</a></nav>"#;
+        let rule = SiteRule {
+            domain: "mag.example".to_string(),
+            next_page: Some("a.pager-next".to_string()),
+            max_pages: 5,
+        };
+        let next = find_next_page(html, &url("https://mag.example/story/1"), Some(&rule));
+        assert_eq!(next, Some(url("https://mag.example/story/2")));
+    }
+
+    #[test]
+    fn test_next_page_ignores_other_hosts_and_self_links() {
+        let offsite = r#"<a rel="next" href="https://ads.example/page2">Next</a>"#;
+        assert_eq!(find_next_page(offsite, &url("https://mag.example/story"), None), None);
+
+        let itself = r#"<a rel="next" href="/story">Next</a>"#;
+        assert_eq!(find_next_page(itself, &url("https://mag.example/story"), None), None);
+
+        let none = "<p>Single-page article</p>";
+        assert_eq!(find_next_page(none, &url("https://mag.example/story"), None), None);
+    }
+
+    // ==================== DomainState ====================
+
+    #[test]
+    fn test_domain_requests_are_spaced_out() {
+        let now = Instant::now();
+        let interval = Duration::from_secs(1);
+        let mut state = DomainState::new(now);
+        assert_eq!(state.reserve(now, interval), now);
+        assert_eq!(state.reserve(now, interval), now + interval);
+        // A slot long past doesn't bank requests
+        let later = now + Duration::from_secs(10);
+        assert_eq!(state.reserve(later, interval), later);
+        assert_eq!(domain_key("WWW.Example.com"), "example.com");
+    }
+
+    #[test]
+    fn test_repeatedly_blocked_domain_is_skipped_until_success() {
+        let now = Instant::now();
+        let mut state = DomainState::new(now);
+        for _ in 1..BLOCKED_THRESHOLD {
+            state.record(true, now);
+        }
+        assert!(!state.is_skipped(now));
+        state.record(true, now);
+        assert!(state.is_skipped(now));
+        assert!(!state.is_skipped(now + BLOCKED_COOLDOWN));
+
+        state.record(false, now);
+        assert!(!state.is_skipped(now));
+        state.record(true, now);
+        assert!(!state.is_skipped(now));
     }
 }
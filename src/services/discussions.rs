@@ -0,0 +1,181 @@
+use std::time::Duration;
+
+use reqwest::Client;
+use serde::Deserialize;
+
+use crate::error::Result;
+use crate::models::Discussion;
+
+const HN_SEARCH_URL: &str = "https://hn.algolia.com/api/v1/search";
+const HN_ITEM_URL: &str = "https://news.ycombinator.com/item?id=";
+const LOBSTERS_URL_LOOKUP: &str = "https://lobste.rs/stories/url/all.json";
+
+#[derive(Debug, Deserialize)]
+struct HnSearchResponse {
+    hits: Vec<HnHit>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HnHit {
+    #[serde(rename = "objectID")]
+    object_id: String,
+    url: Option<String>,
+    points: Option<i64>,
+    num_comments: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LobstersStory {
+    url: String,
+    score: i64,
+    comment_count: i64,
+    comments_url: String,
+}
+
+/// Finds Hacker News and Lobsters threads for article URLs
+#[derive(Clone)]
+pub struct DiscussionClient {
+    client: Client,
+}
+
+impl DiscussionClient {
+    pub fn new() -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(15))
+            .user_agent("beatcheck/1.2.0")
+            .build()
+            .expect("Failed to create HTTP client");
+        Self { client }
+    }
+
+    /// Threads about `url` on either site. A site that can't be reached is
+    /// skipped rather than failing the whole lookup.
+    pub async fn lookup(&self, url: &str) -> Vec<Discussion> {
+        let (hn, lobsters) = tokio::join!(self.lookup_hn(url), self.lookup_lobsters(url));
+        [("Hacker News", hn), ("Lobsters", lobsters)]
+            .into_iter()
+            .filter_map(|(site, result)| match result {
+                Ok(found) => found,
+                Err(e) => {
+                    tracing::debug!("{} lookup failed for {}: {}", site, url, e);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    async fn lookup_hn(&self, url: &str) -> Result<Option<Discussion>> {
+        let response: HnSearchResponse = self
+            .client
+            .get(HN_SEARCH_URL)
+            .query(&[
+                ("query", url),
+                ("restrictSearchableAttributes", "url"),
+                ("tags", "story"),
+            ])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(best_hn_hit(response.hits, url))
+    }
+
+    async fn lookup_lobsters(&self, url: &str) -> Result<Option<Discussion>> {
+        let stories: Vec<LobstersStory> = self
+            .client
+            .get(LOBSTERS_URL_LOOKUP)
+            .query(&[("url", url)])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(best_lobsters_story(stories, url))
+    }
+}
+
+impl Default for DiscussionClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The most-upvoted HN submission of exactly this URL (Algolia's URL search
+/// also returns near misses)
+fn best_hn_hit(hits: Vec<HnHit>, url: &str) -> Option<Discussion> {
+    let wanted = normalize_url(url);
+    hits.into_iter()
+        .filter(|hit| hit.url.as_deref().is_some_and(|u| normalize_url(u) == wanted))
+        .map(|hit| Discussion {
+            site: "hn".to_string(),
+            url: format!("{}{}", HN_ITEM_URL, hit.object_id),
+            points: hit.points.unwrap_or(0),
+            comments: hit.num_comments.unwrap_or(0),
+        })
+        .max_by_key(|d| d.points)
+}
+
+fn best_lobsters_story(stories: Vec<LobstersStory>, url: &str) -> Option<Discussion> {
+    let wanted = normalize_url(url);
+    stories
+        .into_iter()
+        .filter(|story| normalize_url(&story.url) == wanted)
+        .map(|story| Discussion {
+            site: "lobsters".to_string(),
+            url: story.comments_url,
+            points: story.score,
+            comments: story.comment_count,
+        })
+        .max_by_key(|d| d.points)
+}
+
+/// Compare URLs the way aggregators dedupe them: ignore the scheme, a
+/// leading "www.", the fragment and a trailing slash
+fn normalize_url(url: &str) -> String {
+    let url = url.trim();
+    let url = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let url = url.strip_prefix("www.").unwrap_or(url);
+    let url = url.split('#').next().unwrap_or(url);
+    url.trim_end_matches('/').to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_url() {
+        assert_eq!(
+            normalize_url("https://www.Example.com/post/#comments"),
+            normalize_url("http://example.com/post")
+        );
+        assert_ne!(normalize_url("https://example.com/a"), normalize_url("https://example.com/b"));
+    }
+
+    #[test]
+    fn test_best_hn_hit_requires_matching_url() {
+        let json = r#"{"hits": [
+            {"objectID": "1", "url": "https://example.com/post-2", "points": 900, "num_comments": 400},
+            {"objectID": "2", "url": "https://example.com/post", "points": 120, "num_comments": 45},
+            {"objectID": "3", "url": "http://www.example.com/post/", "points": 3, "num_comments": null}
+        ]}"#;
+        let response: HnSearchResponse = serde_json::from_str(json).unwrap();
+        let best = best_hn_hit(response.hits, "https://example.com/post").unwrap();
+        assert_eq!(best.url, "https://news.ycombinator.com/item?id=2");
+        assert_eq!((best.points, best.comments), (120, 45));
+
+        assert_eq!(best_hn_hit(Vec::new(), "https://example.com/post"), None);
+    }
+
+    #[test]
+    fn test_best_lobsters_story() {
+        let json = r#"[{"url": "https://example.com/post", "score": 31, "comment_count": 12,
+                        "comments_url": "https://lobste.rs/s/abc123/post", "short_id": "abc123"}]"#;
+        let stories: Vec<LobstersStory> = serde_json::from_str(json).unwrap();
+        let best = best_lobsters_story(stories, "https://example.com/post").unwrap();
+        assert_eq!(best.site, "lobsters");
+        assert_eq!(best.url, "https://lobste.rs/s/abc123/post");
+        assert_eq!((best.points, best.comments), (31, 12));
+    }
+}
@@ -0,0 +1,85 @@
+use std::collections::VecDeque;
+
+/// Work grouped by site and handed out in turn, so a big backlog from one
+/// site doesn't hold up the rest and no site has more than `per_site`
+/// requests in flight. Whichever worker frees up first takes the next item.
+#[derive(Debug)]
+pub struct FairQueue<T> {
+    /// Sites in rotation order, with their waiting items and how many are
+    /// in flight
+    sites: VecDeque<(String, VecDeque<T>, usize)>,
+    per_site: usize,
+}
+
+impl<T> FairQueue<T> {
+    pub fn new(per_site: usize) -> Self {
+        Self {
+            sites: VecDeque::new(),
+            per_site: per_site.max(1),
+        }
+    }
+
+    pub fn push(&mut self, site: &str, item: T) {
+        match self.sites.iter_mut().find(|(s, _, _)| s == site) {
+            Some((_, items, _)) => items.push_back(item),
+            None => self.sites.push_back((site.to_string(), VecDeque::from([item]), 0)),
+        }
+    }
+
+    /// The next item from the first site in the rotation with room for
+    /// another request; that site goes to the back. `None` when every site
+    /// with work waiting is busy (its worker will take the next one).
+    pub fn claim(&mut self) -> Option<(String, T)> {
+        let index = self
+            .sites
+            .iter()
+            .position(|(_, items, in_flight)| !items.is_empty() && *in_flight < self.per_site)?;
+        let (site, mut items, in_flight) = self.sites.remove(index)?;
+        let item = items.pop_front()?;
+        self.sites.push_back((site.clone(), items, in_flight + 1));
+        Some((site, item))
+    }
+
+    /// A request to `site` finished
+    pub fn done(&mut self, site: &str) {
+        if let Some(index) = self.sites.iter().position(|(s, _, _)| s == site) {
+            let (_, items, in_flight) = &mut self.sites[index];
+            *in_flight = in_flight.saturating_sub(1);
+            if *in_flight == 0 && items.is_empty() {
+                self.sites.remove(index);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sites_take_turns_one_request_each() {
+        let mut queue = FairQueue::new(1);
+        for i in 1..=3 {
+            queue.push("big.example", i);
+        }
+        queue.push("small.example", 10);
+        queue.push("other.example", 20);
+
+        assert_eq!(queue.claim(), Some(("big.example".to_string(), 1)));
+        assert_eq!(queue.claim(), Some(("small.example".to_string(), 10)));
+        assert_eq!(queue.claim(), Some(("other.example".to_string(), 20)));
+        // big.example is busy, and the others are out of work
+        assert_eq!(queue.claim(), None);
+
+        queue.done("small.example");
+        assert_eq!(queue.claim(), None);
+        queue.done("big.example");
+        assert_eq!(queue.claim(), Some(("big.example".to_string(), 2)));
+        queue.done("big.example");
+        queue.done("other.example");
+        assert_eq!(queue.claim(), Some(("big.example".to_string(), 3)));
+        queue.done("big.example");
+        assert_eq!(queue.claim(), None);
+        assert!(queue.sites.is_empty());
+    }
+}
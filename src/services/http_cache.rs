@@ -0,0 +1,319 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Duration, Utc};
+use reqwest::header::{
+    HeaderMap, CACHE_CONTROL, CONTENT_TYPE, DATE, ETAG, EXPIRES, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+use reqwest::{RequestBuilder, StatusCode};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::config::HttpCacheConfig;
+use crate::error::Result;
+
+/// Responses bigger than this are passed through without being kept
+const MAX_ENTRY_BYTES: usize = 10 * 1024 * 1024;
+
+/// Without Cache-Control or Expires, a page is trusted for a tenth of the
+/// time since it last changed, up to this long
+const MAX_HEURISTIC_SECS: i64 = 24 * 60 * 60;
+
+/// What's kept next to a cached body, in `<key>.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    url: String,
+    content_type: Option<String>,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    fresh_until: DateTime<Utc>,
+}
+
+/// A response, from the network or the cache
+#[derive(Debug, Clone)]
+pub struct CachedResponse {
+    pub status: StatusCode,
+    /// The MIME type, without parameters
+    pub content_type: Option<String>,
+    pub body: Vec<u8>,
+}
+
+impl CachedResponse {
+    pub fn is_success(&self) -> bool {
+        self.status.is_success()
+    }
+
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
+
+/// On-disk HTTP cache for article pages and images, content-addressed by
+/// URL. Fresh entries (per Cache-Control, Expires, or a guess from
+/// Last-Modified) are served without a request; stale ones are revalidated
+/// with If-None-Match/If-Modified-Since. Writes are best effort.
+#[derive(Debug, Clone)]
+pub struct HttpCache {
+    /// `None` when the cache is off
+    dir: Option<PathBuf>,
+    max_bytes: u64,
+}
+
+impl HttpCache {
+    pub fn new(config: &HttpCacheConfig) -> Self {
+        Self {
+            dir: config.enabled.then(|| config.dir()),
+            max_bytes: config.max_mb * 1024 * 1024,
+        }
+    }
+
+    /// The cached response for `url` if it's still fresh
+    pub async fn fresh(&self, url: &str) -> Option<CachedResponse> {
+        let (entry, body) = self.load(url).await?;
+        (entry.fresh_until > Utc::now()).then(|| cached_response(&entry, body))
+    }
+
+    /// Send `request` (a GET of `url`) unless a fresh copy is cached,
+    /// asking only for changes when a stale one is
+    pub async fn fetch(&self, url: &str, mut request: RequestBuilder) -> Result<CachedResponse> {
+        let cached = self.load(url).await;
+        if let Some((entry, body)) = &cached {
+            if entry.fresh_until > Utc::now() {
+                return Ok(cached_response(entry, body.clone()));
+            }
+            if let Some(etag) = &entry.etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(modified) = &entry.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, modified);
+            }
+        }
+
+        let response = request.send().await?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        if let (StatusCode::NOT_MODIFIED, Some((mut entry, body))) = (status, cached) {
+            entry.fresh_until = Utc::now() + freshness(&headers, Utc::now()).unwrap_or_else(Duration::zero);
+            self.save(url, &entry, None).await;
+            return Ok(cached_response(&entry, body));
+        }
+
+        let body = response.bytes().await?.to_vec();
+        let content_type = headers
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.split(';').next().unwrap_or(v).trim().to_lowercase());
+        if status.is_success() && body.len() <= MAX_ENTRY_BYTES {
+            if let Some(lifetime) = freshness(&headers, Utc::now()) {
+                let header = |name| headers.get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+                let entry = Entry {
+                    url: url.to_string(),
+                    content_type: content_type.clone(),
+                    etag: header(ETAG),
+                    last_modified: header(LAST_MODIFIED),
+                    fresh_until: Utc::now() + lifetime,
+                };
+                self.save(url, &entry, Some(&body)).await;
+            }
+        }
+        Ok(CachedResponse {
+            status,
+            content_type,
+            body,
+        })
+    }
+
+    /// Remove the least recently stored entries until the cache fits in
+    /// `max_mb`. Returns how many went.
+    pub async fn prune(&self) -> Result<usize> {
+        let Some(dir) = &self.dir else {
+            return Ok(0);
+        };
+        let mut read_dir = match tokio::fs::read_dir(dir).await {
+            Ok(read_dir) => read_dir,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e.into()),
+        };
+        // (key, bytes, last written) per entry, body and metadata together
+        let mut entries: Vec<(String, u64, std::time::SystemTime)> = Vec::new();
+        while let Some(file) = read_dir.next_entry().await? {
+            let path = file.path();
+            let (Some(key), Ok(metadata)) = (path.file_stem().and_then(|s| s.to_str()), file.metadata().await) else {
+                continue;
+            };
+            let modified = metadata.modified()?;
+            match entries.iter_mut().find(|(k, _, _)| k == key) {
+                Some((_, bytes, written)) => {
+                    *bytes += metadata.len();
+                    *written = (*written).max(modified);
+                }
+                None => entries.push((key.to_string(), metadata.len(), modified)),
+            }
+        }
+
+        let mut total: u64 = entries.iter().map(|(_, bytes, _)| bytes).sum();
+        entries.sort_by_key(|(_, _, written)| *written);
+        let mut removed = 0;
+        for (key, bytes, _) in entries {
+            if total <= self.max_bytes {
+                break;
+            }
+            for ext in ["json", "body"] {
+                let _ = tokio::fs::remove_file(dir.join(format!("{}.{}", key, ext))).await;
+            }
+            total = total.saturating_sub(bytes);
+            removed += 1;
+        }
+        if removed > 0 {
+            tracing::debug!("Pruned {} entries from the HTTP cache", removed);
+        }
+        Ok(removed)
+    }
+
+    fn paths(&self, url: &str) -> Option<(PathBuf, PathBuf)> {
+        let dir = self.dir.as_ref()?;
+        let key: String = Sha256::digest(url.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect();
+        Some((dir.join(format!("{}.json", key)), dir.join(format!("{}.body", key))))
+    }
+
+    async fn load(&self, url: &str) -> Option<(Entry, Vec<u8>)> {
+        let (meta, body) = self.paths(url)?;
+        let entry: Entry = serde_json::from_slice(&tokio::fs::read(&meta).await.ok()?).ok()?;
+        // A different URL with the same hash is as good as a miss
+        if entry.url != url {
+            return None;
+        }
+        Some((entry, tokio::fs::read(&body).await.ok()?))
+    }
+
+    /// Write the entry, and its body if it changed
+    async fn save(&self, url: &str, entry: &Entry, body: Option<&[u8]>) {
+        let Some((meta, body_path)) = self.paths(url) else {
+            return;
+        };
+        if let Err(e) = write_entry(&meta, &body_path, entry, body).await {
+            tracing::debug!("Failed to cache {}: {}", url, e);
+        }
+    }
+}
+
+async fn write_entry(meta: &Path, body_path: &Path, entry: &Entry, body: Option<&[u8]>) -> Result<()> {
+    if let Some(parent) = meta.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    if let Some(body) = body {
+        tokio::fs::write(body_path, body).await?;
+    }
+    tokio::fs::write(meta, serde_json::to_vec(entry)?).await?;
+    Ok(())
+}
+
+fn cached_response(entry: &Entry, body: Vec<u8>) -> CachedResponse {
+    CachedResponse {
+        status: StatusCode::OK,
+        content_type: entry.content_type.clone(),
+        body,
+    }
+}
+
+/// How long a response may be served without asking again, or `None` if
+/// it mustn't be (or can't usefully be) kept. Zero means keep it but
+/// revalidate every time.
+fn freshness(headers: &HeaderMap, now: DateTime<Utc>) -> Option<Duration> {
+    let header = |name| headers.get(name).and_then(|v| v.to_str().ok());
+    let date = |name| header(name).and_then(|v| DateTime::parse_from_rfc2822(v).ok()).map(|d| d.with_timezone(&Utc));
+    let validated = header(ETAG).is_some() || header(LAST_MODIFIED).is_some();
+
+    if let Some(cache_control) = header(CACHE_CONTROL) {
+        let directives: Vec<String> = cache_control.split(',').map(|d| d.trim().to_lowercase()).collect();
+        if directives.iter().any(|d| d == "no-store") {
+            return None;
+        }
+        if directives.iter().any(|d| d == "no-cache") {
+            return validated.then(Duration::zero);
+        }
+        let max_age = directives
+            .iter()
+            .find_map(|d| d.strip_prefix("max-age=")?.trim_matches('"').parse::<i64>().ok());
+        if let Some(max_age) = max_age {
+            return (max_age > 0 || validated).then(|| Duration::seconds(max_age.max(0)));
+        }
+    }
+    if let Some(expires) = header(EXPIRES) {
+        let lifetime = DateTime::parse_from_rfc2822(expires)
+            .map(|e| e.with_timezone(&Utc) - date(DATE).unwrap_or(now))
+            .unwrap_or_else(|_| Duration::zero());
+        return (lifetime > Duration::zero() || validated).then(|| lifetime.max(Duration::zero()));
+    }
+    if let Some(modified) = date(LAST_MODIFIED) {
+        let age = (date(DATE).unwrap_or(now) - modified).num_seconds().max(0);
+        return Some(Duration::seconds((age / 10).min(MAX_HEURISTIC_SECS)));
+    }
+    validated.then(Duration::zero)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn headers(pairs: &[(reqwest::header::HeaderName, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(name.clone(), HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_freshness_from_cache_headers() {
+        let now = DateTime::parse_from_rfc3339("2026-03-02T12:00:00Z").unwrap().with_timezone(&Utc);
+        let fresh = |pairs: &[(reqwest::header::HeaderName, &str)]| freshness(&headers(pairs), now);
+
+        assert_eq!(fresh(&[(CACHE_CONTROL, "public, max-age=600")]), Some(Duration::seconds(600)));
+        assert_eq!(fresh(&[(CACHE_CONTROL, "no-store"), (ETAG, "\"a\"")]), None);
+        // Kept only to revalidate, which needs a validator
+        assert_eq!(fresh(&[(CACHE_CONTROL, "no-cache"), (ETAG, "\"a\"")]), Some(Duration::zero()));
+        assert_eq!(fresh(&[(CACHE_CONTROL, "no-cache")]), None);
+        assert_eq!(fresh(&[(CACHE_CONTROL, "max-age=0")]), None);
+        assert_eq!(
+            fresh(&[(EXPIRES, "Mon, 02 Mar 2026 13:00:00 GMT"), (DATE, "Mon, 02 Mar 2026 12:00:00 GMT")]),
+            Some(Duration::hours(1))
+        );
+        // A tenth of the time since it changed, at most a day
+        assert_eq!(fresh(&[(LAST_MODIFIED, "Mon, 02 Mar 2026 02:00:00 GMT")]), Some(Duration::hours(1)));
+        assert_eq!(fresh(&[(LAST_MODIFIED, "Thu, 01 Jan 2026 00:00:00 GMT")]), Some(Duration::days(1)));
+        assert_eq!(fresh(&[(ETAG, "\"a\"")]), Some(Duration::zero()));
+        assert_eq!(fresh(&[]), None);
+    }
+
+    #[tokio::test]
+    async fn test_entries_read_back_and_prune_oldest_first() {
+        let dir = std::env::temp_dir().join(format!("beatcheck-http-cache-{}", std::process::id()));
+        let cache = HttpCache {
+            dir: Some(dir.clone()),
+            max_bytes: 2048,
+        };
+        let entry = |url: &str, fresh_until| Entry {
+            url: url.to_string(),
+            content_type: Some("text/html".to_string()),
+            etag: None,
+            last_modified: None,
+            fresh_until,
+        };
+        let later = Utc::now() + Duration::hours(1);
+        cache.save("https://example.com/old", &entry("https://example.com/old", later), Some(&[b'a'; 1500])).await;
+        cache.save("https://example.com/stale", &entry("https://example.com/stale", Utc::now()), Some(b"b")).await;
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        cache.save("https://example.com/new", &entry("https://example.com/new", later), Some(&[b'c'; 1000])).await;
+
+        assert!(cache.fresh("https://example.com/stale").await.is_none());
+        assert!(cache.fresh("https://example.com/missing").await.is_none());
+        let page = cache.fresh("https://example.com/new").await.unwrap();
+        assert_eq!((page.content_type.as_deref(), page.body.len()), (Some("text/html"), 1000));
+
+        assert!(cache.prune().await.unwrap() >= 1);
+        assert!(cache.fresh("https://example.com/old").await.is_none());
+        assert!(cache.fresh("https://example.com/new").await.is_some());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
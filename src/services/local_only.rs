@@ -0,0 +1,104 @@
+//! Strict local mode: HTTP clients that can only reach this machine and
+//! its private network.
+
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::{ClientBuilder, Proxy};
+use url::{Host, Url};
+
+/// Where requests to public IP addresses are sent: a name the resolver
+/// refuses, so they fail before connecting
+pub(super) const BLOCKED_PROXY: &str = "http://blocked.local-only.invalid";
+
+/// Limit `builder`'s client to local hosts. Hostnames only resolve to
+/// loopback and private addresses, and public IP addresses in URLs go to
+/// a proxy that can't be reached, so nothing is sent anywhere else.
+pub fn restrict_to_local(builder: ClientBuilder) -> ClientBuilder {
+    builder
+        .dns_resolver(Arc::new(LocalResolver))
+        .proxy(Proxy::custom(|url| match url.host() {
+            Some(Host::Ipv4(ip)) if !is_local_ip(ip.into()) => Some(BLOCKED_PROXY),
+            Some(Host::Ipv6(ip)) if !is_local_ip(ip.into()) => Some(BLOCKED_PROXY),
+            _ => None,
+        }))
+}
+
+/// Whether `url` is known to point at this machine or the private network
+/// without resolving it: localhost, or a loopback or private address
+pub fn is_local_url(url: &Url) -> bool {
+    match url.host() {
+        Some(Host::Domain(domain)) => domain == "localhost" || domain.ends_with(".localhost"),
+        Some(Host::Ipv4(ip)) => is_local_ip(ip.into()),
+        Some(Host::Ipv6(ip)) => is_local_ip(ip.into()),
+        None => false,
+    }
+}
+
+fn is_local_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => ip.is_loopback() || ip.is_private() || ip.is_link_local(),
+        IpAddr::V6(ip) => {
+            if let Some(v4) = ip.to_ipv4_mapped() {
+                return is_local_ip(v4.into());
+            }
+            // Unique local (fc00::/7) and link-local (fe80::/10)
+            let first = ip.segments()[0];
+            ip.is_loopback() || first & 0xfe00 == 0xfc00 || first & 0xffc0 == 0xfe80
+        }
+    }
+}
+
+/// System DNS, keeping only local addresses
+struct LocalResolver;
+
+impl Resolve for LocalResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let blocked = || format!("local-only mode: {} is not on this machine or network", host);
+            if host.ends_with(".invalid") {
+                return Err(blocked().into());
+            }
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|addr| is_local_ip(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(blocked().into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_local_url() {
+        for local in [
+            "http://localhost:11434",
+            "http://127.0.0.1:8080/v1",
+            "http://192.168.1.20:11434",
+            "http://10.0.0.5",
+            "http://[::1]:11434",
+            "http://[fd12::1]/",
+        ] {
+            assert!(is_local_url(&Url::parse(local).unwrap()), "{}", local);
+        }
+        for remote in ["https://api.anthropic.com", "http://8.8.8.8", "http://[2001:db8::1]/"] {
+            assert!(!is_local_url(&Url::parse(remote).unwrap()), "{}", remote);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_restricted_client_refuses_public_addresses() {
+        let client = restrict_to_local(reqwest::Client::builder()).build().unwrap();
+        // TEST-NET-1: routed to the blocked proxy rather than connected to
+        assert!(client.get("http://192.0.2.1/").send().await.is_err());
+        assert!(client.get("https://blocked.local-only.invalid/").send().await.is_err());
+    }
+}
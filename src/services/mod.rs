@@ -1,5 +1,22 @@
+mod archiver;
 mod content_fetcher;
+mod discussions;
+mod fair_queue;
+mod http_cache;
+mod local_only;
+mod player;
+mod public_only;
 mod raindrop;
+mod removals;
+mod robots;
 
+pub use archiver::Archiver;
 pub use content_fetcher::ContentFetcher;
+pub use discussions::DiscussionClient;
+pub use fair_queue::FairQueue;
+pub use http_cache::HttpCache;
+pub use local_only::{is_local_url, restrict_to_local};
+pub use player::{Playback, Player};
+pub use public_only::restrict_to_public;
 pub use raindrop::RaindropClient;
+pub use removals::RemovalChecker;
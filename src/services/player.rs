@@ -0,0 +1,192 @@
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixStream;
+use tokio::process::{Child, Command};
+
+use crate::error::{AppError, Result};
+
+/// How long to wait on the player's IPC socket before giving up on a query
+const IPC_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Launches the configured external player for podcast enclosures
+pub struct Player {
+    template: String,
+}
+
+impl Player {
+    pub fn new(template: &str) -> Self {
+        Self {
+            template: template.to_string(),
+        }
+    }
+
+    /// Start playing `url` from `start_secs`. The player runs detached from
+    /// the terminal so it doesn't draw over the TUI.
+    pub fn play(&self, url: &str, start_secs: u32) -> Result<Playback> {
+        let ipc = std::env::temp_dir().join(format!("beatcheck-player-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&ipc);
+
+        let args = player_args(&self.template, url, start_secs, &ipc);
+        let (program, rest) = args
+            .split_first()
+            .ok_or_else(|| AppError::Config("podcast.player is empty".to_string()))?;
+
+        let child = Command::new(program)
+            .args(rest)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()?;
+
+        Ok(Playback {
+            child,
+            ipc: self.template.contains("{ipc}").then_some(ipc),
+            position_secs: start_secs,
+            duration_secs: None,
+        })
+    }
+}
+
+/// A running player process
+pub struct Playback {
+    child: Child,
+    ipc: Option<PathBuf>,
+    /// Last position reported by the player (the start position until then)
+    pub position_secs: u32,
+    /// Length of the episode, once the player knows it
+    pub duration_secs: Option<u32>,
+}
+
+impl Playback {
+    /// Ask the player where it is. Without an IPC socket in the command, the
+    /// position stays where playback started.
+    pub async fn update_position(&mut self) {
+        let Some(ipc) = &self.ipc else {
+            return;
+        };
+        if let Some(secs) = query_property(ipc, "time-pos").await {
+            self.position_secs = secs as u32;
+        }
+        if self.duration_secs.is_none() {
+            self.duration_secs = query_property(ipc, "duration").await.map(|secs| secs as u32);
+        }
+    }
+
+    /// Whether the player has quit; `Some(true)` if it exited cleanly
+    pub fn exited(&mut self) -> Option<bool> {
+        match self.child.try_wait() {
+            Ok(Some(status)) => Some(status.success()),
+            Ok(None) => None,
+            Err(_) => Some(false),
+        }
+    }
+
+    pub async fn stop(&mut self) {
+        let _ = self.child.kill().await;
+    }
+}
+
+impl Drop for Playback {
+    fn drop(&mut self) {
+        if let Some(ipc) = &self.ipc {
+            let _ = std::fs::remove_file(ipc);
+        }
+    }
+}
+
+/// Split the template on whitespace and fill in each argument separately, so
+/// URLs never get re-split or shell-interpreted
+fn player_args(template: &str, url: &str, start_secs: u32, ipc: &Path) -> Vec<String> {
+    let start = start_secs.to_string();
+    let ipc = ipc.to_string_lossy();
+    template
+        .split_whitespace()
+        .map(|arg| {
+            arg.replace("{url}", url)
+                .replace("{start}", &start)
+                .replace("{ipc}", &ipc)
+        })
+        .collect()
+}
+
+async fn query_property(ipc: &Path, name: &str) -> Option<f64> {
+    match tokio::time::timeout(IPC_TIMEOUT, get_property(ipc, name)).await {
+        Ok(Ok(value)) => value,
+        // The socket appears a moment after launch; just try again later
+        Ok(Err(e)) => {
+            tracing::debug!("Player IPC query failed: {}", e);
+            None
+        }
+        Err(_) => {
+            tracing::debug!("Player IPC query timed out");
+            None
+        }
+    }
+}
+
+/// mpv JSON IPC `get_property` for a numeric property. Replies can be
+/// interleaved with event lines, so match on the request id.
+async fn get_property(ipc: &Path, name: &str) -> std::io::Result<Option<f64>> {
+    let stream = UnixStream::connect(ipc).await?;
+    let (reader, mut writer) = stream.into_split();
+    let request = serde_json::json!({ "command": ["get_property", name], "request_id": 1 });
+    writer.write_all(format!("{}\n", request).as_bytes()).await?;
+
+    let mut lines = BufReader::new(reader).lines();
+    while let Some(line) = lines.next_line().await? {
+        let Ok(reply) = serde_json::from_str::<serde_json::Value>(&line) else {
+            continue;
+        };
+        if reply["request_id"] == 1 {
+            return Ok(reply["data"].as_f64());
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_player_args_fill_placeholders_per_argument() {
+        let args = player_args(
+            "mpv --start={start} --input-ipc-server={ipc} {url}",
+            "https://cdn.example/ep 1.mp3",
+            95,
+            Path::new("/tmp/bc.sock"),
+        );
+        assert_eq!(
+            args,
+            vec![
+                "mpv",
+                "--start=95",
+                "--input-ipc-server=/tmp/bc.sock",
+                "https://cdn.example/ep 1.mp3",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_property_skips_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mpv.sock");
+        let listener = tokio::net::UnixListener::bind(&path).unwrap();
+
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = vec![0u8; 128];
+            let _ = tokio::io::AsyncReadExt::read(&mut stream, &mut request).await;
+            stream
+                .write_all(b"{\"event\":\"playback-restart\"}\n{\"data\":42.5,\"request_id\":1,\"error\":\"success\"}\n")
+                .await
+                .unwrap();
+        });
+
+        assert_eq!(get_property(&path, "time-pos").await.unwrap(), Some(42.5));
+    }
+}
@@ -0,0 +1,113 @@
+//! HTTP clients for URLs that come from outside, such as images in article
+//! content and pages the web UI asks about: they may only reach public
+//! addresses, never this machine or its network.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::{ClientBuilder, Proxy};
+use url::Host;
+
+use super::local_only::BLOCKED_PROXY;
+
+/// Limit `builder`'s client to public hosts. Hostnames only resolve to
+/// public addresses and other IP addresses in URLs go to a proxy that
+/// can't be reached, so redirects can't lead anywhere else either.
+pub fn restrict_to_public(builder: ClientBuilder) -> ClientBuilder {
+    builder
+        .dns_resolver(Arc::new(PublicResolver))
+        .proxy(Proxy::custom(|url| match url.host() {
+            Some(Host::Ipv4(ip)) if !is_public_ip(ip.into()) => Some(BLOCKED_PROXY),
+            Some(Host::Ipv6(ip)) if !is_public_ip(ip.into()) => Some(BLOCKED_PROXY),
+            _ => None,
+        }))
+}
+
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => {
+            if let Some(v4) = ip.to_ipv4_mapped() {
+                return is_public_ipv4(v4);
+            }
+            is_public_ipv6(ip)
+        }
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        || a == 0
+        // Carrier-grade NAT (100.64.0.0/10), protocol assignments
+        // (192.0.0.0/24), benchmarking (198.18.0.0/15) and reserved (240/4)
+        || (a == 100 && b & 0xc0 == 64)
+        || (a == 192 && b == 0 && ip.octets()[2] == 0)
+        || (a == 198 && b & 0xfe == 18)
+        || a >= 240)
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    // Unique local (fc00::/7), link-local (fe80::/10), documentation
+    // (2001:db8::/32), and NAT64 (64:ff9b::/96) checked as IPv4
+    if first == 0x64 && ip.segments()[1] == 0xff9b {
+        let [.., a, b, c, d] = ip.octets();
+        return is_public_ipv4(Ipv4Addr::new(a, b, c, d));
+    }
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        || ip.to_ipv4().is_some()
+        || first & 0xfe00 == 0xfc00
+        || first & 0xffc0 == 0xfe80
+        || (first == 0x2001 && ip.segments()[1] == 0xdb8))
+}
+
+/// System DNS, keeping only public addresses
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let blocked = || format!("{} is not a public address", host);
+            if host.ends_with(".invalid") {
+                return Err(blocked().into());
+            }
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|addr| is_public_ip(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(blocked().into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_restricted_client_refuses_private_addresses() {
+        for private in ["0.0.0.0", "10.1.2.3", "100.100.1.1", "169.254.169.254", "::1", "::ffff:192.168.1.1", "fd00::1"] {
+            assert!(!is_public_ip(private.parse().unwrap()), "{}", private);
+        }
+        for public in ["93.184.216.34", "2606:4700::1111"] {
+            assert!(is_public_ip(public.parse().unwrap()), "{}", public);
+        }
+        let client = restrict_to_public(reqwest::Client::builder()).build().unwrap();
+        assert!(client.get("http://127.0.0.1:9/").send().await.is_err());
+        assert!(client.get("http://localhost:9/").send().await.is_err());
+    }
+}
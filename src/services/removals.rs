@@ -0,0 +1,78 @@
+use std::time::Duration;
+
+use reqwest::{Client, StatusCode};
+use url::Url;
+
+use crate::error::Result;
+
+const USER_AGENT: &str =
+    "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+
+/// Re-requests saved articles' pages to spot ones that were taken down
+#[derive(Clone)]
+pub struct RemovalChecker {
+    client: Client,
+}
+
+impl RemovalChecker {
+    pub fn new() -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(20))
+            .user_agent(USER_AGENT)
+            .build()
+            .expect("Failed to create HTTP client");
+        Self { client }
+    }
+
+    /// Whether the page at `url` is gone. Errors (the site being down, a
+    /// timeout) say nothing either way, so the caller checks again later.
+    pub async fn is_removed(&self, url: &str) -> Result<bool> {
+        let requested = Url::parse(url).map_err(|e| anyhow::anyhow!("Bad article URL {}: {}", url, e))?;
+        let response = self.client.get(requested.clone()).send().await?;
+        Ok(is_taken_down(&requested, response.status(), response.url()))
+    }
+}
+
+impl Default for RemovalChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A 404 or 410, or an article link that now lands on a front page
+pub fn is_taken_down(requested: &Url, status: StatusCode, landed: &Url) -> bool {
+    if status == StatusCode::NOT_FOUND || status == StatusCode::GONE {
+        return true;
+    }
+    status.is_success() && is_front_page(landed) && !is_front_page(requested)
+}
+
+fn is_front_page(url: &Url) -> bool {
+    matches!(url.path().trim_end_matches('/'), "" | "/index.html" | "/index.php" | "/home")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_gone_pages_and_redirects_home_count_as_taken_down() {
+        let article = url("https://news.example.com/2026/03/layoffs");
+        assert!(is_taken_down(&article, StatusCode::NOT_FOUND, &article));
+        assert!(is_taken_down(&article, StatusCode::GONE, &article));
+        assert!(is_taken_down(&article, StatusCode::OK, &url("https://news.example.com/")));
+        assert!(is_taken_down(&article, StatusCode::OK, &url("https://www.example.com/index.html")));
+
+        assert!(!is_taken_down(&article, StatusCode::OK, &article));
+        assert!(!is_taken_down(&article, StatusCode::OK, &url("https://news.example.com/2026/03/layoffs-update")));
+        // Down for now, not gone
+        assert!(!is_taken_down(&article, StatusCode::SERVICE_UNAVAILABLE, &article));
+        // A front page that was saved stays one
+        let home = url("https://blog.example.com/");
+        assert!(!is_taken_down(&home, StatusCode::OK, &home));
+    }
+}
@@ -0,0 +1,168 @@
+/// Product token we look for in robots.txt before falling back to `*`
+const ROBOTS_AGENT: &str = "beatcheck";
+
+/// Allow/Disallow rules from the robots.txt group that applies to us
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RobotsRules {
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Rule {
+    allow: bool,
+    pattern: String,
+}
+
+impl RobotsRules {
+    /// Rules that block every path (used when robots.txt is unreachable)
+    pub fn disallow_all() -> Self {
+        Self {
+            rules: vec![Rule {
+                allow: false,
+                pattern: "/".to_string(),
+            }],
+        }
+    }
+
+    /// Parse robots.txt, keeping the group for our agent if there is one and
+    /// the `*` group otherwise
+    pub fn parse(content: &str) -> Self {
+        let mut ours = Vec::new();
+        let mut wildcard = Vec::new();
+        let mut found_ours = false;
+
+        // Consecutive User-agent lines share the rules that follow them
+        let mut agents: Vec<String> = Vec::new();
+        let mut in_rules = false;
+
+        for line in content.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let key = key.trim().to_ascii_lowercase();
+            let value = value.trim();
+
+            match key.as_str() {
+                "user-agent" => {
+                    if in_rules {
+                        agents.clear();
+                        in_rules = false;
+                    }
+                    let agent = value.to_ascii_lowercase();
+                    found_ours |= agent == ROBOTS_AGENT;
+                    agents.push(agent);
+                }
+                "allow" | "disallow" => {
+                    in_rules = true;
+                    // An empty Disallow means "allow everything"
+                    if value.is_empty() {
+                        continue;
+                    }
+                    let rule = Rule {
+                        allow: key == "allow",
+                        pattern: value.to_string(),
+                    };
+                    if agents.iter().any(|a| a == ROBOTS_AGENT) {
+                        ours.push(rule.clone());
+                    }
+                    if agents.iter().any(|a| a == "*") {
+                        wildcard.push(rule);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Self {
+            rules: if found_ours { ours } else { wildcard },
+        }
+    }
+
+    /// Whether `path` (including any query string) may be fetched. The most
+    /// specific matching rule wins; on a tie, Allow wins.
+    pub fn is_allowed(&self, path: &str) -> bool {
+        self.rules
+            .iter()
+            .filter(|rule| pattern_matches(&rule.pattern, path))
+            .max_by_key(|rule| (rule.pattern.len(), rule.allow))
+            .is_none_or(|rule| rule.allow)
+    }
+}
+
+/// robots.txt path matching: prefix match, `*` matches any run of
+/// characters, and a trailing `$` anchors the end
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(p) => (p, true),
+        None => (pattern, false),
+    };
+
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = path.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts: Vec<&str> = parts.collect();
+    for (i, part) in parts.iter().enumerate() {
+        let is_last = i == parts.len() - 1;
+        if is_last && anchored {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+
+    !anchored || rest.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wildcard_group_applies_by_default() {
+        let rules = RobotsRules::parse(
+            "User-agent: *\nDisallow: /private/\n\nUser-agent: Googlebot\nDisallow: /",
+        );
+        assert!(rules.is_allowed("/2026/01/story"));
+        assert!(!rules.is_allowed("/private/page"));
+    }
+
+    #[test]
+    fn test_own_group_overrides_wildcard() {
+        let rules = RobotsRules::parse(
+            "User-agent: *\nDisallow: /\n\nUser-agent: BeatCheck\nUser-agent: other\nAllow: /\n",
+        );
+        assert!(rules.is_allowed("/anything"));
+    }
+
+    #[test]
+    fn test_longest_match_wins_and_allow_breaks_ties() {
+        let rules = RobotsRules::parse(
+            "User-agent: *\nDisallow: /news\nAllow: /news/public\nDisallow: /tie\nAllow: /tie\n",
+        );
+        assert!(!rules.is_allowed("/news/secret"));
+        assert!(rules.is_allowed("/news/public/1"));
+        assert!(rules.is_allowed("/tie"));
+    }
+
+    #[test]
+    fn test_wildcards_and_end_anchor() {
+        let rules = RobotsRules::parse("User-agent: *\nDisallow: /*.pdf$\nDisallow: /*?print=\n");
+        assert!(!rules.is_allowed("/files/report.pdf"));
+        assert!(rules.is_allowed("/files/report.pdf.html"));
+        assert!(!rules.is_allowed("/story?print=1"));
+        assert!(rules.is_allowed("/story?page=2"));
+    }
+
+    #[test]
+    fn test_empty_disallow_and_comments() {
+        let rules = RobotsRules::parse("# hi\nUser-agent: * # everyone\nDisallow:\n");
+        assert!(rules.is_allowed("/"));
+        assert!(!RobotsRules::disallow_all().is_allowed("/"));
+    }
+}
@@ -0,0 +1,86 @@
+//! Grouping headlines from different outlets that cover the same story.
+
+use std::collections::HashSet;
+
+use chrono::Duration;
+
+use crate::feed::content_runs;
+use crate::trends::Headline;
+
+/// Coverage of one story is looked for this far either side of an article
+pub const STORY_WINDOW_HOURS: i64 = 48;
+
+/// Headlines need this many title words in common...
+const MIN_SHARED_WORDS: usize = 3;
+/// ...making up at least this share of the shorter title's words
+const MIN_OVERLAP: f64 = 0.5;
+
+/// `target` and the headlines covering the same story, at most one per
+/// other feed (its closest match) and `limit` in all, best matches first.
+/// Headlines match when their titles share enough content words and they
+/// are within `STORY_WINDOW_HOURS` of `target`.
+pub fn story_group<'a>(target: &'a Headline, headlines: &'a [Headline], limit: usize) -> Vec<&'a Headline> {
+    let words = title_words(&target.title);
+    let window = Duration::hours(STORY_WINDOW_HOURS);
+
+    let mut matches: Vec<(f64, &Headline)> = headlines
+        .iter()
+        .filter(|h| h.feed_id != target.feed_id && (h.at - target.at).abs() <= window)
+        .filter_map(|h| {
+            let other = title_words(&h.title);
+            let shared = words.intersection(&other).count();
+            let overlap = shared as f64 / words.len().min(other.len()).max(1) as f64;
+            (shared >= MIN_SHARED_WORDS && overlap >= MIN_OVERLAP).then_some((overlap, h))
+        })
+        .collect();
+    matches.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.article_id.cmp(&b.1.article_id)));
+
+    let mut group = vec![target];
+    for (_, headline) in matches {
+        if group.len() == limit {
+            break;
+        }
+        if !group.iter().any(|h| h.feed_id == headline.feed_id) {
+            group.push(headline);
+        }
+    }
+    group
+}
+
+fn title_words(title: &str) -> HashSet<String> {
+    content_runs(title).into_iter().flatten().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn headline(article_id: i64, feed_id: i64, title: &str, hours: i64) -> Headline {
+        Headline {
+            article_id,
+            feed_id,
+            title: title.to_string(),
+            at: Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap() + Duration::hours(hours),
+        }
+    }
+
+    #[test]
+    fn test_story_group_one_closest_match_per_outlet() {
+        let target = headline(1, 1, "Apple delays Siri overhaul until next spring", 0);
+        let headlines = vec![
+            target.clone(),
+            headline(2, 2, "Apple reportedly delays its Siri overhaul", 3),
+            headline(3, 2, "Apple delays Siri overhaul again, sources say", 5),
+            headline(4, 3, "Report: Siri overhaul delayed as Apple struggles", -6),
+            // Same words, too long ago
+            headline(5, 4, "Apple delays Siri overhaul", -100),
+            // Same outlet as the target
+            headline(6, 1, "Why Apple delays Siri overhaul matter", 1),
+            headline(7, 5, "Apple ships new iPad", 1),
+        ];
+        let group: Vec<i64> = story_group(&target, &headlines, 10).iter().map(|h| h.article_id).collect();
+        assert_eq!(group, [1, 2, 4]);
+        assert_eq!(story_group(&target, &headlines, 2).len(), 2);
+    }
+}
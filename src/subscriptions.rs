@@ -0,0 +1,150 @@
+//! Config-as-code subscriptions: the `[[subscriptions]]` in the config are
+//! the feed list, and the database is brought in line at startup.
+
+use crate::config::Subscription;
+use crate::db::FeedBulkUpdate;
+use crate::feed::find_by_url;
+use crate::models::Feed;
+
+/// How the feed list differs from `[[subscriptions]]`
+#[derive(Debug, Clone, Default)]
+pub struct Reconciliation {
+    /// Declared but not subscribed to
+    pub missing: Vec<Subscription>,
+    /// Subscribed feeds whose name, folder or tags differ from their
+    /// declaration, with what would fix them
+    pub changes: Vec<FeedChange>,
+    /// Subscribed to but not declared
+    pub extras: Vec<Feed>,
+}
+
+impl Reconciliation {
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.changes.is_empty() && self.extras.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedChange {
+    pub feed_id: i64,
+    /// `Some(None)` goes back to the feed's own title
+    pub title: Option<Option<String>>,
+    /// Folder and tags
+    pub update: FeedBulkUpdate,
+}
+
+/// Compare the declared feeds with the subscribed ones (matched by URL,
+/// ignoring the scheme and a trailing slash). The Saved and Orphaned
+/// pseudo-feeds are never extras.
+pub fn reconcile(declared: &[Subscription], feeds: &[Feed]) -> Reconciliation {
+    let mut reconciliation = Reconciliation::default();
+    let mut matched = Vec::new();
+    for subscription in declared {
+        let Some(feed) = find_by_url(feeds, &subscription.url) else {
+            if !reconciliation.missing.iter().any(|m| m.url == subscription.url) {
+                reconciliation.missing.push(subscription.clone());
+            }
+            continue;
+        };
+        matched.push(feed.id);
+        let title = clean(subscription.title.as_deref());
+        let folder = clean(subscription.folder.as_deref());
+        let tags: Vec<String> = subscription
+            .tags
+            .iter()
+            .map(|t| t.trim().to_lowercase())
+            .filter(|t| !t.is_empty())
+            .collect();
+        let update = FeedBulkUpdate {
+            folder: (feed.folder != folder).then_some(folder),
+            add_tags: tags.iter().filter(|t| !feed.tags.contains(t)).cloned().collect(),
+            remove_tags: feed.tags.iter().filter(|t| !tags.contains(t)).cloned().collect(),
+            ..Default::default()
+        };
+        let title = (feed.custom_title != title).then_some(title);
+        if title.is_some() || update != FeedBulkUpdate::default() {
+            reconciliation.changes.push(FeedChange {
+                feed_id: feed.id,
+                title,
+                update,
+            });
+        }
+    }
+    reconciliation.extras = feeds
+        .iter()
+        .filter(|f| !f.is_local() && !matched.contains(&f.id))
+        .cloned()
+        .collect();
+    reconciliation
+}
+
+fn clean(value: Option<&str>) -> Option<String> {
+    value.map(str::trim).filter(|v| !v.is_empty()).map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed(id: i64, url: &str, custom_title: Option<&str>, folder: Option<&str>, tags: &[&str]) -> Feed {
+        serde_json::from_value(serde_json::json!({
+            "id": id, "title": "Feed", "url": url, "site_url": null, "description": null,
+            "last_fetched": null, "created_at": "2026-03-02T09:00:00Z", "updated_at": "2026-03-02T09:00:00Z",
+            "retry_after": null, "custom_title": custom_title, "folder": folder,
+            "refresh_minutes": null, "paused": false, "tags": tags
+        }))
+        .unwrap()
+    }
+
+    fn subscription(url: &str, title: Option<&str>, folder: Option<&str>, tags: &[&str]) -> Subscription {
+        Subscription {
+            url: url.to_string(),
+            title: title.map(str::to_string),
+            folder: folder.map(str::to_string),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_reconcile_finds_missing_changed_and_extra_feeds() {
+        let feeds = [
+            feed(1, "https://wire.example/rss", None, Some("News"), &["news"]),
+            feed(2, "https://blog.example/feed/", Some("Old name"), None, &["tech", "old"]),
+            feed(3, "https://stray.example/rss", None, None, &[]),
+            feed(4, "beatcheck:saved", None, None, &[]),
+        ];
+        let declared = [
+            subscription("https://wire.example/rss", None, Some("News"), &["News"]),
+            subscription("http://blog.example/feed", Some("Blog"), Some("Tech"), &["tech"]),
+            subscription("https://new.example/atom", None, None, &[]),
+        ];
+        let reconciliation = reconcile(&declared, &feeds);
+
+        assert_eq!(reconciliation.missing, [declared[2].clone()]);
+        assert_eq!(
+            reconciliation.changes,
+            [FeedChange {
+                feed_id: 2,
+                title: Some(Some("Blog".to_string())),
+                update: FeedBulkUpdate {
+                    folder: Some(Some("Tech".to_string())),
+                    remove_tags: vec!["old".to_string()],
+                    ..Default::default()
+                },
+            }]
+        );
+        assert_eq!(reconciliation.extras.iter().map(|f| f.id).collect::<Vec<_>>(), [3]);
+        assert!(!reconciliation.is_empty());
+    }
+
+    #[test]
+    fn test_reconcile_clears_names_and_folders_left_out() {
+        let feeds = [feed(1, "https://wire.example/rss", Some("Wire"), Some("News"), &[])];
+        let reconciliation = reconcile(&[subscription("https://wire.example/rss", None, None, &[])], &feeds);
+        assert_eq!(reconciliation.changes[0].title, Some(None));
+        assert_eq!(reconciliation.changes[0].update.folder, Some(None));
+
+        let declared = [subscription("https://wire.example/rss", Some("Wire"), Some("News"), &[])];
+        assert!(reconcile(&declared, &feeds).is_empty());
+    }
+}
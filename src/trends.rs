@@ -0,0 +1,244 @@
+//! Trending headline terms: words and two-word phrases that show up in
+//! far more headlines in the last day than they usually do across feeds.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+use crate::db::Repository;
+use crate::error::Result;
+use crate::feed::content_runs;
+
+/// The window a term has to be trending in
+pub const TREND_WINDOW_HOURS: i64 = 24;
+
+/// Days before the window that make up a term's usual rate
+pub const BASELINE_DAYS: i64 = 6;
+
+/// A trend needs this many headlines in the window...
+const MIN_RECENT_ARTICLES: usize = 3;
+/// ...from at least this many feeds...
+const MIN_FEEDS: usize = 2;
+/// ...and a rise of this many standard deviations over its usual rate
+/// (counts treated as Poisson)
+const MIN_SCORE: f64 = 2.0;
+
+/// Representative articles kept per trend
+const EXAMPLES_PER_TREND: usize = 3;
+
+/// A headline to look for trends in
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Headline {
+    pub article_id: i64,
+    pub feed_id: i64,
+    pub title: String,
+    pub at: DateTime<Utc>,
+}
+
+/// A term that's picking up steam
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Trend {
+    pub term: String,
+    /// Headlines with the term in the window
+    pub recent: usize,
+    /// Its usual headlines per day before the window
+    pub baseline: f64,
+    /// Feeds the recent headlines came from
+    pub feeds: usize,
+    /// Recent headlines with the term, newest first and from different
+    /// feeds where possible
+    pub examples: Vec<Headline>,
+    /// Every recent article with the term
+    #[serde(skip)]
+    article_ids: Vec<i64>,
+    #[serde(skip)]
+    score: f64,
+}
+
+/// What's trending now across all feeds, or a server user's subscriptions
+pub async fn load_trends(repository: &Repository, user_id: Option<i64>, limit: usize) -> Result<Vec<Trend>> {
+    let now = Utc::now();
+    let since = now - Duration::hours(TREND_WINDOW_HOURS) - Duration::days(BASELINE_DAYS);
+    let headlines = load_headlines(repository, since, user_id).await?;
+    Ok(find_trends(&headlines, now, limit))
+}
+
+/// Headlines published (or fetched) since `since`, across all feeds or a
+/// server user's subscriptions
+pub async fn load_headlines(
+    repository: &Repository,
+    since: DateTime<Utc>,
+    user_id: Option<i64>,
+) -> Result<Vec<Headline>> {
+    Ok(repository
+        .headlines_since(since, user_id)
+        .await?
+        .into_iter()
+        .map(|(article_id, feed_id, title, at)| Headline {
+            article_id,
+            feed_id,
+            title,
+            at,
+        })
+        .collect())
+}
+
+/// Terms trending in the `TREND_WINDOW_HOURS` before `now`, strongest
+/// first. `headlines` should reach back `BASELINE_DAYS` further.
+pub fn find_trends(headlines: &[Headline], now: DateTime<Utc>, limit: usize) -> Vec<Trend> {
+    let window_start = now - Duration::hours(TREND_WINDOW_HOURS);
+    let baseline_start = window_start - Duration::days(BASELINE_DAYS);
+
+    // Each term once per headline: article ids in the window, and a count before it
+    let mut recent: HashMap<String, Vec<&Headline>> = HashMap::new();
+    let mut before: HashMap<String, usize> = HashMap::new();
+    for headline in headlines {
+        if headline.at < baseline_start || headline.at > now {
+            continue;
+        }
+        for term in headline_terms(&headline.title) {
+            if headline.at >= window_start {
+                recent.entry(term).or_default().push(headline);
+            } else {
+                *before.entry(term).or_default() += 1;
+            }
+        }
+    }
+
+    let mut trends: Vec<Trend> = recent
+        .into_iter()
+        .filter_map(|(term, mut matches)| {
+            let feeds: HashSet<i64> = matches.iter().map(|h| h.feed_id).collect();
+            if matches.len() < MIN_RECENT_ARTICLES || feeds.len() < MIN_FEEDS {
+                return None;
+            }
+            let baseline = before.get(&term).copied().unwrap_or(0) as f64 / BASELINE_DAYS as f64;
+            let score = (matches.len() as f64 - baseline) / (baseline + 1.0).sqrt();
+            if score < MIN_SCORE {
+                return None;
+            }
+            matches.sort_by_key(|h| std::cmp::Reverse(h.at));
+            Some(Trend {
+                recent: matches.len(),
+                feeds: feeds.len(),
+                examples: examples(&matches),
+                article_ids: matches.iter().map(|h| h.article_id).collect(),
+                term,
+                baseline,
+                score,
+            })
+        })
+        .collect();
+    // On a tie the phrase goes first, so its words are dropped below
+    let words = |t: &Trend| t.term.split(' ').count();
+    trends.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| words(b).cmp(&words(a)))
+            .then_with(|| a.term.cmp(&b.term))
+    });
+
+    // A word trending only as part of a phrase ("vision" in "vision pro")
+    // adds nothing
+    let mut picked: Vec<Trend> = Vec::new();
+    for trend in trends {
+        if picked.len() == limit {
+            break;
+        }
+        let overlaps = picked.iter().any(|p| {
+            let shares_word = p.term.split(' ').any(|w| trend.term.split(' ').any(|t| t == w));
+            let shared = trend.article_ids.iter().filter(|id| p.article_ids.contains(id)).count();
+            shares_word && shared * 2 >= trend.article_ids.len()
+        });
+        if !overlaps {
+            picked.push(trend);
+        }
+    }
+    picked
+}
+
+/// Distinct words and adjacent word pairs in a headline
+fn headline_terms(title: &str) -> HashSet<String> {
+    let mut terms = HashSet::new();
+    for run in content_runs(title) {
+        for (i, word) in run.iter().enumerate() {
+            terms.insert(word.clone());
+            if let Some(next) = run.get(i + 1) {
+                terms.insert(format!("{} {}", word, next));
+            }
+        }
+    }
+    terms
+}
+
+/// Newest headlines first, one per feed until every feed has one
+fn examples(matches: &[&Headline]) -> Vec<Headline> {
+    let mut seen_feeds = HashSet::new();
+    let mut examples: Vec<Headline> = matches
+        .iter()
+        .filter(|h| seen_feeds.insert(h.feed_id))
+        .take(EXAMPLES_PER_TREND)
+        .map(|h| (*h).clone())
+        .collect();
+    for headline in matches {
+        if examples.len() == EXAMPLES_PER_TREND {
+            break;
+        }
+        if !examples.iter().any(|e| e.article_id == headline.article_id) {
+            examples.push((*headline).clone());
+        }
+    }
+    examples
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headline(id: i64, feed_id: i64, title: &str, hours_ago: i64, now: DateTime<Utc>) -> Headline {
+        Headline {
+            article_id: id,
+            feed_id,
+            title: title.to_string(),
+            at: now - Duration::hours(hours_ago),
+        }
+    }
+
+    #[test]
+    fn test_emerging_term_beats_steady_one() {
+        let now = Utc::now();
+        let mut headlines = vec![
+            headline(1, 1, "Vision Pro sales slump", 2, now),
+            headline(2, 2, "Apple cuts Vision Pro production", 5, now),
+            headline(3, 3, "What the Vision Pro slump means", 8, now),
+            headline(4, 1, "Google ships Android update", 3, now),
+            headline(5, 2, "Google earnings preview", 6, now),
+            headline(6, 3, "Google antitrust ruling", 9, now),
+        ];
+        // Google is in the news every day
+        for day in 1..=6 {
+            for feed in 1..=3 {
+                let id = 100 + day * 10 + feed;
+                headlines.push(headline(id, feed, "Google news roundup", 24 * day + 1, now));
+            }
+        }
+
+        let trends = find_trends(&headlines, now, 5);
+        assert_eq!(trends[0].term, "vision pro");
+        assert_eq!((trends[0].recent, trends[0].feeds), (3, 3));
+        let ids: Vec<i64> = trends[0].examples.iter().map(|h| h.article_id).collect();
+        assert_eq!(ids, [1, 2, 3]);
+        assert!(!trends.iter().any(|t| t.term == "vision" || t.term == "pro"));
+        assert!(!trends.iter().any(|t| t.term == "google"));
+    }
+
+    #[test]
+    fn test_one_feed_repeating_itself_is_not_a_trend() {
+        let now = Utc::now();
+        let headlines: Vec<Headline> = (1..=5)
+            .map(|id| headline(id, 1, "Daily crossword answers", id, now))
+            .collect();
+        assert!(find_trends(&headlines, now, 5).is_empty());
+    }
+}
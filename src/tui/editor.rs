@@ -0,0 +1,51 @@
+//! Notes written in the user's own editor, `$VISUAL` or `$EDITOR`, so they
+//! get its spell checking and key bindings. The TUI steps aside while it
+//! runs; without either variable set, notes are typed in a popup instead.
+
+use std::process::Command;
+
+use crate::error::{AppError, Result};
+
+/// A note to open in the external editor, picked up by the main loop
+#[derive(Debug, Clone)]
+pub struct NoteEdit {
+    pub article_id: i64,
+    pub editor: String,
+    pub text: String,
+}
+
+/// The editor command to use, if one is configured
+pub fn external_editor() -> Option<String> {
+    ["VISUAL", "EDITOR"]
+        .into_iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|editor| !editor.trim().is_empty())
+}
+
+/// Open the note in its editor and wait for it to close. `None` if the
+/// editor exited with an error, in which case the note is left as it was.
+pub fn run_editor(edit: &NoteEdit) -> Result<Option<String>> {
+    let path = std::env::temp_dir().join(format!("beatcheck-note-{}.md", edit.article_id));
+    std::fs::write(&path, &edit.text)?;
+
+    // The command may carry its own arguments, like `code --wait`
+    let parts: Vec<&str> = edit.editor.split_whitespace().collect();
+    let (program, args) = parts
+        .split_first()
+        .ok_or_else(|| AppError::Config("$EDITOR is empty".to_string()))?;
+    let status = Command::new(program).args(args).arg(&path).status();
+
+    let result = match status {
+        Ok(status) if status.success() => Some(std::fs::read_to_string(&path)?),
+        Ok(status) => {
+            tracing::warn!("{} exited with {}; note not saved", edit.editor, status);
+            None
+        }
+        Err(e) => {
+            let _ = std::fs::remove_file(&path);
+            return Err(e.into());
+        }
+    };
+    let _ = std::fs::remove_file(&path);
+    Ok(result)
+}
@@ -1,5 +1,10 @@
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
+use crate::db::FeedRemoval;
+use crate::models::AuthorRule;
+
+use super::{KeyBinding, SnoozeChoice};
+
 #[derive(Debug, Clone)]
 pub enum AppAction {
     Quit,
@@ -10,13 +15,102 @@ pub enum AppAction {
     SelectArticle,
     RefreshFeeds,
     OpenInBrowser,
+    OpenDiscussion,
     EmailArticle,
     SaveToRaindrop,
     SaveToRaindropWithTag(String), // Quick bookmark with preset tag
     RegenerateSummary,
+    FetchComments,
+    ExportPdf,
+    CopyBrief,
+    SaveNote,
+    TogglePlayback,
     DeleteArticle,
     DeleteFeed,
     UndeleteArticle,
+    ToggleStar,
+    TogglePin,
+    ToggleQueue,
+    ToggleRead,
+    MovePinnedUp,
+    MovePinnedDown,
+    CycleFilter,
+    // Pane layout
+    CycleLayout,
+    ResizeList(i16),
+    ResizeFeeds(i16),
+    /// Move the keyboard between the feed list and the article list
+    ToggleFeedFocus,
+    FeedUp,
+    FeedDown,
+    /// Hide (or show again) feeds with nothing unread in the feed list
+    ToggleUnreadFeeds,
+    /// Fold or unfold a group in the feed list; on other rows, go back to
+    /// the article list
+    FeedListSelect,
+    /// Group the feed list by folder or by tag
+    CycleSidebarGrouping,
+    /// Back from a full-screen article to the list
+    CloseReader,
+    // Find in the open article
+    FindStart,
+    FindChar(char),
+    FindBackspace,
+    FindConfirm,
+    FindCancel,
+    FindNext,
+    FindPrev,
+    /// Drop the find and the highlighted link
+    ClearFind,
+    // Links in the open article; a count typed first picks one by number
+    NextLink,
+    PrevLink,
+    CountDigit(u32),
+    // Selection mode: pick articles, then act on all of them at once
+    SelectModeStart,
+    SelectModeEnd,
+    ToggleSelected,
+    /// Start a range at the cursor, or add the range so far to the selection
+    ToggleRange,
+    BatchRead,
+    BatchStar,
+    BatchDelete,
+    BatchTagStart,
+    BatchTagChar(char),
+    BatchTagBackspace,
+    BatchTagConfirm,
+    BatchTagCancel,
+    // Notes on articles: $EDITOR, or the inline editor without one
+    EditNote,
+    NoteInputChar(char),
+    NoteInputBackspace,
+    NoteInputConfirm,
+    NoteInputCancel,
+    // Snoozing: hidden until a chosen time, then back on top unread
+    SnoozeStart,
+    Snooze(SnoozeChoice),
+    SnoozeCustomStart,
+    SnoozeInputChar(char),
+    SnoozeInputBackspace,
+    SnoozeInputConfirm,
+    SnoozeCancel,
+    // Press embargoes: out of listings and announcements until lifted
+    EmbargoStart,
+    EmbargoInputChar(char),
+    EmbargoInputBackspace,
+    EmbargoInputConfirm,
+    EmbargoCancel,
+    /// Full text, first paragraph or headlines only, for the article's feed
+    CycleReadingMode,
+    CycleFeedPriority,
+    // Command palette
+    PaletteStart,
+    PaletteChar(char),
+    PaletteBackspace,
+    PaletteUp,
+    PaletteDown,
+    PaletteConfirm,
+    PaletteCancel,
     AddFeed,
     ShowHelp,
     HideHelp,
@@ -42,27 +136,180 @@ pub enum AppAction {
     OpmlExportBackspace,
     OpmlExportConfirm,
     OpmlExportCancel,
+    // Feed rename actions
+    RenameFeedStart,
+    RenameFeedChar(char),
+    RenameFeedBackspace,
+    RenameFeedConfirm,
+    RenameFeedCancel,
+    // Unsubscribe prompt
+    RemoveFeed(FeedRemoval),
+    RemoveFeedCancel,
+    /// Switch the prompted feed to the URL found on its site
+    AcceptFeedMove,
+    /// Keep the prompted feed's URL
+    DismissFeedMove,
+    /// Ask about the prompted feed again next start
+    FeedMoveLater,
+    // Tag filter actions
+    TagFilterStart,
+    TagFilterChar(char),
+    TagFilterBackspace,
+    TagFilterConfirm,
+    TagFilterCancel,
+    // Search prompt actions
+    SearchStart,
+    SearchChar(char),
+    SearchBackspace,
+    SearchConfirm,
+    SearchCancel,
+    // Entity browser actions
+    ShowEntities,
+    EntityUp,
+    EntityDown,
+    SelectEntity,
+    ClearEntityFilter,
+    CloseEntities,
+    // Author browser actions
+    ShowAuthors,
+    AuthorUp,
+    AuthorDown,
+    SelectAuthor,
+    ClearAuthorFilter,
+    CloseAuthors,
+    /// Set the selected author's rule, or clear it if it's already set
+    ToggleAuthorRule(AuthorRule),
+    /// Every article by the selected article's (first) author
+    ShowArticleAuthor,
+    /// Every article whose content links to the selected one
+    ShowCitingArticles,
+    // Blocklist actions
+    ShowBlocklist,
+    BlocklistUp,
+    BlocklistDown,
+    /// Type a new entry
+    BlocklistAdd,
+    /// Retype the selected entry
+    BlocklistEdit,
+    BlocklistRemove,
+    /// Turn the selected shared list on or off
+    BlocklistToggle,
+    CloseBlocklist,
+    BlocklistInputChar(char),
+    BlocklistInputBackspace,
+    BlocklistInputConfirm,
+    BlocklistInputCancel,
+    // Watchlist actions
+    ShowWatchlist,
+    WatchUp,
+    WatchDown,
+    SelectWatched,
+    CloseWatchlist,
+    // Trending terms actions
+    ShowTrends,
+    TrendUp,
+    TrendDown,
+    SelectTrend,
+    ClearTrendFilter,
+    CloseTrends,
+    // Earlier versions of the selected article
+    ShowRevisions,
+    RevisionOlder,
+    RevisionNewer,
+    RevisionScrollDown,
+    RevisionScrollUp,
+    CloseRevisions,
+    // Calendar of articles per day
+    ShowCalendar,
+    /// Move the picked day by this many days
+    CalendarMove(i64),
+    CalendarToggleFeed,
+    SelectCalendarDay,
+    ClearDayFilter,
+    CloseCalendar,
+    // Ask a question about articles
+    AskStart,
+    ShowBriefing,
+    AskChar(char),
+    AskBackspace,
+    AskToggleScope,
+    AskConfirm,
+    AskCancel,
+    AnswerUp,
+    AnswerDown,
+    OpenAnswerSource,
+    CloseAnswer,
+    // Failed summaries browser
+    ShowFailures,
+    FailureUp,
+    FailureDown,
+    RetryFailure,
+    RetryAllFailures,
+    CloseFailures,
+    // Feed recommendations
+    ShowRecommendations,
+    RecommendationUp,
+    RecommendationDown,
+    SubscribeRecommendation,
+    CloseRecommendations,
     // Space prefix mode for quick bookmarks
     BookmarkPrefixStart,
     CancelBookmarkPrefix,
+    /// Run the configured macro at this index
+    RunMacro(usize),
 }
 
-pub fn handle_key_event(
-    key: KeyEvent,
-    tag_input_active: bool,
-    feed_input_active: bool,
-    opml_input_active: bool,
-    opml_export_active: bool,
-    show_help: bool,
-    bookmark_prefix_active: bool,
-) -> Option<AppAction> {
+/// Which modal state the UI is in, used to route key presses
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KeyContext {
+    pub tag_input_active: bool,
+    pub feed_input_active: bool,
+    pub opml_input_active: bool,
+    pub opml_export_active: bool,
+    pub rename_feed_active: bool,
+    pub remove_feed_active: bool,
+    pub feed_move_active: bool,
+    pub tag_filter_active: bool,
+    pub search_active: bool,
+    pub entities_active: bool,
+    pub authors_active: bool,
+    pub blocklist_active: bool,
+    pub blocklist_input_active: bool,
+    pub watch_active: bool,
+    pub trends_active: bool,
+    pub calendar_active: bool,
+    pub revisions_active: bool,
+    pub ask_input_active: bool,
+    pub answer_active: bool,
+    pub failures_active: bool,
+    pub show_help: bool,
+    pub bookmark_prefix_active: bool,
+    pub recommendations_active: bool,
+    /// The feed list has the keyboard (three-pane layout)
+    pub feeds_focused: bool,
+    /// Reading an article full screen (list-only layout)
+    pub reading: bool,
+    pub find_input_active: bool,
+    /// A find or a link is highlighted in the reader
+    pub reader_marked: bool,
+    pub select_mode: bool,
+    pub batch_tag_active: bool,
+    pub palette_active: bool,
+    pub note_input_active: bool,
+    pub snooze_active: bool,
+    pub snooze_input_active: bool,
+    pub embargo_input_active: bool,
+}
+
+/// `macro_keys` are the configured macros' keys, in order
+pub fn handle_key_event(key: KeyEvent, ctx: &KeyContext, macro_keys: &[KeyBinding]) -> Option<AppAction> {
     // If help is showing, any key closes it
-    if show_help {
+    if ctx.show_help {
         return Some(AppAction::HideHelp);
     }
 
     // Space prefix mode (waiting for second key after Space)
-    if bookmark_prefix_active {
+    if ctx.bookmark_prefix_active {
         return match key.code {
             KeyCode::Char('t') => Some(AppAction::SaveToRaindropWithTag("twit".to_string())),
             KeyCode::Char('i') => Some(AppAction::SaveToRaindropWithTag("im".to_string())),
@@ -72,8 +319,73 @@ pub fn handle_key_event(
         };
     }
 
+    // Inline note editor: Enter starts a new line, Esc saves
+    if ctx.note_input_active {
+        return match (key.code, key.modifiers) {
+            (KeyCode::Char('c'), KeyModifiers::CONTROL) => Some(AppAction::NoteInputCancel),
+            (KeyCode::Esc, _) => Some(AppAction::NoteInputConfirm),
+            (KeyCode::Enter, _) => Some(AppAction::NoteInputChar('\n')),
+            (KeyCode::Backspace, _) => Some(AppAction::NoteInputBackspace),
+            (KeyCode::Char(c), _) => Some(AppAction::NoteInputChar(c)),
+            _ => None,
+        };
+    }
+
+    // Snooze popup: a time, or one typed out
+    if ctx.snooze_input_active {
+        return match key.code {
+            KeyCode::Enter => Some(AppAction::SnoozeInputConfirm),
+            KeyCode::Esc => Some(AppAction::SnoozeCancel),
+            KeyCode::Backspace => Some(AppAction::SnoozeInputBackspace),
+            KeyCode::Char(c) => Some(AppAction::SnoozeInputChar(c)),
+            _ => None,
+        };
+    }
+    if ctx.embargo_input_active {
+        return match key.code {
+            KeyCode::Enter => Some(AppAction::EmbargoInputConfirm),
+            KeyCode::Esc => Some(AppAction::EmbargoCancel),
+            KeyCode::Backspace => Some(AppAction::EmbargoInputBackspace),
+            KeyCode::Char(c) => Some(AppAction::EmbargoInputChar(c)),
+            _ => None,
+        };
+    }
+    if ctx.snooze_active {
+        return match key.code {
+            KeyCode::Char('t') => Some(AppAction::Snooze(SnoozeChoice::Tonight)),
+            KeyCode::Char('m') => Some(AppAction::Snooze(SnoozeChoice::Tomorrow)),
+            KeyCode::Char('w') => Some(AppAction::Snooze(SnoozeChoice::NextWeek)),
+            KeyCode::Char('c') => Some(AppAction::SnoozeCustomStart),
+            _ => Some(AppAction::SnoozeCancel),
+        };
+    }
+
+    // Command palette
+    if ctx.palette_active {
+        return match (key.code, key.modifiers) {
+            (KeyCode::Enter, _) => Some(AppAction::PaletteConfirm),
+            (KeyCode::Esc, _) => Some(AppAction::PaletteCancel),
+            (KeyCode::Backspace, _) => Some(AppAction::PaletteBackspace),
+            (KeyCode::Up, _) | (KeyCode::Char('p'), KeyModifiers::CONTROL) => Some(AppAction::PaletteUp),
+            (KeyCode::Down, _) | (KeyCode::Char('n'), KeyModifiers::CONTROL) => Some(AppAction::PaletteDown),
+            (KeyCode::Char(c), _) => Some(AppAction::PaletteChar(c)),
+            _ => None,
+        };
+    }
+
+    // Feed recommendations popup
+    if ctx.recommendations_active {
+        return match key.code {
+            KeyCode::Char('j') | KeyCode::Down => Some(AppAction::RecommendationDown),
+            KeyCode::Char('k') | KeyCode::Up => Some(AppAction::RecommendationUp),
+            KeyCode::Enter => Some(AppAction::SubscribeRecommendation),
+            KeyCode::Esc | KeyCode::Char('q') => Some(AppAction::CloseRecommendations),
+            _ => None,
+        };
+    }
+
     // Tag input mode
-    if tag_input_active {
+    if ctx.tag_input_active {
         return match key.code {
             KeyCode::Enter => Some(AppAction::TagInputConfirm),
             KeyCode::Esc => Some(AppAction::TagInputCancel),
@@ -84,7 +396,7 @@ pub fn handle_key_event(
     }
 
     // Feed input mode
-    if feed_input_active {
+    if ctx.feed_input_active {
         return match key.code {
             KeyCode::Enter => Some(AppAction::FeedInputConfirm),
             KeyCode::Esc => Some(AppAction::FeedInputCancel),
@@ -95,7 +407,7 @@ pub fn handle_key_event(
     }
 
     // OPML import input mode
-    if opml_input_active {
+    if ctx.opml_input_active {
         return match key.code {
             KeyCode::Enter => Some(AppAction::OpmlInputConfirm),
             KeyCode::Esc => Some(AppAction::OpmlInputCancel),
@@ -106,7 +418,7 @@ pub fn handle_key_event(
     }
 
     // OPML export input mode
-    if opml_export_active {
+    if ctx.opml_export_active {
         return match key.code {
             KeyCode::Enter => Some(AppAction::OpmlExportConfirm),
             KeyCode::Esc => Some(AppAction::OpmlExportCancel),
@@ -116,31 +428,357 @@ pub fn handle_key_event(
         };
     }
 
-    // Normal mode
+    // Feed rename input mode
+    if ctx.rename_feed_active {
+        return match key.code {
+            KeyCode::Enter => Some(AppAction::RenameFeedConfirm),
+            KeyCode::Esc => Some(AppAction::RenameFeedCancel),
+            KeyCode::Backspace => Some(AppAction::RenameFeedBackspace),
+            KeyCode::Char(c) => Some(AppAction::RenameFeedChar(c)),
+            _ => None,
+        };
+    }
+
+    // Entity browser navigation
+    if ctx.entities_active {
+        return match key.code {
+            KeyCode::Char('j') | KeyCode::Down => Some(AppAction::EntityDown),
+            KeyCode::Char('k') | KeyCode::Up => Some(AppAction::EntityUp),
+            KeyCode::Enter => Some(AppAction::SelectEntity),
+            KeyCode::Char('x') => Some(AppAction::ClearEntityFilter),
+            KeyCode::Esc | KeyCode::Char('q') => Some(AppAction::CloseEntities),
+            _ => None,
+        };
+    }
+
+    // Author browser navigation
+    if ctx.authors_active {
+        return match key.code {
+            KeyCode::Char('j') | KeyCode::Down => Some(AppAction::AuthorDown),
+            KeyCode::Char('k') | KeyCode::Up => Some(AppAction::AuthorUp),
+            KeyCode::Enter => Some(AppAction::SelectAuthor),
+            KeyCode::Char('x') => Some(AppAction::ClearAuthorFilter),
+            KeyCode::Char('f') => Some(AppAction::ToggleAuthorRule(AuthorRule::Follow)),
+            KeyCode::Char('m') => Some(AppAction::ToggleAuthorRule(AuthorRule::Mute)),
+            KeyCode::Esc | KeyCode::Char('q') => Some(AppAction::CloseAuthors),
+            _ => None,
+        };
+    }
+
+    // Blocklist entry being typed
+    if ctx.blocklist_input_active {
+        return match key.code {
+            KeyCode::Enter => Some(AppAction::BlocklistInputConfirm),
+            KeyCode::Esc => Some(AppAction::BlocklistInputCancel),
+            KeyCode::Backspace => Some(AppAction::BlocklistInputBackspace),
+            KeyCode::Char(c) => Some(AppAction::BlocklistInputChar(c)),
+            _ => None,
+        };
+    }
+
+    // Blocklist navigation
+    if ctx.blocklist_active {
+        return match key.code {
+            KeyCode::Char('j') | KeyCode::Down => Some(AppAction::BlocklistDown),
+            KeyCode::Char('k') | KeyCode::Up => Some(AppAction::BlocklistUp),
+            KeyCode::Char('a') => Some(AppAction::BlocklistAdd),
+            KeyCode::Char('e') | KeyCode::Enter => Some(AppAction::BlocklistEdit),
+            KeyCode::Char('d') | KeyCode::Delete | KeyCode::Backspace => Some(AppAction::BlocklistRemove),
+            KeyCode::Char('t') | KeyCode::Char(' ') => Some(AppAction::BlocklistToggle),
+            KeyCode::Esc | KeyCode::Char('q') => Some(AppAction::CloseBlocklist),
+            _ => None,
+        };
+    }
+
+    // Watchlist navigation
+    if ctx.watch_active {
+        return match key.code {
+            KeyCode::Char('j') | KeyCode::Down => Some(AppAction::WatchDown),
+            KeyCode::Char('k') | KeyCode::Up => Some(AppAction::WatchUp),
+            KeyCode::Enter => Some(AppAction::SelectWatched),
+            KeyCode::Esc | KeyCode::Char('q') => Some(AppAction::CloseWatchlist),
+            _ => None,
+        };
+    }
+
+    // Trending terms navigation
+    if ctx.trends_active {
+        return match key.code {
+            KeyCode::Char('j') | KeyCode::Down => Some(AppAction::TrendDown),
+            KeyCode::Char('k') | KeyCode::Up => Some(AppAction::TrendUp),
+            KeyCode::Enter => Some(AppAction::SelectTrend),
+            KeyCode::Char('x') => Some(AppAction::ClearTrendFilter),
+            KeyCode::Esc | KeyCode::Char('q') => Some(AppAction::CloseTrends),
+            _ => None,
+        };
+    }
+
+    // Revisions: older and newer versions, scrolling the diff
+    if ctx.revisions_active {
+        return match key.code {
+            KeyCode::Char('[') | KeyCode::Char('h') | KeyCode::Left => Some(AppAction::RevisionOlder),
+            KeyCode::Char(']') | KeyCode::Char('l') | KeyCode::Right => Some(AppAction::RevisionNewer),
+            KeyCode::Char('j') | KeyCode::Down => Some(AppAction::RevisionScrollDown),
+            KeyCode::Char('k') | KeyCode::Up => Some(AppAction::RevisionScrollUp),
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('~') => Some(AppAction::CloseRevisions),
+            _ => None,
+        };
+    }
+
+    // Calendar navigation: weeks across, weekdays down
+    if ctx.calendar_active {
+        return match key.code {
+            KeyCode::Char('h') | KeyCode::Left => Some(AppAction::CalendarMove(-7)),
+            KeyCode::Char('l') | KeyCode::Right => Some(AppAction::CalendarMove(7)),
+            KeyCode::Char('k') | KeyCode::Up => Some(AppAction::CalendarMove(-1)),
+            KeyCode::Char('j') | KeyCode::Down => Some(AppAction::CalendarMove(1)),
+            KeyCode::Char('f') => Some(AppAction::CalendarToggleFeed),
+            KeyCode::Enter => Some(AppAction::SelectCalendarDay),
+            KeyCode::Char('x') => Some(AppAction::ClearDayFilter),
+            KeyCode::Esc | KeyCode::Char('q') => Some(AppAction::CloseCalendar),
+            _ => None,
+        };
+    }
+
+    // Question input mode
+    if ctx.ask_input_active {
+        return match key.code {
+            KeyCode::Enter => Some(AppAction::AskConfirm),
+            KeyCode::Esc => Some(AppAction::AskCancel),
+            KeyCode::Tab => Some(AppAction::AskToggleScope),
+            KeyCode::Backspace => Some(AppAction::AskBackspace),
+            KeyCode::Char(c) => Some(AppAction::AskChar(c)),
+            _ => None,
+        };
+    }
+
+    // Answer popup: pick a cited article
+    if ctx.answer_active {
+        return match key.code {
+            KeyCode::Char('j') | KeyCode::Down => Some(AppAction::AnswerDown),
+            KeyCode::Char('k') | KeyCode::Up => Some(AppAction::AnswerUp),
+            KeyCode::Enter => Some(AppAction::OpenAnswerSource),
+            KeyCode::Esc | KeyCode::Char('q') => Some(AppAction::CloseAnswer),
+            _ => None,
+        };
+    }
+
+    // Failed summaries: retry one or all
+    if ctx.failures_active {
+        return match key.code {
+            KeyCode::Char('j') | KeyCode::Down => Some(AppAction::FailureDown),
+            KeyCode::Char('k') | KeyCode::Up => Some(AppAction::FailureUp),
+            KeyCode::Enter => Some(AppAction::RetryFailure),
+            KeyCode::Char('a') => Some(AppAction::RetryAllFailures),
+            KeyCode::Esc | KeyCode::Char('q') => Some(AppAction::CloseFailures),
+            _ => None,
+        };
+    }
+
+    // Tag filter input mode
+    if ctx.tag_filter_active {
+        return match key.code {
+            KeyCode::Enter => Some(AppAction::TagFilterConfirm),
+            KeyCode::Esc => Some(AppAction::TagFilterCancel),
+            KeyCode::Backspace => Some(AppAction::TagFilterBackspace),
+            KeyCode::Char(c) => Some(AppAction::TagFilterChar(c)),
+            _ => None,
+        };
+    }
+
+    // Search prompt
+    if ctx.search_active {
+        return match key.code {
+            KeyCode::Enter => Some(AppAction::SearchConfirm),
+            KeyCode::Esc => Some(AppAction::SearchCancel),
+            KeyCode::Backspace => Some(AppAction::SearchBackspace),
+            KeyCode::Char(c) => Some(AppAction::SearchChar(c)),
+            _ => None,
+        };
+    }
+
+    // Tag for the selected articles
+    if ctx.batch_tag_active {
+        return match key.code {
+            KeyCode::Enter => Some(AppAction::BatchTagConfirm),
+            KeyCode::Esc => Some(AppAction::BatchTagCancel),
+            KeyCode::Backspace => Some(AppAction::BatchTagBackspace),
+            KeyCode::Char(c) => Some(AppAction::BatchTagChar(c)),
+            _ => None,
+        };
+    }
+
+    // Find-in-article prompt
+    if ctx.find_input_active {
+        return match key.code {
+            KeyCode::Enter => Some(AppAction::FindConfirm),
+            KeyCode::Esc => Some(AppAction::FindCancel),
+            KeyCode::Backspace => Some(AppAction::FindBackspace),
+            KeyCode::Char(c) => Some(AppAction::FindChar(c)),
+            _ => None,
+        };
+    }
+
+    // Unsubscribe prompt: what to do with the feed's articles
+    if ctx.remove_feed_active {
+        return match key.code {
+            KeyCode::Char('k') => Some(AppAction::RemoveFeed(FeedRemoval::KeepStarred)),
+            KeyCode::Char('d') => Some(AppAction::RemoveFeed(FeedRemoval::DeleteAll)),
+            KeyCode::Char('a') => Some(AppAction::RemoveFeed(FeedRemoval::Archive)),
+            _ => Some(AppAction::RemoveFeedCancel),
+        };
+    }
+
+    // A feed's URL stopped working: switch to the one found on its site?
+    if ctx.feed_move_active {
+        return match key.code {
+            KeyCode::Char('y') | KeyCode::Enter => Some(AppAction::AcceptFeedMove),
+            KeyCode::Char('n') => Some(AppAction::DismissFeedMove),
+            _ => Some(AppAction::FeedMoveLater),
+        };
+    }
+
+    // Feed list: moving the selection shows that feed's articles
+    if ctx.feeds_focused {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => return Some(AppAction::FeedDown),
+            KeyCode::Char('k') | KeyCode::Up => return Some(AppAction::FeedUp),
+            KeyCode::Tab | KeyCode::Esc => return Some(AppAction::ToggleFeedFocus),
+            KeyCode::Enter | KeyCode::Char(' ') => return Some(AppAction::FeedListSelect),
+            KeyCode::Char('u') => return Some(AppAction::ToggleUnreadFeeds),
+            KeyCode::Char('g') => return Some(AppAction::CycleSidebarGrouping),
+            _ => {}
+        }
+    }
+
+    // Selection mode: other keys (j/k, filters...) work as usual
+    if ctx.select_mode {
+        match key.code {
+            KeyCode::Char(' ') => return Some(AppAction::ToggleSelected),
+            KeyCode::Char('v') => return Some(AppAction::ToggleRange),
+            KeyCode::Char('m') => return Some(AppAction::BatchRead),
+            KeyCode::Char('s') => return Some(AppAction::BatchStar),
+            KeyCode::Char('t') => return Some(AppAction::BatchTagStart),
+            KeyCode::Char('d') | KeyCode::Backspace => return Some(AppAction::BatchDelete),
+            KeyCode::Esc | KeyCode::Char('M') => return Some(AppAction::SelectModeEnd),
+            _ => {}
+        }
+    }
+
+    // Matches in the reader: n/N step through them
+    if ctx.reader_marked {
+        match key.code {
+            KeyCode::Char('n') => return Some(AppAction::FindNext),
+            KeyCode::Char('N') => return Some(AppAction::FindPrev),
+            KeyCode::Esc => return Some(AppAction::ClearFind),
+            _ => {}
+        }
+    }
+
+    // Full-screen article: j/k still move through the list
+    if ctx.reading {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return Some(AppAction::CloseReader),
+            KeyCode::Char('/') => return Some(AppAction::FindStart),
+            _ => {}
+        }
+    }
+
+    // Macros from the config, on keys nothing else uses
+    if let Some(index) = macro_keys.iter().position(|binding| binding.matches(&key)) {
+        return Some(AppAction::RunMacro(index));
+    }
+
+    normal_mode_action(key)
+}
+
+/// The built-in action on `binding` outside prompts and popups, if any.
+/// Most keys do the same with ctrl or alt held, which doesn't count.
+pub fn built_in_action(binding: &KeyBinding) -> Option<AppAction> {
+    let key = binding.key_event();
+    let action = normal_mode_action(key)?;
+    let held = KeyModifiers::CONTROL | KeyModifiers::ALT;
+    if key.modifiers.intersects(held) {
+        let plain = normal_mode_action(KeyEvent::new(key.code, key.modifiers - held));
+        if plain.is_some_and(|plain| std::mem::discriminant(&plain) == std::mem::discriminant(&action)) {
+            return None;
+        }
+    }
+    Some(action)
+}
+
+fn normal_mode_action(key: KeyEvent) -> Option<AppAction> {
     match (key.code, key.modifiers) {
         (KeyCode::Char('q'), _) => Some(AppAction::Quit),
         (KeyCode::Char('c'), KeyModifiers::CONTROL) => Some(AppAction::Quit),
 
         (KeyCode::Char('j'), _) | (KeyCode::Down, _) => Some(AppAction::MoveDown),
         (KeyCode::Char('k'), _) | (KeyCode::Up, _) => Some(AppAction::MoveUp),
+        (KeyCode::Char('K'), _) => Some(AppAction::MovePinnedUp),
+        (KeyCode::Char('J'), _) => Some(AppAction::MovePinnedDown),
         (KeyCode::Char('<'), _) => Some(AppAction::MoveToTop),
         (KeyCode::Char('>'), _) => Some(AppAction::MoveToBottom),
 
         (KeyCode::Enter, _) => Some(AppAction::SelectArticle),
 
         (KeyCode::Char('r'), _) => Some(AppAction::RefreshFeeds),
+        (KeyCode::Char('R'), _) => Some(AppAction::ShowRecommendations),
         (KeyCode::Char('o'), _) => Some(AppAction::OpenInBrowser),
+        (KeyCode::Char('O'), _) => Some(AppAction::OpenDiscussion),
         (KeyCode::Char('e'), _) => Some(AppAction::EmailArticle),
         (KeyCode::Char('b'), _) => Some(AppAction::SaveToRaindrop),
         (KeyCode::Char(' '), _) => Some(AppAction::BookmarkPrefixStart),
         (KeyCode::Char('g'), _) => Some(AppAction::RegenerateSummary),
+        (KeyCode::Char('c'), _) => Some(AppAction::FetchComments),
+        (KeyCode::Char('x'), _) => Some(AppAction::ExportPdf),
+        (KeyCode::Char('C'), _) => Some(AppAction::CopyBrief),
+        (KeyCode::Char('n'), _) => Some(AppAction::SaveNote),
+        (KeyCode::Char('N'), _) => Some(AppAction::EditNote),
+        (KeyCode::Char('S'), _) => Some(AppAction::CycleReadingMode),
+        (KeyCode::Char('I'), _) => Some(AppAction::CycleFeedPriority),
+        (KeyCode::Char('Z'), _) => Some(AppAction::SnoozeStart),
+        (KeyCode::Char('U'), _) => Some(AppAction::EmbargoStart),
+        (KeyCode::Char('P'), _) => Some(AppAction::TogglePlayback),
         (KeyCode::Char('d'), KeyModifiers::NONE) | (KeyCode::Backspace, _) => Some(AppAction::DeleteArticle),
         (KeyCode::Char('D'), KeyModifiers::SHIFT) => Some(AppAction::DeleteFeed),
         (KeyCode::Char('u'), _) => Some(AppAction::UndeleteArticle),
+        (KeyCode::Char('s'), _) => Some(AppAction::ToggleStar),
+        (KeyCode::Char('p'), KeyModifiers::CONTROL) | (KeyCode::Char(':'), _) => Some(AppAction::PaletteStart),
+        (KeyCode::Char('p'), _) => Some(AppAction::TogglePin),
+        (KeyCode::Char('l'), _) => Some(AppAction::ToggleQueue),
+        (KeyCode::Char('m'), _) => Some(AppAction::ToggleRead),
+        (KeyCode::Char('M'), _) => Some(AppAction::SelectModeStart),
+        (KeyCode::Char('f'), KeyModifiers::CONTROL) => Some(AppAction::FindStart),
+        (KeyCode::Char('f'), _) => Some(AppAction::CycleFilter),
+        (KeyCode::Char('#'), _) => Some(AppAction::TagFilterStart),
+        (KeyCode::Char('/'), _) => Some(AppAction::SearchStart),
+        (KeyCode::Char('E'), _) => Some(AppAction::ShowEntities),
+        (KeyCode::Char('y'), _) => Some(AppAction::ShowAuthors),
+        (KeyCode::Char('Y'), _) => Some(AppAction::ShowArticleAuthor),
+        (KeyCode::Char('X'), _) => Some(AppAction::ShowBlocklist),
+        (KeyCode::Char('@'), _) => Some(AppAction::ShowCitingArticles),
+        (KeyCode::Char('W'), _) => Some(AppAction::ShowWatchlist),
+        (KeyCode::Char('H'), _) => Some(AppAction::ShowTrends),
+        (KeyCode::Char('G'), _) => Some(AppAction::ShowCalendar),
+        (KeyCode::Char('~'), _) => Some(AppAction::ShowRevisions),
+        (KeyCode::Char('A'), _) => Some(AppAction::AskStart),
+        (KeyCode::Char('B'), _) => Some(AppAction::ShowBriefing),
+        (KeyCode::Char('F'), _) => Some(AppAction::ShowFailures),
         (KeyCode::Char('a'), _) => Some(AppAction::AddFeed),
+        (KeyCode::Char('T'), _) => Some(AppAction::RenameFeedStart),
         (KeyCode::Char('i'), _) => Some(AppAction::ImportOpmlStart),
         (KeyCode::Char('w'), _) => Some(AppAction::ExportOpmlStart),
 
+        (KeyCode::Char('L'), _) => Some(AppAction::CycleLayout),
+        (KeyCode::Char('['), _) => Some(AppAction::ResizeList(-3)),
+        (KeyCode::Char(']'), _) => Some(AppAction::ResizeList(3)),
+        (KeyCode::Char('{'), _) => Some(AppAction::ResizeFeeds(-3)),
+        (KeyCode::Char('}'), _) => Some(AppAction::ResizeFeeds(3)),
+        (KeyCode::Tab, _) => Some(AppAction::ToggleFeedFocus),
+        (KeyCode::Char('v'), _) => Some(AppAction::NextLink),
+        (KeyCode::Char('V'), _) => Some(AppAction::PrevLink),
+        (KeyCode::Char(c), _) if c.is_ascii_digit() => c.to_digit(10).map(AppAction::CountDigit),
+
         (KeyCode::Char('?'), _) => Some(AppAction::ShowHelp),
 
         _ => None,
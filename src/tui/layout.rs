@@ -0,0 +1,121 @@
+//! How the main screen is split into panes, and how wide each is. Changes
+//! made with `L`, `[`/`]` and `{`/`}` are saved and come back next launch.
+
+use serde::{Deserialize, Serialize};
+
+use super::SidebarGrouping;
+
+/// Narrowest terminal that gets three panes; below this the feed list is
+/// left out, so `three-pane` still works over an 80-column SSH session
+pub const THREE_PANE_MIN_WIDTH: u16 = 120;
+
+/// Key the pane layout is saved under in the settings table
+pub const PANES_SETTING: &str = "panes";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PaneLayout {
+    /// Feeds | articles | reader
+    ThreePane,
+    /// Articles | reader
+    #[default]
+    TwoPane,
+    /// Just the article list; Enter reads an article full screen
+    ListOnly,
+}
+
+impl PaneLayout {
+    pub fn next(self) -> Self {
+        match self {
+            PaneLayout::ThreePane => PaneLayout::TwoPane,
+            PaneLayout::TwoPane => PaneLayout::ListOnly,
+            PaneLayout::ListOnly => PaneLayout::ThreePane,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PaneLayout::ThreePane => "Three panes",
+            PaneLayout::TwoPane => "Two panes",
+            PaneLayout::ListOnly => "List only",
+        }
+    }
+}
+
+/// The chosen layout and pane widths, in percent of the terminal width
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Panes {
+    pub layout: PaneLayout,
+    /// The feed list, in three-pane layout
+    pub feeds_width: u16,
+    /// The article list, beside the reader
+    pub list_width: u16,
+    /// Leave feeds with nothing unread out of the feed list
+    pub unread_feeds_only: bool,
+    pub grouping: SidebarGrouping,
+}
+
+impl Default for Panes {
+    fn default() -> Self {
+        Self {
+            layout: PaneLayout::default(),
+            feeds_width: 18,
+            list_width: 27,
+            unread_feeds_only: false,
+            grouping: SidebarGrouping::default(),
+        }
+    }
+}
+
+impl Panes {
+    /// Widen (or, negative, narrow) the article list, leaving the reader
+    /// at least a fifth of the screen
+    pub fn resize_list(&mut self, delta: i16) {
+        let max = 80 - self.feeds_width.min(40);
+        self.list_width = self.list_width.saturating_add_signed(delta).clamp(15, max);
+    }
+
+    /// Widen or narrow the feed list
+    pub fn resize_feeds(&mut self, delta: i16) {
+        let max = (80 - self.list_width).min(40);
+        self.feeds_width = self.feeds_width.saturating_add_signed(delta).clamp(10, max);
+    }
+
+    /// The layout to draw in a terminal `width` columns wide
+    pub fn fit(&self, width: u16) -> PaneLayout {
+        match self.layout {
+            PaneLayout::ThreePane if width < THREE_PANE_MIN_WIDTH => PaneLayout::TwoPane,
+            layout => layout,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resizing_keeps_room_for_the_reader() {
+        let mut panes = Panes::default();
+        panes.resize_list(100);
+        assert_eq!(panes.list_width, 62);
+        panes.resize_feeds(100);
+        assert_eq!(panes.feeds_width, 18);
+        panes.resize_list(-100);
+        assert_eq!(panes.list_width, 15);
+        panes.resize_feeds(100);
+        assert_eq!(panes.feeds_width, 40);
+
+        let panes = Panes { layout: PaneLayout::ThreePane, ..Panes::default() };
+        assert_eq!(panes.fit(80), PaneLayout::TwoPane);
+        assert_eq!(panes.fit(200), PaneLayout::ThreePane);
+    }
+
+    #[test]
+    fn test_saved_panes_fill_in_missing_fields() {
+        let panes: Panes = serde_json::from_str(r#"{"layout":"list-only"}"#).unwrap();
+        assert_eq!(panes.layout, PaneLayout::ListOnly);
+        assert_eq!(panes.list_width, Panes::default().list_width);
+    }
+}
@@ -0,0 +1,210 @@
+//! Macros from `[[macros]]`: a named list of steps run on the selected
+//! article with one key, like `star`, `tag column`, `raindrop column`,
+//! `read`. Steps set state rather than toggle it, so running a macro twice
+//! doesn't undo it.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::config::MacroConfig;
+use crate::error::{AppError, Result};
+
+use super::handler::built_in_action;
+
+/// A key as written in the config: one character, `F1`-`F12`, either
+/// after `ctrl+` or `alt+`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyBinding {
+    code: KeyCode,
+    ctrl: bool,
+    alt: bool,
+}
+
+impl KeyBinding {
+    pub fn parse(spec: &str) -> Option<Self> {
+        let (mut ctrl, mut alt) = (false, false);
+        let mut rest = spec.trim();
+        loop {
+            let lower = rest.to_lowercase();
+            if lower.starts_with("ctrl+") || lower.starts_with("ctrl-") {
+                ctrl = true;
+            } else if lower.starts_with("alt+") || lower.starts_with("alt-") {
+                alt = true;
+            } else {
+                break;
+            }
+            rest = &rest[lower.find(['+', '-'])? + 1..];
+        }
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            // Terminals report Ctrl with the lowercase letter
+            (Some(c), None) if ctrl => KeyCode::Char(c.to_ascii_lowercase()),
+            (Some(c), None) => KeyCode::Char(c),
+            _ => {
+                let number: u8 = rest.strip_prefix(['f', 'F'])?.parse().ok()?;
+                (1..=12).contains(&number).then_some(KeyCode::F(number))?
+            }
+        };
+        Some(Self { code, ctrl, alt })
+    }
+
+    /// The key press this is, with shift for uppercase characters
+    pub fn key_event(&self) -> KeyEvent {
+        let mut modifiers = KeyModifiers::NONE;
+        modifiers.set(KeyModifiers::CONTROL, self.ctrl);
+        modifiers.set(KeyModifiers::ALT, self.alt);
+        if matches!(self.code, KeyCode::Char(c) if c.is_uppercase()) {
+            modifiers |= KeyModifiers::SHIFT;
+        }
+        KeyEvent::new(self.code, modifiers)
+    }
+
+    /// Whether `key` is this one. Shift only shows in the character.
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        key.code == self.code
+            && key.modifiers.contains(KeyModifiers::CONTROL) == self.ctrl
+            && key.modifiers.contains(KeyModifiers::ALT) == self.alt
+    }
+}
+
+/// One thing a macro does to the selected article
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MacroStep {
+    Star(bool),
+    Read(bool),
+    Queue(bool),
+    Tag(String),
+    Untag(String),
+    /// Bookmark with these tags (comma-separated, maybe none)
+    Raindrop(String),
+    /// Add to the collection, creating it
+    Collection(String),
+    Summarize,
+    SaveNote,
+    ExportPdf,
+    CopyBrief,
+    Open,
+    Delete,
+    /// Move to the next article; later steps apply to that one
+    Next,
+}
+
+/// Step names, for the error message
+const STEPS: &str = "star, unstar, read, unread, queue, unqueue, tag NAME, untag NAME, raindrop [TAGS], \
+                     collection NAME, summarize, note, pdf, brief, open, delete or next";
+
+impl MacroStep {
+    pub fn parse(step: &str) -> Option<Self> {
+        let step = step.trim();
+        let (verb, arg) = step.split_once(char::is_whitespace).map_or((step, ""), |(v, a)| (v, a.trim()));
+        let tag = || arg.trim_start_matches('#').to_lowercase();
+        Some(match (verb.to_lowercase().as_str(), arg.is_empty()) {
+            ("star", true) => MacroStep::Star(true),
+            ("unstar", true) => MacroStep::Star(false),
+            ("read", true) => MacroStep::Read(true),
+            ("unread", true) => MacroStep::Read(false),
+            ("queue", true) => MacroStep::Queue(true),
+            ("unqueue", true) => MacroStep::Queue(false),
+            ("tag", false) => MacroStep::Tag(tag()),
+            ("untag", false) => MacroStep::Untag(tag()),
+            ("raindrop", _) => MacroStep::Raindrop(arg.to_string()),
+            ("collection", false) => MacroStep::Collection(arg.to_string()),
+            ("summarize", true) => MacroStep::Summarize,
+            ("note", true) => MacroStep::SaveNote,
+            ("pdf", true) => MacroStep::ExportPdf,
+            ("brief", true) => MacroStep::CopyBrief,
+            ("open", true) => MacroStep::Open,
+            ("delete", true) => MacroStep::Delete,
+            ("next", true) => MacroStep::Next,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Macro {
+    pub name: String,
+    pub key: KeyBinding,
+    pub steps: Vec<MacroStep>,
+}
+
+impl Macro {
+    pub fn from_config(config: &MacroConfig) -> Result<Self> {
+        let bad = |what: String| AppError::Config(format!("Bad [[macros]] \"{}\": {}", config.name, what));
+        let key = KeyBinding::parse(&config.key)
+            .ok_or_else(|| bad(format!("key \"{}\" isn't a character or F1-F12, with ctrl+ or alt+", config.key)))?;
+        if let Some(action) = built_in_action(&key) {
+            return Err(bad(format!("key \"{}\" is already taken ({:?})", config.key, action)));
+        }
+        if config.steps.is_empty() {
+            return Err(bad("no steps".to_string()));
+        }
+        let steps = config
+            .steps
+            .iter()
+            .map(|step| MacroStep::parse(step).ok_or_else(|| bad(format!("step \"{}\" isn't one of {}", step, STEPS))))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            name: config.name.clone(),
+            key,
+            steps,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_bindings() {
+        let key = |code, modifiers| KeyEvent::new(code, modifiers);
+        let upper = KeyBinding::parse("K").unwrap();
+        assert!(upper.matches(&key(KeyCode::Char('K'), KeyModifiers::SHIFT)));
+        assert!(!upper.matches(&key(KeyCode::Char('k'), KeyModifiers::NONE)));
+        let ctrl = KeyBinding::parse("Ctrl+K").unwrap();
+        assert!(ctrl.matches(&key(KeyCode::Char('k'), KeyModifiers::CONTROL)));
+        assert!(!ctrl.matches(&key(KeyCode::Char('k'), KeyModifiers::NONE)));
+        let both = KeyBinding::parse("alt+ctrl+x").unwrap();
+        assert!(both.matches(&key(KeyCode::Char('x'), KeyModifiers::CONTROL | KeyModifiers::ALT)));
+        assert!(KeyBinding::parse("F5").unwrap().matches(&key(KeyCode::F(5), KeyModifiers::NONE)));
+        assert!(KeyBinding::parse("+").unwrap().matches(&key(KeyCode::Char('+'), KeyModifiers::NONE)));
+        for bad in ["", "ctrl+", "F13", "kk", "super+k"] {
+            assert!(KeyBinding::parse(bad).is_none(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_macro_from_config() {
+        let config = |key: &str, steps: &[&str]| MacroConfig {
+            name: "Column".to_string(),
+            key: key.to_string(),
+            steps: steps.iter().map(|s| s.to_string()).collect(),
+        };
+        let steps = ["star", "tag #Column", "raindrop column, tech", "read", "next"];
+        let column = Macro::from_config(&config("F2", &steps)).unwrap();
+        assert_eq!(
+            column.steps,
+            [
+                MacroStep::Star(true),
+                MacroStep::Tag("column".to_string()),
+                MacroStep::Raindrop("column, tech".to_string()),
+                MacroStep::Read(true),
+                MacroStep::Next,
+            ]
+        );
+        assert_eq!(MacroStep::parse("raindrop"), Some(MacroStep::Raindrop(String::new())));
+        assert!(Macro::from_config(&config("F2", &[])).is_err());
+        assert!(Macro::from_config(&config("F2", &["tag"])).is_err());
+        assert!(Macro::from_config(&config("F2", &["star it"])).is_err());
+        assert!(Macro::from_config(&config("hyper+k", &["star"])).is_err());
+
+        // Keys with a built-in action are refused, unless ctrl or alt makes
+        // them different keys
+        for taken in ["q", "K", "D", "ctrl+c", "ctrl+p", "ctrl+f", "?", "1"] {
+            assert!(Macro::from_config(&config(taken, &["star"])).is_err(), "{}", taken);
+        }
+        for free in ["h", "z", "Q", "ctrl+k", "alt+s", "F2"] {
+            assert!(Macro::from_config(&config(free, &["star"])).is_ok(), "{}", free);
+        }
+    }
+}
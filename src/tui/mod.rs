@@ -1,5 +1,19 @@
 mod ui;
 mod handler;
+mod layout;
+mod reader;
+mod palette;
+mod editor;
+mod sidebar;
+mod snooze;
+mod macros;
 
 pub use ui::draw;
-pub use handler::{handle_key_event, AppAction};
+pub use handler::{handle_key_event, AppAction, KeyContext};
+pub use layout::{PaneLayout, Panes, PANES_SETTING};
+pub use editor::{external_editor, run_editor, NoteEdit};
+pub use palette::{fuzzy_score, palette_matches, BlockTarget, PaletteCommand};
+pub use sidebar::{sidebar_rows, SidebarGrouping, SidebarRow, SidebarView};
+pub use snooze::{format_snooze, parse_snooze, SnoozeChoice};
+pub use macros::{KeyBinding, Macro, MacroStep};
+pub use reader::{article_links, reader_text, ArticleFind, ArticleLink};
@@ -0,0 +1,241 @@
+//! The command palette (Ctrl-p or `:`): every command by name with its key,
+//! narrowed down by typing a few letters of it, plus commands that take
+//! words, like `tag add security` or `goto feed verge`.
+
+use super::AppAction;
+
+/// A command the palette lists
+#[derive(Debug)]
+pub struct PaletteEntry {
+    pub name: &'static str,
+    /// What to press to run it without the palette
+    pub key: &'static str,
+    pub action: AppAction,
+}
+
+const fn entry(name: &'static str, key: &'static str, action: AppAction) -> PaletteEntry {
+    PaletteEntry { name, key, action }
+}
+
+pub static PALETTE_ENTRIES: &[PaletteEntry] = &[
+    entry("Refresh all feeds", "r", AppAction::RefreshFeeds),
+    entry("Add feed", "a", AppAction::AddFeed),
+    entry("Recommend feeds from starred sites", "R", AppAction::ShowRecommendations),
+    entry("Rename feed", "T", AppAction::RenameFeedStart),
+    entry("Unsubscribe from feed", "D", AppAction::DeleteFeed),
+    entry("Import OPML file", "i", AppAction::ImportOpmlStart),
+    entry("Export OPML file", "w", AppAction::ExportOpmlStart),
+    entry("Generate summary", "Enter", AppAction::SelectArticle),
+    entry("Regenerate summary", "g", AppAction::RegenerateSummary),
+    entry("Failed summaries", "F", AppAction::ShowFailures),
+    entry("Open in browser", "o", AppAction::OpenInBrowser),
+    entry("Open HN/Lobsters discussion", "O", AppAction::OpenDiscussion),
+    entry("Fetch comments", "c", AppAction::FetchComments),
+    entry("Email article", "e", AppAction::EmailArticle),
+    entry("Bookmark to Raindrop.io", "b", AppAction::SaveToRaindrop),
+    entry("Export article to PDF", "x", AppAction::ExportPdf),
+    entry("Copy brief with summary to clipboard", "C", AppAction::CopyBrief),
+    entry("Save as note to vault", "n", AppAction::SaveNote),
+    entry("Write a note on the article", "N", AppAction::EditNote),
+    entry("Play/stop podcast episode", "P", AppAction::TogglePlayback),
+    entry("Toggle starred", "s", AppAction::ToggleStar),
+    entry("Pin/unpin", "p", AppAction::TogglePin),
+    entry("Add to/remove from read-later queue", "l", AppAction::ToggleQueue),
+    entry("Mark read/unread", "m", AppAction::ToggleRead),
+    entry("Snooze until tonight/tomorrow/next week/later", "Z", AppAction::SnoozeStart),
+    entry("Embargo until a set time, or lift the embargo", "U", AppAction::EmbargoStart),
+    entry("Delete article", "d", AppAction::DeleteArticle),
+    entry("Undelete last", "u", AppAction::UndeleteArticle),
+    entry("Select articles", "M", AppAction::SelectModeStart),
+    entry("Cycle filter (All/Starred/Queue/Discussed/Recently read)", "f", AppAction::CycleFilter),
+    entry("Filter by tag", "#", AppAction::TagFilterStart),
+    entry("Search", "/", AppAction::SearchStart),
+    entry("Browse people, companies & tickers", "E", AppAction::ShowEntities),
+    entry("Browse authors", "y", AppAction::ShowAuthors),
+    entry("Articles by this article's author", "Y", AppAction::ShowArticleAuthor),
+    entry("Blocked keywords and sites", "X", AppAction::ShowBlocklist),
+    entry("Articles linking to this one", "@", AppAction::ShowCitingArticles),
+    entry("Watchlist coverage timelines", "W", AppAction::ShowWatchlist),
+    entry("Trending headline terms", "H", AppAction::ShowTrends),
+    entry("Calendar of articles per day", "G", AppAction::ShowCalendar),
+    entry("Earlier versions of the article, with what changed", "~", AppAction::ShowRevisions),
+    entry("Ask about the article", "A", AppAction::AskStart),
+    entry("Compare outlets covering this story", "B", AppAction::ShowBriefing),
+    entry("Find in article", "Ctrl+f", AppAction::FindStart),
+    entry("Highlight next link", "v", AppAction::NextLink),
+    entry("Cycle layout", "L", AppAction::CycleLayout),
+    entry("Read this feed in full/as teasers/as headlines", "S", AppAction::CycleReadingMode),
+    entry("Set this feed's priority (normal/high/low)", "I", AppAction::CycleFeedPriority),
+    entry("Switch to the feed list", "Tab", AppAction::ToggleFeedFocus),
+    entry("Only feeds with unread articles", "u (feed list)", AppAction::ToggleUnreadFeeds),
+    entry("Widen the article list", "]", AppAction::ResizeList(3)),
+    entry("Narrow the article list", "[", AppAction::ResizeList(-3)),
+    entry("Widen the feed list", "}", AppAction::ResizeFeeds(3)),
+    entry("Narrow the feed list", "{", AppAction::ResizeFeeds(-3)),
+    entry("Go to top", "<", AppAction::MoveToTop),
+    entry("Go to bottom", ">", AppAction::MoveToBottom),
+    entry("Help", "?", AppAction::ShowHelp),
+    entry("Quit", "q", AppAction::Quit),
+];
+
+/// A command typed out with its arguments
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PaletteCommand {
+    TagAdd(String),
+    TagRemove(String),
+    /// The feed whose title best matches
+    GotoFeed(String),
+    /// A view by name: all, starred, queue, discussed or history
+    GotoView(String),
+    Search(String),
+    /// Add the selected (or picked) articles to a collection, creating it
+    CollectionAdd(String),
+    CollectionRemove(String),
+    /// Move the selected article to a position in the collection shown
+    CollectionMove(usize),
+    /// Write a collection out as one document, in a format if given
+    CollectionExport(String, Option<String>),
+    GotoCollection(String),
+    Block(BlockTarget),
+    /// Take a keyword or `site:` entry out of the blocklist
+    Unblock(String),
+}
+
+/// What `block` adds to the blocklist
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockTarget {
+    /// A keyword, or `site:` and a domain, as typed
+    Entry(String),
+    /// The selected article's site
+    ArticleSite,
+    /// The selected article's author, muted
+    ArticleAuthor,
+}
+
+impl PaletteCommand {
+    /// `tag add NAME`, `tag remove NAME`, `goto feed NAME`, `goto VIEW`,
+    /// `search QUERY`, `goto collection NAME` or `collection add|remove|export
+    /// NAME`, `collection export md|html|epub NAME`, `collection move N`,
+    /// `block KEYWORD|site|author` and `unblock ENTRY`; `None` for anything
+    /// else, which is looked up by name
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        let (verb, rest) = input.split_once(char::is_whitespace)?;
+        let rest = rest.trim();
+        let (sub, arg) = rest.split_once(char::is_whitespace).map_or((rest, ""), |(s, a)| (s, a.trim()));
+        let tag = || arg.trim_start_matches('#').to_lowercase();
+        match (verb.to_lowercase().as_str(), sub.to_lowercase().as_str()) {
+            ("tag", "add") if !arg.is_empty() => Some(PaletteCommand::TagAdd(tag())),
+            ("tag", "remove" | "rm") if !arg.is_empty() => Some(PaletteCommand::TagRemove(tag())),
+            ("goto", "feed") if !arg.is_empty() => Some(PaletteCommand::GotoFeed(arg.to_string())),
+            ("goto", view) if arg.is_empty() && ["all", "starred", "queue", "discussed", "history"].contains(&view) => {
+                Some(PaletteCommand::GotoView(view.to_string()))
+            }
+            ("search", _) => Some(PaletteCommand::Search(rest.to_string())),
+            ("goto", "collection") if !arg.is_empty() => Some(PaletteCommand::GotoCollection(arg.to_string())),
+            ("collection", "add") if !arg.is_empty() => Some(PaletteCommand::CollectionAdd(arg.to_string())),
+            ("collection", "remove" | "rm") if !arg.is_empty() => Some(PaletteCommand::CollectionRemove(arg.to_string())),
+            ("collection", "move") => arg.parse().ok().map(PaletteCommand::CollectionMove),
+            ("block", "site") if arg.is_empty() => Some(PaletteCommand::Block(BlockTarget::ArticleSite)),
+            ("block", "site") => Some(PaletteCommand::Block(BlockTarget::Entry(format!("site:{}", arg)))),
+            ("block", "author") if arg.is_empty() => Some(PaletteCommand::Block(BlockTarget::ArticleAuthor)),
+            ("block", _) => Some(PaletteCommand::Block(BlockTarget::Entry(rest.to_string()))),
+            ("unblock", _) => Some(PaletteCommand::Unblock(rest.to_string())),
+            ("collection", "export") if !arg.is_empty() => {
+                let format = arg
+                    .split_once(char::is_whitespace)
+                    .filter(|(format, _)| ["md", "markdown", "html", "epub"].contains(&format.to_lowercase().as_str()));
+                Some(match format {
+                    Some((format, name)) => PaletteCommand::CollectionExport(name.trim().to_string(), Some(format.to_string())),
+                    None => PaletteCommand::CollectionExport(arg.to_string(), None),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// How well `query` matches `candidate`: its letters must all appear, in
+/// order, ignoring case. Runs of adjacent letters and letters starting a
+/// word score higher. `None` if it doesn't match at all.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<u32> {
+    let candidate: Vec<char> = candidate.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut at = 0;
+    let mut last: Option<usize> = None;
+    for q in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = at + candidate[at..].iter().position(|&c| c == q)?;
+        score += 1;
+        if last.is_some_and(|l| l + 1 == found) {
+            score += 3;
+        }
+        if found == 0 || !candidate[found - 1].is_alphanumeric() {
+            score += 2;
+        }
+        last = Some(found);
+        at = found + 1;
+    }
+    Some(score)
+}
+
+/// The palette entries matching `query`, best first
+pub fn palette_matches(query: &str) -> Vec<&'static PaletteEntry> {
+    let mut matches: Vec<(u32, &PaletteEntry)> = PALETTE_ENTRIES
+        .iter()
+        .filter_map(|entry| fuzzy_score(query, entry.name).map(|score| (score, entry)))
+        .collect();
+    // Stable, so equal scores keep the table's order
+    matches.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    matches.into_iter().map(|(_, entry)| entry).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_prefers_word_starts_and_runs() {
+        assert!(fuzzy_score("rfa", "Refresh all feeds").is_some());
+        assert!(fuzzy_score("xyz", "Refresh all feeds").is_none());
+        assert!(fuzzy_score("star", "Toggle starred") > fuzzy_score("star", "Compare outlets covering this story"));
+        assert_eq!(palette_matches("quit")[0].name, "Quit");
+        assert_eq!(palette_matches("").len(), PALETTE_ENTRIES.len());
+    }
+
+    #[test]
+    fn test_parse_commands_with_arguments() {
+        assert_eq!(PaletteCommand::parse("tag add #Security"), Some(PaletteCommand::TagAdd("security".into())));
+        assert_eq!(PaletteCommand::parse("goto feed the verge"), Some(PaletteCommand::GotoFeed("the verge".into())));
+        assert_eq!(PaletteCommand::parse("goto starred"), Some(PaletteCommand::GotoView("starred".into())));
+        assert_eq!(
+            PaletteCommand::parse("search tag:ai -crypto"),
+            Some(PaletteCommand::Search("tag:ai -crypto".into()))
+        );
+        assert_eq!(
+            PaletteCommand::parse("collection export epub Tuesday column"),
+            Some(PaletteCommand::CollectionExport("Tuesday column".into(), Some("epub".into())))
+        );
+        assert_eq!(
+            PaletteCommand::parse("collection export Tuesday column"),
+            Some(PaletteCommand::CollectionExport("Tuesday column".into(), None))
+        );
+        assert_eq!(PaletteCommand::parse("collection move 2"), Some(PaletteCommand::CollectionMove(2)));
+        assert_eq!(
+            PaletteCommand::parse("block Elon Musk"),
+            Some(PaletteCommand::Block(BlockTarget::Entry("Elon Musk".into())))
+        );
+        assert_eq!(
+            PaletteCommand::parse("block site example.com"),
+            Some(PaletteCommand::Block(BlockTarget::Entry("site:example.com".into())))
+        );
+        assert_eq!(PaletteCommand::parse("block site"), Some(PaletteCommand::Block(BlockTarget::ArticleSite)));
+        assert_eq!(PaletteCommand::parse("block author"), Some(PaletteCommand::Block(BlockTarget::ArticleAuthor)));
+        assert_eq!(
+            PaletteCommand::parse("unblock site:example.com"),
+            Some(PaletteCommand::Unblock("site:example.com".into()))
+        );
+        assert_eq!(PaletteCommand::parse("block"), None);
+        assert_eq!(PaletteCommand::parse("tag add"), None);
+        assert_eq!(PaletteCommand::parse("refresh"), None);
+    }
+}
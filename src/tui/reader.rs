@@ -0,0 +1,213 @@
+//! What the reader pane shows of an article, and finding things in it:
+//! matches for in-article search, and the numbered links html2text leaves
+//! in the text (`[text][3]`, with `[3]: https://...` at the end).
+
+use std::ops::Range;
+
+use regex::{Regex, RegexBuilder};
+
+use crate::models::{Article, ReadingMode};
+
+/// What the reader shows of an article, on one line: its full text if it
+/// was fetched, else the feed's; or just the feed's first paragraph, or
+/// nothing, as its feed is set to be read
+pub fn reader_text(article: Option<&Article>, mode: ReadingMode) -> String {
+    let text = match mode {
+        ReadingMode::Full => {
+            article.and_then(|a| a.full_text.as_ref().or(a.content_text.as_ref()).or(a.content.as_ref()))
+        }
+        ReadingMode::Teaser => article.and_then(|a| a.content_text.as_ref().or(a.content.as_ref())),
+        ReadingMode::Headlines => {
+            return "Headlines only for this feed: o opens the article, S shows more".to_string();
+        }
+    };
+    let text = match (mode, text) {
+        (ReadingMode::Teaser, Some(text)) => first_paragraph(text),
+        (_, text) => text.map(String::as_str),
+    };
+    text.map(|c| c.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|c| !c.is_empty())
+        .unwrap_or_else(|| "No content available".to_string())
+}
+
+/// Up to the first blank line (or, failing that, line break) that has
+/// something before it
+fn first_paragraph(text: &str) -> Option<&str> {
+    let text = text.trim();
+    let separator = if text.contains("\n\n") { "\n\n" } else { "\n" };
+    text.split(separator).map(str::trim).find(|p| !p.is_empty())
+}
+
+/// A search within the open article, kept while moving between articles
+#[derive(Debug, Clone)]
+pub struct ArticleFind {
+    pub term: String,
+    regex: Regex,
+    /// Which match is selected, for n/N
+    pub current: usize,
+}
+
+impl ArticleFind {
+    /// A case-insensitive search for `term` as typed; `None` if it's blank
+    pub fn new(term: &str) -> Option<Self> {
+        let term = term.trim();
+        if term.is_empty() {
+            return None;
+        }
+        let regex = RegexBuilder::new(&regex::escape(term)).case_insensitive(true).build().ok()?;
+        Some(Self {
+            term: term.to_string(),
+            regex,
+            current: 0,
+        })
+    }
+
+    pub fn matches(&self, text: &str) -> Vec<Range<usize>> {
+        self.regex.find_iter(text).map(|m| m.range()).collect()
+    }
+}
+
+/// A numbered link in the reader text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArticleLink {
+    pub number: usize,
+    pub url: String,
+    /// Its first `[N]` reference in the text, or the footnote if there's none
+    pub at: Range<usize>,
+}
+
+/// Every footnoted link, by number
+pub fn article_links(text: &str) -> Vec<ArticleLink> {
+    let footnote = Regex::new(r"\[(\d+)\]: (\S+)").unwrap();
+    let mut links: Vec<ArticleLink> = footnote
+        .captures_iter(text)
+        .filter_map(|caps| {
+            let number = caps[1].parse().ok()?;
+            let whole = caps.get(0)?;
+            let marker = format!("[{}]", number);
+            let at = text
+                .match_indices(&marker)
+                .map(|(start, m)| start..start + m.len())
+                .find(|r| !text[r.end..].starts_with(':'))
+                .unwrap_or(whole.start()..whole.start() + marker.len());
+            Some(ArticleLink {
+                number,
+                url: caps[2].to_string(),
+                at,
+            })
+        })
+        .collect();
+    links.sort_by_key(|link| link.number);
+    links.dedup_by_key(|link| link.number);
+    links
+}
+
+/// The row `offset` lands on once `text` is word-wrapped to `width`
+/// columns, roughly as the reader paragraph wraps it
+pub fn wrapped_row(text: &str, offset: usize, width: u16) -> u16 {
+    let width = usize::from(width.max(1));
+    let (mut row, mut col) = (0usize, 0usize);
+    let mut start = 0;
+    for word in text.split(' ') {
+        let len = word.chars().count();
+        if col > 0 && col + len > width {
+            row += 1;
+            col = 0;
+        }
+        if offset < start + word.len() + 1 {
+            let within = text[start..offset.max(start)].chars().count();
+            return (row + (col + within) / width) as u16;
+        }
+        // Words longer than a line are broken across rows
+        let end = col + len;
+        if end > 0 {
+            row += (end - 1) / width;
+            col = (end - 1) % width + 2;
+        } else {
+            col += 1;
+        }
+        start += word.len() + 1;
+    }
+    row as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn test_article() -> Article {
+        Article {
+            id: 1,
+            feed_id: 1,
+            guid: "g".to_string(),
+            title: "Title".to_string(),
+            url: "https://example.com/a".to_string(),
+            author: None,
+            content: None,
+            content_text: None,
+            published_at: None,
+            fetched_at: Utc::now(),
+            feed_title: None,
+            is_starred: false,
+            is_pinned: false,
+            reading_minutes: None,
+            is_queued: false,
+            is_read: false,
+            full_text: None,
+            comments_url: None,
+            discussion: None,
+            enclosure: None,
+            playback_secs: None,
+            is_played: false,
+            extras: None,
+            archive_path: None,
+            tags: Vec::new(),
+            by_followed_author: false,
+            embargo_until: None,
+            revisions: 0,
+            removed_at: None,
+        }
+    }
+
+    #[test]
+    fn test_find_is_case_insensitive_and_literal() {
+        let find = ArticleFind::new(" rust (1.80) ").unwrap();
+        let text = "Rust (1.80) ships. rust (1.80) again; Rust 1.80 not";
+        assert_eq!(find.matches(text), [0..11, 19..30]);
+        assert!(ArticleFind::new("  ").is_none());
+    }
+
+    #[test]
+    fn test_links_point_at_their_first_reference() {
+        let text = "See [the post][2] and [docs][1], [again][2]. [1]: https://a.example/docs [2]: /post";
+        let links = article_links(text);
+        assert_eq!(links.len(), 2);
+        assert_eq!((links[0].number, links[0].url.as_str()), (1, "https://a.example/docs"));
+        assert_eq!(&text[links[1].at.clone()], "[2]");
+        assert_eq!(links[1].at.start, 14);
+    }
+
+    #[test]
+    fn test_teaser_is_the_first_paragraph() {
+        let article = Article {
+            content_text: Some("\n  First part\nstill first.\n\nSecond paragraph.".to_string()),
+            full_text: Some("The whole page".to_string()),
+            ..test_article()
+        };
+        assert_eq!(reader_text(Some(&article), ReadingMode::Teaser), "First part still first.");
+        assert_eq!(reader_text(Some(&article), ReadingMode::Full), "The whole page");
+        assert_eq!(reader_text(None, ReadingMode::Teaser), "No content available");
+    }
+
+    #[test]
+    fn test_wrapped_row() {
+        let text = "aaaa bbbb cccc dddd";
+        assert_eq!(wrapped_row(text, 0, 10), 0);
+        assert_eq!(wrapped_row(text, 5, 10), 0);
+        assert_eq!(wrapped_row(text, 10, 10), 1);
+        assert_eq!(wrapped_row(text, 15, 10), 1);
+        assert_eq!(wrapped_row(text, 15, 4), 3);
+    }
+}
@@ -0,0 +1,202 @@
+//! The rows of the feed list: views across every feed at the top ("All",
+//! "Starred", "Today" and the smart views), then feeds grouped by folder or
+//! by tag under headers that fold away.
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::Feed;
+
+/// What the feed list groups feeds by (`g` in the feed list switches)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SidebarGrouping {
+    #[default]
+    Folder,
+    /// A feed with several tags is listed under each
+    Tag,
+}
+
+impl SidebarGrouping {
+    pub fn next(self) -> Self {
+        match self {
+            SidebarGrouping::Folder => SidebarGrouping::Tag,
+            SidebarGrouping::Tag => SidebarGrouping::Folder,
+        }
+    }
+
+    /// The groups a feed is listed under; none leaves it below the groups
+    pub fn groups(self, feed: &Feed) -> Vec<String> {
+        match self {
+            SidebarGrouping::Folder => feed.folder.iter().cloned().collect(),
+            SidebarGrouping::Tag => feed.tags.clone(),
+        }
+    }
+
+    /// How a folded group is remembered, so folding folder "news" leaves
+    /// tag "news" alone
+    pub fn collapse_key(self, group: &str) -> String {
+        match self {
+            SidebarGrouping::Folder => format!("folder:{}", group),
+            SidebarGrouping::Tag => format!("tag:{}", group),
+        }
+    }
+}
+
+/// The views listed above the feeds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SidebarView {
+    All,
+    Starred,
+    /// Published (or, undated, fetched) since midnight
+    Today,
+    Queue,
+    Discussed,
+    History,
+}
+
+pub const SIDEBAR_VIEWS: [SidebarView; 6] = [
+    SidebarView::All,
+    SidebarView::Starred,
+    SidebarView::Today,
+    SidebarView::Queue,
+    SidebarView::Discussed,
+    SidebarView::History,
+];
+
+impl SidebarView {
+    pub fn label(self) -> &'static str {
+        match self {
+            SidebarView::All => "All feeds",
+            SidebarView::Starred => "★ Starred",
+            SidebarView::Today => "Today",
+            SidebarView::Queue => "» Queue",
+            SidebarView::Discussed => "▲ Discussed",
+            SidebarView::History => "◷ Recently read",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum SidebarRow<'a> {
+    View(SidebarView),
+    Group {
+        name: String,
+        unread: u32,
+        collapsed: bool,
+    },
+    Feed {
+        feed: &'a Feed,
+        /// The group it's listed under, if any
+        group: Option<String>,
+    },
+}
+
+/// Every row of the feed list. `feeds` come in display order; groups are
+/// sorted by name, and a collapsed group hides its feeds but keeps its
+/// unread total.
+pub fn sidebar_rows<'a>(feeds: &[&'a Feed], grouping: SidebarGrouping, collapsed: &[String]) -> Vec<SidebarRow<'a>> {
+    let mut rows: Vec<SidebarRow> = SIDEBAR_VIEWS.into_iter().map(SidebarRow::View).collect();
+
+    let mut groups: Vec<String> = feeds.iter().flat_map(|f| grouping.groups(f)).collect();
+    groups.sort_by_key(|g| g.to_lowercase());
+    groups.dedup();
+
+    for name in groups {
+        let members: Vec<&Feed> = feeds.iter().copied().filter(|f| grouping.groups(f).contains(&name)).collect();
+        let is_collapsed = collapsed.contains(&grouping.collapse_key(&name));
+        rows.push(SidebarRow::Group {
+            name: name.clone(),
+            unread: members.iter().map(|f| f.unread).sum(),
+            collapsed: is_collapsed,
+        });
+        if !is_collapsed {
+            rows.extend(members.into_iter().map(|feed| SidebarRow::Feed {
+                feed,
+                group: Some(name.clone()),
+            }));
+        }
+    }
+
+    rows.extend(
+        feeds
+            .iter()
+            .filter(|f| grouping.groups(f).is_empty())
+            .map(|feed| SidebarRow::Feed { feed, group: None }),
+    );
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn feed(id: i64, folder: Option<&str>, tags: &[&str], unread: u32) -> Feed {
+        Feed {
+            id,
+            title: format!("Feed {}", id),
+            url: format!("https://example.com/{}", id),
+            site_url: None,
+            description: None,
+            last_fetched: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            retry_after: None,
+            custom_title: None,
+            folder: folder.map(str::to_string),
+            refresh_minutes: None,
+            paused: false,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            posts_per_day: None,
+            last_post_at: None,
+            unread,
+            reading: Default::default(),
+            priority: Default::default(),
+            source: Default::default(),
+            reader_url: None,
+            missing_count: 0,
+            moved_to: None,
+            repaired: false,
+        }
+    }
+
+    fn labels(rows: &[SidebarRow]) -> Vec<String> {
+        rows.iter()
+            .skip(SIDEBAR_VIEWS.len())
+            .map(|row| match row {
+                SidebarRow::View(view) => view.label().to_string(),
+                SidebarRow::Group { name, unread, collapsed } => {
+                    format!("{}{} {}", if *collapsed { "+" } else { "-" }, name, unread)
+                }
+                SidebarRow::Feed { feed, .. } => feed.id.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rows_group_by_folder_and_fold() {
+        let feeds = [
+            feed(1, Some("tech"), &[], 2),
+            feed(2, None, &[], 1),
+            feed(3, Some("Arts"), &[], 0),
+            feed(4, Some("tech"), &[], 5),
+        ];
+        let feeds: Vec<&Feed> = feeds.iter().collect();
+
+        let rows = sidebar_rows(&feeds, SidebarGrouping::Folder, &[]);
+        assert!(matches!(rows[2], SidebarRow::View(SidebarView::Today)));
+        assert_eq!(labels(&rows), ["-Arts 0", "3", "-tech 7", "1", "4", "2"]);
+
+        let rows = sidebar_rows(&feeds, SidebarGrouping::Folder, &["folder:tech".to_string()]);
+        assert_eq!(labels(&rows), ["-Arts 0", "3", "+tech 7", "2"]);
+    }
+
+    #[test]
+    fn test_rows_group_by_tag_list_feeds_under_each_tag() {
+        let feeds = [feed(1, Some("tech"), &["ai", "news"], 2), feed(2, None, &["news"], 1)];
+        let feeds: Vec<&Feed> = feeds.iter().collect();
+        let rows = sidebar_rows(&feeds, SidebarGrouping::Tag, &["folder:news".to_string()]);
+        assert_eq!(labels(&rows), ["-ai 2", "1", "-news 3", "1", "2"]);
+    }
+}
@@ -0,0 +1,123 @@
+//! When a snoozed article comes back: tonight, tomorrow morning, next
+//! Monday, or a time typed out like `3h`, `2d`, `18:30` or `2026-03-02 09:00`.
+//! Everything is in local time.
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime};
+
+/// Evenings start here; mornings at `MORNING`
+const EVENING: (u32, u32) = (19, 0);
+const MORNING: (u32, u32) = (8, 0);
+
+/// The times offered in the snooze popup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnoozeChoice {
+    /// This evening, or in three hours once it's evening already
+    Tonight,
+    Tomorrow,
+    /// Monday morning, at least a day away
+    NextWeek,
+}
+
+impl SnoozeChoice {
+    pub fn until(self, now: NaiveDateTime) -> NaiveDateTime {
+        match self {
+            SnoozeChoice::Tonight => {
+                let evening = at(now.date(), EVENING);
+                if evening > now {
+                    evening
+                } else {
+                    now + Duration::hours(3)
+                }
+            }
+            SnoozeChoice::Tomorrow => at(now.date() + Duration::days(1), MORNING),
+            SnoozeChoice::NextWeek => {
+                let days = 7 - i64::from(now.weekday().num_days_from_monday());
+                at(now.date() + Duration::days(days), MORNING)
+            }
+        }
+    }
+}
+
+fn at(date: NaiveDate, (hour, minute): (u32, u32)) -> NaiveDateTime {
+    date.and_time(NaiveTime::from_hms_opt(hour, minute, 0).unwrap_or_default())
+}
+
+/// A custom snooze: `90m`, `3h`, `2d` or `1w` from now, `HH:MM` (the
+/// next time the clock says so), `YYYY-MM-DD` (that morning) or
+/// `YYYY-MM-DD HH:MM`. `None` for anything else or a time already past.
+pub fn parse_snooze(input: &str, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let input = input.trim();
+    let until = if let Ok(until) = NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M") {
+        until
+    } else if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        at(date, MORNING)
+    } else if let Ok(time) = NaiveTime::parse_from_str(input, "%H:%M") {
+        let today = now.date().and_time(time);
+        if today > now {
+            today
+        } else {
+            today + Duration::days(1)
+        }
+    } else {
+        let unit = input.chars().last()?;
+        let amount: i64 = input[..input.len() - unit.len_utf8()].trim().parse().ok()?;
+        let duration = match unit.to_ascii_lowercase() {
+            'm' => Duration::minutes(amount),
+            'h' => Duration::hours(amount),
+            'd' => Duration::days(amount),
+            'w' => Duration::weeks(amount),
+            _ => return None,
+        };
+        now + duration
+    };
+    (until > now).then_some(until)
+}
+
+/// `19:00` today, `Thu 08:00` within the week, `Mon Mar 16 08:00` after
+pub fn format_snooze(until: NaiveDateTime, now: NaiveDateTime) -> String {
+    if until.date() == now.date() {
+        until.format("%H:%M").to_string()
+    } else if until.date() < now.date() + Duration::days(7) {
+        until.format("%a %H:%M").to_string()
+    } else {
+        until.format("%a %b %-d %H:%M").to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_choices_from_morning_and_evening() {
+        // 2026-03-04 is a Wednesday
+        let morning = time("2026-03-04 10:00");
+        assert_eq!(SnoozeChoice::Tonight.until(morning), time("2026-03-04 19:00"));
+        assert_eq!(SnoozeChoice::Tomorrow.until(morning), time("2026-03-05 08:00"));
+        assert_eq!(SnoozeChoice::NextWeek.until(morning), time("2026-03-09 08:00"));
+
+        let late = time("2026-03-09 21:30");
+        assert_eq!(SnoozeChoice::Tonight.until(late), time("2026-03-10 00:30"));
+        assert_eq!(SnoozeChoice::NextWeek.until(late), time("2026-03-16 08:00"));
+        assert_eq!(format_snooze(time("2026-03-16 08:00"), late), "Mon Mar 16 08:00");
+        assert_eq!(format_snooze(time("2026-03-10 00:30"), late), "Tue 00:30");
+    }
+
+    #[test]
+    fn test_parse_custom_snoozes() {
+        let now = time("2026-03-04 10:00");
+        assert_eq!(parse_snooze("3h", now), Some(time("2026-03-04 13:00")));
+        assert_eq!(parse_snooze("90m", now), Some(time("2026-03-04 11:30")));
+        assert_eq!(parse_snooze("2d", now), Some(time("2026-03-06 10:00")));
+        assert_eq!(parse_snooze("09:15", now), Some(time("2026-03-05 09:15")));
+        assert_eq!(parse_snooze("2026-03-20", now), Some(time("2026-03-20 08:00")));
+        assert_eq!(parse_snooze("2026-03-20 17:45", now), Some(time("2026-03-20 17:45")));
+        for bad in ["", "soon", "3x", "-2h", "2026-01-01"] {
+            assert_eq!(parse_snooze(bad, now), None, "{}", bad);
+        }
+    }
+}
@@ -7,8 +7,19 @@ use ratatui::{
     Frame,
 };
 
-use crate::app::App;
-use crate::models::SummaryStatus;
+use crate::app::{App, FilterMode, ASK_MAX_ARTICLES};
+use crate::blocklist::{KeywordMatch, SITE_PREFIX};
+use crate::calendar::Calendar;
+use crate::diff::{word_diff, Change};
+use crate::db::QUERY_FIELDS;
+use crate::models::{
+    AuthorRule, Comment, EntityKind, Feed, FeedPriority, ReadingMode, SourceInfo, Summary, SummaryStatus,
+};
+use crate::watchlist::TIMELINE_DAYS;
+
+use super::palette::{palette_matches, PaletteCommand};
+use super::reader::wrapped_row;
+use super::{format_snooze, parse_snooze, PaneLayout, SidebarGrouping, SidebarRow, SidebarView, SnoozeChoice};
 
 pub fn draw(frame: &mut Frame, app: &App) {
     // Main vertical split: content area + status bar
@@ -20,42 +31,36 @@ pub fn draw(frame: &mut Frame, app: &App) {
         ])
         .split(frame.area());
 
-    // Content area: left pane + right pane
-    let main_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage(27), // Left pane: article list
-            Constraint::Percentage(73), // Right pane: summary
-        ])
-        .split(main_vertical[0]);
-
-    // Left pane: header + article list
-    let left_chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3), // Title bar
-            Constraint::Min(0),    // Article list
-        ])
-        .split(main_chunks[0]);
-
-    // Right pane: title + feed content + AI summary
-    let right_chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3),   // Article title (single line)
-            Constraint::Percentage(30), // Feed content (30%)
-            Constraint::Percentage(70), // AI summary (70%)
-        ])
-        .split(main_chunks[1]);
-
-    // Render left pane
-    render_header(frame, app, left_chunks[0]);
-    render_article_list(frame, app, left_chunks[1]);
-
-    // Render right pane
-    render_article_title(frame, app, right_chunks[0]);
-    render_feed_content(frame, app, right_chunks[1]);
-    render_summary(frame, app, right_chunks[2]);
+    let content = main_vertical[0];
+    let panes = &app.panes;
+    match panes.fit(frame.area().width) {
+        // Feeds | articles | reader
+        PaneLayout::ThreePane => {
+            let chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([
+                    Constraint::Percentage(panes.feeds_width),
+                    Constraint::Percentage(panes.list_width),
+                    Constraint::Min(0),
+                ])
+                .split(content);
+            render_feed_list(frame, app, chunks[0]);
+            render_list_pane(frame, app, chunks[1]);
+            render_reader(frame, app, chunks[2]);
+        }
+        // Articles | reader
+        PaneLayout::TwoPane => {
+            let chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(panes.list_width), Constraint::Min(0)])
+                .split(content);
+            render_list_pane(frame, app, chunks[0]);
+            render_reader(frame, app, chunks[1]);
+        }
+        // The list, or the article being read
+        PaneLayout::ListOnly if app.reading => render_reader(frame, app, content),
+        PaneLayout::ListOnly => render_list_pane(frame, app, content),
+    }
 
     // Render unified status bar
     render_status_bar(frame, app, main_vertical[1]);
@@ -80,16 +85,327 @@ pub fn draw(frame: &mut Frame, app: &App) {
         render_opml_export(frame, app);
     }
 
+    // Render feed rename popup if active
+    if app.rename_feed_active {
+        render_rename_feed(frame, app);
+    }
+
+    // Render tag prompt for the selected articles if active
+    if app.batch_tag_active {
+        render_batch_tag(frame, app);
+    }
+
+    // Render find-in-article prompt if active
+    if app.find_input_active {
+        render_find_input(frame, app);
+    }
+
+    // Render inline note editor if active
+    if app.note_input_active {
+        render_note_input(frame, app);
+    }
+
+    // Render snooze popup if active
+    if app.snooze_active {
+        render_snooze(frame, app);
+    }
+
+    // Render embargo input if active
+    if let Some(input) = &app.embargo_input {
+        render_embargo_input(frame, input);
+    }
+
+    // Render command palette if active
+    if app.palette_active {
+        render_palette(frame, app);
+    }
+
+    // Render tag filter popup if active
+    if app.tag_filter_active {
+        render_tag_filter(frame, app);
+    }
+
+    // Render search prompt if active
+    if app.search_active {
+        render_search(frame, app);
+    }
+
+    // Render entity browser if active
+    if app.entities_active {
+        render_entities(frame, app);
+    }
+
+    // Render author browser if active
+    if app.authors_active {
+        render_authors(frame, app);
+    }
+
+    // Render the blocklist if active
+    if app.blocklist_active {
+        render_blocklist(frame, app);
+    }
+
+    // Render watchlist timelines if active
+    if app.watch_active {
+        render_watchlist(frame, app);
+    }
+
+    // Render trending terms if active
+    if app.trends_active {
+        render_trends(frame, app);
+    }
+
+    // Render article revisions if active
+    if app.revisions_active {
+        render_revisions(frame, app);
+    }
+
+    // Render the calendar if active
+    if let Some(calendar) = app.calendar.as_ref().filter(|_| app.calendar_active) {
+        render_calendar(frame, calendar);
+    }
+
+    // Render question input if active
+    if app.ask_input_active {
+        render_ask_input(frame, app);
+    }
+
+    // Render answer popup if active
+    if app.answer_active {
+        render_answer(frame, app);
+    }
+
+    // Render failed summaries if active
+    if app.failures_active {
+        render_failures(frame, app);
+    }
+
+    // Render unsubscribe prompt if active
+    if app.remove_feed_id.is_some() {
+        render_remove_feed(frame, app);
+    }
+
+    // Render moved feed prompt if active
+    if let Some(feed) = app.feed_move_id.and_then(|id| app.feeds.iter().find(|f| f.id == id)) {
+        render_feed_move(frame, feed);
+    }
+
+    // Render feed recommendations popup if active
+    if app.recommendations_active {
+        render_recommendations(frame, app);
+    }
+
     // Render help popup if active
     if app.show_help {
         render_help(frame);
     }
 }
 
+/// Header + article list
+fn render_list_pane(frame: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Title bar
+            Constraint::Min(0),    // Article list
+        ])
+        .split(area);
+    render_header(frame, app, chunks[0]);
+    render_article_list(frame, app, chunks[1]);
+}
+
+/// Title + feed content (and comments) + AI summary
+fn render_reader(frame: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),      // Article title (single line)
+            Constraint::Percentage(30), // Feed content (30%)
+            Constraint::Percentage(70), // AI summary (70%)
+        ])
+        .split(area);
+
+    render_article_title(frame, app, chunks[0]);
+    let content = match &app.note {
+        Some(note) => {
+            let height = (note.lines().count() as u16 + 2).min(chunks[1].height / 2).max(3);
+            let note_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(height), Constraint::Min(1)])
+                .split(chunks[1]);
+            render_note(frame, note, note_chunks[0]);
+            note_chunks[1]
+        }
+        None => chunks[1],
+    };
+    match app.selected_comments() {
+        Some(comments) => {
+            let content_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .split(content);
+            render_feed_content(frame, app, content_chunks[0]);
+            render_comments(frame, comments, content_chunks[1]);
+        }
+        None => render_feed_content(frame, app, content),
+    }
+    render_summary(frame, app, chunks[2]);
+}
+
+/// The reader's own note on the article, above its text
+fn render_note(frame: &mut Frame, note: &str, area: Rect) {
+    let paragraph = Paragraph::new(note.to_string())
+        .block(
+            Block::default()
+                .title(" Note (N: edit) ")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Yellow)),
+        )
+        .style(Style::default().fg(Color::White))
+        .wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, area);
+}
+
+/// The views, then feeds by folder or tag with unread counts; the article
+/// list shows the selected row
+fn render_feed_list(frame: &mut Frame, app: &App, area: Rect) {
+    // Inside the borders, with the count right-aligned
+    let width = area.width.saturating_sub(2) as usize;
+    let row = |name: &str, unread: u32, style: Style| {
+        let count = if unread > 0 { unread.to_string() } else { String::new() };
+        let name: String = name.chars().take(width.saturating_sub(count.len() + 1)).collect();
+        let padding = width.saturating_sub(name.chars().count() + count.len());
+        ListItem::new(Line::from(vec![
+            Span::styled(name, style),
+            Span::raw(" ".repeat(padding)),
+            Span::styled(count, Style::default().fg(Color::Cyan)),
+        ]))
+    };
+
+    let total: u32 = app.feeds.iter().map(|f| f.unread).sum();
+    let items: Vec<ListItem> = app
+        .sidebar_rows()
+        .iter()
+        .map(|sidebar_row| match sidebar_row {
+            SidebarRow::View(view) => {
+                let unread = if *view == SidebarView::All { total } else { 0 };
+                row(view.label(), unread, Style::default().fg(Color::White))
+            }
+            SidebarRow::Group { name, unread, collapsed } => {
+                let name = format!("{} {}", if *collapsed { "▸" } else { "▾" }, name);
+                row(&name, *unread, Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+            }
+            SidebarRow::Feed { feed, group } => {
+                let style = if (feed.paused && !feed.is_local()) || feed.priority == FeedPriority::Low {
+                    Style::default().fg(Color::DarkGray)
+                } else if feed.priority == FeedPriority::High {
+                    Style::default().fg(Color::LightYellow).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Color::White)
+                };
+                let indent = if group.is_some() { "  " } else { "" };
+                row(&format!("{}{}", indent, feed.display_title()), feed.unread, style)
+            }
+        })
+        .collect();
+
+    let border = if app.feeds_focused { Color::Cyan } else { Color::DarkGray };
+    let title = match (app.panes.grouping, app.panes.unread_feeds_only) {
+        (SidebarGrouping::Folder, false) => " Feeds ",
+        (SidebarGrouping::Folder, true) => " Feeds · unread ",
+        (SidebarGrouping::Tag, false) => " Feeds · by tag ",
+        (SidebarGrouping::Tag, true) => " Feeds · by tag · unread ",
+    };
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(border)),
+        )
+        .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD));
+
+    let mut state = ListState::default();
+    state.select(Some(app.sidebar_row()));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
 fn render_header(frame: &mut Frame, app: &App, area: Rect) {
-    let total_articles = app.articles.len();
-    let left_text = format!(" {} Articles", total_articles);
+    let total_articles = app.total_articles;
+    let left_text = match app.filter_mode {
+        FilterMode::All
+            if app.feed_filter.is_some()
+                || app.group_filter.is_some()
+                || app.today_only
+                || app.day_filter.is_some()
+                || app.tag_filter.is_some()
+                || app.entity_filter.is_some()
+                || app.author_filter.is_some()
+                || app.term_filter.is_some()
+                || app.search.is_some() =>
+        {
+            let mut text = format!(
+                " {}{} Articles",
+                app.articles.len(),
+                if app.has_more_articles { "+" } else { "" }
+            );
+            if let Some(feed) = app.feed_filter.and_then(|id| app.feeds.iter().find(|f| f.id == id)) {
+                text.push_str(&format!(" · {}", feed.display_title()));
+            }
+            if let Some(group) = &app.group_filter {
+                text.push_str(&format!(" · {}", group));
+            }
+            if app.today_only {
+                text.push_str(" · Today");
+            }
+            if let Some(day) = app.day_filter {
+                text.push_str(&format!(" · {}", day.format("%a %-d %b %Y")));
+            }
+            if let Some(tag) = &app.tag_filter {
+                text.push_str(&format!(" · #{}", tag));
+            }
+            if let Some(entity) = &app.entity_filter {
+                text.push_str(&format!(" · {}", entity));
+            }
+            if let Some(author) = &app.author_filter {
+                text.push_str(&format!(" · by {}", author));
+            }
+            if let Some(term) = &app.term_filter {
+                text.push_str(&format!(" · \"{}\"", term));
+            }
+            if let Some((search, _)) = &app.search {
+                text.push_str(&format!(" · /{}", search));
+            }
+            text
+        }
+        FilterMode::All => format!(" {} Articles", total_articles),
+        FilterMode::Queue => format!(
+            " {} Queued · {}",
+            total_articles,
+            format_minutes(app.queue_minutes)
+        ),
+        FilterMode::Collection => format!(
+            " {} in {}",
+            total_articles,
+            app.collection.as_deref().unwrap_or_default()
+        ),
+        mode => format!(" {} {}", total_articles, mode.label()),
+    };
     let right_text = format!("{} Saved ", app.saved_count);
+    // Today's reading time, against the goal if there is one
+    let today = (app.reading_today_secs / 60) as u32;
+    let (reading_text, reading_color) = match app.daily_goal_minutes {
+        Some(goal) if today >= goal => (format!("✔ {} read · ", format_minutes(today)), Color::Green),
+        Some(goal) => (format!("{}/{} read · ", format_minutes(today), format_minutes(goal)), Color::DarkGray),
+        None if today > 0 => (format!("{} read · ", format_minutes(today)), Color::DarkGray),
+        None => (String::new(), Color::DarkGray),
+    };
+    // Watched entities with a coverage spike today (see `W`)
+    let spike_text = if app.coverage_spikes.is_empty() {
+        String::new()
+    } else {
+        format!("⚡ {} · ", app.coverage_spikes.join(", "))
+    };
 
     let block = Block::default()
         .title(" BeatCheck v1.2.0 ")
@@ -102,12 +418,14 @@ fn render_header(frame: &mut Frame, app: &App, area: Rect) {
     // Calculate padding to right-justify the saved count
     let available_width = inner.width as usize;
     let left_len = left_text.len();
-    let right_len = right_text.len();
+    let right_len = right_text.len() + spike_text.chars().count() + reading_text.chars().count();
     let padding = available_width.saturating_sub(left_len + right_len);
 
     let line = Line::from(vec![
         Span::styled(&left_text, Style::default().fg(Color::White)),
         Span::raw(" ".repeat(padding)),
+        Span::styled(spike_text, Style::default().fg(Color::Yellow)),
+        Span::styled(reading_text, Style::default().fg(reading_color)),
         Span::styled(right_text, Style::default().fg(Color::White)),
     ]);
 
@@ -117,6 +435,7 @@ fn render_header(frame: &mut Frame, app: &App, area: Rect) {
 
 fn render_article_list(frame: &mut Frame, app: &App, area: Rect) {
     let articles = app.filtered_articles();
+    let selected = if app.select_mode { app.batch_ids() } else { Vec::new() };
 
     let items: Vec<ListItem> = articles
         .iter()
@@ -137,17 +456,70 @@ fn render_article_list(frame: &mut Frame, app: &App, area: Rect) {
                     (day, date)
                 })
                 .unwrap_or(("? ", "??-??".to_string()));
-            let feed = article.feed_title.as_deref().unwrap_or("Unknown");
-
-            let line = Line::from(vec![
+            // One feed's articles are told apart by title; the full-width
+            // list has room for both
+            let headlines = app.reading_mode(article.feed_id) == ReadingMode::Headlines;
+            let feed = match app.feed_filter {
+                Some(_) => article.title.as_str(),
+                None => article.feed_title.as_deref().unwrap_or("Unknown"),
+            };
+            // Beat feeds stand out and link-blog noise fades
+            let (feed_style, title_color) = match app.feed_priority(article.feed_id) {
+                FeedPriority::High => (Style::default().fg(Color::LightYellow).add_modifier(Modifier::BOLD), Color::Gray),
+                FeedPriority::Normal => (Style::default().fg(Color::White), Color::Gray),
+                FeedPriority::Low => (Style::default().fg(Color::DarkGray), Color::DarkGray),
+            };
+            let marker = if selected.contains(&article.id) {
+                Span::styled("✓ ", Style::default().fg(Color::Green))
+            } else if article.is_pinned {
+                Span::styled("◆ ", Style::default().fg(Color::Yellow))
+            } else if article.is_starred {
+                Span::styled("★ ", Style::default().fg(Color::Yellow))
+            } else if article.is_queued {
+                Span::styled("» ", Style::default().fg(Color::Cyan))
+            } else {
+                Span::raw("")
+            };
+
+            let mut spans = vec![
+                marker,
                 Span::styled(day, Style::default().fg(Color::DarkGray)),
                 Span::styled(" ", Style::default()),
                 Span::styled(date, Style::default().fg(Color::DarkGray)),
                 Span::styled(" ", Style::default()),
-                Span::styled(feed.to_string(), Style::default().fg(Color::White)),
-            ]);
-
-            ListItem::new(line)
+                Span::styled(feed.to_string(), feed_style),
+            ];
+            // Headline feeds get compact rows: the title and nothing else
+            if headlines {
+                if app.feed_filter.is_none() {
+                    spans.push(Span::styled(format!("  {}", article.title), Style::default().fg(title_color)));
+                }
+            } else {
+                if app.panes.layout == PaneLayout::ListOnly && app.feed_filter.is_none() {
+                    spans.push(Span::styled(format!("  {}", article.title), Style::default().fg(title_color)));
+                }
+                if article.by_followed_author {
+                    let author = article.author.as_deref().unwrap_or_default();
+                    spans.push(Span::styled(format!(" ✎ {}", author), Style::default().fg(Color::Magenta)));
+                }
+                if article.enclosure.is_some() {
+                    let color = if article.is_played { Color::DarkGray } else { Color::LightBlue };
+                    spans.push(Span::styled(" ♪", Style::default().fg(color)));
+                }
+                if let Some(discussion) = &article.discussion {
+                    spans.push(Span::styled(
+                        format!(" ▲{} 💬{}", discussion.points, discussion.comments),
+                        Style::default().fg(Color::LightRed),
+                    ));
+                }
+            }
+            let line = Line::from(spans);
+
+            if selected.contains(&article.id) {
+                ListItem::new(line).style(Style::default().bg(Color::Rgb(30, 50, 30)))
+            } else {
+                ListItem::new(line)
+            }
         })
         .collect();
 
@@ -168,11 +540,42 @@ fn render_article_list(frame: &mut Frame, app: &App, area: Rect) {
 fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
     // Left side: main status
     let left_status = if app.is_refreshing {
-        (format!("{} Refreshing...", app.spinner_char()), Color::DarkGray)
+        let feeds = match app.refreshing_feeds {
+            1 => "1 feed".to_string(),
+            n => format!("{} feeds", n),
+        };
+        (format!("{} Refreshing {}...", app.spinner_char(), feeds), Color::DarkGray)
     } else if matches!(app.summary_status, SummaryStatus::Generating) {
         (format!("{} Summarizing...", app.spinner_char()), Color::DarkGray)
+    } else if let Some((_, playback)) = &app.playback {
+        (format!("▶ Playing {}  P:stop", format_clock(playback.position_secs)), Color::LightBlue)
+    } else if app.pending_comments_article_id.is_some() {
+        (format!("{} Fetching comments...", app.spinner_char()), Color::DarkGray)
+    } else if app.select_mode {
+        let anchor = if app.range_anchor.is_some() { "v:end range" } else { "v:range" };
+        (
+            format!(
+                "{} selected  Space:toggle {} m:read s:star t:tag b:Raindrop d:delete Esc:done",
+                app.batch_ids().len(),
+                anchor
+            ),
+            Color::Green,
+        )
     } else if app.bookmark_prefix_active {
         ("Space: t=twit  i=im  m=mbw".to_string(), Color::Yellow)
+    } else if let Some(count) = app.count {
+        (format!("{}  o:open link {}  v:jump to it", count, count), Color::Yellow)
+    } else if let Some(find) = &app.find {
+        let total = app.find_matches().len();
+        let at = if total == 0 { 0 } else { find.current.min(total - 1) + 1 };
+        (format!("Find \"{}\": {}/{}  n/N:next/previous  Esc:clear", find.term, at, total), Color::Yellow)
+    } else if let Some(number) = app.link_number {
+        let url = app.link_url(number).unwrap_or_default();
+        (format!("Link [{}] {}  o:open  v/V:next/previous  Esc:clear", number, url), Color::Cyan)
+    } else if app.feeds_focused {
+        ("j/k:pick feed  u:only unread  Tab:back to articles".to_string(), Color::DarkGray)
+    } else if app.reading {
+        ("j/k:next/previous article  Esc:back to list  o:open".to_string(), Color::DarkGray)
     } else {
         ("j/k:move  Enter:summarize  o:open  d:delete  a:add  ?:help  q:quit".to_string(), Color::DarkGray)
     };
@@ -203,8 +606,84 @@ fn render_article_title(frame: &mut Frame, app: &App, area: Rect) {
         .map(|a| a.title.as_str())
         .unwrap_or("No article selected");
 
+    let mut block_title = match app.selected_article().and_then(|a| a.reading_minutes) {
+        Some(minutes) => format!(" Article · {} read ", format_minutes(minutes)),
+        None => " Article ".to_string(),
+    };
+    if let Some(article) = app.selected_article().filter(|a| a.enclosure.is_some()) {
+        let duration = article.enclosure.as_ref().and_then(|e| e.duration_secs);
+        let episode = match &app.playback {
+            Some((id, playback)) if *id == article.id => {
+                format!("▶ {}", format_position(playback.position_secs, playback.duration_secs.or(duration)))
+            }
+            _ if article.is_played && article.playback_secs.unwrap_or(0) == 0 => "♪ played".to_string(),
+            _ => match article.playback_secs.filter(|&secs| secs > 0) {
+                Some(secs) => format!("♪ {} (P resumes)", format_position(secs, duration)),
+                None => "♪ P to play".to_string(),
+            },
+        };
+        let number = article.extras.as_ref().and_then(|x| match (x.itunes_season(), x.itunes_episode()) {
+            (Some(season), Some(episode)) => Some(format!("S{}E{} ", season, episode)),
+            (None, Some(episode)) => Some(format!("#{} ", episode)),
+            _ => None,
+        });
+        block_title.push_str(&format!("· {}{} ", number.unwrap_or_default(), episode));
+    }
+    if let Some(discussion) = app.selected_article().and_then(|a| a.discussion.as_ref()) {
+        let site = if discussion.site == "hn" { "HN" } else { "Lobsters" };
+        block_title.push_str(&format!(
+            "· {} {} points, {} comments (O) ",
+            site, discussion.points, discussion.comments
+        ));
+    }
+    match app.selected_article().map(|a| (a.removed_at, a.archive_path.is_some())) {
+        Some((Some(removed), archived)) => block_title.push_str(&format!(
+            "· taken down {}{} ",
+            removed.with_timezone(&chrono::Local).format("%-d %b"),
+            if archived { ", o opens the archived copy" } else { "" }
+        )),
+        Some((None, true)) => block_title.push_str("· archived "),
+        _ => {}
+    }
+    if let Some(article) = app.selected_article().filter(|a| a.revisions > 0) {
+        block_title.push_str(&format!(
+            "· edited {} (~) ",
+            match article.revisions {
+                1 => "once".to_string(),
+                n => format!("{} times", n),
+            }
+        ));
+    }
+    // Only reachable through an `is:embargoed` search until it lifts
+    if let Some(until) = app
+        .selected_article()
+        .and_then(|a| a.embargo_until)
+        .filter(|until| *until > chrono::Utc::now())
+    {
+        let now = chrono::Local::now().naive_local();
+        let until = format_snooze(until.with_timezone(&chrono::Local).naive_local(), now);
+        block_title.push_str(&format!("· embargoed until {} ", until));
+    }
+    // What's on file about the source (`feeds bulk ... --beat/--reliability`)
+    let source = app
+        .selected_article()
+        .and_then(|a| app.feeds.iter().find(|f| f.id == a.feed_id))
+        .map(|f| &f.source);
+    if let Some(beat) = source.and_then(|s| s.beat.as_ref()) {
+        block_title.push_str(&format!("· {} beat ", beat));
+    }
+    if let Some(rating) = source.and_then(|s| s.reliability) {
+        let stars = "★".repeat(rating as usize) + &"☆".repeat(SourceInfo::MAX_RELIABILITY.saturating_sub(rating) as usize);
+        block_title.push_str(&format!("· {} ", stars));
+    }
+    // Time actually spent on it, once it's a minute or more
+    let spent = app.time_on_article() / 60;
+    if spent > 0 {
+        block_title.push_str(&format!("· you've read {} ", format_minutes(spent as u32)));
+    }
+
     let block = Block::default()
-        .title(" Article ")
+        .title(block_title)
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Green));
 
@@ -216,18 +695,95 @@ fn render_article_title(frame: &mut Frame, app: &App, area: Rect) {
 }
 
 fn render_feed_content(frame: &mut Frame, app: &App, area: Rect) {
-    let content = app
-        .selected_article()
-        .and_then(|a| a.content_text.as_ref().or(a.content.as_ref()))
-        .map(|c| c.split_whitespace().collect::<Vec<_>>().join(" "))
-        .unwrap_or_else(|| "No content available".to_string());
+    let article = app.selected_article();
+    let full_text = article.and_then(|a| a.full_text.as_ref());
+    let content = app.reader_text();
+    let mut title = if full_text.is_some() { " Full Article " } else { " Feed Content " }.to_string();
+    if let Some(article) = article.filter(|a| !a.tags.is_empty()) {
+        let tags: Vec<String> = article.tags.iter().map(|t| format!("#{}", t)).collect();
+        title.push_str(&format!("· {} ", tags.join(" ")));
+    }
 
     let block = Block::default()
-        .title(" Feed Content ")
+        .title(title)
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Blue));
 
-    let paragraph = Paragraph::new(content)
+    // Find matches, or the highlighted link, and scroll the current one into view
+    let mark = app.reader_mark();
+    let mut marks: Vec<(std::ops::Range<usize>, Style)> = match &app.find {
+        Some(_) => app
+            .find_matches()
+            .into_iter()
+            .map(|range| {
+                let current = mark.as_ref() == Some(&range);
+                let color = if current { Color::LightRed } else { Color::Yellow };
+                (range, Style::default().fg(Color::Black).bg(color))
+            })
+            .collect(),
+        None => mark.iter().map(|range| (range.clone(), Style::default().fg(Color::Black).bg(Color::Cyan))).collect(),
+    };
+    marks.sort_by_key(|(range, _)| range.start);
+    let inner = block.inner(area);
+    let scroll = mark.map_or(0, |range| wrapped_row(&content, range.start, inner.width).saturating_sub(inner.height / 3));
+
+    let mut spans = Vec::new();
+    let mut at = 0;
+    for (range, style) in marks {
+        spans.push(Span::raw(content[at..range.start].to_string()));
+        spans.push(Span::styled(content[range.clone()].to_string(), style));
+        at = range.end;
+    }
+    spans.push(Span::raw(content[at..].to_string()));
+
+    let paragraph = Paragraph::new(Line::from(spans))
+        .block(block)
+        .wrap(Wrap { trim: true })
+        .scroll((scroll, 0));
+
+    frame.render_widget(paragraph, area);
+}
+
+fn render_find_input(frame: &mut Frame, app: &App) {
+    let area = centered_rect(60, 20, frame.area());
+
+    let block = Block::default()
+        .title(" Find in Article - n/N next/previous, Esc clears ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+
+    // Clear the area first
+    frame.render_widget(ratatui::widgets::Clear, area);
+    frame.render_widget(block, area);
+
+    let input_text = format!("> {}_", app.find_input);
+    let paragraph = Paragraph::new(input_text).style(Style::default().fg(Color::White));
+    frame.render_widget(paragraph, inner);
+}
+
+fn render_comments(frame: &mut Frame, comments: &[Comment], area: Rect) {
+    let mut lines = Vec::new();
+    for comment in comments {
+        let mut byline = comment.author.clone().unwrap_or_else(|| "Anonymous".to_string());
+        if let Some(published) = comment.published_at {
+            byline.push_str(&format!(" · {}", published.format("%b %d %H:%M")));
+        }
+        lines.push(Line::from(Span::styled(byline, Style::default().fg(Color::Cyan))));
+        lines.push(Line::from(comment.text.split_whitespace().collect::<Vec<_>>().join(" ")));
+        lines.push(Line::from(""));
+    }
+    if comments.is_empty() {
+        lines.push(Line::from("No comments yet"));
+    }
+
+    let block = Block::default()
+        .title(format!(" Comments ({}) ", comments.len()))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Blue));
+
+    let paragraph = Paragraph::new(lines)
         .block(block)
         .wrap(Wrap { trim: true });
 
@@ -239,7 +795,7 @@ fn render_summary(frame: &mut Frame, app: &App, area: Rect) {
         SummaryStatus::NotGenerated => "Press Enter to generate summary...".to_string(),
         SummaryStatus::Generating => format!("{} Generating summary...", app.spinner_char()),
         SummaryStatus::Failed => "Failed to generate summary. Press 'g' to retry.".to_string(),
-        SummaryStatus::NoApiKey => "Claude API key not configured.\n\nPlease add your API key to:\n~/.config/beatcheck/config.toml\n\nExample:\nclaude_api_key = \"sk-ant-...\"".to_string(),
+        SummaryStatus::NoApiKey => "Claude API key not configured.\n\nPlease add your API key to:\n~/.config/beatcheck/config.toml\n\nExample:\nclaude_api_key = \"sk-ant-...\"\n\nOr use a local model: [local_llm] with model = \"...\"".to_string(),
         SummaryStatus::Generated => app
             .current_summary
             .as_ref()
@@ -247,8 +803,15 @@ fn render_summary(frame: &mut Frame, app: &App, area: Rect) {
             .unwrap_or_else(|| "No summary available".to_string()),
     };
 
+    // Which provider wrote it, for summaries that recorded one
+    let title = match (&app.summary_status, &app.current_summary) {
+        (SummaryStatus::Generated, Some(Summary { provider: Some(provider), .. })) => {
+            format!(" AI Summary ({}) ", provider)
+        }
+        _ => " AI Summary ".to_string(),
+    };
     let block = Block::default()
-        .title(" AI Summary ")
+        .title(title)
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Magenta));
 
@@ -364,7 +927,10 @@ fn render_opml_export(frame: &mut Frame, app: &App) {
     let area = centered_rect(70, 25, frame.area());
 
     let block = Block::default()
-        .title(" Export OPML - Enter file path ")
+        .title(match &app.group_filter {
+            Some(group) => format!(" Export OPML - {} feeds in {} - Enter file path ", app.opml_export_feeds().len(), group),
+            None => " Export OPML - Enter file path ".to_string(),
+        })
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Green));
 
@@ -400,50 +966,1120 @@ fn render_opml_export(frame: &mut Frame, app: &App) {
     }
 }
 
-fn render_help(frame: &mut Frame) {
-    let area = centered_rect(50, 80, frame.area());
+fn render_rename_feed(frame: &mut Frame, app: &App) {
+    let area = centered_rect(60, 20, frame.area());
 
-    let help_text = vec![
-        "",
-        " Navigation:",
-        "   j / ↓    Move down",
-        "   k / ↑    Move up",
-        "   <        Go to top",
-        "   >        Go to bottom",
-        "   Enter    Select / Generate summary",
-        "",
-        " Actions:",
-        "   r        Refresh all feeds",
-        "   a        Add new feed",
-        "   i        Import OPML file",
-        "   w        Export OPML file",
-        "   o        Open in browser",
-        "   e        Email article",
-        "   b        Bookmark to Raindrop.io (enter tags)",
-        "   Space+t/i/m  Quick bookmark (twit/im/mbw)",
-        "   g        Regenerate summary",
-        "   d / ⌫    Delete article",
-        "   D        Delete feed",
-        "   u        Undelete last",
-        "",
-        " General:",
-        "   ?        Toggle this help",
-        "   q        Quit",
-        "",
-        " Press any key to close",
-    ];
+    let block = Block::default()
+        .title(" Rename Feed - empty restores the feed's own title ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+
+    // Clear the area first
+    frame.render_widget(ratatui::widgets::Clear, area);
+    frame.render_widget(block, area);
+
+    let input_text = format!("> {}_", app.rename_feed_input);
+    let paragraph = Paragraph::new(input_text).style(Style::default().fg(Color::White));
+    frame.render_widget(paragraph, inner);
+}
+
+fn render_batch_tag(frame: &mut Frame, app: &App) {
+    let area = centered_rect(60, 20, frame.area());
 
     let block = Block::default()
-        .title(" Help ")
+        .title(format!(" Tag {} Selected Articles ", app.batch_ids().len()))
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Cyan));
 
-    let paragraph = Paragraph::new(help_text.join("\n"))
-        .block(block)
-        .style(Style::default().fg(Color::White));
+    let inner = block.inner(area);
 
+    // Clear the area first
     frame.render_widget(ratatui::widgets::Clear, area);
-    frame.render_widget(paragraph, area);
+    frame.render_widget(block, area);
+
+    let input_text = format!("> {}_", app.batch_tag_input);
+    let paragraph = Paragraph::new(input_text).style(Style::default().fg(Color::White));
+    frame.render_widget(paragraph, inner);
+}
+
+fn render_snooze(frame: &mut Frame, app: &App) {
+    let area = centered_rect(50, 30, frame.area());
+
+    let block = Block::default()
+        .title(" Snooze Until ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+
+    // Clear the area first
+    frame.render_widget(ratatui::widgets::Clear, area);
+    frame.render_widget(block, area);
+
+    let now = chrono::Local::now().naive_local();
+    let key = Style::default().fg(Color::Yellow);
+    let mut lines: Vec<Line> = [
+        ('t', "Tonight", SnoozeChoice::Tonight),
+        ('m', "Tomorrow", SnoozeChoice::Tomorrow),
+        ('w', "Next week", SnoozeChoice::NextWeek),
+    ]
+    .into_iter()
+    .map(|(k, label, choice)| {
+        Line::from(vec![
+            Span::styled(format!(" {}  ", k), key),
+            Span::raw(format!("{:<11}", label)),
+            Span::styled(format_snooze(choice.until(now), now), Style::default().fg(Color::DarkGray)),
+        ])
+    })
+    .collect();
+    lines.push(Line::from(vec![
+        Span::styled(" c  ", key),
+        Span::raw("Custom: 3h, 2d, 18:30, 2026-03-02 09:00"),
+    ]));
+    if let Some(input) = &app.snooze_input {
+        let until = parse_snooze(input, now).map(|until| format_snooze(until, now)).unwrap_or_default();
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::styled(format!("> {}_  ", input), Style::default().fg(Color::White)),
+            Span::styled(until, Style::default().fg(Color::DarkGray)),
+        ]));
+    }
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn render_embargo_input(frame: &mut Frame, input: &str) {
+    let area = centered_rect(50, 20, frame.area());
+
+    let block = Block::default()
+        .title(" Embargo Until ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Red));
+
+    let inner = block.inner(area);
+
+    // Clear the area first
+    frame.render_widget(ratatui::widgets::Clear, area);
+    frame.render_widget(block, area);
+
+    let now = chrono::Local::now().naive_local();
+    let until = parse_snooze(input, now).map(|until| format_snooze(until, now)).unwrap_or_default();
+    let lines = vec![
+        Line::from(Span::styled(
+            " 18:30, 2026-03-02 09:00, 3h; empty lifts it",
+            Style::default().fg(Color::DarkGray),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(format!("> {}_  ", input), Style::default().fg(Color::White)),
+            Span::styled(until, Style::default().fg(Color::DarkGray)),
+        ]),
+    ];
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn render_note_input(frame: &mut Frame, app: &App) {
+    let area = centered_rect(70, 50, frame.area());
+
+    let block = Block::default()
+        .title(" Note - Enter: new line  Esc: save  Ctrl+c: discard (set $EDITOR to use your editor) ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow));
+
+    let inner = block.inner(area);
+
+    // Clear the area first
+    frame.render_widget(ratatui::widgets::Clear, area);
+    frame.render_widget(block, area);
+
+    // Keep the end of a long note, where the typing is, in view
+    let text = format!("{}_", app.note_input);
+    let lines = text.lines().count() as u16;
+    let paragraph = Paragraph::new(text)
+        .style(Style::default().fg(Color::White))
+        .wrap(Wrap { trim: false })
+        .scroll((lines.saturating_sub(inner.height), 0));
+    frame.render_widget(paragraph, inner);
+}
+
+fn render_palette(frame: &mut Frame, app: &App) {
+    let area = centered_rect(60, 60, frame.area());
+
+    let block = Block::default()
+        .title(" Commands - ↑/↓: choose  Enter: run  Esc: close ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+
+    // Clear the area first
+    frame.render_widget(ratatui::widgets::Clear, area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(2), Constraint::Min(1)])
+        .split(inner);
+
+    let input = Paragraph::new(format!("> {}_", app.palette_input)).style(Style::default().fg(Color::White));
+    frame.render_widget(input, chunks[0]);
+
+    if PaletteCommand::parse(&app.palette_input).is_some() {
+        let hint = Paragraph::new(format!("Enter: {}", app.palette_input.trim())).style(Style::default().fg(Color::Yellow));
+        frame.render_widget(hint, chunks[1]);
+        return;
+    }
+
+    let matches = palette_matches(&app.palette_input);
+    if matches.is_empty() {
+        let hint = "No matching command. Also: tag add|remove TAG, goto feed NAME, goto starred|queue|discussed|all, search QUERY, block KEYWORD|site|author";
+        let paragraph = Paragraph::new(hint)
+            .style(Style::default().fg(Color::DarkGray))
+            .wrap(Wrap { trim: true });
+        frame.render_widget(paragraph, chunks[1]);
+        return;
+    }
+
+    let width = usize::from(chunks[1].width).saturating_sub(16);
+    let items: Vec<ListItem> = matches
+        .iter()
+        .map(|entry| {
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{:<width$}", entry.name), Style::default().fg(Color::White)),
+                Span::styled(format!(" {:>14}", entry.key), Style::default().fg(Color::DarkGray)),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items).highlight_style(
+        Style::default()
+            .bg(Color::DarkGray)
+            .add_modifier(Modifier::BOLD),
+    );
+
+    let mut state = ListState::default();
+    state.select(Some(app.palette_index));
+    frame.render_stateful_widget(list, chunks[1], &mut state);
+}
+
+fn render_tag_filter(frame: &mut Frame, app: &App) {
+    let area = centered_rect(60, 30, frame.area());
+
+    let block = Block::default()
+        .title(" Filter by Tag - empty shows all articles ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+
+    // Clear the area first
+    frame.render_widget(ratatui::widgets::Clear, area);
+    frame.render_widget(block, area);
+
+    let mut lines = vec![Line::from(format!("> {}_", app.tag_filter_input)), Line::from("")];
+    let popular: Vec<String> = app
+        .tag_suggestions
+        .iter()
+        .map(|(tag, count)| format!("#{} ({})", tag, count))
+        .collect();
+    if !popular.is_empty() {
+        lines.push(Line::from(Span::styled(popular.join("  "), Style::default().fg(Color::DarkGray))));
+    }
+    let paragraph = Paragraph::new(lines)
+        .style(Style::default().fg(Color::White))
+        .wrap(Wrap { trim: true });
+    frame.render_widget(paragraph, inner);
+}
+
+fn render_search(frame: &mut Frame, app: &App) {
+    let area = centered_rect(70, 30, frame.area());
+
+    let block = Block::default()
+        .title(" Search - empty shows all articles ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+
+    // Clear the area first
+    frame.render_widget(ratatui::widgets::Clear, area);
+    frame.render_widget(block, area);
+
+    let mut lines = vec![Line::from(format!("> {}_", app.search_input)), Line::from("")];
+    match &app.search_error {
+        Some(error) => lines.push(Line::from(Span::styled(error.clone(), Style::default().fg(Color::Red)))),
+        None => {
+            lines.push(Line::from(Span::styled(
+                "e.g. feed:verge tag:ai -title:rumor after:2025-01-01 \"open source\"",
+                Style::default().fg(Color::DarkGray),
+            )));
+            lines.push(Line::from(Span::styled(
+                format!("Fields: {}; OR, -term and (groups) combine them", QUERY_FIELDS),
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+    }
+    let paragraph = Paragraph::new(lines)
+        .style(Style::default().fg(Color::White))
+        .wrap(Wrap { trim: true });
+    frame.render_widget(paragraph, inner);
+}
+
+fn render_remove_feed(frame: &mut Frame, app: &App) {
+    let area = centered_rect(60, 25, frame.area());
+    let title = app
+        .remove_feed_id
+        .and_then(|id| app.feeds.iter().find(|f| f.id == id))
+        .map(|f| f.display_title())
+        .unwrap_or("feed");
+
+    let block = Block::default()
+        .title(format!(" Unsubscribe from {} ", title))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Red));
+
+    let text = [
+        "",
+        "  k  Keep starred articles (moved to \"Orphaned\"), delete the rest",
+        "  d  Delete the feed and all its articles",
+        "  a  Archive: hide the feed, keep everything",
+        "",
+        "  Any other key cancels",
+    ];
+    let paragraph = Paragraph::new(text.join("\n"))
+        .block(block)
+        .style(Style::default().fg(Color::White));
+
+    frame.render_widget(ratatui::widgets::Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+fn render_feed_move(frame: &mut Frame, feed: &Feed) {
+    let area = centered_rect(70, 30, frame.area());
+
+    let block = Block::default()
+        .title(format!(" {} moved? ", feed.display_title()))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow));
+
+    let text = [
+        String::new(),
+        format!("  {} answered \"not found\" {} times in a row.", feed.url, feed.missing_count),
+        "  Its site now links to:".to_string(),
+        format!("  {}", feed.moved_to.as_deref().unwrap_or_default()),
+        String::new(),
+        "  y  Fetch from the new URL, keeping the feed's articles".to_string(),
+        "  n  Keep the old URL".to_string(),
+        "  Any other key asks again when beatcheck next starts".to_string(),
+    ];
+    let paragraph = Paragraph::new(text.join("\n"))
+        .block(block)
+        .style(Style::default().fg(Color::White))
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(ratatui::widgets::Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+fn render_entities(frame: &mut Frame, app: &App) {
+    let area = centered_rect(60, 60, frame.area());
+
+    let block = Block::default()
+        .title(" Mentioned This Week - Enter: show articles  x: clear  Esc: close ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+
+    // Clear the area first
+    frame.render_widget(ratatui::widgets::Clear, area);
+    frame.render_widget(block, area);
+
+    if app.entities.is_empty() {
+        let paragraph = Paragraph::new("No people, companies or tickers in recent articles")
+            .style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(paragraph, inner);
+        return;
+    }
+
+    let items: Vec<ListItem> = app
+        .entities
+        .iter()
+        .map(|entity| {
+            let (marker, color) = match entity.kind {
+                EntityKind::Person => ("person", Color::Green),
+                EntityKind::Organization => ("org", Color::Cyan),
+                EntityKind::Ticker => ("ticker", Color::Yellow),
+            };
+            let name = if entity.kind == EntityKind::Ticker {
+                format!("${}", entity.name)
+            } else {
+                entity.name.clone()
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{:>4} ", entity.articles), Style::default().fg(Color::DarkGray)),
+                Span::styled(name, Style::default().fg(Color::White)),
+                Span::styled(format!("  {}", marker), Style::default().fg(color)),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items).highlight_style(
+        Style::default()
+            .bg(Color::DarkGray)
+            .add_modifier(Modifier::BOLD),
+    );
+
+    let mut state = ListState::default();
+    state.select(Some(app.entity_index));
+    frame.render_stateful_widget(list, inner, &mut state);
+}
+
+fn render_authors(frame: &mut Frame, app: &App) {
+    let area = centered_rect(60, 60, frame.area());
+
+    let block = Block::default()
+        .title(" Authors This Month - Enter: show articles  f: follow  m: mute  x: clear  Esc: close ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+
+    // Clear the area first
+    frame.render_widget(ratatui::widgets::Clear, area);
+    frame.render_widget(block, area);
+
+    if app.authors.is_empty() {
+        let paragraph = Paragraph::new("No bylines in recent articles")
+            .style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(paragraph, inner);
+        return;
+    }
+
+    let items: Vec<ListItem> = app
+        .authors
+        .iter()
+        .map(|author| {
+            let feeds = match author.feeds {
+                1 => "1 feed".to_string(),
+                n => format!("{} feeds", n),
+            };
+            let mut spans = vec![
+                Span::styled(format!("{:>4} ", author.articles), Style::default().fg(Color::DarkGray)),
+                Span::styled(author.name.clone(), Style::default().fg(Color::White)),
+                Span::styled(format!("  {}", feeds), Style::default().fg(Color::Cyan)),
+            ];
+            match author.rule {
+                Some(AuthorRule::Follow) => spans.push(Span::styled("  following", Style::default().fg(Color::Magenta))),
+                Some(AuthorRule::Mute) => spans.push(Span::styled("  muted", Style::default().fg(Color::DarkGray))),
+                None => {}
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let list = List::new(items).highlight_style(
+        Style::default()
+            .bg(Color::DarkGray)
+            .add_modifier(Modifier::BOLD),
+    );
+
+    let mut state = ListState::default();
+    state.select(Some(app.author_index));
+    frame.render_stateful_widget(list, inner, &mut state);
+}
+
+fn render_blocklist(frame: &mut Frame, app: &App) {
+    let area = centered_rect(60, 60, frame.area());
+
+    let title = if app.blocklist_input.is_some() {
+        " Blocklist - keyword, site:example.com or include URL  Enter: save  Esc: cancel "
+    } else {
+        " Blocklist - a: add  e: edit  d: remove  t: shared list on/off  Esc: close "
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+
+    // Clear the area first
+    frame.render_widget(ratatui::widgets::Clear, area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(2), Constraint::Min(0)])
+        .split(inner);
+
+    if let Some(input) = &app.blocklist_input {
+        let paragraph = Paragraph::new(format!("> {}_", input)).style(Style::default().fg(Color::White));
+        frame.render_widget(paragraph, chunks[0]);
+    } else {
+        let paragraph = Paragraph::new("Articles matching these are dropped as feeds are fetched")
+            .style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(paragraph, chunks[0]);
+    }
+
+    if app.blocklist_entries.is_empty() {
+        let paragraph = Paragraph::new("Nothing blocked yet: press a to add a keyword or site")
+            .style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(paragraph, chunks[1]);
+        return;
+    }
+
+    let items: Vec<ListItem> = app
+        .blocklist_entries
+        .iter()
+        .map(|entry| {
+            let subscription = app.blocklist_subscriptions.iter().find(|s| entry.ends_with(&s.url));
+            let spans = match (subscription, entry.strip_prefix(SITE_PREFIX)) {
+                (Some(subscription), _) => {
+                    let state = match (subscription.enabled, subscription.fetched) {
+                        (false, _) => "  off".to_string(),
+                        (true, None) => "  downloading".to_string(),
+                        (true, Some(_)) => format!("  {} rules", subscription.rules),
+                    };
+                    let color = if subscription.enabled { Color::White } else { Color::DarkGray };
+                    vec![
+                        Span::styled("list  ", Style::default().fg(Color::Magenta)),
+                        Span::styled(subscription.url.clone(), Style::default().fg(color)),
+                        Span::styled(state, Style::default().fg(Color::DarkGray)),
+                    ]
+                }
+                (None, Some(site)) => vec![
+                    Span::styled("site  ", Style::default().fg(Color::Cyan)),
+                    Span::styled(site.to_string(), Style::default().fg(Color::White)),
+                ],
+                (None, None) => vec![
+                    Span::styled(
+                        match KeywordMatch::split(entry).0 {
+                            KeywordMatch::Word => "word  ",
+                            KeywordMatch::Stem => "stem  ",
+                            _ => "part  ",
+                        },
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::styled(entry.clone(), Style::default().fg(Color::White)),
+                ],
+            };
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let list = List::new(items).highlight_style(
+        Style::default()
+            .bg(Color::DarkGray)
+            .add_modifier(Modifier::BOLD),
+    );
+
+    let mut state = ListState::default();
+    state.select(Some(app.blocklist_index));
+    frame.render_stateful_widget(list, chunks[1], &mut state);
+}
+
+fn render_watchlist(frame: &mut Frame, app: &App) {
+    let area = centered_rect(70, 60, frame.area());
+
+    let block = Block::default()
+        .title(format!(" Watchlist - last {} days  Enter: show articles  Esc: close ", TIMELINE_DAYS))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+
+    // Clear the area first
+    frame.render_widget(ratatui::widgets::Clear, area);
+    frame.render_widget(block, area);
+
+    if app.watch_timelines.is_empty() {
+        let paragraph = Paragraph::new("Nothing watched yet: beatcheck watch add LIST NAME...")
+            .style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(paragraph, inner);
+        return;
+    }
+
+    let width = app.watch_timelines.iter().map(|t| t.entity.chars().count()).max().unwrap_or(0);
+    let items: Vec<ListItem> = app
+        .watch_timelines
+        .iter()
+        .map(|timeline| {
+            let spike = timeline.is_spike();
+            let mut spans = vec![
+                Span::styled(format!("{:<width$}  ", timeline.entity), Style::default().fg(Color::White)),
+                Span::styled(
+                    timeline.sparkline(),
+                    Style::default().fg(if spike { Color::Yellow } else { Color::Cyan }),
+                ),
+                Span::styled(format!("  {} today", timeline.today()), Style::default().fg(Color::DarkGray)),
+            ];
+            if spike {
+                spans.push(Span::styled(
+                    format!("  ⚡ usually {:.1}/day", timeline.baseline()),
+                    Style::default().fg(Color::Yellow),
+                ));
+            }
+            spans.push(Span::styled(
+                format!("  [{}]", timeline.lists.join(", ")),
+                Style::default().fg(Color::DarkGray),
+            ));
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let list = List::new(items).highlight_style(
+        Style::default()
+            .bg(Color::DarkGray)
+            .add_modifier(Modifier::BOLD),
+    );
+
+    let mut state = ListState::default();
+    state.select(Some(app.watch_index));
+    frame.render_stateful_widget(list, inner, &mut state);
+}
+
+fn render_failures(frame: &mut Frame, app: &App) {
+    let area = centered_rect(70, 70, frame.area());
+
+    let block = Block::default()
+        .title(" Failed Summaries - Enter: retry  a: retry all  Esc: close ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Red));
+
+    let inner = block.inner(area);
+
+    // Clear the area first
+    frame.render_widget(ratatui::widgets::Clear, area);
+    frame.render_widget(block, area);
+
+    if app.failures.is_empty() {
+        let paragraph = Paragraph::new("No failed summaries")
+            .style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(paragraph, inner);
+        return;
+    }
+
+    let items: Vec<ListItem> = app
+        .failures
+        .iter()
+        .map(|failure| {
+            let retry = match failure.next_retry_at {
+                Some(at) => format!("retrying {}", at.with_timezone(&chrono::Local).format("%b %d %H:%M")),
+                None => "not retrying".to_string(),
+            };
+            let error = failure.error.split_whitespace().collect::<Vec<_>>().join(" ");
+            ListItem::new(vec![
+                Line::from(vec![
+                    Span::styled(failure.title.clone(), Style::default().fg(Color::White)),
+                    Span::styled(
+                        format!("  {} attempt(s), {}", failure.attempts, retry),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ]),
+                Line::from(Span::styled(format!("  {}", error), Style::default().fg(Color::Red))),
+            ])
+        })
+        .collect();
+
+    let list = List::new(items).highlight_style(
+        Style::default()
+            .bg(Color::DarkGray)
+            .add_modifier(Modifier::BOLD),
+    );
+
+    let mut state = ListState::default();
+    state.select(Some(app.failure_index));
+    frame.render_stateful_widget(list, inner, &mut state);
+}
+
+fn render_revisions(frame: &mut Frame, app: &App) {
+    let area = centered_rect(80, 80, frame.area());
+    let Some(revision) = app.revisions.get(app.revision_index) else {
+        return;
+    };
+    // Each version against the one that replaced it
+    let (new_title, new_text) = match app.revision_index {
+        0 => app
+            .selected_article()
+            .map(|a| (a.title.clone(), a.content_text.clone()))
+            .unwrap_or_default(),
+        i => (app.revisions[i - 1].title.clone(), app.revisions[i - 1].content_text.clone()),
+    };
+
+    let block = Block::default()
+        .title(format!(
+            " Revision {} of {}, replaced {}  [ ]: older/newer  j/k: scroll  Esc: close ",
+            app.revisions.len() - app.revision_index,
+            app.revisions.len(),
+            revision.replaced_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Magenta));
+
+    let inner = block.inner(area);
+
+    // Clear the area first
+    frame.render_widget(ratatui::widgets::Clear, area);
+    frame.render_widget(block, area);
+
+    let removed = Style::default().fg(Color::Red).add_modifier(Modifier::CROSSED_OUT);
+    let added = Style::default().fg(Color::Green).add_modifier(Modifier::UNDERLINED);
+    let mut lines: Vec<Line> = Vec::new();
+    if revision.title != new_title {
+        lines.push(Line::from(vec![
+            Span::styled("Title: ", Style::default().fg(Color::DarkGray)),
+            Span::styled(revision.title.clone(), removed),
+            Span::raw(" → "),
+            Span::styled(new_title, added),
+        ]));
+        lines.push(Line::from(""));
+    }
+    let old_text = revision.content_text.as_deref().unwrap_or_default();
+    let mut spans: Vec<Span> = Vec::new();
+    for change in word_diff(old_text, new_text.as_deref().unwrap_or_default()) {
+        let (text, style) = match change {
+            Change::Same(text) => (text, Style::default().fg(Color::White)),
+            Change::Removed(text) => (text, removed),
+            Change::Added(text) => (text, added),
+        };
+        // Paragraph breaks start a new line after a blank one
+        for (n, part) in text.split('\n').enumerate() {
+            if n > 0 {
+                lines.push(Line::from(std::mem::take(&mut spans)));
+                lines.push(Line::from(""));
+            }
+            if !part.is_empty() {
+                if !spans.is_empty() {
+                    spans.push(Span::raw(" "));
+                }
+                spans.push(Span::styled(part.to_string(), style));
+            }
+        }
+    }
+    lines.push(Line::from(spans));
+
+    let paragraph = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .scroll((app.revision_scroll, 0));
+    frame.render_widget(paragraph, inner);
+}
+
+fn render_calendar(frame: &mut Frame, calendar: &Calendar) {
+    let area = centered_rect(80, 50, frame.area());
+
+    let title = calendar.feed.as_ref().map_or("All feeds", |(_, title)| title.as_str());
+    let block = Block::default()
+        .title(format!(
+            " Calendar - {}  ←→ week  ↑↓ day  Enter: show day  f: this feed/all  x: clear  Esc: close ",
+            title
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let inner = block.inner(area);
+
+    // Clear the area first
+    frame.render_widget(ratatui::widgets::Clear, area);
+    frame.render_widget(block, area);
+
+    // Month names over the week their first day falls in
+    let weeks = calendar.weeks();
+    let mut months = String::new();
+    for week in 0..weeks {
+        let first = (0..7).filter_map(|d| calendar.day_at(week, d)).find(|day| day.day() == 1);
+        let month = match (week, first) {
+            (_, Some(day)) => day,
+            (0, None) => calendar.start,
+            _ => continue,
+        };
+        let column = 5 + week * 2;
+        let len = months.chars().count();
+        if len <= column {
+            months.push_str(&" ".repeat(column - len));
+            months.push_str(&month.format("%b").to_string());
+        }
+    }
+    let mut lines = vec![Line::from(Span::styled(months, Style::default().fg(Color::DarkGray)))];
+
+    for (weekday, label) in ["Mon", "", "Wed", "", "Fri", "", "Sun"].iter().enumerate() {
+        let mut spans = vec![Span::styled(format!("{:<4} ", label), Style::default().fg(Color::DarkGray))];
+        for week in 0..weeks {
+            let Some(day) = calendar.day_at(week, weekday) else {
+                spans.push(Span::raw("  "));
+                continue;
+            };
+            let mut style = Style::default().fg(if calendar.count(day) == 0 { Color::DarkGray } else { Color::Green });
+            if day == calendar.selected {
+                style = style.bg(Color::DarkGray).fg(Color::Yellow).add_modifier(Modifier::BOLD);
+            }
+            spans.push(Span::styled(format!("{} ", calendar.shade(day)), style));
+        }
+        lines.push(Line::from(spans));
+    }
+
+    lines.push(Line::from(""));
+    let count = calendar.count(calendar.selected);
+    lines.push(Line::from(vec![
+        Span::styled(
+            format!(" {}: ", calendar.selected.format("%a %Y-%m-%d")),
+            Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            match count {
+                1 => "1 article".to_string(),
+                n => format!("{} articles", n),
+            },
+            Style::default().fg(Color::Yellow),
+        ),
+        Span::styled(
+            format!("   {} over {} weeks", calendar.total(), weeks),
+            Style::default().fg(Color::DarkGray),
+        ),
+    ]));
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn render_trends(frame: &mut Frame, app: &App) {
+    let area = centered_rect(70, 70, frame.area());
+
+    let block = Block::default()
+        .title(" Trending Today - Enter: show articles  x: clear  Esc: close ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Magenta));
+
+    let inner = block.inner(area);
+
+    // Clear the area first
+    frame.render_widget(ratatui::widgets::Clear, area);
+    frame.render_widget(block, area);
+
+    if app.trends.is_empty() {
+        let paragraph = Paragraph::new("Nothing is picking up steam right now")
+            .style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(paragraph, inner);
+        return;
+    }
+
+    // Each trend with its example headlines underneath
+    let items: Vec<ListItem> = app
+        .trends
+        .iter()
+        .map(|trend| {
+            let mut lines = vec![Line::from(vec![
+                Span::styled(trend.term.clone(), Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
+                Span::styled(
+                    format!(
+                        "  {} headlines from {} feeds (usually {:.1}/day)",
+                        trend.recent, trend.feeds, trend.baseline
+                    ),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ])];
+            for example in &trend.examples {
+                lines.push(Line::from(Span::styled(
+                    format!("    {}", example.title),
+                    Style::default().fg(Color::Gray),
+                )));
+            }
+            ListItem::new(lines)
+        })
+        .collect();
+
+    let list = List::new(items).highlight_style(Style::default().bg(Color::DarkGray));
+
+    let mut state = ListState::default();
+    state.select(Some(app.trend_index));
+    frame.render_stateful_widget(list, inner, &mut state);
+}
+
+fn render_ask_input(frame: &mut Frame, app: &App) {
+    let area = centered_rect(60, 20, frame.area());
+
+    let title = if app.ask_all {
+        format!(" Ask about the {} articles in view - Tab: just this one ", app.filtered_articles().len().min(ASK_MAX_ARTICLES))
+    } else {
+        " Ask about this article - Tab: the articles in view ".to_string()
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Magenta));
+
+    let paragraph = Paragraph::new(format!("> {}_", app.ask_input))
+        .block(block)
+        .style(Style::default().fg(Color::White))
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(ratatui::widgets::Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+fn render_answer(frame: &mut Frame, app: &App) {
+    let area = centered_rect(70, 70, frame.area());
+
+    let block = Block::default()
+        .title(" Answer - Enter: go to article  Esc: close ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Magenta));
+
+    let inner = block.inner(area);
+
+    // Clear the area first
+    frame.render_widget(ratatui::widgets::Clear, area);
+    frame.render_widget(block, area);
+
+    let Some(answer) = &app.answer else {
+        let status = app.answer_status.clone().unwrap_or_default();
+        let status = if app.answer_pending() {
+            format!("{} {}", app.spinner_char(), status)
+        } else {
+            status
+        };
+        let paragraph = Paragraph::new(status).style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(paragraph, inner);
+        return;
+    };
+
+    // The answer, then the articles it cites
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(3),
+            Constraint::Length(answer.citations.len() as u16 + 2),
+        ])
+        .split(inner);
+
+    let mut text = vec![
+        Line::from(Span::styled(
+            answer.question.clone(),
+            Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+    text.extend(answer.text.lines().map(|line| Line::from(line.to_string())));
+    let paragraph = Paragraph::new(text)
+        .style(Style::default().fg(Color::Gray))
+        .wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, chunks[0]);
+
+    let mut items = vec![ListItem::new(Line::from(Span::styled(
+        app.answer_status.clone().unwrap_or_else(|| "Sources".to_string()),
+        Style::default().fg(Color::DarkGray),
+    )))];
+    if answer.citations.is_empty() {
+        items.push(ListItem::new(Span::styled("(no articles cited)", Style::default().fg(Color::DarkGray))));
+    }
+    items.extend(
+        answer
+            .citations
+            .iter()
+            .map(|c| ListItem::new(format!("[{}] {}", c.number, c.title))),
+    );
+    let list = List::new(items).highlight_style(Style::default().bg(Color::DarkGray));
+
+    let mut state = ListState::default();
+    if !answer.citations.is_empty() {
+        // Skip the heading row
+        state.select(Some(app.answer_index + 1));
+    }
+    frame.render_stateful_widget(list, chunks[1], &mut state);
+}
+
+fn render_recommendations(frame: &mut Frame, app: &App) {
+    let area = centered_rect(70, 60, frame.area());
+
+    let block = Block::default()
+        .title(" Recommended Feeds - Enter: subscribe  Esc: close ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow));
+
+    let inner = block.inner(area);
+
+    // Clear the area first
+    frame.render_widget(ratatui::widgets::Clear, area);
+    frame.render_widget(block, area);
+
+    // Split inner area for list and status
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(inner);
+
+    let items: Vec<ListItem> = app
+        .recommendations
+        .iter()
+        .map(|rec| {
+            let stars = format!("{:>3}★ ", rec.starred_count);
+            let mut spans = vec![
+                Span::styled(stars, Style::default().fg(Color::Yellow)),
+                Span::styled(rec.feed.title.clone(), Style::default().fg(Color::White)),
+                Span::styled(format!("  {}", rec.domain), Style::default().fg(Color::DarkGray)),
+            ];
+            if !rec.authors.is_empty() {
+                spans.push(Span::styled(
+                    format!("  by {}", rec.authors.join(", ")),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let list = List::new(items).highlight_style(
+        Style::default()
+            .bg(Color::DarkGray)
+            .add_modifier(Modifier::BOLD),
+    );
+
+    let mut state = ListState::default();
+    if !app.recommendations.is_empty() {
+        state.select(Some(app.recommendation_index));
+    }
+    frame.render_stateful_widget(list, chunks[0], &mut state);
+
+    // Show status message if any
+    if let Some(status) = &app.recommendations_status {
+        let (display_status, color) = if status.starts_with("Subscribed:") {
+            (status.clone(), Color::Green)
+        } else if status.starts_with("Error:") {
+            (status.clone(), Color::Red)
+        } else if status.starts_with("Checking") {
+            (format!("{} {}", app.spinner_char(), status), Color::Yellow)
+        } else {
+            (status.clone(), Color::DarkGray)
+        };
+        let status_paragraph = Paragraph::new(display_status).style(Style::default().fg(color));
+        frame.render_widget(status_paragraph, chunks[1]);
+    }
+}
+
+fn render_help(frame: &mut Frame) {
+    let area = centered_rect(50, 80, frame.area());
+
+    let help_text = vec![
+        "",
+        " Navigation:",
+        "   j / ↓    Move down",
+        "   k / ↑    Move up",
+        "   <        Go to top",
+        "   >        Go to bottom",
+        "   Enter    Select / Generate summary",
+        "   f        Cycle filter (All/Starred/Queue/Discussed/Recently read)",
+        "   #        Filter by suggested tag",
+        "   /        Search (feed:, tag:, author:, re:, -term, OR...)",
+        "   E        Browse people, companies & tickers",
+        "   y        Browse authors across feeds (f: follow, m: mute)",
+        "   Y        Articles by this article's author",
+        "   X        Blocked keywords, sites and shared lists (a: add, e: edit, d: remove, t: on/off)",
+        "   @        Articles linking to this one (search links:SITE or links:URL for others)",
+        "   W        Watchlist coverage timelines",
+        "   H        Trending headline terms",
+        "   G        Calendar of articles per day (f: this feed/all, Enter: that day's articles)",
+        "   ~        Earlier versions of the article, word by word ([ ]: older/newer)",
+        "   A        Ask about the article (Tab: articles in view)",
+        "   B        Compare outlets covering this story",
+        "   K / J    Move pinned article up/down",
+        "",
+        " Actions:",
+        "   r        Refresh all feeds",
+        "   a        Add new feed",
+        "   R        Recommend feeds from starred sites",
+        "   i        Import OPML file",
+        "   w        Export OPML file",
+        "   o        Open in browser",
+        "   O        Open HN/Lobsters discussion",
+        "   e        Email article",
+        "   b        Bookmark to Raindrop.io (enter tags)",
+        "   Space+t/i/m  Quick bookmark (twit/im/mbw)",
+        "   g        Regenerate summary",
+        "   F        Failed summaries (Enter: retry, a: retry all)",
+        "   c        Fetch comments",
+        "   x        Export article to PDF",
+        "   n        Save as note to Obsidian/Logseq vault",
+        "   N        Write a note on the article ($EDITOR if set)",
+        "   P        Play/stop podcast episode",
+        "   d / ⌫    Delete article",
+        "   D        Unsubscribe from feed",
+        "   T        Rename feed",
+        "   u        Undelete last",
+        "   s        Toggle starred",
+        "   p        Pin/unpin (top of Starred)",
+        "   l        Add to/remove from read-later queue",
+        "   m        Mark read/unread",
+        "",
+        " Selecting:",
+        "   M        Select articles (Esc: done)",
+        "   Space    Select/deselect the article",
+        "   v        Start a range, v again to add it",
+        "   m s t    Mark read, star or tag the selection",
+        "   b / d    Bookmark to Raindrop / delete the selection",
+        "",
+        " Layout:",
+        "   L        Cycle layout (three panes/two panes/list only)",
+        "   S        Read this feed in full/first paragraph/headlines",
+        "   I        Feed priority: normal/high (sorted up, announced)/low",
+        "   Z        Snooze: hide until tonight/tomorrow/next week/later, back unread",
+        "   U        Embargo: hide until a set time (empty lifts it; is:embargoed finds them)",
+        "   Tab      Switch to the feed list and back (three panes)",
+        "            (u there: only feeds with unread articles,",
+        "             Enter/Space: fold a group, g: group by folder/tag)",
+        "   [ / ]    Narrow/widen the article list",
+        "   { / }    Narrow/widen the feed list",
+        "   Esc      Back to the list from an article (list only)",
+        "",
+        " Reading:",
+        "   Ctrl+f   Find in article (also / when reading full screen)",
+        "   n / N    Next/previous match (Esc clears)",
+        "   v / V    Highlight next/previous link (o opens it)",
+        "   3o / 3v  Open link [3] / highlight it",
+        "",
+        " General:",
+        "   Ctrl+p / :  Command palette (also tag add TAG, goto feed NAME,",
+        "               block KEYWORD|site|author, unblock ENTRY)",
+        "   ?        Toggle this help",
+        "   q        Quit",
+        "",
+        " Press any key to close",
+    ];
+
+    let block = Block::default()
+        .title(" Help ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let paragraph = Paragraph::new(help_text.join("\n"))
+        .block(block)
+        .style(Style::default().fg(Color::White));
+
+    frame.render_widget(ratatui::widgets::Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+/// Format a duration in minutes as "45m" or "1h 25m"
+fn format_minutes(minutes: u32) -> String {
+    if minutes < 60 {
+        format!("{}m", minutes)
+    } else {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    }
+}
+
+/// Playback clock as "m:ss" or "h:mm:ss"
+fn format_clock(secs: u32) -> String {
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+fn format_position(secs: u32, duration: Option<u32>) -> String {
+    match duration {
+        Some(duration) => format!("{} / {}", format_clock(secs), format_clock(duration)),
+        None => format_clock(secs),
+    }
 }
 
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
@@ -0,0 +1,163 @@
+//! Coverage timelines for watched entities, and spotting days when
+//! coverage of one jumps.
+
+use chrono::{Duration, NaiveDate, Utc};
+use serde::Serialize;
+
+use crate::db::Repository;
+use crate::error::Result;
+
+/// Days shown in coverage timelines
+pub const TIMELINE_DAYS: usize = 14;
+
+/// A spike needs at least this many articles today...
+const SPIKE_MIN_ARTICLES: usize = 3;
+/// ...and this many times the entity's usual daily coverage
+const SPIKE_FACTOR: f64 = 3.0;
+
+/// Articles per day mentioning one watched entity, oldest day first and
+/// ending today
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Timeline {
+    pub entity: String,
+    /// Watchlists the entity is on
+    pub lists: Vec<String>,
+    pub start: NaiveDate,
+    pub counts: Vec<usize>,
+}
+
+impl Timeline {
+    pub fn today(&self) -> usize {
+        self.counts.last().copied().unwrap_or(0)
+    }
+
+    /// Average articles per day before today
+    pub fn baseline(&self) -> f64 {
+        let before = &self.counts[..self.counts.len().saturating_sub(1)];
+        if before.is_empty() {
+            return 0.0;
+        }
+        before.iter().sum::<usize>() as f64 / before.len() as f64
+    }
+
+    /// Whether today's coverage is well above the usual
+    pub fn is_spike(&self) -> bool {
+        let today = self.today();
+        today >= SPIKE_MIN_ARTICLES && today as f64 >= SPIKE_FACTOR * self.baseline()
+    }
+
+    /// e.g. "Nvidia: 9 articles today (usually 1.5/day)"
+    pub fn describe_spike(&self) -> String {
+        format!(
+            "{}: {} articles today (usually {:.1}/day)",
+            self.entity,
+            self.today(),
+            self.baseline()
+        )
+    }
+
+    /// One block character per day, scaled to the busiest day
+    pub fn sparkline(&self) -> String {
+        const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+        let max = self.counts.iter().copied().max().unwrap_or(0);
+        self.counts
+            .iter()
+            .map(|&count| match count {
+                0 => ' ',
+                _ => BARS[count * (BARS.len() - 1) / max],
+            })
+            .collect()
+    }
+}
+
+/// Timelines of every watched entity over the last `days` days
+pub async fn load_timelines(repository: &Repository, days: usize) -> Result<Vec<Timeline>> {
+    let today = Utc::now().date_naive();
+    let since = today - Duration::days(days.saturating_sub(1) as i64);
+    let watched = repository.get_watchlists().await?;
+    let coverage = repository.get_entity_coverage(since).await?;
+    Ok(build_timelines(&watched, &coverage, today, days))
+}
+
+/// One timeline per watched entity over the `days` days ending `today`.
+/// `watched` is (list, entity) pairs; `coverage` is (entity, day, articles).
+/// Entity names match case-insensitively.
+pub fn build_timelines(
+    watched: &[(String, String)],
+    coverage: &[(String, NaiveDate, usize)],
+    today: NaiveDate,
+    days: usize,
+) -> Vec<Timeline> {
+    let start = today - Duration::days(days.saturating_sub(1) as i64);
+    let mut timelines: Vec<Timeline> = Vec::new();
+    for (list, entity) in watched {
+        match timelines.iter_mut().find(|t| t.entity.eq_ignore_ascii_case(entity)) {
+            Some(timeline) => timeline.lists.push(list.clone()),
+            None => timelines.push(Timeline {
+                entity: entity.clone(),
+                lists: vec![list.clone()],
+                start,
+                counts: vec![0; days],
+            }),
+        }
+    }
+
+    for (entity, day, articles) in coverage {
+        let Ok(offset) = usize::try_from((*day - start).num_days()) else {
+            continue;
+        };
+        if let Some(timeline) = timelines.iter_mut().find(|t| t.entity.eq_ignore_ascii_case(entity)) {
+            if let Some(count) = timeline.counts.get_mut(offset) {
+                *count += articles;
+            }
+        }
+    }
+    timelines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, d).unwrap()
+    }
+
+    #[test]
+    fn test_build_timelines_merges_lists_and_places_days() {
+        let watched = vec![
+            ("chips".to_string(), "Nvidia".to_string()),
+            ("ai".to_string(), "nvidia".to_string()),
+            ("chips".to_string(), "Intel".to_string()),
+        ];
+        let coverage = vec![
+            ("NVIDIA".to_string(), day(10), 4),
+            ("Nvidia".to_string(), day(8), 1),
+            // Outside the window
+            ("Nvidia".to_string(), day(1), 7),
+        ];
+        let timelines = build_timelines(&watched, &coverage, day(10), 3);
+
+        assert_eq!(timelines.len(), 2);
+        assert_eq!(timelines[0].lists, ["chips", "ai"]);
+        assert_eq!(timelines[0].start, day(8));
+        assert_eq!(timelines[0].counts, [1, 0, 4]);
+        assert_eq!(timelines[1].counts, [0, 0, 0]);
+    }
+
+    #[test]
+    fn test_spike_needs_volume_and_jump() {
+        let timeline = |counts: Vec<usize>| Timeline {
+            entity: "Nvidia".to_string(),
+            lists: vec![],
+            start: day(1),
+            counts,
+        };
+        assert!(timeline(vec![1, 0, 2, 1, 9]).is_spike());
+        // A big day for a busy entity isn't a spike
+        assert!(!timeline(vec![5, 6, 4, 5, 9]).is_spike());
+        // Nor is a couple of articles about a quiet one
+        assert!(!timeline(vec![0, 0, 0, 0, 2]).is_spike());
+        assert_eq!(timeline(vec![0, 1, 0, 8]).sparkline(), " ▁ █");
+    }
+}